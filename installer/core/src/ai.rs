@@ -0,0 +1,339 @@
+//! AI tool keys and model defaults
+//! Exports API keys from the managed rc block and writes aichat's config
+//! for aichat and Claude Code.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::mpsc;
+
+use crate::executor::InstallMessage;
+use crate::rcblock;
+use crate::secret::{Secret, TextField};
+use crate::system::SystemInfo;
+use crate::wizard::{ShellChoice, WizardState};
+
+/// Where API keys come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KeySource {
+    /// Set keys up later
+    #[default]
+    Skip,
+    /// Typed in (masked) and exported as-is
+    Paste,
+    /// `op://` references, read by the 1Password CLI when a shell starts
+    OnePassword,
+}
+
+impl KeySource {
+    pub fn all() -> &'static [KeySource] {
+        &[KeySource::Skip, KeySource::Paste, KeySource::OnePassword]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeySource::Skip => "Skip",
+            KeySource::Paste => "Paste keys",
+            KeySource::OnePassword => "1Password",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeySource::Skip => "Set API keys up yourself later",
+            KeySource::Paste => "Stored in your rc file, so keep it out of dotfile repos",
+            KeySource::OnePassword => "op:// references, read with `op read` when a shell starts",
+        }
+    }
+
+    /// Text fields this source asks for, in screen order
+    pub fn fields(&self) -> &'static [AiField] {
+        match self {
+            KeySource::Skip => &[],
+            KeySource::Paste | KeySource::OnePassword => {
+                &[AiField::AnthropicKey, AiField::OpenAiKey, AiField::Model]
+            }
+        }
+    }
+}
+
+/// A text field on the AI tools screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiField {
+    AnthropicKey,
+    OpenAiKey,
+    Model,
+}
+
+impl AiField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AiField::AnthropicKey => "ANTHROPIC_API_KEY",
+            AiField::OpenAiKey => "OPENAI_API_KEY",
+            AiField::Model => "AICHAT MODEL (blank for default)",
+        }
+    }
+
+    /// Environment variable the field is exported as
+    pub fn env_var(&self) -> Option<&'static str> {
+        match self {
+            AiField::AnthropicKey => Some("ANTHROPIC_API_KEY"),
+            AiField::OpenAiKey => Some("OPENAI_API_KEY"),
+            AiField::Model => None,
+        }
+    }
+}
+
+/// Answers from the AI tools step
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AiSetup {
+    pub source: KeySource,
+    /// Keys are never saved; a profile only remembers the source
+    #[serde(skip)]
+    pub anthropic_key: Secret,
+    #[serde(skip)]
+    pub openai_key: Secret,
+    pub model: String,
+    /// Ollama model to pull, from the profile's `[ai]` section; empty
+    /// picks one for the machine (`localmodel::pick`)
+    #[serde(default)]
+    pub local_model: String,
+}
+
+impl AiSetup {
+    pub fn field(&self, field: AiField) -> &str {
+        match field {
+            AiField::AnthropicKey => self.anthropic_key.expose(),
+            AiField::OpenAiKey => self.openai_key.expose(),
+            AiField::Model => &self.model,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: AiField) -> &mut dyn TextField {
+        match field {
+            AiField::AnthropicKey => &mut self.anthropic_key,
+            AiField::OpenAiKey => &mut self.openai_key,
+            AiField::Model => &mut self.model,
+        }
+    }
+
+    /// Whether a field's value should be hidden on screen
+    pub fn is_masked(&self, field: AiField) -> bool {
+        self.source == KeySource::Paste && field.env_var().is_some()
+    }
+
+    /// Key fields that were filled in
+    pub fn keys(&self) -> Vec<AiField> {
+        [AiField::AnthropicKey, AiField::OpenAiKey]
+            .into_iter()
+            .filter(|f| self.source != KeySource::Skip && !self.field(*f).trim().is_empty())
+            .collect()
+    }
+
+    /// Value to export for a key field: the key itself, or a command that
+    /// reads it from 1Password
+    pub fn export_value(&self, field: AiField) -> String {
+        let value = self.field(field).trim();
+        match self.source {
+            KeySource::OnePassword => format!("$(op read '{}')", value),
+            KeySource::Skip | KeySource::Paste => value.to_string(),
+        }
+    }
+
+    /// aichat's default model: the one given, or one from the first
+    /// provider with a key
+    pub fn aichat_model(&self) -> Option<String> {
+        let model = self.model.trim();
+        if !model.is_empty() {
+            return Some(model.to_string());
+        }
+        self.keys().first().map(|field| match field {
+            AiField::OpenAiKey => "openai:gpt-4o".to_string(),
+            _ => "anthropic:claude-sonnet-4-5".to_string(),
+        })
+    }
+}
+
+// ─── Setup ───────────────────────────────────────────────────────────
+
+/// Run the AI tools step: export keys, write aichat's config
+pub fn setup_ai(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    if !wizard.wants_ai() {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "AI Tools".to_string(),
+    });
+    let ai = &wizard.ai;
+    let keys = ai.keys();
+
+    if keys.is_empty() {
+        let _ = tx.send(InstallMessage::NextStep {
+            command: "export ANTHROPIC_API_KEY=...".to_string(),
+            note: "in your shell rc, for Claude Code and aichat".to_string(),
+        });
+        return;
+    }
+
+    export_keys(wizard, system, tx);
+
+    if wizard.configures("aichat") {
+        write_aichat_config(ai, system, wizard.dry_run, tx);
+    }
+}
+
+fn export_keys(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let ai = &wizard.ai;
+    let shell = wizard.shell_config.shell;
+    let rc = rcblock::rc_file(shell, system);
+
+    for field in ai.keys() {
+        let var = field.env_var().expect("key field");
+        let reference = ai.field(field).trim();
+
+        if ai.source == KeySource::OnePassword {
+            if !reference.starts_with("op://") {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "{} doesn't look like an op:// reference",
+                    var
+                )));
+            }
+            // A Nushell string won't run a subshell; env.nu needs a
+            // parenthesized call instead
+            if shell == ShellChoice::Nushell {
+                let _ = tx.send(InstallMessage::NextStep {
+                    command: format!("$env.{} = (op read '{}')", var, reference),
+                    note: "add to env.nu".to_string(),
+                });
+                continue;
+            }
+        }
+
+        if wizard.dry_run {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[DRY] export {} from {}",
+                var,
+                rc.display()
+            )));
+            continue;
+        }
+        match rcblock::set_var(&rc, shell, var, &ai.export_value(field)) {
+            Ok(()) => {
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "[AI] Exported {} from {}",
+                    var,
+                    rc.display()
+                )));
+            }
+            Err(e) => {
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "  [ERROR] Failed to set {}: {}",
+                    var, e
+                )));
+            }
+        }
+    }
+}
+
+fn write_aichat_config(
+    ai: &AiSetup,
+    system: &SystemInfo,
+    dry_run: bool,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let path = system.config_dir.join("aichat").join("config.yaml");
+
+    // Clients and roles in an existing config are the user's own
+    if path.exists() {
+        let _ = tx.send(InstallMessage::Log(
+            "[AI] aichat config exists, leaving it alone".to_string(),
+        ));
+        return;
+    }
+    if dry_run {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] write {}",
+            path.display()
+        )));
+        return;
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match fs::write(&path, generate_aichat_config(ai)) {
+        Ok(()) => {
+            let _ = tx.send(InstallMessage::Log(
+                "[AI] Wrote aichat/config.yaml".to_string(),
+            ));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Could not write {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    }
+}
+
+/// aichat config with one client per key. Keys stay out of the file:
+/// aichat reads `<CLIENT NAME>_API_KEY`, so naming the Claude client
+/// "anthropic" picks up ANTHROPIC_API_KEY.
+fn generate_aichat_config(ai: &AiSetup) -> String {
+    let mut config =
+        String::from("# Generated by LOAD\"*\",8,1\n# https://github.com/oddurs/loadstar\n\n");
+    if let Some(model) = ai.aichat_model() {
+        config.push_str(&format!("model: {}\n", model));
+    }
+    config.push_str("save: true\nkeybindings: emacs\n\nclients:\n");
+    for field in ai.keys() {
+        config.push_str(match field {
+            AiField::OpenAiKey => "  - type: openai\n",
+            _ => "  - type: claude\n    name: anthropic\n",
+        });
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_password_exports_read_the_reference() {
+        let ai = AiSetup {
+            source: KeySource::OnePassword,
+            anthropic_key: Secret::new("op://Private/Anthropic/credential"),
+            ..Default::default()
+        };
+        assert_eq!(ai.keys(), [AiField::AnthropicKey]);
+        assert_eq!(
+            ai.export_value(AiField::AnthropicKey),
+            "$(op read 'op://Private/Anthropic/credential')"
+        );
+        assert!(!ai.is_masked(AiField::AnthropicKey));
+    }
+
+    #[test]
+    fn aichat_config_lists_a_client_per_key() {
+        let mut ai = AiSetup {
+            source: KeySource::Paste,
+            openai_key: Secret::new("sk-test"),
+            ..Default::default()
+        };
+        let config = generate_aichat_config(&ai);
+        assert!(config.contains("model: openai:gpt-4o\n"));
+        assert!(config.contains("  - type: openai\n"));
+        assert!(!config.contains("sk-test"));
+
+        ai.anthropic_key = Secret::new("sk-ant-test");
+        ai.model = "anthropic:claude-opus-4-1".to_string();
+        let config = generate_aichat_config(&ai);
+        assert!(config.contains("model: anthropic:claude-opus-4-1\n"));
+        assert!(config.contains("    name: anthropic\n"));
+
+        ai.source = KeySource::Skip;
+        assert!(ai.keys().is_empty());
+    }
+}
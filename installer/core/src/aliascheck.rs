@@ -0,0 +1,366 @@
+//! Alias conflict check
+//! Finds the user's own aliases and functions for the names loadstar
+//! aliases (`ls`, `cat`, `cd`...), so they can keep theirs instead of
+//! having it silently shadowed by loadstar's.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::executor::InstallMessage;
+use crate::hooks::{self, ShellAlias};
+use crate::rcblock;
+use crate::system::SystemInfo;
+use crate::wizard::{ShellChoice, WizardState};
+
+/// One of the user's definitions for a name loadstar wants to alias
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub alias: &'static ShellAlias,
+    pub file: PathBuf,
+    /// 1-based; 0 for a fish function file, which is all definition
+    pub line: usize,
+    /// The whole definition, every line of a function
+    pub definition: String,
+}
+
+/// The user's definitions that loadstar's aliases would shadow, one per
+/// name. A definition that's already loadstar's own isn't one.
+pub fn find(wizard: &WizardState, system: &SystemInfo) -> Vec<Conflict> {
+    let shell = wizard.shell_config.shell;
+    let wanted: Vec<&'static ShellAlias> = hooks::wanted_aliases(wizard)
+        .into_iter()
+        .filter(|a| a.line(shell).is_some())
+        .collect();
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let mut found = Vec::new();
+    for file in rc_files(shell, system) {
+        let Ok(text) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for (name, line, definition) in definitions(&text, shell) {
+            let alias = wanted.iter().find(|a| a.name == name);
+            if let Some(alias) = alias.filter(|a| a.line(shell).as_ref() != Some(&definition)) {
+                found.push(Conflict {
+                    alias,
+                    file: file.clone(),
+                    line,
+                    definition,
+                });
+            }
+        }
+    }
+
+    // fish autoloads a function from its own file, until an alias of the
+    // same name replaces it
+    if shell == ShellChoice::Fish {
+        let functions = system.config_dir.join("fish").join("functions");
+        for alias in &wanted {
+            let file = functions.join(format!("{}.fish", alias.name));
+            if let Ok(text) = fs::read_to_string(&file) {
+                found.push(Conflict {
+                    alias,
+                    file,
+                    line: 0,
+                    definition: text.trim_end().to_string(),
+                });
+            }
+        }
+    }
+
+    let mut conflicts: Vec<Conflict> = Vec::new();
+    for conflict in found {
+        if !conflicts
+            .iter()
+            .any(|c| c.alias.name == conflict.alias.name)
+        {
+            conflicts.push(conflict);
+        }
+    }
+    conflicts
+}
+
+/// Files whose definitions loadstar's would come after. zsh's are in the
+/// `.zshrc` loadstar replaces; `.zshrc.local` is sourced after it, so
+/// what's there wins anyway. Nushell reads `config.nu` after the `env.nu`
+/// the aliases go in, likewise.
+fn rc_files(shell: ShellChoice, system: &SystemInfo) -> Vec<PathBuf> {
+    let mut files = vec![rcblock::rc_file(shell, system)];
+    if shell == ShellChoice::Bash {
+        files.push(system.home_dir.join(".bash_aliases"));
+    }
+    files
+}
+
+/// `(name, line, definition)` for each alias and function `text` defines
+/// outside loadstar's block
+fn definitions(text: &str, shell: ShellChoice) -> Vec<(String, usize, String)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut found = Vec::new();
+    let mut inside = false;
+    for (i, raw) in lines.iter().enumerate() {
+        let line = raw.trim();
+        if line == rcblock::BEGIN || line == rcblock::END {
+            inside = line == rcblock::BEGIN;
+            continue;
+        }
+        if inside || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, is_function)) = defined_name(line, shell) else {
+            continue;
+        };
+        let definition = if is_function {
+            function_body(&lines[i..], shell)
+        } else {
+            line.to_string()
+        };
+        found.push((name, i + 1, definition));
+    }
+    found
+}
+
+/// The name a line defines, and whether it starts a function
+fn defined_name(line: &str, shell: ShellChoice) -> Option<(String, bool)> {
+    let mut words = line.split_whitespace();
+    let first = words.next()?;
+    let first = match (shell, first) {
+        (ShellChoice::Nushell, "export") => words.next()?,
+        _ => first,
+    };
+    let name_of = |word: &str| -> Option<String> {
+        let name = word.split('=').next()?.trim_matches(['\'', '"']);
+        (!name.is_empty()).then(|| name.to_string())
+    };
+    match (shell, first) {
+        (_, "alias") => {
+            let word = words.find(|w| !w.starts_with('-'))?;
+            Some((name_of(word)?, false))
+        }
+        (ShellChoice::Zsh | ShellChoice::Bash, "function") => {
+            Some((name_of(words.next()?.trim_end_matches("()"))?, true))
+        }
+        (ShellChoice::Fish, "function") => Some((name_of(words.next()?)?, true)),
+        (ShellChoice::Nushell, "def") => {
+            let word = words.find(|w| !w.starts_with('-'))?;
+            Some((name_of(word)?, true))
+        }
+        (ShellChoice::Zsh | ShellChoice::Bash, _) => {
+            let (name, rest) = line.split_once("()")?;
+            let name = name.trim();
+            let is_word = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "_-.".contains(c));
+            (is_word && (rest.trim().is_empty() || rest.trim_start().starts_with('{')))
+                .then(|| (name.to_string(), true))
+        }
+        _ => None,
+    }
+}
+
+/// A function's lines, from the one naming it to its closing brace or,
+/// in fish, its `end`
+fn function_body(lines: &[&str], shell: ShellChoice) -> String {
+    let mut body = Vec::new();
+    let mut depth = 0i32;
+    let mut opened = false;
+    for line in lines {
+        body.push(*line);
+        if shell == ShellChoice::Fish {
+            let word = line.split_whitespace().next().unwrap_or("");
+            if ["function", "if", "for", "while", "switch", "begin"].contains(&word) {
+                depth += 1;
+            } else if word == "end" {
+                depth -= 1;
+            }
+        } else {
+            opened |= line.contains('{');
+            depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        }
+        if depth <= 0 && (shell == ShellChoice::Fish || opened) {
+            break;
+        }
+    }
+    body.join("\n")
+}
+
+/// For zsh: definitions the user keeps that are in the `.zshrc` about to
+/// be replaced go to `.zshrc.local`, which the new one sources
+pub fn keep_in_local(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    if wizard.shell_config.shell != ShellChoice::Zsh {
+        return;
+    }
+    let zshrc = rcblock::rc_file(ShellChoice::Zsh, system);
+    let kept: Vec<Conflict> = find(wizard, system)
+        .into_iter()
+        .filter(|c| c.file == zshrc && wizard.kept_aliases.contains(c.alias.name))
+        .collect();
+    if kept.is_empty() {
+        return;
+    }
+
+    let local = system.home_dir.join(".zshrc.local");
+    match append_missing(&local, &kept) {
+        Ok(0) => {}
+        Ok(count) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Kept {} of your aliases from ~/.zshrc in ~/.zshrc.local",
+                count
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not keep your aliases in {}: {}; they're still in the .zshrc backup",
+                local.display(),
+                e
+            )));
+        }
+    }
+}
+
+/// Add the definitions `path` doesn't have yet, returning how many
+fn append_missing(path: &Path, kept: &[Conflict]) -> std::io::Result<usize> {
+    let mut text = fs::read_to_string(path).unwrap_or_default();
+    let missing: Vec<&Conflict> = kept
+        .iter()
+        .filter(|c| !text.contains(&c.definition))
+        .collect();
+    if missing.is_empty() {
+        return Ok(0);
+    }
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str("\n# Kept from your earlier .zshrc by loadstar\n");
+    for conflict in &missing {
+        text.push_str(&conflict.definition);
+        text.push('\n');
+    }
+    fs::write(path, text)?;
+    Ok(missing.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_system(name: &str) -> SystemInfo {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-{}-{}", name, std::process::id()));
+        system.config_dir = system.home_dir.join(".config");
+        let _ = fs::remove_dir_all(&system.home_dir);
+        fs::create_dir_all(&system.home_dir).unwrap();
+        system
+    }
+
+    fn wizard_with(shell: ShellChoice, apps: &[&str]) -> WizardState {
+        let mut wizard = WizardState::new();
+        wizard.shell_config.shell = shell;
+        wizard.selected_apps = apps.iter().map(|s| s.to_string()).collect();
+        wizard
+    }
+
+    #[test]
+    fn definitions_are_found_in_each_shell() {
+        let bash = "# alias ls='ls -G'\nalias ls='ls --color=auto'\ncat () {\n  command cat \"$@\"\n}\nfunction cd { builtin cd \"$@\"; }\n";
+        let names: Vec<(String, usize, String)> = definitions(bash, ShellChoice::Bash);
+        assert_eq!(
+            names,
+            [
+                (
+                    "ls".to_string(),
+                    2,
+                    "alias ls='ls --color=auto'".to_string()
+                ),
+                (
+                    "cat".to_string(),
+                    3,
+                    "cat () {\n  command cat \"$@\"\n}".to_string()
+                ),
+                (
+                    "cd".to_string(),
+                    6,
+                    "function cd { builtin cd \"$@\"; }".to_string()
+                ),
+            ]
+        );
+
+        let fish = "function cat --wraps cat\n    if true\n        bat $argv\n    end\nend\nalias top=htop\n";
+        let names = definitions(fish, ShellChoice::Fish);
+        assert_eq!(names[0].2.lines().count(), 5);
+        assert_eq!((names[1].0.as_str(), names[1].1), ("top", 6));
+
+        let nu = "export alias ll = ls -l\ndef --env cd [dir] { }\n";
+        let names: Vec<String> = definitions(nu, ShellChoice::Nushell)
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect();
+        assert_eq!(names, ["ll", "cd"]);
+
+        let ours = format!("{}\nalias ls='eza'\n{}\n", rcblock::BEGIN, rcblock::END);
+        assert!(definitions(&ours, ShellChoice::Bash).is_empty());
+    }
+
+    #[test]
+    fn conflicts_skip_loadstars_own_aliases() {
+        let system = temp_system("aliascheck");
+        let wizard = wizard_with(ShellChoice::Bash, &["eza", "bat"]);
+        fs::write(
+            system.home_dir.join(".bashrc"),
+            "alias ls='eza --icons --git'\nalias cd='pushd'\n",
+        )
+        .unwrap();
+        fs::write(
+            system.home_dir.join(".bash_aliases"),
+            "alias cat='cat -n'\n",
+        )
+        .unwrap();
+        let conflicts = find(&wizard, &system);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].alias.name, "cat");
+        assert_eq!(conflicts[0].line, 1);
+
+        let mut fish = wizard_with(ShellChoice::Fish, &["fd"]);
+        let functions = system.config_dir.join("fish").join("functions");
+        fs::create_dir_all(&functions).unwrap();
+        fs::write(
+            functions.join("find.fish"),
+            "function find\n    fd $argv\nend\n",
+        )
+        .unwrap();
+        assert_eq!(find(&fish, &system)[0].line, 0);
+        fish.shell_config.aliases = false;
+        assert!(find(&fish, &system).is_empty());
+        let _ = fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn kept_zsh_aliases_move_to_the_local_file() {
+        let system = temp_system("aliaskeep");
+        let mut wizard = wizard_with(ShellChoice::Zsh, &["eza", "bat"]);
+        fs::write(
+            system.home_dir.join(".zshrc"),
+            "alias ls='ls -G'\ncat() {\n  bat \"$@\"\n}\n",
+        )
+        .unwrap();
+        wizard.kept_aliases.insert("ls".to_string());
+        let (tx, rx) = mpsc::channel();
+        keep_in_local(&wizard, &system, &tx);
+        keep_in_local(&wizard, &system, &tx);
+
+        let local = fs::read_to_string(system.home_dir.join(".zshrc.local")).unwrap();
+        assert_eq!(
+            local,
+            "\n# Kept from your earlier .zshrc by loadstar\nalias ls='ls -G'\n"
+        );
+        assert_eq!(rx.try_iter().count(), 1);
+        assert!(!hooks::zsh_aliases(&wizard).contains("alias ls="));
+        assert!(hooks::zsh_aliases(&wizard).contains("alias cat="));
+        let _ = fs::remove_dir_all(&system.home_dir);
+    }
+}
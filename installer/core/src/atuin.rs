@@ -0,0 +1,312 @@
+//! Atuin history sync setup
+//! Imports existing shell history and points sync at the chosen server.
+//! Register and login prompt for secrets, so they go on the checklist.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::process::Command;
+use std::sync::mpsc;
+
+use crate::executor::InstallMessage;
+use crate::system::SystemInfo;
+use crate::wizard::{ShellChoice, WizardState};
+
+/// What to do about an Atuin sync account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AtuinSync {
+    /// History stays on this machine
+    #[default]
+    Off,
+    /// New account
+    Register,
+    /// Existing account, e.g. from another machine
+    Login,
+}
+
+impl AtuinSync {
+    pub fn all() -> &'static [AtuinSync] {
+        &[AtuinSync::Off, AtuinSync::Register, AtuinSync::Login]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AtuinSync::Off => "Local only",
+            AtuinSync::Register => "Register",
+            AtuinSync::Login => "Log in",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AtuinSync::Off => "Keep history on this machine",
+            AtuinSync::Register => "Create a sync account",
+            AtuinSync::Login => "Use an account you already have",
+        }
+    }
+
+    /// Text fields this choice asks for, in screen order
+    pub fn fields(&self) -> &'static [AtuinField] {
+        match self {
+            AtuinSync::Off => &[],
+            AtuinSync::Register => &[
+                AtuinField::Username,
+                AtuinField::Email,
+                AtuinField::SyncAddress,
+            ],
+            AtuinSync::Login => &[AtuinField::Username, AtuinField::SyncAddress],
+        }
+    }
+}
+
+/// A text field on the Atuin screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtuinField {
+    Username,
+    Email,
+    SyncAddress,
+}
+
+impl AtuinField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AtuinField::Username => "USERNAME",
+            AtuinField::Email => "EMAIL",
+            AtuinField::SyncAddress => "SYNC SERVER (blank for atuin.sh)",
+        }
+    }
+}
+
+/// Answers from the Atuin step
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AtuinSetup {
+    pub sync: AtuinSync,
+    pub username: String,
+    pub email: String,
+    pub sync_address: String,
+}
+
+impl AtuinSetup {
+    pub fn field(&self, field: AtuinField) -> &str {
+        match field {
+            AtuinField::Username => &self.username,
+            AtuinField::Email => &self.email,
+            AtuinField::SyncAddress => &self.sync_address,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: AtuinField) -> &mut String {
+        match field {
+            AtuinField::Username => &mut self.username,
+            AtuinField::Email => &mut self.email,
+            AtuinField::SyncAddress => &mut self.sync_address,
+        }
+    }
+
+    /// Self-hosted server, if one was given
+    pub fn sync_address(&self) -> Option<&str> {
+        let address = self.sync_address.trim();
+        (self.sync != AtuinSync::Off && !address.is_empty()).then_some(address)
+    }
+
+    /// The account command for the checklist; it prompts for the password
+    /// (and, on login, the encryption key) itself
+    pub fn account_command(&self) -> Option<String> {
+        let mut command = match self.sync {
+            AtuinSync::Off => return None,
+            AtuinSync::Register => String::from("atuin register"),
+            AtuinSync::Login => String::from("atuin login"),
+        };
+        let username = self.username.trim();
+        if !username.is_empty() {
+            command.push_str(&format!(" -u {}", username));
+        }
+        let email = self.email.trim();
+        if self.sync == AtuinSync::Register && !email.is_empty() {
+            command.push_str(&format!(" -e {}", email));
+        }
+        Some(command)
+    }
+}
+
+// ─── Setup ───────────────────────────────────────────────────────────
+
+/// Run the Atuin step: sync server, history import, account checklist
+pub fn setup_atuin(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    if !wizard.configures("atuin") {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Atuin".to_string(),
+    });
+    let atuin = &wizard.atuin;
+
+    if let Some(address) = atuin.sync_address() {
+        if wizard.dry_run {
+            let path = system.config_dir.join("atuin").join("config.toml");
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[DRY] set sync_address to {} in {}",
+                address,
+                path.display()
+            )));
+        } else if let Err(e) = write_sync_address(address, system, tx) {
+            let _ = tx.send(InstallMessage::Log(format!("  [ERROR] {}", e)));
+        }
+    }
+
+    import_history(wizard.shell_config.shell, system, wizard.dry_run, tx);
+
+    if let Some(command) = atuin.account_command() {
+        let note = match atuin.sync {
+            AtuinSync::Register => "create your sync account; keep the key `atuin key` prints",
+            _ => "prompts for your password and encryption key",
+        };
+        let _ = tx.send(InstallMessage::NextStep {
+            command,
+            note: note.to_string(),
+        });
+        let _ = tx.send(InstallMessage::NextStep {
+            command: "atuin sync".to_string(),
+            note: "push your imported history".to_string(),
+        });
+    }
+}
+
+fn write_sync_address(
+    address: &str,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    let path = system.config_dir.join("atuin").join("config.toml");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    fs::write(&path, with_sync_address(&existing, address))
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+
+    let _ = tx.send(InstallMessage::Log(format!(
+        "[ATUIN] Sync server set to {}",
+        address
+    )));
+    Ok(())
+}
+
+/// `content` with its sync_address replaced. The key goes first, since
+/// top-level TOML keys can't follow a table.
+fn with_sync_address(content: &str, address: &str) -> String {
+    let mut config = if content.trim().is_empty() {
+        String::from("# Generated by LOAD\"*\",8,1\n# https://github.com/oddurs/loadstar\n\n")
+    } else {
+        String::new()
+    };
+    config.push_str(&format!("sync_address = \"{}\"\n", address));
+    for line in content.lines() {
+        if !line.trim_start().starts_with("sync_address") {
+            config.push_str(line);
+            config.push('\n');
+        }
+    }
+    config
+}
+
+/// Import the shell's existing history, once: a second import would
+/// duplicate every entry
+fn import_history(
+    shell: ShellChoice,
+    system: &SystemInfo,
+    dry_run: bool,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let database = system.home_dir.join(".local/share/atuin/history.db");
+    if database.exists() {
+        let _ = tx.send(InstallMessage::Log(
+            "[ATUIN] History database exists, skipping import".to_string(),
+        ));
+        return;
+    }
+
+    let source = match shell {
+        ShellChoice::Zsh => "zsh",
+        ShellChoice::Bash => "bash",
+        ShellChoice::Fish => "fish",
+        ShellChoice::Nushell => "nu",
+    };
+    if dry_run {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] atuin import {}",
+            source
+        )));
+        return;
+    }
+    let _ = tx.send(InstallMessage::Log(format!(
+        "[ATUIN] Importing {} history",
+        source
+    )));
+    let result = Command::new("atuin")
+        .args(["import", source])
+        .env("PATH", system.search_path())
+        .output();
+    match result {
+        Ok(output) if output.status.success() => {
+            let _ = tx.send(InstallMessage::Log("  Imported".to_string()));
+        }
+        Ok(output) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "atuin import failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Failed to run atuin: {}",
+                e
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_command_includes_given_fields() {
+        let mut atuin = AtuinSetup {
+            sync: AtuinSync::Register,
+            username: "ada".to_string(),
+            email: "ada@example.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            atuin.account_command().as_deref(),
+            Some("atuin register -u ada -e ada@example.com")
+        );
+
+        atuin.sync = AtuinSync::Login;
+        assert_eq!(
+            atuin.account_command().as_deref(),
+            Some("atuin login -u ada")
+        );
+
+        atuin.sync = AtuinSync::Off;
+        assert!(atuin.account_command().is_none());
+    }
+
+    #[test]
+    fn sync_address_replaces_old_value_and_stays_top_level() {
+        let existing =
+            "sync_address = \"https://old.example\"\n\n[stats]\ncommon_prefix = [\"sudo\"]\n";
+        let config = with_sync_address(existing, "https://atuin.example");
+        assert!(config.starts_with("sync_address = \"https://atuin.example\"\n"));
+        assert!(!config.contains("old.example"));
+
+        let parsed: toml::Value = toml::from_str(&config).unwrap();
+        assert_eq!(
+            parsed["sync_address"].as_str(),
+            Some("https://atuin.example")
+        );
+        assert!(parsed.get("stats").is_some());
+    }
+}
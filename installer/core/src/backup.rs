@@ -0,0 +1,358 @@
+//! Setup backup
+//! The profile and the config files loadstar generated, pushed to a private
+//! GitHub repo with gh. The next machine pulls the profile back from it.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::base64;
+use crate::config;
+use crate::github::{self, GitRunner};
+use crate::github_api::{Client, Format};
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// The repo backups go to unless the profile names another
+pub const DEFAULT_REPO: &str = "loadstar-setup";
+
+/// Where the profile sits in the repo
+pub const PROFILE_FILE: &str = "profile.toml";
+
+/// A file to back up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupFile {
+    /// Path in the repo
+    pub path: String,
+    pub content: String,
+}
+
+/// What goes in a backup
+#[derive(Debug, Clone, Default)]
+pub struct Contents {
+    pub files: Vec<BackupFile>,
+    /// Generated files left out because one of the wizard's secrets is in
+    /// them, by their path in the repo
+    pub withheld: Vec<String>,
+}
+
+/// The profile at `profile` (or, if there's none, one listing the apps
+/// picked), then every generated config file that's on disk, under `home/`
+pub fn collect(wizard: &WizardState, system: &SystemInfo, profile: &Path) -> Contents {
+    let profile = fs::read_to_string(profile).unwrap_or_else(|_| selection_profile(wizard));
+    let mut contents = Contents {
+        files: vec![BackupFile {
+            path: PROFILE_FILE.to_string(),
+            content: profile,
+        }],
+        withheld: Vec::new(),
+    };
+
+    let secrets = secrets(wizard);
+    for planned in config::planned_configs(wizard, system, None) {
+        let Ok(relative) = planned.path.strip_prefix(&system.home_dir) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&planned.path) else {
+            continue;
+        };
+        let path = format!("home/{}", relative.display());
+        if secrets.iter().any(|secret| content.contains(secret)) {
+            contents.withheld.push(path);
+        } else {
+            contents.files.push(BackupFile { path, content });
+        }
+    }
+    contents
+}
+
+/// A profile that preselects what was picked this time
+fn selection_profile(wizard: &WizardState) -> String {
+    #[derive(Serialize)]
+    struct Selection {
+        apps: Vec<String>,
+    }
+    let mut apps: Vec<String> = wizard.selected_apps.iter().cloned().collect();
+    apps.sort();
+    let body = toml::to_string(&Selection { apps }).unwrap_or_default();
+    format!("# The apps picked in loadstar's wizard\n{}", body)
+}
+
+/// Values that mustn't leave the machine. 1Password references are only
+/// pointers, so they don't count.
+pub(crate) fn secrets(wizard: &WizardState) -> Vec<&str> {
+    [
+        &wizard.ssh_passphrase,
+        &wizard.github_token,
+        &wizard.ai.anthropic_key,
+        &wizard.ai.openai_key,
+    ]
+    .into_iter()
+    .map(|secret| secret.expose())
+    .filter(|value| !value.is_empty() && !value.starts_with("op://"))
+    .collect()
+}
+
+/// Push `files` to `repo` under gh's logged-in account, creating it as a
+/// private repo if it isn't there. A repo of that name that isn't private
+/// is refused: the configs would be public. Files that haven't changed
+/// are left alone. Returns the repo's full name.
+pub fn push(
+    runner: &dyn GitRunner,
+    repo: &str,
+    files: &[BackupFile],
+    hostname: &str,
+) -> Result<String, String> {
+    let owner = gh(runner, &["api", "user", "--jq", ".login"])?;
+    let full_name = format!("{}/{}", owner.trim(), repo);
+    let view = ["repo", "view", &full_name, "--json", "isPrivate,visibility"];
+    if let Ok(json) = gh(runner, &view) {
+        let repo: serde_json::Value = serde_json::from_str(&json)
+            .map_err(|e| format!("Could not read gh repo view's answer: {}", e))?;
+        if repo["isPrivate"] != true {
+            let visibility = repo["visibility"].as_str().unwrap_or("not private");
+            return Err(format!(
+                "{} is {}, so nothing was pushed to it; make it private or name another repo",
+                full_name,
+                visibility.to_lowercase()
+            ));
+        }
+    } else {
+        gh(
+            runner,
+            &[
+                "repo",
+                "create",
+                &full_name,
+                "--private",
+                "--description",
+                "Machine setup backed up by loadstar",
+            ],
+        )?;
+    }
+
+    for file in files {
+        let api = format!("repos/{}/contents/{}", full_name, encode_path(&file.path));
+        let existing = gh(runner, &["api", &api, "--jq", r#".sha + " " + .content"#]).ok();
+        let mut body = serde_json::json!({
+            "message": format!("Back up {} from {}", file.path, hostname),
+            "content": base64::encode(file.content.as_bytes()),
+        });
+        match existing.as_deref().and_then(|out| out.split_once(' ')) {
+            Some((_, content)) if base64::decode(content) == file.content.as_bytes() => continue,
+            Some((sha, _)) => body["sha"] = sha.into(),
+            None => {}
+        }
+        let args = ["api", "--method", "PUT", &api, "--input", "-"];
+        match runner.run_with_input("gh", &args, &body.to_string()) {
+            Ok(out) if out.success => {}
+            Ok(out) => {
+                return Err(format!(
+                    "Could not push {}: {}",
+                    file.path,
+                    out.stderr.trim()
+                ))
+            }
+            Err(e) => return Err(format!("Could not run gh: {}", e)),
+        }
+    }
+    Ok(full_name)
+}
+
+/// `path` for a URL: each segment percent-encoded, the slashes kept
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            segment
+                .bytes()
+                .map(|b| match b {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                        (b as char).to_string()
+                    }
+                    _ => format!("%{:02X}", b),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn gh(runner: &dyn GitRunner, args: &[&str]) -> Result<String, String> {
+    match runner.run("gh", args) {
+        Ok(out) if out.success => Ok(out.stdout),
+        Ok(out) => Err(format!("gh {}: {}", args[..2].join(" "), out.stderr.trim())),
+        Err(e) => Err(format!("Could not run gh: {}", e)),
+    }
+}
+
+// ─── Pulling ─────────────────────────────────────────────────────────
+
+/// A backup repo named on the command line as `gh:owner` or `gh:owner/repo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remote {
+    pub owner: String,
+    pub repo: String,
+}
+
+impl Remote {
+    /// `None` for anything that isn't a `gh:` spec, such as a file path
+    pub fn parse(spec: &str) -> Option<Self> {
+        let rest = spec.strip_prefix("gh:")?;
+        let (owner, repo) = rest.split_once('/').unwrap_or((rest, DEFAULT_REPO));
+        let valid = |name: &str| {
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        (valid(owner) && valid(repo)).then(|| Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+/// The profile backed up at `remote`. Fetched through gh when it's logged
+/// in; otherwise through the API client and `$GH_TOKEN`, for a new machine
+/// that has neither gh nor a login yet.
+pub fn pull(runner: &dyn GitRunner, remote: &Remote) -> Result<String, String> {
+    let api = format!(
+        "repos/{}/{}/contents/{}",
+        remote.owner, remote.repo, PROFILE_FILE
+    );
+    let logged_in = runner
+        .run("gh", &["auth", "status"])
+        .is_ok_and(|out| out.success);
+    if logged_in {
+        return gh(
+            runner,
+            &["api", "-H", "Accept: application/vnd.github.raw", &api],
+        );
+    }
+
+    let Some((var, token)) = github::token_from_env() else {
+        return Err(
+            "Pulling a profile from GitHub needs gh logged in (gh auth login) or $GH_TOKEN set"
+                .to_string(),
+        );
+    };
+    // Not cached: the point is the profile as it is now
+    let client = Client::new(runner, Some(token), None);
+    match client.get(&api, Format::Raw) {
+        Ok(Some(profile)) => Ok(profile),
+        Ok(None) => Err(format!(
+            "There's no {} in {}/{} that ${} can see",
+            PROFILE_FILE, remote.owner, remote.repo, var
+        )),
+        Err(e) => Err(format!("Could not fetch the profile with ${}: {}", var, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakerunner::{fail, ok, FakeRunner};
+    use crate::secret::Secret;
+
+    #[test]
+    fn push_creates_the_repo_and_skips_unchanged_files() {
+        let same = base64::encode(b"[user]\n");
+        let runner = FakeRunner::default()
+            .answer("gh api user", ok("octo\n"))
+            .answer("gh repo create octo/loadstar-setup --private", ok(""))
+            .answer(
+                "gh api repos/octo/loadstar-setup/contents/home/.gitconfig --jq",
+                ok(&format!("abc123 {}\n", same)),
+            )
+            .answer("gh api --method PUT", ok("{}"))
+            .otherwise(fail(""));
+        let files = [
+            BackupFile {
+                path: PROFILE_FILE.to_string(),
+                content: "apps = []\n".to_string(),
+            },
+            BackupFile {
+                path: "home/.gitconfig".to_string(),
+                content: "[user]\n".to_string(),
+            },
+        ];
+
+        let pushed = push(&runner, DEFAULT_REPO, &files, "den").unwrap();
+        assert_eq!(pushed, "octo/loadstar-setup");
+        let calls = runner.calls();
+        assert!(calls.iter().any(|c| c.starts_with("gh repo create")));
+        let puts: Vec<&String> = calls
+            .iter()
+            .filter(|c| c.starts_with("gh api --method PUT"))
+            .collect();
+        assert_eq!(
+            puts,
+            ["gh api --method PUT repos/octo/loadstar-setup/contents/profile.toml --input -"]
+        );
+        assert!(runner
+            .stdin()
+            .iter()
+            .any(|body| body.contains("\"Back up profile.toml from den\"")));
+    }
+
+    #[test]
+    fn a_public_repo_of_the_same_name_is_refused() {
+        let runner = FakeRunner::default()
+            .answer("gh api user", ok("octo\n"))
+            .answer(
+                "gh repo view octo/loadstar-setup --json isPrivate,visibility",
+                ok(r#"{"isPrivate":false,"visibility":"PUBLIC"}"#),
+            )
+            .otherwise(ok("{}"));
+        let files = [BackupFile {
+            path: PROFILE_FILE.to_string(),
+            content: "apps = []\n".to_string(),
+        }];
+
+        let err = push(&runner, DEFAULT_REPO, &files, "den").unwrap_err();
+        assert_eq!(
+            err,
+            "octo/loadstar-setup is public, so nothing was pushed to it; make it private or name another repo"
+        );
+        assert!(!runner.ran("gh api --method PUT"));
+        assert!(!runner.ran("gh repo create"));
+    }
+
+    #[test]
+    fn repo_paths_are_percent_encoded_by_segment() {
+        assert_eq!(
+            encode_path("home/.config/my app/50%#1?.toml"),
+            "home/.config/my%20app/50%25%231%3F.toml"
+        );
+    }
+
+    #[test]
+    fn files_holding_a_secret_stay_home() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-backup-{}", std::process::id()));
+        system.config_dir = system.home_dir.join(".config");
+        fs::create_dir_all(system.config_dir.join("git")).unwrap();
+        let mut wizard = WizardState::new();
+        wizard.ai.openai_key = Secret::new("sk-live-123");
+        fs::write(system.home_dir.join(".gitconfig"), "[user]\n").unwrap();
+        fs::write(system.home_dir.join(".editorconfig"), "key = sk-live-123\n").unwrap();
+
+        let contents = collect(&wizard, &system, &system.home_dir.join("none.toml"));
+        let paths: Vec<&str> = contents.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, [PROFILE_FILE, "home/.gitconfig"]);
+        assert!(contents.files[0].content.contains("apps = ["));
+        assert_eq!(contents.withheld, ["home/.editorconfig"]);
+        let _ = fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn remotes_default_the_repo() {
+        let remote = Remote::parse("gh:octo").unwrap();
+        assert_eq!(remote.repo, DEFAULT_REPO);
+        assert_eq!(Remote::parse("gh:octo/setup").unwrap().repo, "setup");
+        assert_eq!(Remote::parse("profile.toml"), None);
+        assert_eq!(Remote::parse("gh:"), None);
+        assert_eq!(Remote::parse("gh:octo/a/b"), None);
+    }
+}
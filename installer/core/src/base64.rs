@@ -0,0 +1,51 @@
+//! Base64
+//! Standard alphabet with padding: age's armor and GitHub's contents API
+//! both use it, and it's not worth a dependency.
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().fold(0u32, |n, b| (n << 8) | *b as u32) << (8 * (3 - chunk.len()));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+        out.push_str(&"=".repeat(3 - chunk.len()));
+    }
+    out
+}
+
+/// Decode up to the padding. Line breaks and anything else outside the
+/// alphabet are skipped.
+pub fn decode(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in text.bytes().take_while(|c| *c != b'=') {
+        let Some(value) = ALPHABET.iter().position(|a| *a == c) else {
+            continue;
+        };
+        acc = (acc << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_padding() {
+        for text in ["", "h", "he", "hel", "hello\n"] {
+            assert_eq!(decode(&encode(text.as_bytes())), text.as_bytes());
+        }
+        assert_eq!(encode(b"hello"), "aGVsbG8=");
+        assert_eq!(decode("aGVs\nbG8="), b"hello");
+    }
+}
@@ -1,15 +1,22 @@
 //! Comprehensive catalog of CLI-configurable apps and tools
 //! Organized by category with descriptions and configuration options
 
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 
-#[derive(Debug, Clone)]
+use crate::system::Os;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct App {
-    pub id: &'static str,
+    pub id: AppId,
     pub name: &'static str,
     pub description: &'static str,
     pub category: Category,
     pub install_method: InstallMethod,
+    /// Environment variables its install command needs, set for that
+    /// command alone
+    pub env: &'static [(&'static str, &'static str)],
     pub config_files: &'static [&'static str],
     pub dependencies: &'static [&'static str],
     pub tags: &'static [&'static str],
@@ -28,12 +35,15 @@ pub enum Category {
     Network,
     Container,
     Language,
+    LanguageServer,
     Database,
     Security,
     Productivity,
     Media,
     Cloud,
     AI,
+    /// Cross-listed only: every app in it lives in another category too
+    Tui,
 }
 
 impl Category {
@@ -49,12 +59,14 @@ impl Category {
             Category::Network => "🌐",
             Category::Container => "📦",
             Category::Language => "⟨⟩",
+            Category::LanguageServer => "◇",
             Category::Database => "⛁",
             Category::Security => "🔒",
             Category::Productivity => "⚡",
             Category::Media => "🎬",
             Category::Cloud => "☁️",
             Category::AI => "🤖",
+            Category::Tui => "▤",
         }
     }
 
@@ -70,15 +82,45 @@ impl Category {
             Category::Network => "Network Tools",
             Category::Container => "Containers & VMs",
             Category::Language => "Languages & Runtimes",
+            Category::LanguageServer => "Language Servers & Formatters",
             Category::Database => "Databases",
             Category::Security => "Security",
             Category::Productivity => "Productivity",
             Category::Media => "Media",
             Category::Cloud => "Cloud & DevOps",
             Category::AI => "AI & ML Tools",
+            Category::Tui => "TUI Apps",
+        }
+    }
+
+    /// Name used in the profile's `[categories]` section
+    pub fn id(&self) -> &'static str {
+        match self {
+            Category::Shell => "shell",
+            Category::Editor => "editor",
+            Category::Git => "git",
+            Category::Terminal => "terminal",
+            Category::FileManager => "file_manager",
+            Category::Search => "search",
+            Category::System => "system",
+            Category::Network => "network",
+            Category::Container => "container",
+            Category::Language => "language",
+            Category::LanguageServer => "language_server",
+            Category::Database => "database",
+            Category::Security => "security",
+            Category::Productivity => "productivity",
+            Category::Media => "media",
+            Category::Cloud => "cloud",
+            Category::AI => "ai",
+            Category::Tui => "tui",
         }
     }
 
+    pub fn from_id(id: &str) -> Option<Category> {
+        Self::all().iter().copied().find(|c| c.id() == id)
+    }
+
     pub fn all() -> &'static [Category] {
         &[
             Category::Shell,
@@ -91,17 +133,25 @@ impl Category {
             Category::Network,
             Category::Container,
             Category::Language,
+            Category::LanguageServer,
             Category::Database,
             Category::Security,
             Category::Productivity,
             Category::Media,
             Category::Cloud,
             Category::AI,
+            Category::Tui,
         ]
     }
 }
 
-#[derive(Debug, Clone)]
+/// Tags that list an app under a second category as well as its own, so
+/// lazydocker shows in Containers & VMs and in TUI Apps
+const TAG_CATEGORIES: &[(&str, Category)] =
+    &[("tui", Category::Tui), ("kubernetes", Category::Container)];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InstallMethod {
     Brew(&'static str),
     BrewCask(&'static str),
@@ -112,6 +162,11 @@ pub enum InstallMethod {
     Script(&'static str),
     Manual(&'static str),
     Apt(&'static str),
+    /// Different method per OS (e.g. a cask on macOS, a script on Linux)
+    PerOs {
+        macos: &'static InstallMethod,
+        linux: &'static InstallMethod,
+    },
 }
 
 impl InstallMethod {
@@ -121,11 +176,167 @@ impl InstallMethod {
             InstallMethod::BrewCask(pkg) => format!("brew install --cask {}", pkg),
             InstallMethod::Cargo(pkg) => format!("cargo install {}", pkg),
             InstallMethod::Npm(pkg) => format!("npm install -g {}", pkg),
-            InstallMethod::Pip(pkg) => format!("pip install {}", pkg),
+            InstallMethod::Pip(pkg) => format!("uv tool install {}", pkg),
             InstallMethod::Go(pkg) => format!("go install {}", pkg),
             InstallMethod::Script(url) => format!("curl -fsSL {} | sh", url),
             InstallMethod::Manual(cmd) => cmd.to_string(),
             InstallMethod::Apt(pkg) => format!("sudo apt install -y {}", pkg),
+            InstallMethod::PerOs { .. } => self.for_current_os().command(),
+        }
+    }
+
+    /// The method that applies on this build's OS. Binaries are built per
+    /// platform, so this is decided at compile time.
+    pub fn for_current_os(&self) -> &InstallMethod {
+        if cfg!(target_os = "macos") {
+            self.for_os(Os::MacOS)
+        } else {
+            self.for_os(Os::Linux)
+        }
+    }
+
+    /// The method that applies on `os`
+    pub fn for_os(&self, os: Os) -> &InstallMethod {
+        match (self, os) {
+            (InstallMethod::PerOs { macos, .. }, Os::MacOS) => macos,
+            (InstallMethod::PerOs { linux, .. }, Os::Linux) => linux,
+            (other, _) => other,
+        }
+    }
+
+    /// The toolchain this method needs beyond Homebrew itself
+    pub fn toolchain(&self) -> Option<Toolchain> {
+        let (command, provider) = match self.for_current_os() {
+            InstallMethod::Cargo(_) => ("cargo", "rustup"),
+            InstallMethod::Go(_) => ("go", "go"),
+            InstallMethod::Npm(_) => ("npm", "node"),
+            InstallMethod::Pip(_) => ("uv", "uv"),
+            _ => return None,
+        };
+        Some(Toolchain { command, provider })
+    }
+}
+
+/// A command an install method shells out to, and the catalog app that
+/// provides it when it is missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Toolchain {
+    pub command: &'static str,
+    pub provider: &'static str,
+}
+
+impl App {
+    /// Install method for this OS
+    pub fn method(&self) -> &InstallMethod {
+        self.install_method.for_current_os()
+    }
+
+    /// The install command as it runs here, with its environment in front
+    pub fn install_command(&self) -> String {
+        let mut command: Vec<String> = self
+            .env
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        command.push(self.method().command());
+        command.join(" ")
+    }
+
+    /// Every category the app is listed under: `category`, where it
+    /// lives, then the ones its tags add
+    pub fn categories(&self) -> Vec<Category> {
+        let mut categories = vec![self.category];
+        for (tag, category) in TAG_CATEGORIES {
+            if self.tags.contains(tag) && !categories.contains(category) {
+                categories.push(*category);
+            }
+        }
+        categories
+    }
+
+    pub fn in_category(&self, category: Category) -> bool {
+        self.categories().contains(&category)
+    }
+
+    /// Whether there is a way to install this on `os`. Casks are macOS
+    /// only and apt is Linux only.
+    pub fn supports(&self, os: Os) -> bool {
+        match self.install_method.for_os(os) {
+            InstallMethod::BrewCask(_) => os == Os::MacOS,
+            InstallMethod::Apt(_) => os == Os::Linux,
+            _ => true,
+        }
+    }
+}
+
+// ─── App ids ─────────────────────────────────────────────────────────
+
+/// The id of a catalog app. Only the catalog and registered custom
+/// packages make these, and reading one back looks it up, so an `AppId`
+/// always names an app that exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AppId(&'static str);
+
+impl AppId {
+    /// The id of the app called `id`, if the catalog has one
+    pub fn parse(id: &str) -> Option<AppId> {
+        find_app(id).map(|app| app.id)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// The id of a custom package's app, which `find_app` knows once
+    /// it's registered
+    pub(crate) fn custom(id: &'static str) -> AppId {
+        AppId(id)
+    }
+
+    /// The app this id names
+    pub fn app(&self) -> &'static App {
+        find_app(self.0).expect("every AppId comes from the catalog")
+    }
+}
+
+impl fmt::Display for AppId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl PartialEq<&str> for AppId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Serialize for AppId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for AppId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        AppId::parse(&id).ok_or_else(|| D::Error::custom(format!("unknown app '{}'", id)))
+    }
+}
+
+/// An app reads back as the catalog entry it names, from either its id or
+/// a full serialized `App`
+impl<'de> Deserialize<'de> for &'static App {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum AppRef {
+            Id(AppId),
+            Full { id: AppId },
+        }
+
+        match AppRef::deserialize(deserializer)? {
+            AppRef::Id(id) | AppRef::Full { id } => Ok(id.app()),
         }
     }
 }
@@ -136,55 +347,88 @@ pub const CATALOG: &[App] = &[
     // SHELL & PROMPT
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "zsh",
+        id: AppId("zsh"),
         name: "Zsh",
         description: "Extended Bourne shell with many improvements",
         category: Category::Shell,
         install_method: InstallMethod::Brew("zsh"),
+        env: &[],
         config_files: &["~/.zshrc", "~/.zshenv", "~/.zprofile"],
         dependencies: &[],
         tags: &["shell", "essential"],
         url: "https://www.zsh.org/",
     },
     App {
-        id: "starship",
+        id: AppId("starship"),
         name: "Starship",
         description: "Minimal, blazing-fast, customizable prompt for any shell",
         category: Category::Shell,
         install_method: InstallMethod::Brew("starship"),
+        env: &[],
         config_files: &["~/.config/starship.toml"],
         dependencies: &[],
         tags: &["prompt", "rust", "essential"],
         url: "https://starship.rs/",
     },
     App {
-        id: "zoxide",
+        id: AppId("powerlevel10k"),
+        name: "Powerlevel10k",
+        description: "Fast, flexible zsh theme with instant prompt",
+        category: Category::Shell,
+        install_method: InstallMethod::Manual(
+            "[ -d ~/.local/share/zsh/powerlevel10k ] || git clone --depth=1 https://github.com/romkatv/powerlevel10k.git ~/.local/share/zsh/powerlevel10k",
+        ),
+        env: &[],
+        config_files: &["~/.p10k.zsh"],
+        dependencies: &["git"],
+        tags: &["prompt", "zsh"],
+        url: "https://github.com/romkatv/powerlevel10k",
+    },
+    App {
+        id: AppId("pure"),
+        name: "Pure",
+        description: "Pretty, minimal and fast zsh prompt",
+        category: Category::Shell,
+        install_method: InstallMethod::Manual(
+            "[ -d ~/.local/share/zsh/pure ] || git clone --depth=1 https://github.com/sindresorhus/pure.git ~/.local/share/zsh/pure",
+        ),
+        env: &[],
+        config_files: &[],
+        dependencies: &["git"],
+        tags: &["prompt", "zsh"],
+        url: "https://github.com/sindresorhus/pure",
+    },
+    App {
+        id: AppId("zoxide"),
         name: "Zoxide",
         description: "Smarter cd command - remembers your most used directories",
         category: Category::Shell,
         install_method: InstallMethod::Brew("zoxide"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["navigation", "rust", "essential"],
         url: "https://github.com/ajeetdsouza/zoxide",
     },
     App {
-        id: "direnv",
+        id: AppId("direnv"),
         name: "Direnv",
         description: "Environment switcher for the shell - auto-load .envrc files",
         category: Category::Shell,
         install_method: InstallMethod::Brew("direnv"),
+        env: &[],
         config_files: &["~/.config/direnv/direnvrc"],
         dependencies: &[],
         tags: &["environment", "essential"],
         url: "https://direnv.net/",
     },
     App {
-        id: "atuin",
+        id: AppId("atuin"),
         name: "Atuin",
         description: "Magical shell history - sync, search, and stats",
         category: Category::Shell,
         install_method: InstallMethod::Brew("atuin"),
+        env: &[],
         config_files: &["~/.config/atuin/config.toml"],
         dependencies: &[],
         tags: &["history", "rust", "sync"],
@@ -194,22 +438,24 @@ pub const CATALOG: &[App] = &[
     // EDITORS
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "neovim",
+        id: AppId("neovim"),
         name: "Neovim",
         description: "Hyperextensible Vim-based text editor",
         category: Category::Editor,
         install_method: InstallMethod::Brew("neovim"),
+        env: &[],
         config_files: &["~/.config/nvim/init.lua", "~/.config/nvim/"],
         dependencies: &[],
         tags: &["editor", "vim", "lua"],
         url: "https://neovim.io/",
     },
     App {
-        id: "helix",
+        id: AppId("helix"),
         name: "Helix",
         description: "Post-modern modal text editor with built-in LSP",
         category: Category::Editor,
         install_method: InstallMethod::Brew("helix"),
+        env: &[],
         config_files: &[
             "~/.config/helix/config.toml",
             "~/.config/helix/languages.toml",
@@ -219,22 +465,39 @@ pub const CATALOG: &[App] = &[
         url: "https://helix-editor.com/",
     },
     App {
-        id: "micro",
+        id: AppId("zed"),
+        name: "Zed",
+        description: "High-performance collaborative code editor",
+        category: Category::Editor,
+        install_method: InstallMethod::PerOs {
+            macos: &InstallMethod::BrewCask("zed"),
+            linux: &InstallMethod::Script("https://zed.dev/install.sh"),
+        },
+        env: &[],
+        config_files: &["~/.config/zed/settings.json"],
+        dependencies: &[],
+        tags: &["editor", "gui", "rust"],
+        url: "https://zed.dev/",
+    },
+    App {
+        id: AppId("micro"),
         name: "Micro",
         description: "Modern and intuitive terminal-based text editor",
         category: Category::Editor,
         install_method: InstallMethod::Brew("micro"),
+        env: &[],
         config_files: &["~/.config/micro/settings.json"],
         dependencies: &[],
         tags: &["editor", "simple"],
         url: "https://micro-editor.github.io/",
     },
     App {
-        id: "vscode",
+        id: AppId("vscode"),
         name: "Visual Studio Code",
         description: "Code editor with extensions and integrated terminal",
         category: Category::Editor,
         install_method: InstallMethod::BrewCask("visual-studio-code"),
+        env: &[],
         config_files: &["~/.config/Code/User/settings.json"],
         dependencies: &[],
         tags: &["editor", "gui", "extensions"],
@@ -244,55 +507,60 @@ pub const CATALOG: &[App] = &[
     // GIT & VERSION CONTROL
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "git",
+        id: AppId("git"),
         name: "Git",
         description: "Distributed version control system",
         category: Category::Git,
         install_method: InstallMethod::Brew("git"),
+        env: &[],
         config_files: &["~/.gitconfig", "~/.gitignore_global"],
         dependencies: &[],
         tags: &["vcs", "essential"],
         url: "https://git-scm.com/",
     },
     App {
-        id: "gh",
+        id: AppId("gh"),
         name: "GitHub CLI",
         description: "GitHub's official CLI for PRs, issues, and more",
         category: Category::Git,
         install_method: InstallMethod::Brew("gh"),
+        env: &[],
         config_files: &["~/.config/gh/config.yml"],
         dependencies: &["git"],
         tags: &["github", "cli"],
         url: "https://cli.github.com/",
     },
     App {
-        id: "delta",
+        id: AppId("delta"),
         name: "Delta",
         description: "Beautiful syntax-highlighting pager for git and diff",
         category: Category::Git,
         install_method: InstallMethod::Brew("git-delta"),
+        env: &[],
         config_files: &[],
         dependencies: &["git"],
         tags: &["diff", "rust", "essential"],
         url: "https://github.com/dandavison/delta",
     },
     App {
-        id: "lazygit",
+        id: AppId("lazygit"),
         name: "Lazygit",
         description: "Simple terminal UI for git commands",
         category: Category::Git,
         install_method: InstallMethod::Brew("lazygit"),
+        env: &[],
         config_files: &["~/.config/lazygit/config.yml"],
         dependencies: &["git"],
         tags: &["tui", "go"],
         url: "https://github.com/jesseduffield/lazygit",
     },
     App {
-        id: "git-absorb",
+        id: AppId("git-absorb"),
         name: "Git Absorb",
         description: "Automatically absorb staged changes into your current branch",
         category: Category::Git,
         install_method: InstallMethod::Brew("git-absorb"),
+        env: &[],
         config_files: &[],
         dependencies: &["git"],
         tags: &["workflow", "rust"],
@@ -302,135 +570,228 @@ pub const CATALOG: &[App] = &[
     // TERMINAL TOOLS
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "tmux",
+        id: AppId("tmux"),
         name: "Tmux",
         description: "Terminal multiplexer - multiple windows in one terminal",
         category: Category::Terminal,
         install_method: InstallMethod::Brew("tmux"),
+        env: &[],
         config_files: &["~/.tmux.conf", "~/.config/tmux/tmux.conf"],
         dependencies: &[],
         tags: &["multiplexer", "essential"],
         url: "https://github.com/tmux/tmux",
     },
     App {
-        id: "zellij",
+        id: AppId("zellij"),
         name: "Zellij",
         description: "Modern terminal workspace with layouts and plugins",
         category: Category::Terminal,
         install_method: InstallMethod::Brew("zellij"),
+        env: &[],
         config_files: &["~/.config/zellij/config.kdl"],
         dependencies: &[],
         tags: &["multiplexer", "rust"],
         url: "https://zellij.dev/",
     },
     App {
-        id: "wezterm",
+        id: AppId("wezterm"),
         name: "WezTerm",
         description: "GPU-accelerated terminal emulator with Lua config",
         category: Category::Terminal,
         install_method: InstallMethod::BrewCask("wezterm"),
+        env: &[],
         config_files: &["~/.wezterm.lua", "~/.config/wezterm/wezterm.lua"],
         dependencies: &[],
         tags: &["emulator", "rust", "gpu"],
         url: "https://wezfurlong.org/wezterm/",
     },
     App {
-        id: "alacritty",
+        id: AppId("alacritty"),
         name: "Alacritty",
         description: "GPU-accelerated terminal emulator",
         category: Category::Terminal,
         install_method: InstallMethod::BrewCask("alacritty"),
+        env: &[],
         config_files: &["~/.config/alacritty/alacritty.toml"],
         dependencies: &[],
         tags: &["emulator", "rust", "gpu"],
         url: "https://alacritty.org/",
     },
     App {
-        id: "kitty",
+        id: AppId("kitty"),
         name: "Kitty",
         description: "Fast, feature-rich, GPU-based terminal emulator",
         category: Category::Terminal,
         install_method: InstallMethod::BrewCask("kitty"),
+        env: &[],
         config_files: &["~/.config/kitty/kitty.conf"],
         dependencies: &[],
         tags: &["emulator", "gpu"],
         url: "https://sw.kovidgoyal.net/kitty/",
     },
+    App {
+        id: AppId("iterm2"),
+        name: "iTerm2",
+        description: "Feature-packed macOS terminal emulator",
+        category: Category::Terminal,
+        install_method: InstallMethod::BrewCask("iterm2"),
+        env: &[],
+        config_files: &[
+            "~/Library/Preferences/com.googlecode.iterm2.plist",
+            "~/Library/Application Support/iTerm2/DynamicProfiles/loadstar.json",
+        ],
+        dependencies: &[],
+        tags: &["emulator", "macos"],
+        url: "https://iterm2.com/",
+    },
+    App {
+        id: AppId("ghostty"),
+        name: "Ghostty",
+        description: "Fast, native, GPU-accelerated terminal emulator",
+        category: Category::Terminal,
+        install_method: InstallMethod::PerOs {
+            macos: &InstallMethod::BrewCask("ghostty"),
+            linux: &InstallMethod::Manual("sudo snap install ghostty --classic"),
+        },
+        env: &[],
+        config_files: &["~/.config/ghostty/config"],
+        dependencies: &[],
+        tags: &["emulator", "gpu", "zig"],
+        url: "https://ghostty.org/",
+    },
+    App {
+        id: AppId("font-jetbrains-mono"),
+        name: "JetBrains Mono Nerd Font",
+        description: "Patched with icons for prompts and file listings",
+        category: Category::Terminal,
+        install_method: InstallMethod::PerOs {
+            macos: &InstallMethod::BrewCask("font-jetbrains-mono-nerd-font"),
+            linux: &InstallMethod::Manual(
+                "mkdir -p ~/.local/share/fonts && curl -fsSL https://github.com/ryanoasis/nerd-fonts/releases/latest/download/JetBrainsMono.tar.xz | tar -xJ -C ~/.local/share/fonts && fc-cache -f",
+            ),
+        },
+        env: &[],
+        config_files: &[],
+        dependencies: &[],
+        tags: &["font"],
+        url: "https://www.nerdfonts.com/",
+    },
+    App {
+        id: AppId("font-fira-code"),
+        name: "Fira Code Nerd Font",
+        description: "Patched with icons for prompts and file listings",
+        category: Category::Terminal,
+        install_method: InstallMethod::PerOs {
+            macos: &InstallMethod::BrewCask("font-fira-code-nerd-font"),
+            linux: &InstallMethod::Manual(
+                "mkdir -p ~/.local/share/fonts && curl -fsSL https://github.com/ryanoasis/nerd-fonts/releases/latest/download/FiraCode.tar.xz | tar -xJ -C ~/.local/share/fonts && fc-cache -f",
+            ),
+        },
+        env: &[],
+        config_files: &[],
+        dependencies: &[],
+        tags: &["font"],
+        url: "https://www.nerdfonts.com/",
+    },
+    App {
+        id: AppId("font-hack"),
+        name: "Hack Nerd Font",
+        description: "Patched with icons for prompts and file listings",
+        category: Category::Terminal,
+        install_method: InstallMethod::PerOs {
+            macos: &InstallMethod::BrewCask("font-hack-nerd-font"),
+            linux: &InstallMethod::Manual(
+                "mkdir -p ~/.local/share/fonts && curl -fsSL https://github.com/ryanoasis/nerd-fonts/releases/latest/download/Hack.tar.xz | tar -xJ -C ~/.local/share/fonts && fc-cache -f",
+            ),
+        },
+        env: &[],
+        config_files: &[],
+        dependencies: &[],
+        tags: &["font"],
+        url: "https://www.nerdfonts.com/",
+    },
     // ═══════════════════════════════════════════════════════════════
     // FILE MANAGEMENT
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "eza",
+        id: AppId("eza"),
         name: "Eza",
         description: "Modern replacement for ls with icons and git integration",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("eza"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["ls", "rust", "essential"],
         url: "https://eza.rocks/",
     },
     App {
-        id: "bat",
+        id: AppId("bat"),
         name: "Bat",
         description: "Cat clone with syntax highlighting and git integration",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("bat"),
+        env: &[],
         config_files: &["~/.config/bat/config"],
         dependencies: &[],
         tags: &["cat", "rust", "essential"],
         url: "https://github.com/sharkdp/bat",
     },
     App {
-        id: "yazi",
+        id: AppId("yazi"),
         name: "Yazi",
         description: "Blazing fast terminal file manager with async I/O",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("yazi"),
+        env: &[],
         config_files: &["~/.config/yazi/yazi.toml", "~/.config/yazi/keymap.toml"],
         dependencies: &[],
         tags: &["tui", "rust", "async"],
         url: "https://yazi-rs.github.io/",
     },
     App {
-        id: "broot",
+        id: AppId("broot"),
         name: "Broot",
         description: "New way to see and navigate directory trees",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("broot"),
+        env: &[],
         config_files: &["~/.config/broot/conf.hjson"],
         dependencies: &[],
-        tags: &["tree", "rust"],
+        tags: &["tui", "tree", "rust"],
         url: "https://dystroy.org/broot/",
     },
     App {
-        id: "dua",
+        id: AppId("dua"),
         name: "Dua",
         description: "Disk usage analyzer with interactive mode",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("dua-cli"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
-        tags: &["disk", "rust"],
+        tags: &["tui", "disk", "rust"],
         url: "https://github.com/Byron/dua-cli",
     },
     App {
-        id: "dust",
+        id: AppId("dust"),
         name: "Dust",
         description: "More intuitive version of du in rust",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("dust"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["disk", "rust"],
         url: "https://github.com/bootandy/dust",
     },
     App {
-        id: "trash-cli",
+        id: AppId("trash-cli"),
         name: "Trash CLI",
         description: "Move files to trash instead of permanent deletion",
         category: Category::FileManager,
         install_method: InstallMethod::Brew("trash-cli"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["safety"],
@@ -440,44 +801,48 @@ pub const CATALOG: &[App] = &[
     // SEARCH & NAVIGATION
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "fzf",
+        id: AppId("fzf"),
         name: "FZF",
         description: "Fuzzy finder for the command line",
         category: Category::Search,
         install_method: InstallMethod::Brew("fzf"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["fuzzy", "go", "essential"],
         url: "https://github.com/junegunn/fzf",
     },
     App {
-        id: "ripgrep",
+        id: AppId("ripgrep"),
         name: "Ripgrep",
         description: "Recursively search directories for a regex pattern",
         category: Category::Search,
         install_method: InstallMethod::Brew("ripgrep"),
+        env: &[],
         config_files: &["~/.ripgreprc"],
         dependencies: &[],
         tags: &["grep", "rust", "essential"],
         url: "https://github.com/BurntSushi/ripgrep",
     },
     App {
-        id: "fd",
+        id: AppId("fd"),
         name: "Fd",
         description: "Simple, fast alternative to find",
         category: Category::Search,
         install_method: InstallMethod::Brew("fd"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["find", "rust", "essential"],
         url: "https://github.com/sharkdp/fd",
     },
     App {
-        id: "ag",
+        id: AppId("ag"),
         name: "The Silver Searcher",
         description: "Code searching tool similar to ack, but faster",
         category: Category::Search,
         install_method: InstallMethod::Brew("the_silver_searcher"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["grep"],
@@ -487,66 +852,72 @@ pub const CATALOG: &[App] = &[
     // SYSTEM UTILITIES
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "htop",
+        id: AppId("htop"),
         name: "Htop",
         description: "Interactive process viewer",
         category: Category::System,
         install_method: InstallMethod::Brew("htop"),
+        env: &[],
         config_files: &["~/.config/htop/htoprc"],
         dependencies: &[],
-        tags: &["process", "essential"],
+        tags: &["tui", "process", "essential"],
         url: "https://htop.dev/",
     },
     App {
-        id: "btop",
+        id: AppId("btop"),
         name: "Btop",
         description: "Resource monitor with beautiful graphs",
         category: Category::System,
         install_method: InstallMethod::Brew("btop"),
+        env: &[],
         config_files: &["~/.config/btop/btop.conf"],
         dependencies: &[],
-        tags: &["monitor", "cpp"],
+        tags: &["tui", "monitor", "cpp"],
         url: "https://github.com/aristocratos/btop",
     },
     App {
-        id: "bottom",
+        id: AppId("bottom"),
         name: "Bottom",
         description: "Cross-platform graphical process/system monitor",
         category: Category::System,
         install_method: InstallMethod::Brew("bottom"),
+        env: &[],
         config_files: &["~/.config/bottom/bottom.toml"],
         dependencies: &[],
-        tags: &["monitor", "rust"],
+        tags: &["tui", "monitor", "rust"],
         url: "https://github.com/ClementTsang/bottom",
     },
     App {
-        id: "procs",
+        id: AppId("procs"),
         name: "Procs",
         description: "Modern replacement for ps",
         category: Category::System,
         install_method: InstallMethod::Brew("procs"),
+        env: &[],
         config_files: &["~/.config/procs/config.toml"],
         dependencies: &[],
         tags: &["process", "rust"],
         url: "https://github.com/dalance/procs",
     },
     App {
-        id: "hyperfine",
+        id: AppId("hyperfine"),
         name: "Hyperfine",
         description: "Command-line benchmarking tool",
         category: Category::System,
         install_method: InstallMethod::Brew("hyperfine"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["benchmark", "rust"],
         url: "https://github.com/sharkdp/hyperfine",
     },
     App {
-        id: "tokei",
+        id: AppId("tokei"),
         name: "Tokei",
         description: "Count lines of code quickly",
         category: Category::System,
         install_method: InstallMethod::Brew("tokei"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["stats", "rust"],
@@ -556,55 +927,60 @@ pub const CATALOG: &[App] = &[
     // NETWORK TOOLS
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "curl",
+        id: AppId("curl"),
         name: "cURL",
         description: "Transfer data with URLs",
         category: Category::Network,
         install_method: InstallMethod::Brew("curl"),
+        env: &[],
         config_files: &["~/.curlrc"],
         dependencies: &[],
         tags: &["http", "essential"],
         url: "https://curl.se/",
     },
     App {
-        id: "httpie",
+        id: AppId("httpie"),
         name: "HTTPie",
         description: "Human-friendly HTTP client for the API era",
         category: Category::Network,
         install_method: InstallMethod::Brew("httpie"),
+        env: &[],
         config_files: &["~/.config/httpie/config.json"],
         dependencies: &[],
         tags: &["http", "python"],
         url: "https://httpie.io/",
     },
     App {
-        id: "xh",
+        id: AppId("xh"),
         name: "xh",
         description: "Friendly and fast tool for sending HTTP requests",
         category: Category::Network,
         install_method: InstallMethod::Brew("xh"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["http", "rust"],
         url: "https://github.com/ducaale/xh",
     },
     App {
-        id: "bandwhich",
+        id: AppId("bandwhich"),
         name: "Bandwhich",
         description: "Terminal bandwidth utilization tool",
         category: Category::Network,
         install_method: InstallMethod::Brew("bandwhich"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["monitor", "rust"],
         url: "https://github.com/imsnif/bandwhich",
     },
     App {
-        id: "doggo",
+        id: AppId("doggo"),
         name: "Doggo",
         description: "Command-line DNS client for humans",
         category: Category::Network,
         install_method: InstallMethod::Brew("doggo"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["dns", "go"],
@@ -614,55 +990,60 @@ pub const CATALOG: &[App] = &[
     // CONTAINERS & VMs
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "docker",
+        id: AppId("docker"),
         name: "Docker",
         description: "Container runtime and build system",
         category: Category::Container,
         install_method: InstallMethod::BrewCask("docker"),
+        env: &[],
         config_files: &["~/.docker/config.json"],
         dependencies: &[],
         tags: &["container", "essential"],
         url: "https://www.docker.com/",
     },
     App {
-        id: "podman",
+        id: AppId("podman"),
         name: "Podman",
         description: "Daemonless container engine",
         category: Category::Container,
         install_method: InstallMethod::Brew("podman"),
+        env: &[],
         config_files: &["~/.config/containers/"],
         dependencies: &[],
         tags: &["container"],
         url: "https://podman.io/",
     },
     App {
-        id: "lazydocker",
+        id: AppId("lazydocker"),
         name: "Lazydocker",
         description: "Terminal UI for docker and docker-compose",
         category: Category::Container,
         install_method: InstallMethod::Brew("lazydocker"),
+        env: &[],
         config_files: &["~/.config/lazydocker/config.yml"],
         dependencies: &["docker"],
         tags: &["tui", "go"],
         url: "https://github.com/jesseduffield/lazydocker",
     },
     App {
-        id: "dive",
+        id: AppId("dive"),
         name: "Dive",
         description: "Explore docker image layers",
         category: Category::Container,
         install_method: InstallMethod::Brew("dive"),
+        env: &[],
         config_files: &[],
         dependencies: &["docker"],
         tags: &["docker", "go"],
         url: "https://github.com/wagoodman/dive",
     },
     App {
-        id: "colima",
+        id: AppId("colima"),
         name: "Colima",
         description: "Container runtimes on macOS with minimal setup",
         category: Category::Container,
         install_method: InstallMethod::Brew("colima"),
+        env: &[],
         config_files: &["~/.colima/default/colima.yaml"],
         dependencies: &[],
         tags: &["docker", "macos"],
@@ -672,124 +1053,250 @@ pub const CATALOG: &[App] = &[
     // LANGUAGES & RUNTIMES
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "mise",
+        id: AppId("mise"),
         name: "Mise",
         description: "Polyglot runtime manager (replaces asdf, nvm, pyenv)",
         category: Category::Language,
         install_method: InstallMethod::Brew("mise"),
+        env: &[],
         config_files: &["~/.config/mise/config.toml", ".mise.toml"],
         dependencies: &[],
         tags: &["version-manager", "rust", "essential"],
         url: "https://mise.jdx.dev/",
     },
     App {
-        id: "rustup",
+        id: AppId("rustup"),
         name: "Rustup",
         description: "Rust toolchain installer and manager",
         category: Category::Language,
         install_method: InstallMethod::Script("https://sh.rustup.rs"),
+        // A Homebrew rust on PATH would otherwise stop rustup-init with
+        // a question nobody is there to answer
+        env: &[("RUSTUP_INIT_SKIP_PATH_CHECK", "yes")],
         config_files: &["~/.cargo/config.toml"],
         dependencies: &[],
         tags: &["rust"],
         url: "https://rustup.rs/",
     },
     App {
-        id: "go",
+        id: AppId("go"),
         name: "Go",
         description: "Go programming language",
         category: Category::Language,
         install_method: InstallMethod::Brew("go"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["go"],
         url: "https://go.dev/",
     },
     App {
-        id: "python",
+        id: AppId("node"),
+        name: "Node.js",
+        description: "JavaScript runtime, with npm",
+        category: Category::Language,
+        install_method: InstallMethod::Brew("node"),
+        env: &[],
+        config_files: &["~/.npmrc"],
+        dependencies: &[],
+        tags: &["javascript"],
+        url: "https://nodejs.org/",
+    },
+    App {
+        id: AppId("python"),
         name: "Python",
         description: "Python programming language",
         category: Category::Language,
         install_method: InstallMethod::Brew("python"),
+        env: &[],
         config_files: &["~/.config/pip/pip.conf"],
         dependencies: &[],
         tags: &["python"],
         url: "https://www.python.org/",
     },
     App {
-        id: "uv",
+        id: AppId("uv"),
         name: "uv",
         description: "Extremely fast Python package installer",
         category: Category::Language,
         install_method: InstallMethod::Brew("uv"),
+        env: &[],
         config_files: &["~/.config/uv/uv.toml"],
         dependencies: &[],
         tags: &["python", "rust", "package-manager"],
         url: "https://github.com/astral-sh/uv",
     },
     App {
-        id: "deno",
+        id: AppId("deno"),
         name: "Deno",
         description: "Secure runtime for JavaScript and TypeScript",
         category: Category::Language,
         install_method: InstallMethod::Brew("deno"),
+        env: &[],
         config_files: &["deno.json"],
         dependencies: &[],
         tags: &["javascript", "typescript", "rust"],
         url: "https://deno.land/",
     },
     App {
-        id: "bun",
+        id: AppId("bun"),
         name: "Bun",
         description: "Incredibly fast JavaScript runtime and toolkit",
         category: Category::Language,
         install_method: InstallMethod::Brew("bun"),
+        env: &[],
         config_files: &["bunfig.toml"],
         dependencies: &[],
         tags: &["javascript", "fast"],
         url: "https://bun.sh/",
     },
     // ═══════════════════════════════════════════════════════════════
+    // LANGUAGE SERVERS & FORMATTERS
+    // ═══════════════════════════════════════════════════════════════
+    App {
+        id: AppId("rust-analyzer"),
+        name: "rust-analyzer",
+        description: "Rust language server",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Brew("rust-analyzer"),
+        env: &[],
+        config_files: &[],
+        dependencies: &[],
+        tags: &["lsp", "rust"],
+        url: "https://rust-analyzer.github.io/",
+    },
+    App {
+        id: AppId("gopls"),
+        name: "gopls",
+        description: "Official Go language server",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Go("golang.org/x/tools/gopls@latest"),
+        // gopls@latest tracks the newest Go; distro Go packages pin
+        // GOTOOLCHAIN=local, which refuses to fetch it
+        env: &[("GOTOOLCHAIN", "auto")],
+        config_files: &[],
+        dependencies: &[],
+        tags: &["lsp", "go"],
+        url: "https://pkg.go.dev/golang.org/x/tools/gopls",
+    },
+    App {
+        id: AppId("pyright"),
+        name: "Pyright",
+        description: "Fast Python type checker and language server",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Npm("pyright"),
+        env: &[],
+        config_files: &["pyrightconfig.json"],
+        dependencies: &[],
+        tags: &["lsp", "python"],
+        url: "https://github.com/microsoft/pyright",
+    },
+    App {
+        id: AppId("prettier"),
+        name: "Prettier",
+        description: "Opinionated formatter for JS, TS, CSS, JSON and Markdown",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Npm("prettier"),
+        env: &[],
+        config_files: &[".prettierrc"],
+        dependencies: &[],
+        tags: &["formatter", "javascript"],
+        url: "https://prettier.io/",
+    },
+    App {
+        id: AppId("ruff"),
+        name: "Ruff",
+        description: "Extremely fast Python linter and formatter",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Pip("ruff"),
+        env: &[],
+        config_files: &["ruff.toml"],
+        dependencies: &[],
+        tags: &["formatter", "linter", "python", "rust"],
+        url: "https://docs.astral.sh/ruff/",
+    },
+    App {
+        id: AppId("shellcheck"),
+        name: "ShellCheck",
+        description: "Static analysis for shell scripts",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Brew("shellcheck"),
+        env: &[],
+        config_files: &[".shellcheckrc"],
+        dependencies: &[],
+        tags: &["linter", "shell"],
+        url: "https://www.shellcheck.net/",
+    },
+    App {
+        id: AppId("shfmt"),
+        name: "shfmt",
+        description: "Shell script formatter",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Brew("shfmt"),
+        env: &[],
+        config_files: &[],
+        dependencies: &[],
+        tags: &["formatter", "shell", "go"],
+        url: "https://github.com/mvdan/sh",
+    },
+    App {
+        id: AppId("stylua"),
+        name: "StyLua",
+        description: "Opinionated Lua formatter",
+        category: Category::LanguageServer,
+        install_method: InstallMethod::Brew("stylua"),
+        env: &[],
+        config_files: &["stylua.toml"],
+        dependencies: &[],
+        tags: &["formatter", "lua", "rust"],
+        url: "https://github.com/JohnnyMorganz/StyLua",
+    },
+    // ═══════════════════════════════════════════════════════════════
     // DATABASES
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "sqlite",
+        id: AppId("sqlite"),
         name: "SQLite",
         description: "Self-contained SQL database engine",
         category: Category::Database,
         install_method: InstallMethod::Brew("sqlite"),
+        env: &[],
         config_files: &["~/.sqliterc"],
         dependencies: &[],
         tags: &["sql"],
         url: "https://www.sqlite.org/",
     },
     App {
-        id: "postgresql",
+        id: AppId("postgresql"),
         name: "PostgreSQL",
         description: "Powerful open-source relational database",
         category: Category::Database,
         install_method: InstallMethod::Brew("postgresql@16"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["sql"],
         url: "https://www.postgresql.org/",
     },
     App {
-        id: "redis",
+        id: AppId("redis"),
         name: "Redis",
         description: "In-memory data structure store",
         category: Category::Database,
         install_method: InstallMethod::Brew("redis"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["nosql", "cache"],
         url: "https://redis.io/",
     },
     App {
-        id: "usql",
+        id: AppId("usql"),
         name: "usql",
         description: "Universal command-line interface for SQL databases",
         category: Category::Database,
         install_method: InstallMethod::Brew("usql"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["sql", "universal"],
@@ -799,55 +1306,60 @@ pub const CATALOG: &[App] = &[
     // SECURITY
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "age",
+        id: AppId("age"),
         name: "Age",
         description: "Simple, modern, secure file encryption",
         category: Category::Security,
         install_method: InstallMethod::Brew("age"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["encryption", "go"],
         url: "https://age-encryption.org/",
     },
     App {
-        id: "gnupg",
+        id: AppId("gnupg"),
         name: "GnuPG",
         description: "GNU Privacy Guard - encryption and signing",
         category: Category::Security,
         install_method: InstallMethod::Brew("gnupg"),
+        env: &[],
         config_files: &["~/.gnupg/gpg.conf", "~/.gnupg/gpg-agent.conf"],
         dependencies: &[],
         tags: &["encryption", "signing"],
         url: "https://gnupg.org/",
     },
     App {
-        id: "pass",
+        id: AppId("pass"),
         name: "Pass",
         description: "Standard unix password manager",
         category: Category::Security,
         install_method: InstallMethod::Brew("pass"),
+        env: &[],
         config_files: &["~/.password-store/"],
         dependencies: &["gnupg"],
         tags: &["password"],
         url: "https://www.passwordstore.org/",
     },
     App {
-        id: "sops",
+        id: AppId("sops"),
         name: "SOPS",
         description: "Secrets OPerationS - manage encrypted files",
         category: Category::Security,
         install_method: InstallMethod::Brew("sops"),
+        env: &[],
         config_files: &[".sops.yaml"],
         dependencies: &[],
         tags: &["secrets", "go"],
         url: "https://github.com/getsops/sops",
     },
     App {
-        id: "1password-cli",
+        id: AppId("1password-cli"),
         name: "1Password CLI",
         description: "1Password command-line tool",
         category: Category::Security,
         install_method: InstallMethod::BrewCask("1password-cli"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["password", "commercial"],
@@ -857,66 +1369,72 @@ pub const CATALOG: &[App] = &[
     // PRODUCTIVITY
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "jq",
+        id: AppId("jq"),
         name: "jq",
         description: "Lightweight command-line JSON processor",
         category: Category::Productivity,
         install_method: InstallMethod::Brew("jq"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["json", "essential"],
         url: "https://jqlang.github.io/jq/",
     },
     App {
-        id: "yq",
+        id: AppId("yq"),
         name: "yq",
         description: "YAML/JSON/XML processor like jq",
         category: Category::Productivity,
         install_method: InstallMethod::Brew("yq"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["yaml", "json"],
         url: "https://mikefarah.gitbook.io/yq/",
     },
     App {
-        id: "jless",
+        id: AppId("jless"),
         name: "jless",
         description: "Command-line JSON viewer",
         category: Category::Productivity,
         install_method: InstallMethod::Brew("jless"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
-        tags: &["json", "rust"],
+        tags: &["tui", "json", "rust"],
         url: "https://jless.io/",
     },
     App {
-        id: "glow",
+        id: AppId("glow"),
         name: "Glow",
         description: "Render markdown on the CLI with style",
         category: Category::Productivity,
         install_method: InstallMethod::Brew("glow"),
+        env: &[],
         config_files: &["~/.config/glow/glow.yml"],
         dependencies: &[],
         tags: &["markdown", "go"],
         url: "https://github.com/charmbracelet/glow",
     },
     App {
-        id: "slides",
+        id: AppId("slides"),
         name: "Slides",
         description: "Terminal-based presentation tool",
         category: Category::Productivity,
         install_method: InstallMethod::Brew("slides"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
-        tags: &["presentation", "go"],
+        tags: &["tui", "presentation", "go"],
         url: "https://github.com/maaslalani/slides",
     },
     App {
-        id: "chezmoi",
+        id: AppId("chezmoi"),
         name: "Chezmoi",
         description: "Manage your dotfiles across multiple machines",
         category: Category::Productivity,
         install_method: InstallMethod::Brew("chezmoi"),
+        env: &[],
         config_files: &["~/.config/chezmoi/chezmoi.toml"],
         dependencies: &[],
         tags: &["dotfiles", "go", "essential"],
@@ -926,33 +1444,36 @@ pub const CATALOG: &[App] = &[
     // MEDIA
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "ffmpeg",
+        id: AppId("ffmpeg"),
         name: "FFmpeg",
         description: "Complete solution for audio/video processing",
         category: Category::Media,
         install_method: InstallMethod::Brew("ffmpeg"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["video", "audio"],
         url: "https://ffmpeg.org/",
     },
     App {
-        id: "imagemagick",
+        id: AppId("imagemagick"),
         name: "ImageMagick",
         description: "Create, edit, compose, or convert images",
         category: Category::Media,
         install_method: InstallMethod::Brew("imagemagick"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["image"],
         url: "https://imagemagick.org/",
     },
     App {
-        id: "yt-dlp",
+        id: AppId("yt-dlp"),
         name: "yt-dlp",
         description: "Download videos from YouTube and other sites",
         category: Category::Media,
         install_method: InstallMethod::Brew("yt-dlp"),
+        env: &[],
         config_files: &["~/.config/yt-dlp/config"],
         dependencies: &["ffmpeg"],
         tags: &["video", "download"],
@@ -962,55 +1483,125 @@ pub const CATALOG: &[App] = &[
     // CLOUD & DEVOPS
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "awscli",
+        id: AppId("awscli"),
         name: "AWS CLI",
         description: "Amazon Web Services command-line interface",
         category: Category::Cloud,
         install_method: InstallMethod::Brew("awscli"),
+        env: &[],
         config_files: &["~/.aws/config", "~/.aws/credentials"],
         dependencies: &[],
         tags: &["aws", "cloud"],
         url: "https://aws.amazon.com/cli/",
     },
     App {
-        id: "terraform",
+        id: AppId("gcloud"),
+        name: "Google Cloud CLI",
+        description: "gcloud, gsutil and bq for Google Cloud",
+        category: Category::Cloud,
+        install_method: InstallMethod::PerOs {
+            macos: &InstallMethod::BrewCask("gcloud-cli"),
+            linux: &InstallMethod::Manual(
+                "curl -fsSL https://sdk.cloud.google.com | bash -s -- --disable-prompts --install-dir=$HOME/.local/share",
+            ),
+        },
+        env: &[],
+        config_files: &["~/.config/gcloud/"],
+        dependencies: &[],
+        tags: &["gcp", "cloud"],
+        url: "https://cloud.google.com/sdk",
+    },
+    App {
+        id: AppId("azure-cli"),
+        name: "Azure CLI",
+        description: "Microsoft Azure command-line interface",
+        category: Category::Cloud,
+        install_method: InstallMethod::Brew("azure-cli"),
+        env: &[],
+        config_files: &["~/.azure/config"],
+        dependencies: &[],
+        tags: &["azure", "cloud"],
+        url: "https://learn.microsoft.com/cli/azure/",
+    },
+    App {
+        id: AppId("terraform"),
         name: "Terraform",
         description: "Infrastructure as Code tool",
         category: Category::Cloud,
         install_method: InstallMethod::Brew("terraform"),
+        env: &[],
         config_files: &["~/.terraformrc"],
         dependencies: &[],
         tags: &["iac", "hashicorp"],
         url: "https://www.terraform.io/",
     },
     App {
-        id: "kubectl",
+        id: AppId("tflint"),
+        name: "TFLint",
+        description: "Linter for Terraform that catches provider-specific mistakes",
+        category: Category::Cloud,
+        install_method: InstallMethod::Brew("tflint"),
+        env: &[],
+        config_files: &[".tflint.hcl"],
+        dependencies: &[],
+        tags: &["iac", "linter"],
+        url: "https://github.com/terraform-linters/tflint",
+    },
+    App {
+        id: AppId("terragrunt"),
+        name: "Terragrunt",
+        description: "Thin wrapper that keeps Terraform configs DRY",
+        category: Category::Cloud,
+        install_method: InstallMethod::Brew("terragrunt"),
+        env: &[],
+        config_files: &["terragrunt.hcl"],
+        dependencies: &["terraform"],
+        tags: &["iac"],
+        url: "https://terragrunt.gruntwork.io/",
+    },
+    App {
+        id: AppId("pre-commit"),
+        name: "pre-commit",
+        description: "Git hook manager for linting and formatting before commit",
+        category: Category::Cloud,
+        install_method: InstallMethod::Pip("pre-commit"),
+        env: &[],
+        config_files: &[".pre-commit-config.yaml"],
+        dependencies: &[],
+        tags: &["git", "hooks", "iac"],
+        url: "https://pre-commit.com/",
+    },
+    App {
+        id: AppId("kubectl"),
         name: "kubectl",
         description: "Kubernetes command-line tool",
         category: Category::Cloud,
         install_method: InstallMethod::Brew("kubectl"),
+        env: &[],
         config_files: &["~/.kube/config"],
         dependencies: &[],
         tags: &["kubernetes"],
         url: "https://kubernetes.io/docs/reference/kubectl/",
     },
     App {
-        id: "k9s",
+        id: AppId("k9s"),
         name: "K9s",
         description: "Terminal UI to interact with Kubernetes clusters",
         category: Category::Cloud,
         install_method: InstallMethod::Brew("k9s"),
+        env: &[],
         config_files: &["~/.config/k9s/config.yml"],
         dependencies: &["kubectl"],
         tags: &["kubernetes", "tui", "go"],
         url: "https://k9scli.io/",
     },
     App {
-        id: "helm",
+        id: AppId("helm"),
         name: "Helm",
         description: "Kubernetes package manager",
         category: Category::Cloud,
         install_method: InstallMethod::Brew("helm"),
+        env: &[],
         config_files: &[],
         dependencies: &["kubectl"],
         tags: &["kubernetes"],
@@ -1020,33 +1611,36 @@ pub const CATALOG: &[App] = &[
     // AI & ML TOOLS
     // ═══════════════════════════════════════════════════════════════
     App {
-        id: "ollama",
+        id: AppId("ollama"),
         name: "Ollama",
         description: "Run large language models locally",
         category: Category::AI,
         install_method: InstallMethod::Brew("ollama"),
+        env: &[],
         config_files: &[],
         dependencies: &[],
         tags: &["llm", "local"],
         url: "https://ollama.ai/",
     },
     App {
-        id: "aichat",
+        id: AppId("aichat"),
         name: "AIChat",
         description: "Chat with AI models in the terminal",
         category: Category::AI,
         install_method: InstallMethod::Brew("aichat"),
+        env: &[],
         config_files: &["~/.config/aichat/config.yaml"],
         dependencies: &[],
         tags: &["llm", "rust"],
         url: "https://github.com/sigoden/aichat",
     },
     App {
-        id: "claude-code",
+        id: AppId("claude-code"),
         name: "Claude Code",
         description: "Anthropic's CLI coding assistant",
         category: Category::AI,
         install_method: InstallMethod::Npm("@anthropic-ai/claude-code"),
+        env: &[],
         config_files: &["~/.claude/"],
         dependencies: &[],
         tags: &["llm", "coding"],
@@ -1054,20 +1648,204 @@ pub const CATALOG: &[App] = &[
     },
 ];
 
-/// Get all apps in a specific category
-pub fn apps_by_category(category: &Category) -> Vec<&'static App> {
+/// Apps that are picked together as one unit on the DevTools screen
+#[derive(Debug)]
+pub struct Bundle {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Category whose list shows the bundle
+    pub category: Category,
+    pub apps: &'static [&'static str],
+}
+
+pub const BUNDLES: &[Bundle] = &[Bundle {
+    name: "Infrastructure as Code",
+    description: "Terraform with linting, Terragrunt and pre-commit hooks",
+    category: Category::Cloud,
+    apps: &["terraform", "tflint", "terragrunt", "pre-commit"],
+}];
+
+/// The bundle shown with a category, if it has one
+pub fn bundle_for(category: &Category) -> Option<&'static Bundle> {
+    BUNDLES.iter().find(|b| b.category == *category)
+}
+
+/// Apps that do the same job. Picking more than `max` of one group is
+/// usually an accident; Review points it out but installs them anyway.
+#[derive(Debug)]
+pub struct Overlap {
+    pub label: &'static str,
+    pub category: Category,
+    pub apps: &'static [&'static str],
+    pub max: usize,
+}
+
+pub const OVERLAPS: &[Overlap] = &[
+    Overlap {
+        label: "terminal emulators",
+        category: Category::Terminal,
+        apps: &["wezterm", "alacritty", "kitty", "iterm2", "ghostty"],
+        max: 2,
+    },
+    Overlap {
+        label: "multiplexers",
+        category: Category::Terminal,
+        apps: &["tmux", "zellij"],
+        max: 1,
+    },
+    Overlap {
+        label: "prompts",
+        category: Category::Shell,
+        apps: &["starship", "powerlevel10k", "pure"],
+        max: 1,
+    },
+    Overlap {
+        label: "system monitors",
+        category: Category::System,
+        apps: &["htop", "btop", "bottom"],
+        max: 1,
+    },
+    Overlap {
+        label: "disk usage viewers",
+        category: Category::FileManager,
+        apps: &["dua", "dust"],
+        max: 1,
+    },
+    Overlap {
+        label: "HTTP clients",
+        category: Category::Network,
+        apps: &["httpie", "xh"],
+        max: 1,
+    },
+    Overlap {
+        label: "container engines",
+        category: Category::Container,
+        apps: &["docker", "podman"],
+        max: 1,
+    },
+];
+
+/// Look up an app by id
+pub fn find_app(id: &str) -> Option<&'static App> {
     CATALOG
         .iter()
-        .filter(|app| app.category == *category)
-        .collect()
+        .find(|a| a.id.as_str() == id)
+        .or_else(|| crate::custom::find(id))
 }
 
-/// Get all apps with a specific tag
-pub fn apps_by_tag(tag: &str) -> Vec<&'static App> {
+// ─── Queries ─────────────────────────────────────────────────────────
+
+/// A filter over the catalog, built up one condition at a time:
+/// `Query::new().category(Category::Cloud).platform(Os::Linux).apps()`.
+/// Apps come back in catalog order.
+#[derive(Default)]
+pub struct Query<'a> {
+    categories: Vec<Category>,
+    tags: Vec<&'a str>,
+    platform: Option<Os>,
+    installed: Option<(bool, InstalledCheck<'a>)>,
+}
+
+type InstalledCheck<'a> = Box<dyn Fn(&App) -> bool + 'a>;
+
+impl<'a> Query<'a> {
+    /// Every app in the catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apps in `category`. Given more than once, apps in any of them.
+    pub fn category(mut self, category: Category) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    /// Apps tagged `tag`. Given more than once, apps with all of them.
+    pub fn tag(mut self, tag: &'a str) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Apps that can be installed on `os`
+    pub fn platform(mut self, os: Os) -> Self {
+        self.platform = Some(os);
+        self
+    }
+
+    /// Apps that are installed, or with `false` the ones that aren't.
+    /// The catalog can't tell by itself, so `is_installed` decides.
+    pub fn installed(mut self, installed: bool, is_installed: impl Fn(&App) -> bool + 'a) -> Self {
+        self.installed = Some((installed, Box::new(is_installed)));
+        self
+    }
+
+    pub fn matches(&self, app: &App) -> bool {
+        (self.categories.is_empty() || self.categories.iter().any(|c| app.in_category(*c)))
+            && self.tags.iter().all(|tag| app.tags.contains(tag))
+            && self.platform.is_none_or(|os| app.supports(os))
+            && self
+                .installed
+                .as_ref()
+                .is_none_or(|(installed, check)| check(app) == *installed)
+    }
+
+    pub fn apps(&self) -> Vec<&'static App> {
+        CATALOG.iter().filter(|app| self.matches(app)).collect()
+    }
+
+    pub fn ids(&self) -> Vec<AppId> {
+        self.apps().into_iter().map(|app| app.id).collect()
+    }
+}
+
+/// Add the provider of every missing toolchain ahead of the apps that need
+/// it. `has_command` says whether a toolchain command is already available.
+/// Returns the full install list and the providers that were added.
+pub fn with_toolchains(
+    apps: Vec<&'static App>,
+    has_command: impl Fn(&str) -> bool,
+) -> (Vec<&'static App>, Vec<&'static App>) {
+    let mut added: Vec<&'static App> = Vec::new();
+
+    for app in &apps {
+        let Some(toolchain) = app.method().toolchain() else {
+            continue;
+        };
+        if has_command(toolchain.command)
+            || apps.iter().any(|a| a.id == toolchain.provider)
+            || added.iter().any(|a| a.id == toolchain.provider)
+        {
+            continue;
+        }
+        if let Some(provider) = find_app(toolchain.provider) {
+            added.push(provider);
+        }
+    }
+
+    let mut resolved = added.clone();
+    resolved.extend(apps);
+
+    // Providers the user picked themselves must come first too
+    resolved.sort_by_key(|a| !is_toolchain_provider(a));
+
+    (resolved, added)
+}
+
+fn is_toolchain_provider(app: &App) -> bool {
     CATALOG
         .iter()
-        .filter(|app| app.tags.contains(&tag))
-        .collect()
+        .filter_map(|a| a.method().toolchain())
+        .any(|t| app.id == t.provider)
+}
+
+/// Get all apps in a specific category
+pub fn apps_by_category(category: &Category) -> Vec<&'static App> {
+    Query::new().category(*category).apps()
+}
+
+/// Get all apps with a specific tag
+pub fn apps_by_tag(tag: &str) -> Vec<&'static App> {
+    Query::new().tag(tag).apps()
 }
 
 /// Get essential apps (tagged as essential)
@@ -1093,6 +1871,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dependencies_are_in_catalog() {
+        for app in CATALOG {
+            for dep in app.dependencies {
+                assert!(
+                    find_app(dep).is_some(),
+                    "{} depends on {}, which isn't in the catalog",
+                    app.id,
+                    dep
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bundle_apps_are_in_catalog() {
+        for bundle in BUNDLES {
+            for id in bundle.apps {
+                assert!(find_app(id).is_some(), "{} not in catalog", id);
+            }
+        }
+        assert!(bundle_for(&Category::Cloud).is_some());
+        assert!(bundle_for(&Category::Media).is_none());
+    }
+
+    #[test]
+    fn overlap_groups_are_in_catalog() {
+        for overlap in OVERLAPS {
+            for id in overlap.apps {
+                let app = find_app(id).unwrap_or_else(|| panic!("{} not in catalog", id));
+                assert_eq!(app.category, overlap.category, "{}", id);
+            }
+        }
+    }
+
     #[test]
     fn essential_apps_exist() {
         let essentials = essential_apps();
@@ -1146,6 +1959,145 @@ mod tests {
         }
     }
 
+    #[test]
+    fn missing_toolchains_are_added_first() {
+        let claude = find_app("claude-code").unwrap();
+        let (apps, added) = with_toolchains(vec![claude], |_| false);
+        assert_eq!(added.iter().map(|a| a.id).collect::<Vec<_>>(), ["node"]);
+        assert_eq!(
+            apps.iter().map(|a| a.id).collect::<Vec<_>>(),
+            ["node", "claude-code"]
+        );
+
+        // Already on the system: nothing added
+        let (apps, added) = with_toolchains(vec![claude], |cmd| cmd == "npm");
+        assert!(added.is_empty());
+        assert_eq!(apps.len(), 1);
+    }
+
+    #[test]
+    fn selected_provider_is_moved_ahead_not_duplicated() {
+        let claude = find_app("claude-code").unwrap();
+        let node = find_app("node").unwrap();
+        let (apps, added) = with_toolchains(vec![claude, node], |_| false);
+        assert!(added.is_empty());
+        assert_eq!(
+            apps.iter().map(|a| a.id).collect::<Vec<_>>(),
+            ["node", "claude-code"]
+        );
+    }
+
+    #[test]
+    fn per_os_methods_resolve_to_a_concrete_method() {
+        for app in CATALOG {
+            assert!(
+                !matches!(app.method(), InstallMethod::PerOs { .. }),
+                "{} resolves to another PerOs",
+                app.id
+            );
+        }
+        let zed = find_app("zed").unwrap();
+        if cfg!(target_os = "macos") {
+            assert!(matches!(zed.method(), InstallMethod::BrewCask("zed")));
+        } else {
+            assert!(matches!(zed.method(), InstallMethod::Script(_)));
+        }
+    }
+
+    #[test]
+    fn toolchain_providers_exist_in_catalog() {
+        for app in CATALOG {
+            if let Some(t) = app.method().toolchain() {
+                assert!(find_app(t.provider).is_some(), "{} missing", t.provider);
+            }
+        }
+    }
+
+    #[test]
+    fn query_conditions_narrow_the_result() {
+        let terminal = Query::new().category(Category::Terminal).apps();
+        let rust_terminal = Query::new().category(Category::Terminal).tag("rust").apps();
+        assert!(!rust_terminal.is_empty());
+        assert!(rust_terminal.len() < terminal.len());
+        assert!(rust_terminal
+            .iter()
+            .all(|a| a.category == Category::Terminal && a.tags.contains(&"rust")));
+
+        // Two categories match either; two tags need both
+        let either = Query::new()
+            .category(Category::Terminal)
+            .category(Category::Git)
+            .apps();
+        assert_eq!(
+            either.len(),
+            terminal.len() + apps_by_category(&Category::Git).len()
+        );
+        assert!(Query::new()
+            .tag("rust")
+            .tag("emulator")
+            .ids()
+            .contains(&AppId::parse("wezterm").unwrap()));
+    }
+
+    #[test]
+    fn query_by_platform_and_installed_state() {
+        let iterm = AppId::parse("iterm2").unwrap();
+        let zed = AppId::parse("zed").unwrap();
+        let linux = Query::new().platform(Os::Linux).ids();
+        assert!(!linux.contains(&iterm), "casks are macOS only");
+        assert!(linux.contains(&zed), "zed has a Linux script");
+        assert!(Query::new().platform(Os::MacOS).ids().contains(&iterm));
+
+        let installed = |app: &App| app.id == "git" || app.id == "ripgrep";
+        let have = Query::new().installed(true, installed).ids();
+        assert_eq!(have, ["git", "ripgrep"]);
+        let missing = Query::new().installed(false, installed).apps();
+        assert_eq!(missing.len(), CATALOG.len() - 2);
+    }
+
+    #[test]
+    fn apps_and_ids_round_trip_through_serde() {
+        let ripgrep = find_app("ripgrep").unwrap();
+        let json = serde_json::to_string(ripgrep).unwrap();
+        assert!(json.starts_with(r#"{"id":"ripgrep","name":"Ripgrep""#));
+        assert!(json.contains(r#""install_method":{"brew":"ripgrep"}"#));
+
+        let back: &'static App = serde_json::from_str(&json).unwrap();
+        assert!(std::ptr::eq(back, ripgrep));
+        let back: &'static App = serde_json::from_str(r#""ripgrep""#).unwrap();
+        assert!(std::ptr::eq(back, ripgrep));
+
+        let ids: Vec<AppId> = serde_json::from_str(r#"["bat", "fd"]"#).unwrap();
+        assert_eq!(ids, ["bat", "fd"]);
+        let err = serde_json::from_str::<AppId>(r#""not-an-app""#).unwrap_err();
+        assert!(err.to_string().contains("unknown app 'not-an-app'"));
+    }
+
+    #[test]
+    fn tags_cross_list_apps() {
+        let lazydocker = find_app("lazydocker").unwrap();
+        assert_eq!(
+            lazydocker.categories(),
+            [Category::Container, Category::Tui]
+        );
+        let k9s = find_app("k9s").unwrap();
+        assert_eq!(
+            k9s.categories(),
+            [Category::Cloud, Category::Tui, Category::Container]
+        );
+        assert!(Query::new()
+            .category(Category::Container)
+            .ids()
+            .contains(&k9s.id));
+        assert!(apps_by_category(&Category::Tui)
+            .iter()
+            .all(|a| a.category != Category::Tui));
+        assert_eq!(
+            find_app("ripgrep").unwrap().categories(),
+            [Category::Search]
+        );
+    }
+
     #[test]
     fn category_metadata() {
         for cat in Category::all() {
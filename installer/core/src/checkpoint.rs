@@ -0,0 +1,212 @@
+//! Install checkpoint
+//! How far an install got, saved after every package so a run cut short
+//! (a closed lid, a killed terminal, an abort) can pick up from there on
+//! the next launch instead of starting over.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::catalog::App;
+use crate::executor::InstallMessage;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// An install in progress: the answers it started with and the app ids
+/// it has finished with
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Checkpoint {
+    pub wizard: WizardState,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Secrets the run was given, by name; they aren't saved with it
+    pub secrets_left_out: Vec<String>,
+}
+
+/// Where the checkpoint is kept while an install runs
+pub fn path(system: &SystemInfo) -> PathBuf {
+    system
+        .home_dir
+        .join(".local")
+        .join("state")
+        .join("loadstar")
+        .join("checkpoint.json")
+}
+
+impl Checkpoint {
+    /// A run of `wizard` with nothing done yet
+    pub fn new(wizard: &WizardState) -> Self {
+        let secrets = [
+            ("the SSH key passphrase", wizard.ssh_passphrase.expose()),
+            ("the GitHub token", wizard.github_token.expose()),
+            ("the Anthropic API key", wizard.ai.anthropic_key.expose()),
+            ("the OpenAI API key", wizard.ai.openai_key.expose()),
+        ];
+        Self {
+            wizard: wizard.clone(),
+            secrets_left_out: secrets
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// This checkpoint's progress, carried over to a run of `wizard`
+    pub fn resumed_as(&self, wizard: &WizardState) -> Self {
+        Self {
+            succeeded: self.succeeded.clone(),
+            failed: self.failed.clone(),
+            skipped: self.skipped.clone(),
+            ..Self::new(wizard)
+        }
+    }
+
+    /// Note a package result. Packages go by name in messages, so the
+    /// name is looked up among the run's apps; `false` when the message
+    /// isn't a result for one of them.
+    pub fn record(&mut self, message: &InstallMessage) -> bool {
+        let name = match message {
+            InstallMessage::PackageSuccess { name, .. }
+            | InstallMessage::PackageFailed { name, .. }
+            | InstallMessage::PackageSkipped { name, .. } => name,
+            _ => return false,
+        };
+        let Some(app) = self
+            .wizard
+            .get_apps_to_install()
+            .into_iter()
+            .find(|app| app.name == name.as_str())
+        else {
+            return false;
+        };
+        let id = app.id.to_string();
+        // A failed package that's retried and then installs moves over
+        self.succeeded.retain(|done| *done != id);
+        self.failed.retain(|done| *done != id);
+        self.skipped.retain(|done| *done != id);
+        match message {
+            InstallMessage::PackageSuccess { .. } => self.succeeded.push(id),
+            InstallMessage::PackageFailed { .. } => self.failed.push(id),
+            _ => self.skipped.push(id),
+        }
+        true
+    }
+
+    /// Whether `app` needs no second try: installed, or already there
+    pub fn finished(&self, app: &App) -> bool {
+        let id = app.id.as_str();
+        self.succeeded
+            .iter()
+            .chain(&self.skipped)
+            .any(|done| done == id)
+    }
+
+    /// The run's apps that are left, failed ones included
+    pub fn remaining(&self) -> Vec<&'static App> {
+        self.wizard
+            .get_apps_to_install()
+            .into_iter()
+            .filter(|app| !self.finished(app))
+            .collect()
+    }
+
+    /// "12 of 30 packages done, 1 failed"
+    pub fn progress(&self) -> String {
+        let total = self.wizard.get_apps_to_install().len();
+        let done = total - self.remaining().len();
+        let mut text = format!("{} of {} packages done", done, total);
+        if !self.failed.is_empty() {
+            text.push_str(&format!(", {} failed", self.failed.len()));
+        }
+        text
+    }
+
+    /// Write the checkpoint, replacing the last one. It goes to a
+    /// temporary file first, so an interruption mid-write leaves the
+    /// previous one whole.
+    pub fn save(&self, system: &SystemInfo) -> io::Result<PathBuf> {
+        let path = path(system);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(self).map_err(io::Error::other)?;
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, json)?;
+        fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+
+    /// The checkpoint an interrupted install left, if there is one
+    pub fn load(system: &SystemInfo) -> Option<Self> {
+        let json = fs::read_to_string(path(system)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Remove the checkpoint once its install has finished
+    pub fn clear(system: &SystemInfo) {
+        let _ = fs::remove_file(path(system));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::Secret;
+
+    #[test]
+    fn a_resumed_run_skips_what_finished() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-checkpoint-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&system.home_dir);
+
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["ripgrep", "bat", "fd"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        wizard.ssh_passphrase = Secret::new("correct horse");
+        let mut checkpoint = Checkpoint::new(&wizard);
+        assert!(checkpoint.record(&InstallMessage::PackageSuccess {
+            name: "Ripgrep".to_string(),
+            duration_ms: 900,
+        }));
+        assert!(checkpoint.record(&InstallMessage::PackageFailed {
+            name: "Bat".to_string(),
+            error: "exit 1".to_string(),
+        }));
+        assert!(!checkpoint.record(&InstallMessage::Log("[RUN] brew".to_string())));
+        checkpoint.save(&system).unwrap();
+
+        let saved = Checkpoint::load(&system).unwrap();
+        assert_eq!(saved.succeeded, ["ripgrep"]);
+        assert_eq!(saved.failed, ["bat"]);
+        assert_eq!(saved.secrets_left_out, ["the SSH key passphrase"]);
+        assert!(!fs::read_to_string(path(&system))
+            .unwrap()
+            .contains("correct horse"));
+
+        // The editor, prompt and font are in the run too
+        let resumed = saved.resumed_as(&saved.wizard);
+        let left: Vec<&str> = resumed
+            .remaining()
+            .iter()
+            .map(|app| app.id.as_str())
+            .collect();
+        assert!(left.contains(&"bat") && left.contains(&"fd"));
+        assert!(!left.contains(&"ripgrep"));
+        let total = wizard.get_apps_to_install().len();
+        assert_eq!(
+            resumed.progress(),
+            format!("1 of {} packages done, 1 failed", total)
+        );
+
+        Checkpoint::clear(&system);
+        assert!(Checkpoint::load(&system).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! Cloud CLI auth scaffolding
+//! Logins are interactive, so this writes what it can (SSO profile stubs)
+//! and puts the rest on the next-steps checklist.
+
+use std::fs;
+use std::sync::mpsc;
+
+use crate::executor::InstallMessage;
+use crate::kube::KubeSource;
+use crate::system::SystemInfo;
+use crate::wizard::{SetupType, WizardState};
+
+pub fn setup_cloud(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let aws = wizard.configures("awscli");
+    let gcloud = wizard.configures("gcloud");
+    let azure = wizard.configures("azure-cli");
+    if !(aws || gcloud || azure) {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Cloud Auth".to_string(),
+    });
+    let next = |command: &str, note: &str| {
+        let _ = tx.send(InstallMessage::NextStep {
+            command: command.to_string(),
+            note: note.to_string(),
+        });
+    };
+
+    if aws {
+        write_aws_config(wizard, system, tx);
+        next(
+            "aws configure sso",
+            "fill in the SSO profile in ~/.aws/config",
+        );
+        next("aws sso login", "start an AWS session");
+    }
+    if gcloud {
+        let _ = tx.send(InstallMessage::Log(
+            "[CLOUD] gcloud needs an interactive login".to_string(),
+        ));
+        next("gcloud init", "log in and pick a default project");
+        next(
+            "gcloud auth application-default login",
+            "credentials for SDKs and Terraform",
+        );
+    }
+    if azure {
+        let _ = tx.send(InstallMessage::Log(
+            "[CLOUD] Azure CLI needs an interactive login".to_string(),
+        ));
+        next("az login", "sign in to Azure");
+    }
+}
+
+fn write_aws_config(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let path = system.home_dir.join(".aws").join("config");
+
+    // An existing config has real profiles in it; stubs would only get in the way
+    if fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false) {
+        let _ = tx.send(InstallMessage::Log(
+            "[CLOUD] ~/.aws/config exists, leaving it alone".to_string(),
+        ));
+        return;
+    }
+    if wizard.dry_run {
+        let _ = tx.send(InstallMessage::Log(
+            "[DRY] write SSO profile stubs to ~/.aws/config".to_string(),
+        ));
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::Log(
+        "[CLOUD] Writing SSO profile stubs to ~/.aws/config".to_string(),
+    ));
+    let result = fs::create_dir_all(path.parent().unwrap_or(&system.home_dir))
+        .and_then(|_| fs::write(&path, generate_aws_config(wizard)));
+    match result {
+        Ok(()) => {
+            let _ = tx.send(InstallMessage::Log("  Wrote ~/.aws/config".to_string()));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Failed to write ~/.aws/config: {}",
+                e
+            )));
+        }
+    }
+}
+
+fn generate_aws_config(wizard: &WizardState) -> String {
+    let session = match wizard.identity.setup_type {
+        SetupType::Work => "work",
+        _ => "personal",
+    };
+    // Reuse the EKS region from the Kubernetes step when there is one
+    let kube = &wizard.kube;
+    let region = match kube.source {
+        KubeSource::Eks if !kube.region.trim().is_empty() => kube.region.trim(),
+        _ => "us-east-1",
+    };
+
+    format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+#
+# SSO profile stubs. Uncomment and fill in the values, or let
+# `aws configure sso` write them for you, then run `aws sso login`.
+
+[default]
+region = {region}
+output = json
+
+# [sso-session {session}]
+# sso_start_url = https://<your-org>.awsapps.com/start
+# sso_region = {region}
+# sso_registration_scopes = sso:account:access
+
+# [profile {session}]
+# sso_session = {session}
+# sso_account_id = <account-id>
+# sso_role_name = <role-name>
+# region = {region}
+"#,
+        session = session,
+        region = region,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aws_config_stubs_follow_setup_type_and_eks_region() {
+        let mut wizard = WizardState::new();
+        wizard.identity.setup_type = SetupType::Work;
+        wizard.kube.source = KubeSource::Eks;
+        wizard.kube.region = "eu-north-1".to_string();
+
+        let config = generate_aws_config(&wizard);
+        assert!(config.contains("# [sso-session work]"));
+        assert!(config.contains("region = eu-north-1\n"));
+        // Placeholders stay commented so the AWS CLI can still parse the file
+        assert!(config
+            .lines()
+            .filter(|l| l.contains('<'))
+            .all(|l| l.starts_with('#')));
+    }
+}
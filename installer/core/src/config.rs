@@ -0,0 +1,2253 @@
+//! Dotfile and configuration generator
+//! Generates config files from wizard selections and writes them to disk.
+//! Backs up existing files before overwriting.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+
+use crate::aliascheck;
+use crate::executor::InstallMessage;
+use crate::hooks;
+use crate::rcblock;
+use crate::system::SystemInfo;
+use crate::wizard::{MultiplexerChoice, PromptChoice, ShellChoice, WizardState};
+
+/// A config file the install will write, shown on the preview screen
+/// before anything touches disk
+#[derive(Debug, Clone)]
+pub struct PlannedConfig {
+    pub path: PathBuf,
+    pub content: String,
+    /// Name used in the install log
+    pub label: &'static str,
+}
+
+/// Every config file the wizard's answers produce, in write order.
+/// Reads the disk at most; `ffmpeg` goes into the yt-dlp config.
+pub fn planned_configs(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    ffmpeg: Option<&Path>,
+) -> Vec<PlannedConfig> {
+    let home = &system.home_dir;
+    let config = &system.config_dir;
+    let mut planned = Vec::new();
+    let mut plan = |path: PathBuf, content: String, label: &'static str| {
+        planned.push(PlannedConfig {
+            path,
+            content,
+            label,
+        })
+    };
+
+    plan(
+        home.join(".gitconfig"),
+        generate_gitconfig(wizard),
+        ".gitconfig",
+    );
+    plan(
+        config.join("git").join("ignore"),
+        generate_gitignore(),
+        "git/ignore",
+    );
+
+    // starship.toml, unless starship is set to keep its own config
+    if wizard.configures("starship") {
+        plan(
+            config.join("starship.toml"),
+            generate_starship_config(),
+            "starship.toml",
+        );
+    }
+
+    // .zshrc (if zsh selected); other shells get a managed rc block instead
+    if wizard.shell_config.shell == ShellChoice::Zsh {
+        plan(
+            home.join(".zshrc"),
+            generate_zshrc(wizard, system),
+            ".zshrc",
+        );
+    }
+
+    // bat config with the theme's syntax colors (if bat selected)
+    if wizard.configures("bat") {
+        plan(
+            config.join("bat").join("config"),
+            generate_bat_config(wizard),
+            "bat/config",
+        );
+    }
+
+    // .ripgreprc (if ripgrep selected); rg only reads it through
+    // $RIPGREP_CONFIG_PATH, set in the rc file
+    if wizard.configures("ripgrep") {
+        plan(home.join(".ripgreprc"), generate_ripgreprc(), ".ripgreprc");
+    }
+
+    // tmux.conf (if tmux multiplexer selected)
+    if wizard.shell_config.multiplexer == Some(MultiplexerChoice::Tmux) || wizard.configures("tmux")
+    {
+        plan(
+            home.join(".tmux.conf"),
+            generate_tmux_config(system),
+            "tmux.conf",
+        );
+    }
+
+    // Zed settings (if Zed is the editor or selected)
+    if wizard.configures("zed") {
+        plan(
+            config.join("zed").join("settings.json"),
+            generate_zed_settings(wizard),
+            "zed/settings.json",
+        );
+    }
+
+    // Ghostty config (if Ghostty is the terminal or selected)
+    if wizard.configures("ghostty") {
+        plan(
+            config.join("ghostty").join("config"),
+            generate_ghostty_config(wizard),
+            "ghostty/config",
+        );
+    }
+
+    // iTerm2 has no dotfile; it picks up profiles dropped into this
+    // folder instead (if iTerm2 is the terminal or selected, on macOS)
+    if wizard.configures("iterm2") && system.os == crate::system::Os::MacOS {
+        plan(
+            home.join("Library")
+                .join("Application Support")
+                .join("iTerm2")
+                .join("DynamicProfiles")
+                .join("loadstar.json"),
+            generate_iterm2_profile(wizard),
+            "iTerm2 dynamic profile",
+        );
+    }
+
+    // lazygit theme (if lazygit selected)
+    if wizard.configures("lazygit") {
+        plan(
+            config.join("lazygit").join("config.yml"),
+            generate_lazygit_config(wizard),
+            "lazygit/config.yml",
+        );
+    }
+
+    // yt-dlp defaults (if yt-dlp selected); merging formats needs ffmpeg
+    if wizard.configures("yt-dlp") {
+        plan(
+            config.join("yt-dlp").join("config"),
+            generate_ytdlp_config(ffmpeg),
+            "yt-dlp/config",
+        );
+    }
+
+    // k9s skin (if k9s selected), and a config pointing at it unless k9s
+    // already has one of its own
+    if wizard.configures("k9s") {
+        let k9s_dir = config.join("k9s");
+        plan(
+            k9s_dir.join("skins").join("loadstar.yaml"),
+            generate_k9s_skin(wizard),
+            "k9s/skins/loadstar.yaml",
+        );
+        if !k9s_dir.join("config.yaml").exists() {
+            plan(
+                k9s_dir.join("config.yaml"),
+                "k9s:\n  ui:\n    skin: loadstar\n".to_string(),
+                "k9s/config.yaml",
+            );
+        }
+    }
+
+    // System monitors in the theme's colors. btop gets a theme file, and
+    // a config choosing it unless btop already has one of its own. htop
+    // and procs only take the terminal's 16 colors, which the terminal's
+    // config already themes.
+    if wizard.configures("btop") {
+        let btop_dir = config.join("btop");
+        plan(
+            btop_dir.join("themes").join("loadstar.theme"),
+            generate_btop_theme(wizard),
+            "btop/themes/loadstar.theme",
+        );
+        if !btop_dir.join("btop.conf").exists() {
+            plan(
+                btop_dir.join("btop.conf"),
+                "color_theme = \"loadstar\"\ntheme_background = False\n".to_string(),
+                "btop/btop.conf",
+            );
+        }
+    }
+    if wizard.configures("bottom") {
+        plan(
+            config.join("bottom").join("bottom.toml"),
+            generate_bottom_config(wizard),
+            "bottom/bottom.toml",
+        );
+    }
+
+    // .terraformrc with a shared provider cache (if terraform selected)
+    if wizard.configures("terraform") {
+        plan(
+            home.join(".terraformrc"),
+            generate_terraformrc(),
+            ".terraformrc",
+        );
+    }
+
+    // Starter pre-commit hooks for IaC repos (if both are selected)
+    if wizard.configures("pre-commit") && wizard.configures("terraform") {
+        plan(
+            pre_commit_template(system),
+            generate_iac_pre_commit(wizard),
+            "pre-commit template for IaC repos",
+        );
+    }
+
+    plan(
+        home.join(".editorconfig"),
+        generate_editorconfig(),
+        ".editorconfig",
+    );
+
+    planned
+}
+
+/// Generate and write all config files based on wizard state
+pub fn generate_configs(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Configuration Files".to_string(),
+    });
+
+    if let Some(warning) = wizard.prompt_warning() {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "{}; leaving the prompt alone",
+            warning
+        )));
+    }
+    if wizard.dry_run {
+        dry_run_configs(wizard, system, tx);
+        return;
+    }
+
+    // Other shells get no generated rc; $EDITOR/$VISUAL go into the
+    // managed block of their rc file instead
+    if wizard.shell_config.shell != ShellChoice::Zsh {
+        set_editor_vars(wizard, system, tx);
+        add_shell_hooks(wizard, system, tx);
+        if wizard.configures("ripgrep") {
+            set_ripgrep_config_var(wizard, system, tx);
+        }
+    }
+
+    let ffmpeg = if wizard.configures("yt-dlp") {
+        check_ffmpeg(system, tx)
+    } else {
+        None
+    };
+
+    let planned = planned_configs(wizard, system, ffmpeg.as_deref());
+    if wizard.configures("k9s") && !planned.iter().any(|c| c.label == "k9s/config.yaml") {
+        let _ = tx.send(InstallMessage::Log(
+            "  Set k9s.ui.skin: loadstar in k9s/config.yaml to use it".to_string(),
+        ));
+    }
+    if wizard.configures("btop") && !planned.iter().any(|c| c.label == "btop/btop.conf") {
+        let _ = tx.send(InstallMessage::Log(
+            "  Pick loadstar under btop's Options → Color theme to use it".to_string(),
+        ));
+    }
+
+    if planned.iter().any(|c| c.label == "iTerm2 dynamic profile") {
+        let _ = tx.send(InstallMessage::Log(
+            "  iTerm2: Settings → Profiles → loadstar → Other Actions → Set as Default".to_string(),
+        ));
+    }
+
+    // Before the .zshrc they're in is replaced
+    aliascheck::keep_in_local(wizard, system, tx);
+
+    for config in planned {
+        write_file(&config.path, &config.content, config.label, system, tx);
+    }
+
+    // Terraform won't create its plugin cache directory itself
+    if wizard.configures("terraform") {
+        let cache_dir = system.home_dir.join(".terraform.d").join("plugin-cache");
+        if let Err(e) = fs::create_dir_all(&cache_dir) {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not create {}: {}",
+                display_path(&cache_dir, &system.home_dir),
+                e
+            )));
+        }
+    }
+
+    if wizard.configures("pre-commit") && wizard.configures("terraform") {
+        let _ = tx.send(InstallMessage::NextStep {
+            command: format!(
+                "cp {} .pre-commit-config.yaml && pre-commit install",
+                display_path(&pre_commit_template(system), &system.home_dir)
+            ),
+            note: "in each IaC repo, to lint and format on commit".to_string(),
+        });
+    }
+
+    let _ = tx.send(InstallMessage::Log(
+        "[CONF] All configuration files written".to_string(),
+    ));
+}
+
+/// What `generate_configs` would write, each as a `[DRY]` line
+fn dry_run_configs(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let home = &system.home_dir;
+    let dry = |line: String| {
+        let _ = tx.send(InstallMessage::Log(format!("[DRY] {}", line)));
+    };
+    if wizard.shell_config.shell != ShellChoice::Zsh {
+        let rc = rcblock::rc_file(wizard.shell_config.shell, system);
+        dry(format!(
+            "set EDITOR, VISUAL and the shell hooks in {}",
+            display_path(&rc, home)
+        ));
+    }
+    for config in planned_configs(wizard, system, None) {
+        let lines = config.content.lines().count();
+        let backup = if config.path.exists() {
+            ", backing up the one there"
+        } else {
+            ""
+        };
+        dry(format!(
+            "write {} ({} lines{})",
+            display_path(&config.path, home),
+            lines,
+            backup
+        ));
+    }
+    if wizard.configures("terraform") {
+        let cache_dir = home.join(".terraform.d").join("plugin-cache");
+        dry(format!("mkdir -p {}", display_path(&cache_dir, home)));
+    }
+}
+
+fn pre_commit_template(system: &SystemInfo) -> PathBuf {
+    system
+        .config_dir
+        .join("loadstar")
+        .join("templates")
+        .join("pre-commit-iac.yaml")
+}
+
+// ─── Config generators ───────────────────────────────────────────────
+
+fn generate_gitconfig(wizard: &WizardState) -> String {
+    let editor_cmd = wizard.editor.command();
+
+    let is_work = wizard.identity.setup_type == crate::wizard::SetupType::Work;
+    let has_delta = wizard.configures("delta");
+
+    let mut config = format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+[user]
+    name = {name}
+    email = {email}
+
+[core]
+    editor = {editor}
+    autocrlf = input
+    excludesfile = ~/.config/git/ignore
+"#,
+        name = wizard.identity.name,
+        email = wizard.identity.email,
+        editor = editor_cmd,
+    );
+
+    if has_delta {
+        config.push_str("    pager = delta\n");
+    }
+
+    config.push_str(
+        r#"
+[init]
+    defaultBranch = main
+
+[push]
+    default = simple
+    autoSetupRemote = true
+
+[pull]
+    rebase = true
+    ff = only
+
+[rebase]
+    autoStash = true
+    updateRefs = true
+
+[merge]
+    conflictstyle = zdiff3
+
+[diff]
+    algorithm = histogram
+    colorMoved = default
+
+[fetch]
+    prune = true
+    tags = true
+
+[branch]
+    autoSetupRebase = always
+    sort = -committerdate
+
+[status]
+    showUntrackedFiles = all
+"#,
+    );
+
+    // Delta config
+    if has_delta {
+        config.push_str(
+            r#"
+[interactive]
+    diffFilter = delta --color-only
+
+[delta]
+    navigate = true
+    light = false
+    side-by-side = false
+    line-numbers = true
+    line-numbers-left-format = "{nm:>4}|"
+    line-numbers-right-format = "{np:>4}|"
+"#,
+        );
+        config.push_str(&delta_theme(wizard));
+    }
+
+    // URL rewrites for SSH, when opted into on the GitHub screen
+    if wizard.rewrite_github_https {
+        config.push_str(
+            r#"
+[url "git@github.com:"]
+    insteadOf = "https://github.com/"
+"#,
+        );
+    }
+
+    // Aliases
+    config.push_str(
+        r#"
+[alias]
+    s = status --short --branch
+    st = status
+    b = branch
+    co = checkout
+    cb = checkout -b
+    sw = switch
+    c = commit
+    cm = commit --message
+    ca = commit --all
+    cam = commit --all --message
+    amend = commit --amend
+    a = add
+    aa = add --all
+    ap = add --patch
+    d = diff
+    ds = diff --staged
+    l = log --oneline --graph --decorate
+    lg = log --graph --pretty=format:'%C(red)%h%C(reset) -%C(yellow)%d%C(reset) %s %C(green)(%cr) %C(bold blue)<%an>%C(reset)' --abbrev-commit
+    f = fetch --all
+    p = push
+    pf = push --force-with-lease
+    pl = pull
+    unstage = reset HEAD --
+    undo = reset HEAD~1 --mixed
+    cleanup = "!git branch --merged | grep -v '\\*\\|master\\|main' | xargs -n 1 git branch -d"
+"#,
+    );
+
+    // GitHub user
+    if !wizard.identity.github_username.is_empty() {
+        config.push_str(&format!(
+            "\n[github]\n    user = {}\n",
+            wizard.identity.github_username
+        ));
+    }
+
+    // Work-specific includes
+    if is_work {
+        config.push_str(
+            r#"
+[includeIf "gitdir:~/work/"]
+    path = ~/.config/git/config.work
+"#,
+        );
+    }
+
+    config
+}
+
+/// Global gitignore, referenced by core.excludesfile above
+fn generate_gitignore() -> String {
+    r#".DS_Store
+*.swp
+*.swo
+*~
+.env
+.env.local
+.idea/
+.vscode/
+*.log
+node_modules/
+__pycache__/
+.pytest_cache/
+target/
+"#
+    .to_string()
+}
+
+fn generate_starship_config() -> String {
+    // Use a clean, good-defaults starship config
+    r#"# Generated by LOAD"*",8,1
+# Minimal, fast, beautiful prompt
+
+format = """$all$character"""
+right_format = """$cmd_duration$time"""
+add_newline = true
+scan_timeout = 10
+command_timeout = 1000
+
+[character]
+success_symbol = "[❯](bold green)"
+error_symbol = "[❯](bold red)"
+vimcmd_symbol = "[❮](bold green)"
+
+[directory]
+truncation_length = 3
+truncation_symbol = "…/"
+truncate_to_repo = true
+style = "bold blue"
+
+[git_branch]
+format = "on [$symbol$branch(:$remote_branch)]($style) "
+symbol = " "
+style = "bold purple"
+
+[git_status]
+format = "([\\[$all_status$ahead_behind\\]]($style) )"
+style = "bold red"
+
+[cmd_duration]
+min_time = 2_000
+format = "took [$duration]($style)"
+style = "yellow bold"
+
+[time]
+disabled = false
+format = "at [$time]($style) "
+style = "bold white"
+time_format = "%H:%M"
+
+[nodejs]
+format = "via [$symbol($version )]($style)"
+symbol = " "
+
+[python]
+format = "via [$symbol$pyenv_prefix($version )]($style)"
+symbol = " "
+
+[rust]
+format = "via [$symbol($version )]($style)"
+symbol = " "
+
+[golang]
+format = "via [$symbol($version )]($style)"
+symbol = " "
+
+[docker_context]
+format = "via [$symbol$context]($style) "
+symbol = " "
+only_with_files = true
+
+[kubernetes]
+format = "on [$symbol$context( \\($namespace\\))]($style) "
+symbol = "☸ "
+disabled = false
+
+[package]
+format = "is [$symbol$version]($style) "
+symbol = "📦 "
+"#
+    .to_string()
+}
+
+fn generate_zshrc(wizard: &WizardState, system: &SystemInfo) -> String {
+    let editor_cmd = wizard.editor.command();
+
+    let is_macos = system.os == crate::system::Os::MacOS;
+    let has_bat = wizard.configures("bat");
+    let has_ripgrep = wizard.configures("ripgrep");
+    let prompt = wizard.shell_config.prompt;
+    let has_npm_tools = wizard
+        .get_selected_apps()
+        .iter()
+        .any(|a| matches!(a.method(), crate::catalog::InstallMethod::Npm(_)));
+
+    let mut zshrc = String::from(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+# ═══ Environment ═══
+export XDG_CONFIG_HOME="$HOME/.config"
+export XDG_CACHE_HOME="$HOME/.cache"
+export XDG_DATA_HOME="$HOME/.local/share"
+export XDG_STATE_HOME="$HOME/.local/state"
+"#,
+    );
+
+    // p10k's instant prompt has to run before anything that prints
+    if prompt == PromptChoice::Powerlevel10k {
+        zshrc.push_str(
+            r#"
+# Powerlevel10k instant prompt
+if [[ -r "${XDG_CACHE_HOME}/p10k-instant-prompt-${(%):-%n}.zsh" ]]; then
+    source "${XDG_CACHE_HOME}/p10k-instant-prompt-${(%):-%n}.zsh"
+fi
+"#,
+        );
+    }
+
+    zshrc.push_str(&format!(
+        r#"
+export EDITOR="{editor}"
+export VISUAL="{editor}"
+export LANG={locale}
+export LC_ALL={locale}
+"#,
+        editor = editor_cmd,
+        locale = wizard
+            .locale
+            .as_deref()
+            .unwrap_or(crate::locale::DEFAULT_LOCALE)
+    ));
+
+    // History
+    zshrc.push_str(
+        r#"
+# ═══ History ═══
+export HISTFILE="$XDG_STATE_HOME/zsh/history"
+export HISTSIZE=1000000
+export SAVEHIST=1000000
+mkdir -p "$(dirname "$HISTFILE")"
+
+setopt EXTENDED_HISTORY
+setopt SHARE_HISTORY
+setopt HIST_EXPIRE_DUPS_FIRST
+setopt HIST_IGNORE_DUPS
+setopt HIST_IGNORE_ALL_DUPS
+setopt HIST_FIND_NO_DUPS
+setopt HIST_IGNORE_SPACE
+setopt HIST_SAVE_NO_DUPS
+setopt HIST_VERIFY
+setopt AUTO_PUSHD
+setopt PUSHD_IGNORE_DUPS
+setopt EXTENDED_GLOB
+setopt AUTO_CD
+setopt INTERACTIVE_COMMENTS
+"#,
+    );
+
+    // PATH
+    zshrc.push_str(
+        r#"
+# ═══ Path ═══
+path_prepend() { [[ -d "$1" ]] && [[ ":$PATH:" != *":$1:"* ]] && export PATH="$1:$PATH"; }
+
+path_prepend "$HOME/.local/bin"
+path_prepend "$HOME/.cargo/bin"
+path_prepend "$HOME/.local/share/mise/shims"
+"#,
+    );
+
+    if is_macos {
+        zshrc.push_str(
+            r#"path_prepend "/opt/homebrew/bin"
+path_prepend "/opt/homebrew/sbin"
+"#,
+        );
+    }
+
+    zshrc.push_str("path_prepend \"$HOME/go/bin\"\n");
+
+    // Global npm packages live under the user's home, so `npm install -g`
+    // never needs sudo
+    if has_npm_tools {
+        zshrc.push_str(
+            r#"export NPM_CONFIG_PREFIX="$HOME/.npm-global"
+path_prepend "$NPM_CONFIG_PREFIX/bin"
+"#,
+        );
+    }
+
+    // Tool aliases
+    zshrc.push_str("\n# ═══ Aliases ═══\n");
+    zshrc.push_str(&hooks::zsh_aliases(wizard));
+
+    if has_bat {
+        zshrc.push_str(
+            "command -v bat >/dev/null 2>&1 && export MANPAGER=\"sh -c 'col -bx | bat -l man -p'\"\n",
+        );
+    }
+
+    if has_ripgrep {
+        zshrc.push_str("export RIPGREP_CONFIG_PATH=\"$HOME/.ripgreprc\"\n");
+    }
+
+    // Git aliases
+    zshrc.push_str(
+        r#"
+alias g='git'
+alias ga='git add'
+alias gaa='git add --all'
+alias gc='git commit'
+alias gcm='git commit -m'
+alias gco='git checkout'
+alias gd='git diff'
+alias gp='git push'
+alias gs='git status'
+alias glog='git log --oneline --graph --decorate'
+
+# Navigation
+alias ..='cd ..'
+alias ...='cd ../..'
+alias ....='cd ../../..'
+alias reload='exec zsh'
+"#,
+    );
+
+    if is_macos {
+        zshrc.push_str(
+            r#"
+# macOS
+alias o='open'
+alias flushdns='sudo dscacheutil -flushcache && sudo killall -HUP mDNSResponder'
+"#,
+        );
+    }
+
+    // Tool initialization
+    zshrc.push_str("\n# ═══ Tool Initialization ═══\n");
+
+    // Starship comes from the hook registry below
+    match prompt {
+        _ if wizard.uses_starship() => {}
+        PromptChoice::Powerlevel10k => zshrc.push_str(
+            r#"if [[ -r "$XDG_DATA_HOME/zsh/powerlevel10k/powerlevel10k.zsh-theme" ]]; then
+    source "$XDG_DATA_HOME/zsh/powerlevel10k/powerlevel10k.zsh-theme"
+    # Run `p10k configure` to create this
+    [[ -f ~/.p10k.zsh ]] && source ~/.p10k.zsh
+fi
+"#,
+        ),
+        PromptChoice::PurePurple => zshrc.push_str(
+            r#"if [[ -d "$XDG_DATA_HOME/zsh/pure" ]]; then
+    fpath+=("$XDG_DATA_HOME/zsh/pure")
+    autoload -U promptinit && promptinit
+    prompt pure
+fi
+"#,
+        ),
+        PromptChoice::Minimal => zshrc.push_str("PROMPT='%F{cyan}%~%f %# '\n"),
+        _ => {}
+    }
+
+    for hook in hooks::active_hooks(wizard) {
+        zshrc.push_str(hook.zsh);
+    }
+    if let Some(hook) = hooks::ssh_agent_hook(wizard, system) {
+        zshrc.push_str(hook.zsh);
+    }
+
+    // Completion
+    zshrc.push_str(
+        r#"
+# ═══ Completion ═══
+autoload -Uz compinit
+if [[ -n ~/.zcompdump(#qN.mh+24) ]]; then
+    compinit -d "$XDG_CACHE_HOME/zsh/zcompdump-$ZSH_VERSION"
+else
+    compinit -C -d "$XDG_CACHE_HOME/zsh/zcompdump-$ZSH_VERSION"
+fi
+
+zstyle ':completion:*' menu select
+zstyle ':completion:*' matcher-list 'm:{a-z}={A-Z}'
+"#,
+    );
+
+    // Custom functions
+    zshrc.push_str(
+        r#"
+# ═══ Functions ═══
+mkcd() { mkdir -p "$1" && cd "$1"; }
+
+# Local overrides
+[[ -f "$HOME/.zshrc.local" ]] && source "$HOME/.zshrc.local"
+"#,
+    );
+
+    zshrc
+}
+
+fn generate_tmux_config(system: &SystemInfo) -> String {
+    let is_macos = system.os == crate::system::Os::MacOS;
+
+    let mut config = String::from(
+        r##"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+# Prefix: Ctrl+Space
+unbind C-b
+set -g prefix C-Space
+bind C-Space send-prefix
+
+# General
+set -g mouse on
+set -g base-index 1
+set -g pane-base-index 1
+set -g renumber-windows on
+set -g history-limit 50000
+set -g display-time 4000
+set -g status-interval 5
+set -g focus-events on
+set -g default-terminal "tmux-256color"
+set -ga terminal-overrides ",xterm-256color*:Tc"
+set -s escape-time 10
+setw -g aggressive-resize on
+
+# Reload
+bind r source-file ~/.tmux.conf \; display-message "Config reloaded!"
+
+# Navigation
+bind h select-pane -L
+bind j select-pane -D
+bind k select-pane -U
+bind l select-pane -R
+
+# Resize
+bind -r H resize-pane -L 2
+bind -r J resize-pane -D 2
+bind -r K resize-pane -U 2
+bind -r L resize-pane -R 2
+
+# Split in current path
+bind | split-window -h -c "#{pane_current_path}"
+bind - split-window -v -c "#{pane_current_path}"
+unbind '"'
+unbind %
+
+bind c new-window -c "#{pane_current_path}"
+
+# Vi copy mode
+setw -g mode-keys vi
+bind-key -T copy-mode-vi v send-keys -X begin-selection
+"##,
+    );
+
+    if is_macos {
+        config
+            .push_str("bind-key -T copy-mode-vi y send-keys -X copy-pipe-and-cancel \"pbcopy\"\n");
+    } else {
+        config.push_str("bind-key -T copy-mode-vi y send-keys -X copy-pipe-and-cancel \"xclip -selection clipboard\"\n");
+    }
+
+    // Minimal clean status bar
+    config.push_str(
+        r##"
+# Status bar
+set -g status on
+set -g status-style "bg=#1e1e2e,fg=#cdd6f4"
+set -g status-left "#[fg=#1e1e2e,bg=#89b4fa,bold] #S #[fg=#89b4fa,bg=#1e1e2e]"
+set -g status-right "#[fg=#cdd6f4] %H:%M "
+set -g status-left-length 50
+set -g pane-border-style "fg=#313244"
+set -g pane-active-border-style "fg=#89b4fa"
+set -g message-style "fg=#1e1e2e,bg=#f9e2af"
+
+# Window status
+set -g window-status-format " #I #{b:pane_current_path} "
+set -g window-status-current-format "#[fg=#1e1e2e,bg=#fab387] #I #{b:pane_current_path} "
+
+# Local overrides
+if-shell "[ -f ~/.tmux.local.conf ]" 'source ~/.tmux.local.conf'
+"##,
+    );
+
+    config
+}
+
+fn generate_zed_settings(wizard: &WizardState) -> String {
+    let palette = wizard.theme.palette();
+
+    let mut settings = vec![format!("  \"theme\": \"{}\"", palette.zed_theme)];
+    if let Some(ext) = palette.zed_extension {
+        settings.push(format!(
+            "  \"auto_install_extensions\": {{ \"{}\": true }}",
+            ext
+        ));
+    }
+    if let Some(family) = wizard.font.family() {
+        settings.push(format!("  \"buffer_font_family\": \"{}\"", family));
+        settings.push(format!(
+            "  \"terminal\": {{ \"font_family\": \"{}\" }}",
+            family
+        ));
+    }
+    settings.push("  \"buffer_font_size\": 14".to_string());
+    settings.push("  \"ui_font_size\": 15".to_string());
+    settings.push("  \"format_on_save\": \"on\"".to_string());
+    settings.push("  \"telemetry\": { \"metrics\": false }".to_string());
+
+    format!(
+        r#"// Generated by LOAD"*",8,1
+// https://github.com/oddurs/loadstar
+{{
+{}
+}}
+"#,
+        settings.join(",\n")
+    )
+}
+
+fn generate_ghostty_config(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+    let hex = |c: &str| c.trim_start_matches('#').to_string();
+
+    let mut config = format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+# {theme}
+background = {bg}
+foreground = {fg}
+cursor-color = {accent}
+selection-background = {surface}
+selection-foreground = {fg}
+"#,
+        theme = p.name,
+        bg = hex(p.background),
+        fg = hex(p.foreground),
+        accent = hex(p.accent),
+        surface = hex(p.surface),
+    );
+
+    for (i, color) in p.ansi().iter().enumerate() {
+        config.push_str(&format!("palette = {}={}\n", i, color));
+    }
+
+    if let Some(family) = wizard.font.family() {
+        config.push_str(&format!("\nfont-family = \"{}\"\nfont-size = 14\n", family));
+    }
+
+    config.push_str(
+        r#"
+window-padding-x = 8
+window-padding-y = 8
+macos-option-as-alt = true
+copy-on-select = clipboard
+shell-integration = detect
+"#,
+    );
+
+    config
+}
+
+/// GUID of the loadstar profile. Fixed, so a rerun replaces the profile
+/// instead of adding a second one.
+const ITERM2_PROFILE_GUID: &str = "6c0a3d52-7f4e-4b1a-9d0e-10ad57a2c0de";
+
+/// An iTerm2 Dynamic Profile: the theme's colors, the font, and new tabs
+/// and splits opening in the directory of the session they came from.
+/// iTerm2 reads the file as plain JSON, so it can't carry a header comment.
+fn generate_iterm2_profile(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+    let color = |hex: &str| {
+        let hex = hex.trim_start_matches('#');
+        let [r, g, b] = [0, 2, 4].map(|i| {
+            let value = u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0) as f64;
+            (value / 255.0 * 10000.0).round() / 10000.0
+        });
+        serde_json::json!({
+            "Red Component": r,
+            "Green Component": g,
+            "Blue Component": b,
+            "Color Space": "sRGB",
+        })
+    };
+
+    let mut profile = serde_json::json!({
+        "Name": "loadstar",
+        "Guid": ITERM2_PROFILE_GUID,
+        "Tags": ["loadstar", p.name],
+        "Background Color": color(p.background),
+        "Foreground Color": color(p.foreground),
+        "Bold Color": color(p.foreground),
+        "Cursor Color": color(p.accent),
+        "Cursor Text Color": color(p.background),
+        "Selection Color": color(p.surface),
+        "Selected Text Color": color(p.foreground),
+        "Link Color": color(p.blue),
+        "Custom Directory": "Recycle",
+        "Option Key Sends": 2,
+        "Right Option Key Sends": 2,
+        "Unlimited Scrollback": true,
+    });
+    for (i, ansi) in p.ansi().iter().enumerate() {
+        profile[format!("Ansi {} Color", i)] = color(ansi);
+    }
+    if let Some(font) = wizard.font.postscript_name() {
+        profile["Normal Font"] = serde_json::json!(format!("{} 14", font));
+    }
+
+    let profiles = serde_json::json!({ "Profiles": [profile] });
+    let mut json = serde_json::to_string_pretty(&profiles).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+/// Delta colors from the theme. Hex colors are quoted, or git would read
+/// them as comments.
+fn delta_theme(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+    format!(
+        r#"    syntax-theme = {syntax}
+    minus-style = syntax "{minus}"
+    minus-emph-style = syntax "{minus_emph}"
+    plus-style = syntax "{plus}"
+    plus-emph-style = syntax "{plus_emph}"
+    line-numbers-minus-style = "{red}"
+    line-numbers-plus-style = "{green}"
+    line-numbers-zero-style = "{overlay}"
+    file-style = "{accent}" bold
+    file-decoration-style = "{accent}" ul
+    hunk-header-style = file line-number syntax
+    hunk-header-decoration-style = "{overlay}" box
+"#,
+        syntax = p.bat_theme,
+        minus = p.tint(p.red, 0.2),
+        minus_emph = p.tint(p.red, 0.4),
+        plus = p.tint(p.green, 0.2),
+        plus_emph = p.tint(p.green, 0.4),
+        red = p.red,
+        green = p.green,
+        overlay = p.overlay,
+        accent = p.accent,
+    )
+}
+
+fn generate_lazygit_config(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+    let mut config = format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+# {name}
+
+gui:
+  theme:
+    activeBorderColor:
+      - "{accent}"
+      - bold
+    inactiveBorderColor:
+      - "{overlay}"
+    searchingActiveBorderColor:
+      - "{yellow}"
+      - bold
+    optionsTextColor:
+      - "{blue}"
+    selectedLineBgColor:
+      - "{surface}"
+    cherryPickedCommitFgColor:
+      - "{blue}"
+    cherryPickedCommitBgColor:
+      - "{surface}"
+    markedBaseCommitFgColor:
+      - "{blue}"
+    markedBaseCommitBgColor:
+      - "{yellow}"
+    unstagedChangesColor:
+      - "{red}"
+    defaultFgColor:
+      - "{fg}"
+"#,
+        name = p.name,
+        accent = p.accent,
+        overlay = p.overlay,
+        yellow = p.yellow,
+        blue = p.blue,
+        surface = p.surface,
+        red = p.red,
+        fg = p.foreground,
+    );
+    if wizard.font.family().is_some() {
+        config.push_str("  nerdFontsVersion: \"3\"\n");
+    }
+    if wizard.configures("delta") {
+        config.push_str(
+            r#"git:
+  paging:
+    colorArg: always
+    pager: delta --paging=never
+"#,
+        );
+    }
+    config
+}
+
+fn generate_ytdlp_config(ffmpeg: Option<&Path>) -> String {
+    let mut config = String::from(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+# Into ~/Downloads/yt-dlp, named by title and video id
+-o "~/Downloads/yt-dlp/%(title)s [%(id)s].%(ext)s"
+
+# Best video up to 1080p with the best audio, merged into mp4
+-f "bv*[height<=1080]+ba/b[height<=1080]/b"
+--merge-output-format mp4
+
+# Keep metadata, thumbnail, and English subtitles with the file
+--embed-metadata
+--embed-thumbnail
+--embed-subs
+--sub-langs "en.*"
+
+# File times are when you downloaded it, not when it was uploaded
+--no-mtime
+"#,
+    );
+    if let Some(ffmpeg) = ffmpeg {
+        config.push_str(&format!("\n--ffmpeg-location \"{}\"\n", ffmpeg.display()));
+    }
+    config
+}
+
+/// Find ffmpeg and make sure it runs; yt-dlp can't merge or embed without it
+fn check_ffmpeg(system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) -> Option<PathBuf> {
+    let Some(ffmpeg) = system.find_tool("ffmpeg") else {
+        let _ = tx.send(InstallMessage::Warning(
+            "ffmpeg not found; yt-dlp can't merge formats or embed thumbnails".to_string(),
+        ));
+        return None;
+    };
+
+    match Command::new(&ffmpeg).arg("-version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let _ = tx.send(InstallMessage::Log(format!("  Found {}", version)));
+            Some(ffmpeg)
+        }
+        _ => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "{} doesn't run; yt-dlp can't merge formats or embed thumbnails",
+                ffmpeg.display()
+            )));
+            None
+        }
+    }
+}
+
+fn generate_k9s_skin(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+
+    format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+# {name}
+
+k9s:
+  body:
+    fgColor: "{fg}"
+    bgColor: default
+    logoColor: "{accent}"
+  prompt:
+    fgColor: "{fg}"
+    bgColor: default
+    suggestColor: "{muted}"
+  info:
+    fgColor: "{yellow}"
+    sectionColor: "{fg}"
+  dialog:
+    fgColor: "{fg}"
+    bgColor: default
+    buttonFgColor: "{bg}"
+    buttonBgColor: "{accent}"
+    buttonFocusFgColor: "{bg}"
+    buttonFocusBgColor: "{magenta}"
+    labelFgColor: "{yellow}"
+    fieldFgColor: "{fg}"
+  frame:
+    border:
+      fgColor: "{overlay}"
+      focusColor: "{accent}"
+    menu:
+      fgColor: "{fg}"
+      keyColor: "{blue}"
+      numKeyColor: "{magenta}"
+    crumbs:
+      fgColor: "{bg}"
+      bgColor: "{blue}"
+      activeColor: "{accent}"
+    status:
+      newColor: "{cyan}"
+      modifyColor: "{blue}"
+      addColor: "{green}"
+      pendingColor: "{yellow}"
+      errorColor: "{red}"
+      highlightColor: "{accent}"
+      killColor: "{magenta}"
+      completedColor: "{muted}"
+    title:
+      fgColor: "{fg}"
+      bgColor: default
+      highlightColor: "{accent}"
+      counterColor: "{blue}"
+      filterColor: "{magenta}"
+  views:
+    charts:
+      bgColor: default
+      defaultDialColors: ["{blue}", "{red}"]
+      defaultChartColors: ["{blue}", "{red}"]
+    table:
+      fgColor: "{fg}"
+      bgColor: default
+      cursorFgColor: "{bg}"
+      cursorBgColor: "{accent}"
+      markColor: "{yellow}"
+      header:
+        fgColor: "{yellow}"
+        bgColor: default
+        sorterColor: "{cyan}"
+    xray:
+      fgColor: "{fg}"
+      bgColor: default
+      cursorColor: "{surface}"
+      graphicColor: "{blue}"
+    yaml:
+      keyColor: "{blue}"
+      colonColor: "{muted}"
+      valueColor: "{fg}"
+    logs:
+      fgColor: "{fg}"
+      bgColor: default
+      indicator:
+        fgColor: "{accent}"
+        bgColor: default
+"#,
+        name = p.name,
+        fg = p.foreground,
+        bg = p.background,
+        surface = p.surface,
+        overlay = p.overlay,
+        muted = p.muted,
+        red = p.red,
+        green = p.green,
+        yellow = p.yellow,
+        blue = p.blue,
+        magenta = p.magenta,
+        cyan = p.cyan,
+        accent = p.accent,
+    )
+}
+
+fn generate_btop_theme(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+
+    format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+# {name}
+
+# Empty keeps the terminal's own background
+theme[main_bg]=""
+theme[main_fg]="{fg}"
+theme[title]="{fg}"
+theme[hi_fg]="{accent}"
+theme[selected_bg]="{surface}"
+theme[selected_fg]="{accent}"
+theme[inactive_fg]="{overlay}"
+theme[graph_text]="{muted}"
+theme[meter_bg]="{surface}"
+theme[proc_misc]="{cyan}"
+theme[cpu_box]="{blue}"
+theme[mem_box]="{green}"
+theme[net_box]="{magenta}"
+theme[proc_box]="{accent}"
+theme[div_line]="{overlay}"
+
+# Gradients, low to high
+theme[temp_start]="{green}"
+theme[temp_mid]="{yellow}"
+theme[temp_end]="{red}"
+theme[cpu_start]="{cyan}"
+theme[cpu_mid]="{blue}"
+theme[cpu_end]="{magenta}"
+theme[free_start]="{cyan}"
+theme[free_mid]=""
+theme[free_end]="{green}"
+theme[cached_start]="{blue}"
+theme[cached_mid]=""
+theme[cached_end]="{cyan}"
+theme[available_start]="{yellow}"
+theme[available_mid]=""
+theme[available_end]="{green}"
+theme[used_start]="{yellow}"
+theme[used_mid]=""
+theme[used_end]="{red}"
+theme[download_start]="{blue}"
+theme[download_mid]=""
+theme[download_end]="{cyan}"
+theme[upload_start]="{magenta}"
+theme[upload_mid]=""
+theme[upload_end]="{red}"
+theme[process_start]="{green}"
+theme[process_mid]="{yellow}"
+theme[process_end]="{red}"
+"#,
+        name = p.name,
+        fg = p.foreground,
+        surface = p.surface,
+        overlay = p.overlay,
+        muted = p.muted,
+        red = p.red,
+        green = p.green,
+        yellow = p.yellow,
+        blue = p.blue,
+        magenta = p.magenta,
+        cyan = p.cyan,
+        accent = p.accent,
+    )
+}
+
+fn generate_bottom_config(wizard: &WizardState) -> String {
+    let p = wizard.theme.palette();
+
+    format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+# {name}
+
+[styles.cpu]
+all_entry_color = "{accent}"
+avg_entry_color = "{red}"
+cpu_core_colors = ["{blue}", "{cyan}", "{green}", "{yellow}", "{magenta}", "{red}"]
+
+[styles.memory]
+ram_color = "{green}"
+cache_color = "{cyan}"
+swap_color = "{yellow}"
+arc_color = "{blue}"
+gpu_colors = ["{magenta}", "{blue}", "{cyan}"]
+
+[styles.network]
+rx_color = "{blue}"
+tx_color = "{magenta}"
+rx_total_color = "{cyan}"
+tx_total_color = "{red}"
+
+[styles.battery]
+high_battery_color = "{green}"
+medium_battery_color = "{yellow}"
+low_battery_color = "{red}"
+
+[styles.tables]
+headers = {{ color = "{accent}", bold = true }}
+
+[styles.graphs]
+graph_color = "{overlay}"
+legend_text = {{ color = "{muted}" }}
+
+[styles.widgets]
+border_color = "{overlay}"
+selected_border_color = "{accent}"
+widget_title = {{ color = "{fg}" }}
+text = {{ color = "{fg}" }}
+selected_text = {{ color = "{bg}", bg_color = "{accent}" }}
+disabled_text = {{ color = "{surface}" }}
+"#,
+        name = p.name,
+        fg = p.foreground,
+        bg = p.background,
+        surface = p.surface,
+        overlay = p.overlay,
+        muted = p.muted,
+        red = p.red,
+        green = p.green,
+        yellow = p.yellow,
+        blue = p.blue,
+        magenta = p.magenta,
+        cyan = p.cyan,
+        accent = p.accent,
+    )
+}
+
+fn generate_terraformrc() -> String {
+    r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+# Download each provider once and share it across every working directory
+plugin_cache_dir = "$HOME/.terraform.d/plugin-cache"
+disable_checkpoint = true
+"#
+    .to_string()
+}
+
+fn generate_iac_pre_commit(wizard: &WizardState) -> String {
+    let mut hooks = String::from(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+# Copy to .pre-commit-config.yaml in an IaC repo, then run `pre-commit install`
+
+repos:
+  - repo: https://github.com/pre-commit/pre-commit-hooks
+    rev: v5.0.0
+    hooks:
+      - id: trailing-whitespace
+      - id: end-of-file-fixer
+      - id: check-yaml
+      - id: check-merge-conflict
+
+  - repo: https://github.com/antonbabenko/pre-commit-terraform
+    rev: v1.96.1
+    hooks:
+      - id: terraform_fmt
+      - id: terraform_validate
+"#,
+    );
+    if wizard.configures("tflint") {
+        hooks.push_str(
+            "      - id: terraform_tflint
+",
+        );
+    }
+    if wizard.configures("terragrunt") {
+        hooks.push_str(
+            "      - id: terragrunt_fmt
+",
+        );
+    }
+    hooks
+}
+
+fn generate_bat_config(wizard: &WizardState) -> String {
+    format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+--theme="{}"
+--style="numbers,changes,header"
+--italic-text=always
+--map-syntax="*.conf:INI"
+--map-syntax=".ignore:Git Ignore"
+"#,
+        wizard.theme.palette().bat_theme
+    )
+}
+
+fn generate_ripgreprc() -> String {
+    r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+# Case-insensitive unless the pattern has capitals
+--smart-case
+
+# Search dotfiles, but not the noise
+--hidden
+--glob=!.git/
+--glob=!node_modules/
+--glob=!target/
+--glob=!.venv/
+--glob=!*.lock
+
+# Don't flood the terminal with minified lines
+--max-columns=200
+--max-columns-preview
+"#
+    .to_string()
+}
+
+fn generate_editorconfig() -> String {
+    r#"# Generated by LOAD"*",8,1
+
+root = true
+
+[*]
+indent_style = space
+indent_size = 4
+end_of_line = lf
+charset = utf-8
+trim_trailing_whitespace = true
+insert_final_newline = true
+
+[*.{js,jsx,ts,tsx,json,yml,yaml,css,scss,html}]
+indent_size = 2
+
+[*.md]
+trim_trailing_whitespace = false
+
+[Makefile]
+indent_style = tab
+"#
+    .to_string()
+}
+
+fn set_editor_vars(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let shell = wizard.shell_config.shell;
+    let rc = rcblock::rc_file(shell, system);
+    let editor = wizard.editor.command();
+
+    for var in ["EDITOR", "VISUAL"] {
+        if let Err(e) = rcblock::set_var(&rc, shell, var, editor) {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Failed to set {} in {}: {}",
+                var,
+                rc.display(),
+                e
+            )));
+            return;
+        }
+    }
+    let _ = tx.send(InstallMessage::Log(format!(
+        "  Set EDITOR and VISUAL to \"{}\" in {}",
+        editor,
+        display_path(&rc, &system.home_dir)
+    )));
+}
+
+/// Wire the registry's init hooks and aliases into the managed block of a
+/// non-zsh rc
+fn add_shell_hooks(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let shell = wizard.shell_config.shell;
+    let mut lines = hooks::rc_lines(wizard, shell);
+    lines.extend(hooks::alias_lines(wizard, shell));
+    let agent = hooks::ssh_agent_hook(wizard, system);
+    lines.extend(agent.and_then(|h| h.line(shell)).map(str::to_string));
+    if lines.is_empty() {
+        return;
+    }
+
+    let rc = rcblock::rc_file(shell, system);
+    match rcblock::add_lines(&rc, &lines) {
+        Ok(added) if added.is_empty() => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Shell hooks and aliases already in {}",
+                display_path(&rc, &system.home_dir)
+            )));
+        }
+        Ok(added) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Added {} hook and alias line(s) to {}",
+                added.len(),
+                display_path(&rc, &system.home_dir)
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Failed to add shell hooks to {}: {}",
+                rc.display(),
+                e
+            )));
+        }
+    }
+
+    let agent_unsupported = agent.filter(|h| h.line(shell).is_none()).map(|h| h.app);
+    for app in hooks::unsupported(wizard, shell)
+        .into_iter()
+        .chain(agent_unsupported)
+    {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "{} has no hook for {}; set it up yourself",
+            shell.name(),
+            app
+        )));
+    }
+}
+
+fn set_ripgrep_config_var(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let shell = wizard.shell_config.shell;
+    let rc = rcblock::rc_file(shell, system);
+    let path = system.home_dir.join(".ripgreprc");
+    match rcblock::set_var(&rc, shell, "RIPGREP_CONFIG_PATH", &path.to_string_lossy()) {
+        Ok(()) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Set RIPGREP_CONFIG_PATH in {}",
+                display_path(&rc, &system.home_dir)
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Failed to set RIPGREP_CONFIG_PATH in {}: {}",
+                rc.display(),
+                e
+            )));
+        }
+    }
+}
+
+// ─── File writing utilities ──────────────────────────────────────────
+
+/// Write one config file, backing up whatever is there as `*.load-backup`
+pub fn write_file(
+    path: &Path,
+    content: &str,
+    label: &str,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let _ = tx.send(InstallMessage::Log(format!("[CONF] Generating {}", label)));
+    ensure_parent_dir(path);
+    write_config(path, content, label, &system.home_dir, tx);
+}
+
+/// Folders that load every file in them, so a backup left inside would
+/// load as a second copy (iTerm2 would get two profiles with one GUID)
+const LOADS_EVERY_FILE: &[&str] = &["DynamicProfiles"];
+
+/// Where `path`'s backup goes: next to it, or next to its folder when
+/// that folder loads every file
+fn backup_path(path: &Path) -> PathBuf {
+    let backup = path.with_extension(format!(
+        "{}.load-backup",
+        path.extension().and_then(|e| e.to_str()).unwrap_or("bak")
+    ));
+    let folder = path.parent().filter(|dir| {
+        dir.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| LOADS_EVERY_FILE.contains(&name))
+    });
+    match (folder.and_then(Path::parent), backup.file_name()) {
+        (Some(outside), Some(name)) => outside.join(name),
+        _ => backup,
+    }
+}
+
+fn write_config(
+    path: &Path,
+    content: &str,
+    label: &str,
+    home_dir: &Path,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    // Backup existing file
+    if path.exists() {
+        let backup = backup_path(path);
+
+        match fs::copy(path, &backup) {
+            Ok(_) => {
+                let display_path = display_path(&backup, home_dir);
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "  Backed up existing file to {}",
+                    display_path
+                )));
+            }
+            Err(e) => {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "Could not backup {}: {}",
+                    path.display(),
+                    e
+                )));
+            }
+        }
+    }
+
+    // Write new config
+    match fs::File::create(path).and_then(|mut f| f.write_all(content.as_bytes())) {
+        Ok(()) => {
+            let display = display_path(path, home_dir);
+            let _ = tx.send(InstallMessage::Log(format!("  Wrote {}", display)));
+            let _ = tx.send(InstallMessage::ConfigWritten {
+                label: label.to_string(),
+                path: path.to_path_buf(),
+            });
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Failed to write {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    }
+}
+
+fn ensure_parent_dir(path: &Path) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+}
+
+/// Display a path relative to home directory (using ~)
+pub(crate) fn display_path(path: &Path, home_dir: &Path) -> String {
+    path.strip_prefix(home_dir)
+        .map(|p| format!("~/{}", p.display()))
+        .unwrap_or_else(|_| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wizard::{Identity, SetupType};
+
+    fn test_wizard() -> WizardState {
+        let mut state = WizardState::new();
+        state.identity = Identity {
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            github_username: "testuser".to_string(),
+            setup_type: SetupType::Personal,
+        };
+        state
+    }
+
+    fn test_system() -> crate::system::SystemInfo {
+        crate::system::SystemInfo::detect().unwrap()
+    }
+
+    #[test]
+    fn gitconfig_contains_identity() {
+        let wizard = test_wizard();
+        let config = generate_gitconfig(&wizard);
+
+        assert!(config.contains("name = Test User"));
+        assert!(config.contains("email = test@example.com"));
+        assert!(config.contains("user = testuser"));
+        assert!(config.contains("defaultBranch = main"));
+    }
+
+    #[test]
+    fn gitconfig_includes_delta_when_selected() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.insert("delta".to_string());
+        let config = generate_gitconfig(&wizard);
+
+        assert!(config.contains("pager = delta"));
+        assert!(config.contains("[delta]"));
+    }
+
+    #[test]
+    fn ytdlp_config_points_at_ffmpeg_when_found() {
+        let config = generate_ytdlp_config(None);
+        assert!(config.contains("--merge-output-format mp4"));
+        assert!(!config.contains("--ffmpeg-location"));
+
+        let config = generate_ytdlp_config(Some(Path::new("/opt/homebrew/bin/ffmpeg")));
+        assert!(config.contains("--ffmpeg-location \"/opt/homebrew/bin/ffmpeg\""));
+    }
+
+    #[test]
+    fn delta_and_lazygit_follow_the_theme() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.insert("delta".to_string());
+        wizard.theme = crate::theme::ThemeChoice::Nord;
+        let config = generate_gitconfig(&wizard);
+        assert!(config.contains("syntax-theme = Nord"));
+        assert!(config.contains("line-numbers-minus-style = \"#bf616a\""));
+        // 20% of Nord red over Nord's background
+        assert!(config.contains("minus-style = syntax \"#4b3d48\""));
+
+        let lazygit = generate_lazygit_config(&wizard);
+        assert!(lazygit.contains("activeBorderColor:\n      - \"#88c0d0\""));
+        assert!(lazygit.contains("pager: delta --paging=never"));
+        assert!(lazygit.contains("nerdFontsVersion"));
+    }
+
+    #[test]
+    fn gitconfig_excludes_delta_when_not_selected() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.remove("delta");
+        let config = generate_gitconfig(&wizard);
+
+        assert!(!config.contains("pager = delta"));
+    }
+
+    #[test]
+    fn gitconfig_work_mode_has_includeif() {
+        let mut wizard = test_wizard();
+        wizard.identity.setup_type = SetupType::Work;
+        let config = generate_gitconfig(&wizard);
+
+        assert!(config.contains("includeIf"));
+        assert!(config.contains("~/work/"));
+    }
+
+    #[test]
+    fn starship_config_is_valid_toml() {
+        let config = generate_starship_config();
+        assert!(config.contains("[character]"));
+        assert!(config.contains("[directory]"));
+        assert!(config.contains("[git_branch]"));
+        assert!(config.contains("success_symbol"));
+    }
+
+    #[test]
+    fn zshrc_contains_core_sections() {
+        let wizard = test_wizard();
+        let system = test_system();
+        let zshrc = generate_zshrc(&wizard, &system);
+
+        assert!(zshrc.contains("XDG_CONFIG_HOME"));
+        assert!(zshrc.contains("HISTFILE"));
+        assert!(zshrc.contains("setopt"));
+        assert!(zshrc.contains("path_prepend"));
+        assert!(zshrc.contains("alias"));
+        assert!(zshrc.contains("compinit"));
+    }
+
+    #[test]
+    fn zshrc_includes_tools_when_selected() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.insert("eza".to_string());
+        wizard.selected_apps.insert("bat".to_string());
+        wizard.selected_apps.insert("zoxide".to_string());
+        wizard.selected_apps.insert("fzf".to_string());
+        wizard.selected_apps.insert("starship".to_string());
+        let system = test_system();
+        let zshrc = generate_zshrc(&wizard, &system);
+
+        assert!(zshrc.contains("eza"));
+        assert!(zshrc.contains("bat"));
+        assert!(zshrc.contains("zoxide"));
+        assert!(zshrc.contains("fzf"));
+        assert!(zshrc.contains("starship"));
+    }
+
+    #[test]
+    fn aliases_option_controls_zshrc_aliases() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.insert("bat".to_string());
+        wizard.selected_apps.insert("zoxide".to_string());
+        let zshrc = generate_zshrc(&wizard, &test_system());
+        assert!(zshrc.contains("alias cat='bat --style=auto'"));
+        assert!(zshrc.contains("alias cd='z'"));
+
+        // Init and MANPAGER stay; only the aliases go
+        wizard.shell_config.aliases = false;
+        let zshrc = generate_zshrc(&wizard, &test_system());
+        assert!(!zshrc.contains("alias cat="));
+        assert!(!zshrc.contains("alias cd="));
+        assert!(zshrc.contains("zoxide init zsh"));
+        assert!(zshrc.contains("MANPAGER"));
+    }
+
+    #[test]
+    fn bat_config_uses_theme_and_ripgreprc_has_no_comments_inline() {
+        let mut wizard = test_wizard();
+        wizard.theme = crate::theme::ThemeChoice::GruvboxDark;
+        assert!(generate_bat_config(&wizard).contains("--theme=\"gruvbox-dark\""));
+
+        // rg treats every non-comment line as one argument
+        for line in generate_ripgreprc().lines() {
+            let line = line.trim();
+            assert!(line.is_empty() || line.starts_with('#') || line.starts_with("--"));
+        }
+    }
+
+    #[test]
+    fn zshrc_wires_only_the_chosen_prompt() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.insert("starship".to_string());
+        wizard.shell_config.prompt = PromptChoice::Powerlevel10k;
+        let zshrc = generate_zshrc(&wizard, &test_system());
+        assert!(zshrc.contains("p10k-instant-prompt"));
+        assert!(zshrc.contains("powerlevel10k.zsh-theme"));
+        assert!(!zshrc.contains("starship init"));
+
+        wizard.shell_config.prompt = PromptChoice::PurePurple;
+        let zshrc = generate_zshrc(&wizard, &test_system());
+        assert!(zshrc.contains("prompt pure"));
+        assert!(!zshrc.contains("powerlevel10k"));
+    }
+
+    #[test]
+    fn zshrc_sets_npm_prefix_only_for_npm_tools() {
+        let mut wizard = test_wizard();
+        let system = test_system();
+        wizard.selected_apps.remove("claude-code");
+        assert!(!generate_zshrc(&wizard, &system).contains("NPM_CONFIG_PREFIX"));
+
+        wizard.selected_apps.insert("claude-code".to_string());
+        let zshrc = generate_zshrc(&wizard, &system);
+        assert!(zshrc.contains("export NPM_CONFIG_PREFIX=\"$HOME/.npm-global\""));
+        assert!(zshrc.contains("path_prepend \"$NPM_CONFIG_PREFIX/bin\""));
+    }
+
+    #[test]
+    fn planned_configs_follow_the_answers() {
+        let (_, mut wizard, system) = golden_fixtures().remove(1);
+        let labels = |wizard: &WizardState| -> Vec<&'static str> {
+            planned_configs(wizard, &system, None)
+                .iter()
+                .map(|c| c.label)
+                .collect()
+        };
+        // fish gets an rc block rather than a generated rc file
+        assert!(!labels(&wizard).contains(&".zshrc"));
+        assert_eq!(labels(&wizard).first(), Some(&".gitconfig"));
+        assert_eq!(labels(&wizard).last(), Some(&".editorconfig"));
+
+        wizard.selected_apps.insert("bat".to_string());
+        let planned = planned_configs(&wizard, &system, None);
+        let bat = planned.iter().find(|c| c.label == "bat/config").unwrap();
+        assert_eq!(bat.path, PathBuf::from("/home/ada/.config/bat/config"));
+        assert_eq!(bat.content, generate_bat_config(&wizard));
+
+        // The starship prompt, kept on its own config
+        wizard.shell_config.prompt = PromptChoice::Starship;
+        assert!(labels(&wizard).contains(&"starship.toml"));
+        wizard
+            .app_modes
+            .insert("starship".to_string(), crate::wizard::AppMode::SkipConfig);
+        assert!(!labels(&wizard).contains(&"starship.toml"));
+    }
+
+    #[test]
+    fn gui_editors_wait_for_git() {
+        let mut wizard = test_wizard();
+        let system = test_system();
+        wizard.editor = crate::wizard::EditorChoice::VSCode;
+
+        assert!(generate_gitconfig(&wizard).contains("editor = code --wait"));
+        assert!(generate_zshrc(&wizard, &system).contains("export VISUAL=\"code --wait\""));
+    }
+
+    #[test]
+    fn zed_settings_use_theme_and_font() {
+        let mut wizard = test_wizard();
+        wizard.theme = crate::theme::ThemeChoice::TokyoNight;
+        wizard.font = crate::theme::FontChoice::FiraCode;
+        let zed = generate_zed_settings(&wizard);
+
+        assert!(zed.contains("\"theme\": \"Tokyo Night\""));
+        assert!(zed.contains("\"tokyo-night\": true"));
+        assert!(zed.contains("\"buffer_font_family\": \"FiraCode Nerd Font\""));
+
+        wizard.theme = crate::theme::ThemeChoice::GruvboxDark;
+        wizard.font = crate::theme::FontChoice::System;
+        let zed = generate_zed_settings(&wizard);
+        assert!(!zed.contains("auto_install_extensions"));
+        assert!(!zed.contains("font_family"));
+    }
+
+    #[test]
+    fn ghostty_config_uses_palette_and_font() {
+        let mut wizard = test_wizard();
+        wizard.theme = crate::theme::ThemeChoice::Nord;
+        let config = generate_ghostty_config(&wizard);
+
+        assert!(config.contains("background = 2e3440"));
+        assert!(config.contains("palette = 1=#bf616a"));
+        assert!(config.contains("palette = 15="));
+        assert!(config.contains("font-family = \"JetBrainsMono Nerd Font\""));
+    }
+
+    #[test]
+    fn iterm2_profile_is_json_with_palette_and_font() {
+        let mut wizard = test_wizard();
+        wizard.theme = crate::theme::ThemeChoice::Nord;
+        let json: serde_json::Value =
+            serde_json::from_str(&generate_iterm2_profile(&wizard)).unwrap();
+        let profile = &json["Profiles"][0];
+
+        assert_eq!(profile["Guid"], ITERM2_PROFILE_GUID);
+        assert_eq!(profile["Normal Font"], "JetBrainsMonoNF-Regular 14");
+        assert_eq!(profile["Custom Directory"], "Recycle");
+        // #bf616a
+        assert_eq!(profile["Ansi 1 Color"]["Red Component"], 0.749);
+        assert!(profile["Ansi 15 Color"].is_object());
+
+        wizard.font = crate::theme::FontChoice::System;
+        let json = generate_iterm2_profile(&wizard);
+        assert!(!json.contains("Normal Font"));
+
+        // iTerm2 loads every file in DynamicProfiles, backups included
+        let iterm = Path::new("/Users/ada/Library/Application Support/iTerm2");
+        assert_eq!(
+            backup_path(&iterm.join("DynamicProfiles").join("loadstar.json")),
+            iterm.join("loadstar.json.load-backup")
+        );
+        assert_eq!(
+            backup_path(Path::new("/Users/ada/.config/k9s/skin.yaml")),
+            Path::new("/Users/ada/.config/k9s/skin.yaml.load-backup")
+        );
+    }
+
+    #[test]
+    fn k9s_skin_uses_palette() {
+        let mut wizard = test_wizard();
+        wizard.theme = crate::theme::ThemeChoice::GruvboxDark;
+        let skin = generate_k9s_skin(&wizard);
+
+        assert!(skin.contains("# Gruvbox Dark"));
+        assert!(skin.contains("logoColor: \"#fe8019\""));
+        assert!(!skin.contains('{'));
+    }
+
+    #[test]
+    fn monitors_get_the_palette_and_btop_keeps_its_own_config() {
+        let mut wizard = test_wizard();
+        wizard.theme = crate::theme::ThemeChoice::Nord;
+        for id in ["btop", "bottom"] {
+            wizard.selected_apps.insert(id.to_string());
+        }
+        let mut system = test_system();
+        system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-monitors-{}", std::process::id()));
+        let labels = |system: &SystemInfo| -> Vec<&'static str> {
+            planned_configs(&wizard, system, None)
+                .iter()
+                .map(|c| c.label)
+                .collect()
+        };
+        let planned = labels(&system);
+        for label in [
+            "btop/themes/loadstar.theme",
+            "btop/btop.conf",
+            "bottom/bottom.toml",
+        ] {
+            assert!(planned.contains(&label), "{}", label);
+        }
+        assert!(generate_btop_theme(&wizard).contains("theme[hi_fg]=\"#88c0d0\""));
+        let bottom: toml::Table = toml::from_str(&generate_bottom_config(&wizard)).unwrap();
+        assert_eq!(
+            bottom["styles"]["network"]["rx_color"].as_str(),
+            Some("#81a1c1")
+        );
+
+        fs::create_dir_all(system.config_dir.join("btop")).unwrap();
+        fs::write(system.config_dir.join("btop").join("btop.conf"), "").unwrap();
+        assert!(!labels(&system).contains(&"btop/btop.conf"));
+        let _ = fs::remove_dir_all(&system.config_dir);
+    }
+
+    #[test]
+    fn iac_pre_commit_hooks_follow_selection() {
+        let mut wizard = test_wizard();
+        wizard.selected_apps.insert("tflint".to_string());
+        let hooks = generate_iac_pre_commit(&wizard);
+
+        assert!(hooks.contains("id: terraform_fmt"));
+        assert!(hooks.contains("id: terraform_tflint"));
+        assert!(!hooks.contains("terragrunt"));
+        assert!(generate_terraformrc().contains("plugin_cache_dir"));
+    }
+
+    #[test]
+    fn tmux_config_has_key_sections() {
+        let system = test_system();
+        let config = generate_tmux_config(&system);
+
+        assert!(config.contains("prefix C-Space"));
+        assert!(config.contains("mouse on"));
+        assert!(config.contains("mode-keys vi"));
+        assert!(config.contains("status"));
+    }
+
+    #[test]
+    fn editorconfig_is_valid() {
+        let config = generate_editorconfig();
+        assert!(config.contains("root = true"));
+        assert!(config.contains("indent_style"));
+        assert!(config.contains("[*.md]"));
+        assert!(config.contains("[Makefile]"));
+    }
+
+    #[test]
+    fn display_path_uses_tilde() {
+        let home = std::path::PathBuf::from("/Users/test");
+        let path = std::path::PathBuf::from("/Users/test/.gitconfig");
+        assert_eq!(display_path(&path, &home), "~/.gitconfig");
+    }
+
+    #[test]
+    fn display_path_absolute_when_not_in_home() {
+        let home = std::path::PathBuf::from("/Users/test");
+        let path = std::path::PathBuf::from("/etc/hosts");
+        assert_eq!(display_path(&path, &home), "/etc/hosts");
+    }
+
+    // ─── Golden files ────────────────────────────────────────────────
+    // Every template is rendered for each fixture below and compared with
+    // tests/golden/<fixture>/<file>. After an intended change, accept the
+    // new output with `UPDATE_GOLDEN=1 cargo test -p loadstar-core golden`
+    // and review the diff like any other.
+
+    /// A fixed machine, so nothing from the one running the tests leaks in
+    fn golden_system(os: crate::system::Os) -> SystemInfo {
+        use crate::system::{Arch, Hardware, PackageManagers};
+        SystemInfo {
+            os,
+            arch: Arch::Aarch64,
+            hostname: "c64".to_string(),
+            shell: "/bin/zsh".to_string(),
+            home_dir: PathBuf::from("/home/ada"),
+            config_dir: PathBuf::from("/home/ada/.config"),
+            package_managers: PackageManagers {
+                homebrew: None,
+                cargo: None,
+                npm: None,
+                go: None,
+                pip: None,
+                pipx: None,
+                uv: None,
+                apt: None,
+            },
+            linux_distro: None,
+            hardware: Hardware::default(),
+        }
+    }
+
+    fn golden_fixtures() -> Vec<(&'static str, WizardState, SystemInfo)> {
+        use crate::system::Os;
+        use crate::theme::ThemeChoice;
+
+        let mut zsh = test_wizard();
+        zsh.shell_config.shell = ShellChoice::Zsh;
+        zsh.shell_config.prompt = PromptChoice::Starship;
+        zsh.shell_config.multiplexer = Some(MultiplexerChoice::Tmux);
+        for id in ["delta", "bat", "ripgrep", "lazygit", "zoxide"] {
+            zsh.selected_apps.insert(id.to_string());
+        }
+        zsh.rewrite_github_https = true;
+
+        let mut fish = test_wizard();
+        fish.shell_config.shell = ShellChoice::Fish;
+        fish.shell_config.prompt = PromptChoice::None;
+        fish.shell_config.multiplexer = None;
+        fish.theme = ThemeChoice::GruvboxDark;
+
+        let mut work = test_wizard();
+        work.identity.setup_type = SetupType::Work;
+        work.identity.email = "test@corp.example".to_string();
+        work.theme = ThemeChoice::Nord;
+        for id in ["terraform", "pre-commit", "k9s"] {
+            work.selected_apps.insert(id.to_string());
+        }
+
+        vec![
+            ("zsh-starship-tmux", zsh, golden_system(Os::MacOS)),
+            ("fish-none", fish, golden_system(Os::Linux)),
+            ("work-identity", work, golden_system(Os::MacOS)),
+        ]
+    }
+
+    /// Every template, named by the golden file it is compared with
+    fn render_all(wizard: &WizardState, system: &SystemInfo) -> Vec<(&'static str, String)> {
+        vec![
+            ("gitconfig", generate_gitconfig(wizard)),
+            ("starship.toml", generate_starship_config()),
+            ("zshrc", generate_zshrc(wizard, system)),
+            ("tmux.conf", generate_tmux_config(system)),
+            ("zed-settings.json", generate_zed_settings(wizard)),
+            ("ghostty-config", generate_ghostty_config(wizard)),
+            ("iterm2-profile.json", generate_iterm2_profile(wizard)),
+            ("lazygit-config.yml", generate_lazygit_config(wizard)),
+            ("yt-dlp-config", generate_ytdlp_config(None)),
+            ("k9s-skin.yaml", generate_k9s_skin(wizard)),
+            ("btop-loadstar.theme", generate_btop_theme(wizard)),
+            ("bottom.toml", generate_bottom_config(wizard)),
+            ("terraformrc", generate_terraformrc()),
+            ("pre-commit-iac.yaml", generate_iac_pre_commit(wizard)),
+            ("bat-config", generate_bat_config(wizard)),
+            ("ripgreprc", generate_ripgreprc()),
+            ("editorconfig", generate_editorconfig()),
+        ]
+    }
+
+    /// Where two texts first differ, as "line N: expected / actual"
+    fn first_difference(expected: &str, actual: &str) -> String {
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = actual.lines();
+        for n in 1.. {
+            match (expected_lines.next(), actual_lines.next()) {
+                (Some(e), Some(a)) if e == a => continue,
+                (None, None) => break,
+                (e, a) => {
+                    return format!(
+                        "line {}:\n    expected: {}\n    actual:   {}",
+                        n,
+                        e.unwrap_or("<end of file>"),
+                        a.unwrap_or("<end of file>")
+                    )
+                }
+            }
+        }
+        "trailing newline differs".to_string()
+    }
+
+    #[test]
+    fn generated_configs_match_golden_files() {
+        let golden = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("golden");
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        let mut failures = Vec::new();
+
+        for (fixture, wizard, system) in golden_fixtures() {
+            let dir = golden.join(fixture);
+            let rendered = render_all(&wizard, &system);
+
+            if update {
+                let _ = fs::remove_dir_all(&dir);
+                fs::create_dir_all(&dir).unwrap();
+                for (file, content) in &rendered {
+                    fs::write(dir.join(file), content).unwrap();
+                }
+                continue;
+            }
+
+            for (file, content) in &rendered {
+                let path = dir.join(file);
+                match fs::read_to_string(&path) {
+                    Ok(expected) if expected == *content => {}
+                    Ok(expected) => failures.push(format!(
+                        "{}/{}: {}",
+                        fixture,
+                        file,
+                        first_difference(&expected, content)
+                    )),
+                    Err(_) => failures.push(format!("{}/{}: no golden file", fixture, file)),
+                }
+            }
+            for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !rendered.iter().any(|(file, _)| *file == name) {
+                    failures.push(format!("{}/{}: no longer generated", fixture, name));
+                }
+            }
+        }
+
+        assert!(
+            failures.is_empty(),
+            "Generated configs differ from tests/golden:\n  {}\n\
+             If the change is intended: UPDATE_GOLDEN=1 cargo test -p loadstar-core golden",
+            failures.join("\n  ")
+        );
+    }
+}
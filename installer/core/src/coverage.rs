@@ -0,0 +1,122 @@
+//! Catalog coverage
+//! Which apps this machine has no way to install: apt packages without apt,
+//! scripts without curl, a toolchain whose provider can't install either.
+
+use crate::catalog::{find_app, App, AppId, InstallMethod, Query};
+use crate::system::SystemInfo;
+
+/// An app that can't be installed here, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap {
+    pub app: AppId,
+    pub reason: String,
+}
+
+/// Check every app offered on this machine's platform
+pub fn check(system: &SystemInfo) -> Vec<Gap> {
+    let apps = Query::new().platform(system.os).apps();
+    check_with(&apps, &|cmd| system.find_tool(cmd).is_some())
+}
+
+/// Check `apps` with `has_tool` standing in for PATH lookups
+pub fn check_with(apps: &[&App], has_tool: &dyn Fn(&str) -> bool) -> Vec<Gap> {
+    apps.iter()
+        .filter_map(|app| {
+            missing(app.method(), has_tool).map(|reason| Gap {
+                app: app.id,
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// What `method` needs that isn't here and won't be installed first, if
+/// anything. Homebrew is installed by the run itself as long as curl is
+/// there to fetch it, and toolchains come from their provider app.
+pub fn missing(method: &InstallMethod, has_tool: &dyn Fn(&str) -> bool) -> Option<String> {
+    match method {
+        InstallMethod::Brew(_) | InstallMethod::BrewCask(_) => (!has_tool("brew")
+            && !has_tool("curl"))
+        .then(|| "no Homebrew, and no curl to install it with".to_string()),
+        InstallMethod::Apt(_) => (!has_tool("apt"))
+            .then(|| "apt isn't available (not a Debian-based system)".to_string()),
+        InstallMethod::Script(_) => (!has_tool("curl")).then(|| "curl isn't installed".to_string()),
+        InstallMethod::Manual(cmd) => programs(cmd)
+            .into_iter()
+            .find(|program| !has_tool(program))
+            .map(|program| format!("{} isn't installed", program)),
+        InstallMethod::Cargo(_)
+        | InstallMethod::Go(_)
+        | InstallMethod::Npm(_)
+        | InstallMethod::Pip(_) => {
+            let toolchain = method.toolchain()?;
+            if has_tool(toolchain.command) {
+                return None;
+            }
+            let provider = find_app(toolchain.provider)?;
+            missing(provider.method(), has_tool).map(|reason| {
+                format!(
+                    "needs {} from {}, which can't be installed: {}",
+                    toolchain.command, provider.name, reason
+                )
+            })
+        }
+        InstallMethod::PerOs { .. } => missing(method.for_current_os(), has_tool),
+    }
+}
+
+/// The programs a shell command runs: the first word of each pipeline
+/// stage, past `sudo` and variable assignments. `[` tests are skipped.
+fn programs(command: &str) -> Vec<&str> {
+    command
+        .split(['|', '&', ';'])
+        .filter_map(|stage| {
+            stage
+                .split_whitespace()
+                .find(|word| *word != "sudo" && !word.contains('='))
+        })
+        .filter(|program| *program != "[")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gaps_name_what_is_missing() {
+        let bare = |cmd: &str| matches!(cmd, "mkdir" | "tar" | "fc-cache");
+        assert_eq!(
+            missing(&InstallMethod::Apt("fd-find"), &bare).as_deref(),
+            Some("apt isn't available (not a Debian-based system)")
+        );
+        assert_eq!(
+            missing(&InstallMethod::Script("https://sh.rustup.rs"), &bare).as_deref(),
+            Some("curl isn't installed")
+        );
+        assert_eq!(
+            missing(&InstallMethod::Manual("sudo snap install ghostty"), &bare).as_deref(),
+            Some("snap isn't installed")
+        );
+        // rustup comes from a script, and that needs curl
+        let cargo = missing(&InstallMethod::Cargo("eza"), &bare).unwrap();
+        assert!(cargo.starts_with("needs cargo from"), "{}", cargo);
+
+        // curl alone is enough to bootstrap Homebrew and rustup
+        let curl = |cmd: &str| cmd == "curl";
+        assert_eq!(missing(&InstallMethod::Brew("bat"), &curl), None);
+        assert_eq!(missing(&InstallMethod::Cargo("eza"), &curl), None);
+    }
+
+    #[test]
+    fn manual_commands_check_every_stage() {
+        assert_eq!(
+            programs("mkdir -p ~/f && curl -fsSL x | tar -xJ -C ~/f && fc-cache -f"),
+            ["mkdir", "curl", "tar", "fc-cache"]
+        );
+        assert_eq!(
+            programs("[ -d ~/p ] || git clone --depth=1 https://x ~/p"),
+            ["git"]
+        );
+    }
+}
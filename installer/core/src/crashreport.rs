@@ -0,0 +1,306 @@
+//! Crash reports
+//! When an install dies, write what a bug report needs to a local file.
+//! Nothing leaves the machine, and the run's name, email, GitHub username,
+//! git identity and API keys are replaced in the log before it's written.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// How much of the install log goes in a report
+const LOG_LINES: usize = 500;
+
+/// Git config keys whose values are someone's identity
+const IDENTITY_KEYS: &[&str] = &["user.name", "user.email", "user.signingkey"];
+
+/// The install the panic hook reports on, and its log so far
+struct Watched {
+    wizard: WizardState,
+    system: SystemInfo,
+    log: VecDeque<String>,
+}
+
+static WATCHED: Mutex<Option<Watched>> = Mutex::new(None);
+
+/// Where the panic hook last saved a report
+static SAVED: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Write a report from whichever thread panics during an install. Release
+/// builds abort on panic, so the install thread's `join` never sees one;
+/// the hook runs before the abort.
+pub fn install_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // try_lock: the panic may have come from inside `note`
+        let saved = WATCHED.try_lock().ok().and_then(|watched| {
+            let watched = watched.as_ref()?;
+            let error = match info.location() {
+                Some(at) => format!("Panicked at {}: {}", at, panic_message(info.payload())),
+                None => format!("Panicked: {}", panic_message(info.payload())),
+            };
+            let log: Vec<String> = watched.log.iter().cloned().collect();
+            save(&error, &log, &watched.wizard, &watched.system).ok()
+        });
+        if let Some(path) = saved {
+            eprintln!("loadstar crashed; report saved to {}", path.display());
+            if let Ok(mut last) = SAVED.lock() {
+                *last = Some(path);
+            }
+        }
+        previous(info);
+    }));
+}
+
+/// Start keeping what a report on this install needs
+pub fn watch(wizard: &WizardState, system: &SystemInfo) {
+    if let Ok(mut watched) = WATCHED.lock() {
+        *watched = Some(Watched {
+            wizard: wizard.clone(),
+            system: system.clone(),
+            log: VecDeque::new(),
+        });
+    }
+}
+
+/// Keep a log line for the report, while an install is watched
+pub fn note(line: &str) {
+    if let Ok(mut watched) = WATCHED.lock() {
+        if let Some(watched) = watched.as_mut() {
+            if watched.log.len() == LOG_LINES {
+                watched.log.pop_front();
+            }
+            watched.log.push_back(line.to_string());
+        }
+    }
+}
+
+/// The install is over; nothing to report on
+pub fn unwatch() {
+    if let Ok(mut watched) = WATCHED.lock() {
+        *watched = None;
+    }
+}
+
+/// The report the panic hook saved, if it did
+pub fn take_saved() -> Option<PathBuf> {
+    SAVED.lock().ok()?.take()
+}
+
+/// A log line with the run's personal details swapped for placeholders.
+/// A git identity key's value is cut off whatever it is, since what it
+/// replaced may not be the run's own.
+fn redact_line(line: &str, wizard: &WizardState) -> String {
+    if let Some(key) = IDENTITY_KEYS.iter().find(|key| line.contains(*key)) {
+        let end = line.find(key).unwrap_or(0) + key.len();
+        return format!("{} <redacted>", &line[..end]);
+    }
+    let identity = &wizard.identity;
+    let replacements = [
+        (identity.email.as_str(), "<email>"),
+        (identity.name.as_str(), "<name>"),
+        (identity.github_username.as_str(), "<github user>"),
+        (wizard.github_token.expose(), "<secret>"),
+        (wizard.ai.anthropic_key.expose(), "<secret>"),
+        (wizard.ai.openai_key.expose(), "<secret>"),
+        (wizard.ssh_passphrase.expose(), "<secret>"),
+    ];
+    let mut line = line.to_string();
+    // Short values would eat parts of ordinary words
+    for (value, placeholder) in replacements {
+        if value.trim().len() > 2 {
+            line = line.replace(value, placeholder);
+        }
+    }
+    line
+}
+
+/// Text of a panic payload; `panic!` gives a `&str` or a `String`
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// The report: error, versions, system, selection, and the tail of the log.
+/// The home directory is shown as `~` so the username isn't in it, and the
+/// log goes through `redact_line`.
+pub fn render_report(
+    error: &str,
+    log: &[String],
+    wizard: &WizardState,
+    system: &SystemInfo,
+    brew_version: Option<&str>,
+) -> String {
+    let home = system.home_dir.to_string_lossy().to_string();
+    let redact = |text: &str| {
+        if home.len() > 1 {
+            text.replace(&home, "~")
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut report = String::from("# loadstar crash report\n\n");
+    report.push_str(
+        "Attach this file to a bug report. Your name, email, GitHub username and API keys \
+         are replaced in the log, but look it over before you share it.\n\n",
+    );
+
+    report.push_str(&format!("## Error\n\n```\n{}\n```\n\n", redact(error)));
+
+    report.push_str("## Versions\n\n");
+    report.push_str(&format!("- loadstar {}\n", env!("CARGO_PKG_VERSION")));
+    report.push_str(&format!(
+        "- {}\n\n",
+        brew_version.unwrap_or("Homebrew not found")
+    ));
+
+    report.push_str("## System\n\n");
+    let os = match &system.linux_distro {
+        Some(distro) => format!("{} ({})", system.os.name(), distro.name),
+        None => system.os.name().to_string(),
+    };
+    report.push_str(&format!("- OS: {} {}\n", os, system.arch.name()));
+    report.push_str(&format!("- Shell: {}\n", system.shell));
+    let managers = &system.package_managers;
+    let found: Vec<&str> = [
+        ("brew", &managers.homebrew),
+        ("cargo", &managers.cargo),
+        ("npm", &managers.npm),
+        ("go", &managers.go),
+        ("pip", &managers.pip),
+        ("pipx", &managers.pipx),
+        ("uv", &managers.uv),
+        ("apt", &managers.apt),
+    ]
+    .into_iter()
+    .filter(|(_, path)| path.is_some())
+    .map(|(name, _)| name)
+    .collect();
+    report.push_str(&format!("- Package managers: {}\n\n", found.join(", ")));
+
+    report.push_str("## Selection\n\n");
+    let shell = &wizard.shell_config;
+    report.push_str(&format!(
+        "- Shell: {}, prompt: {}, terminal: {}, editor: {}\n",
+        shell.shell.name(),
+        shell.prompt.name(),
+        shell.terminal.name(),
+        wizard.editor.name()
+    ));
+    report.push_str(&format!(
+        "- Theme: {}, font: {}\n",
+        wizard.theme.name(),
+        wizard.font.name()
+    ));
+    let mut apps: Vec<String> = wizard
+        .selected_apps
+        .iter()
+        .map(|id| match wizard.app_modes.get(id) {
+            Some(mode) => format!("{} ({})", id, mode.name()),
+            None => id.clone(),
+        })
+        .collect();
+    apps.sort();
+    report.push_str(&format!("- Apps ({}): {}\n\n", apps.len(), apps.join(", ")));
+
+    let tail = &log[log.len().saturating_sub(LOG_LINES)..];
+    report.push_str(&format!("## Log (last {} lines)\n\n```\n", tail.len()));
+    for line in tail {
+        report.push_str(&redact(&redact_line(line, wizard)));
+        report.push('\n');
+    }
+    report.push_str("```\n");
+    report
+}
+
+/// Write a report next to the profile, one file per crash
+pub fn save(
+    error: &str,
+    log: &[String],
+    wizard: &WizardState,
+    system: &SystemInfo,
+) -> io::Result<PathBuf> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = system
+        .config_dir
+        .join("loadstar")
+        .join(format!("crash-{}.md", stamp));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let brew_version = Command::new("brew")
+        .arg("--version")
+        .env("PATH", system.search_path())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .next()
+                .map(str::to_string)
+        });
+
+    fs::write(
+        &path,
+        render_report(error, log, wizard, system, brew_version.as_deref()),
+    )?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_keeps_the_log_tail_and_hides_personal_details() {
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        wizard.identity.name = "Ada Lovelace".to_string();
+        wizard.identity.email = "ada@example.com".to_string();
+        wizard.ai.anthropic_key = crate::secret::Secret::new("sk-ant-secret");
+
+        let home = system.home_dir.display().to_string();
+        let mut log: Vec<String> = (0..600)
+            .map(|i| format!("line {} in {}/.zshrc", i, home))
+            .collect();
+        log.push("  changed user.email from old@corp.example → ada@example.com".to_string());
+        log.push("[SSH] Generating key for ada@example.com as Ada Lovelace".to_string());
+        let report = render_report("boom", &log, &wizard, &system, Some("Homebrew 4.4.0"));
+
+        assert!(report.contains("```\nboom\n```"));
+        assert!(report.contains("- Homebrew 4.4.0\n"));
+        assert!(report.contains("## Log (last 500 lines)"));
+        assert!(!report.contains("line 101 in"));
+        assert!(report.contains("line 102 in ~/.zshrc\n"));
+        assert!(report.contains("  changed user.email <redacted>\n"));
+        assert!(report.contains("[SSH] Generating key for <email> as <name>\n"));
+        assert!(!report.contains("old@corp.example"));
+        assert!(!report.contains("ada@example.com"));
+        assert!(!report.contains("sk-ant-secret"));
+    }
+
+    #[test]
+    fn panic_payloads_become_text() {
+        let payload = std::panic::catch_unwind(|| panic!("install thread {}", 7)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "install thread 7");
+        let payload = std::panic::catch_unwind(|| panic!("static")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "static");
+    }
+}
@@ -0,0 +1,402 @@
+//! Custom packages
+//! Packages outside the catalog, typed into the expert palette as
+//! `cargo:name` and the like. Each becomes an app of its own for the run,
+//! so it installs, times and reports like a catalog app.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::catalog::{App, AppId, Category, InstallMethod, CATALOG};
+use crate::system::SystemInfo;
+
+/// What installs a custom package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CustomSource {
+    Brew,
+    Cask,
+    Cargo,
+    Npm,
+}
+
+impl CustomSource {
+    pub fn all() -> &'static [CustomSource] {
+        &[
+            CustomSource::Brew,
+            CustomSource::Cask,
+            CustomSource::Cargo,
+            CustomSource::Npm,
+        ]
+    }
+
+    /// Prefix in the palette, as in `cargo:bacon`
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            CustomSource::Brew => "brew",
+            CustomSource::Cask => "cask",
+            CustomSource::Cargo => "cargo",
+            CustomSource::Npm => "npm",
+        }
+    }
+}
+
+/// A package typed into the palette
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CustomPackage {
+    pub source: CustomSource,
+    pub name: String,
+}
+
+impl fmt::Display for CustomPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.source.prefix(), self.name)
+    }
+}
+
+impl CustomPackage {
+    /// `brew:`, `cask:`, `cargo:` or `npm:` and a package name; a bare
+    /// name is a brew formula. Names are limited to what package names
+    /// use, taps (`owner/tap/name`) and npm scopes (`@scope/name`) included.
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let entry = entry.trim();
+        let (source, name) = match entry.split_once(':') {
+            Some((prefix, name)) => {
+                let source = CustomSource::all()
+                    .iter()
+                    .find(|s| s.prefix() == prefix)
+                    .ok_or_else(|| {
+                        format!("'{}:' isn't one of brew:, cask:, cargo: or npm:", prefix)
+                    })?;
+                (*source, name)
+            }
+            None => (CustomSource::Brew, entry),
+        };
+        if name.is_empty() {
+            return Err("Type a package name".to_string());
+        }
+        let allowed = |c: char| c.is_ascii_alphanumeric() || "@/._+-".contains(c);
+        if let Some(c) = name.chars().find(|c| !allowed(*c)) {
+            return Err(format!("'{}' can't be in a package name", c));
+        }
+        if name.starts_with('-') {
+            return Err("A package name can't start with '-'".to_string());
+        }
+        Ok(Self {
+            source,
+            name: name.to_string(),
+        })
+    }
+
+    /// The catalog app that installs this same package, if there is one
+    pub fn in_catalog(&self) -> Option<&'static App> {
+        CATALOG
+            .iter()
+            .find(|app| match (self.source, app.method()) {
+                (CustomSource::Brew, InstallMethod::Brew(name))
+                | (CustomSource::Cask, InstallMethod::BrewCask(name))
+                | (CustomSource::Cargo, InstallMethod::Cargo(name))
+                | (CustomSource::Npm, InstallMethod::Npm(name)) => *name == self.name,
+                _ => false,
+            })
+    }
+
+    /// The app this package installs as. Made the first time it's asked
+    /// for and kept for the rest of the process, so `find_app` knows it.
+    pub fn app(&self) -> &'static App {
+        let id = self.to_string();
+        let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(app) = registered.iter().find(|app| app.id.as_str() == id) {
+            return app;
+        }
+        let name: &'static str = Box::leak(self.name.clone().into_boxed_str());
+        let install_method = match self.source {
+            CustomSource::Brew => InstallMethod::Brew(name),
+            CustomSource::Cask => InstallMethod::BrewCask(name),
+            CustomSource::Cargo => InstallMethod::Cargo(name),
+            CustomSource::Npm => InstallMethod::Npm(name),
+        };
+        let app: &'static App = Box::leak(Box::new(App {
+            id: AppId::custom(Box::leak(id.into_boxed_str())),
+            name,
+            description: "Added in expert mode",
+            category: Category::System,
+            install_method,
+            env: &[],
+            config_files: &[],
+            dependencies: &[],
+            tags: &["custom"],
+            url: "",
+        }));
+        registered.push(app);
+        app
+    }
+}
+
+/// Every custom package turned into an app so far
+static REGISTERED: Mutex<Vec<&'static App>> = Mutex::new(Vec::new());
+
+/// The custom app called `id`, if one has been made
+pub fn find(id: &str) -> Option<&'static App> {
+    let registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    registered.iter().find(|app| app.id.as_str() == id).copied()
+}
+
+// ─── Checking ────────────────────────────────────────────────────────
+
+/// Make sure Homebrew knows a brew or cask package, with `brew search`.
+/// `Ok` says how far it was checked; cargo and npm packages, taps, and
+/// anything typed before Homebrew is installed are only checked when they
+/// install.
+pub fn check(package: &CustomPackage, system: &SystemInfo) -> Result<String, String> {
+    let brew = system.find_tool("brew");
+    check_with(package, &|args| {
+        let brew = brew.as_ref()?;
+        Command::new(brew)
+            .args(args)
+            .output()
+            .ok()
+            .map(|out| out.status.success() && !out.stdout.is_empty())
+    })
+}
+
+/// `check` with `search` standing in for running brew: `None` when brew
+/// can't be run, otherwise whether the search found anything
+pub fn check_with(
+    package: &CustomPackage,
+    search: &dyn Fn(&[&str]) -> Option<bool>,
+) -> Result<String, String> {
+    let kind = match package.source {
+        CustomSource::Brew => "--formula",
+        CustomSource::Cask => "--cask",
+        CustomSource::Cargo | CustomSource::Npm => {
+            return Ok(format!(
+                "{} queued; {} checks it when it installs",
+                package,
+                package.source.prefix()
+            ))
+        }
+    };
+    if package.name.contains('/') {
+        return Ok(format!(
+            "{} queued; taps are checked when it installs",
+            package
+        ));
+    }
+    let pattern = format!("/^{}$/", escape_regex(&package.name));
+    match search(&["search", kind, &pattern]) {
+        Some(true) => Ok(format!("{} found with brew search", package)),
+        Some(false) => Err(format!(
+            "brew search found no {} called {}",
+            kind.trim_start_matches("--"),
+            package.name
+        )),
+        None => Ok(format!(
+            "{} queued; brew isn't installed yet, so it's checked when it installs",
+            package
+        )),
+    }
+}
+
+// ─── Suggestions ─────────────────────────────────────────────────────
+
+/// Most suggestions shown for one entry
+pub const MAX_SUGGESTIONS: usize = 6;
+
+/// A package `brew search` turned up for what's been typed so far
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub package: CustomPackage,
+    /// From `brew info`; empty if it had none
+    pub description: String,
+}
+
+/// Brew formulae and casks matching a partly typed entry, best first.
+/// Empty for cargo and npm entries, taps, and when brew isn't installed.
+pub fn suggest(entry: &str, system: &SystemInfo) -> Vec<Suggestion> {
+    let Some(brew) = system.find_tool("brew") else {
+        return Vec::new();
+    };
+    suggest_with(entry, &|args| {
+        Command::new(&brew)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+    })
+}
+
+/// `suggest` with `brew` standing in for running brew: what it printed,
+/// or `None` if it failed. `brew search` has no JSON output, so the names
+/// come from it and the descriptions from `brew info --json=v2`.
+pub fn suggest_with(entry: &str, brew: &dyn Fn(&[&str]) -> Option<String>) -> Vec<Suggestion> {
+    let entry = entry.trim();
+    let (sources, term) = match entry.split_once(':') {
+        Some(("brew", term)) => (&[CustomSource::Brew][..], term),
+        Some(("cask", term)) => (&[CustomSource::Cask][..], term),
+        Some(_) => return Vec::new(),
+        None => (&[CustomSource::Brew, CustomSource::Cask][..], entry),
+    };
+    if term.len() < 2 || term.contains('/') || CustomPackage::parse(entry).is_err() {
+        return Vec::new();
+    }
+
+    let mut suggestions = Vec::new();
+    for source in sources {
+        let kind = match source {
+            CustomSource::Cask => "--cask",
+            _ => "--formula",
+        };
+        let Some(found) = brew(&["search", kind, term]) else {
+            continue;
+        };
+        let mut names: Vec<&str> = found
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("==>"))
+            .collect();
+        // An exact match first, then the closest by length
+        names.sort_by_key(|name| (*name != term, name.len()));
+        names.truncate(MAX_SUGGESTIONS);
+        if names.is_empty() {
+            continue;
+        }
+
+        let mut args = vec!["info", "--json=v2", kind];
+        args.extend(&names);
+        let descriptions = brew(&args)
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .unwrap_or_default();
+        let described = |name: &str| {
+            ["formulae", "casks"]
+                .iter()
+                .filter_map(|key| descriptions[key].as_array())
+                .flatten()
+                .find(|info| info["name"] == name || info["token"] == name)
+                .and_then(|info| info["desc"].as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        suggestions.extend(names.into_iter().map(|name| Suggestion {
+            package: CustomPackage {
+                source: *source,
+                name: name.to_string(),
+            },
+            description: described(name),
+        }));
+    }
+    suggestions.sort_by_key(|s| (s.package.name != term, s.package.name.len()));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// `name` with the characters brew's regex search treats specially escaped
+fn escape_regex(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        if ".+".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::find_app;
+
+    #[test]
+    fn entries_name_a_source_and_a_package() {
+        let bacon = CustomPackage::parse(" cargo:bacon ").unwrap();
+        assert_eq!(bacon.source, CustomSource::Cargo);
+        assert_eq!(bacon.to_string(), "cargo:bacon");
+        assert_eq!(
+            CustomPackage::parse("tldr").unwrap().source,
+            CustomSource::Brew
+        );
+        assert_eq!(
+            CustomPackage::parse("npm:@scope/pkg").unwrap().name,
+            "@scope/pkg"
+        );
+        assert!(CustomPackage::parse("pip:httpie").is_err());
+        assert!(CustomPackage::parse("brew:").is_err());
+        assert!(CustomPackage::parse("brew:rg; rm -rf ~").is_err());
+        assert!(CustomPackage::parse("cargo:--force").is_err());
+    }
+
+    #[test]
+    fn a_custom_package_becomes_an_app_once() {
+        let package = CustomPackage::parse("cargo:bacon").unwrap();
+        let app = package.app();
+        assert!(std::ptr::eq(app, package.app()));
+        assert!(matches!(app.method(), InstallMethod::Cargo("bacon")));
+        assert!(std::ptr::eq(find_app("cargo:bacon").unwrap(), app));
+        assert_eq!(app.id.app().name, "bacon");
+        assert!(package.in_catalog().is_none());
+        let ripgrep = CustomPackage::parse("ripgrep").unwrap().in_catalog();
+        assert_eq!(ripgrep.map(|app| app.id.as_str()), Some("ripgrep"));
+    }
+
+    #[test]
+    fn brew_packages_are_searched_for() {
+        let searched = std::cell::RefCell::new(Vec::new());
+        let only_tldr = |args: &[&str]| {
+            searched.borrow_mut().push(args.join(" "));
+            Some(args[1..] == ["--formula", "/^tldr$/"])
+        };
+        let check = |entry: &str| check_with(&CustomPackage::parse(entry).unwrap(), &only_tldr);
+
+        assert!(check("tldr").is_ok());
+        assert_eq!(
+            check("cask:tldr").unwrap_err(),
+            "brew search found no cask called tldr"
+        );
+        assert!(check("cargo:bacon").is_ok());
+        assert!(check("owner/tap/tool").is_ok());
+        assert!(check("c.tool").is_err());
+        assert_eq!(
+            searched.into_inner(),
+            [
+                "search --formula /^tldr$/",
+                "search --cask /^tldr$/",
+                "search --formula /^c\\.tool$/",
+            ]
+        );
+        let no_brew = |_: &[&str]| None;
+        assert!(check_with(&CustomPackage::parse("tldr").unwrap(), &no_brew)
+            .unwrap()
+            .contains("brew isn't installed yet"));
+    }
+
+    #[test]
+    fn suggestions_come_from_brew_search_and_info() {
+        let brew = |args: &[&str]| {
+            match args {
+            ["search", "--formula", "rip"] => Some("==> Formulae\nripgrep-all\nripgrep\n".into()),
+            ["search", "--cask", "rip"] => Some("ripcord\n".into()),
+            ["info", "--json=v2", "--formula", "ripgrep", "ripgrep-all"] => Some(
+                r#"{"formulae": [{"name": "ripgrep", "desc": "Search tool like grep"}], "casks": []}"#
+                    .into(),
+            ),
+            _ => None,
+        }
+        };
+
+        let found = suggest_with("rip", &brew);
+        let names: Vec<String> = found.iter().map(|s| s.package.to_string()).collect();
+        assert_eq!(names, ["brew:ripgrep", "cask:ripcord", "brew:ripgrep-all"]);
+        assert_eq!(found[0].description, "Search tool like grep");
+        assert_eq!(found[1].description, "");
+
+        let casks: Vec<String> = suggest_with("cask:rip", &brew)
+            .iter()
+            .map(|s| s.package.to_string())
+            .collect();
+        assert_eq!(casks, ["cask:ripcord"]);
+        assert!(suggest_with("cargo:rip", &brew).is_empty());
+        assert!(suggest_with("r", &brew).is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+//! Devcontainer export
+//! Writes a devcontainer.json and Dockerfile that install the selected CLI
+//! tools, so Codespaces and devcontainers get the same toolbox.
+
+use std::fs;
+use std::path::Path;
+
+use crate::catalog::{find_app, App, Category, InstallMethod, CATALOG};
+use crate::system::Os;
+use crate::wizard::{ShellChoice, WizardState};
+
+/// Base image: Ubuntu with a `vscode` user that has passwordless sudo
+const BASE_IMAGE: &str = "mcr.microsoft.com/devcontainers/base:ubuntu";
+
+/// What an export wrote and what it left out
+#[derive(Debug)]
+pub struct Export {
+    pub tools: usize,
+    pub skipped: Vec<&'static str>,
+}
+
+/// Write `.devcontainer/` under `dir`, backing up files already there
+pub fn export(wizard: &WizardState, dir: &Path) -> Result<Export, String> {
+    let target = dir.join(".devcontainer");
+    fs::create_dir_all(&target)
+        .map_err(|e| format!("Could not create {}: {}", target.display(), e))?;
+
+    let files = [
+        (
+            target.join("devcontainer.json"),
+            generate_devcontainer_json(wizard),
+        ),
+        (target.join("Dockerfile"), generate_dockerfile(wizard)),
+    ];
+    for (path, content) in &files {
+        if path.exists() {
+            let _ = fs::copy(path, path.with_extension("load-backup"));
+        }
+        fs::write(path, content)
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    }
+
+    let (tools, skipped) = container_apps(wizard);
+    Ok(Export {
+        tools: tools.len(),
+        skipped: skipped.iter().map(|a| a.name).collect(),
+    })
+}
+
+/// The Linux install method, whatever this binary was built for
+fn linux_method(app: &App) -> &InstallMethod {
+    app.install_method.for_os(Os::Linux)
+}
+
+/// Apps to install in the container, and the ones that can't go in one:
+/// terminals, GUI apps, and macOS casks
+fn container_apps(wizard: &WizardState) -> (Vec<&'static App>, Vec<&'static App>) {
+    let mut wanted: Vec<&'static App> = CATALOG
+        .iter()
+        .filter(|a| wizard.installs(a.id.as_str()))
+        .collect();
+
+    // Toolchains the container needs even when they're installed locally
+    for app in wanted.clone() {
+        if let Some(toolchain) = linux_method(app).toolchain() {
+            if let Some(provider) = find_app(toolchain.provider) {
+                if !wanted.iter().any(|a| a.id == provider.id) {
+                    wanted.push(provider);
+                }
+            }
+        }
+    }
+
+    wanted.into_iter().partition(|app| {
+        app.category != Category::Terminal
+            && !app.tags.contains(&"gui")
+            && !matches!(linux_method(app), InstallMethod::BrewCask(_))
+    })
+}
+
+fn generate_dockerfile(wizard: &WizardState) -> String {
+    let (apps, skipped) = container_apps(wizard);
+    let of = |pick: fn(&InstallMethod) -> Option<&'static str>| -> Vec<&'static str> {
+        apps.iter().filter_map(|a| pick(linux_method(a))).collect()
+    };
+
+    let mut file = format!(
+        r#"# Generated by LOAD"*",8,1
+# https://github.com/oddurs/loadstar
+
+FROM {}
+"#,
+        BASE_IMAGE
+    );
+    if !skipped.is_empty() {
+        let names: Vec<&str> = skipped.iter().map(|a| a.name).collect();
+        file.push_str(&format!("# Not for containers: {}\n", names.join(", ")));
+    }
+
+    let apt = of(|m| match m {
+        InstallMethod::Apt(pkg) => Some(*pkg),
+        _ => None,
+    });
+    if !apt.is_empty() {
+        file.push_str(&format!(
+            "\nRUN apt-get update \\\n    && apt-get install -y --no-install-recommends {} \\\n    && rm -rf /var/lib/apt/lists/*\n",
+            apt.join(" ")
+        ));
+    }
+
+    file.push_str(
+        r#"
+USER vscode
+ENV PATH="/home/linuxbrew/.linuxbrew/bin:/home/linuxbrew/.linuxbrew/sbin:/home/vscode/.cargo/bin:/home/vscode/go/bin:/home/vscode/.local/bin:${PATH}"
+RUN NONINTERACTIVE=1 /bin/bash -c "$(curl -fsSL https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh)"
+"#,
+    );
+
+    let brew = of(|m| match m {
+        InstallMethod::Brew(pkg) => Some(*pkg),
+        _ => None,
+    });
+    if !brew.is_empty() {
+        file.push_str(&run_list("brew install", &brew));
+    }
+
+    // Scripts run unattended, so they get -y (rustup waits for a prompt
+    // otherwise)
+    for url in of(|m| match m {
+        InstallMethod::Script(url) => Some(*url),
+        _ => None,
+    }) {
+        file.push_str(&format!("RUN curl -fsSL {} | sh -s -- -y\n", url));
+    }
+    for cmd in of(|m| match m {
+        InstallMethod::Manual(cmd) => Some(*cmd),
+        _ => None,
+    }) {
+        file.push_str(&format!("RUN {}\n", cmd));
+    }
+
+    let cargo = of(|m| match m {
+        InstallMethod::Cargo(pkg) => Some(*pkg),
+        _ => None,
+    });
+    if !cargo.is_empty() {
+        file.push_str(&run_list("cargo install", &cargo));
+    }
+    let go = of(|m| match m {
+        InstallMethod::Go(pkg) => Some(*pkg),
+        _ => None,
+    });
+    if !go.is_empty() {
+        file.push_str(&run_list("go install", &go));
+    }
+    let npm = of(|m| match m {
+        InstallMethod::Npm(pkg) => Some(*pkg),
+        _ => None,
+    });
+    if !npm.is_empty() {
+        file.push_str(&run_list("npm install -g", &npm));
+    }
+    // uv installs one tool per call
+    for pkg in of(|m| match m {
+        InstallMethod::Pip(pkg) => Some(*pkg),
+        _ => None,
+    }) {
+        file.push_str(&format!("RUN uv tool install {}\n", pkg));
+    }
+
+    file
+}
+
+/// One RUN line per command, one package per continuation line
+fn run_list(command: &str, packages: &[&str]) -> String {
+    let mut run = format!("RUN {}", command);
+    for pkg in packages {
+        run.push_str(&format!(" \\\n    {}", pkg));
+    }
+    run.push('\n');
+    run
+}
+
+fn generate_devcontainer_json(wizard: &WizardState) -> String {
+    // VS Code's built-in terminal profiles; Nushell doesn't have one
+    let shell = match wizard.shell_config.shell {
+        ShellChoice::Zsh => Some("zsh"),
+        ShellChoice::Bash => Some("bash"),
+        ShellChoice::Fish => Some("fish"),
+        ShellChoice::Nushell => None,
+    };
+    let settings = match shell {
+        Some(shell) => format!(
+            r#"
+      "settings": {{
+        "terminal.integrated.defaultProfile.linux": "{}"
+      }}"#,
+            shell
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        r#"{{
+  "name": "loadstar",
+  "build": {{
+    "dockerfile": "Dockerfile"
+  }},
+  "remoteUser": "vscode",
+  "customizations": {{
+    "vscode": {{{}
+    }}
+  }}
+}}
+"#,
+        settings
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wizard_with(apps: &[&str]) -> WizardState {
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = apps.iter().map(|s| s.to_string()).collect();
+        wizard
+    }
+
+    #[test]
+    fn dockerfile_groups_tools_and_skips_gui_apps() {
+        let wizard = wizard_with(&["ripgrep", "bat", "ruff", "kitty", "vscode"]);
+        let dockerfile = generate_dockerfile(&wizard);
+
+        assert!(dockerfile.starts_with("# Generated by LOAD"));
+        assert!(dockerfile.contains(&format!("FROM {}", BASE_IMAGE)));
+        assert!(dockerfile.contains("RUN brew install \\\n"));
+        assert!(dockerfile.contains("    ripgrep"));
+        // ruff needs uv, which comes along
+        assert!(dockerfile.contains("    uv"));
+        assert!(dockerfile.contains("RUN uv tool install ruff\n"));
+        assert!(dockerfile.contains("# Not for containers: "));
+        assert!(!dockerfile.contains("brew install --cask"));
+    }
+
+    #[test]
+    fn devcontainer_json_sets_the_terminal_shell() {
+        let mut wizard = wizard_with(&[]);
+        let json = generate_devcontainer_json(&wizard);
+        assert!(json.contains("\"terminal.integrated.defaultProfile.linux\": \"zsh\""));
+        assert!(json.contains("\"dockerfile\": \"Dockerfile\""));
+
+        wizard.shell_config.shell = ShellChoice::Nushell;
+        assert!(!generate_devcontainer_json(&wizard).contains("defaultProfile"));
+    }
+}
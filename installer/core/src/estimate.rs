@@ -0,0 +1,144 @@
+//! Install time estimates
+//! How long each package should take: its time in earlier runs' event
+//! logs, or a guess from how it installs, scaled by how past guesses held
+//! up on this machine. The progress bar is weighted by them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+use crate::catalog::{App, InstallMethod};
+use crate::events::{self, Event};
+use crate::executor::InstallMessage;
+use crate::stats;
+use crate::system::SystemInfo;
+
+/// First-run guesses for apps much bigger than their install method
+/// suggests, in seconds
+const HEAVY: &[(&str, u64)] = &[
+    ("docker", 360),
+    ("ollama", 180),
+    ("gcloud", 180),
+    ("azure-cli", 120),
+    ("vscode", 120),
+    ("postgresql", 90),
+];
+
+/// Expected install times, by app name as the install messages carry it
+#[derive(Debug, Clone, Default)]
+pub struct Estimates {
+    past: HashMap<String, Duration>,
+    /// Guesses are multiplied by this; `None` until a run has checked one
+    pace: Option<f64>,
+}
+
+impl Estimates {
+    /// Read every recorded run. Unreadable files and lines are skipped.
+    pub fn load(system: &SystemInfo) -> Self {
+        let pace = stats::pace(&stats::history(system));
+        let Ok(entries) = fs::read_dir(events::runs_dir(system)) else {
+            return Self {
+                pace,
+                ..Self::default()
+            };
+        };
+        // Run files are named by start time, so sorting puts them in order
+        let mut paths: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+        let events = paths
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .flat_map(|text| {
+                text.lines()
+                    .filter_map(|line| serde_json::from_str::<Event>(line).ok())
+                    .collect::<Vec<_>>()
+            });
+        Self {
+            pace,
+            ..Self::from_events(events)
+        }
+    }
+
+    /// Estimates from events, oldest first; a later time replaces an
+    /// earlier one. Packages that were only skipped or failed don't count.
+    pub fn from_events(events: impl IntoIterator<Item = Event>) -> Self {
+        let mut past = HashMap::new();
+        for event in events {
+            if let InstallMessage::PackageSuccess { name, duration_ms } = event.message {
+                past.insert(name, Duration::from_millis(duration_ms));
+            }
+        }
+        Self { past, pace: None }
+    }
+
+    /// How long `app` should take to install
+    pub fn expected(&self, app: &App) -> Duration {
+        self.past
+            .get(app.name)
+            .copied()
+            .unwrap_or_else(|| guess(app).mul_f64(self.pace.unwrap_or(1.0)))
+    }
+
+    /// Whether `app`'s estimate is a guess, with no earlier time behind it
+    pub fn is_guess(&self, app: &App) -> bool {
+        !self.past.contains_key(app.name)
+    }
+}
+
+/// A first-run guess from the install method
+pub fn guess(app: &App) -> Duration {
+    if let Some((_, secs)) = HEAVY.iter().find(|(id, _)| *id == app.id.as_str()) {
+        return Duration::from_secs(*secs);
+    }
+    let secs = match app.method() {
+        InstallMethod::Brew(_) | InstallMethod::Apt(_) => 20,
+        InstallMethod::BrewCask(_) | InstallMethod::Script(_) => 60,
+        // Compiled from source
+        InstallMethod::Cargo(_) => 120,
+        InstallMethod::Go(_) => 45,
+        InstallMethod::Npm(_) | InstallMethod::Pip(_) => 15,
+        InstallMethod::Manual(_) | InstallMethod::PerOs { .. } => 5,
+    };
+    Duration::from_secs(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::find_app;
+
+    #[test]
+    fn past_runs_beat_guesses() {
+        let success = |name: &str, duration_ms| {
+            Event::now(InstallMessage::PackageSuccess {
+                name: name.to_string(),
+                duration_ms,
+            })
+        };
+        let estimates = Estimates::from_events([
+            success("Ripgrep", 9_000),
+            success("Ripgrep", 4_000),
+            Event::now(InstallMessage::PackageFailed {
+                name: "Bat".to_string(),
+                error: "exit 1".to_string(),
+            }),
+        ]);
+
+        let ripgrep = find_app("ripgrep").unwrap();
+        assert_eq!(estimates.expected(ripgrep), Duration::from_secs(4));
+        let bat = find_app("bat").unwrap();
+        assert_eq!(estimates.expected(bat), guess(bat));
+        assert!(estimates.is_guess(bat) && !estimates.is_guess(ripgrep));
+        // A machine where guesses ran long has its guesses stretched, and
+        // only its guesses
+        let slow = Estimates {
+            pace: Some(1.5),
+            ..estimates.clone()
+        };
+        assert_eq!(slow.expected(bat), guess(bat).mul_f64(1.5));
+        assert_eq!(slow.expected(ripgrep), Duration::from_secs(4));
+        // The Docker cask dwarfs a formula
+        let docker = find_app("docker").unwrap();
+        assert!(guess(docker) > guess(bat) * 10);
+    }
+}
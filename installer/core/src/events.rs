@@ -0,0 +1,295 @@
+//! Install event log
+//! Every `InstallMessage` of a run, appended to a JSONL file as it happens.
+//! Resume, rollback, reports and replay read this instead of the log text.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::executor::InstallMessage;
+use crate::system::SystemInfo;
+
+/// One line of the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    /// Milliseconds since the Unix epoch
+    pub ts_ms: u64,
+    #[serde(flatten)]
+    pub message: InstallMessage,
+}
+
+impl Event {
+    pub fn now(message: InstallMessage) -> Self {
+        Self {
+            ts_ms: now_ms(),
+            message,
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Where runs are recorded, one file each
+pub fn runs_dir(system: &SystemInfo) -> PathBuf {
+    system.config_dir.join("loadstar").join("runs")
+}
+
+/// Records messages on their way from the install thread to the UI
+pub struct Recorder {
+    handle: JoinHandle<()>,
+}
+
+impl Recorder {
+    /// Start recording a new run. Returns the sender the install steps
+    /// use; everything sent on it is written out, then passed to `out`.
+    pub fn start(
+        system: &SystemInfo,
+        out: mpsc::Sender<InstallMessage>,
+    ) -> (mpsc::Sender<InstallMessage>, Self) {
+        Self::start_at(&new_run_path(system), out)
+    }
+
+    /// Record to `path`, chosen ahead of time (a detached job's log,
+    /// which whoever started it is already following)
+    pub fn start_at(
+        path: &Path,
+        out: mpsc::Sender<InstallMessage>,
+    ) -> (mpsc::Sender<InstallMessage>, Self) {
+        let (tx, rx) = mpsc::channel::<InstallMessage>();
+
+        let mut file = match create_run_file(path) {
+            Ok(file) => {
+                let _ = out.send(InstallMessage::Log(format!(
+                    "[EVENTS] Recording to {}",
+                    path.display()
+                )));
+                Some(file)
+            }
+            Err(e) => {
+                let _ = out.send(InstallMessage::Warning(format!(
+                    "Not recording this run's events: {}",
+                    e
+                )));
+                None
+            }
+        };
+
+        let handle = std::thread::spawn(move || {
+            for message in rx {
+                if let Some(f) = &mut file {
+                    // A full disk shouldn't stop the install; stop recording
+                    if append(f, &Event::now(message.clone())).is_err() {
+                        file = None;
+                    }
+                }
+                let _ = out.send(message);
+            }
+        });
+
+        (tx, Self { handle })
+    }
+
+    /// Wait until every message is written and forwarded. Drop all the
+    /// senders first, or this waits forever.
+    pub fn finish(self) {
+        let _ = self.handle.join();
+    }
+}
+
+// ─── Phase timing ────────────────────────────────────────────────────
+
+/// How long each phase of a run took, timed from `PhaseStart` messages as
+/// they arrive. A phase ends when the next starts or the run finishes; one
+/// that runs twice ("Additional Tools" in background mode) is added up.
+#[derive(Debug, Clone)]
+pub struct PhaseTimes {
+    started: Instant,
+    current: Option<(String, Instant)>,
+    /// Phases in the order they first started
+    pub phases: Vec<(String, Duration)>,
+    /// The whole run, once it's finished
+    pub total: Option<Duration>,
+}
+
+impl Default for PhaseTimes {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            current: None,
+            phases: Vec::new(),
+            total: None,
+        }
+    }
+}
+
+impl PhaseTimes {
+    /// Time since the run started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn record(&mut self, message: &InstallMessage) {
+        if let InstallMessage::PhaseStart { phase } = message {
+            self.record_at(phase, Instant::now());
+        }
+    }
+
+    /// Start timing `phase`, closing the one before. For clocks that
+    /// aren't driven by install messages, like the wizard's screens.
+    pub fn enter(&mut self, phase: &str) {
+        self.record_at(phase, Instant::now());
+    }
+
+    fn record_at(&mut self, phase: &str, now: Instant) {
+        self.finish_at(now);
+        self.current = Some((phase.to_string(), now));
+    }
+
+    /// Close the phase still running and stop the clock
+    pub fn finish(&mut self) {
+        self.finish_at(Instant::now());
+        self.total = Some(self.elapsed());
+    }
+
+    fn finish_at(&mut self, now: Instant) {
+        let Some((phase, since)) = self.current.take() else {
+            return;
+        };
+        let took = now.saturating_duration_since(since);
+        match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += took,
+            None => self.phases.push((phase, took)),
+        }
+    }
+}
+
+/// A duration the way the log and summaries show it: 8s, 4m12s, 1h03m
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// A file name for a run starting now. Names sort in start order.
+pub fn new_run_path(system: &SystemInfo) -> PathBuf {
+    runs_dir(system).join(format!("{}.jsonl", now_ms()))
+}
+
+fn create_run_file(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// One event per line, flushed so a crash loses nothing already sent
+fn append(writer: &mut impl Write, event: &Event) -> io::Result<()> {
+    let line = serde_json::to_string(event).map_err(io::Error::other)?;
+    writeln!(writer, "{}", line)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_round_trip_one_per_line() {
+        let messages = [
+            InstallMessage::PhaseStart {
+                phase: "Homebrew Formulae".to_string(),
+            },
+            InstallMessage::PackageSuccess {
+                name: "ripgrep".to_string(),
+                duration_ms: 2500,
+            },
+            InstallMessage::Log("line with \"quotes\"\nand a newline".to_string()),
+            InstallMessage::FatalError("boom".to_string()),
+        ];
+
+        let mut buffer = Vec::new();
+        for message in &messages {
+            append(&mut buffer, &Event::now(message.clone())).unwrap();
+        }
+        let text = String::from_utf8(buffer).unwrap();
+        assert_eq!(text.lines().count(), messages.len());
+        assert!(text
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains(r#""event":"package_success","data":{"name":"ripgrep","duration_ms":2500}"#));
+
+        let read: Vec<InstallMessage> = text
+            .lines()
+            .map(|l| serde_json::from_str::<Event>(l).unwrap().message)
+            .collect();
+        assert_eq!(read, messages);
+    }
+
+    #[test]
+    fn phase_times_add_up_repeats_and_close_on_finish() {
+        let mut times = PhaseTimes::default();
+        let t0 = Instant::now();
+        times.record_at("Homebrew Bootstrap", t0);
+        times.record_at("Additional Tools", t0 + Duration::from_secs(20));
+        times.record_at("Homebrew Formulae", t0 + Duration::from_secs(23));
+        times.record_at("Additional Tools", t0 + Duration::from_secs(275));
+        times.finish_at(t0 + Duration::from_secs(277));
+        times.finish_at(t0 + Duration::from_secs(999));
+
+        let shown: Vec<(&str, String)> = times
+            .phases
+            .iter()
+            .map(|(name, took)| (name.as_str(), format_duration(*took)))
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                ("Homebrew Bootstrap", "20s".to_string()),
+                ("Additional Tools", "5s".to_string()),
+                ("Homebrew Formulae", "4m12s".to_string()),
+            ]
+        );
+        assert_eq!(format_duration(Duration::from_secs(3780)), "1h03m");
+    }
+
+    #[test]
+    fn recorder_forwards_everything_before_finishing() {
+        let mut system = SystemInfo::detect().unwrap();
+        let dir = std::env::temp_dir().join(format!("loadstar-events-{}", std::process::id()));
+        system.config_dir = dir.clone();
+
+        let (out, rx) = mpsc::channel();
+        let (tx, recorder) = Recorder::start(&system, out);
+        for i in 0..50 {
+            tx.send(InstallMessage::Log(format!("line {}", i))).unwrap();
+        }
+        drop(tx);
+        recorder.finish();
+
+        // The "Recording to" notice, then all 50
+        assert_eq!(rx.try_iter().count(), 51);
+        let run = fs::read_dir(runs_dir(&system))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert_eq!(fs::read_to_string(run).unwrap().lines().count(), 50);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
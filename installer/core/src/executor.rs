@@ -0,0 +1,2371 @@
+//! Package installation executor
+//! Runs actual install commands, captures output, handles errors and retries.
+//! Designed to be driven from an async task that streams results back to the TUI.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::catalog::{self, App, InstallMethod};
+use crate::events;
+use crate::failure::{self, FailureKind};
+use crate::rcblock;
+use crate::secret::Secret;
+use crate::system::{Os, SystemInfo};
+use crate::wizard::ShellChoice;
+
+/// Message sent from the install thread back to the TUI. Every one is
+/// also recorded to the run's event log (see `events`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum InstallMessage {
+    /// A new phase of installation is starting
+    PhaseStart { phase: String },
+    /// Starting to install a specific package, or a batch of them
+    PackageStart {
+        name: String,
+        method: String,
+        /// The most it may take before it's stopped
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// Package installed successfully
+    PackageSuccess { name: String, duration_ms: u64 },
+    /// Package was already installed
+    PackageSkipped { name: String, reason: String },
+    /// Package installation failed
+    PackageFailed { name: String, error: String },
+    /// A log line from command output
+    Log(String),
+    /// Something went wrong without failing a package: ssh-add didn't
+    /// work, gh isn't logged in. Counted, so it isn't lost in the log.
+    Warning(String),
+    /// Something the user has to finish after the install
+    NextStep { command: String, note: String },
+    /// A `git config --global` key was written. `old` is what it held
+    /// before (`None` if unset), kept in the run's event log.
+    GitConfigSet {
+        key: String,
+        old: Option<String>,
+        new: String,
+    },
+    /// A config file was written; `label` names it as the Review screen
+    /// does
+    ConfigWritten { label: String, path: PathBuf },
+    /// Progress update (completed, total)
+    Progress { completed: usize, total: usize },
+    /// Installation complete
+    Done {
+        succeeded: usize,
+        failed: usize,
+        skipped: usize,
+    },
+    /// Fatal error — installation cannot continue
+    FatalError(String),
+}
+
+/// Tracks the result of the entire installation
+#[derive(Debug, Default)]
+pub struct InstallSummary {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<(String, String)>,  // (name, error)
+    pub skipped: Vec<(String, String)>, // (name, reason)
+    pub warnings: Vec<String>,
+    pub configs: Vec<(String, PathBuf)>, // (label, path)
+}
+
+impl InstallSummary {
+    /// Count a package result. The executor and the UI both count with
+    /// this, from the same messages, so their totals can't drift apart.
+    pub fn record(&mut self, message: &InstallMessage) {
+        match message {
+            InstallMessage::PackageSuccess { name, .. } => self.succeeded.push(name.clone()),
+            InstallMessage::PackageSkipped { name, reason } => {
+                self.skipped.push((name.clone(), reason.clone()))
+            }
+            InstallMessage::PackageFailed { name, error } => {
+                self.failed.push((name.clone(), error.clone()))
+            }
+            InstallMessage::Warning(warning) => self.warnings.push(warning.clone()),
+            InstallMessage::ConfigWritten { label, path } => {
+                self.configs.push((label.clone(), path.clone()))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// When and whether `brew update` runs before installing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrewUpdate {
+    /// Update first, then install (slowest, freshest)
+    #[default]
+    Full,
+    /// Never update; installs use whatever formulae are already tapped
+    Skip,
+    /// Update on a side thread while non-brew tools install
+    Background,
+}
+
+impl BrewUpdate {
+    pub fn all() -> &'static [BrewUpdate] {
+        &[BrewUpdate::Full, BrewUpdate::Skip, BrewUpdate::Background]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrewUpdate::Full => "full",
+            BrewUpdate::Skip => "skip",
+            BrewUpdate::Background => "background",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BrewUpdate> {
+        Self::all().iter().copied().find(|m| m.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            BrewUpdate::Full => "Update before installing",
+            BrewUpdate::Skip => "Skipped",
+            BrewUpdate::Background => "In parallel with other tools",
+        }
+    }
+}
+
+/// Knobs for a single installation run (from the profile and CLI)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallOptions {
+    pub brew_update: BrewUpdate,
+    /// Run `brew cleanup` once everything else is done
+    pub brew_cleanup: bool,
+    /// One `brew install` per phase instead of one per package
+    pub brew_batch: bool,
+    /// On Linux, leave Homebrew out: no bootstrap, and brew packages are
+    /// skipped for the distro's own packages to cover
+    pub native_packages: bool,
+    /// More tries for a package that failed on the network or on another
+    /// install's lock, each after a longer wait
+    pub network_retries: u32,
+    /// How long one package's install may run before it's stopped and
+    /// counted as failed; `None` waits as long as it takes
+    pub package_timeout: Option<Duration>,
+    /// Log each command as `[DRY] ...` instead of running it. What's
+    /// already installed is still looked up, so the list is what a real
+    /// run would do.
+    pub dry_run: bool,
+    /// Handed to `sudo -v` before anything runs, so the sudo calls later
+    /// in the run don't stop at a password prompt nobody can see
+    #[serde(skip)]
+    pub sudo_password: Option<Secret>,
+}
+
+/// Network retries when the profile doesn't say, and the most it may say
+pub const NETWORK_RETRIES: u32 = 2;
+pub const MAX_NETWORK_RETRIES: u32 = 10;
+
+/// Seconds a package gets when the profile doesn't say
+pub const PACKAGE_TIMEOUT: u64 = 10 * 60;
+
+/// How often a command with a time limit is checked on
+const WAIT_POLL: Duration = Duration::from_millis(100);
+
+/// Set on every brew invocation. Auto-update is handled by `BrewUpdate`,
+/// per-install cleanup is batched into one optional step at the end, and
+/// env hints are noise in the install log.
+pub(crate) const BREW_ENV: &[(&str, &str)] = &[
+    ("HOMEBREW_NO_AUTO_UPDATE", "1"),
+    ("HOMEBREW_NO_INSTALL_CLEANUP", "1"),
+    ("HOMEBREW_NO_ENV_HINTS", "1"),
+];
+
+/// Run the full installation sequence.
+/// This is designed to run on a background thread.
+/// It sends progress messages through the channel.
+pub fn run_install(
+    system: &SystemInfo,
+    apps: Vec<&'static App>,
+    options: &InstallOptions,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> InstallSummary {
+    let mut summary = InstallSummary::default();
+    if options.dry_run {
+        let _ = tx.send(InstallMessage::Log(
+            "[DRY] Dry run: each command is shown, none of them run".to_string(),
+        ));
+    }
+
+    // ─── Toolchain prerequisites ─────────────────────────────────
+    // `cargo install` without cargo fails with "command not found"; queue
+    // the toolchain ahead of whatever needs it instead
+    let mut env = RunEnv::new(system);
+    env.dry_run = options.dry_run;
+    let (apps, providers) = catalog::with_toolchains(apps, |cmd| has_toolchain(cmd, system, &env));
+    for provider in &providers {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DEPS] Adding {} — a selected tool needs it and it is not installed",
+            provider.name
+        )));
+    }
+    let total = apps.len();
+
+    if let Some(password) = &options.sudo_password {
+        match prime_sudo(password) {
+            Ok(()) => {
+                let _ = tx.send(InstallMessage::Log(
+                    "[SUDO] Credentials cached for this run".to_string(),
+                ));
+            }
+            Err(e) => {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "{} — steps that need sudo may fail",
+                    e
+                )));
+            }
+        }
+    }
+
+    // ─── Phase 0: Command Line Tools ─────────────────────────────
+    // Homebrew's installer would ask for them with a GUI dialog that
+    // nobody sees from this thread, and wait on it forever
+    if system.os == Os::MacOS && !system.has_homebrew() && !system.has_xcode_clt() {
+        let _ = tx.send(InstallMessage::PhaseStart {
+            phase: "Xcode Command Line Tools".to_string(),
+        });
+        if let Err(e) = install_xcode_clt(system, &env, tx) {
+            let _ = tx.send(InstallMessage::FatalError(format!(
+                "Xcode Command Line Tools are needed for Homebrew: {}",
+                e
+            )));
+            return summary;
+        }
+    }
+
+    // ─── Phase 1: Homebrew bootstrap ─────────────────────────────
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Homebrew Bootstrap".to_string(),
+    });
+
+    let mut background_update = None;
+    let skip_brew = system.os == Os::Linux && options.native_packages;
+
+    if skip_brew {
+        let _ = tx.send(InstallMessage::Log(
+            "[BREW] native_packages is set — not using Linuxbrew".to_string(),
+        ));
+    } else if !system.has_homebrew() {
+        let _ = tx.send(InstallMessage::Log(
+            "[BREW] Homebrew not found — installing...".to_string(),
+        ));
+        match bootstrap_homebrew(system, &mut env, tx) {
+            Ok(()) if options.dry_run => {}
+            Ok(()) => {
+                let _ = tx.send(InstallMessage::Log(
+                    "[BREW] Homebrew installed successfully".to_string(),
+                ));
+            }
+            Err(e) => {
+                let _ = tx.send(InstallMessage::FatalError(format!(
+                    "Failed to install Homebrew: {}",
+                    e
+                )));
+                return summary;
+            }
+        }
+    } else {
+        match options.brew_update {
+            BrewUpdate::Full => {
+                let _ = tx.send(InstallMessage::Log(
+                    "[BREW] Homebrew found — updating...".to_string(),
+                ));
+                let _ = run_brew(&env, &["update"], tx);
+            }
+            BrewUpdate::Skip => {
+                let _ = tx.send(InstallMessage::Log(
+                    "[BREW] Homebrew found — skipping brew update".to_string(),
+                ));
+            }
+            BrewUpdate::Background => {
+                let _ = tx.send(InstallMessage::Log(
+                    "[BREW] Homebrew found — updating in the background...".to_string(),
+                ));
+                let update_tx = tx.clone();
+                let update_env = env.clone();
+                background_update = Some(std::thread::spawn(move || {
+                    let _ = run_brew(&update_env, &["update"], &update_tx);
+                }));
+            }
+        }
+    }
+
+    // ─── Phase 2: Batch brew installs ────────────────────────────
+    let mut plan = Plan::new(&apps, |cmd| has_toolchain(cmd, system, &env));
+
+    let mut progress = Progress {
+        completed: 0,
+        total,
+    };
+
+    if skip_brew {
+        for app in plan.formulae.drain(..).chain(plan.casks.drain(..)) {
+            report(
+                InstallMessage::PackageSkipped {
+                    name: app.name.to_string(),
+                    reason: "Homebrew package, and native_packages is set".to_string(),
+                },
+                tx,
+                &mut summary,
+            );
+            progress.advance(tx);
+        }
+    }
+
+    // With a background update running, do the non-brew tools first and
+    // only wait for the update once brew is actually needed
+    if let Some(handle) = background_update {
+        install_other(
+            &plan.ready,
+            options,
+            system,
+            &mut env,
+            tx,
+            &mut summary,
+            &mut progress,
+        );
+        let _ = tx.send(InstallMessage::Log(
+            "[BREW] Waiting for brew update to finish...".to_string(),
+        ));
+        let _ = handle.join();
+        install_formulae(
+            &plan.formulae,
+            options,
+            &env,
+            tx,
+            &mut summary,
+            &mut progress,
+        );
+        install_casks(&plan.casks, options, &env, tx, &mut summary, &mut progress);
+        install_other(
+            &plan.waiting,
+            options,
+            system,
+            &mut env,
+            tx,
+            &mut summary,
+            &mut progress,
+        );
+    } else {
+        install_formulae(
+            &plan.formulae,
+            options,
+            &env,
+            tx,
+            &mut summary,
+            &mut progress,
+        );
+        install_casks(&plan.casks, options, &env, tx, &mut summary, &mut progress);
+        install_other(
+            &plan.other,
+            options,
+            system,
+            &mut env,
+            tx,
+            &mut summary,
+            &mut progress,
+        );
+    }
+
+    // ─── Phase 3: Cleanup (optional) ─────────────────────────────
+    // Not a package: a failure here is reported but never counted
+    if options.brew_cleanup {
+        let _ = tx.send(InstallMessage::PhaseStart {
+            phase: "Homebrew Cleanup".to_string(),
+        });
+        match run_brew(&env, &["cleanup"], tx) {
+            Ok(()) if options.dry_run => {}
+            Ok(()) => {
+                let _ = tx.send(InstallMessage::Log(
+                    "[CLEAN] Old versions and caches removed".to_string(),
+                ));
+            }
+            Err(e) => {
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "[CLEAN] brew cleanup failed (install unaffected): {}",
+                    e
+                )));
+            }
+        }
+    }
+
+    let _ = tx.send(InstallMessage::Done {
+        succeeded: summary.succeeded.len(),
+        failed: summary.failed.len(),
+        skipped: summary.skipped.len(),
+    });
+
+    summary
+}
+
+/// `brew upgrade` the apps Homebrew installed. Casks in `greedy` are
+/// upgraded with `--greedy`, as brew leaves casks that update themselves
+/// alone otherwise.
+pub fn run_upgrade(
+    apps: &[&'static App],
+    greedy: &[&'static App],
+    tx: &mpsc::Sender<InstallMessage>,
+) -> InstallSummary {
+    let mut summary = InstallSummary::default();
+    let inventory = BrewInventory::detect();
+    let env = RunEnv::from_process();
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Homebrew Upgrade".to_string(),
+    });
+    let _ = run_brew(&env, &["update"], tx);
+
+    let formulae: Vec<&App> = apps
+        .iter()
+        .copied()
+        .filter(|a| matches!(a.method(), InstallMethod::Brew(_)) && inventory.has(a) == Some(true))
+        .collect();
+    let (greedy_casks, casks): (Vec<&App>, Vec<&App>) = apps
+        .iter()
+        .copied()
+        .filter(|a| {
+            matches!(a.method(), InstallMethod::BrewCask(_)) && inventory.has(a) == Some(true)
+        })
+        .partition(|a| greedy.iter().any(|g| g.id == a.id));
+    let elsewhere = apps.iter().filter(|a| inventory.has(a).is_none()).count();
+    if elsewhere > 0 {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[UPGRADE] {} tools don't come from Homebrew; upgrade those with their own installers",
+            elsewhere
+        )));
+    }
+
+    let mut progress = Progress {
+        completed: 0,
+        total: formulae.len() + casks.len() + greedy_casks.len(),
+    };
+    let groups = [
+        (formulae, &[][..]),
+        (casks, &["--cask"][..]),
+        (greedy_casks, &["--cask", "--greedy"][..]),
+    ];
+    for (group, flags) in groups {
+        if group.is_empty() {
+            continue;
+        }
+        let start = Instant::now();
+        let mut args = vec!["upgrade"];
+        args.extend(flags);
+        for app in &group {
+            if let InstallMethod::Brew(pkg) | InstallMethod::BrewCask(pkg) = app.method() {
+                args.push(pkg);
+            }
+        }
+        let result = run_brew(&env, &args, tx);
+        for app in group {
+            record_result(app, result.clone(), start, false, tx, &mut summary);
+            progress.advance(tx);
+        }
+    }
+
+    let _ = tx.send(InstallMessage::Done {
+        succeeded: summary.succeeded.len(),
+        failed: summary.failed.len(),
+        skipped: summary.skipped.len(),
+    });
+
+    summary
+}
+
+/// Apps split into the phases they install in
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub formulae: Vec<&'static App>,
+    pub casks: Vec<&'static App>,
+    /// Everything not from brew, in install order
+    pub other: Vec<&'static App>,
+    /// The part of `other` whose toolchain is already there
+    pub ready: Vec<&'static App>,
+    /// The part of `other` waiting on a toolchain this run installs
+    pub waiting: Vec<&'static App>,
+}
+
+impl Plan {
+    /// `has_toolchain` says whether a toolchain command is available
+    /// before anything is installed
+    pub fn new(apps: &[&'static App], has_toolchain: impl Fn(&str) -> bool) -> Self {
+        let mut plan = Plan::default();
+        for app in apps {
+            match app.method() {
+                InstallMethod::Brew(_) => plan.formulae.push(app),
+                InstallMethod::BrewCask(_) => plan.casks.push(app),
+                _ => plan.other.push(app),
+            }
+        }
+
+        // Tools whose toolchain is being installed in this run (node and go
+        // come from brew) cannot go before the brew phases
+        (plan.ready, plan.waiting) = plan.other.iter().partition(|a| {
+            a.method()
+                .toolchain()
+                .is_none_or(|t| has_toolchain(t.command))
+        });
+        plan
+    }
+}
+
+/// Running count shared by the install phases
+struct Progress {
+    completed: usize,
+    total: usize,
+}
+
+impl Progress {
+    fn advance(&mut self, tx: &mpsc::Sender<InstallMessage>) {
+        self.completed += 1;
+        let _ = tx.send(InstallMessage::Progress {
+            completed: self.completed,
+            total: self.total,
+        });
+    }
+}
+
+// ─── Download stage ──────────────────────────────────────────────────
+
+/// `brew fetch` for a phase's packages, one download at a time on a
+/// thread of its own, so the next package comes down while brew builds
+/// or installs the one before it. Installing a package waits for its
+/// download; a download that fails is left for `brew install` to retry.
+/// Each download is stopped at the package timeout, and installing waits
+/// no longer than its own.
+pub struct FetchStage {
+    fetched: mpsc::Receiver<&'static str>,
+    done: HashSet<&'static str>,
+}
+
+impl FetchStage {
+    /// Start downloading `pkgs` in order. Ones already installed are
+    /// passed over.
+    pub fn start(
+        pkgs: Vec<&'static str>,
+        cask: bool,
+        timeout: Option<Duration>,
+        env: &RunEnv,
+        tx: &mpsc::Sender<InstallMessage>,
+    ) -> Self {
+        let (done_tx, fetched) = mpsc::channel();
+        // Nothing to download ahead of; with the sender gone, `wait`
+        // never blocks
+        if env.dry_run {
+            return Self {
+                fetched,
+                done: HashSet::new(),
+            };
+        }
+        let env = env.clone();
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for pkg in pkgs {
+                let installed = if cask {
+                    is_brew_cask_installed(&env, pkg)
+                } else {
+                    is_brew_installed(&env, pkg)
+                };
+                if !installed {
+                    let limited = env.with_timeout(timeout, Instant::now());
+                    let line = match fetch(&limited, pkg, cask) {
+                        Ok(()) => format!("[FETCH] {} downloaded", pkg),
+                        Err(e) => format!(
+                            "[FETCH] {} didn't download ({}); installing fetches it",
+                            pkg, e
+                        ),
+                    };
+                    let _ = tx.send(InstallMessage::Log(line));
+                }
+                // Nobody's waiting any more: the phase is over
+                if done_tx.send(pkg).is_err() {
+                    return;
+                }
+            }
+        });
+        Self {
+            fetched,
+            done: HashSet::new(),
+        }
+    }
+
+    /// Block until `pkg`'s download is over, however it went, or until
+    /// `deadline`. `false` when it gave up waiting.
+    pub fn wait(&mut self, pkg: &str, deadline: Option<Instant>) -> bool {
+        while !self.done.contains(pkg) {
+            let next = match deadline {
+                Some(deadline) => self
+                    .fetched
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self
+                    .fetched
+                    .recv()
+                    .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            match next {
+                Ok(fetched) => {
+                    self.done.insert(fetched);
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return true,
+                Err(mpsc::RecvTimeoutError::Timeout) => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Download a package, and a formula's dependencies, into brew's cache
+/// without installing anything, stopped at `env`'s time limit. Its output
+/// isn't streamed: it would land in the middle of whatever is installing.
+pub fn fetch(env: &RunEnv, pkg: &str, cask: bool) -> Result<(), String> {
+    // With its dependencies in the cache too, installing a formula
+    // downloads nothing, so it never waits on a lock the next fetch holds
+    let kind = if cask { "--cask" } else { "--deps" };
+    let mut command = env.command("brew");
+    command
+        .args(["fetch", kind, pkg])
+        .envs(BREW_ENV.iter().copied());
+    let output = match env.limit {
+        Some(limit) => output_until(command, limit),
+        None => command.output(),
+    }
+    .map_err(|e| run_failed("'brew fetch'", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(failure::telling_line(&stderr)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("exited with code {}", output.status)))
+}
+
+// ─── Install phases ──────────────────────────────────────────────────
+
+fn install_formulae(
+    apps: &[&App],
+    options: &InstallOptions,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+    progress: &mut Progress,
+) {
+    if apps.is_empty() {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Homebrew Formulae".to_string(),
+    });
+
+    let single = batch_apart(apps, false, options, env, tx, summary, progress);
+    let pkgs = single
+        .iter()
+        .filter_map(|app| match app.method() {
+            InstallMethod::Brew(pkg) => Some(*pkg),
+            _ => None,
+        })
+        .collect();
+    let mut fetches = FetchStage::start(pkgs, false, options.package_timeout, env, tx);
+    for app in single {
+        let start = Instant::now();
+        let pkg = match app.method() {
+            InstallMethod::Brew(p) => p,
+            _ => unreachable!(),
+        };
+
+        let _ = tx.send(InstallMessage::PackageStart {
+            name: app.name.to_string(),
+            method: app.install_command(),
+            timeout_ms: millis(options.package_timeout),
+        });
+
+        // Check if already installed
+        if is_brew_installed(env, pkg) {
+            report(
+                InstallMessage::PackageSkipped {
+                    name: app.name.to_string(),
+                    reason: "Already installed".to_string(),
+                },
+                tx,
+                summary,
+            );
+        } else {
+            let app_env = app_env(app, env, tx).with_timeout(options.package_timeout, start);
+            // Past its time waiting on the download, the install fails
+            // as timed out
+            if !fetches.wait(pkg, app_env.deadline()) {
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "[FETCH] Gave up waiting on {}'s download at the package timeout",
+                    pkg
+                )));
+            }
+            let install = || run_brew(&app_env, &["install", pkg], tx);
+            let result = with_retries(app, options, tx, install);
+            record_result(app, result, start, options.dry_run, tx, summary);
+        }
+
+        progress.advance(tx);
+    }
+}
+
+fn install_casks(
+    apps: &[&App],
+    options: &InstallOptions,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+    progress: &mut Progress,
+) {
+    if apps.is_empty() {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Homebrew Casks".to_string(),
+    });
+
+    let single = batch_apart(apps, true, options, env, tx, summary, progress);
+    let pkgs = single
+        .iter()
+        .filter_map(|app| match app.method() {
+            InstallMethod::BrewCask(pkg) => Some(*pkg),
+            _ => None,
+        })
+        .collect();
+    let mut fetches = FetchStage::start(pkgs, true, options.package_timeout, env, tx);
+    for app in single {
+        let start = Instant::now();
+        let pkg = match app.method() {
+            InstallMethod::BrewCask(p) => p,
+            _ => unreachable!(),
+        };
+
+        let _ = tx.send(InstallMessage::PackageStart {
+            name: app.name.to_string(),
+            method: app.install_command(),
+            timeout_ms: millis(options.package_timeout),
+        });
+
+        if is_brew_cask_installed(env, pkg) {
+            report(
+                InstallMessage::PackageSkipped {
+                    name: app.name.to_string(),
+                    reason: "Already installed".to_string(),
+                },
+                tx,
+                summary,
+            );
+        } else {
+            let app_env = app_env(app, env, tx).with_timeout(options.package_timeout, start);
+            // Past its time waiting on the download, the install fails
+            // as timed out
+            if !fetches.wait(pkg, app_env.deadline()) {
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "[FETCH] Gave up waiting on {}'s download at the package timeout",
+                    pkg
+                )));
+            }
+            let install = || run_brew(&app_env, &["install", "--cask", pkg], tx);
+            let result = with_retries(app, options, tx, install);
+            record_result(app, result, start, options.dry_run, tx, summary);
+        }
+
+        progress.advance(tx);
+    }
+}
+
+fn install_other(
+    apps: &[&App],
+    options: &InstallOptions,
+    system: &SystemInfo,
+    env: &mut RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+    progress: &mut Progress,
+) {
+    if apps.is_empty() {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Additional Tools".to_string(),
+    });
+
+    for app in apps {
+        let start = Instant::now();
+
+        let _ = tx.send(InstallMessage::PackageStart {
+            name: app.name.to_string(),
+            method: app.install_command(),
+            timeout_ms: millis(options.package_timeout),
+        });
+
+        let app_env = app_env(app, env, tx).with_timeout(options.package_timeout, start);
+        let install = || install_app(app, system, &app_env, tx);
+        let result = with_retries(app, options, tx, install);
+        record_result(app, result, start, options.dry_run, tx, summary);
+        // A toolchain or version manager this installed is for the tools
+        // after it to use
+        env.refresh(system, tx);
+
+        progress.advance(tx);
+    }
+}
+
+/// In fast mode, install the apps that can share one `brew install` and
+/// hand back the rest, whose own environment needs a command to
+/// themselves. Otherwise every app is handed back.
+fn batch_apart<'a>(
+    apps: &[&'a App],
+    cask: bool,
+    options: &InstallOptions,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+    progress: &mut Progress,
+) -> Vec<&'a App> {
+    if !options.brew_batch {
+        return apps.to_vec();
+    }
+    let (batched, single): (Vec<&App>, Vec<&App>) = apps.iter().partition(|app| app.env.is_empty());
+    if !batched.is_empty() {
+        install_brew_batch(&batched, cask, options, env, tx, summary, progress);
+    }
+    single
+}
+
+/// Fast mode: one `brew install` for the whole phase. Brew's startup cost
+/// is paid once; per-package results come from re-checking `brew list`
+/// afterwards, with the error taken from brew's output where it names the
+/// package.
+fn install_brew_batch(
+    apps: &[&App],
+    cask: bool,
+    options: &InstallOptions,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+    progress: &mut Progress,
+) {
+    let is_installed = |pkg: &str| {
+        if cask {
+            is_brew_cask_installed(env, pkg)
+        } else {
+            is_brew_installed(env, pkg)
+        }
+    };
+
+    let mut pending = Vec::new();
+    for app in apps {
+        let pkg = match app.method() {
+            InstallMethod::Brew(p) | InstallMethod::BrewCask(p) => p,
+            _ => unreachable!(),
+        };
+
+        if is_installed(pkg) {
+            report(
+                InstallMessage::PackageSkipped {
+                    name: app.name.to_string(),
+                    reason: "Already installed".to_string(),
+                },
+                tx,
+                summary,
+            );
+            progress.advance(tx);
+        } else {
+            pending.push((*app, pkg));
+        }
+    }
+
+    if pending.is_empty() {
+        return;
+    }
+
+    let mut args = vec!["install"];
+    if cask {
+        args.push("--cask");
+    }
+    args.extend(pending.iter().map(|(_, pkg)| *pkg));
+
+    // The whole phase gets every pending package's time
+    let timeout = options
+        .package_timeout
+        .map(|timeout| timeout * pending.len() as u32);
+    let start = Instant::now();
+    let _ = tx.send(InstallMessage::PackageStart {
+        name: format!(
+            "{} {}",
+            pending.len(),
+            if cask { "casks" } else { "formulae" }
+        ),
+        method: format!("brew {}", args.join(" ")),
+        timeout_ms: millis(timeout),
+    });
+    let batch_env = env.with_timeout(timeout, start);
+    let (stderr, timed_out) = match run_command_captured(&batch_env, "brew", &args, BREW_ENV, tx) {
+        Ok((_, stderr)) => (stderr, None),
+        Err(e) if FailureKind::classify(&e) == FailureKind::Timeout => (String::new(), Some(e)),
+        Err(e) => (e, None),
+    };
+
+    // A network error or lock in brew's output names the download or the
+    // keg, not always the package; every package it left out gets its
+    // retries on its own
+    let transient = failure::telling_line(&stderr)
+        .filter(|line| FailureKind::classify(line).transient().is_some());
+    for (app, pkg) in pending {
+        let result = if options.dry_run || is_installed(pkg) {
+            Ok(())
+        } else if let Some(e) = &timed_out {
+            Err(e.clone())
+        } else if let Some(line) = transient {
+            let mut args = vec!["install"];
+            if cask {
+                args.push("--cask");
+            }
+            args.push(pkg);
+            let install = || run_brew(env, &args, tx);
+            let first = Err(line.to_string());
+            retry_transient(
+                app,
+                first,
+                options.network_retries,
+                RETRY_DELAY,
+                tx,
+                install,
+            )
+        } else {
+            Err(batch_error(pkg, &stderr))
+        };
+        record_result(app, result, start, options.dry_run, tx, summary);
+        progress.advance(tx);
+    }
+}
+
+/// The last `Error:` line in a batched run's output that mentions `pkg`
+fn batch_error(pkg: &str, output: &str) -> String {
+    output
+        .lines()
+        .rev()
+        .find(|l| l.contains("Error") && l.contains(pkg))
+        .map(|l| l.trim().to_string())
+        .unwrap_or_else(|| "Not installed after batched brew install".to_string())
+}
+
+/// How long the first retry waits; each one after waits twice as long as
+/// the one before, up to the most any one waits
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Install `app` with `install`, trying again on a transient failure as
+/// the options allow
+fn with_retries(
+    app: &App,
+    options: &InstallOptions,
+    tx: &mpsc::Sender<InstallMessage>,
+    mut install: impl FnMut() -> Result<(), String>,
+) -> Result<(), String> {
+    let first = install();
+    retry_transient(
+        app,
+        first,
+        options.network_retries,
+        RETRY_DELAY,
+        tx,
+        install,
+    )
+}
+
+/// Try `attempt` again while `result` is a transient failure (the
+/// network, another install's lock), up to
+/// `retries` more times, backing off from `delay`. Anything else, success
+/// included, is handed back as it is.
+fn retry_transient(
+    app: &App,
+    mut result: Result<(), String>,
+    retries: u32,
+    delay: Duration,
+    tx: &mpsc::Sender<InstallMessage>,
+    mut attempt: impl FnMut() -> Result<(), String>,
+) -> Result<(), String> {
+    let mut delay = delay;
+    for n in 1..=retries {
+        let Some(what) = result
+            .as_ref()
+            .err()
+            .and_then(|e| FailureKind::classify(e).transient())
+        else {
+            break;
+        };
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[RETRY] {} hit {}; retry {} of {} in {}s",
+            app.name,
+            what,
+            n,
+            retries,
+            delay.as_secs()
+        )));
+        std::thread::sleep(delay);
+        result = attempt();
+        delay = delay.saturating_mul(2).min(MAX_RETRY_DELAY);
+    }
+    result
+}
+
+/// Report a finished install attempt and add it to the summary. A dry
+/// run installed nothing, so its apps count as skipped.
+fn record_result(
+    app: &App,
+    result: Result<(), String>,
+    start: Instant,
+    dry_run: bool,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+) {
+    let message = match result {
+        Ok(()) if dry_run => InstallMessage::PackageSkipped {
+            name: app.name.to_string(),
+            reason: "Dry run".to_string(),
+        },
+        Ok(()) => InstallMessage::PackageSuccess {
+            name: app.name.to_string(),
+            duration_ms: start.elapsed().as_millis() as u64,
+        },
+        Err(error) => InstallMessage::PackageFailed {
+            name: app.name.to_string(),
+            error,
+        },
+    };
+    report(message, tx, summary);
+}
+
+/// Count a package result and send it on
+fn report(
+    message: InstallMessage,
+    tx: &mpsc::Sender<InstallMessage>,
+    summary: &mut InstallSummary,
+) {
+    summary.record(&message);
+    let _ = tx.send(message);
+}
+
+// ─── Command Line Tools ──────────────────────────────────────────────
+
+/// How often to look for the Command Line Tools while they install, and
+/// how long to wait for them. The download alone can take a while.
+const CLT_POLL: Duration = Duration::from_secs(5);
+const CLT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Open Apple's Command Line Tools installer and wait for it to finish
+fn install_xcode_clt(
+    system: &SystemInfo,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    if env.dry_run {
+        let _ = tx.send(InstallMessage::Log(
+            "[DRY] xcode-select --install".to_string(),
+        ));
+        return Ok(());
+    }
+    let _ = tx.send(InstallMessage::Log(
+        "[CLT] Command Line Tools not found — opening Apple's installer...".to_string(),
+    ));
+    Command::new("xcode-select")
+        .arg("--install")
+        .output()
+        .map_err(|e| format!("Failed to run xcode-select: {}", e))?;
+    let _ = tx.send(InstallMessage::Log(
+        "[CLT] Click Install in the dialog; Homebrew starts once they're in".to_string(),
+    ));
+    wait_for_clt(
+        || system.has_xcode_clt(),
+        std::thread::sleep,
+        CLT_POLL,
+        CLT_TIMEOUT,
+        tx,
+    )?;
+    let _ = tx.send(InstallMessage::Log(
+        "[CLT] Command Line Tools installed".to_string(),
+    ));
+    Ok(())
+}
+
+/// Poll `installed` every `interval` until it's true, logging once a
+/// minute so the Install screen shows the wait is still going
+fn wait_for_clt(
+    installed: impl Fn() -> bool,
+    sleep: impl Fn(Duration),
+    interval: Duration,
+    timeout: Duration,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    let mut waited = Duration::ZERO;
+    while !installed() {
+        if waited >= timeout {
+            return Err(format!(
+                "still not installed after {} minutes",
+                timeout.as_secs() / 60
+            ));
+        }
+        sleep(interval);
+        let minutes = |d: Duration| d.as_secs() / 60;
+        if minutes(waited + interval) > minutes(waited) {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[CLT] Waiting for the Command Line Tools install... {}m",
+                minutes(waited + interval)
+            )));
+        }
+        waited += interval;
+    }
+    Ok(())
+}
+
+// ─── Homebrew bootstrap ──────────────────────────────────────────────
+
+fn bootstrap_homebrew(
+    system: &SystemInfo,
+    env: &mut RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    if system.os == Os::Linux {
+        install_brew_build_deps(env, tx);
+    }
+
+    // Homebrew's installer is a bash script, but bash isn't always at
+    // /bin/bash (NixOS, Guix, some containers)
+    let bash = env
+        .find("bash")
+        .ok_or("Homebrew's installer needs bash, and it isn't installed")?;
+    // The official Homebrew install command
+    let install = "NONINTERACTIVE=1 bash -c \"$(curl -fsSL https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh)\"";
+    if env.dry_run {
+        let _ = tx.send(InstallMessage::Log(format!("[DRY] {}", install)));
+        return Ok(());
+    }
+    let _ = tx.send(InstallMessage::Log(
+        "[BREW] Downloading Homebrew installer...".to_string(),
+    ));
+
+    let output = env
+        .command(&bash)
+        .args(["-c", install])
+        .output()
+        .map_err(|e| format!("Failed to run installer: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Homebrew install failed: {}", stderr.trim()));
+    }
+
+    let brew = Path::new(system.brew_prefix()).join("bin").join("brew");
+    env.refresh(system, tx);
+    if system.os == Os::Linux {
+        // Linuxbrew's prefix isn't on the user's PATH either
+        add_brew_shellenv(system, &brew, tx);
+    }
+    match env.command("brew").arg("--version").output() {
+        Ok(output) if output.status.success() => Ok(()),
+        _ => Err(format!(
+            "the installer finished, but brew doesn't run from {}",
+            brew.display()
+        )),
+    }
+}
+
+/// The tools Homebrew needs to build on Linux
+const BREW_BUILD_TOOLS: &[&str] = &["gcc", "make", "curl", "file", "git"];
+
+/// What Homebrew needs to build on Linux, installed with the distro's
+/// package manager when any of it is missing. Everything here is only a
+/// warning: Homebrew's own installer says what it still lacks.
+fn install_brew_build_deps(env: &RunEnv, tx: &mpsc::Sender<InstallMessage>) {
+    let missing: Vec<&str> = BREW_BUILD_TOOLS
+        .iter()
+        .copied()
+        .filter(|tool| env.find(tool).is_none())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    let Some(commands) = brew_build_deps(|cmd| env.find(cmd).is_some()) else {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "No apt-get or dnf to install Homebrew's build tools; it needs {}",
+            missing.join(", ")
+        )));
+        return;
+    };
+    let _ = tx.send(InstallMessage::Log(format!(
+        "[BREW] Installing the build tools Homebrew needs on Linux ({} missing)...",
+        missing.join(", ")
+    )));
+    let root = is_root();
+    for args in commands {
+        let result = if root {
+            run_command(env, args[0], &args[1..], tx)
+        } else {
+            run_command(env, "sudo", &args, tx)
+        };
+        if let Err(e) = result {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not install Homebrew's build tools: {}",
+                e
+            )));
+            return;
+        }
+    }
+}
+
+/// The package manager commands that install Homebrew's Linux
+/// requirements, for whichever of apt-get and dnf is there. apt-get's
+/// package lists are refreshed first: fresh containers ship without them.
+fn brew_build_deps(has_tool: impl Fn(&str) -> bool) -> Option<Vec<Vec<&'static str>>> {
+    if has_tool("apt-get") {
+        Some(vec![
+            vec!["apt-get", "update"],
+            vec![
+                "apt-get",
+                "install",
+                "-y",
+                "build-essential",
+                "procps",
+                "curl",
+                "file",
+                "git",
+            ],
+        ])
+    } else if has_tool("dnf") {
+        Some(vec![vec![
+            "dnf",
+            "install",
+            "-y",
+            "gcc",
+            "gcc-c++",
+            "make",
+            "procps-ng",
+            "curl",
+            "file",
+            "git",
+        ]])
+    } else {
+        None
+    }
+}
+
+/// Whether loadstar runs as root, where sudo is unneeded and often not
+/// installed (containers)
+fn is_root() -> bool {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| effective_uid(&status))
+        == Some(0)
+}
+
+/// The effective uid from a `/proc/<pid>/status` file: the second field
+/// of its `Uid:` line
+fn effective_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|ids| ids.split_whitespace().nth(1))
+        .and_then(|uid| uid.parse().ok())
+}
+
+/// Load `brew shellenv` from the rc file of the login shell, so new
+/// shells find what brew installs
+fn add_brew_shellenv(system: &SystemInfo, brew: &Path, tx: &mpsc::Sender<InstallMessage>) {
+    let shell = match Path::new(&system.shell)
+        .file_name()
+        .and_then(|n| n.to_str())
+    {
+        Some("zsh") => ShellChoice::Zsh,
+        Some("fish") => ShellChoice::Fish,
+        Some("nu") => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "brew shellenv has no Nushell output; add {}/bin to PATH in env.nu",
+                system.brew_prefix()
+            )));
+            return;
+        }
+        _ => ShellChoice::Bash,
+    };
+    let rc = rcblock::rc_file(shell, system);
+    match rcblock::add_lines(&rc, &[shellenv_line(shell, brew)]) {
+        Ok(_) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[BREW] brew shellenv added to {}",
+                rc.display()
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not add brew shellenv to {}: {}",
+                rc.display(),
+                e
+            )));
+        }
+    }
+}
+
+fn shellenv_line(shell: ShellChoice, brew: &Path) -> String {
+    match shell {
+        ShellChoice::Fish => format!("{} shellenv | source", brew.display()),
+        _ => format!("eval \"$({} shellenv)\"", brew.display()),
+    }
+}
+
+// ─── Individual app installation ─────────────────────────────────────
+
+// ─── sudo ────────────────────────────────────────────────────────────
+
+/// Whether a run will call sudo: Homebrew's installer does when brew isn't
+/// there yet, and so do apt packages and manual commands that start with it
+pub fn needs_sudo(apps: &[&App], system: &SystemInfo) -> bool {
+    !system.has_homebrew()
+        || apps.iter().any(|app| match app.method() {
+            InstallMethod::Apt(_) => true,
+            InstallMethod::Manual(cmd) => cmd.starts_with("sudo "),
+            _ => false,
+        })
+}
+
+/// Whether sudo runs without asking, from a NOPASSWD rule or credentials
+/// cached by an earlier sudo. No sudo at all counts too: a password
+/// wouldn't help.
+pub fn sudo_ready() -> bool {
+    Command::new("sudo")
+        .args(["-n", "true"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
+
+/// Cache sudo credentials with `sudo -v`. The password goes in on stdin and
+/// never into an argument or a log line.
+fn prime_sudo(password: &Secret) -> Result<(), String> {
+    let mut child = Command::new("sudo")
+        .args(["-S", "-p", "", "-v"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not run sudo: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "{}", password.expose());
+    }
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => Err("sudo didn't accept the password".to_string()),
+        Err(e) => Err(format!("sudo failed: {}", e)),
+    }
+}
+
+/// The run's environment plus the variables `app`'s catalog entry sets,
+/// logged so the install's output can be read with them in mind
+fn app_env(app: &App, env: &RunEnv, tx: &mpsc::Sender<InstallMessage>) -> RunEnv {
+    for (name, value) in app.env {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[ENV] {}={} for {}",
+            name, value, app.name
+        )));
+    }
+    env.with_vars(app.env)
+}
+
+fn install_app(
+    app: &App,
+    system: &SystemInfo,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    let toolchain = |cmd: &str| {
+        toolchain_program(cmd, system, env)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| cmd.to_string())
+    };
+
+    match app.method() {
+        InstallMethod::Brew(pkg) => run_brew(env, &["install", pkg], tx),
+        InstallMethod::BrewCask(pkg) => run_brew(env, &["install", "--cask", pkg], tx),
+        InstallMethod::Cargo(pkg) => run_command(env, &toolchain("cargo"), &["install", pkg], tx),
+        InstallMethod::Npm(pkg) => {
+            let prefix = system.npm_prefix();
+            run_command_env(
+                env,
+                &toolchain("npm"),
+                &["install", "-g", pkg],
+                &[("NPM_CONFIG_PREFIX", &prefix.display().to_string())],
+                tx,
+            )
+        }
+        InstallMethod::Pip(pkg) => install_python_tool(pkg, system, env, tx),
+        InstallMethod::Go(pkg) => run_command(env, &toolchain("go"), &["install", pkg], tx),
+        InstallMethod::Script(url) => install_via_script(env, url, tx),
+        InstallMethod::Manual(cmd) => run_shell_command(env, cmd, tx),
+        InstallMethod::Apt(pkg) => run_command(env, "sudo", &["apt", "install", "-y", pkg], tx),
+        InstallMethod::PerOs { .. } => unreachable!("App::method resolves PerOs"),
+    }
+}
+
+/// Whether a toolchain is usable without installing anything. pipx stands
+/// in for uv, since either can install Python tools.
+fn has_toolchain(command: &str, system: &SystemInfo, env: &RunEnv) -> bool {
+    toolchain_program(command, system, env).is_some()
+        || (command == "uv" && system.package_managers.pipx.is_some())
+}
+
+/// Where a toolchain command lives: wherever the system had it, otherwise
+/// wherever the run's PATH finds it now
+fn toolchain_program(command: &str, system: &SystemInfo, env: &RunEnv) -> Option<PathBuf> {
+    let managers = &system.package_managers;
+    let detected = match command {
+        "cargo" => managers.cargo.clone(),
+        "npm" => managers.npm.clone(),
+        "go" => managers.go.clone(),
+        "uv" => managers.uv.clone(),
+        _ => None,
+    };
+
+    detected.or_else(|| env.find(command))
+}
+
+/// Python CLIs go into their own venvs via `uv tool` (or `pipx` when that
+/// is what the system has). A bare `pip install` fails on Homebrew and
+/// distro Pythons that are marked externally managed (PEP 668).
+fn install_python_tool(
+    pkg: &str,
+    system: &SystemInfo,
+    env: &RunEnv,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    if let Some(uv) = toolchain_program("uv", system, env) {
+        return run_command(
+            env,
+            &uv.display().to_string(),
+            &["tool", "install", pkg],
+            tx,
+        );
+    }
+    if system.package_managers.pipx.is_some() {
+        return run_command(env, "pipx", &["install", pkg], tx);
+    }
+    Err("Needs uv or pipx to install Python tools".to_string())
+}
+
+fn install_via_script(
+    env: &RunEnv,
+    url: &str,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    if env.dry_run {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] curl -fsSL {} | sh",
+            url
+        )));
+        return Ok(());
+    }
+    let _ = tx.send(InstallMessage::Log(format!("[SCRIPT] Downloading {}", url)));
+
+    // Download the script first, then pipe to sh
+    let mut command = env.command("bash");
+    command.args(["-c", &format!("curl -fsSL {} | sh", url)]);
+    let (status, stderr) =
+        run_streamed(command, env.limit, tx).map_err(|e| run_failed("script", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Script failed: {}", stderr.trim()))
+    }
+}
+
+fn run_shell_command(
+    env: &RunEnv,
+    cmd: &str,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    if env.dry_run {
+        let _ = tx.send(InstallMessage::Log(format!("[DRY] {}", cmd)));
+        return Ok(());
+    }
+    let _ = tx.send(InstallMessage::Log(format!("[CMD] {}", cmd)));
+
+    let mut command = env.command("bash");
+    command.args(["-c", cmd]);
+    let (status, stderr) =
+        run_streamed(command, env.limit, tx).map_err(|e| run_failed("command", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Command failed: {}", stderr.trim()))
+    }
+}
+
+// ─── Command runner with log streaming ───────────────────────────────
+
+fn run_brew(env: &RunEnv, args: &[&str], tx: &mpsc::Sender<InstallMessage>) -> Result<(), String> {
+    run_command_env(env, "brew", args, BREW_ENV, tx)
+}
+
+/// Run a command in `env` with its output streamed to the log, stopped at
+/// `env`'s time limit. A failure is the telling line of its stderr.
+pub fn run_command(
+    env: &RunEnv,
+    program: &str,
+    args: &[&str],
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    run_command_env(env, program, args, &[], tx)
+}
+
+fn run_command_env(
+    env: &RunEnv,
+    program: &str,
+    args: &[&str],
+    vars: &[(&str, &str)],
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    let (status, stderr) = run_command_captured(env, program, args, vars, tx)?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        let error_msg = match failure::telling_line(&stderr) {
+            Some(line) => line.to_string(),
+            None => format!("exited with code {}", status),
+        };
+        Err(error_msg)
+    }
+}
+
+/// Run a command, stream its output to the log, and hand back the exit
+/// status and stderr for callers that need to look at them
+fn run_command_captured(
+    env: &RunEnv,
+    program: &str,
+    args: &[&str],
+    vars: &[(&str, &str)],
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(ExitStatus, String), String> {
+    let cmd_str = format!("{} {}", program, args.join(" "));
+    if env.dry_run {
+        let _ = tx.send(InstallMessage::Log(format!("[DRY] {}", cmd_str)));
+        return Ok((ExitStatus::default(), String::new()));
+    }
+    let _ = tx.send(InstallMessage::Log(format!("[RUN] {}", cmd_str)));
+
+    let mut command = env.command(program);
+    command.args(args).envs(vars.iter().copied());
+    run_streamed(command, env.limit, tx).map_err(|e| run_failed(&format!("'{}'", cmd_str), e))
+}
+
+/// The error for a command that couldn't run, or ran out of time
+fn run_failed(what: &str, e: std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::TimedOut => e.to_string(),
+        _ => format!("Failed to run {}: {}", what, e),
+    }
+}
+
+/// Run `command` with its output sent to the log line by line as it's
+/// printed, handing back the exit status and stderr. Past `limit`'s
+/// deadline it's stopped, with a `TimedOut` error.
+fn run_streamed(
+    mut command: Command,
+    limit: Option<Limit>,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> std::io::Result<(ExitStatus, String)> {
+    if let Some(limit) = limit.filter(|l| Instant::now() >= l.deadline) {
+        return Err(timed_out(limit));
+    }
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let grouped = limit.is_some() && own_group(&mut command);
+    let mut child = command.spawn()?;
+
+    // Both pipes are drained at once, so a child filling one while the
+    // other is read can't stall. Not every stderr line is an error — brew
+    // uses stderr for progress.
+    let stdout = stream_in_background(child.stdout.take(), tx);
+    let stderr = stream_in_background(child.stderr.take(), tx);
+    match (wait_within(&mut child, limit, grouped)?, limit) {
+        (Some(status), _) => {
+            let _ = stdout.join();
+            Ok((status, stderr.join().unwrap_or_default()))
+        }
+        // The pipes aren't waited for: something it started may still
+        // hold them
+        (None, Some(limit)) => Err(timed_out(limit)),
+        (None, None) => unreachable!("only a limit stops a command"),
+    }
+}
+
+/// Run `command` for its output, as `Command::output` does, but stop it
+/// once `timeout` has passed, with a `TimedOut` error
+pub fn output_within(command: Command, timeout: Duration) -> std::io::Result<Output> {
+    let limit = Limit {
+        deadline: Instant::now() + timeout,
+        timeout,
+    };
+    output_until(command, limit)
+}
+
+/// `output_within`, up to `limit`'s deadline
+fn output_until(mut command: Command, limit: Limit) -> std::io::Result<Output> {
+    if Instant::now() >= limit.deadline {
+        return Err(timed_out(limit));
+    }
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let grouped = own_group(&mut command);
+    let mut child = command.spawn()?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    match wait_within(&mut child, Some(limit), grouped)? {
+        Some(status) => Ok(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        }),
+        None => Err(timed_out(limit)),
+    }
+}
+
+/// Put `command` in a process group of its own, so stopping it stops
+/// whatever it started, and say whether it was. Not while this process is
+/// the terminal's foreground job: outside that group, the first thing to
+/// read the terminal (a password or host key prompt) would be stopped
+/// until the timeout, so it's left in the foreground with us.
+fn own_group(command: &mut Command) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: both only look up this process's own state
+        let foreground = unsafe { libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp() };
+        if !foreground {
+            command.process_group(0);
+            return true;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = command;
+    false
+}
+
+/// Send `pipe` to the log line by line on a thread of its own
+fn stream_in_background(
+    pipe: Option<impl Read + Send + 'static>,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> JoinHandle<String> {
+    let tx = tx.clone();
+    std::thread::spawn(move || pipe.map(|pipe| stream_lines(pipe, &tx)).unwrap_or_default())
+}
+
+/// Read all of `pipe` on a thread of its own
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut all = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut all);
+        }
+        all
+    })
+}
+
+fn millis(timeout: Option<Duration>) -> Option<u64> {
+    timeout.map(|timeout| timeout.as_millis() as u64)
+}
+
+fn timed_out(limit: Limit) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!(
+            "Package timeout: still running after {}, so it was stopped",
+            events::format_duration(limit.timeout)
+        ),
+    )
+}
+
+/// Wait for `child` to exit, or stop it — and its whole group, if it
+/// leads one — at `limit`'s deadline and hand back `None`
+fn wait_within(
+    child: &mut Child,
+    limit: Option<Limit>,
+    grouped: bool,
+) -> std::io::Result<Option<ExitStatus>> {
+    let Some(limit) = limit else {
+        return child.wait().map(Some);
+    };
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        let left = limit.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            #[cfg(unix)]
+            if grouped {
+                // SAFETY: a signal to the group the child leads, which
+                // can't be reused while the child is unreaped
+                unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+            }
+            #[cfg(not(unix))]
+            let _ = grouped;
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(None);
+        }
+        std::thread::sleep(left.min(WAIT_POLL));
+    }
+}
+
+/// Send each line of `pipe` to the log as it arrives, handing back all
+/// of it once the pipe closes
+fn stream_lines(pipe: impl Read, tx: &mpsc::Sender<InstallMessage>) -> String {
+    let mut all = String::new();
+    for line in BufReader::new(pipe).split(b'\n') {
+        let Ok(line) = line else { break };
+        let line = String::from_utf8_lossy(&line);
+        let line = line.trim_end_matches('\r');
+        if !line.is_empty() {
+            let _ = tx.send(InstallMessage::Log(format!("  {}", line)));
+        }
+        all.push_str(line);
+        all.push('\n');
+    }
+    all
+}
+
+// ─── Run environment ─────────────────────────────────────────────────
+
+/// The PATH every command in a run gets. Tools installed mid-run (brew
+/// itself, a rustup toolchain, mise) land in directories this process's
+/// PATH didn't have when it started, so the run keeps its own and adds
+/// them as they appear.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunEnv {
+    path: Vec<PathBuf>,
+    /// Set on every command, on top of this process's environment
+    vars: Vec<(String, String)>,
+    /// When one package's commands are stopped
+    limit: Option<Limit>,
+    /// Commands are logged, not run
+    dry_run: bool,
+}
+
+/// A package's time budget
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Limit {
+    deadline: Instant,
+    timeout: Duration,
+}
+
+impl RunEnv {
+    /// This process's PATH, with whichever install directories already
+    /// exist in front
+    pub fn new(system: &SystemInfo) -> Self {
+        let mut env = Self::from_process();
+        env.add_install_dirs(system);
+        env
+    }
+
+    /// This process's PATH as it is
+    pub fn from_process() -> Self {
+        Self::from_path(&std::env::var_os("PATH").unwrap_or_default())
+    }
+
+    pub fn from_path(path: &std::ffi::OsStr) -> Self {
+        Self {
+            path: std::env::split_paths(path).collect(),
+            vars: Vec::new(),
+            limit: None,
+            dry_run: false,
+        }
+    }
+
+    /// Where installers put their commands, in the order they should be
+    /// found: the same order the generated shell config puts them in
+    fn install_dirs(system: &SystemInfo) -> Vec<PathBuf> {
+        let brew = Path::new(system.brew_prefix());
+        let home = &system.home_dir;
+        vec![
+            brew.join("bin"),
+            brew.join("sbin"),
+            home.join(".local/share/mise/shims"),
+            home.join(".cargo/bin"),
+            home.join(".local/bin"),
+            home.join("go/bin"),
+            system.npm_prefix().join("bin"),
+        ]
+    }
+
+    /// Put the install directories that exist and aren't on PATH yet in
+    /// front of it. Returns the ones added.
+    fn add_install_dirs(&mut self, system: &SystemInfo) -> Vec<PathBuf> {
+        let added: Vec<PathBuf> = Self::install_dirs(system)
+            .into_iter()
+            .filter(|dir| dir.is_dir() && !self.path.contains(dir))
+            .collect();
+        self.path.splice(0..0, added.iter().cloned());
+        added
+    }
+
+    /// Pick up directories that a step just created, logging each
+    pub fn refresh(&mut self, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+        for dir in self.add_install_dirs(system) {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[PATH] {} added for the rest of the run",
+                dir.display()
+            )));
+        }
+    }
+
+    /// PATH as one string, for a child's environment
+    pub fn path(&self) -> OsString {
+        std::env::join_paths(&self.path).unwrap_or_default()
+    }
+
+    /// Where `command` is on this PATH. A path is taken as it is.
+    pub fn find(&self, command: &str) -> Option<PathBuf> {
+        if command.contains('/') {
+            return Some(PathBuf::from(command));
+        }
+        self.path
+            .iter()
+            .map(|dir| dir.join(command))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// This environment with `vars` set as well, for one app's commands
+    pub fn with_vars(&self, vars: &[(&str, &str)]) -> RunEnv {
+        let mut env = self.clone();
+        env.vars
+            .extend(vars.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        env
+    }
+
+    /// This environment with its commands stopped once `timeout` has
+    /// passed since `start`
+    pub fn with_timeout(&self, timeout: Option<Duration>, start: Instant) -> RunEnv {
+        let mut env = self.clone();
+        env.limit = timeout.map(|timeout| Limit {
+            deadline: start + timeout,
+            timeout,
+        });
+        env
+    }
+
+    /// When this environment's commands are stopped, if they are
+    fn deadline(&self) -> Option<Instant> {
+        self.limit.map(|limit| limit.deadline)
+    }
+
+    /// A `Command` for `program`, looked up on this PATH and run with it
+    pub fn command(&self, program: impl AsRef<Path>) -> Command {
+        let program = program.as_ref();
+        let resolved = program
+            .to_str()
+            .and_then(|name| self.find(name))
+            .unwrap_or_else(|| program.to_path_buf());
+        let mut command = Command::new(resolved);
+        command.env("PATH", self.path());
+        command.envs(self.vars.iter().map(|(k, v)| (k, v)));
+        command
+    }
+}
+
+// ─── Already-installed detection ─────────────────────────────────────
+
+/// Everything Homebrew has installed, from one `brew list` per kind
+/// rather than one per package
+#[derive(Debug, Clone, Default)]
+pub struct BrewInventory {
+    pub formulae: HashSet<String>,
+    pub casks: HashSet<String>,
+}
+
+impl BrewInventory {
+    /// Empty when brew is missing or fails
+    pub fn detect() -> Self {
+        let list = |kind: &str| {
+            Command::new("brew")
+                .args(["list", kind, "-1"])
+                .envs(BREW_ENV.iter().copied())
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| {
+                    String::from_utf8_lossy(&o.stdout)
+                        .lines()
+                        .map(|l| l.trim().to_string())
+                        .filter(|l| !l.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            formulae: list("--formula"),
+            casks: list("--cask"),
+        }
+    }
+
+    /// Whether brew has the app's package, or `None` for apps brew
+    /// doesn't install. Tapped formulae are listed by their short name.
+    pub fn has(&self, app: &App) -> Option<bool> {
+        let short = |pkg: &str| pkg.rsplit('/').next().unwrap_or(pkg).to_string();
+        match app.method() {
+            InstallMethod::Brew(pkg) => Some(self.formulae.contains(&short(pkg))),
+            InstallMethod::BrewCask(pkg) => Some(self.casks.contains(&short(pkg))),
+            _ => None,
+        }
+    }
+}
+
+fn is_brew_installed(env: &RunEnv, formula: &str) -> bool {
+    env.command("brew")
+        .args(["list", "--formula", formula])
+        .envs(BREW_ENV.iter().copied())
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn is_brew_cask_installed(env: &RunEnv, cask: &str) -> bool {
+    env.command("brew")
+        .args(["list", "--cask", cask])
+        .envs(BREW_ENV.iter().copied())
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_error_picks_the_line_naming_the_package() {
+        let output = "\
+==> Fetching ripgrep
+Error: No available formula with the name \"fzff\".
+==> Installing ripgrep
+Error: bat: failed to download resource
+";
+        assert_eq!(
+            batch_error("fzff", output),
+            "Error: No available formula with the name \"fzff\"."
+        );
+        assert_eq!(
+            batch_error("bat", output),
+            "Error: bat: failed to download resource"
+        );
+        assert_eq!(
+            batch_error("jq", output),
+            "Not installed after batched brew install"
+        );
+    }
+
+    #[test]
+    fn sudo_is_needed_for_a_brew_bootstrap_or_a_sudo_command() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.package_managers.homebrew = Some(PathBuf::from("/opt/homebrew/bin/brew"));
+        let ripgrep = catalog::find_app("ripgrep").unwrap();
+        assert!(!needs_sudo(&[ripgrep], &system));
+
+        // Ghostty installs with `sudo snap` on Linux and as a cask on macOS
+        let ghostty = catalog::find_app("ghostty").unwrap();
+        assert_eq!(needs_sudo(&[ghostty], &system), cfg!(target_os = "linux"));
+
+        system.package_managers.homebrew = None;
+        assert!(needs_sudo(&[ripgrep], &system));
+    }
+
+    #[test]
+    fn clt_wait_reports_each_minute_and_gives_up() {
+        let (tx, rx) = mpsc::channel();
+        let polls = std::cell::Cell::new(0);
+        let installed = || {
+            polls.set(polls.get() + 1);
+            polls.get() > 30
+        };
+        let step = Duration::from_secs(5);
+        let limit = Duration::from_secs(600);
+        assert!(wait_for_clt(installed, |_| {}, step, limit, &tx).is_ok());
+        // 30 waits of 5s cross two minute marks
+        let logged: Vec<_> = rx.try_iter().collect();
+        assert_eq!(logged.len(), 2);
+        assert_eq!(
+            logged[1],
+            InstallMessage::Log(
+                "[CLT] Waiting for the Command Line Tools install... 2m".to_string()
+            )
+        );
+
+        let err = wait_for_clt(|| false, |_| {}, step, limit, &tx).unwrap_err();
+        assert_eq!(err, "still not installed after 10 minutes");
+    }
+
+    #[test]
+    fn linux_brew_setup_follows_the_distro_and_shell() {
+        let deps = brew_build_deps(|cmd| cmd == "dnf").unwrap();
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0][..3], ["dnf", "install", "-y"]);
+        assert!(deps[0].contains(&"procps-ng"));
+        let deps = brew_build_deps(|cmd| cmd == "apt-get").unwrap();
+        assert_eq!(deps[0], ["apt-get", "update"]);
+        assert!(deps[1].contains(&"build-essential"));
+        assert_eq!(brew_build_deps(|_| false), None);
+
+        let status = "Name:\tload\nUid:\t1000\t0\t1000\t1000\nGid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(effective_uid(status), Some(0));
+        assert_eq!(effective_uid("Name:\tload\n"), None);
+
+        let brew = Path::new("/home/linuxbrew/.linuxbrew/bin/brew");
+        assert_eq!(
+            shellenv_line(ShellChoice::Bash, brew),
+            "eval \"$(/home/linuxbrew/.linuxbrew/bin/brew shellenv)\""
+        );
+        assert_eq!(
+            shellenv_line(ShellChoice::Fish, brew),
+            "/home/linuxbrew/.linuxbrew/bin/brew shellenv | source"
+        );
+    }
+
+    #[test]
+    fn run_env_picks_up_directories_created_mid_run() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-runenv-{}", std::process::id()));
+        let cargo_bin = system.home_dir.join(".cargo/bin");
+        let mut env = RunEnv::from_path(std::ffi::OsStr::new("/usr/bin:/bin"));
+        let (tx, rx) = mpsc::channel();
+
+        env.refresh(&system, &tx);
+        assert!(!env.path.contains(&cargo_bin));
+
+        // rustup just ran
+        std::fs::create_dir_all(&cargo_bin).unwrap();
+        std::fs::write(cargo_bin.join("cargo"), "").unwrap();
+        env.refresh(&system, &tx);
+        assert_eq!(env.find("cargo"), Some(cargo_bin.join("cargo")));
+        assert!(rx
+            .try_iter()
+            .any(|m| matches!(m, InstallMessage::Log(line) if line.starts_with("[PATH]"))));
+
+        // Once is enough
+        env.refresh(&system, &tx);
+        assert_eq!(env.path.iter().filter(|dir| **dir == cargo_bin).count(), 1);
+        assert_eq!(env.find("/bin/sh"), Some(PathBuf::from("/bin/sh")));
+        let _ = std::fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn an_apps_env_is_set_for_its_command_alone() {
+        let rustup = catalog::find_app("rustup").unwrap();
+        assert!(rustup
+            .install_command()
+            .starts_with("RUSTUP_INIT_SKIP_PATH_CHECK=yes curl"));
+
+        let env = RunEnv::from_path(std::ffi::OsStr::new("/usr/bin:/bin"));
+        let (tx, rx) = mpsc::channel();
+        let echo = |env: &RunEnv| {
+            let out = env
+                .command("sh")
+                .args(["-c", "echo \"$RUSTUP_INIT_SKIP_PATH_CHECK\""])
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        assert_eq!(echo(&app_env(rustup, &env, &tx)), "yes");
+        assert_eq!(echo(&env), "");
+        let logged: Vec<String> = rx
+            .try_iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(logged, ["[ENV] RUSTUP_INIT_SKIP_PATH_CHECK=yes for Rustup"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn a_stalled_download_is_stopped_and_not_waited_on_past_the_deadline() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("loadstar-stall-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // A brew whose downloads never finish
+        let brew = dir.join("brew");
+        std::fs::write(
+            &brew,
+            "#!/bin/sh
+case \"$1\" in list) exit 1 ;; esac
+sleep 30
+",
+        )
+        .unwrap();
+        std::fs::set_permissions(&brew, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let env = RunEnv::from_path(&std::env::join_paths([&dir, Path::new("/bin")]).unwrap());
+        let (tx, rx) = mpsc::channel();
+        let start = Instant::now();
+        let timeout = Duration::from_millis(300);
+        let mut fetches = FetchStage::start(vec!["ripgrep"], false, Some(timeout), &env, &tx);
+        assert!(!fetches.wait("ripgrep", Some(start + Duration::from_millis(100))));
+        assert!(fetches.wait("ripgrep", None));
+        assert!(start.elapsed() < Duration::from_secs(10));
+        let logged: Vec<String> = rx
+            .try_iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            logged,
+            ["[FETCH] ripgrep didn't download (Package timeout: still running after 0s, so it was stopped); installing fetches it"]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn downloads_run_ahead_of_installs_in_order() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("loadstar-fetch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        // A brew that has `jq` installed, can't download `nope` and
+        // records every fetch
+        let brew = dir.join("brew");
+        std::fs::write(
+            &brew,
+            format!(
+                "#!/bin/sh\n\
+                 case \"$1 $3\" in\n\
+                 \"list jq\") exit 0 ;;\n\
+                 list*) exit 1 ;;\n\
+                 \"fetch nope\") echo 'curl: (6) Could not resolve host' >&2; exit 1 ;;\n\
+                 esac\n\
+                 echo \"$@\" >> {}\n",
+                dir.join("fetched").display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&brew, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let env = RunEnv::from_path(&std::env::join_paths([&dir, Path::new("/bin")]).unwrap());
+        let (tx, rx) = mpsc::channel();
+        let mut fetches =
+            FetchStage::start(vec!["ripgrep", "jq", "nope", "fd"], false, None, &env, &tx);
+        assert!(fetches.wait("fd", None));
+        assert!(fetches.wait("ripgrep", None));
+
+        let fetched = std::fs::read_to_string(dir.join("fetched")).unwrap();
+        assert_eq!(fetched, "fetch --deps ripgrep\nfetch --deps fd\n");
+        let logged: Vec<String> = rx
+            .try_iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            logged,
+            [
+                "[FETCH] ripgrep downloaded",
+                "[FETCH] nope didn't download (curl: (6) Could not resolve host); installing fetches it",
+                "[FETCH] fd downloaded",
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn command_output_reaches_the_log_before_the_command_exits() {
+        let dir = std::env::temp_dir().join(format!("loadstar-stream-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let go = dir.join("go");
+        // Waits up to 10s for the test to see its first line
+        let script = format!(
+            "echo first; i=0; while [ ! -e {} ] && [ $i -lt 200 ]; do sleep 0.05; i=$((i+1)); done; \
+             echo 'Error: second' >&2; exit 3",
+            go.display()
+        );
+        let env = RunEnv::from_path(std::ffi::OsStr::new("/usr/bin:/bin"));
+        let (tx, rx) = mpsc::channel();
+        let (status, stderr) = std::thread::scope(|scope| {
+            let run = scope.spawn(|| run_command_captured(&env, "sh", &["-c", &script], &[], &tx));
+            let logged = |rx: &mpsc::Receiver<InstallMessage>| match rx
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+            {
+                InstallMessage::Log(line) => line,
+                other => panic!("{:?}", other),
+            };
+            assert!(logged(&rx).starts_with("[RUN] sh -c"));
+            assert_eq!(logged(&rx), "  first");
+            std::fs::write(&go, "").unwrap();
+            assert_eq!(logged(&rx), "  Error: second");
+            run.join().unwrap().unwrap()
+        });
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(stderr, "Error: second\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn a_package_past_its_timeout_is_stopped_with_what_it_started() {
+        let env = RunEnv::from_path(std::ffi::OsStr::new("/usr/bin:/bin"));
+        let (tx, _rx) = mpsc::channel();
+        let start = Instant::now();
+        // The background sleep would keep the pipes open if it were left
+        let limited = env.with_timeout(Some(Duration::from_millis(300)), start);
+        let error = run_command(&limited, "sh", &["-c", "sleep 30 & sleep 30"], &tx).unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(FailureKind::classify(&error), FailureKind::Timeout);
+        assert_eq!(
+            error,
+            "Package timeout: still running after 0s, so it was stopped"
+        );
+        // A retry after the deadline doesn't start at all
+        assert!(run_command(&limited, "true", &[], &tx).is_err());
+        assert!(run_command(&env.with_timeout(None, start), "true", &[], &tx).is_ok());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn a_dry_run_logs_commands_and_runs_none() {
+        let dir = std::env::temp_dir().join(format!("loadstar-dry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut env = RunEnv::from_path(std::ffi::OsStr::new("/usr/bin:/bin"));
+        env.dry_run = true;
+        let (tx, rx) = mpsc::channel();
+        let touch = format!("touch {}", dir.display());
+        run_command(&env, "sh", &["-c", &touch], &tx).unwrap();
+        run_shell_command(&env, &touch, &tx).unwrap();
+        install_via_script(&env, "https://sh.rustup.rs", &tx).unwrap();
+        assert!(!dir.exists());
+
+        let mut summary = InstallSummary::default();
+        let app = catalog::find_app("ripgrep").unwrap();
+        record_result(app, Ok(()), Instant::now(), true, &tx, &mut summary);
+        assert!(summary.succeeded.is_empty());
+        assert_eq!(
+            summary.skipped,
+            [("Ripgrep".to_string(), "Dry run".to_string())]
+        );
+
+        let logged: Vec<String> = rx
+            .try_iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            logged,
+            [
+                format!("[DRY] sh -c {}", touch),
+                format!("[DRY] {}", touch),
+                "[DRY] curl -fsSL https://sh.rustup.rs | sh".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn transient_failures_are_retried_and_others_are_not() {
+        let app = catalog::find_app("ripgrep").unwrap();
+        let (tx, rx) = mpsc::channel();
+        let offline = || Err("curl: (6) Could not resolve host: ghcr.io".to_string());
+
+        let mut tries = 0;
+        let result = retry_transient(app, offline(), 3, Duration::ZERO, &tx, || {
+            tries += 1;
+            if tries < 2 {
+                offline()
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!((result, tries), (Ok(()), 2));
+        let logged: Vec<String> = rx
+            .try_iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(logged.len(), 2);
+        assert!(logged[1].starts_with("[RETRY] Ripgrep hit a network error; retry 2 of 3"));
+
+        let mut tries = 0;
+        let result = retry_transient(app, offline(), 2, Duration::ZERO, &tx, || {
+            tries += 1;
+            offline()
+        });
+        assert!(result.is_err());
+        assert_eq!(tries, 2);
+
+        let mut tries = 0;
+        let missing = Err("Error: No available formula with the name \"rg\"".to_string());
+        let result = retry_transient(app, missing, 2, Duration::ZERO, &tx, || {
+            tries += 1;
+            Ok(())
+        });
+        assert!(result.is_err());
+        assert_eq!(tries, 0);
+
+        // Another brew holding the lock goes away too
+        let (tx, rx) = mpsc::channel();
+        let locked = Err(
+            "Error: A `brew install fd` process has already locked /opt/homebrew/Cellar/fd"
+                .to_string(),
+        );
+        let result = retry_transient(app, locked, 2, Duration::ZERO, &tx, || Ok(()));
+        assert_eq!(result, Ok(()));
+        let logged = rx.try_iter().next();
+        assert!(matches!(logged, Some(InstallMessage::Log(line))
+            if line.starts_with("[RETRY] Ripgrep hit a lock another install held; retry 1 of 2")));
+    }
+
+    #[test]
+    fn warnings_are_counted_apart_from_log_lines() {
+        let mut summary = InstallSummary::default();
+        summary.record(&InstallMessage::Log("[RUN] gh auth status".to_string()));
+        summary.record(&InstallMessage::Warning(
+            "GitHub CLI isn't logged in".to_string(),
+        ));
+        assert_eq!(summary.warnings, ["GitHub CLI isn't logged in"]);
+        assert!(summary.failed.is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+//! Install failure hints
+//! Known ways an install fails, told apart by what the installer printed,
+//! each with what to do about it.
+
+use crate::catalog::InstallMethod;
+
+/// Why a package failed, as far as its output says
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The Xcode Command Line Tools are missing or broken
+    XcodeTools,
+    /// A cask's installer asked for a password nobody could type
+    NeedsPassword,
+    Network,
+    /// Another brew or apt held the lock this install needed
+    Locked,
+    /// The package isn't where the catalog says it is
+    NotFound,
+    /// A directory the installer writes to isn't the user's
+    Permission,
+    /// It ran past the profile's `package_timeout` and was stopped
+    Timeout,
+    Other,
+}
+
+/// Lowercase snippets that give each kind away, checked in this order
+const PATTERNS: &[(FailureKind, &[&str])] = &[
+    (FailureKind::Timeout, &["package timeout:"]),
+    (
+        FailureKind::XcodeTools,
+        &[
+            "xcrun: error",
+            "xcode-select",
+            "command line tools",
+            "no developer tools were found",
+        ],
+    ),
+    (
+        FailureKind::NeedsPassword,
+        &[
+            "sudo: a terminal is required",
+            "sudo: a password is required",
+            "sudo: no tty present",
+            "password may be necessary",
+        ],
+    ),
+    (
+        FailureKind::Network,
+        &[
+            "could not resolve host",
+            "timed out",
+            "failed to connect",
+            "network is unreachable",
+            "connection reset",
+            "connection refused",
+            "temporary failure in name resolution",
+        ],
+    ),
+    (
+        FailureKind::Locked,
+        &[
+            "has already locked",
+            "another active homebrew",
+            "could not get lock",
+            "waiting for cache lock",
+        ],
+    ),
+    (
+        FailureKind::NotFound,
+        &[
+            "no available formula",
+            "no available cask",
+            "no formulae or casks found",
+            "unable to locate package",
+            "could not find `",
+            "404 not found",
+            "npm error 404",
+            "npm err! 404",
+        ],
+    ),
+    (
+        FailureKind::Permission,
+        &[
+            "permission denied",
+            "operation not permitted",
+            "eacces",
+            "not writable",
+        ],
+    ),
+];
+
+impl FailureKind {
+    /// The kind `error` reads as
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        PATTERNS
+            .iter()
+            .find(|(_, snippets)| snippets.iter().any(|s| error.contains(s)))
+            .map_or(FailureKind::Other, |(kind, _)| *kind)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FailureKind::XcodeTools => "Xcode tools",
+            FailureKind::NeedsPassword => "needs a password",
+            FailureKind::Network => "network",
+            FailureKind::Locked => "locked",
+            FailureKind::NotFound => "not found",
+            FailureKind::Permission => "permissions",
+            FailureKind::Timeout => "timed out",
+            FailureKind::Other => "other",
+        }
+    }
+
+    /// What went wrong, for a failure that's worth trying again: it may
+    /// well not happen twice
+    pub fn transient(&self) -> Option<&'static str> {
+        match self {
+            FailureKind::Network => Some("a network error"),
+            FailureKind::Locked => Some("a lock another install held"),
+            _ => None,
+        }
+    }
+
+    /// What to do about it; `None` when the error says all there is.
+    /// `method` is how the package installs, where that's known.
+    pub fn hint(&self, method: Option<&InstallMethod>) -> Option<&'static str> {
+        match self {
+            FailureKind::XcodeTools => Some(
+                "Run `xcode-select --install`, wait for it to finish, then run loadstar again",
+            ),
+            FailureKind::NeedsPassword => Some(
+                "The cask's installer runs sudo. Run `sudo -v` just before loadstar so it's cached, or install it yourself",
+            ),
+            FailureKind::Network => Some(
+                "Check the connection (and any proxy or VPN), then run loadstar again; what installed is kept",
+            ),
+            FailureKind::Locked => Some(
+                "Another brew or apt was busy and outlasted the retries; let it finish, then run loadstar again",
+            ),
+            FailureKind::NotFound => Some(
+                "The package may have been renamed or removed; `loadstar catalog check` lists entries that stopped resolving",
+            ),
+            FailureKind::Permission => Some(match method {
+                Some(InstallMethod::Brew(_) | InstallMethod::BrewCask(_)) => {
+                    "Something under Homebrew's prefix isn't yours; `sudo chown -R $(whoami) $(brew --prefix)/*` gives it back"
+                }
+                Some(InstallMethod::Npm(_)) => {
+                    "npm's global directory isn't yours. `npm config set prefix ~/.npm-global`, with ~/.npm-global/bin on PATH, installs there instead; don't reach for sudo"
+                }
+                _ => {
+                    "A file or directory it writes to isn't yours; the error names it. Give it back with `sudo chown` rather than running loadstar with sudo"
+                }
+            }),
+            FailureKind::Timeout => Some(
+                "It may be waiting on a prompt or a stalled download. Install it yourself, or raise `package_timeout` under `[install]`",
+            ),
+            FailureKind::Other => None,
+        }
+    }
+}
+
+/// The line of an installer's output that best says why it failed: the
+/// first error line matching a known kind, then any other line that does,
+/// then the first error line, or else the last line. Warnings never count:
+/// an installer can warn about one thing and fail on another.
+pub fn telling_line(output: &str) -> Option<&str> {
+    let lines = || {
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !is_warning(l))
+    };
+    let known = |line: &&str| FailureKind::classify(line) != FailureKind::Other;
+    lines()
+        .find(|line| is_error(line) && known(line))
+        .or_else(|| lines().find(known))
+        .or_else(|| lines().find(|line| is_error(line)))
+        .or_else(|| output.lines().map(str::trim).rfind(|l| !l.is_empty()))
+}
+
+/// `Error: ...` from brew, `error: ...` from cargo, `E: ...` from apt and
+/// `npm error ...` (`npm ERR!` before npm 10)
+fn is_error(line: &str) -> bool {
+    let line = line.to_lowercase();
+    ["error", "e: ", "npm err"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+fn is_warning(line: &str) -> bool {
+    let line = line.to_lowercase();
+    ["warning", "w: ", "npm warn"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_told_apart_by_their_output() {
+        let cases = [
+            (
+                "xcrun: error: invalid active developer path (/Library/Developer/CommandLineTools)",
+                FailureKind::XcodeTools,
+            ),
+            (
+                "sudo: a terminal is required to read the password; either use the -S option",
+                FailureKind::NeedsPassword,
+            ),
+            (
+                "curl: (28) Operation timed out after 30001 milliseconds",
+                FailureKind::Network,
+            ),
+            (
+                "Error: A `brew install wget` process has already locked /opt/homebrew/Cellar/wget",
+                FailureKind::Locked,
+            ),
+            (
+                "E: Could not get lock /var/lib/dpkg/lock-frontend. It is held by process 4242 (apt)",
+                FailureKind::Locked,
+            ),
+            (
+                "Error: No available formula with the name \"ripgrepp\".",
+                FailureKind::NotFound,
+            ),
+            (
+                "Error: The following directories are not writable by your user:",
+                FailureKind::Permission,
+            ),
+            (
+                "Package timeout: still running after 10m00s, so it was stopped",
+                FailureKind::Timeout,
+            ),
+            ("Error: An unexpected error occurred", FailureKind::Other),
+        ];
+        for (error, kind) in cases {
+            assert_eq!(FailureKind::classify(error), kind, "{}", error);
+            assert_eq!(kind.hint(None).is_some(), kind != FailureKind::Other);
+        }
+    }
+
+    #[test]
+    fn permission_hints_follow_the_install_method() {
+        let kind = FailureKind::Permission;
+        let brew = kind.hint(Some(&InstallMethod::Brew("bat"))).unwrap();
+        assert!(brew.contains("brew --prefix"));
+        let npm = kind.hint(Some(&InstallMethod::Npm("pyright"))).unwrap();
+        assert!(npm.contains("npm config set prefix"));
+        let other = kind.hint(None).unwrap();
+        assert!(!other.contains("brew") && !other.contains("npm"));
+    }
+
+    #[test]
+    fn the_telling_line_beats_the_last_one() {
+        let stderr = "==> Downloading https://example.com/x.tar.gz\n\
+                      curl: (6) Could not resolve host: example.com\n\
+                      Error: x: Failed to download resource\n";
+        assert_eq!(
+            telling_line(stderr),
+            Some("curl: (6) Could not resolve host: example.com")
+        );
+        assert_eq!(
+            telling_line("warming up\nError: boom\n\n"),
+            Some("Error: boom")
+        );
+        assert_eq!(telling_line(""), None);
+
+        // A warning that reads like a failure doesn't hide the error
+        let stderr = "Warning: /usr/local/share is not writable
+                      Error: No available formula with the name \"bta\"
+";
+        assert_eq!(
+            telling_line(stderr),
+            Some("Error: No available formula with the name \"bta\"")
+        );
+        assert_eq!(
+            telling_line(
+                "Warning: timed out on a mirror
+Error: boom
+bye
+"
+            ),
+            Some("Error: boom")
+        );
+        assert_eq!(
+            telling_line(
+                "Warning: only this
+"
+            ),
+            Some("Warning: only this")
+        );
+    }
+}
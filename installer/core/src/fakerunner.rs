@@ -0,0 +1,122 @@
+//! Fake command runner
+//! A [`GitRunner`] for tests: canned answers by command prefix, and a record
+//! of every command, stdin and environment it was handed. Built for this
+//! crate's tests, and for the installer's with the `fake-runner` feature.
+
+use std::sync::Mutex;
+
+use crate::github::{CommandOutput, GitRunner};
+
+/// Answers by command prefix. Several answers for one prefix play back in
+/// order, the last one for good; a command nothing answers gets the
+/// fallback, which succeeds quietly unless set.
+pub struct FakeRunner {
+    answers: Mutex<Vec<(String, Result<CommandOutput, String>)>>,
+    otherwise: Result<CommandOutput, String>,
+    calls: Mutex<Vec<String>>,
+    stdin: Mutex<Vec<String>>,
+    env: Mutex<Vec<String>>,
+}
+
+impl Default for FakeRunner {
+    fn default() -> Self {
+        Self {
+            answers: Mutex::default(),
+            otherwise: ok(""),
+            calls: Mutex::default(),
+            stdin: Mutex::default(),
+            env: Mutex::default(),
+        }
+    }
+}
+
+impl FakeRunner {
+    pub fn answer(self, prefix: impl Into<String>, result: Result<CommandOutput, String>) -> Self {
+        self.answers.lock().unwrap().push((prefix.into(), result));
+        self
+    }
+
+    /// What commands no answer matches get
+    pub fn otherwise(mut self, result: Result<CommandOutput, String>) -> Self {
+        self.otherwise = result;
+        self
+    }
+
+    pub fn ran(&self, prefix: &str) -> bool {
+        self.calls().iter().any(|c| c.starts_with(prefix))
+    }
+
+    /// Every command run, as `program args...`
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// What each command was handed on stdin
+    pub fn stdin(&self) -> Vec<String> {
+        self.stdin.lock().unwrap().clone()
+    }
+
+    /// The variables commands were run with, as `NAME=value`
+    pub fn env(&self) -> Vec<String> {
+        self.env.lock().unwrap().clone()
+    }
+}
+
+impl GitRunner for FakeRunner {
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&str],
+        input: &str,
+    ) -> Result<CommandOutput, String> {
+        self.stdin.lock().unwrap().push(input.to_string());
+        self.run(program, args)
+    }
+
+    fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+    ) -> Result<CommandOutput, String> {
+        self.env.lock().unwrap().extend(
+            env.iter()
+                .map(|(name, value)| format!("{}={}", name, value)),
+        );
+        self.run(program, args)
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String> {
+        let command = format!("{} {}", program, args.join(" "));
+        self.calls.lock().unwrap().push(command.clone());
+        let mut answers = self.answers.lock().unwrap();
+        let mut matching = answers
+            .iter()
+            .enumerate()
+            .filter(|(_, (prefix, _))| command.starts_with(prefix.as_str()))
+            .map(|(i, _)| i);
+        match (matching.next(), matching.next()) {
+            (Some(first), Some(_)) => answers.remove(first).1,
+            (Some(only), None) => answers[only].1.clone(),
+            (None, _) => self.otherwise.clone(),
+        }
+    }
+}
+
+/// A command that succeeded, printing `stdout`
+pub fn ok(stdout: &str) -> Result<CommandOutput, String> {
+    Ok(CommandOutput {
+        success: true,
+        stdout: stdout.to_string(),
+        stderr: String::new(),
+    })
+}
+
+/// A command that failed, printing `stderr`
+pub fn fail(stderr: &str) -> Result<CommandOutput, String> {
+    Ok(CommandOutput {
+        success: false,
+        stdout: String::new(),
+        stderr: stderr.to_string(),
+    })
+}
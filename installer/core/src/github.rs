@@ -0,0 +1,1795 @@
+//! GitHub CLI setup — SSH keys, git config, gh auth
+//! Runs as part of the installation flow after packages are installed.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use crate::executor::InstallMessage;
+use crate::rcblock;
+use crate::secret::Secret;
+use crate::system::{Os, SystemInfo};
+use crate::wizard::{SshKeyChoice, WizardState};
+
+// ─── Command layer ───────────────────────────────────────────────────
+
+/// What a finished command printed, and whether it succeeded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl From<Output> for CommandOutput {
+    fn from(output: Output) -> Self {
+        Self {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }
+    }
+}
+
+/// Runs the commands this module shells out to: git, gh, gpg and the ssh
+/// tools. `Err` means the program couldn't be started at all.
+pub trait GitRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String>;
+
+    /// Like `run`, with `input` written to the command's stdin. For
+    /// secrets, which don't belong in argument lists.
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&str],
+        input: &str,
+    ) -> Result<CommandOutput, String>;
+
+    /// Like `run`, with `env` added to the command's environment. Also for
+    /// secrets: unlike arguments, other users can't read them from `ps`.
+    fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&str],
+        _env: &[(&str, &str)],
+    ) -> Result<CommandOutput, String> {
+        self.run(program, args)
+    }
+
+    /// Whether commands are only logged, as `DryRunner` does. Steps that
+    /// write files themselves leave them alone too.
+    fn dry_run(&self) -> bool {
+        false
+    }
+}
+
+/// Runs commands on this machine
+pub struct SystemRunner;
+
+impl GitRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String> {
+        Command::new(program)
+            .args(args)
+            .output()
+            .map(CommandOutput::from)
+            .map_err(|e| e.to_string())
+    }
+
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&str],
+        input: &str,
+    ) -> Result<CommandOutput, String> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        child
+            .wait_with_output()
+            .map(CommandOutput::from)
+            .map_err(|e| e.to_string())
+    }
+
+    fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+    ) -> Result<CommandOutput, String> {
+        Command::new(program)
+            .args(args)
+            .envs(env.iter().copied())
+            .output()
+            .map(CommandOutput::from)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// For a dry run: commands that only look run on this machine, so the
+/// setup takes the turns it would for real. The rest are logged as
+/// `[DRY] ...` and count as succeeding, with nothing printed.
+pub struct DryRunner {
+    tx: mpsc::Sender<InstallMessage>,
+}
+
+impl DryRunner {
+    pub fn new(tx: &mpsc::Sender<InstallMessage>) -> Self {
+        Self { tx: tx.clone() }
+    }
+
+    /// Version checks, config lookups, gh's login status and the keys
+    /// already there
+    fn only_looks(program: &str, args: &[&str]) -> bool {
+        match (program, args) {
+            (_, ["--version"]) => true,
+            ("git", ["config", "--global", "--get", _]) => true,
+            ("gh", ["auth", "status"] | ["ssh-key", "list"]) => true,
+            ("env", args) => args.ends_with(&["gh", "auth", "status"]),
+            ("ssh-keygen", ["-lf", _]) => true,
+            ("systemctl", ["--user", "show-environment"]) => true,
+            ("gpg", [first, ..]) => *first == "--list-secret-keys",
+            _ => false,
+        }
+    }
+
+    fn log(&self, program: &str, args: &[&str]) {
+        let mut shown = vec![program.to_string()];
+        for arg in args {
+            shown.push(match arg {
+                arg if arg.is_empty() || arg.contains(' ') => format!("'{}'", arg),
+                arg => arg.to_string(),
+            });
+        }
+        let _ = self
+            .tx
+            .send(InstallMessage::Log(format!("[DRY] {}", shown.join(" "))));
+    }
+}
+
+impl GitRunner for DryRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String> {
+        if Self::only_looks(program, args) {
+            return SystemRunner.run(program, args);
+        }
+        self.log(program, args);
+        Ok(CommandOutput {
+            success: true,
+            ..CommandOutput::default()
+        })
+    }
+
+    /// The input is a secret, so only the command is logged
+    fn run_with_input(
+        &self,
+        program: &str,
+        args: &[&str],
+        _input: &str,
+    ) -> Result<CommandOutput, String> {
+        self.run(program, args)
+    }
+
+    fn dry_run(&self) -> bool {
+        true
+    }
+}
+
+/// Whether `program --version` runs and succeeds
+fn is_available(runner: &dyn GitRunner, program: &str) -> bool {
+    runner
+        .run(program, &["--version"])
+        .map(|o| o.success)
+        .unwrap_or(false)
+}
+
+/// Run the full GitHub/git setup sequence
+pub fn setup_github(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    if wizard.dry_run {
+        setup_github_with(&DryRunner::new(tx), wizard, system, tx);
+    } else {
+        setup_github_with(&SystemRunner, wizard, system, tx);
+    }
+}
+
+/// The setup sequence, with every command going through `runner`
+pub fn setup_github_with(
+    runner: &dyn GitRunner,
+    wizard: &WizardState,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Git & GitHub Setup".to_string(),
+    });
+
+    // Step 1: Configure git identity
+    configure_git_identity(runner, wizard, tx);
+
+    // Step 2: SSH key generation
+    let ssh_key_path = generate_ssh_key(runner, wizard, system, tx);
+
+    // Step 3: Start ssh-agent and add key
+    if let Some(ref key_path) = ssh_key_path {
+        add_key_to_agent(runner, key_path, system, tx);
+    }
+
+    // Step 4: gh CLI auth (if gh is installed/selected)
+    if wizard.configures("gh") {
+        let token = token_for(wizard);
+        let token = token
+            .as_ref()
+            .map(|(source, token)| (source.as_str(), token));
+        setup_gh_cli(runner, ssh_key_path.as_deref(), token, tx);
+    }
+
+    // Step 5: Confirm GitHub takes the key
+    if let Some(ref key_path) = ssh_key_path {
+        check_github_ssh(runner, key_path, tx);
+    }
+
+    // Step 6: GPG signing (if opted in)
+    if wizard.setup_git_signing {
+        setup_gpg_signing(runner, wizard, tx);
+    }
+
+    let _ = tx.send(InstallMessage::Log(
+        "[GIT] Git & GitHub setup complete".to_string(),
+    ));
+}
+
+// ─── Git identity ────────────────────────────────────────────────────
+
+fn configure_git_identity(
+    runner: &dyn GitRunner,
+    wizard: &WizardState,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let _ = tx.send(InstallMessage::Log(
+        "[GIT] Configuring git identity...".to_string(),
+    ));
+
+    for (key, value) in git_settings(wizard) {
+        let _ = run_git_config(runner, key, &value, tx);
+    }
+
+    let _ = tx.send(InstallMessage::Log(
+        "[GIT] Git identity configured".to_string(),
+    ));
+}
+
+/// Every global git config key the install sets, with its value, in order
+pub fn git_settings(wizard: &WizardState) -> Vec<(&'static str, String)> {
+    let mut settings = Vec::new();
+    if !wizard.identity.name.is_empty() {
+        settings.push(("user.name", wizard.identity.name.clone()));
+    }
+    if !wizard.identity.email.is_empty() {
+        settings.push(("user.email", wizard.identity.email.clone()));
+    }
+
+    // Sane defaults
+    settings.extend([
+        ("init.defaultBranch", "main".to_string()),
+        ("push.autoSetupRemote", "true".to_string()),
+        ("pull.rebase", "true".to_string()),
+        ("fetch.prune", "true".to_string()),
+        ("rebase.autoStash", "true".to_string()),
+    ]);
+
+    // Delta as pager if selected
+    if wizard.configures("delta") {
+        settings.extend([
+            ("core.pager", "delta".to_string()),
+            ("interactive.diffFilter", "delta --color-only".to_string()),
+            ("delta.navigate", "true".to_string()),
+            ("delta.line-numbers", "true".to_string()),
+            (
+                "delta.syntax-theme",
+                wizard.theme.palette().bat_theme.to_string(),
+            ),
+        ]);
+    }
+
+    // SSH for GitHub URLs, only when asked for
+    if wizard.rewrite_github_https {
+        settings.push((
+            "url.git@github.com:.insteadOf",
+            "https://github.com/".to_string(),
+        ));
+    }
+    settings
+}
+
+/// Set each global git config key in `settings`, recording what it held
+/// before, as the install does
+pub fn set_git_config(settings: &[(&str, &str)], tx: &mpsc::Sender<InstallMessage>) {
+    for (key, value) in settings {
+        let _ = run_git_config(&SystemRunner, key, value, tx);
+    }
+}
+
+/// Set a global git config key, recording what it held before
+fn run_git_config(
+    runner: &dyn GitRunner,
+    key: &str,
+    value: &str,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    let old = current_git_config(runner, key);
+    if old.as_deref() == Some(value) {
+        let _ = tx.send(InstallMessage::Log(format!("  {} already {}", key, value)));
+        return Ok(());
+    }
+
+    let output = runner
+        .run("git", &["config", "--global", key, value])
+        .map_err(|e| format!("git config failed: {}", e))?;
+
+    if runner.dry_run() {
+        Ok(())
+    } else if output.success {
+        let _ = tx.send(InstallMessage::GitConfigSet {
+            key: key.to_string(),
+            old,
+            new: value.to_string(),
+        });
+        Ok(())
+    } else {
+        let err = format!("git config {} failed: {}", key, output.stderr.trim());
+        let _ = tx.send(InstallMessage::Warning(err.clone()));
+        Err(err)
+    }
+}
+
+/// What a global git config key holds now. Unset and empty both read as
+/// `None`.
+fn current_git_config(runner: &dyn GitRunner, key: &str) -> Option<String> {
+    let output = runner
+        .run("git", &["config", "--global", "--get", key])
+        .ok()?;
+    let value = output.stdout.trim_end_matches('\n');
+    (output.success && !value.is_empty()).then(|| value.to_string())
+}
+
+/// Log line for a git config write: "changed X from a → b" when it
+/// replaced something, "set X=b (new)" when it didn't
+pub fn describe_git_change(key: &str, old: Option<&str>, new: &str) -> String {
+    match old {
+        Some(old) => format!("  changed {} from {} → {}", key, old, new),
+        None => format!("  set {}={} (new)", key, new),
+    }
+}
+
+// ─── SSH key generation ──────────────────────────────────────────────
+
+fn generate_ssh_key(
+    runner: &dyn GitRunner,
+    wizard: &WizardState,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Option<PathBuf> {
+    let ssh_dir = system.home_dir.join(".ssh");
+    let key_path = match &wizard.ssh_key {
+        SshKeyChoice::Existing(path) if path.exists() => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[SSH] Using the picked key {}",
+                path.display()
+            )));
+            show_public_key(path, tx);
+            return Some(path.clone());
+        }
+        SshKeyChoice::Existing(path) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "The picked SSH key {} is gone; skipping SSH setup",
+                path.display()
+            )));
+            return None;
+        }
+        SshKeyChoice::Generate => new_key_path(&ssh_dir),
+        SshKeyChoice::Default => {
+            let key_path = ssh_dir.join("id_ed25519");
+
+            // Check for existing keys
+            if key_path.exists() {
+                let _ = tx.send(InstallMessage::Log(
+                    "[SSH] Existing SSH key found — skipping generation".to_string(),
+                ));
+
+                // Show the public key
+                show_public_key(&key_path, tx);
+                return Some(key_path);
+            }
+
+            // Also check for RSA keys
+            let rsa_path = ssh_dir.join("id_rsa");
+            if rsa_path.exists() {
+                let _ = tx.send(InstallMessage::Log(
+                    "[SSH] Existing RSA key found at ~/.ssh/id_rsa — skipping generation"
+                        .to_string(),
+                ));
+                show_public_key(&rsa_path, tx);
+                return Some(rsa_path);
+            }
+
+            if !wizard.generate_ssh_key {
+                let _ = tx.send(InstallMessage::Log(
+                    "[SSH] No SSH key found and key generation is off — skipping".to_string(),
+                ));
+                return None;
+            }
+            key_path
+        }
+    };
+
+    let _ = tx.send(InstallMessage::Log(format!(
+        "[SSH] Generating ed25519 SSH key at {}...",
+        key_path.display()
+    )));
+
+    // Ensure .ssh directory exists with correct permissions
+    if runner.dry_run() {
+        let _ = tx.send(InstallMessage::Log(
+            "[DRY] mkdir -p -m 700 ~/.ssh".to_string(),
+        ));
+    } else if let Err(e) = std::fs::create_dir_all(&ssh_dir) {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  [ERROR] Failed to create ~/.ssh: {}",
+            e
+        )));
+        return None;
+    }
+
+    // Set permissions on .ssh directory (700)
+    #[cfg(unix)]
+    if !runner.dry_run() {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&ssh_dir, std::fs::Permissions::from_mode(0o700));
+    }
+
+    // Build comment for the key
+    let comment = if !wizard.identity.email.is_empty() {
+        wizard.identity.email.clone()
+    } else {
+        format!("{}@loadstar", whoami::username())
+    };
+
+    // `-N` would put the passphrase where `ps` shows it, so ssh-keygen asks
+    // for it instead, and an askpass helper answers from the environment.
+    // An empty one leaves the key unprotected; `ssh-keygen -p` adds one.
+    let key_arg = key_path.to_string_lossy();
+    let mut args = vec!["-t", "ed25519", "-C", &comment, "-f", &key_arg];
+    let output = if wizard.ssh_passphrase.is_empty() {
+        args.extend(["-N", ""]);
+        runner.run("ssh-keygen", &args)
+    } else {
+        keygen_with_askpass(runner, &args, wizard.ssh_passphrase.expose(), system)
+    };
+
+    match output {
+        Ok(out) if out.success => {
+            let _ = tx.send(InstallMessage::Log(
+                "[SSH] SSH key generated successfully".to_string(),
+            ));
+            if !wizard.ssh_passphrase.is_empty() {
+                let keychain = if system.os == Os::MacOS {
+                    "--apple-use-keychain "
+                } else {
+                    ""
+                };
+                let file_name = key_path.file_name().unwrap_or_default().to_string_lossy();
+                let _ = tx.send(InstallMessage::NextStep {
+                    command: format!("ssh-add {}~/.ssh/{}", keychain, file_name),
+                    note: "so the passphrase is asked for once, not on every push".to_string(),
+                });
+            }
+            // Set correct permissions on the private key (600)
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600));
+            }
+
+            show_public_key(&key_path, tx);
+            Some(key_path)
+        }
+        Ok(out) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] ssh-keygen failed: {}",
+                out.stderr.trim()
+            )));
+            None
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  [ERROR] Could not run ssh-keygen: {}",
+                e
+            )));
+            None
+        }
+    }
+}
+
+/// Holds the passphrase for the askpass helper
+const PASSPHRASE_VAR: &str = "LOADSTAR_SSH_PASSPHRASE";
+
+/// Run ssh-keygen with `SSH_ASKPASS` pointing at a script that prints the
+/// passphrase, each time it's asked (OpenSSH 8.4 and later take
+/// `SSH_ASKPASS_REQUIRE=force`). The script holds no secret and is
+/// removed after.
+fn keygen_with_askpass(
+    runner: &dyn GitRunner,
+    args: &[&str],
+    passphrase: &str,
+    system: &SystemInfo,
+) -> Result<CommandOutput, String> {
+    let script = system.config_dir.join("loadstar").join("askpass.sh");
+    if !runner.dry_run() {
+        write_askpass(&script).map_err(|e| format!("Could not write the askpass helper: {}", e))?;
+    }
+    let askpass = script.to_string_lossy();
+    let output = runner.run_with_env(
+        "ssh-keygen",
+        args,
+        &[
+            ("SSH_ASKPASS", &askpass),
+            ("SSH_ASKPASS_REQUIRE", "force"),
+            (PASSPHRASE_VAR, passphrase),
+        ],
+    );
+    let _ = std::fs::remove_file(&script);
+    output
+}
+
+fn write_askpass(script: &Path) -> std::io::Result<()> {
+    if let Some(parent) = script.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(
+        script,
+        format!("#!/bin/sh\nprintf '%s\\n' \"${}\"\n", PASSPHRASE_VAR),
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(script, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Where a new key goes: `id_ed25519` if that's free, otherwise a
+/// dedicated `id_ed25519_github` so no existing key is overwritten
+fn new_key_path(ssh_dir: &Path) -> PathBuf {
+    let default = ssh_dir.join("id_ed25519");
+    if !default.exists() {
+        return default;
+    }
+    (1..)
+        .map(|n| match n {
+            1 => ssh_dir.join("id_ed25519_github"),
+            n => ssh_dir.join(format!("id_ed25519_github{}", n)),
+        })
+        .find(|path| !path.exists() && !path.with_extension("pub").exists())
+        .expect("some numbered key name is free")
+}
+
+fn show_public_key(private_key_path: &Path, tx: &mpsc::Sender<InstallMessage>) {
+    let pub_path = private_key_path.with_extension("pub");
+    if let Ok(pub_key) = std::fs::read_to_string(&pub_path) {
+        let trimmed = pub_key.trim();
+        // Show truncated key for the log
+        if trimmed.len() > 60 {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Public key: {}...{}",
+                &trimmed[..30],
+                &trimmed[trimmed.len() - 20..]
+            )));
+        } else {
+            let _ = tx.send(InstallMessage::Log(format!("  Public key: {}", trimmed)));
+        }
+    }
+}
+
+// ─── Existing keys ───────────────────────────────────────────────────
+
+/// A key pair already in `~/.ssh`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshKey {
+    /// The private key; its `.pub` sits next to it
+    pub path: PathBuf,
+    /// "ED25519", "RSA", …
+    pub kind: String,
+    pub comment: String,
+    /// `SHA256:…`, or empty if ssh-keygen couldn't read the key
+    pub fingerprint: String,
+    pub modified: Option<SystemTime>,
+}
+
+impl SshKey {
+    /// How long ago the key was last changed, e.g. "3 months ago"
+    pub fn age(&self) -> String {
+        self.modified
+            .and_then(|m| m.elapsed().ok())
+            .map(format_age)
+            .unwrap_or_else(|| "unknown age".to_string())
+    }
+}
+
+/// Every key pair in `~/.ssh`, newest first
+pub fn find_ssh_keys(system: &SystemInfo) -> Vec<SshKey> {
+    find_ssh_keys_with(&SystemRunner, &system.home_dir.join(".ssh"))
+}
+
+fn find_ssh_keys_with(runner: &dyn GitRunner, ssh_dir: &Path) -> Vec<SshKey> {
+    let Ok(entries) = std::fs::read_dir(ssh_dir) else {
+        return Vec::new();
+    };
+    let mut keys: Vec<SshKey> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pub"))
+        .filter(|pub_path| pub_path.with_extension("").is_file())
+        .filter_map(|pub_path| read_ssh_key(runner, &pub_path))
+        .collect();
+    keys.sort_by_key(|key| std::cmp::Reverse(key.modified));
+    keys
+}
+
+/// Describe one key from `ssh-keygen -lf`, falling back to the `.pub`
+/// file itself for keys ssh-keygen can't read
+fn read_ssh_key(runner: &dyn GitRunner, pub_path: &Path) -> Option<SshKey> {
+    let public = std::fs::read_to_string(pub_path).ok()?;
+    let mut fields = public.split_whitespace();
+    let algorithm = fields.next()?;
+    let _blob = fields.next()?;
+    let mut key = SshKey {
+        path: pub_path.with_extension(""),
+        kind: key_kind(algorithm),
+        comment: fields.collect::<Vec<_>>().join(" "),
+        fingerprint: String::new(),
+        modified: std::fs::metadata(pub_path).and_then(|m| m.modified()).ok(),
+    };
+
+    // "256 SHA256:Ab1… ada@laptop (ED25519)"
+    let pub_arg = pub_path.to_string_lossy();
+    if let Ok(out) = runner.run("ssh-keygen", &["-lf", &pub_arg]) {
+        let words: Vec<&str> = out.stdout.split_whitespace().collect();
+        if out.success && words.len() >= 3 {
+            key.fingerprint = words[1].to_string();
+            key.kind = words[words.len() - 1]
+                .trim_matches(|c| c == '(' || c == ')')
+                .to_string();
+        }
+    }
+    Some(key)
+}
+
+/// "ssh-ed25519" → "ED25519", "ecdsa-sha2-nistp256" → "ECDSA"
+fn key_kind(algorithm: &str) -> String {
+    let name = algorithm.strip_prefix("ssh-").unwrap_or(algorithm);
+    let name = name.split('-').next().unwrap_or(name);
+    name.to_uppercase()
+}
+
+fn format_age(age: Duration) -> String {
+    let days = age.as_secs() / 86_400;
+    match days {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..=59 => format!("{} days ago", days),
+        60..=729 => format!("{} months ago", days / 30),
+        _ => format!("{} years ago", days / 365),
+    }
+}
+
+// ─── ssh-agent ───────────────────────────────────────────────────────
+
+/// systemd user unit running one ssh-agent per login, on the socket the
+/// shell hook looks for. `{agent}` is the ssh-agent binary.
+const AGENT_UNIT: &str = r#"[Unit]
+Description=SSH key agent (added by LOAD"*",8,1)
+
+[Service]
+Type=simple
+Environment=SSH_AUTH_SOCK=%t/ssh-agent.socket
+ExecStart={agent} -D -a $SSH_AUTH_SOCK
+
+[Install]
+WantedBy=default.target
+"#;
+
+fn add_key_to_agent(
+    runner: &dyn GitRunner,
+    key_path: &Path,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let _ = tx.send(InstallMessage::Log(
+        "[SSH] Adding key to ssh-agent...".to_string(),
+    ));
+
+    // Start ssh-agent if not running
+    let _ = runner.run("ssh-agent", &["-s"]);
+
+    // On macOS, use --apple-use-keychain to store in Keychain
+    let key_arg = key_path.to_string_lossy();
+    let result = if cfg!(target_os = "macos") {
+        // Also write SSH config to use Keychain
+        write_ssh_config(runner, key_path, true, tx);
+
+        runner.run("ssh-add", &["--apple-use-keychain", &key_arg])
+    } else {
+        // AddKeysToAgent loads the key into the login's agent on first
+        // use, so a new terminal never needs a manual ssh-add
+        write_ssh_config(runner, key_path, false, tx);
+        let socket = if system.os == Os::Linux {
+            start_agent_service(runner, system, tx)
+        } else {
+            None
+        };
+        match socket {
+            // This process predates the agent, so point ssh-add at it
+            Some(socket) if std::env::var_os("SSH_AUTH_SOCK").is_none() => {
+                let env = format!("SSH_AUTH_SOCK={}", socket.display());
+                runner.run("env", &[&env, "ssh-add", &key_arg])
+            }
+            _ => runner.run("ssh-add", &[&key_arg]),
+        }
+    };
+
+    match result {
+        Ok(out) if out.success => {
+            let _ = tx.send(InstallMessage::Log("  Key added to ssh-agent".to_string()));
+        }
+        Ok(out) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "ssh-add: {}",
+                out.stderr.trim()
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not run ssh-add: {}",
+                e
+            )));
+        }
+    }
+}
+
+/// Run ssh-agent as a systemd user service so one agent lasts the whole
+/// login. Returns its socket, or `None` without a systemd user manager, in
+/// which case the shell hook starts an agent itself.
+fn start_agent_service(
+    runner: &dyn GitRunner,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Option<PathBuf> {
+    let environment = match runner.run("systemctl", &["--user", "show-environment"]) {
+        Ok(out) if out.success => out.stdout,
+        _ => {
+            let _ = tx.send(InstallMessage::Log(
+                "  No systemd user session; new shells start ssh-agent themselves".to_string(),
+            ));
+            return None;
+        }
+    };
+    let runtime_dir = environment
+        .lines()
+        .find_map(|line| line.strip_prefix("XDG_RUNTIME_DIR="))?;
+
+    let unit = system
+        .config_dir
+        .join("systemd")
+        .join("user")
+        .join("ssh-agent.service");
+    if !unit.exists() {
+        let agent = system
+            .find_tool("ssh-agent")
+            .unwrap_or_else(|| PathBuf::from("/usr/bin/ssh-agent"));
+        let content = AGENT_UNIT.replace("{agent}", &agent.to_string_lossy());
+        if runner.dry_run() {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[DRY] write {}",
+                unit.display()
+            )));
+        } else {
+            ensure_parent(&unit);
+            if let Err(e) = std::fs::write(&unit, content) {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "Could not write {}: {}",
+                    unit.display(),
+                    e
+                )));
+                return None;
+            }
+            let _ = tx.send(InstallMessage::Log(format!("  Wrote {}", unit.display())));
+        }
+    }
+
+    let _ = runner.run("systemctl", &["--user", "daemon-reload"]);
+    match runner.run(
+        "systemctl",
+        &["--user", "enable", "--now", "ssh-agent.service"],
+    ) {
+        Ok(out) if out.success => {
+            let _ = tx.send(InstallMessage::Log(
+                "  ssh-agent now starts with every login (systemd user service)".to_string(),
+            ));
+            Some(Path::new(runtime_dir).join("ssh-agent.socket"))
+        }
+        Ok(out) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not start the ssh-agent service: {}",
+                out.stderr.trim()
+            )));
+            None
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not run systemctl: {}",
+                e
+            )));
+            None
+        }
+    }
+}
+
+fn ensure_parent(path: &Path) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+}
+
+/// Put loadstar's block in ~/.ssh/config so GitHub gets this key, with
+/// macOS Keychain integration when `keychain` is set (other ssh builds
+/// reject UseKeychain). Re-runs replace the block instead of adding another.
+fn write_ssh_config(
+    runner: &dyn GitRunner,
+    key_path: &Path,
+    keychain: bool,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let ssh_config_path = key_path
+        .parent()
+        .map(|p| p.join("config"))
+        .unwrap_or_else(|| PathBuf::from("~/.ssh/config"));
+    if runner.dry_run() {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] add loadstar's block to {}",
+            ssh_config_path.display()
+        )));
+        return;
+    }
+
+    let existing = std::fs::read_to_string(&ssh_config_path).unwrap_or_default();
+    let updated = rcblock::with_block(
+        &strip_legacy_block(&existing),
+        &ssh_config_lines(key_path, keychain),
+    );
+    if updated == existing {
+        let _ = tx.send(InstallMessage::Log(
+            "  ~/.ssh/config already up to date".to_string(),
+        ));
+        return;
+    }
+
+    match std::fs::write(&ssh_config_path, updated) {
+        Ok(()) => {
+            // ssh refuses a config others can write; keep it private (600)
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = std::fs::set_permissions(
+                    &ssh_config_path,
+                    std::fs::Permissions::from_mode(0o600),
+                );
+            }
+
+            let _ = tx.send(InstallMessage::Log(if keychain {
+                "  Wrote ~/.ssh/config (Keychain integration)".to_string()
+            } else {
+                format!(
+                    "  Wrote ~/.ssh/config (AddKeysToAgent, github.com uses {})",
+                    key_path.display()
+                )
+            }));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not write ~/.ssh/config: {}",
+                e
+            )));
+        }
+    }
+}
+
+/// What goes between the markers in ~/.ssh/config
+fn ssh_config_lines(key_path: &Path, keychain: bool) -> Vec<String> {
+    let mut lines = vec![
+        "Host github.com".to_string(),
+        "    AddKeysToAgent yes".to_string(),
+    ];
+    if keychain {
+        lines.push("    UseKeychain yes".to_string());
+    }
+    lines.push(format!("    IdentityFile {}", key_path.display()));
+    if keychain {
+        lines.extend(
+            [
+                "",
+                "Host *",
+                "    AddKeysToAgent yes",
+                "    UseKeychain yes",
+            ]
+            .map(String::from),
+        );
+    }
+    lines
+}
+
+/// Drop the unmarked block older versions appended: the header comment
+/// and the `Host github.com` and `Host *` stanzas under it
+fn strip_legacy_block(content: &str) -> String {
+    if !content.lines().any(|l| l.trim() == LEGACY_HEADER) {
+        return content.to_string();
+    }
+    let mut kept: Vec<&str> = Vec::new();
+    let mut in_legacy = false;
+    for line in content.lines() {
+        if line.trim() == LEGACY_HEADER {
+            in_legacy = true;
+            continue;
+        }
+        let stanza = line.trim().is_empty()
+            || line.starts_with(char::is_whitespace)
+            || line == "Host github.com"
+            || line == "Host *";
+        if in_legacy && stanza {
+            continue;
+        }
+        in_legacy = false;
+        kept.push(line);
+    }
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+    kept.iter().map(|l| format!("{}\n", l)).collect()
+}
+
+const LEGACY_HEADER: &str = "# Added by LOAD\"*\",8,1";
+
+// ─── SSH connectivity check ──────────────────────────────────────────
+
+/// What GitHub said to `ssh -T git@github.com`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SshCheck {
+    /// The greeting, with the account the key belongs to
+    Authenticated(String),
+    /// GitHub was reached but doesn't know the key
+    Denied,
+    /// github.com's host key didn't match the one in known_hosts
+    HostKeyMismatch,
+    /// No answer from GitHub; the first line ssh printed
+    Unreachable(String),
+}
+
+impl SshCheck {
+    /// Read ssh's output. It exits 1 even when the key works, since GitHub
+    /// gives no shell, so the greeting is the only sign of success.
+    pub fn parse(output: &CommandOutput) -> SshCheck {
+        let text = format!("{}\n{}", output.stderr, output.stdout);
+        if let Some(rest) = text
+            .lines()
+            .filter(|line| line.contains("successfully authenticated"))
+            .find_map(|line| line.trim().strip_prefix("Hi "))
+        {
+            let user = rest.split('!').next().unwrap_or(rest);
+            SshCheck::Authenticated(user.to_string())
+        } else if text.contains("Permission denied") {
+            SshCheck::Denied
+        } else if text.contains("Host key verification failed")
+            || text.contains("REMOTE HOST IDENTIFICATION HAS CHANGED")
+        {
+            SshCheck::HostKeyMismatch
+        } else {
+            let first = text.lines().map(str::trim).find(|l| !l.is_empty());
+            SshCheck::Unreachable(first.unwrap_or("no answer from github.com").to_string())
+        }
+    }
+}
+
+/// Try the key against GitHub without prompting for anything, and say
+/// plainly whether `git push` over SSH will work
+fn check_github_ssh(runner: &dyn GitRunner, key_path: &Path, tx: &mpsc::Sender<InstallMessage>) {
+    let _ = tx.send(InstallMessage::Log(
+        "[SSH] Testing SSH access to GitHub...".to_string(),
+    ));
+
+    let key_arg = key_path.to_string_lossy();
+    let output = runner.run(
+        "ssh",
+        &[
+            "-T",
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ConnectTimeout=10",
+            "-o",
+            "StrictHostKeyChecking=accept-new",
+            "-i",
+            &key_arg,
+            "git@github.com",
+        ],
+    );
+    let check = match output {
+        // Nothing was asked, so there's no answer to read
+        Ok(_) if runner.dry_run() => return,
+        Ok(out) => SshCheck::parse(&out),
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!("Could not run ssh: {}", e)));
+            return;
+        }
+    };
+
+    let pub_key = key_path.with_extension("pub");
+    let (warning, command, note) = match check {
+        SshCheck::Authenticated(user) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[SSH] GitHub accepts your key — authenticated as {}",
+                user
+            )));
+            return;
+        }
+        SshCheck::Denied => (
+            "GitHub doesn't know this SSH key yet".to_string(),
+            format!("gh ssh-key add {}", pub_key.display()),
+            "or paste the .pub file at github.com/settings/keys, then `ssh -T git@github.com`",
+        ),
+        SshCheck::HostKeyMismatch => (
+            "github.com's host key doesn't match ~/.ssh/known_hosts".to_string(),
+            "ssh-keygen -R github.com".to_string(),
+            "drop the stale host key after checking GitHub's published fingerprints",
+        ),
+        SshCheck::Unreachable(reason) => (
+            format!("Couldn't reach GitHub over SSH: {}", reason),
+            "ssh -T git@github.com".to_string(),
+            "test SSH access once the network allows port 22",
+        ),
+    };
+    let _ = tx.send(InstallMessage::Warning(warning));
+    let _ = tx.send(InstallMessage::NextStep {
+        command,
+        note: note.to_string(),
+    });
+}
+
+// ─── GitHub CLI ──────────────────────────────────────────────────────
+
+/// Variables gh reads a token from, in the order it prefers them
+pub const TOKEN_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// A GitHub token from the environment, for unattended setups, with the
+/// variable it came from
+pub fn token_from_env() -> Option<(&'static str, Secret)> {
+    TOKEN_VARS.iter().find_map(|var| {
+        let value = std::env::var(var).ok()?;
+        let value = value.trim();
+        (!value.is_empty()).then(|| (*var, Secret::new(value)))
+    })
+}
+
+/// The token gh logs in with, and where it came from: the environment
+/// first, when the run may use it, then the profile's secrets
+fn token_for(wizard: &WizardState) -> Option<(String, Secret)> {
+    token_from_env()
+        .filter(|_| wizard.use_env_token)
+        .map(|(var, token)| (format!("${}", var), token))
+        .or_else(|| {
+            (!wizard.github_token.is_empty())
+                .then(|| ("the profile".to_string(), wizard.github_token.clone()))
+        })
+}
+
+/// Store a token from the environment or the profile as gh's own login,
+/// so gh stays logged in once it's gone. gh refuses `--with-token` while
+/// either variable is set, so both are cleared for the call.
+fn login_with_token(
+    runner: &dyn GitRunner,
+    source: &str,
+    token: &Secret,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let without_env = ["-u", TOKEN_VARS[0], "-u", TOKEN_VARS[1], "gh", "auth"];
+    let stored = runner
+        .run("env", &[&without_env[..], &["status"]].concat())
+        .map(|o| o.success)
+        .unwrap_or(false);
+    if stored {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::Log(format!(
+        "[GH] Logging in with the token from {}...",
+        source
+    )));
+    let args = [&without_env[..], &["login", "--with-token"]].concat();
+    match runner.run_with_input("env", &args, token.expose()) {
+        Ok(out) if out.success => {
+            let _ = tx.send(InstallMessage::Log(
+                "  Logged in; gh keeps the token after this run".to_string(),
+            ));
+        }
+        Ok(out) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "gh auth login --with-token: {}",
+                out.stderr.trim()
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not run gh auth login: {}",
+                e
+            )));
+        }
+    }
+}
+
+fn setup_gh_cli(
+    runner: &dyn GitRunner,
+    ssh_key_path: Option<&Path>,
+    token: Option<(&str, &Secret)>,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    if !is_available(runner, "gh") {
+        let _ = tx.send(InstallMessage::Log(
+            "[GH] GitHub CLI not found — skipping auth setup".to_string(),
+        ));
+        return;
+    }
+
+    if let Some((source, token)) = token {
+        login_with_token(runner, source, token, tx);
+    }
+
+    // Check if already authenticated
+    let auth_status = runner
+        .run("gh", &["auth", "status"])
+        .map(|o| o.success)
+        .unwrap_or(false);
+
+    if auth_status {
+        let _ = tx.send(InstallMessage::Log(
+            "[GH] Already authenticated with GitHub CLI".to_string(),
+        ));
+    } else {
+        // We can't run interactive auth in a background thread,
+        // so we log instructions for the user to run after install
+        let _ = tx.send(InstallMessage::Warning(
+            "GitHub CLI isn't logged in; log in after install to upload your SSH key".to_string(),
+        ));
+        let _ = tx.send(InstallMessage::NextStep {
+            command: "gh auth login --protocol ssh --web".to_string(),
+            note: "authenticate GitHub CLI".to_string(),
+        });
+    }
+
+    // Upload SSH key if we generated one and gh is authenticated
+    if auth_status {
+        if let Some(key_path) = ssh_key_path {
+            upload_ssh_key(runner, key_path, tx);
+        }
+
+        // Set gh as credential helper
+        let _ = runner.run("gh", &["auth", "setup-git"]);
+        let _ = tx.send(InstallMessage::Log(
+            "  Set gh as git credential helper".to_string(),
+        ));
+    }
+}
+
+fn upload_ssh_key(runner: &dyn GitRunner, key_path: &Path, tx: &mpsc::Sender<InstallMessage>) {
+    let pub_path = key_path.with_extension("pub");
+    // A dry run's new key was never made
+    if !pub_path.exists() && !runner.dry_run() {
+        return;
+    }
+
+    // Check if key is already on GitHub
+    let existing = runner
+        .run("gh", &["ssh-key", "list"])
+        .map(|o| o.stdout)
+        .unwrap_or_default();
+
+    // Read the public key to check if it's already uploaded
+    let pub_key = std::fs::read_to_string(&pub_path).unwrap_or_default();
+    let key_fingerprint = pub_key.split_whitespace().nth(1).unwrap_or("");
+
+    if !key_fingerprint.is_empty() && existing.contains(key_fingerprint) {
+        let _ = tx.send(InstallMessage::Log(
+            "  SSH key already on GitHub — skipping upload".to_string(),
+        ));
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::Log(
+        "[GH] Uploading SSH key to GitHub...".to_string(),
+    ));
+
+    let hostname = whoami::fallible::hostname().unwrap_or_else(|_| "c64".to_string());
+    let title = format!("LOAD*,8,1 ({})", hostname);
+
+    let pub_arg = pub_path.to_string_lossy();
+    let output = runner.run("gh", &["ssh-key", "add", &pub_arg, "--title", &title]);
+
+    match output {
+        Ok(out) if out.success => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  SSH key uploaded as '{}'",
+                title
+            )));
+        }
+        Ok(out) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Failed to upload SSH key: {}",
+                out.stderr.trim()
+            )));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not run gh ssh-key add: {}",
+                e
+            )));
+        }
+    }
+}
+
+// ─── GPG signing ─────────────────────────────────────────────────────
+
+fn setup_gpg_signing(
+    runner: &dyn GitRunner,
+    wizard: &WizardState,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    if !is_available(runner, "gpg") {
+        let _ = tx.send(InstallMessage::Log(
+            "[GPG] GnuPG not found — skipping signing setup".to_string(),
+        ));
+        let _ = tx.send(InstallMessage::Log(
+            "  Install gnupg and re-run, or set up manually".to_string(),
+        ));
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::Log(
+        "[GPG] Checking for existing GPG keys...".to_string(),
+    ));
+
+    // Check for existing keys matching the email
+    let existing = runner
+        .run(
+            "gpg",
+            &[
+                "--list-secret-keys",
+                "--keyid-format=long",
+                &wizard.identity.email,
+            ],
+        )
+        .map(|o| o.stdout)
+        .unwrap_or_default();
+
+    if !existing.trim().is_empty() {
+        let _ = tx.send(InstallMessage::Log(
+            "  Existing GPG key found — configuring git to use it".to_string(),
+        ));
+
+        // Extract key ID from output
+        if let Some(key_id) = extract_gpg_key_id(&existing) {
+            let _ = run_git_config(runner, "user.signingkey", &key_id, tx);
+            let _ = run_git_config(runner, "commit.gpgsign", "true", tx);
+            let _ = run_git_config(runner, "tag.gpgsign", "true", tx);
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Git configured to sign with key {}",
+                key_id
+            )));
+        }
+    } else {
+        // GPG key generation is interactive and slow — give instructions
+        let _ = tx.send(InstallMessage::Log(
+            "[GPG] No GPG key found for this email".to_string(),
+        ));
+        let _ = tx.send(InstallMessage::Log(
+            "  Run after install: gpg --full-generate-key".to_string(),
+        ));
+        let _ = tx.send(InstallMessage::Log(
+            "  Then: git config --global user.signingkey <KEY_ID>".to_string(),
+        ));
+        let _ = tx.send(InstallMessage::Log(
+            "  Then: git config --global commit.gpgsign true".to_string(),
+        ));
+    }
+}
+
+/// Extract the key ID from gpg --list-secret-keys output
+fn extract_gpg_key_id(output: &str) -> Option<String> {
+    // GPG output format:
+    //   sec   ed25519/ABCDEF1234567890 2024-01-01 [SC]
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("sec") {
+            // Extract the part after the /
+            if let Some(slash_pos) = trimmed.find('/') {
+                let after_slash = &trimmed[slash_pos + 1..];
+                // Key ID is the next space-delimited token
+                if let Some(key_id) = after_slash.split_whitespace().next() {
+                    return Some(key_id.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakerunner::{fail, ok, FakeRunner};
+    use crate::secret::Secret;
+
+    /// Log lines and next-step commands, in order
+    fn output(rx: mpsc::Receiver<InstallMessage>) -> Vec<String> {
+        rx.try_iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) | InstallMessage::Warning(line) => Some(line),
+                InstallMessage::NextStep { command, .. } => Some(command),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("loadstar-github-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn dry_runner_only_logs_what_would_change() {
+        assert!(DryRunner::only_looks(
+            "git",
+            &["config", "--global", "--get", "user.name"]
+        ));
+        assert!(DryRunner::only_looks("gh", &["auth", "status"]));
+        assert!(!DryRunner::only_looks(
+            "git",
+            &["config", "--global", "user.name", "Ada"]
+        ));
+        assert!(!DryRunner::only_looks(
+            "gh",
+            &["auth", "login", "--with-token"]
+        ));
+
+        let (tx, rx) = mpsc::channel();
+        let runner = DryRunner::new(&tx);
+        let keygen = ["-t", "ed25519", "-N", "", "-f", "/tmp/k"];
+        assert!(runner.run("ssh-keygen", &keygen).unwrap().success);
+        let login = ["auth", "login", "--with-token"];
+        assert!(
+            runner
+                .run_with_input("gh", &login, "ghp_x")
+                .unwrap()
+                .success
+        );
+        assert!(run_git_config(&runner, "loadstar.dry-test", "on", &tx).is_ok());
+
+        let dir = scratch_dir("dry");
+        write_ssh_config(&runner, &dir.join(".ssh").join("id_ed25519"), false, &tx);
+        assert!(!dir.join(".ssh").exists());
+
+        let lines = output(rx);
+        assert_eq!(lines[0], "[DRY] ssh-keygen -t ed25519 -N '' -f /tmp/k");
+        assert_eq!(lines[1], "[DRY] gh auth login --with-token");
+        assert_eq!(lines[2], "[DRY] git config --global loadstar.dry-test on");
+        assert!(lines[3].starts_with("[DRY] add loadstar's block to "));
+        assert!(!lines.concat().contains("ghp_x"));
+    }
+
+    #[test]
+    fn failed_git_config_is_a_warning_not_an_abort() {
+        let runner = FakeRunner::default().answer(
+            "git config --global user.name",
+            fail("could not lock config file"),
+        );
+        let (tx, rx) = mpsc::channel();
+        let mut wizard = WizardState::new();
+        wizard.identity.name = "Ada".to_string();
+        configure_git_identity(&runner, &wizard, &tx);
+
+        let lines = output(rx);
+        assert!(
+            lines.contains(&"git config user.name failed: could not lock config file".to_string())
+        );
+        assert!(runner.ran("git config --global init.defaultBranch main"));
+        assert!(!runner.ran("git config --global url.git@github.com:.insteadOf"));
+
+        wizard.rewrite_github_https = true;
+        let (tx, _rx) = mpsc::channel();
+        configure_git_identity(&runner, &wizard, &tx);
+        assert!(runner.ran("git config --global url.git@github.com:.insteadOf https://github.com/"));
+    }
+
+    #[test]
+    fn git_config_writes_record_what_they_replaced() {
+        let runner = FakeRunner::default()
+            .answer("git config --global --get core.pager", ok("less\n"))
+            .answer("git config --global --get pull.rebase", ok("true\n"))
+            .answer("git config --global --get", fail(""));
+        let (tx, rx) = mpsc::channel();
+        run_git_config(&runner, "core.pager", "delta", &tx).unwrap();
+        run_git_config(&runner, "pull.rebase", "true", &tx).unwrap();
+        run_git_config(&runner, "fetch.prune", "true", &tx).unwrap();
+        assert!(!runner.ran("git config --global pull.rebase"));
+
+        let messages: Vec<InstallMessage> = rx.try_iter().collect();
+        assert_eq!(
+            messages[0],
+            InstallMessage::GitConfigSet {
+                key: "core.pager".to_string(),
+                old: Some("less".to_string()),
+                new: "delta".to_string(),
+            }
+        );
+        assert_eq!(
+            describe_git_change("core.pager", Some("less"), "delta"),
+            "  changed core.pager from less → delta"
+        );
+        assert_eq!(
+            describe_git_change("fetch.prune", None, "true"),
+            "  set fetch.prune=true (new)"
+        );
+    }
+
+    #[test]
+    fn existing_ssh_key_is_kept_and_failed_keygen_gives_none() {
+        let mut system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+
+        system.home_dir = scratch_dir("keygen");
+        wizard.generate_ssh_key = false;
+        let runner = FakeRunner::default();
+        let (tx, _rx) = mpsc::channel();
+        assert_eq!(generate_ssh_key(&runner, &wizard, &system, &tx), None);
+        assert!(!runner.ran("ssh-keygen"));
+
+        wizard.generate_ssh_key = true;
+        let runner = FakeRunner::default().answer("ssh-keygen", fail("bad key type"));
+        let (tx, rx) = mpsc::channel();
+        assert_eq!(generate_ssh_key(&runner, &wizard, &system, &tx), None);
+        assert!(output(rx).contains(&"  [ERROR] ssh-keygen failed: bad key type".to_string()));
+
+        let key = system.home_dir.join(".ssh").join("id_ed25519");
+        std::fs::write(&key, "private").unwrap();
+        let runner = FakeRunner::default();
+        let (tx, _rx) = mpsc::channel();
+        assert_eq!(generate_ssh_key(&runner, &wizard, &system, &tx), Some(key));
+        assert!(!runner.ran("ssh-keygen"));
+        let _ = std::fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn picked_or_dedicated_keys_leave_existing_ones_alone() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir = scratch_dir("picked");
+        let ssh_dir = system.home_dir.join(".ssh");
+        std::fs::create_dir_all(&ssh_dir).unwrap();
+        std::fs::write(ssh_dir.join("id_ed25519"), "private").unwrap();
+        let work = ssh_dir.join("work");
+        std::fs::write(&work, "private").unwrap();
+
+        let mut wizard = WizardState::new();
+        wizard.ssh_key = SshKeyChoice::Existing(work.clone());
+        let runner = FakeRunner::default();
+        let (tx, _rx) = mpsc::channel();
+        assert_eq!(generate_ssh_key(&runner, &wizard, &system, &tx), Some(work));
+        assert!(!runner.ran("ssh-keygen"));
+
+        wizard.ssh_key = SshKeyChoice::Generate;
+        let runner = FakeRunner::default();
+        let (tx, _rx) = mpsc::channel();
+        let dedicated = ssh_dir.join("id_ed25519_github");
+        assert_eq!(
+            generate_ssh_key(&runner, &wizard, &system, &tx),
+            Some(dedicated.clone())
+        );
+        assert!(runner.calls()[0].contains(&dedicated.display().to_string()));
+
+        std::fs::write(dedicated.with_extension("pub"), "public").unwrap();
+        assert_eq!(new_key_path(&ssh_dir), ssh_dir.join("id_ed25519_github2"));
+        let _ = std::fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn existing_keys_are_described_newest_first() {
+        let dir = scratch_dir("keys");
+        for (name, public) in [
+            ("id_rsa", "ssh-rsa AAAAB3Nza old laptop\n"),
+            ("id_ed25519", "ssh-ed25519 AAAAC3Nza ada@work\n"),
+            ("orphan", "ssh-ed25519 AAAAC3Nza no private half\n"),
+        ] {
+            std::fs::write(dir.join(format!("{}.pub", name)), public).unwrap();
+            if name != "orphan" {
+                std::fs::write(dir.join(name), "private").unwrap();
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let runner = FakeRunner::default().answer(
+            "ssh-keygen -lf",
+            ok("256 SHA256:Ab1cD2 ada@work (ED25519)\n"),
+        );
+        let keys = find_ssh_keys_with(&runner, &dir);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].path, dir.join("id_ed25519"));
+        assert_eq!(keys[0].fingerprint, "SHA256:Ab1cD2");
+        assert_eq!(keys[1].comment, "old laptop");
+
+        let runner = FakeRunner::default().answer("ssh-keygen -lf", fail("not a key"));
+        let keys = find_ssh_keys_with(&runner, &dir);
+        assert_eq!(keys[1].kind, "RSA");
+        assert_eq!(keys[1].fingerprint, "");
+        assert_eq!(key_kind("ecdsa-sha2-nistp256"), "ECDSA");
+        assert_eq!(format_age(Duration::from_secs(86_400 * 90)), "3 months ago");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn agent_runs_as_a_user_service_when_systemd_is_there() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.config_dir = scratch_dir("agent");
+        let unit = system.config_dir.join("systemd/user/ssh-agent.service");
+
+        let runner =
+            FakeRunner::default().answer("systemctl --user show-environment", fail("no bus"));
+        let (tx, _rx) = mpsc::channel();
+        assert_eq!(start_agent_service(&runner, &system, &tx), None);
+        assert!(!unit.exists());
+
+        let runner = FakeRunner::default().answer(
+            "systemctl --user show-environment",
+            ok("HOME=/home/ada\nXDG_RUNTIME_DIR=/run/user/1000\n"),
+        );
+        let (tx, _rx) = mpsc::channel();
+        assert_eq!(
+            start_agent_service(&runner, &system, &tx),
+            Some(PathBuf::from("/run/user/1000/ssh-agent.socket"))
+        );
+        let content = std::fs::read_to_string(&unit).unwrap();
+        assert!(content.contains("ssh-agent -D -a $SSH_AUTH_SOCK"));
+        assert!(runner.ran("systemctl --user enable --now ssh-agent.service"));
+        let _ = std::fs::remove_dir_all(&system.config_dir);
+    }
+
+    #[test]
+    fn ssh_config_block_is_replaced_not_appended() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir = scratch_dir("sshconfig");
+        let ssh_dir = system.home_dir.join(".ssh");
+        std::fs::create_dir_all(&ssh_dir).unwrap();
+        let config = ssh_dir.join("config");
+        std::fs::write(
+            &config,
+            "Host box\n    User ada\n\n# Added by LOAD\"*\",8,1\nHost github.com\n    AddKeysToAgent yes\n    UseKeychain yes\n    IdentityFile /old\n\nHost *\n    AddKeysToAgent yes\n    UseKeychain yes\n",
+        )
+        .unwrap();
+
+        let key = ssh_dir.join("id_ed25519_github");
+        let (tx, _rx) = mpsc::channel();
+        write_ssh_config(&SystemRunner, &key, false, &tx);
+        write_ssh_config(&SystemRunner, &key, false, &tx);
+        let content = std::fs::read_to_string(&config).unwrap();
+        assert!(content.starts_with("Host box\n    User ada\n\n# >>> loadstar >>>\n"));
+        assert_eq!(content.matches("Host github.com").count(), 1);
+        assert!(!content.contains("UseKeychain"));
+        assert!(content.contains(&format!("IdentityFile {}", key.display())));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&config).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = std::fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn passphrase_reaches_ssh_keygen_but_not_the_log() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir = scratch_dir("passphrase");
+        system.config_dir = system.home_dir.join(".config");
+        let mut wizard = WizardState::new();
+        wizard.ssh_passphrase = Secret::new("correct horse");
+
+        let runner = FakeRunner::default();
+        let (tx, rx) = mpsc::channel();
+        assert!(generate_ssh_key(&runner, &wizard, &system, &tx).is_some());
+        // Through askpass, where `ps` doesn't show it
+        assert!(!runner.calls()[0].contains("correct horse"));
+        let env = runner.env();
+        assert!(env.contains(&"SSH_ASKPASS_REQUIRE=force".to_string()));
+        assert!(env.contains(&format!("{}=correct horse", PASSPHRASE_VAR)));
+        let script = system.config_dir.join("loadstar").join("askpass.sh");
+        assert!(!script.exists());
+
+        let lines = output(rx);
+        assert!(lines.iter().any(|l| l.starts_with("ssh-add ")));
+        assert!(!lines.iter().any(|l| l.contains("correct horse")));
+        let _ = std::fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn gh_missing_or_logged_out_skips_auth_steps() {
+        let runner = FakeRunner::default().answer("gh --version", Err("not found".to_string()));
+        let (tx, rx) = mpsc::channel();
+        setup_gh_cli(&runner, None, None, &tx);
+        assert!(output(rx)[0].contains("GitHub CLI not found"));
+        assert!(!runner.ran("gh auth"));
+
+        let runner = FakeRunner::default().answer("gh auth status", fail("not logged in"));
+        let (tx, rx) = mpsc::channel();
+        setup_gh_cli(&runner, None, None, &tx);
+        assert!(output(rx).contains(&"gh auth login --protocol ssh --web".to_string()));
+        assert!(!runner.ran("gh auth setup-git"));
+    }
+
+    #[test]
+    fn token_from_the_environment_becomes_a_stored_login() {
+        let token = Secret::new("ghp_abc123");
+        let runner = FakeRunner::default().answer(
+            "env -u GH_TOKEN -u GITHUB_TOKEN gh auth status",
+            fail("not logged in"),
+        );
+        let (tx, rx) = mpsc::channel();
+        setup_gh_cli(&runner, None, Some(("$GH_TOKEN", &token)), &tx);
+
+        assert!(runner.ran("env -u GH_TOKEN -u GITHUB_TOKEN gh auth login --with-token"));
+        assert_eq!(runner.stdin(), ["ghp_abc123"]);
+        assert!(runner.ran("gh auth setup-git"));
+        let lines = output(rx);
+        assert!(lines.contains(&"[GH] Logging in with the token from $GH_TOKEN...".to_string()));
+        assert!(!lines.iter().any(|l| l.contains("ghp_abc123")));
+
+        // A login gh already stored is left alone
+        let runner = FakeRunner::default();
+        let (tx, _rx) = mpsc::channel();
+        setup_gh_cli(&runner, None, Some(("$GH_TOKEN", &token)), &tx);
+        assert!(!runner.ran("env -u GH_TOKEN -u GITHUB_TOKEN gh auth login"));
+    }
+
+    #[test]
+    fn ssh_key_upload_is_skipped_when_github_has_it() {
+        let dir = scratch_dir("upload");
+        let key = dir.join("id_ed25519");
+        std::fs::write(
+            key.with_extension("pub"),
+            "ssh-ed25519 AAAAC3Nza test@example.com\n",
+        )
+        .unwrap();
+
+        let runner = FakeRunner::default().answer(
+            "gh ssh-key list",
+            ok("LOAD*,8,1 (c64)\tssh-ed25519 AAAAC3Nza\t2024-01-01\n"),
+        );
+        let (tx, rx) = mpsc::channel();
+        upload_ssh_key(&runner, &key, &tx);
+        assert!(output(rx)[0].contains("already on GitHub"));
+        assert!(!runner.ran("gh ssh-key add"));
+
+        let runner =
+            FakeRunner::default().answer("gh ssh-key add", fail("HTTP 422: key is already in use"));
+        let (tx, rx) = mpsc::channel();
+        upload_ssh_key(&runner, &key, &tx);
+        assert!(output(rx)
+            .contains(&"Failed to upload SSH key: HTTP 422: key is already in use".to_string()));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn ssh_greeting_is_read_whatever_the_exit_status() {
+        let greeting = fail(
+            "Hi octocat! You've successfully authenticated, but GitHub does not provide shell access.\n",
+        )
+        .unwrap();
+        assert_eq!(
+            SshCheck::parse(&greeting),
+            SshCheck::Authenticated("octocat".to_string())
+        );
+        let denied = fail("git@github.com: Permission denied (publickey).\n").unwrap();
+        assert_eq!(SshCheck::parse(&denied), SshCheck::Denied);
+        let offline = fail("ssh: Could not resolve hostname github.com\n").unwrap();
+        assert_eq!(
+            SshCheck::parse(&offline),
+            SshCheck::Unreachable("ssh: Could not resolve hostname github.com".to_string())
+        );
+    }
+
+    #[test]
+    fn failed_ssh_check_leaves_a_next_step() {
+        let key = Path::new("/home/ada/.ssh/id_ed25519");
+        let runner = FakeRunner::default().answer(
+            "ssh -T",
+            fail("Hi ada! You've successfully authenticated, but GitHub does not provide shell access."),
+        );
+        let (tx, rx) = mpsc::channel();
+        check_github_ssh(&runner, key, &tx);
+        assert!(runner.ran("ssh -T -o BatchMode=yes"));
+        assert_eq!(
+            output(rx)[1],
+            "[SSH] GitHub accepts your key — authenticated as ada"
+        );
+
+        let runner = FakeRunner::default().answer("ssh -T", fail("Permission denied (publickey)."));
+        let (tx, rx) = mpsc::channel();
+        check_github_ssh(&runner, key, &tx);
+        assert_eq!(
+            output(rx)[1..],
+            [
+                "GitHub doesn't know this SSH key yet",
+                "gh ssh-key add /home/ada/.ssh/id_ed25519.pub"
+            ]
+        );
+    }
+
+    #[test]
+    fn existing_gpg_key_is_used_for_signing() {
+        let runner = FakeRunner::default().answer(
+            "gpg --list-secret-keys",
+            ok("sec   ed25519/ABCDEF1234567890 2024-01-01 [SC]\n"),
+        );
+        let (tx, _rx) = mpsc::channel();
+        setup_gpg_signing(&runner, &WizardState::new(), &tx);
+        assert!(runner.ran("git config --global user.signingkey ABCDEF1234567890"));
+        assert!(runner.ran("git config --global commit.gpgsign true"));
+
+        let runner = FakeRunner::default().answer("gpg --version", Err("not found".to_string()));
+        let (tx, _rx) = mpsc::channel();
+        setup_gpg_signing(&runner, &WizardState::new(), &tx);
+        assert!(!runner.ran("gpg --list-secret-keys"));
+        assert!(!runner.ran("git"));
+    }
+
+    #[test]
+    fn extract_gpg_key_id_ed25519() {
+        let output = r#"sec   ed25519/ABCDEF1234567890 2024-01-01 [SC]
+      AABBCCDD11223344AABBCCDD11223344ABCDEF1234567890
+uid           [ultimate] Test User <test@example.com>
+ssb   cv25519/1234567890ABCDEF 2024-01-01 [E]"#;
+
+        let key_id = extract_gpg_key_id(output);
+        assert_eq!(key_id, Some("ABCDEF1234567890".to_string()));
+    }
+
+    #[test]
+    fn extract_gpg_key_id_rsa() {
+        let output = r#"sec   rsa4096/DEADBEEF12345678 2023-06-15 [SC] [expires: 2025-06-15]
+      AABBCCDDAABBCCDDAABBCCDDAABBCCDDDEADBEEF12345678
+uid           [ultimate] Another User <user@example.com>
+ssb   rsa4096/FEDCBA9876543210 2023-06-15 [E] [expires: 2025-06-15]"#;
+
+        let key_id = extract_gpg_key_id(output);
+        assert_eq!(key_id, Some("DEADBEEF12345678".to_string()));
+    }
+
+    #[test]
+    fn extract_gpg_key_id_empty_output() {
+        assert_eq!(extract_gpg_key_id(""), None);
+        assert_eq!(extract_gpg_key_id("no keys here"), None);
+    }
+}
@@ -0,0 +1,443 @@
+//! GitHub REST API client
+//! One way to ask api.github.com things: curl with the token on stdin,
+//! answers cached on disk and revalidated by ETag, and a spent rate limit
+//! waited out when the wait is short.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::github::{self, GitRunner};
+use crate::secret::Secret;
+use crate::system::SystemInfo;
+
+pub const API: &str = "https://api.github.com";
+
+/// Cached answers younger than this are used without asking again
+const FRESH_FOR: Duration = Duration::from_secs(10 * 60);
+
+/// Longest wait for a rate limit to reset before giving up on a request
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Tries after the first one, each after waiting out the limit
+const RETRIES: usize = 2;
+
+/// What `Accept` asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    /// A file's bytes instead of its JSON description, for contents
+    Raw,
+}
+
+impl Format {
+    fn accept(&self) -> &'static str {
+        match self {
+            Format::Json => "application/vnd.github+json",
+            Format::Raw => "application/vnd.github.raw",
+        }
+    }
+}
+
+/// Where answers are cached between runs
+pub fn cache_dir(system: &SystemInfo) -> PathBuf {
+    system
+        .home_dir
+        .join(".cache")
+        .join("loadstar")
+        .join("github-api")
+}
+
+/// A GitHub account, as `users/{login}` describes it
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct User {
+    pub login: String,
+    pub id: u64,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl User {
+    /// The address GitHub attributes commits by for users who keep their
+    /// email private
+    pub fn noreply_email(&self) -> String {
+        format!("{}+{}@users.noreply.github.com", self.id, self.login)
+    }
+}
+
+/// Whether `login` could be a GitHub username: letters, digits and single
+/// hyphens, not at either end, at most 39 characters
+pub fn valid_login(login: &str) -> bool {
+    !login.is_empty()
+        && login.len() <= 39
+        && !login.starts_with('-')
+        && !login.ends_with('-')
+        && !login.contains("--")
+        && login.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// A cached answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Cached {
+    etag: Option<String>,
+    body: String,
+    /// Seconds since the epoch
+    fetched: u64,
+}
+
+/// One HTTP response, as `curl -i` prints it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    /// Names lowercased
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Response {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The error GitHub gave, from the body's `message`
+    fn message(&self) -> String {
+        serde_json::from_str::<serde_json::Value>(&self.body)
+            .ok()
+            .and_then(|v| v["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| self.body.trim().chars().take(200).collect())
+    }
+
+    /// How long until the rate limit lets requests through again, if
+    /// this is a rate-limit refusal
+    fn rate_limit_wait(&self, now: u64) -> Option<Duration> {
+        if !matches!(self.status, 403 | 429) {
+            return None;
+        }
+        if let Some(secs) = self.header("retry-after").and_then(|v| v.parse().ok()) {
+            return Some(Duration::from_secs(secs));
+        }
+        if self.header("x-ratelimit-remaining") != Some("0") {
+            return None;
+        }
+        let reset: u64 = self.header("x-ratelimit-reset")?.parse().ok()?;
+        Some(Duration::from_secs(reset.saturating_sub(now) + 1))
+    }
+}
+
+/// Split `curl -i` output into status, headers and body. Interim blocks
+/// (`100 Continue`, a proxy's `Connection established`) are skipped.
+fn parse_response(raw: &str) -> Result<Response, String> {
+    let mut rest = raw;
+    loop {
+        let (head, body) = rest
+            .split_once("\r\n\r\n")
+            .or_else(|| rest.split_once("\n\n"))
+            .unwrap_or((rest, ""));
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .filter(|line| line.starts_with("HTTP/"))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| "curl printed something that isn't an HTTP response".to_string())?;
+        if body.starts_with("HTTP/") {
+            rest = body;
+            continue;
+        }
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+        return Ok(Response {
+            status,
+            headers,
+            body: body.to_string(),
+        });
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Asks api.github.com through curl
+pub struct Client<'a> {
+    runner: &'a dyn GitRunner,
+    token: Option<Secret>,
+    /// `None` fetches every time
+    cache: Option<PathBuf>,
+    sleep: fn(Duration),
+}
+
+impl<'a> Client<'a> {
+    pub fn new(runner: &'a dyn GitRunner, token: Option<Secret>, cache: Option<PathBuf>) -> Self {
+        Self {
+            runner,
+            token,
+            cache,
+            sleep: std::thread::sleep,
+        }
+    }
+
+    /// A client with the token from `$GH_TOKEN` or `$GITHUB_TOKEN`, if
+    /// either is set, caching under `cache_dir`
+    pub fn from_env(runner: &'a dyn GitRunner, system: &SystemInfo) -> Self {
+        let token = github::token_from_env().map(|(_, token)| token);
+        Self::new(runner, token, Some(cache_dir(system)))
+    }
+
+    /// The account called `login`; `None` if there's no such user
+    pub fn user(&self, login: &str) -> Result<Option<User>, String> {
+        if !valid_login(login) {
+            return Ok(None);
+        }
+        let Some(body) = self.get(&format!("users/{}", login), Format::Json)? else {
+            return Ok(None);
+        };
+        serde_json::from_str(&body)
+            .map(Some)
+            .map_err(|e| format!("GitHub's answer for {} didn't parse: {}", login, e))
+    }
+
+    /// `path` under the API root; `None` when GitHub says it isn't there.
+    /// A fresh cached answer is used as is, and a stale one when GitHub
+    /// can't be reached or the rate limit is spent.
+    pub fn get(&self, path: &str, format: Format) -> Result<Option<String>, String> {
+        let cache_file = self.cache_file(path, format);
+        let cached = cache_file.as_deref().and_then(read_cache);
+        if let Some(cached) = &cached {
+            if now().saturating_sub(cached.fetched) < FRESH_FOR.as_secs() {
+                return Ok(Some(cached.body.clone()));
+            }
+        }
+        let stale = || cached.as_ref().map(|c| Some(c.body.clone()));
+
+        let mut attempt = 0;
+        let response = loop {
+            let response = match self.request(path, format, cached.as_ref()) {
+                Ok(response) => response,
+                Err(e) => return stale().ok_or(e),
+            };
+            match response.rate_limit_wait(now()) {
+                Some(wait) if wait <= MAX_WAIT && attempt < RETRIES => {
+                    (self.sleep)(wait);
+                    attempt += 1;
+                }
+                Some(wait) => {
+                    return stale().ok_or_else(|| {
+                        let mut e = format!(
+                            "GitHub's API rate limit is spent for another {} minute(s)",
+                            wait.as_secs().div_ceil(60)
+                        );
+                        if self.token.is_none() {
+                            e.push_str("; set $GH_TOKEN to raise it");
+                        }
+                        e
+                    })
+                }
+                None => break response,
+            }
+        };
+
+        match response.status {
+            200..=299 => {
+                let fresh = Cached {
+                    etag: response.header("etag").map(str::to_string),
+                    body: response.body,
+                    fetched: now(),
+                };
+                if let Some(file) = &cache_file {
+                    write_cache(file, &fresh);
+                }
+                Ok(Some(fresh.body))
+            }
+            304 => {
+                let Some(mut cached) = cached else {
+                    return Err(format!(
+                        "GitHub answered 304 for {} with nothing cached",
+                        path
+                    ));
+                };
+                cached.fetched = now();
+                if let Some(file) = &cache_file {
+                    write_cache(file, &cached);
+                }
+                Ok(Some(cached.body))
+            }
+            404 => Ok(None),
+            status => Err(format!(
+                "GitHub answered {} for {}: {}",
+                status,
+                path,
+                response.message()
+            )),
+        }
+    }
+
+    fn request(
+        &self,
+        path: &str,
+        format: Format,
+        cached: Option<&Cached>,
+    ) -> Result<Response, String> {
+        let url = format!("{}/{}", API, path.trim_start_matches('/'));
+        // Headers go in on stdin, so the token is never where `ps` shows it
+        let mut headers = format!(
+            "Accept: {}\nX-GitHub-Api-Version: 2022-11-28\n",
+            format.accept()
+        );
+        if let Some(token) = &self.token {
+            headers.push_str(&format!("Authorization: Bearer {}\n", token.expose()));
+        }
+        if let Some(etag) = cached.and_then(|c| c.etag.as_deref()) {
+            headers.push_str(&format!("If-None-Match: {}\n", etag));
+        }
+        let args = ["-sS", "-i", "--max-time", "20", "-H", "@-", &url];
+        match self.runner.run_with_input("curl", &args, &headers) {
+            Ok(out) if out.success => parse_response(&out.stdout),
+            Ok(out) => Err(format!("Could not reach GitHub: {}", out.stderr.trim())),
+            Err(e) => Err(format!("Could not run curl: {}", e)),
+        }
+    }
+
+    /// Answers differ by token (private repos), so the file says whose
+    fn cache_file(&self, path: &str, format: Format) -> Option<PathBuf> {
+        let dir = self.cache.as_ref()?;
+        let who = if self.token.is_some() {
+            "token"
+        } else {
+            "anon"
+        };
+        let name: String = format!("{}-{:?}-{}", who, format, path)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Some(dir.join(format!("{}.json", name)))
+    }
+}
+
+fn read_cache(file: &Path) -> Option<Cached> {
+    serde_json::from_str(&fs::read_to_string(file).ok()?).ok()
+}
+
+/// Best effort: a cache that can't be written only means asking again
+fn write_cache(file: &Path, cached: &Cached) {
+    if let Some(dir) = file.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string(cached) {
+        let _ = fs::write(file, json);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakerunner::{ok, FakeRunner};
+
+    /// Plays back curl outputs in order, the way curl prints them with
+    /// `-i`. The headers go on stdin, never in the arguments.
+    fn plays(responses: &[&str]) -> FakeRunner {
+        responses
+            .iter()
+            .fold(FakeRunner::default(), |runner, response| {
+                runner.answer("curl", ok(&response.replace('\n', "\r\n")))
+            })
+    }
+
+    fn sent(curl: &FakeRunner) -> Vec<String> {
+        assert!(!curl.calls().iter().any(|c| c.contains("Bearer")));
+        curl.stdin()
+    }
+
+    const ADA: &str =
+        "HTTP/2 200\netag: \"v1\"\n\n{\"login\": \"ada\", \"id\": 42, \"name\": \"Ada\"}";
+
+    fn temp_cache(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("loadstar-api-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn users_are_looked_up_and_cached_by_etag() {
+        let cache = temp_cache("etag");
+        let curl = plays(&[
+            ADA,
+            "HTTP/2 304\n\n",
+            "HTTP/2 404\n\n{\"message\": \"Not Found\"}",
+        ]);
+        let client = Client::new(&curl, Some(Secret::new("ghp_x")), Some(cache.clone()));
+
+        let ada = client.user("ada").unwrap().unwrap();
+        assert_eq!(ada.noreply_email(), "42+ada@users.noreply.github.com");
+        // Fresh: no request at all
+        assert_eq!(client.user("ada").unwrap(), Some(ada.clone()));
+        assert_eq!(sent(&curl).len(), 1);
+        assert!(sent(&curl)[0].contains("Authorization: Bearer ghp_x\n"));
+
+        // Stale: revalidated, and a 304 keeps the cached answer
+        let file = client.cache_file("users/ada", Format::Json).unwrap();
+        let mut cached = read_cache(&file).unwrap();
+        cached.fetched = 0;
+        write_cache(&file, &cached);
+        assert_eq!(client.user("ada").unwrap(), Some(ada));
+        assert!(sent(&curl)[1].contains("If-None-Match: \"v1\"\n"));
+
+        assert_eq!(client.user("nobody-here").unwrap(), None);
+        assert_eq!(client.user("-bad").unwrap(), None);
+        assert_eq!(sent(&curl).len(), 3);
+        let _ = fs::remove_dir_all(&cache);
+    }
+
+    #[test]
+    fn a_spent_rate_limit_is_waited_out_when_the_wait_is_short() {
+        let limited = "HTTP/2 429\nretry-after: 0\n\n{\"message\": \"slow down\"}";
+        let curl = plays(&[limited, limited, ADA]);
+        let mut client = Client::new(&curl, None, None);
+        static WAITS: std::sync::Mutex<Vec<Duration>> = std::sync::Mutex::new(Vec::new());
+        client.sleep = |wait| WAITS.lock().unwrap().push(wait);
+
+        assert_eq!(client.user("ada").unwrap().unwrap().id, 42);
+        assert_eq!(WAITS.lock().unwrap().len(), 2);
+
+        // An hour to go: not waited for
+        let reset = format!(
+            "HTTP/2 403\nx-ratelimit-remaining: 0\nx-ratelimit-reset: {}\n\n{{}}",
+            now() + 3600
+        );
+        let curl = plays(&[&reset]);
+        let client = Client::new(&curl, None, None);
+        let err = client.user("ada").unwrap_err();
+        assert!(
+            err.starts_with("GitHub's API rate limit is spent"),
+            "{}",
+            err
+        );
+        assert!(err.contains("$GH_TOKEN"));
+    }
+
+    #[test]
+    fn interim_responses_are_skipped() {
+        let raw = "HTTP/1.1 200 Connection established\r\n\r\nHTTP/2 404\r\nX-GitHub-Request-Id: 1\r\n\r\n{\"message\": \"Not Found\"}";
+        let response = parse_response(raw).unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.header("x-github-request-id"), Some("1"));
+        assert_eq!(response.message(), "Not Found");
+        assert!(parse_response("<html>").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Post-install shell integration check
+//! Starts the chosen shell the way a new terminal would and asks it which
+//! tool hooks loaded, so a broken rc file shows up in the report rather
+//! than in the next terminal opened.
+
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::executor::{self, InstallMessage};
+use crate::hooks::{self, Probe, ShellHook};
+use crate::rcblock;
+use crate::system::SystemInfo;
+use crate::wizard::{ShellChoice, WizardState};
+
+/// Marks the probe's own output among whatever the rc file prints
+const MARKER: &str = "loadstar-hook";
+
+/// How long the probe shell gets when the run has no package timeout
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What the shell said about one hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Loaded,
+    /// The hook's guard skipped it: the tool isn't on the shell's PATH
+    NoTool,
+    NotLoaded,
+}
+
+/// Report which of the hooks written for the chosen shell load in it. The
+/// shell is stopped after `timeout`, the run's package timeout.
+pub fn verify_hooks(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    timeout: Option<Duration>,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let shell = wizard.shell_config.shell;
+    let hooks: Vec<&ShellHook> = hooks::active_hooks(wizard)
+        .into_iter()
+        .filter(|hook| hook.line(shell).is_some())
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Shell Integrations".to_string(),
+    });
+    // A dry run wrote no hooks to find
+    if wizard.dry_run {
+        let apps: Vec<&str> = hooks.iter().map(|hook| hook.app).collect();
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] start {} as a new terminal does and check {} load",
+            shell.name(),
+            apps.join(", ")
+        )));
+        return;
+    }
+
+    // Nushell's hooks save scripts for its autoload directory, which only
+    // the shell after the one that ran them picks up
+    let Some(script) = probe_script(shell, &hooks) else {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[HOOK] {} loads its hooks from the next shell on; not checked",
+            shell.name()
+        )));
+        return;
+    };
+
+    let program = match shell {
+        ShellChoice::Zsh => "zsh",
+        ShellChoice::Bash => "bash",
+        ShellChoice::Fish => "fish",
+        ShellChoice::Nushell => "nu",
+    };
+    let command = probe_command(program, &script, &hooks);
+    let output = match executor::output_within(command, timeout.unwrap_or(PROBE_TIMEOUT)) {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "{} didn't finish loading {}: {}",
+                shell.name(),
+                rc_display(shell, system),
+                e
+            )));
+            return;
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not start {} to check its shell integrations: {}",
+                shell.name(),
+                e
+            )));
+            return;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let results = parse(&stdout);
+    if results.is_empty() {
+        // The probe runs after the rc file, so nothing from it means the
+        // rc file never finished
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("no output");
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "{} didn't finish loading {}: {}",
+            shell.name(),
+            rc_display(shell, system),
+            reason.trim()
+        )));
+        return;
+    }
+
+    for hook in hooks {
+        let status = results
+            .iter()
+            .find(|(app, _)| *app == hook.app)
+            .map(|(_, status)| *status);
+        match status {
+            Some(Status::Loaded) => {
+                let _ = tx.send(InstallMessage::Log(format!(
+                    "[HOOK] {} loads in {}",
+                    hook.app,
+                    shell.name()
+                )));
+            }
+            Some(Status::NoTool) => {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "{} isn't on the {} PATH, so its shell integration is skipped",
+                    hook.app,
+                    shell.name()
+                )));
+            }
+            Some(Status::NotLoaded) | None => {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "{}'s shell integration didn't load in {}; check {}",
+                    hook.app,
+                    shell.name(),
+                    rc_display(shell, system)
+                )));
+            }
+        }
+    }
+}
+
+/// A login shell running `script`. The variables the hooks export are
+/// cleared first: this process may have them from the shell it was
+/// started in, and the probe would take them for the hooks loading.
+fn probe_command(program: &str, script: &str, hooks: &[&ShellHook]) -> Command {
+    let mut command = Command::new(program);
+    command.args(["-l", "-i", "-c", script]);
+    for hook in hooks {
+        if let Probe::Var(var) = hook.loaded {
+            command.env_remove(var);
+        }
+    }
+    command
+}
+
+/// One `MARKER app status` line per hook, or `None` for a shell that
+/// can't be asked
+fn probe_script(shell: ShellChoice, hooks: &[&ShellHook]) -> Option<String> {
+    let mut lines = Vec::new();
+    for hook in hooks {
+        let report = |status: &str| format!("echo {} {} {}", MARKER, hook.app, status);
+        let line = match shell {
+            ShellChoice::Zsh | ShellChoice::Bash => {
+                let loaded = match hook.loaded {
+                    Probe::Var(var) => format!("[ -n \"${{{}:-}}\" ]", var),
+                    Probe::Defined(names) => {
+                        let any: Vec<String> =
+                            names.iter().map(|name| format!("type {}", name)).collect();
+                        format!("{{ {}; }} >/dev/null 2>&1", any.join(" || "))
+                    }
+                };
+                format!(
+                    "if ! command -v {} >/dev/null 2>&1; then {}; elif {}; then {}; else {}; fi",
+                    hook.app,
+                    report("no-tool"),
+                    loaded,
+                    report("loaded"),
+                    report("not-loaded")
+                )
+            }
+            ShellChoice::Fish => {
+                let loaded = match hook.loaded {
+                    Probe::Var(var) => format!("set -q {}", var),
+                    Probe::Defined(names) => names
+                        .iter()
+                        .map(|name| format!("type -q {}", name))
+                        .collect::<Vec<_>>()
+                        .join("; or "),
+                };
+                format!(
+                    "if not type -q {}; {}; else if {}; {}; else; {}; end",
+                    hook.app,
+                    report("no-tool"),
+                    loaded,
+                    report("loaded"),
+                    report("not-loaded")
+                )
+            }
+            ShellChoice::Nushell => return None,
+        };
+        lines.push(line);
+    }
+    Some(lines.join("\n"))
+}
+
+/// The probe's lines out of everything the shell printed
+fn parse(stdout: &str) -> Vec<(&str, Status)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            if words.next()? != MARKER {
+                return None;
+            }
+            let app = words.next()?;
+            let status = match words.next()? {
+                "loaded" => Status::Loaded,
+                "no-tool" => Status::NoTool,
+                "not-loaded" => Status::NotLoaded,
+                _ => return None,
+            };
+            Some((app, status))
+        })
+        .collect()
+}
+
+fn rc_display(shell: ShellChoice, system: &SystemInfo) -> String {
+    let rc = rcblock::rc_file(shell, system);
+    rc.strip_prefix(&system.home_dir)
+        .map(|p| format!("~/{}", p.display()))
+        .unwrap_or_else(|_| rc.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_probe_reports_each_hook_past_any_banner() {
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["zoxide".to_string(), "direnv".to_string()]
+            .into_iter()
+            .collect();
+        let hooks: Vec<&ShellHook> = hooks::active_hooks(&wizard);
+
+        let zsh = probe_script(ShellChoice::Zsh, &hooks).unwrap();
+        assert!(zsh.contains("elif [ -n \"${STARSHIP_SHELL:-}\" ]; then"));
+        assert!(zsh.contains("{ type _direnv_hook || type __direnv_export_eval; }"));
+        let fish = probe_script(ShellChoice::Fish, &hooks).unwrap();
+        assert!(fish.contains("else if type -q z; echo loadstar-hook zoxide loaded;"));
+        assert_eq!(probe_script(ShellChoice::Nushell, &hooks), None);
+
+        let out = "Welcome back!\nloadstar-hook starship loaded\n\
+                   loadstar-hook zoxide not-loaded\nloadstar-hook direnv no-tool\n";
+        assert_eq!(
+            parse(out),
+            [
+                ("starship", Status::Loaded),
+                ("zoxide", Status::NotLoaded),
+                ("direnv", Status::NoTool),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn bash_runs_the_probe() {
+        if Command::new("bash").arg("--version").output().is_err() {
+            return;
+        }
+        let hook = ShellHook {
+            app: "sh",
+            zsh: "",
+            bash: Some(""),
+            fish: None,
+            nu: None,
+            loaded: Probe::Var("LOADSTAR_PROBE"),
+        };
+        let script = probe_script(ShellChoice::Bash, &[&hook]).unwrap();
+        let output = Command::new("bash")
+            .args(["-c", &script])
+            .env("LOADSTAR_PROBE", "1")
+            .output()
+            .unwrap();
+        assert_eq!(
+            parse(&String::from_utf8_lossy(&output.stdout)),
+            [("sh", Status::Loaded)]
+        );
+
+        // Set in this process, but not for the probe
+        let mut probe = probe_command("bash", &script, &[&hook]);
+        assert!(probe
+            .get_envs()
+            .any(|(var, value)| var == "LOADSTAR_PROBE" && value.is_none()));
+        probe.env("HOME", std::env::temp_dir());
+        let output = executor::output_within(probe, Duration::from_secs(10)).unwrap();
+        assert_eq!(
+            parse(&String::from_utf8_lossy(&output.stdout)),
+            [("sh", Status::NotLoaded)]
+        );
+
+        let mut hang = Command::new("bash");
+        hang.args(["-c", "sleep 5"]);
+        let err = executor::output_within(hang, Duration::from_millis(200)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    }
+}
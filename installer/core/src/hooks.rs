@@ -0,0 +1,404 @@
+//! Shell init hooks and aliases for shell-integrated tools
+//! One registry says how each tool hooks into each shell, so the generated
+//! .zshrc and the managed block in other shells' rc files stay in step.
+
+use crate::system::{Os, SystemInfo};
+use crate::wizard::{ShellChoice, WizardState};
+
+/// How one tool hooks into each shell. `None` means the tool has no
+/// integration for that shell.
+#[derive(Debug)]
+pub struct ShellHook {
+    pub app: &'static str,
+    /// Block for the generated .zshrc
+    pub zsh: &'static str,
+    /// Single lines for the managed block of other shells' rc files
+    pub bash: Option<&'static str>,
+    pub fish: Option<&'static str>,
+    pub nu: Option<&'static str>,
+    /// What a shell that ran the hook has and one that didn't lacks
+    pub loaded: Probe,
+}
+
+/// How to tell from inside a shell that a hook ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Probe {
+    /// An environment variable the init script exports
+    Var(&'static str),
+    /// A function or command it defines; shells differ in the names, so
+    /// any of them will do
+    Defined(&'static [&'static str]),
+}
+
+impl ShellHook {
+    pub fn line(&self, shell: ShellChoice) -> Option<&'static str> {
+        match shell {
+            ShellChoice::Zsh => Some(self.zsh),
+            ShellChoice::Bash => self.bash,
+            ShellChoice::Fish => self.fish,
+            ShellChoice::Nushell => self.nu,
+        }
+    }
+}
+
+/// Nushell sources everything in its vendor autoload directory, so hooks
+/// write their init scripts there
+const NU_AUTOLOAD_DIR: &str = r#"mkdir ($nu.data-dir | path join "vendor/autoload")"#;
+
+pub const STARSHIP: ShellHook = ShellHook {
+    app: "starship",
+    zsh: "command -v starship >/dev/null 2>&1 && eval \"$(starship init zsh)\"\n",
+    bash: Some("command -v starship >/dev/null 2>&1 && eval \"$(starship init bash)\""),
+    fish: Some("type -q starship; and starship init fish | source"),
+    nu: Some(
+        r#"starship init nu | save -f ($nu.data-dir | path join "vendor/autoload/starship.nu")"#,
+    ),
+    loaded: Probe::Var("STARSHIP_SHELL"),
+};
+
+/// One ssh-agent per login, started by the first shell that finds no
+/// live socket and reused by every shell after it. A socket that already
+/// works (a desktop keyring, a forwarded agent, the systemd unit) is left
+/// alone. The path is the one the systemd unit listens on.
+pub const SSH_AGENT: ShellHook = ShellHook {
+    app: "ssh-agent",
+    zsh: r#"if [[ ! -S "$SSH_AUTH_SOCK" ]]; then
+    export SSH_AUTH_SOCK="${XDG_RUNTIME_DIR:-$HOME/.ssh}/ssh-agent.socket"
+    ssh-add -l >/dev/null 2>&1
+    if [[ $? -eq 2 ]]; then
+        rm -f "$SSH_AUTH_SOCK"
+        ssh-agent -a "$SSH_AUTH_SOCK" >/dev/null
+    fi
+fi
+"#,
+    bash: Some(
+        r#"[ -S "$SSH_AUTH_SOCK" ] || { export SSH_AUTH_SOCK="${XDG_RUNTIME_DIR:-$HOME/.ssh}/ssh-agent.socket"; ssh-add -l >/dev/null 2>&1; [ $? -eq 2 ] && { rm -f "$SSH_AUTH_SOCK"; ssh-agent -a "$SSH_AUTH_SOCK" >/dev/null; }; }"#,
+    ),
+    fish: Some(
+        r#"test -S "$SSH_AUTH_SOCK"; or begin; set -q XDG_RUNTIME_DIR; or set -l XDG_RUNTIME_DIR $HOME/.ssh; set -gx SSH_AUTH_SOCK $XDG_RUNTIME_DIR/ssh-agent.socket; ssh-add -l >/dev/null 2>&1; test $status -eq 2; and rm -f $SSH_AUTH_SOCK; and ssh-agent -a $SSH_AUTH_SOCK >/dev/null; end"#,
+    ),
+    nu: None,
+    loaded: Probe::Var("SSH_AUTH_SOCK"),
+};
+
+/// Tool hooks, in the order they're written
+pub const HOOKS: &[ShellHook] = &[
+    ShellHook {
+        app: "zoxide",
+        zsh: "command -v zoxide >/dev/null 2>&1 && eval \"$(zoxide init zsh)\"\n",
+        bash: Some("command -v zoxide >/dev/null 2>&1 && eval \"$(zoxide init bash)\""),
+        fish: Some("type -q zoxide; and zoxide init fish | source"),
+        nu: Some(
+            r#"zoxide init nushell | save -f ($nu.data-dir | path join "vendor/autoload/zoxide.nu")"#,
+        ),
+        loaded: Probe::Defined(&["z"]),
+    },
+    ShellHook {
+        app: "fzf",
+        zsh: r#"if command -v fzf >/dev/null 2>&1; then
+    if command -v fd >/dev/null 2>&1; then
+        export FZF_DEFAULT_COMMAND='fd --type f --hidden --follow --exclude .git'
+        export FZF_CTRL_T_COMMAND="$FZF_DEFAULT_COMMAND"
+    fi
+    export FZF_DEFAULT_OPTS="--height 40% --layout=reverse --border"
+    source <(fzf --zsh) 2>/dev/null || true
+fi
+"#,
+        bash: Some("command -v fzf >/dev/null 2>&1 && eval \"$(fzf --bash)\""),
+        fish: Some("type -q fzf; and fzf --fish | source"),
+        nu: None,
+        loaded: Probe::Defined(&["fzf-file-widget"]),
+    },
+    ShellHook {
+        app: "mise",
+        zsh: "command -v mise >/dev/null 2>&1 && eval \"$(mise activate zsh)\"\n",
+        bash: Some("command -v mise >/dev/null 2>&1 && eval \"$(mise activate bash)\""),
+        fish: Some("type -q mise; and mise activate fish | source"),
+        nu: Some(
+            r#"mise activate nu | save -f ($nu.data-dir | path join "vendor/autoload/mise.nu")"#,
+        ),
+        loaded: Probe::Var("MISE_SHELL"),
+    },
+    ShellHook {
+        app: "direnv",
+        zsh: "command -v direnv >/dev/null 2>&1 && eval \"$(direnv hook zsh)\"\n",
+        bash: Some("command -v direnv >/dev/null 2>&1 && eval \"$(direnv hook bash)\""),
+        fish: Some("type -q direnv; and direnv hook fish | source"),
+        nu: None,
+        loaded: Probe::Defined(&["_direnv_hook", "__direnv_export_eval"]),
+    },
+    ShellHook {
+        app: "atuin",
+        zsh: "command -v atuin >/dev/null 2>&1 && eval \"$(atuin init zsh)\"\n",
+        bash: Some("command -v atuin >/dev/null 2>&1 && eval \"$(atuin init bash)\""),
+        fish: Some("type -q atuin; and atuin init fish | source"),
+        nu: Some(
+            r#"atuin init nu | save -f ($nu.data-dir | path join "vendor/autoload/atuin.nu")"#,
+        ),
+        loaded: Probe::Var("ATUIN_SESSION"),
+    },
+];
+
+/// Hooks for the tools the wizard will configure, starship included when
+/// it is the prompt
+pub fn active_hooks(wizard: &WizardState) -> Vec<&'static ShellHook> {
+    let mut hooks = Vec::new();
+    if wizard.uses_starship() {
+        hooks.push(&STARSHIP);
+    }
+    hooks.extend(HOOKS.iter().filter(|h| wizard.configures(h.app)));
+    hooks
+}
+
+/// Managed-block lines wiring the active hooks into a non-zsh shell
+pub fn rc_lines(wizard: &WizardState, shell: ShellChoice) -> Vec<String> {
+    let lines: Vec<String> = active_hooks(wizard)
+        .iter()
+        .filter_map(|h| h.line(shell))
+        .map(str::to_string)
+        .collect();
+
+    if shell == ShellChoice::Nushell && !lines.is_empty() {
+        std::iter::once(NU_AUTOLOAD_DIR.to_string())
+            .chain(lines)
+            .collect()
+    } else {
+        lines
+    }
+}
+
+/// Active tools with no hook for the shell
+pub fn unsupported(wizard: &WizardState, shell: ShellChoice) -> Vec<&'static str> {
+    active_hooks(wizard)
+        .iter()
+        .filter(|h| h.line(shell).is_none())
+        .map(|h| h.app)
+        .collect()
+}
+
+/// The ssh-agent hook, on Linux when setup leaves a key to load. macOS
+/// already runs an agent from launchd and keeps the passphrase in the
+/// Keychain.
+pub fn ssh_agent_hook(wizard: &WizardState, system: &SystemInfo) -> Option<&'static ShellHook> {
+    (system.os == Os::Linux && wizard.sets_up_ssh_key()).then_some(&SSH_AGENT)
+}
+
+// ─── Aliases ─────────────────────────────────────────────────────────
+
+/// A classic command swapped for its modern replacement
+#[derive(Debug)]
+pub struct ShellAlias {
+    pub app: &'static str,
+    pub name: &'static str,
+    pub command: &'static str,
+    /// Nushell's own `ls`, `du` and friends return structured data; leave
+    /// those alone there
+    pub nu: bool,
+}
+
+impl ShellAlias {
+    /// The alias as `shell` writes it; `None` where it's left alone
+    pub fn line(&self, shell: ShellChoice) -> Option<String> {
+        match shell {
+            ShellChoice::Zsh | ShellChoice::Bash => {
+                Some(format!("alias {}='{}'", self.name, self.command))
+            }
+            ShellChoice::Fish => Some(format!("alias {} '{}'", self.name, self.command)),
+            ShellChoice::Nushell => self
+                .nu
+                .then(|| format!("alias {} = {}", self.name, self.command)),
+        }
+    }
+}
+
+/// Aliases for the modern CLI replacements, grouped by tool
+pub const ALIASES: &[ShellAlias] = &[
+    ShellAlias {
+        app: "eza",
+        name: "ls",
+        command: "eza --icons --git",
+        nu: false,
+    },
+    ShellAlias {
+        app: "eza",
+        name: "ll",
+        command: "eza -l --icons --git --header",
+        nu: true,
+    },
+    ShellAlias {
+        app: "eza",
+        name: "la",
+        command: "eza -la --icons --git --header",
+        nu: true,
+    },
+    ShellAlias {
+        app: "eza",
+        name: "tree",
+        command: "eza --tree --icons",
+        nu: true,
+    },
+    ShellAlias {
+        app: "bat",
+        name: "cat",
+        command: "bat --style=auto",
+        nu: true,
+    },
+    ShellAlias {
+        app: "zoxide",
+        name: "cd",
+        command: "z",
+        nu: true,
+    },
+    ShellAlias {
+        app: "fd",
+        name: "find",
+        command: "fd",
+        nu: false,
+    },
+    ShellAlias {
+        app: "btop",
+        name: "top",
+        command: "btop",
+        nu: true,
+    },
+    ShellAlias {
+        app: "dust",
+        name: "du",
+        command: "dust",
+        nu: false,
+    },
+];
+
+/// Aliases for the tools the wizard will configure, or none when the
+/// aliases option is off
+pub fn wanted_aliases(wizard: &WizardState) -> Vec<&'static ShellAlias> {
+    if !wizard.shell_config.aliases {
+        return Vec::new();
+    }
+    ALIASES
+        .iter()
+        .filter(|a| wizard.configures(a.app))
+        .collect()
+}
+
+/// The wanted aliases, less those the user keeps their own of
+pub fn active_aliases(wizard: &WizardState) -> Vec<&'static ShellAlias> {
+    wanted_aliases(wizard)
+        .into_iter()
+        .filter(|a| !wizard.kept_aliases.contains(a.name))
+        .collect()
+}
+
+/// Alias block for the generated .zshrc, one `command -v` guard per tool
+pub fn zsh_aliases(wizard: &WizardState) -> String {
+    let mut block = String::new();
+    let mut current = None;
+    for alias in active_aliases(wizard) {
+        if current != Some(alias.app) {
+            if current.is_some() {
+                block.push_str("fi\n");
+            }
+            block.push_str(&format!(
+                "if command -v {} >/dev/null 2>&1; then\n",
+                alias.app
+            ));
+            current = Some(alias.app);
+        }
+        if let Some(line) = alias.line(ShellChoice::Zsh) {
+            block.push_str(&format!("    {}\n", line));
+        }
+    }
+    if current.is_some() {
+        block.push_str("fi\n");
+    }
+    block
+}
+
+/// Managed-block alias lines for a non-zsh shell
+pub fn alias_lines(wizard: &WizardState, shell: ShellChoice) -> Vec<String> {
+    active_aliases(wizard)
+        .into_iter()
+        .filter_map(|a| {
+            let line = a.line(shell)?;
+            match shell {
+                ShellChoice::Zsh => None,
+                ShellChoice::Bash => {
+                    Some(format!("command -v {} >/dev/null 2>&1 && {}", a.app, line))
+                }
+                ShellChoice::Fish => Some(format!("type -q {}; and {}", a.app, line)),
+                ShellChoice::Nushell => Some(line),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wizard_with(apps: &[&str]) -> WizardState {
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = apps.iter().map(|s| s.to_string()).collect();
+        wizard
+    }
+
+    #[test]
+    fn fish_gets_a_line_per_tool() {
+        let wizard = wizard_with(&["zoxide", "direnv"]);
+        let lines = rc_lines(&wizard, ShellChoice::Fish);
+        assert_eq!(
+            lines,
+            [
+                "type -q starship; and starship init fish | source",
+                "type -q zoxide; and zoxide init fish | source",
+                "type -q direnv; and direnv hook fish | source",
+            ]
+        );
+    }
+
+    #[test]
+    fn aliases_follow_the_option_and_shell() {
+        let mut wizard = wizard_with(&["eza", "zoxide"]);
+        let zsh = zsh_aliases(&wizard);
+        assert!(zsh
+            .contains("if command -v eza >/dev/null 2>&1; then\n    alias ls='eza --icons --git'"));
+        assert!(zsh.contains("    alias cd='z'\nfi\n"));
+
+        assert!(alias_lines(&wizard, ShellChoice::Fish)
+            .contains(&"type -q zoxide; and alias cd 'z'".to_string()));
+        let nu = alias_lines(&wizard, ShellChoice::Nushell);
+        assert!(nu.contains(&"alias cd = z".to_string()));
+        assert!(nu.iter().all(|l| !l.starts_with("alias ls ")));
+
+        wizard.shell_config.aliases = false;
+        assert!(zsh_aliases(&wizard).is_empty());
+        assert!(alias_lines(&wizard, ShellChoice::Bash).is_empty());
+    }
+
+    #[test]
+    fn ssh_agent_hook_is_for_linux_keys_only() {
+        let mut system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        system.os = Os::Linux;
+        assert!(ssh_agent_hook(&wizard, &system).is_some());
+        assert!(SSH_AGENT
+            .line(ShellChoice::Bash)
+            .unwrap()
+            .starts_with("[ -S \"$SSH_AUTH_SOCK\" ] ||"));
+
+        wizard.generate_ssh_key = false;
+        assert!(ssh_agent_hook(&wizard, &system).is_none());
+        wizard.generate_ssh_key = true;
+        system.os = Os::MacOS;
+        assert!(ssh_agent_hook(&wizard, &system).is_none());
+    }
+
+    #[test]
+    fn nushell_creates_autoload_dir_and_skips_unsupported() {
+        let wizard = wizard_with(&["zoxide", "fzf"]);
+        let lines = rc_lines(&wizard, ShellChoice::Nushell);
+        assert_eq!(lines[0], NU_AUTOLOAD_DIR);
+        assert!(lines.iter().all(|l| !l.contains("fzf")));
+        assert_eq!(unsupported(&wizard, ShellChoice::Nushell), ["fzf"]);
+    }
+}
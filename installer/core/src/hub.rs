@@ -0,0 +1,320 @@
+//! Returning-user hub
+//! A machine loadstar has set up before gets a menu after boot instead of
+//! going straight into the wizard: start over, sync, audit or upgrade.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::catalog::{App, InstallMethod};
+use crate::executor::{BrewInventory, BREW_ENV};
+use crate::github::GitRunner;
+use crate::profile::UpgradeSection;
+use crate::system::SystemInfo;
+
+/// Where loadstar keeps its profile, run logs and reports
+pub fn state_dir(system: &SystemInfo) -> PathBuf {
+    system.config_dir.join("loadstar")
+}
+
+/// Left in the state dir once an install has finished
+fn installed_marker(system: &SystemInfo) -> PathBuf {
+    state_dir(system).join("installed")
+}
+
+/// Whether an install has finished here before. Plenty else writes to the
+/// state dir (a fetched profile, a crash report), so it takes the marker.
+pub fn is_returning(system: &SystemInfo) -> bool {
+    installed_marker(system).is_file()
+}
+
+/// Note a finished install, for `is_returning`
+pub fn mark_installed(system: &SystemInfo) -> io::Result<()> {
+    fs::create_dir_all(state_dir(system))?;
+    fs::write(
+        installed_marker(system),
+        format!("{}\n", env!("CARGO_PKG_VERSION")),
+    )
+}
+
+// ─── Hub actions ─────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HubAction {
+    /// Only offered when an install left a checkpoint
+    Resume,
+    NewSetup,
+    Sync,
+    Audit,
+    Upgrade,
+}
+
+impl HubAction {
+    /// The hub's entries, led by Resume when there's an install to resume
+    pub fn menu(resumable: bool) -> Vec<HubAction> {
+        let mut menu = vec![
+            HubAction::NewSetup,
+            HubAction::Sync,
+            HubAction::Audit,
+            HubAction::Upgrade,
+        ];
+        if resumable {
+            menu.insert(0, HubAction::Resume);
+        }
+        menu
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HubAction::Resume => "Resume previous install",
+            HubAction::NewSetup => "New setup",
+            HubAction::Sync => "Sync with saved profile",
+            HubAction::Audit => "Audit machine",
+            HubAction::Upgrade => "Upgrade tools",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            HubAction::Resume => {
+                "Pick up the install that stopped, skipping the packages it finished"
+            }
+            HubAction::NewSetup => "Run the whole wizard again, configs and all",
+            HubAction::Sync => "Install the profile's apps that this machine is missing",
+            HubAction::Audit => {
+                "See which of the profile's apps are here, without changing anything"
+            }
+            HubAction::Upgrade => {
+                "See which catalog tools Homebrew has newer versions of, then upgrade them"
+            }
+        }
+    }
+}
+
+// ─── Audit ───────────────────────────────────────────────────────────
+
+/// Which of a set of apps this machine has
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    pub installed: Vec<&'static App>,
+    pub missing: Vec<&'static App>,
+    /// Not from Homebrew and not on PATH by id; the binary may just be
+    /// named differently (ripgrep is `rg`)
+    pub unverified: Vec<&'static App>,
+}
+
+/// Sort `apps` by whether they're installed. Brew packages are looked up
+/// in `inventory`, everything else on PATH by its id.
+pub fn audit(apps: &[&'static App], system: &SystemInfo, inventory: &BrewInventory) -> AuditReport {
+    let mut report = AuditReport::default();
+    for app in apps {
+        match inventory.has(app) {
+            Some(true) => report.installed.push(app),
+            Some(false) => report.missing.push(app),
+            None if system.find_tool(app.id.as_str()).is_some() => report.installed.push(app),
+            None => report.unverified.push(app),
+        }
+    }
+    report
+}
+
+// ─── Upgrade ─────────────────────────────────────────────────────────
+
+/// A catalog tool Homebrew has a newer version of
+#[derive(Debug, Clone)]
+pub struct Outdated {
+    pub app: &'static App,
+    pub installed: String,
+    pub latest: String,
+    /// A cask that updates itself, which brew only upgrades greedily
+    pub self_updating: bool,
+}
+
+/// Which of `apps` Homebrew has newer versions of, as of its last `brew
+/// update`. Casks that update themselves are included and marked.
+pub fn outdated(runner: &dyn GitRunner, apps: &[&'static App]) -> Result<Vec<Outdated>, String> {
+    let plain = parse_outdated(&brew_outdated(runner, false)?, apps)?;
+    let mut greedy = parse_outdated(&brew_outdated(runner, true)?, apps)?;
+    for tool in &mut greedy {
+        tool.self_updating = !plain.iter().any(|p| p.app.id == tool.app.id);
+    }
+    Ok(greedy)
+}
+
+fn brew_outdated(runner: &dyn GitRunner, greedy: bool) -> Result<String, String> {
+    let mut args = vec!["outdated", "--json=v2"];
+    if greedy {
+        args.push("--greedy");
+    }
+    let output = runner
+        .run_with_env("brew", &args, BREW_ENV)
+        .map_err(|e| format!("Could not run brew: {}", e))?;
+    if !output.success {
+        return Err(format!(
+            "brew outdated: {}",
+            output.stderr.lines().last().unwrap_or("failed").trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// `brew outdated --json=v2` matched up with `apps`, in their order.
+/// Tapped formulae are matched by their short name.
+fn parse_outdated(json: &str, apps: &[&'static App]) -> Result<Vec<Outdated>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("brew outdated: {}", e))?;
+    let short = |pkg: &str| pkg.rsplit('/').next().unwrap_or(pkg).to_string();
+    let find = |kind: &str, pkg: &str| {
+        value[kind].as_array()?.iter().find_map(|entry| {
+            if short(entry["name"].as_str()?) != short(pkg) {
+                return None;
+            }
+            // Casks have given the installed version as a string, formulae
+            // as a list with the newest last
+            let installed = match &entry["installed_versions"] {
+                serde_json::Value::Array(versions) => versions.last()?.as_str()?,
+                version => version.as_str()?,
+            };
+            Some((
+                installed.to_string(),
+                entry["current_version"].as_str()?.to_string(),
+            ))
+        })
+    };
+    Ok(apps
+        .iter()
+        .filter_map(|app| {
+            let (installed, latest) = match app.method() {
+                InstallMethod::Brew(pkg) => find("formulae", pkg)?,
+                InstallMethod::BrewCask(pkg) => find("casks", pkg)?,
+                _ => return None,
+            };
+            Some(Outdated {
+                app,
+                installed,
+                latest,
+                self_updating: false,
+            })
+        })
+        .collect())
+}
+
+/// Whether an upgrade run takes `tool`: it isn't excluded, and if it
+/// updates itself it's been made greedy
+pub fn will_upgrade(tool: &Outdated, prefs: &UpgradeSection) -> bool {
+    let id = tool.app.id.as_str();
+    !prefs.is_excluded(id) && (!tool.self_updating || prefs.is_greedy(id))
+}
+
+/// Where to read what changed in `app`: the releases page for a GitHub
+/// project, otherwise its homepage
+pub fn release_notes(app: &App) -> String {
+    let url = app.url.trim_end_matches('/');
+    if let Some(path) = url.strip_prefix("https://github.com/") {
+        let repo: Vec<&str> = path.split('/').take(2).collect();
+        if repo.len() == 2 {
+            return format!("https://github.com/{}/releases", repo.join("/"));
+        }
+    }
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::{find_app, CATALOG};
+
+    #[test]
+    fn returning_means_an_install_finished() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-hub-{}", std::process::id()));
+        assert!(!is_returning(&system));
+
+        // A crash report or fetched profile alone doesn't count
+        std::fs::create_dir_all(state_dir(&system)).unwrap();
+        assert!(!is_returning(&system));
+        mark_installed(&system).unwrap();
+        assert!(is_returning(&system));
+        let _ = std::fs::remove_dir_all(&system.config_dir);
+    }
+
+    #[test]
+    fn audit_sorts_by_where_the_app_comes_from() {
+        let system = SystemInfo::detect().unwrap();
+        let ripgrep = find_app("ripgrep").unwrap();
+        let bat = find_app("bat").unwrap();
+        let pyright = find_app("pyright").unwrap();
+        assert!(matches!(pyright.method(), InstallMethod::Npm(_)));
+
+        let mut inventory = BrewInventory::default();
+        inventory.formulae.insert("ripgrep".to_string());
+        let report = audit(&[ripgrep, bat, pyright], &system, &inventory);
+
+        assert_eq!(report.installed[0].id, "ripgrep");
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].id, "bat");
+        // Not brew's to say, so never reported missing
+        assert_eq!(report.installed.len() + report.unverified.len(), 2);
+    }
+
+    #[test]
+    fn outdated_tools_show_both_versions() {
+        let ripgrep = find_app("ripgrep").unwrap();
+        let bat = find_app("bat").unwrap();
+        let pyright = find_app("pyright").unwrap();
+        let json = r#"{
+            "formulae": [
+                {"name": "ripgrep", "installed_versions": ["14.0.3", "14.1.0"],
+                 "current_version": "14.1.1", "pinned": false}
+            ],
+            "casks": []
+        }"#;
+        let outdated = parse_outdated(json, &[ripgrep, bat, pyright]).unwrap();
+        assert_eq!(outdated.len(), 1);
+        assert_eq!(outdated[0].app.id, "ripgrep");
+        assert_eq!(
+            (outdated[0].installed.as_str(), outdated[0].latest.as_str()),
+            ("14.1.0", "14.1.1")
+        );
+        assert!(parse_outdated("not json", &[ripgrep]).is_err());
+    }
+
+    #[test]
+    fn self_updating_casks_wait_to_be_made_greedy() {
+        let tool = Outdated {
+            app: find_app("vscode").unwrap(),
+            installed: "1.94.0".to_string(),
+            latest: "1.95.1".to_string(),
+            self_updating: true,
+        };
+        let mut prefs = UpgradeSection::default();
+        assert!(!will_upgrade(&tool, &prefs));
+        UpgradeSection::toggle(&mut prefs.greedy, "vscode");
+        assert!(will_upgrade(&tool, &prefs));
+        UpgradeSection::toggle(&mut prefs.exclude, "vscode");
+        assert!(!will_upgrade(&tool, &prefs));
+    }
+
+    #[test]
+    fn release_notes_are_on_github_where_the_project_is() {
+        let ripgrep = find_app("ripgrep").unwrap();
+        assert!(ripgrep.url.starts_with("https://github.com/"));
+        assert!(release_notes(ripgrep).ends_with("/ripgrep/releases"));
+        let homepage = CATALOG
+            .iter()
+            .find(|app| !app.url.contains("github.com"))
+            .unwrap();
+        assert_eq!(release_notes(homepage), homepage.url.trim_end_matches('/'));
+    }
+
+    #[test]
+    fn every_action_is_described() {
+        for action in HubAction::menu(true) {
+            assert!(!action.name().is_empty());
+            assert!(!action.description().is_empty());
+        }
+        assert_eq!(HubAction::menu(true)[0], HubAction::Resume);
+        assert!(!HubAction::menu(false).contains(&HubAction::Resume));
+    }
+}
@@ -0,0 +1,359 @@
+//! Detached install jobs
+//! An install running in a process of its own, so closing the terminal
+//! doesn't stop it. Its event log is its only output; attaching follows it.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::checkpoint::Checkpoint;
+use crate::events::{self, Event};
+use crate::executor::{InstallMessage, InstallOptions};
+use crate::secret::Secret;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// Everything a detached install needs, handed to it on its stdin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub wizard: WizardState,
+    pub options: InstallOptions,
+    /// The event log the job records to
+    pub events: PathBuf,
+    /// The interrupted install it picks up from, when resuming
+    #[serde(default)]
+    pub resume: Option<Checkpoint>,
+}
+
+/// What the job needs that never goes in a file. Sent on the same pipe as
+/// the rest, one line after it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Secrets {
+    ssh_passphrase: String,
+    github_token: String,
+    anthropic_key: String,
+    openai_key: String,
+    sudo_password: Option<String>,
+}
+
+impl Job {
+    /// A job for `wizard`'s install, recording to a new run file
+    pub fn new(wizard: &WizardState, options: &InstallOptions, system: &SystemInfo) -> Self {
+        Self {
+            wizard: wizard.clone(),
+            options: options.clone(),
+            events: events::new_run_path(system),
+            resume: None,
+        }
+    }
+
+    /// The job as one JSON line and its secrets as another
+    pub fn to_wire(&self) -> Result<String, String> {
+        let secrets = Secrets {
+            ssh_passphrase: self.wizard.ssh_passphrase.expose().to_string(),
+            github_token: self.wizard.github_token.expose().to_string(),
+            anthropic_key: self.wizard.ai.anthropic_key.expose().to_string(),
+            openai_key: self.wizard.ai.openai_key.expose().to_string(),
+            sudo_password: self
+                .options
+                .sudo_password
+                .as_ref()
+                .map(|p| p.expose().to_string()),
+        };
+        let job = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        let secrets = serde_json::to_string(&secrets).map_err(|e| e.to_string())?;
+        Ok(format!("{}\n{}\n", job, secrets))
+    }
+
+    /// Read back what `to_wire` wrote
+    pub fn from_wire(input: impl BufRead) -> Result<Self, String> {
+        let mut lines = input.lines();
+        let mut next = |what: &str| match lines.next() {
+            Some(Ok(line)) => Ok(line),
+            Some(Err(e)) => Err(format!("Could not read the {}: {}", what, e)),
+            None => Err(format!("The {} is missing", what)),
+        };
+        let mut job: Job =
+            serde_json::from_str(&next("job")?).map_err(|e| format!("Bad job: {}", e))?;
+        let secrets: Secrets = serde_json::from_str(&next("job's secrets")?)
+            .map_err(|e| format!("Bad job secrets: {}", e))?;
+        job.wizard.ssh_passphrase = Secret::new(secrets.ssh_passphrase);
+        job.wizard.github_token = Secret::new(secrets.github_token);
+        job.wizard.ai.anthropic_key = Secret::new(secrets.anthropic_key);
+        job.wizard.ai.openai_key = Secret::new(secrets.openai_key);
+        job.options.sudo_password = secrets.sudo_password.map(Secret::new);
+        Ok(job)
+    }
+
+    /// Start `program job` in a process group of its own, so the hangup
+    /// sent when the terminal closes doesn't reach it, and hand it this
+    /// job. Returns its pid.
+    pub fn spawn(&self, program: &Path) -> Result<u32, String> {
+        let mut command = Command::new(program);
+        command
+            .arg("job")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Could not start the background install: {}", e))?;
+        let wire = self.to_wire()?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or("The background install has no stdin")?;
+        stdin
+            .write_all(wire.as_bytes())
+            .map_err(|e| format!("Could not hand the install over: {}", e))?;
+        let pid = child.id();
+        // Reap it when it exits, so it doesn't linger looking alive for as
+        // long as this process does
+        std::thread::spawn(move || child.wait());
+        Ok(pid)
+    }
+}
+
+// ─── Running jobs ────────────────────────────────────────────────────
+
+/// A job still running, as its marker file records it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Running {
+    pub pid: u32,
+    pub events: PathBuf,
+}
+
+impl Running {
+    /// The marker next to the event log, there for as long as the job runs
+    pub fn marker(&self) -> PathBuf {
+        marker_path(&self.events)
+    }
+
+    /// Until the job removes its marker, or dies without getting to
+    pub fn is_running(&self) -> bool {
+        self.marker().exists() && is_alive(self.pid)
+    }
+}
+
+fn marker_path(events: &Path) -> PathBuf {
+    events.with_extension("job")
+}
+
+/// Record that the job writing `events` is running as `pid`
+pub fn mark_running(events: &Path, pid: u32) -> io::Result<Running> {
+    let running = Running {
+        pid,
+        events: events.to_path_buf(),
+    };
+    let json = serde_json::to_string(&running).map_err(io::Error::other)?;
+    if let Some(dir) = events.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(running.marker(), json)?;
+    Ok(running)
+}
+
+/// The job is done; whoever follows it stops once they've read the rest
+pub fn mark_finished(events: &Path) {
+    let _ = fs::remove_file(marker_path(events));
+}
+
+/// The newest job still running. Markers left by a job that died without
+/// finishing are cleared out on the way.
+pub fn running(system: &SystemInfo) -> Option<Running> {
+    let entries = fs::read_dir(events::runs_dir(system)).ok()?;
+    let mut markers: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "job"))
+        .collect();
+    markers.sort();
+    markers.into_iter().rev().find_map(|marker| {
+        let running: Running = serde_json::from_str(&fs::read_to_string(&marker).ok()?).ok()?;
+        if is_alive(running.pid) {
+            Some(running)
+        } else {
+            let _ = fs::remove_file(&marker);
+            None
+        }
+    })
+}
+
+/// Whether process `pid` is running. One owned by another user still
+/// counts, though we may not signal it.
+pub(crate) fn is_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        // SAFETY: signal 0 only checks that the process exists
+        let signalled = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+        signalled || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        false
+    }
+}
+
+// ─── Following ───────────────────────────────────────────────────────
+
+/// How often to look for new events while the job is quiet
+const FOLLOW_POLL: Duration = Duration::from_millis(250);
+
+/// Send every message in the event log at `events`, from the start, then
+/// each new one as it's written, until `running` says the job is done and
+/// everything it wrote has been sent
+pub fn follow(events: &Path, running: impl Fn() -> bool, tx: &mpsc::Sender<InstallMessage>) {
+    let mut reader = Tail::default();
+    loop {
+        // Checked before reading, so the last read sees everything the
+        // job wrote before it finished
+        let done = !running();
+        let got = reader.read(events);
+        for event in &got {
+            if tx.send(event.message.clone()).is_err() {
+                return;
+            }
+        }
+        if done {
+            return;
+        }
+        if got.is_empty() {
+            std::thread::sleep(FOLLOW_POLL);
+        }
+    }
+}
+
+/// Reads a growing JSONL file a chunk at a time, holding back a line
+/// that's still being written
+#[derive(Debug, Default)]
+struct Tail {
+    offset: u64,
+    partial: String,
+}
+
+impl Tail {
+    fn read(&mut self, path: &Path) -> Vec<Event> {
+        let Ok(mut file) = File::open(path) else {
+            return Vec::new();
+        };
+        let mut chunk = String::new();
+        if file.seek(SeekFrom::Start(self.offset)).is_err()
+            || file.read_to_string(&mut chunk).is_err()
+        {
+            return Vec::new();
+        }
+        self.offset += chunk.len() as u64;
+        self.partial.push_str(&chunk);
+
+        let complete = match self.partial.rfind('\n') {
+            Some(end) => self.partial.drain(..=end).collect::<String>(),
+            None => return Vec::new(),
+        };
+        complete
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn only_running_processes_are_alive() {
+        assert!(is_alive(std::process::id()));
+        // init runs as root, so this is the EPERM case for anyone else
+        assert!(is_alive(1));
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert!(!is_alive(pid));
+    }
+
+    #[test]
+    fn secrets_travel_on_their_own_line() {
+        let mut wizard = WizardState::new();
+        wizard.ssh_passphrase = Secret::new("correct horse");
+        wizard.ai.openai_key = Secret::new("sk-123");
+        wizard.cursor_position = 7;
+        let options = InstallOptions {
+            sudo_password: Some(Secret::new("hunter2")),
+            ..InstallOptions::default()
+        };
+        let job = Job {
+            wizard,
+            options,
+            events: PathBuf::from("/tmp/runs/1.jsonl"),
+            resume: None,
+        };
+
+        let wire = job.to_wire().unwrap();
+        let (first, second) = wire.split_once('\n').unwrap();
+        assert!(!first.contains("hunter2") && !first.contains("sk-123"));
+        assert!(second.contains("hunter2"));
+
+        let back = Job::from_wire(wire.as_bytes()).unwrap();
+        assert_eq!(back.wizard.ssh_passphrase.expose(), "correct horse");
+        assert_eq!(back.wizard.ai.openai_key.expose(), "sk-123");
+        assert_eq!(back.options.sudo_password, Some(Secret::new("hunter2")));
+        assert_eq!(back.wizard.selected_apps, job.wizard.selected_apps);
+        // Where the screens were left isn't part of the job
+        assert_eq!(back.wizard.cursor_position, 0);
+        assert!(Job::from_wire(first.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn follow_reads_to_the_end_of_a_finished_job() {
+        let dir = std::env::temp_dir().join(format!("loadstar-job-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let events = dir.join("1.jsonl");
+        let line = |message| serde_json::to_string(&Event::now(message)).unwrap();
+        let done = line(InstallMessage::Done {
+            succeeded: 1,
+            failed: 0,
+            skipped: 0,
+        });
+        fs::write(
+            &events,
+            format!(
+                "{}\n{}",
+                line(InstallMessage::Log("[RUN] brew".into())),
+                done
+            ),
+        )
+        .unwrap();
+
+        // The last line has no newline yet: it's still being written
+        let mut tail = Tail::default();
+        assert_eq!(tail.read(&events).len(), 1);
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&events)
+            .unwrap()
+            .write_all(b"\n")
+            .unwrap();
+        assert_eq!(tail.read(&events).len(), 1);
+
+        let running = mark_running(&events, std::process::id()).unwrap();
+        assert!(running.is_running());
+        mark_finished(&events);
+        let (tx, rx) = mpsc::channel();
+        follow(&events, || running.is_running(), &tx);
+        let messages: Vec<_> = rx.try_iter().collect();
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[1], InstallMessage::Done { .. }));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,420 @@
+//! Kubernetes context setup
+//! Pulls cluster credentials into ~/.kube/config and sets a default namespace.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+
+use crate::executor::InstallMessage;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// Where cluster credentials come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KubeSource {
+    /// Leave ~/.kube/config as it is
+    #[default]
+    Keep,
+    /// Merge an existing kubeconfig file
+    File,
+    /// `aws eks update-kubeconfig`
+    Eks,
+    /// `gcloud container clusters get-credentials`
+    Gke,
+}
+
+impl KubeSource {
+    pub fn all() -> &'static [KubeSource] {
+        &[
+            KubeSource::Keep,
+            KubeSource::File,
+            KubeSource::Eks,
+            KubeSource::Gke,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KubeSource::Keep => "Keep existing",
+            KubeSource::File => "Kubeconfig file",
+            KubeSource::Eks => "AWS EKS",
+            KubeSource::Gke => "Google GKE",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            KubeSource::Keep => "Don't touch ~/.kube/config",
+            KubeSource::File => "Merge a kubeconfig you already have",
+            KubeSource::Eks => "Fetch credentials with the AWS CLI",
+            KubeSource::Gke => "Fetch credentials with gcloud",
+        }
+    }
+
+    /// Text fields this source asks for, in screen order
+    pub fn fields(&self) -> &'static [KubeField] {
+        match self {
+            KubeSource::Keep => &[KubeField::Namespace],
+            KubeSource::File => &[KubeField::Kubeconfig, KubeField::Namespace],
+            KubeSource::Eks | KubeSource::Gke => {
+                &[KubeField::Cluster, KubeField::Region, KubeField::Namespace]
+            }
+        }
+    }
+}
+
+/// A text field on the Kubernetes screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KubeField {
+    Kubeconfig,
+    Cluster,
+    Region,
+    Namespace,
+}
+
+impl KubeField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            KubeField::Kubeconfig => "KUBECONFIG PATH",
+            KubeField::Cluster => "CLUSTER",
+            KubeField::Region => "REGION",
+            KubeField::Namespace => "DEFAULT NAMESPACE",
+        }
+    }
+}
+
+/// Answers from the Kubernetes step
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KubeSetup {
+    pub source: KubeSource,
+    pub kubeconfig: String,
+    pub cluster: String,
+    pub region: String,
+    pub namespace: String,
+}
+
+impl KubeSetup {
+    pub fn field(&self, field: KubeField) -> &str {
+        match field {
+            KubeField::Kubeconfig => &self.kubeconfig,
+            KubeField::Cluster => &self.cluster,
+            KubeField::Region => &self.region,
+            KubeField::Namespace => &self.namespace,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: KubeField) -> &mut String {
+        match field {
+            KubeField::Kubeconfig => &mut self.kubeconfig,
+            KubeField::Cluster => &mut self.cluster,
+            KubeField::Region => &mut self.region,
+            KubeField::Namespace => &mut self.namespace,
+        }
+    }
+
+    /// Whether the step has anything to do
+    pub fn is_set(&self) -> bool {
+        self.source != KubeSource::Keep || !self.namespace.trim().is_empty()
+    }
+
+    /// Command that writes cluster credentials into the kubeconfig, for the
+    /// sources that fetch them from a cloud CLI
+    pub fn credentials_command(&self) -> Option<(&'static str, Vec<String>)> {
+        let cluster = self.cluster.trim().to_string();
+        let region = self.region.trim().to_string();
+        match self.source {
+            KubeSource::Eks => Some((
+                "aws",
+                vec![
+                    "eks".into(),
+                    "update-kubeconfig".into(),
+                    "--name".into(),
+                    cluster,
+                    "--region".into(),
+                    region,
+                ],
+            )),
+            KubeSource::Gke => Some((
+                "gcloud",
+                vec![
+                    "container".into(),
+                    "clusters".into(),
+                    "get-credentials".into(),
+                    cluster,
+                    "--region".into(),
+                    region,
+                ],
+            )),
+            KubeSource::Keep | KubeSource::File => None,
+        }
+    }
+}
+
+// ─── Setup ───────────────────────────────────────────────────────────
+
+/// Run the Kubernetes step: pull in credentials, then set the namespace
+pub fn setup_kube(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let kube = &wizard.kube;
+    if !wizard.wants_kube() || !kube.is_set() {
+        return;
+    }
+
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Kubernetes".to_string(),
+    });
+    if wizard.dry_run {
+        dry_run_kube(kube, system, tx);
+        return;
+    }
+
+    let kubeconfig = system.home_dir.join(".kube").join("config");
+    let result = match kube.source {
+        KubeSource::Keep => Ok(()),
+        KubeSource::File => merge_kubeconfig(
+            &expand_home(&kube.kubeconfig, system),
+            &kubeconfig,
+            system,
+            tx,
+        ),
+        KubeSource::Eks | KubeSource::Gke => {
+            let (program, args) = kube.credentials_command().expect("cloud source");
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[KUBE] {} {}",
+                program,
+                args.join(" ")
+            )));
+            run(program, &args, system).map(|_| ())
+        }
+    };
+    if let Err(e) = result {
+        let _ = tx.send(InstallMessage::Log(format!("  [ERROR] {}", e)));
+        return;
+    }
+
+    let namespace = kube.namespace.trim();
+    if !namespace.is_empty() {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[KUBE] Setting default namespace to {}",
+            namespace
+        )));
+        let arg = format!("--namespace={}", namespace);
+        if let Err(e) = run(
+            "kubectl",
+            &["config", "set-context", "--current", &arg],
+            system,
+        ) {
+            let _ = tx.send(InstallMessage::Warning(e));
+        }
+    }
+
+    if let Ok(context) = run("kubectl", &["config", "current-context"], system) {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  Current context: {}",
+            context.trim()
+        )));
+    }
+}
+
+/// What `setup_kube` would run, each as a `[DRY]` line
+fn dry_run_kube(kube: &KubeSetup, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let dry = |line: String| {
+        let _ = tx.send(InstallMessage::Log(format!("[DRY] {}", line)));
+    };
+    match kube.source {
+        KubeSource::Keep => {}
+        KubeSource::File => dry(format!(
+            "merge {} into ~/.kube/config",
+            expand_home(&kube.kubeconfig, system).display()
+        )),
+        KubeSource::Eks | KubeSource::Gke => {
+            let (program, args) = kube.credentials_command().expect("cloud source");
+            dry(format!("{} {}", program, args.join(" ")));
+        }
+    }
+    let namespace = kube.namespace.trim();
+    if !namespace.is_empty() {
+        dry(format!(
+            "kubectl config set-context --current --namespace={}",
+            namespace
+        ));
+    }
+}
+
+/// Merge `source` into the kubeconfig, or copy it when there isn't one yet.
+/// The old kubeconfig is backed up first.
+fn merge_kubeconfig(
+    source: &Path,
+    kubeconfig: &Path,
+    system: &SystemInfo,
+    tx: &mpsc::Sender<InstallMessage>,
+) -> Result<(), String> {
+    if !source.is_file() {
+        return Err(format!("Kubeconfig not found: {}", source.display()));
+    }
+    let _ = tx.send(InstallMessage::Log(format!(
+        "[KUBE] Merging {}",
+        source.display()
+    )));
+
+    let merged = if kubeconfig.exists() {
+        let backup = kubeconfig.with_extension("load-backup");
+        fs::copy(kubeconfig, &backup)
+            .map_err(|e| format!("Could not back up {}: {}", kubeconfig.display(), e))?;
+        let _ = tx.send(InstallMessage::Log(
+            "  Backed up existing file to ~/.kube/config.load-backup".to_string(),
+        ));
+
+        // kubectl does the merging: earlier files win on conflicts
+        let paths = format!("{}:{}", kubeconfig.display(), source.display());
+        let output = Command::new("kubectl")
+            .args(["config", "view", "--flatten"])
+            .env("KUBECONFIG", paths)
+            .env("PATH", system.search_path())
+            .output()
+            .map_err(|e| format!("Failed to run kubectl: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "kubectl config view failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        String::from_utf8_lossy(&output.stdout).to_string()
+    } else {
+        fs::read_to_string(source)
+            .map_err(|e| format!("Could not read {}: {}", source.display(), e))?
+    };
+
+    if let Some(parent) = kubeconfig.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    fs::write(kubeconfig, merged)
+        .map_err(|e| format!("Could not write {}: {}", kubeconfig.display(), e))?;
+
+    // Credentials inside: keep it private
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(kubeconfig, fs::Permissions::from_mode(0o600));
+    }
+
+    let _ = tx.send(InstallMessage::Log("  Wrote ~/.kube/config".to_string()));
+    Ok(())
+}
+
+fn run<S: AsRef<std::ffi::OsStr>>(
+    program: &str,
+    args: &[S],
+    system: &SystemInfo,
+) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .env("PATH", system.search_path())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn expand_home(path: &str, system: &SystemInfo) -> PathBuf {
+    match path.trim().strip_prefix("~/") {
+        Some(rest) => system.home_dir.join(rest),
+        None => PathBuf::from(path.trim()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cloud_sources_build_credentials_commands() {
+        let mut kube = KubeSetup {
+            source: KubeSource::Eks,
+            cluster: "prod".to_string(),
+            region: "eu-west-1".to_string(),
+            ..Default::default()
+        };
+        let (program, args) = kube.credentials_command().unwrap();
+        assert_eq!(program, "aws");
+        assert_eq!(
+            args,
+            [
+                "eks",
+                "update-kubeconfig",
+                "--name",
+                "prod",
+                "--region",
+                "eu-west-1"
+            ]
+        );
+
+        kube.source = KubeSource::Gke;
+        let (program, args) = kube.credentials_command().unwrap();
+        assert_eq!(program, "gcloud");
+        assert_eq!(args[3], "prod");
+
+        kube.source = KubeSource::File;
+        assert!(kube.credentials_command().is_none());
+    }
+
+    #[test]
+    fn keep_without_namespace_does_nothing() {
+        let mut kube = KubeSetup::default();
+        assert!(!kube.is_set());
+        kube.namespace = "dev".to_string();
+        assert!(kube.is_set());
+    }
+
+    #[test]
+    fn a_dry_run_shows_the_merge_and_writes_nothing() {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-kube-dry-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&system.home_dir);
+        fs::create_dir_all(&system.home_dir).unwrap();
+        fs::write(system.home_dir.join("work.yaml"), "apiVersion: v1\n").unwrap();
+
+        let mut wizard = WizardState::new();
+        wizard.selected_apps.insert("kubectl".to_string());
+        wizard.dry_run = true;
+        wizard.kube = KubeSetup {
+            source: KubeSource::File,
+            kubeconfig: "~/work.yaml".to_string(),
+            namespace: "dev".to_string(),
+            ..Default::default()
+        };
+        let (tx, rx) = mpsc::channel();
+        setup_kube(&wizard, &system, &tx);
+        drop(tx);
+
+        let lines: Vec<String> = rx
+            .iter()
+            .filter_map(|m| match m {
+                InstallMessage::Log(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                format!(
+                    "[DRY] merge {} into ~/.kube/config",
+                    system.home_dir.join("work.yaml").display()
+                ),
+                "[DRY] kubectl config set-context --current --namespace=dev".to_string(),
+            ]
+        );
+        assert!(!system.home_dir.join(".kube").exists());
+        let _ = fs::remove_dir_all(&system.home_dir);
+    }
+}
@@ -0,0 +1,102 @@
+/*!
+# loadstar-core
+
+The install engine behind `load`, without the TUI: the app catalog, wizard
+state, system detection, the package executor and config generation.
+A GUI, a script or a company wrapper can drive an install with it.
+
+An install is a [`wizard::WizardState`] (what to install and how to set it
+up), a [`system::SystemInfo`] (where), and a channel that
+[`executor::InstallMessage`]s come back on:
+
+```no_run
+use std::sync::mpsc;
+
+use loadstar_core::catalog::Query;
+use loadstar_core::{config, executor, system::SystemInfo, wizard::WizardState};
+
+let system = SystemInfo::detect()?;
+let mut wizard = WizardState::new();
+// Every essential tool that installs on this machine
+for id in Query::new().tag("essential").platform(system.os).ids() {
+    wizard.selected_apps.insert(id.to_string());
+}
+
+let (tx, rx) = mpsc::channel();
+let apps = wizard.get_apps_to_install();
+let summary = executor::run_install(&system, apps, &executor::InstallOptions::default(), &tx);
+config::generate_configs(&wizard, &system, &tx);
+drop(tx);
+
+for message in rx {
+    println!("{:?}", message);
+}
+println!("{} installed, {} failed", summary.succeeded.len(), summary.failed.len());
+# Ok::<(), anyhow::Error>(())
+```
+
+Apps are picked with [`catalog::Query`], and come back as typed
+[`catalog::AppId`]s that always name a catalog entry. Both apps and ids
+serialize, so a selection can be saved as JSON and read back.
+
+Every step reports through the channel instead of printing, and failures
+of one package never stop the run; check the summary or the
+`PackageFailed` messages.
+*/
+
+// ─── Selection ───────────────────────────────────────────────────────
+pub mod catalog;
+pub mod custom;
+pub mod preset;
+pub mod profile;
+pub mod sealed;
+pub mod secret;
+pub mod theme;
+pub mod wizard;
+
+// ─── Environment ─────────────────────────────────────────────────────
+pub mod coverage;
+pub mod scan;
+pub mod system;
+
+// ─── Install ─────────────────────────────────────────────────────────
+pub mod checkpoint;
+pub mod estimate;
+pub mod events;
+pub mod executor;
+pub mod failure;
+pub mod hookcheck;
+pub mod hub;
+pub mod job;
+pub mod lock;
+pub mod pathcheck;
+pub mod stats;
+
+// ─── Configuration ───────────────────────────────────────────────────
+pub mod ai;
+pub mod aliascheck;
+pub mod atuin;
+pub mod cloud;
+pub mod config;
+pub mod github;
+pub mod github_api;
+pub mod hooks;
+pub mod kube;
+pub mod locale;
+pub mod localmodel;
+pub mod rcblock;
+
+// ─── Reports and exports ─────────────────────────────────────────────
+pub mod backup;
+pub mod crashreport;
+pub mod devcontainer;
+pub mod motd;
+pub mod nextsteps;
+pub mod onboarding;
+pub mod plan;
+pub mod report;
+
+// ─── Internals ───────────────────────────────────────────────────────
+mod base64;
+#[cfg(any(test, feature = "fake-runner"))]
+pub mod fakerunner;
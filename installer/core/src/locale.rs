@@ -0,0 +1,280 @@
+//! Locale and timezone
+//! The profile's `[system]` section: `LANG` and `LC_ALL` exported from the
+//! shell's rc file, and the system timezone set with sudo. A cloud dev box
+//! tends to come up in UTC, with no locale to match the laptop ssh-ing in.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+
+use crate::executor::InstallMessage;
+use crate::rcblock;
+use crate::system::{Os, SystemInfo};
+use crate::wizard::{ShellChoice, WizardState};
+
+/// What the generated .zshrc exports when the profile doesn't say
+pub const DEFAULT_LOCALE: &str = "en_US.UTF-8";
+
+/// Where both macOS and Linux keep the timezone database
+const ZONEINFO: &str = "/usr/share/zoneinfo";
+
+/// A locale name such as `en_US.UTF-8`. It ends up in an rc file, so
+/// nothing a shell would read into.
+pub fn check_locale(locale: &str) -> Result<(), String> {
+    let fine = !locale.is_empty()
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.@-".contains(c));
+    if fine {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' isn't a locale name; use one like {}",
+            locale, DEFAULT_LOCALE
+        ))
+    }
+}
+
+/// A timezone name such as `Europe/Berlin`: a path under the timezone
+/// database that stays there
+pub fn check_timezone(timezone: &str) -> Result<(), String> {
+    let fine = !timezone.is_empty()
+        && !timezone.starts_with('/')
+        && timezone
+            .split('/')
+            .all(|part| part != ".." && !part.is_empty())
+        && timezone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_/+-".contains(c));
+    if fine {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' isn't a timezone name; use one like Europe/Berlin or UTC",
+            timezone
+        ))
+    }
+}
+
+/// The system timezone, from where `/etc/localtime` points
+pub fn current_timezone() -> Option<String> {
+    zone_name(&fs::read_link("/etc/localtime").ok()?)
+}
+
+/// `Europe/Berlin` out of `/var/db/timezone/zoneinfo/Europe/Berlin` or
+/// `../usr/share/zoneinfo/Europe/Berlin`
+fn zone_name(target: &Path) -> Option<String> {
+    let target = target.to_str()?;
+    let (_, name) = target.rsplit_once("zoneinfo/")?;
+    Some(name.to_string())
+}
+
+/// Whether setting the timezone will need sudo: it's set and isn't the
+/// system's already
+pub fn needs_sudo(wizard: &WizardState) -> bool {
+    wizard
+        .timezone
+        .as_deref()
+        .is_some_and(|tz| current_timezone().as_deref() != Some(tz))
+}
+
+/// Export the locale and set the timezone, where the wizard has them
+pub fn setup_locale(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    if wizard.locale.is_none() && wizard.timezone.is_none() {
+        return;
+    }
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Locale and Timezone".to_string(),
+    });
+    if let Some(locale) = &wizard.locale {
+        export_locale(
+            locale,
+            wizard.shell_config.shell,
+            system,
+            wizard.dry_run,
+            tx,
+        );
+    }
+    if let Some(timezone) = &wizard.timezone {
+        set_timezone(timezone, system, wizard.dry_run, tx);
+    }
+}
+
+fn export_locale(
+    locale: &str,
+    shell: ShellChoice,
+    system: &SystemInfo,
+    dry_run: bool,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    let rc = rcblock::rc_file(shell, system);
+    // A generated .zshrc exports it itself; a headless run doesn't write one
+    let exported = format!("export LANG={}", locale);
+    let generated = shell == ShellChoice::Zsh
+        && fs::read_to_string(&rc).is_ok_and(|rc| rc.lines().any(|l| l.trim() == exported));
+    if generated {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  LANG and LC_ALL are {} in {}",
+            locale,
+            rc.display()
+        )));
+    } else if dry_run {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] set LANG and LC_ALL to {} in {}",
+            locale,
+            rc.display()
+        )));
+    } else {
+        for var in ["LANG", "LC_ALL"] {
+            if let Err(e) = rcblock::set_var(&rc, shell, var, locale) {
+                let _ = tx.send(InstallMessage::Warning(format!(
+                    "Could not set {} in {}: {}",
+                    var,
+                    rc.display(),
+                    e
+                )));
+                return;
+            }
+        }
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  Set LANG and LC_ALL to {} in {}",
+            locale,
+            rc.display()
+        )));
+    }
+
+    // macOS has every locale; a Linux box has only those generated
+    if system.os == Os::Linux && !locale_installed(locale) {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "{} isn't generated on this machine, so programs fall back to C",
+            locale
+        )));
+        let _ = tx.send(InstallMessage::NextStep {
+            command: format!("sudo locale-gen {}", locale),
+            note: "generate the locale (Debian and Ubuntu; `sudo dnf install glibc-langpack-en` on Fedora)".to_string(),
+        });
+    }
+}
+
+/// Whether `locale -a` lists `locale`, which it writes as `en_US.utf8`
+fn locale_installed(locale: &str) -> bool {
+    let Ok(output) = Command::new("locale").arg("-a").output() else {
+        // Nothing to ask; don't cry wolf
+        return true;
+    };
+    let normal = |name: &str| name.to_lowercase().replace('-', "");
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| normal(line.trim()) == normal(locale))
+}
+
+fn set_timezone(
+    timezone: &str,
+    system: &SystemInfo,
+    dry_run: bool,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    if current_timezone().as_deref() == Some(timezone) {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  Timezone is already {}",
+            timezone
+        )));
+        return;
+    }
+    let zone = PathBuf::from(ZONEINFO).join(timezone);
+    if !zone.is_file() {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "{} isn't in {}; timezone left as it is",
+            timezone, ZONEINFO
+        )));
+        return;
+    }
+
+    let args = timezone_command(timezone, &zone, system);
+    if dry_run {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[DRY] sudo -n {}",
+            args.join(" ")
+        )));
+        return;
+    }
+    // -n: sudo was primed before the install if it needed a password, and
+    // a prompt now would go nowhere
+    let status = Command::new("sudo")
+        .arg("-n")
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if status.is_ok_and(|s| s.success()) {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  Set the timezone to {}",
+            timezone
+        )));
+    } else {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "Could not set the timezone to {}",
+            timezone
+        )));
+        let _ = tx.send(InstallMessage::NextStep {
+            command: format!("sudo {}", args.join(" ")),
+            note: "set the timezone".to_string(),
+        });
+    }
+}
+
+/// What sets the timezone here. Linux without systemd (most containers)
+/// gets the `/etc/localtime` link changed directly.
+fn timezone_command(timezone: &str, zone: &Path, system: &SystemInfo) -> Vec<String> {
+    let has_timedatectl = Path::new("/run/systemd/system").is_dir();
+    match system.os {
+        Os::MacOS => vec![
+            "systemsetup".to_string(),
+            "-settimezone".to_string(),
+            timezone.to_string(),
+        ],
+        Os::Linux if has_timedatectl => vec![
+            "timedatectl".to_string(),
+            "set-timezone".to_string(),
+            timezone.to_string(),
+        ],
+        Os::Linux => vec![
+            "ln".to_string(),
+            "-sf".to_string(),
+            zone.display().to_string(),
+            "/etc/localtime".to_string(),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_names_are_taken() {
+        assert!(check_locale("en_US.UTF-8").is_ok());
+        assert!(check_locale("sr_RS.UTF-8@latin").is_ok());
+        assert!(check_locale("").is_err());
+        assert!(check_locale("en_US.UTF-8\"; rm -rf ~").is_err());
+
+        assert!(check_timezone("Europe/Berlin").is_ok());
+        assert!(check_timezone("America/Argentina/Buenos_Aires").is_ok());
+        assert!(check_timezone("Etc/GMT+3").is_ok());
+        assert!(check_timezone("/etc/passwd").is_err());
+        assert!(check_timezone("../../etc/passwd").is_err());
+        assert!(check_timezone("Europe//Berlin").is_err());
+
+        assert_eq!(
+            zone_name(Path::new("/var/db/timezone/zoneinfo/Europe/Berlin")).as_deref(),
+            Some("Europe/Berlin")
+        );
+        assert_eq!(
+            zone_name(Path::new("../usr/share/zoneinfo/UTC")).as_deref(),
+            Some("UTC")
+        );
+        assert_eq!(zone_name(Path::new("/etc/localtime.bak")), None);
+    }
+}
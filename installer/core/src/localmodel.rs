@@ -0,0 +1,343 @@
+//! Local models for Ollama
+//! Picks a model that fits this machine's memory and GPU, warns when the
+//! one asked for won't, and pulls it once Ollama is installed.
+
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::executor::{self, InstallMessage, RunEnv};
+use crate::system::{Gpu, Hardware, SystemInfo};
+use crate::wizard::WizardState;
+
+/// A model from Ollama's library, with its 4-bit download size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalModel {
+    pub name: &'static str,
+    pub size_gb: f32,
+}
+
+/// Defaults, smallest first: the largest that fits is picked
+pub const MODELS: &[LocalModel] = &[
+    LocalModel {
+        name: "llama3.2:3b",
+        size_gb: 2.0,
+    },
+    LocalModel {
+        name: "llama3.1:8b",
+        size_gb: 4.9,
+    },
+    LocalModel {
+        name: "qwen2.5-coder:14b",
+        size_gb: 9.0,
+    },
+    LocalModel {
+        name: "qwen2.5-coder:32b",
+        size_gb: 20.0,
+    },
+    LocalModel {
+        name: "llama3.3:70b",
+        size_gb: 43.0,
+    },
+];
+
+/// Memory the system keeps for itself and whatever else is open
+const SYSTEM_RESERVE_GB: f32 = 6.0;
+
+/// GPU memory taken beyond the weights, for the context
+const VRAM_RESERVE_GB: f32 = 1.0;
+
+/// Past this, a model without a GPU answers too slowly to be worth it
+const CPU_ONLY_MAX_GB: f32 = 5.0;
+
+/// Disk left free after the download
+const DISK_MARGIN_GB: f32 = 2.0;
+
+/// A model name such as `llama3.1:8b`. It's passed to `ollama pull`, so
+/// nothing a shell or ollama would read as an option.
+pub fn check_name(name: &str) -> Result<(), String> {
+    let fine = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._:/-".contains(c));
+    if fine {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' isn't an Ollama model name; use one like llama3.1:8b",
+            name
+        ))
+    }
+}
+
+/// Download size in GB: from `MODELS`, or estimated from a `:70b`-style
+/// tag at about 0.6 GB per billion parameters
+pub fn size_gb(name: &str) -> Option<f32> {
+    if let Some(model) = MODELS.iter().find(|m| m.name == name) {
+        return Some(model.size_gb);
+    }
+    let (_, tag) = name.rsplit_once(':')?;
+    let digits: String = tag
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let rest = &tag[digits.len()..];
+    if !rest.starts_with('b') {
+        return None;
+    }
+    digits
+        .parse::<f32>()
+        .ok()
+        .map(|billions| billions * 6.0 / 10.0)
+}
+
+/// Memory a model runs in, and what's kept back from it: the GPU's own
+/// when it has some, otherwise the system's (Apple Silicon shares it)
+fn memory(hardware: &Hardware) -> Option<(u64, f32)> {
+    match &hardware.gpu {
+        Some(Gpu::Nvidia {
+            memory_gb: Some(vram),
+            ..
+        }) => Some((*vram, VRAM_RESERVE_GB)),
+        _ => hardware.memory_gb.map(|gb| (gb, SYSTEM_RESERVE_GB)),
+    }
+}
+
+/// Memory left over for a model's weights
+fn room_gb(hardware: &Hardware) -> Option<f32> {
+    memory(hardware).map(|(gb, reserve)| (gb as f32 - reserve).max(0.0))
+}
+
+/// The largest default this machine runs well; the smallest when its
+/// memory is unknown
+pub fn pick(hardware: &Hardware) -> &'static LocalModel {
+    let room = room_gb(hardware).unwrap_or(0.0);
+    // A GPU only speeds up a model held in memory it's known to have: an
+    // AMD card's, or an NVIDIA card's that didn't say, may be too small
+    let gpu_memory = matches!(
+        hardware.gpu,
+        Some(Gpu::AppleSilicon)
+            | Some(Gpu::Nvidia {
+                memory_gb: Some(_),
+                ..
+            })
+    );
+    let cap = if gpu_memory {
+        room
+    } else {
+        room.min(CPU_ONLY_MAX_GB)
+    };
+    MODELS
+        .iter()
+        .rev()
+        .find(|model| model.size_gb <= cap)
+        .unwrap_or(&MODELS[0])
+}
+
+/// The profile's model, or the pick for this machine
+pub fn chosen(wizard: &WizardState, hardware: &Hardware) -> String {
+    let asked = wizard.ai.local_model.trim();
+    if asked.is_empty() {
+        pick(hardware).name.to_string()
+    } else {
+        asked.to_string()
+    }
+}
+
+/// Set when Ollama is picked with a model too big for this machine's
+/// memory or free disk. Shown on Review; the pull still goes ahead if
+/// there's room on disk.
+pub fn warning(wizard: &WizardState, hardware: &Hardware) -> Option<String> {
+    if !wizard.installs("ollama") {
+        return None;
+    }
+    let model = chosen(wizard, hardware);
+    let size = size_gb(&model)?;
+    if let Some(free) = hardware.free_disk_gb {
+        if size + DISK_MARGIN_GB > free as f32 {
+            return Some(format!(
+                "{} is a {:.0} GB download and only {} GB of disk is free",
+                model,
+                size.ceil(),
+                free
+            ));
+        }
+    }
+    let (gb, reserve) = memory(hardware)?;
+    let has = match &hardware.gpu {
+        Some(Gpu::Nvidia {
+            name,
+            memory_gb: Some(_),
+        }) => format!("the {} has {} GB", name, gb),
+        _ => format!("this machine has {} GB", gb),
+    };
+    (size + reserve > gb as f32).then(|| {
+        format!(
+            "{} needs about {:.0} GB of memory and {}; {} fits",
+            model,
+            (size + reserve).ceil(),
+            has,
+            pick(hardware).name
+        )
+    })
+}
+
+/// Pull the model with Ollama, when it's installed and running. Otherwise
+/// the pull becomes a next step. The pull is stopped after `timeout`, the
+/// run's package timeout.
+pub fn setup_local_model(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    timeout: Option<Duration>,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    if !wizard.installs("ollama") {
+        return;
+    }
+    // A dry run installed nothing, so Ollama needn't be there yet
+    let ollama = system.find_tool("ollama");
+    if ollama.is_none() && !wizard.dry_run {
+        return;
+    }
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "Local Model".to_string(),
+    });
+
+    let hardware = &system.hardware;
+    let model = chosen(wizard, hardware);
+    if wizard.ai.local_model.trim().is_empty() {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "  Picked {} for {}",
+            model,
+            hardware.summary()
+        )));
+    }
+    if let Some(warning) = warning(wizard, hardware) {
+        let _ = tx.send(InstallMessage::Warning(warning));
+    }
+    let size = size_gb(&model).unwrap_or(0.0);
+    if let Some(free) = hardware.free_disk_gb {
+        if size + DISK_MARGIN_GB > free as f32 {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "  Not pulling {}; free up disk space first",
+                model
+            )));
+            return;
+        }
+    }
+    let ollama = match ollama {
+        Some(ollama) if !wizard.dry_run => ollama,
+        _ => {
+            let _ = tx.send(InstallMessage::Log(format!("[DRY] ollama pull {}", model)));
+            return;
+        }
+    };
+
+    // `ollama list` only answers when the server is up
+    let running = Command::new(&ollama)
+        .arg("list")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    if !running {
+        let _ = tx.send(InstallMessage::NextStep {
+            command: format!("ollama pull {}", model),
+            note: format!(
+                "once Ollama is running (`brew services start ollama` or `ollama serve`); about {:.0} GB",
+                size.ceil()
+            ),
+        });
+        return;
+    }
+
+    let env = RunEnv::new(system).with_timeout(timeout, Instant::now());
+    let program = ollama.to_string_lossy();
+    match executor::run_command(&env, &program, &["pull", &model], tx) {
+        Ok(()) => {
+            let _ = tx.send(InstallMessage::Log(format!("  Pulled {}", model)));
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Warning(format!(
+                "Could not pull {}: {}",
+                model, e
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(memory_gb: u64, gpu: Option<Gpu>) -> Hardware {
+        Hardware {
+            memory_gb: Some(memory_gb),
+            gpu,
+            free_disk_gb: Some(500),
+        }
+    }
+
+    #[test]
+    fn the_pick_fits_the_machine() {
+        assert_eq!(
+            pick(&machine(8, Some(Gpu::AppleSilicon))).name,
+            "llama3.2:3b"
+        );
+        assert_eq!(
+            pick(&machine(16, Some(Gpu::AppleSilicon))).name,
+            "qwen2.5-coder:14b"
+        );
+        assert_eq!(
+            pick(&machine(64, Some(Gpu::AppleSilicon))).name,
+            "llama3.3:70b"
+        );
+        // Without a GPU, bigger models are too slow to pick
+        assert_eq!(pick(&machine(64, None)).name, "llama3.1:8b");
+        let rtx = Gpu::Nvidia {
+            name: "RTX 4090".to_string(),
+            memory_gb: Some(24),
+        };
+        assert_eq!(pick(&machine(32, Some(rtx))).name, "qwen2.5-coder:32b");
+        // Nor with a GPU whose own memory isn't known
+        assert_eq!(pick(&machine(64, Some(Gpu::Amd))).name, "llama3.1:8b");
+        let unknown = Gpu::Nvidia {
+            name: "Tesla T4".to_string(),
+            memory_gb: None,
+        };
+        assert_eq!(pick(&machine(64, Some(unknown))).name, "llama3.1:8b");
+        assert_eq!(pick(&Hardware::default()).name, "llama3.2:3b");
+
+        assert_eq!(size_gb("deepseek-r1:70b"), Some(42.0));
+        assert_eq!(size_gb("llama3.1:8b-instruct-q8_0"), Some(4.8));
+        assert_eq!(size_gb("mixtral:8x7b"), None);
+        assert!(check_name("llama3.1:8b").is_ok());
+        assert!(check_name("--insecure").is_err());
+    }
+
+    #[test]
+    fn a_big_model_on_a_small_machine_is_warned_about() {
+        let mut wizard = WizardState::new();
+        wizard.selected_apps.insert("ollama".to_string());
+        let laptop = machine(8, Some(Gpu::AppleSilicon));
+        assert_eq!(warning(&wizard, &laptop), None);
+
+        wizard.ai.local_model = "llama3.3:70b".to_string();
+        assert_eq!(
+            warning(&wizard, &laptop).as_deref(),
+            Some("llama3.3:70b needs about 49 GB of memory and this machine has 8 GB; llama3.2:3b fits")
+        );
+        let full = Hardware {
+            free_disk_gb: Some(30),
+            ..machine(128, Some(Gpu::AppleSilicon))
+        };
+        assert_eq!(
+            warning(&wizard, &full).as_deref(),
+            Some("llama3.3:70b is a 43 GB download and only 30 GB of disk is free")
+        );
+
+        wizard.selected_apps.remove("ollama");
+        assert_eq!(warning(&wizard, &laptop), None);
+    }
+}
@@ -0,0 +1,188 @@
+//! Run lock
+//! One loadstar at a time changes brew and the dotfiles. A run holds
+//! `run.lock` under the state directory, with its PID in it, while it installs.
+//! Every change to it is made under an advisory lock on `run.guard`, so two
+//! runs can't both find it free, and it's written whole by a rename, so it's
+//! never seen empty.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::hub;
+use crate::job;
+use crate::system::SystemInfo;
+
+/// Where the lock lives
+pub fn path(system: &SystemInfo) -> PathBuf {
+    hub::state_dir(system).join("run.lock")
+}
+
+/// Held until dropped; the file goes with it, unless another run has
+/// taken it over with `--force` since
+#[derive(Debug)]
+pub struct RunLock {
+    path: PathBuf,
+}
+
+impl RunLock {
+    /// Give the lock to the process `pid`, which takes it as its own when
+    /// it acquires it, so no other run can start in between
+    pub fn hand_over(self, pid: u32) -> io::Result<()> {
+        let _guard = Guard::take(&self.path)?;
+        write_pid(&self.path, pid)
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _guard = Guard::take(&self.path);
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Exclusive use of the lock file until dropped: an advisory lock on the
+/// guard file beside it, which the system lets go of if the holder dies
+struct Guard {
+    _file: File,
+}
+
+impl Guard {
+    fn take(lock: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock.with_extension("guard"))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            // SAFETY: flock on a descriptor this owns, waiting its turn
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(Self { _file: file })
+    }
+}
+
+/// Put `pid` in the lock file in one step: written beside it, then
+/// renamed over it
+fn write_pid(path: &Path, pid: u32) -> io::Result<()> {
+    let partial = path.with_extension("lock.partial");
+    fs::write(&partial, pid.to_string())?;
+    fs::rename(&partial, path)
+}
+
+/// The PID of another live run holding the lock. A lock left by a run
+/// that died isn't anyone's.
+pub fn holder(system: &SystemInfo) -> Option<u32> {
+    holder_at(&path(system))
+}
+
+fn holder_at(path: &Path) -> Option<u32> {
+    read_pid(path).filter(|pid| *pid != std::process::id() && job::is_alive(*pid))
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// What's said when `pid` holds the lock
+pub fn busy(pid: u32) -> String {
+    format!("Another loadstar run is in progress (PID {})", pid)
+}
+
+/// Take the lock. With `force`, a live holder's lock is taken over.
+pub fn acquire(system: &SystemInfo, force: bool) -> Result<RunLock, String> {
+    acquire_at(&path(system), force)
+}
+
+fn acquire_at(path: &Path, force: bool) -> Result<RunLock, String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    }
+    let _guard =
+        Guard::take(path).map_err(|e| format!("Could not lock {}: {}", path.display(), e))?;
+    // Handed over to this process; otherwise a lock left by a run that
+    // died, or an empty one, is taken, and a live holder's only with force
+    if read_pid(path) != Some(std::process::id()) {
+        if let (Some(pid), false) = (holder_at(path), force) {
+            return Err(busy(pid));
+        }
+        write_pid(path, std::process::id())
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    }
+    // Made once the guard is no longer needed: dropping it takes the guard
+    Ok(RunLock {
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn a_live_holder_keeps_the_lock_unless_forced() {
+        let dir = std::env::temp_dir().join(format!("loadstar-lock-{}", std::process::id()));
+        let path = dir.join("run.lock");
+        let _ = fs::remove_dir_all(&dir);
+
+        // A run that died left its lock behind
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "4194303").unwrap();
+        let lock = acquire_at(&path, false).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        drop(lock);
+        assert!(!path.exists());
+
+        // The test runner's parent (cargo) is alive for as long as this is
+        let parent = std::os::unix::process::parent_id();
+        fs::write(&path, parent.to_string()).unwrap();
+        assert_eq!(holder_at(&path), Some(parent));
+        assert_eq!(acquire_at(&path, false).unwrap_err(), busy(parent));
+        let lock = acquire_at(&path, true).unwrap();
+        assert_eq!(holder_at(&path), None);
+
+        // Taken over by another run: dropping ours leaves theirs
+        fs::write(&path, parent.to_string()).unwrap();
+        drop(lock);
+        assert_eq!(read_pid(&path), Some(parent));
+
+        // Handed over: the new holder has it, and takes it without force
+        let lock = acquire_at(&path, true).unwrap();
+        lock.hand_over(parent).unwrap();
+        assert_eq!(read_pid(&path), Some(parent));
+        fs::write(&path, std::process::id().to_string()).unwrap();
+        let lock = acquire_at(&path, false).unwrap();
+        drop(lock);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn an_empty_lock_is_taken_and_written_whole() {
+        let dir = std::env::temp_dir().join(format!("loadstar-lock-empty-{}", std::process::id()));
+        let path = dir.join("run.lock");
+        let _ = fs::remove_dir_all(&dir);
+
+        // Left by a run that died before writing its PID
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "").unwrap();
+        assert_eq!(holder_at(&path), None);
+        let lock = acquire_at(&path, false).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        assert!(!path.with_extension("lock.partial").exists());
+
+        // and the guard is let go of once it's taken
+        assert!(Guard::take(&path).is_ok());
+        drop(lock);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
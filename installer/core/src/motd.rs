@@ -0,0 +1,186 @@
+//! Message of the day
+//! What a `--cloud-init` run set up, shown at every ssh login so whoever
+//! lands on a fresh dev box knows what's on it without reading logs.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config;
+use crate::executor::InstallSummary;
+use crate::nextsteps::{self, NextStep};
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// Where pam_motd picks it up on Debian, Ubuntu and Fedora
+pub const PATH: &str = "/etc/motd.d/loadstar";
+
+/// Next steps listed before the rest are left to the checklist
+const SHOWN_STEPS: usize = 3;
+
+/// Config files named before the rest are counted
+const SHOWN_CONFIGS: usize = 4;
+
+/// Width of the rules above and below
+const WIDTH: usize = 60;
+
+/// The message for a finished run, as plain text
+pub fn render(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    summary: &InstallSummary,
+    next_steps: &[NextStep],
+) -> String {
+    let title = "─── loadstar ";
+    let mut out = format!("{}{}\n", title, "─".repeat(WIDTH - title.chars().count()));
+    out.push_str(&format!(
+        " {}: {} on {}, set up on first boot\n\n",
+        system.hostname,
+        system.os.name(),
+        system.arch.name()
+    ));
+
+    let row = |out: &mut String, label: &str, value: &str| {
+        out.push_str(&format!(" {:<10} {}\n", label, value));
+    };
+    row(
+        &mut out,
+        "Installed",
+        &format!(
+            "{} tools, {} already there",
+            summary.succeeded.len(),
+            summary.skipped.len()
+        ),
+    );
+    if !summary.failed.is_empty() {
+        let names: Vec<&str> = summary
+            .failed
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        row(&mut out, "Failed", &names.join(", "));
+    }
+
+    let shell = &wizard.shell_config;
+    let mut setup = vec![shell.shell.name(), shell.prompt.name()];
+    setup.extend(shell.multiplexer.map(|m| m.name()));
+    row(&mut out, "Shell", &setup.join(" · "));
+    row(&mut out, "Editor", wizard.editor.name());
+    if let Some(locale) = &wizard.locale {
+        row(&mut out, "Locale", locale);
+    }
+    if let Some(timezone) = &wizard.timezone {
+        row(&mut out, "Timezone", timezone);
+    }
+
+    let configs: Vec<String> = summary
+        .configs
+        .iter()
+        .map(|(_, path)| config::display_path(path, &system.home_dir))
+        .collect();
+    if !configs.is_empty() {
+        let mut shown = configs[..configs.len().min(SHOWN_CONFIGS)].join(", ");
+        if configs.len() > SHOWN_CONFIGS {
+            shown.push_str(&format!(", +{} more", configs.len() - SHOWN_CONFIGS));
+        }
+        row(&mut out, "Configs", &shown);
+    }
+
+    if !next_steps.is_empty() {
+        out.push_str("\n To do:\n");
+        for step in next_steps.iter().take(SHOWN_STEPS) {
+            out.push_str(&format!("   {}  # {}\n", step.command, step.note));
+        }
+        if next_steps.len() > SHOWN_STEPS {
+            out.push_str(&format!(
+                "   +{} more in {}\n",
+                next_steps.len() - SHOWN_STEPS,
+                config::display_path(&nextsteps::checklist_path(system), &system.home_dir)
+            ));
+        }
+    }
+    out.push_str(&"─".repeat(WIDTH));
+    out.push('\n');
+    out
+}
+
+/// Write the message to `PATH`, through `sudo -n` when this isn't root.
+/// Never prompts: there's no one to answer on first boot.
+pub fn write(text: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(PATH);
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    if fs::create_dir_all(dir).is_ok() && fs::write(&path, text).is_ok() {
+        return Ok(path);
+    }
+
+    let made = sudo(&["mkdir", "-p", &dir.display().to_string()], None);
+    if made && sudo(&["tee", PATH], Some(text)) {
+        Ok(path)
+    } else {
+        Err(format!(
+            "Could not write {}; it needs root, and sudo wants a password",
+            PATH
+        ))
+    }
+}
+
+fn sudo(args: &[&str], input: Option<&str>) -> bool {
+    let child = Command::new("sudo")
+        .arg("-n")
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return false;
+    };
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        if stdin.write_all(input.as_bytes()).is_err() {
+            let _ = child.kill();
+        }
+    }
+    child.wait().is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_message_names_what_was_set_up_and_what_is_left() {
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        wizard.timezone = Some("Europe/Berlin".to_string());
+        let summary = InstallSummary {
+            succeeded: vec!["Ripgrep".to_string(), "Bat".to_string()],
+            failed: vec![("fd".to_string(), "exit 1".to_string())],
+            skipped: vec![("Git".to_string(), "Already installed".to_string())],
+            warnings: Vec::new(),
+            configs: vec![(".gitconfig".to_string(), system.home_dir.join(".gitconfig"))],
+        };
+        let steps: Vec<NextStep> = (1..=5)
+            .map(|n| NextStep {
+                command: format!("step {}", n),
+                note: "left over".to_string(),
+            })
+            .collect();
+
+        let motd = render(&wizard, &system, &summary, &steps);
+        assert!(motd.starts_with("─── loadstar ───"));
+        assert!(motd.contains(" Installed  2 tools, 1 already there\n"));
+        assert!(motd.contains(" Failed     fd\n"));
+        assert!(motd.contains(" Timezone   Europe/Berlin\n"));
+        assert!(motd.contains(" Configs    ~/.gitconfig\n"));
+        assert!(!motd.contains("Locale"));
+        assert!(motd.contains("   step 3  # left over\n"));
+        assert!(!motd.contains("step 4"));
+        assert!(motd.contains("   +2 more in "));
+        assert!(motd.ends_with(&format!("{}\n", "─".repeat(WIDTH))));
+    }
+}
@@ -0,0 +1,64 @@
+//! Post-install checklist
+//! Steps only the user can finish, saved next to the profile so they
+//! outlive the READY. screen.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::system::SystemInfo;
+
+/// One thing left to do after the install
+#[derive(Debug, Clone, PartialEq)]
+pub struct NextStep {
+    pub command: String,
+    pub note: String,
+}
+
+/// Where the checklist is saved
+pub fn checklist_path(system: &SystemInfo) -> PathBuf {
+    system.config_dir.join("loadstar").join("next-steps.md")
+}
+
+/// Markdown checklist, one unchecked box per step
+pub fn render_checklist(steps: &[NextStep]) -> String {
+    let mut out = String::from(
+        "# Next steps\n\nLeft over from the last loadstar run. Tick them off as you go.\n\n",
+    );
+    for step in steps {
+        out.push_str(&format!("- [ ] `{}` — {}\n", step.command, step.note));
+    }
+    out
+}
+
+/// Write the checklist, replacing the one from the previous run
+pub fn save(steps: &[NextStep], system: &SystemInfo) -> io::Result<PathBuf> {
+    let path = checklist_path(system);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, render_checklist(steps))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_has_a_box_per_step() {
+        let steps = [
+            NextStep {
+                command: "gcloud init".to_string(),
+                note: "log in and pick a project".to_string(),
+            },
+            NextStep {
+                command: "az login".to_string(),
+                note: "sign in to Azure".to_string(),
+            },
+        ];
+        let checklist = render_checklist(&steps);
+        assert!(checklist.contains("- [ ] `gcloud init` — log in and pick a project\n"));
+        assert_eq!(checklist.matches("- [ ]").count(), 2);
+    }
+}
@@ -0,0 +1,212 @@
+//! New-hire onboarding
+//! `--onboarding <url>` fetches an org's team profile whose `[onboarding]`
+//! section lists what loadstar can't do itself (VPN, SSO, internal
+//! portals). The wizard walks through it after the install and writes a
+//! completion report for IT.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::executor::InstallSummary;
+use crate::github::GitRunner;
+use crate::profile::{ChecklistItem, OnboardingSection, Profile};
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// File name the report is written under
+pub const FILE_NAME: &str = "loadstar-onboarding.md";
+
+/// The team profile at `url`, once it's known to parse and to carry a
+/// checklist. Only https is fetched: the profile picks what gets installed.
+pub fn fetch(runner: &dyn GitRunner, url: &str) -> Result<String, String> {
+    if !url.starts_with("https://") {
+        return Err(format!(
+            "--onboarding needs an https:// link, not '{}'",
+            url
+        ));
+    }
+    let out = runner
+        .run("curl", &["-fsSL", "--proto", "=https", url])
+        .map_err(|e| format!("Could not run curl: {}", e))?;
+    if !out.success {
+        return Err(format!("Could not fetch {}: {}", url, out.stderr.trim()));
+    }
+    let profile =
+        Profile::parse(&out.stdout).map_err(|e| format!("{} isn't a profile: {}", url, e))?;
+    if profile.onboarding.items.is_empty() {
+        return Err(format!(
+            "{} has no [[onboarding.items]] to walk through",
+            url
+        ));
+    }
+    Ok(out.stdout)
+}
+
+/// The checklist being walked through
+#[derive(Debug, Clone)]
+pub struct Checklist {
+    /// Where the profile came from
+    pub source: String,
+    pub org: Option<String>,
+    pub contact: Option<String>,
+    pub items: Vec<ChecklistItem>,
+    /// Ticked off, one per item
+    pub done: Vec<bool>,
+}
+
+impl Checklist {
+    /// `None` when the section has nothing to do
+    pub fn new(source: &str, section: &OnboardingSection) -> Option<Self> {
+        (!section.items.is_empty()).then(|| Self {
+            source: source.to_string(),
+            org: section.org.clone(),
+            contact: section.contact.clone(),
+            items: section.items.clone(),
+            done: vec![false; section.items.len()],
+        })
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(done) = self.done.get_mut(index) {
+            *done = !*done;
+        }
+    }
+
+    pub fn done_count(&self) -> usize {
+        self.done.iter().filter(|d| **d).count()
+    }
+}
+
+/// The completion report: who, which machine, what's ticked off and how
+/// the install went
+pub fn report(
+    checklist: &Checklist,
+    wizard: &WizardState,
+    system: &SystemInfo,
+    summary: &InstallSummary,
+) -> String {
+    let who = match wizard.identity.name.trim() {
+        "" => wizard.identity.email.clone(),
+        name if wizard.identity.email.is_empty() => name.to_string(),
+        name => format!("{} <{}>", name, wizard.identity.email),
+    };
+    let mut out = format!(
+        "# Onboarding: {}\n\n{}'s checklist from {}, on {} ({} on {}). {} of {} done.\n\n## Checklist\n\n",
+        who,
+        checklist.org.as_deref().unwrap_or("The team"),
+        checklist.source,
+        system.hostname,
+        system.os.name(),
+        system.arch.name(),
+        checklist.done_count(),
+        checklist.items.len()
+    );
+    for (item, done) in checklist.items.iter().zip(&checklist.done) {
+        out.push_str(&format!(
+            "- [{}] {}\n",
+            if *done { "x" } else { " " },
+            item.title
+        ));
+    }
+
+    out.push_str(&format!(
+        "\n## Tools\n\n{} installed, {} already there, {} failed.\n",
+        summary.succeeded.len(),
+        summary.skipped.len(),
+        summary.failed.len()
+    ));
+    for (name, error) in &summary.failed {
+        out.push_str(&format!("- **{}** failed: `{}`\n", name, error));
+    }
+
+    if let Some(contact) = &checklist.contact {
+        out.push_str(&format!("\nSend this report to {}.\n", contact));
+    }
+    out
+}
+
+/// Write `report` to `FILE_NAME` under `dir`, returning the path
+pub fn write(report: &str, dir: &Path) -> Result<PathBuf, String> {
+    let path = dir.join(FILE_NAME);
+    fs::write(&path, report).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fakerunner::{fail, ok, FakeRunner};
+    use crate::github::CommandOutput;
+
+    const TEAM: &str = r#"
+apps = ["ripgrep"]
+
+[onboarding]
+org = "Acme"
+contact = "it@acme.example"
+
+[[onboarding.items]]
+title = "Connect to the VPN"
+url = "https://acme.example/vpn"
+
+[[onboarding.items]]
+title = "Sign in to SSO"
+detail = "Use the invite in your inbox"
+"#;
+
+    /// Answers curl's fetch over https with `result`, and nothing else
+    fn curl(result: Result<CommandOutput, String>) -> FakeRunner {
+        FakeRunner::default()
+            .answer("curl -fsSL --proto =https", result)
+            .otherwise(Err("not a curl fetch".to_string()))
+    }
+
+    fn serves(body: &str) -> FakeRunner {
+        curl(ok(body))
+    }
+
+    #[test]
+    fn only_a_team_profile_with_a_checklist_is_fetched() {
+        let url = "https://acme.example/onboarding.toml";
+        assert_eq!(fetch(&serves(TEAM), url).unwrap(), TEAM);
+        assert!(fetch(&serves(TEAM), "http://acme.example/p.toml")
+            .unwrap_err()
+            .contains("https://"));
+        assert!(fetch(&serves("apps = [\"ripgrep\"]\n"), url)
+            .unwrap_err()
+            .contains("no [[onboarding.items]]"));
+        assert!(fetch(&serves("<html>"), url).is_err());
+        let not_found = curl(fail("curl: (22) The requested URL returned error: 404\n"));
+        assert!(fetch(&not_found, url).unwrap_err().ends_with("error: 404"));
+
+        let bad = TEAM.replace("https://acme.example/vpn", "javascript:alert(1)");
+        assert!(Profile::parse(&bad).is_err());
+    }
+
+    #[test]
+    fn the_report_shows_what_is_ticked_off() {
+        let profile = Profile::parse(TEAM).unwrap();
+        let mut checklist =
+            Checklist::new("https://acme.example/p.toml", &profile.onboarding).unwrap();
+        checklist.toggle(0);
+        checklist.toggle(5);
+        assert_eq!(checklist.done_count(), 1);
+
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        wizard.identity.name = "Ada Lovelace".to_string();
+        wizard.identity.email = "ada@acme.example".to_string();
+        let summary = InstallSummary {
+            succeeded: vec!["Ripgrep".to_string()],
+            ..InstallSummary::default()
+        };
+        let report = report(&checklist, &wizard, &system, &summary);
+        assert!(report.starts_with("# Onboarding: Ada Lovelace <ada@acme.example>\n"));
+        assert!(report.contains("Acme's checklist from https://acme.example/p.toml"));
+        assert!(report.contains(". 1 of 2 done.\n"));
+        assert!(report.contains("- [x] Connect to the VPN\n- [ ] Sign in to SSO\n"));
+        assert!(report.contains("1 installed, 0 already there, 0 failed."));
+        assert!(report.ends_with("Send this report to it@acme.example.\n"));
+        assert!(Checklist::new("x", &OnboardingSection::default()).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+//! Post-install PATH verification
+//! Asks the chosen shell for its PATH after everything is installed and adds
+//! any missing tool directories to the managed rc block.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+
+use crate::catalog::InstallMethod;
+use crate::executor::InstallMessage;
+use crate::rcblock;
+use crate::system::SystemInfo;
+use crate::wizard::{ShellChoice, WizardState};
+
+/// Check every directory the selected installers put binaries in and repair
+/// the chosen shell's rc file where they are not reachable.
+pub fn verify_path(wizard: &WizardState, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+    let _ = tx.send(InstallMessage::PhaseStart {
+        phase: "PATH Check".to_string(),
+    });
+
+    let shell = wizard.shell_config.shell;
+    let dirs = expected_dirs(wizard, system);
+    if dirs.is_empty() {
+        let _ = tx.send(InstallMessage::Log(
+            "[PATH] Nothing installed outside the system PATH".to_string(),
+        ));
+        return;
+    }
+
+    let Some(shell_path) = shell_path(shell) else {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "Could not start {} to read its PATH — skipping the PATH check",
+            shell.name()
+        )));
+        return;
+    };
+
+    let missing: Vec<&PathBuf> = dirs.iter().filter(|d| !shell_path.contains(d)).collect();
+    if missing.is_empty() {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[PATH] All {} tool directories are on the {} PATH",
+            dirs.len(),
+            shell.name()
+        )));
+        return;
+    }
+
+    let rc = rcblock::rc_file(shell, system);
+    let lines: Vec<String> = missing
+        .iter()
+        .map(|d| path_line(shell, d, &system.home_dir))
+        .collect();
+    if wizard.dry_run {
+        for line in lines {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[DRY] add {} to {}",
+                line,
+                display(&rc, &system.home_dir)
+            )));
+        }
+        return;
+    }
+
+    match rcblock::add_lines(&rc, &lines) {
+        Ok(added) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[PATH] Added {} line(s) to {}:",
+                added.len(),
+                display(&rc, &system.home_dir)
+            )));
+            for line in added {
+                let _ = tx.send(InstallMessage::Log(format!("  {}", line)));
+            }
+        }
+        Err(e) => {
+            let _ = tx.send(InstallMessage::Log(format!(
+                "[PATH] [ERROR] Could not update {}: {}",
+                rc.display(),
+                e
+            )));
+        }
+    }
+}
+
+/// Directories that hold binaries from the selected install methods.
+/// Only directories that exist are returned — an empty install leaves
+/// nothing to put on PATH.
+fn expected_dirs(wizard: &WizardState, system: &SystemInfo) -> Vec<PathBuf> {
+    let home = &system.home_dir;
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    for app in wizard.get_selected_apps() {
+        let dir = match app.method() {
+            InstallMethod::Brew(_) | InstallMethod::BrewCask(_) => {
+                Some(Path::new(system.brew_prefix()).join("bin"))
+            }
+            InstallMethod::Cargo(_) => Some(home.join(".cargo/bin")),
+            InstallMethod::Npm(_) => Some(system.npm_prefix().join("bin")),
+            InstallMethod::Go(_) => Some(
+                command_output("go", &["env", "GOPATH"])
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| home.join("go"))
+                    .join("bin"),
+            ),
+            InstallMethod::Pip(_) | InstallMethod::Script(_) => Some(home.join(".local/bin")),
+            InstallMethod::Manual(_) | InstallMethod::Apt(_) | InstallMethod::PerOs { .. } => None,
+        };
+
+        if let Some(dir) = dir {
+            if dir.is_dir() && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    dirs
+}
+
+/// PATH as an interactive login shell of the given kind sees it
+fn shell_path(shell: ShellChoice) -> Option<Vec<PathBuf>> {
+    let (program, script) = match shell {
+        ShellChoice::Zsh => ("zsh", "printf '%s\\n' \"$PATH\""),
+        ShellChoice::Bash => ("bash", "printf '%s\\n' \"$PATH\""),
+        ShellChoice::Fish => ("fish", "string join : $PATH"),
+        ShellChoice::Nushell => ("nu", "$env.PATH | str join ':'"),
+    };
+
+    let output = Command::new(program)
+        .args(["-l", "-i", "-c", script])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // rc files may print banners; PATH is the last line
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let path = stdout.lines().rev().find(|l| !l.trim().is_empty())?;
+    Some(split_path(path))
+}
+
+fn split_path(path: &str) -> Vec<PathBuf> {
+    path.split(':')
+        .filter(|p| !p.is_empty())
+        .map(|p| PathBuf::from(p.trim_end_matches('/')))
+        .collect()
+}
+
+/// The rc line that puts `dir` on PATH, written relative to `$HOME` where
+/// the shell expands it inside strings
+fn path_line(shell: ShellChoice, dir: &Path, home: &Path) -> String {
+    let home_relative = || {
+        dir.strip_prefix(home)
+            .map(|rel| format!("$HOME/{}", rel.display()))
+            .unwrap_or_else(|_| dir.display().to_string())
+    };
+
+    match shell {
+        ShellChoice::Zsh | ShellChoice::Bash => {
+            format!("export PATH=\"{}:$PATH\"", home_relative())
+        }
+        ShellChoice::Fish => format!("fish_add_path {}", home_relative()),
+        ShellChoice::Nushell => format!("$env.PATH = ($env.PATH | prepend '{}')", dir.display()),
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!out.is_empty()).then_some(out)
+}
+
+fn display(path: &Path, home: &Path) -> String {
+    path.strip_prefix(home)
+        .map(|p| format!("~/{}", p.display()))
+        .unwrap_or_else(|_| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_lines_per_shell() {
+        let home = Path::new("/home/u");
+        let dir = Path::new("/home/u/.cargo/bin");
+        assert_eq!(
+            path_line(ShellChoice::Zsh, dir, home),
+            "export PATH=\"$HOME/.cargo/bin:$PATH\""
+        );
+        assert_eq!(
+            path_line(ShellChoice::Fish, dir, home),
+            "fish_add_path $HOME/.cargo/bin"
+        );
+        assert_eq!(
+            path_line(ShellChoice::Nushell, dir, home),
+            "$env.PATH = ($env.PATH | prepend '/home/u/.cargo/bin')"
+        );
+        assert_eq!(
+            path_line(ShellChoice::Bash, Path::new("/opt/homebrew/bin"), home),
+            "export PATH=\"/opt/homebrew/bin:$PATH\""
+        );
+    }
+
+    #[test]
+    fn split_path_normalizes_trailing_slashes() {
+        assert_eq!(
+            split_path("/usr/bin:/home/u/.local/bin/::/bin"),
+            vec![
+                PathBuf::from("/usr/bin"),
+                PathBuf::from("/home/u/.local/bin"),
+                PathBuf::from("/bin"),
+            ]
+        );
+    }
+}
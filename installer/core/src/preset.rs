@@ -0,0 +1,146 @@
+//! Named selection presets
+//! An app selection saved under a name from the Review screen, one TOML
+//! file each in `~/.config/loadstar/presets/`, loaded back on DevTools.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::catalog::find_app;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    /// App ids. Ones the catalog no longer has are skipped on load.
+    pub apps: Vec<String>,
+}
+
+/// Where presets are saved
+pub fn presets_dir(system: &SystemInfo) -> PathBuf {
+    system.config_dir.join("loadstar").join("presets")
+}
+
+impl Preset {
+    /// The wizard's current selection under `name`
+    pub fn from_selection(name: &str, wizard: &WizardState) -> Result<Self, String> {
+        let name = name.trim();
+        if file_stem(name).is_empty() {
+            return Err("A preset needs a name with at least one letter or digit".to_string());
+        }
+        let mut apps: Vec<String> = wizard.selected_apps.iter().cloned().collect();
+        apps.sort();
+        Ok(Self {
+            name: name.to_string(),
+            apps,
+        })
+    }
+
+    /// Write the preset, replacing any saved under the same name
+    pub fn save(&self, system: &SystemInfo) -> Result<PathBuf, String> {
+        let dir = presets_dir(system);
+        fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let path = dir.join(format!("{}.toml", file_stem(&self.name)));
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    /// Add the preset's apps to the selection, or with `replace` make them
+    /// the whole selection. Dependencies come along either way.
+    pub fn apply(&self, wizard: &mut WizardState, replace: bool) {
+        if replace {
+            wizard.selected_apps.clear();
+        }
+        wizard.selected_apps.extend(
+            self.apps
+                .iter()
+                .filter(|id| find_app(id).is_some())
+                .cloned(),
+        );
+        wizard.include_dependencies();
+    }
+}
+
+/// Every saved preset, sorted by name. Files that don't parse are skipped.
+pub fn list(system: &SystemInfo) -> Vec<Preset> {
+    let Ok(entries) = fs::read_dir(presets_dir(system)) else {
+        return Vec::new();
+    };
+    let mut presets: Vec<Preset> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| toml::from_str(&content).ok())
+        .collect();
+    presets.sort_by_key(|p| p.name.to_lowercase());
+    presets
+}
+
+/// File name for a preset: lowercase letters and digits, dashes between
+fn file_stem(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_system(name: &str) -> SystemInfo {
+        let mut system = SystemInfo::detect().unwrap();
+        system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-{}-{}", name, std::process::id()));
+        system
+    }
+
+    #[test]
+    fn presets_round_trip_by_name() {
+        let system = scratch_system("presets");
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["ripgrep", "bat"].iter().map(|s| s.to_string()).collect();
+
+        let path = Preset::from_selection("  Work Laptop ", &wizard)
+            .unwrap()
+            .save(&system)
+            .unwrap();
+        assert!(path.ends_with("presets/work-laptop.toml"));
+
+        let presets = list(&system);
+        assert_eq!(presets.len(), 1);
+        assert_eq!(presets[0].name, "Work Laptop");
+        assert_eq!(presets[0].apps, ["bat", "ripgrep"]);
+        let _ = fs::remove_dir_all(&system.config_dir);
+    }
+
+    #[test]
+    fn apply_merges_or_replaces_and_skips_unknown_apps() {
+        let preset = Preset {
+            name: "k8s".to_string(),
+            apps: vec!["k9s".to_string(), "no-such-app".to_string()],
+        };
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["ripgrep".to_string()].into_iter().collect();
+
+        preset.apply(&mut wizard, false);
+        assert!(wizard.selected_apps.contains("ripgrep"));
+        assert!(wizard.selected_apps.contains("k9s"));
+        assert!(!wizard.selected_apps.contains("no-such-app"));
+
+        preset.apply(&mut wizard, true);
+        assert!(!wizard.selected_apps.contains("ripgrep"));
+        assert!(wizard.selected_apps.contains("k9s"));
+    }
+
+    #[test]
+    fn names_need_something_to_file_under() {
+        let wizard = WizardState::new();
+        assert!(Preset::from_selection(" !? ", &wizard).is_err());
+        assert_eq!(file_stem("Rust + Go (2026)"), "rust-go-2026");
+    }
+}
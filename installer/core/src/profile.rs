@@ -0,0 +1,717 @@
+//! User profile — persistent preferences loaded from TOML
+//! Lives at `~/.config/loadstar/profile.toml`. Every section is optional;
+//! a missing file is the same as an empty one. `[hosts.<hostname>]` and
+//! `[tags.<tag>]` sections overlay the rest on the machines they name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::catalog::{find_app, Category};
+use crate::executor::{BrewUpdate, MAX_NETWORK_RETRIES};
+use crate::locale;
+use crate::localmodel;
+use crate::sealed;
+use crate::system::SystemInfo;
+use crate::wizard::ScreenCategories;
+
+/// Parsed profile contents
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Key rebinds: action name → one key or a list of keys
+    pub keys: HashMap<String, KeyList>,
+    /// Installation behaviour
+    pub install: InstallSection,
+    /// Catalog ids to preselect instead of the defaults. Headless runs
+    /// (and `loadstar test`) install exactly these.
+    pub apps: Option<Vec<String>>,
+    /// Which categories DevTools and Apps show
+    pub categories: CategoriesSection,
+    /// Age-encrypted API keys, tokens and passphrases
+    pub secrets: SecretsSection,
+    /// Where the Complete screen backs the setup up to
+    pub backup: BackupSection,
+    /// What the hub's upgrade leaves alone or upgrades greedily
+    pub upgrade: UpgradeSection,
+    /// An org's checklist for new hires, walked through after the install
+    pub onboarding: OnboardingSection,
+    /// How loadstar itself looks
+    pub appearance: AppearanceSection,
+    /// Locale and timezone, mostly for cloud dev boxes
+    pub system: SystemSection,
+    /// The model Ollama pulls
+    pub ai: AiSection,
+}
+
+/// `[upgrade]` section, written back by the hub's upgrade screen
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UpgradeSection {
+    /// Casks that update themselves but are upgraded anyway, as with
+    /// `brew upgrade --greedy`
+    pub greedy: Vec<String>,
+    /// Apps never upgraded, by catalog id
+    pub exclude: Vec<String>,
+}
+
+impl UpgradeSection {
+    pub fn is_greedy(&self, id: &str) -> bool {
+        self.greedy.iter().any(|g| g == id)
+    }
+
+    pub fn is_excluded(&self, id: &str) -> bool {
+        self.exclude.iter().any(|e| e == id)
+    }
+
+    /// Add `id` to `list` or take it out, keeping the list sorted
+    pub fn toggle(list: &mut Vec<String>, id: &str) {
+        match list.iter().position(|entry| entry == id) {
+            Some(i) => {
+                list.remove(i);
+            }
+            None => {
+                list.push(id.to_string());
+                list.sort();
+            }
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, ids) in [("greedy", &self.greedy), ("exclude", &self.exclude)] {
+            if let Some(unknown) = ids.iter().find(|id| find_app(id).is_none()) {
+                anyhow::bail!("Unknown app '{}' in upgrade.{}", unknown, name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write this as the `[upgrade]` section of the profile at `path`,
+    /// replacing the one there. The rest of the file, comments included,
+    /// is left as it was.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let existing = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+        let body = toml::to_string(self).map_err(|e| e.to_string())?;
+        let content = replace_section(&existing, "upgrade", &body);
+        // A file this can't make sense of is left for its owner to fix
+        Profile::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(path, content)
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+}
+
+/// `content` with the `[name]` table's lines swapped for `body`, or with
+/// the table added at the end if there isn't one
+fn replace_section(content: &str, name: &str, body: &str) -> String {
+    let header = format!("[{}]", name);
+    let lines: Vec<&str> = content.lines().collect();
+    let section = format!("{}\n{}", header, body);
+    let Some(start) = lines.iter().position(|line| line.trim() == header) else {
+        let mut content = content.to_string();
+        if !content.is_empty() {
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push('\n');
+        }
+        return content + &section;
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let mut replaced: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+    replaced.extend(section.lines().map(str::to_string));
+    if end < lines.len() {
+        replaced.push(String::new());
+        replaced.extend(lines[end..].iter().map(|l| l.to_string()));
+    }
+    replaced.join("\n") + "\n"
+}
+
+/// `[appearance]` section
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppearanceSection {
+    /// Banner on the boot and Complete screens, by name; a random one
+    /// each run if not set or `"random"`
+    pub banner: Option<String>,
+    /// Seconds without a key before the Install screen goes to attract
+    /// mode; 0 never does
+    pub attract_after: Option<u64>,
+    /// `"braille"`, `"dots"` or `"blocks"`
+    pub spinner: Option<String>,
+    /// How progress bars are drawn: `"blocks"`, `"line"`, `"dots"` or
+    /// `"ascii"`
+    pub gauge: Option<String>,
+}
+
+/// `[system]` section
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SystemSection {
+    /// `LANG` and `LC_ALL` in the shell's rc file, such as `"en_US.UTF-8"`
+    pub locale: Option<String>,
+    /// Set with sudo, such as `"Europe/Berlin"`; left alone if not set
+    pub timezone: Option<String>,
+}
+
+impl SystemSection {
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(locale) = &self.locale {
+            locale::check_locale(locale).map_err(|e| anyhow::anyhow!("system.locale: {}", e))?;
+        }
+        if let Some(timezone) = &self.timezone {
+            locale::check_timezone(timezone)
+                .map_err(|e| anyhow::anyhow!("system.timezone: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// `[ai]` section
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AiSection {
+    /// Such as `"llama3.1:8b"`; unset picks one that fits the machine
+    pub local_model: Option<String>,
+}
+
+impl AiSection {
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(model) = &self.local_model {
+            localmodel::check_name(model).map_err(|e| anyhow::anyhow!("ai.local_model: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// `[backup]` section
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BackupSection {
+    /// Repo name under your GitHub account; `loadstar-setup` if not set
+    pub repo: Option<String>,
+}
+
+/// `[secrets]` section: each value an armored age file (`age --armor`),
+/// never plain text. See `sealed` for decrypting them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecretsSection {
+    /// Key file for values encrypted to a recipient rather than with a
+    /// passphrase. `~/.config/loadstar/key.txt` if not set.
+    pub identity: Option<String>,
+    pub ssh_passphrase: Option<String>,
+    /// Logs gh in, like `$GH_TOKEN` (which wins if both are set)
+    pub github_token: Option<String>,
+    pub anthropic_key: Option<String>,
+    pub openai_key: Option<String>,
+}
+
+impl SecretsSection {
+    pub fn is_empty(&self) -> bool {
+        self.values().all(|(_, value)| value.is_none())
+    }
+
+    fn values(&self) -> impl Iterator<Item = (&'static str, &Option<String>)> {
+        [
+            ("ssh_passphrase", &self.ssh_passphrase),
+            ("github_token", &self.github_token),
+            ("anthropic_key", &self.anthropic_key),
+            ("openai_key", &self.openai_key),
+        ]
+        .into_iter()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, value) in self.values() {
+            if value.as_deref().is_some_and(|v| !sealed::is_armored(v)) {
+                anyhow::bail!(
+                    "secrets.{} isn't age-encrypted; replace it with the output of `age --armor`",
+                    name
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[onboarding]` section: what a new hire does by hand once the tools
+/// are in, like the VPN, SSO and internal portals
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OnboardingSection {
+    /// Who the checklist is from, shown on the screen and the report
+    pub org: Option<String>,
+    /// Where the report goes, such as IT's address or channel
+    pub contact: Option<String>,
+    pub items: Vec<ChecklistItem>,
+}
+
+/// One `[[onboarding.items]]` entry
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChecklistItem {
+    pub title: String,
+    /// How to do it, in a sentence or two
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// A page that does it or explains it
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl OnboardingSection {
+    fn validate(&self) -> anyhow::Result<()> {
+        for (i, item) in self.items.iter().enumerate() {
+            if item.title.trim().is_empty() {
+                anyhow::bail!("onboarding.items[{}] needs a title", i);
+            }
+            if let Some(url) = &item.url {
+                if !url.starts_with("https://") && !url.starts_with("http://") {
+                    anyhow::bail!(
+                        "onboarding.items[{}].url must be an http(s) link, not '{}'",
+                        i,
+                        url
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[categories]` section: category ids per screen, in display order
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CategoriesSection {
+    pub devtools: Option<Vec<String>>,
+    pub apps: Option<Vec<String>>,
+}
+
+impl CategoriesSection {
+    /// The lists to show, defaults filling in for screens not listed
+    pub fn screens(&self) -> ScreenCategories {
+        let resolve = |ids: &Vec<String>| -> Vec<Category> {
+            ids.iter().filter_map(|id| Category::from_id(id)).collect()
+        };
+        let defaults = ScreenCategories::default();
+        ScreenCategories {
+            devtools: self.devtools.as_ref().map_or(defaults.devtools, resolve),
+            apps: self.apps.as_ref().map_or(defaults.apps, resolve),
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (screen, ids) in [("devtools", &self.devtools), ("apps", &self.apps)] {
+            let Some(ids) = ids else { continue };
+            if ids.is_empty() {
+                anyhow::bail!("categories.{} needs at least one category", screen);
+            }
+            if let Some(unknown) = ids.iter().find(|id| Category::from_id(id).is_none()) {
+                anyhow::bail!("Unknown category '{}' in categories.{}", unknown, screen);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[install]` section
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct InstallSection {
+    /// `"full"`, `"skip"` or `"background"`
+    pub brew_update: Option<BrewUpdate>,
+    /// Run `brew cleanup` after installing
+    pub brew_cleanup: Option<bool>,
+    /// One `brew install` per phase (faster, coarser progress)
+    pub brew_batch: Option<bool>,
+    /// Don't install or use Homebrew on Linux
+    pub native_packages: Option<bool>,
+    /// How many more times a package that failed on the network is tried
+    pub network_retries: Option<u32>,
+    /// Seconds a package may take before it's stopped; 0 for no limit
+    pub package_timeout: Option<u64>,
+}
+
+impl InstallSection {
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(retries) = self.network_retries.filter(|r| *r > MAX_NETWORK_RETRIES) {
+            anyhow::bail!(
+                "install.network_retries is {}; at most {} retries are tried",
+                retries,
+                MAX_NETWORK_RETRIES
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A binding may be written as `up = "k"` or `up = ["Up", "k"]`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(k) => vec![k.as_str()],
+            KeyList::Many(ks) => ks.iter().map(|k| k.as_str()).collect(),
+        }
+    }
+}
+
+impl Profile {
+    /// Default profile location under the user's config directory
+    pub fn default_path(system: &SystemInfo) -> PathBuf {
+        system.config_dir.join("loadstar").join("profile.toml")
+    }
+
+    /// Load a profile from disk, as the machine called `hostname` sees it.
+    /// A missing file yields the default profile.
+    pub fn load(path: &Path, hostname: &str) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read {}: {}", path.display(), e))?;
+        Self::parse_for(&content, hostname)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// Parse profile TOML, leaving out every host and tag section
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        Self::parse_for(content, "")
+    }
+
+    /// Parse profile TOML with the sections for `hostname` applied. Every
+    /// other host's and tag's sections are checked too, so a mistake in one
+    /// shows up on whichever machine loads the file.
+    pub fn parse_for(content: &str, hostname: &str) -> anyhow::Result<Self> {
+        let table: toml::Table = toml::from_str(content)?;
+        let machines = Machines::take(table)?;
+        for host in machines.hosts.keys() {
+            Self::build(machines.resolve(host)?)
+                .map_err(|e| anyhow::anyhow!("With hosts.{}: {}", host, e))?;
+        }
+        for (tag, section) in &machines.tags {
+            let mut profile = machines.base.clone();
+            merge(&mut profile, overlay(section, &format!("tags.{}", tag))?);
+            Self::build(profile).map_err(|e| anyhow::anyhow!("With tags.{}: {}", tag, e))?;
+        }
+        let host = host_section(&machines.hosts, hostname)
+            .cloned()
+            .unwrap_or_default();
+        Self::build(machines.resolve(&host)?)
+    }
+
+    fn build(table: toml::Table) -> anyhow::Result<Self> {
+        let profile: Self = table.try_into()?;
+        if let Some(apps) = &profile.apps {
+            if let Some(unknown) = apps.iter().find(|id| find_app(id).is_none()) {
+                anyhow::bail!("Unknown app '{}' in apps", unknown);
+            }
+        }
+        profile.categories.validate()?;
+        profile.install.validate()?;
+        profile.secrets.validate()?;
+        profile.upgrade.validate()?;
+        profile.onboarding.validate()?;
+        profile.system.validate()?;
+        profile.ai.validate()?;
+        Ok(profile)
+    }
+}
+
+// ─── Machine sections ────────────────────────────────────────────────
+
+/// A profile split into the part every machine shares and its overlays
+struct Machines {
+    base: toml::Table,
+    hosts: toml::Table,
+    tags: toml::Table,
+}
+
+impl Machines {
+    fn take(mut base: toml::Table) -> anyhow::Result<Self> {
+        let mut sections = |key: &str| match base.remove(key) {
+            None => Ok(toml::Table::new()),
+            Some(toml::Value::Table(sections)) => Ok(sections),
+            Some(_) => Err(anyhow::anyhow!("{} must be a table of sections", key)),
+        };
+        let hosts = sections("hosts")?;
+        let tags = sections("tags")?;
+        Ok(Self { base, hosts, tags })
+    }
+
+    /// The profile for `host`: the base, then each of its tags' sections
+    /// in the order it lists them, then its own section. No section named
+    /// `host` leaves the base as it is.
+    fn resolve(&self, host: &str) -> anyhow::Result<toml::Table> {
+        let mut profile = self.base.clone();
+        let Some(section) = self.hosts.get(host) else {
+            return Ok(profile);
+        };
+        let mut section = overlay(section, &format!("hosts.{}", host))?;
+        let tags: Vec<String> = match section.remove("tags") {
+            None => Vec::new(),
+            Some(tags) => tags
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("hosts.{}.tags must be a list of names", host))?,
+        };
+        for tag in &tags {
+            let Some(tagged) = self.tags.get(tag) else {
+                anyhow::bail!(
+                    "hosts.{} has tag '{}', but there's no [tags.{}]",
+                    host,
+                    tag,
+                    tag
+                );
+            };
+            merge(&mut profile, overlay(tagged, &format!("tags.{}", tag))?);
+        }
+        merge(&mut profile, section);
+        Ok(profile)
+    }
+}
+
+/// One overlay section, which can't hold overlays of its own
+fn overlay(section: &toml::Value, name: &str) -> anyhow::Result<toml::Table> {
+    let Some(table) = section.as_table() else {
+        anyhow::bail!("{} must be a section", name);
+    };
+    // A host's `tags` is a list of names; a table there is another overlay
+    if let Some(key) = ["hosts", "tags"]
+        .into_iter()
+        .find(|key| table.get(*key).is_some_and(toml::Value::is_table))
+    {
+        anyhow::bail!("{} can't contain [{}] sections", name, key);
+    }
+    Ok(table.clone())
+}
+
+/// Lay `top` over `base`: sections merge key by key, anything else
+/// (a list of apps included) replaces what was there
+fn merge(base: &mut toml::Table, top: toml::Table) {
+    for (key, value) in top {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(below)), toml::Value::Table(above)) => merge(below, above),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The `[hosts.*]` section for `hostname`. One named exactly for it wins
+/// over one that only shares its first label.
+fn host_section<'a>(hosts: &'a toml::Table, hostname: &str) -> Option<&'a String> {
+    let exact = hosts
+        .keys()
+        .find(|host| !hostname.is_empty() && host.eq_ignore_ascii_case(hostname));
+    exact.or_else(|| hosts.keys().find(|host| same_host(host, hostname)))
+}
+
+/// Host names compare without case, and `mbp` matches `mbp.local`
+fn same_host(section: &str, hostname: &str) -> bool {
+    let short = |name: &str| name.split('.').next().unwrap_or(name).to_lowercase();
+    !hostname.is_empty()
+        && (section.eq_ignore_ascii_case(hostname) || short(section) == short(hostname))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_upgrade_section_is_replaced_in_place() {
+        let content = "# My machines\napps = [\"bat\"]\n\n[upgrade]\nexclude = [\"docker\"]\n\n[install]\nbrew_batch = true\n";
+        let section = UpgradeSection {
+            greedy: vec!["vscode".to_string()],
+            exclude: Vec::new(),
+        };
+        let body = toml::to_string(&section).unwrap();
+        let replaced = replace_section(content, "upgrade", &body);
+        assert!(replaced.starts_with("# My machines\n"));
+        assert!(!replaced.contains("docker"));
+        assert!(replaced.ends_with("\n\n[install]\nbrew_batch = true\n"));
+        let profile = Profile::parse(&replaced).unwrap();
+        assert_eq!(profile.upgrade, section);
+        assert_eq!(profile.install.brew_batch, Some(true));
+
+        let added = replace_section("apps = []", "upgrade", &body);
+        assert!(added.starts_with("apps = []\n\n[upgrade]\n"));
+        assert!(Profile::parse("[upgrade]\nexclude = [\"nope\"]\n").is_err());
+    }
+
+    #[test]
+    fn empty_profile_parses() {
+        let profile = Profile::parse("").unwrap();
+        assert!(profile.keys.is_empty());
+    }
+
+    #[test]
+    fn keys_accept_string_or_list() {
+        let profile = Profile::parse(
+            r#"
+[keys]
+up = "w"
+down = ["Down", "s"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(profile.keys["up"].keys(), vec!["w"]);
+        assert_eq!(profile.keys["down"].keys(), vec!["Down", "s"]);
+    }
+
+    #[test]
+    fn install_section_parses_brew_update() {
+        let profile = Profile::parse("[install]\nbrew_update = \"background\"\n").unwrap();
+        assert_eq!(profile.install.brew_update, Some(BrewUpdate::Background));
+
+        assert_eq!(profile.install.brew_cleanup, None);
+
+        assert!(Profile::parse("[install]\nbrew_update = \"sometimes\"\n").is_err());
+        assert!(Profile::parse("[install]\nnetwork_retries = 10\n").is_ok());
+        assert!(Profile::parse("[install]\nnetwork_retries = 4000000000\n").is_err());
+    }
+
+    #[test]
+    fn apps_must_be_in_the_catalog() {
+        let profile = Profile::parse("apps = [\"ripgrep\", \"bat\"]\n").unwrap();
+        assert_eq!(profile.apps.unwrap(), ["ripgrep", "bat"]);
+
+        assert!(Profile::parse("").unwrap().apps.is_none());
+        assert!(Profile::parse("apps = [\"ripgrap\"]\n").is_err());
+    }
+
+    #[test]
+    fn system_section_takes_only_plain_names() {
+        let profile =
+            Profile::parse("[system]\nlocale = \"de_DE.UTF-8\"\ntimezone = \"Europe/Berlin\"\n")
+                .unwrap();
+        assert_eq!(profile.system.locale.as_deref(), Some("de_DE.UTF-8"));
+        assert_eq!(profile.system.timezone.as_deref(), Some("Europe/Berlin"));
+
+        let err = Profile::parse("[system]\ntimezone = \"../../etc/shadow\"\n").unwrap_err();
+        assert!(err.to_string().contains("system.timezone"));
+
+        let profile = Profile::parse("[ai]\nlocal_model = \"llama3.1:8b\"\n").unwrap();
+        assert_eq!(profile.ai.local_model.as_deref(), Some("llama3.1:8b"));
+        assert!(Profile::parse("[ai]\nlocal_model = \"--insecure\"\n").is_err());
+    }
+
+    #[test]
+    fn categories_override_one_screen_at_a_time() {
+        let profile =
+            Profile::parse("[categories]\ndevtools = [\"ai\", \"language_server\"]\n").unwrap();
+        let screens = profile.categories.screens();
+        assert_eq!(screens.devtools, [Category::AI, Category::LanguageServer]);
+        assert_eq!(screens.apps, Category::all());
+
+        assert!(Profile::parse("[categories]\napps = []\n").is_err());
+        assert!(Profile::parse("[categories]\napps = [\"games\"]\n").is_err());
+    }
+
+    const MACHINES: &str = r#"
+apps = ["ripgrep", "bat"]
+
+[install]
+brew_update = "full"
+brew_batch = true
+
+[tags.work.install]
+brew_update = "skip"
+
+[tags.quiet]
+apps = ["ripgrep"]
+
+[hosts.acme-mbp]
+tags = ["work", "quiet"]
+apps = ["ripgrep", "docker"]
+
+[hosts.den]
+tags = ["quiet"]
+"#;
+
+    #[test]
+    fn host_and_tag_sections_overlay_the_base() {
+        let base = Profile::parse(MACHINES).unwrap();
+        assert_eq!(base.apps.unwrap(), ["ripgrep", "bat"]);
+        assert_eq!(base.install.brew_update, Some(BrewUpdate::Full));
+
+        // Tags first, in order, then the host's own section
+        let work = Profile::parse_for(MACHINES, "ACME-MBP.local").unwrap();
+        assert_eq!(work.apps.unwrap(), ["ripgrep", "docker"]);
+        assert_eq!(work.install.brew_update, Some(BrewUpdate::Skip));
+        assert_eq!(work.install.brew_batch, Some(true));
+
+        let den = Profile::parse_for(MACHINES, "den").unwrap();
+        assert_eq!(den.apps.unwrap(), ["ripgrep"]);
+        assert_eq!(den.install.brew_update, Some(BrewUpdate::Full));
+        assert_eq!(
+            Profile::parse_for(MACHINES, "ci-runner")
+                .unwrap()
+                .apps
+                .unwrap(),
+            ["ripgrep", "bat"]
+        );
+    }
+
+    #[test]
+    fn every_host_section_is_checked() {
+        let typo = "[hosts.elsewhere]\napps = [\"ripgrap\"]\n";
+        let err = Profile::parse_for(typo, "here").unwrap_err().to_string();
+        assert!(err.contains("hosts.elsewhere"), "{}", err);
+
+        // `mbp` sorts first, but `mbp.work` is this host's own section
+        let both = "[hosts.mbp]\napps = [\"bat\"]\n\n[hosts.\"mbp.work\"]\napps = [\"ripgrep\"]\n";
+        let work = Profile::parse_for(both, "MBP.work").unwrap();
+        assert_eq!(work.apps.unwrap(), ["ripgrep"]);
+        let home = Profile::parse_for(both, "mbp.local").unwrap();
+        assert_eq!(home.apps.unwrap(), ["bat"]);
+        assert!(Profile::parse("[hosts.den]\ntags = [\"home\"]\n").is_err());
+        assert!(Profile::parse("[tags.work.hosts.den]\napps = []\n").is_err());
+        assert!(Profile::parse("hosts = 1\n").is_err());
+    }
+
+    #[test]
+    fn secrets_must_be_encrypted() {
+        let armored =
+            "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----\n";
+        let profile =
+            Profile::parse(&format!("[secrets]\nopenai_key = '''\n{}'''\n", armored)).unwrap();
+        assert_eq!(profile.secrets.openai_key.as_deref(), Some(armored));
+        assert!(Profile::parse("").unwrap().secrets.is_empty());
+
+        let err = Profile::parse("[secrets]\nopenai_key = \"sk-123\"\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("secrets.openai_key isn't age-encrypted"));
+    }
+
+    #[test]
+    fn missing_file_is_default() {
+        let profile =
+            Profile::load(Path::new("/nonexistent/loadstar/profile.toml"), "host").unwrap();
+        assert!(profile.keys.is_empty());
+    }
+
+    #[test]
+    fn invalid_toml_is_an_error() {
+        assert!(Profile::parse("[keys\nup = ").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Managed block in shell rc files and ~/.ssh/config
+//! loadstar owns the lines between its markers and never touches anything
+//! outside them, so re-runs update the block instead of appending to it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::system::SystemInfo;
+use crate::wizard::ShellChoice;
+
+pub const BEGIN: &str = "# >>> loadstar >>>";
+pub const END: &str = "# <<< loadstar <<<";
+
+/// Lines currently inside the managed block (empty if there is no block)
+pub fn block_lines(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .skip_while(|l| l.trim() != BEGIN)
+        .skip(1)
+        .take_while(|l| l.trim() != END)
+        .collect()
+}
+
+/// Merge `lines` into the managed block of `content`, creating the block at
+/// the end if needed. Returns the new content and the lines that were not
+/// already present.
+pub fn merge(content: &str, lines: &[String]) -> (String, Vec<String>) {
+    let existing = block_lines(content);
+    let added: Vec<String> = lines
+        .iter()
+        .filter(|l| !existing.contains(&l.as_str()))
+        .cloned()
+        .collect();
+
+    if added.is_empty() {
+        return (content.to_string(), added);
+    }
+
+    let mut block: Vec<String> = existing.iter().map(|l| l.to_string()).collect();
+    block.extend(added.iter().cloned());
+    (with_block(content, &block), added)
+}
+
+/// Put `line` in the managed block of `content`, dropping any block lines
+/// that start with `prefix` (an older value of the same setting)
+pub fn replace(content: &str, prefix: &str, line: &str) -> String {
+    let mut block: Vec<String> = block_lines(content)
+        .into_iter()
+        .filter(|l| !l.starts_with(prefix))
+        .map(|l| l.to_string())
+        .collect();
+    block.push(line.to_string());
+    with_block(content, &block)
+}
+
+/// `content` with its managed block (or a new one at the end) holding `block`
+pub fn with_block(content: &str, block: &[String]) -> String {
+    let mut out = String::new();
+    let push_block = |out: &mut String| {
+        out.push_str(BEGIN);
+        out.push('\n');
+        for line in block {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(END);
+        out.push('\n');
+    };
+
+    if content.lines().any(|l| l.trim() == BEGIN) {
+        let mut inside = false;
+        for line in content.lines() {
+            if line.trim() == BEGIN {
+                inside = true;
+                push_block(&mut out);
+            } else if inside {
+                inside = line.trim() != END;
+            } else {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    } else {
+        out.push_str(content);
+        if !content.is_empty() {
+            if !content.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        push_block(&mut out);
+    }
+
+    out
+}
+
+/// The rc file loadstar manages for a shell
+pub fn rc_file(shell: ShellChoice, system: &SystemInfo) -> PathBuf {
+    match shell {
+        ShellChoice::Zsh => system.home_dir.join(".zshrc"),
+        ShellChoice::Bash => system.home_dir.join(".bashrc"),
+        ShellChoice::Fish => system.config_dir.join("fish").join("config.fish"),
+        ShellChoice::Nushell => system.config_dir.join("nushell").join("env.nu"),
+    }
+}
+
+/// Export an environment variable from the managed block, replacing any
+/// earlier value loadstar wrote
+pub fn set_var(path: &Path, shell: ShellChoice, name: &str, value: &str) -> std::io::Result<()> {
+    let (prefix, line) = match shell {
+        ShellChoice::Zsh | ShellChoice::Bash => (
+            format!("export {}=", name),
+            format!("export {}=\"{}\"", name, value),
+        ),
+        ShellChoice::Fish => (
+            format!("set -gx {} ", name),
+            format!("set -gx {} \"{}\"", name, value),
+        ),
+        ShellChoice::Nushell => (
+            format!("$env.{} =", name),
+            format!("$env.{} = \"{}\"", name, value),
+        ),
+    };
+
+    let content = read_or_empty(path)?;
+    write(path, &replace(&content, &prefix, &line))
+}
+
+/// Add `lines` to the managed block of the file at `path` (created if
+/// missing). Returns the lines that were actually added.
+pub fn add_lines(path: &Path, lines: &[String]) -> std::io::Result<Vec<String>> {
+    let content = read_or_empty(path)?;
+    let (updated, added) = merge(&content, lines);
+    if !added.is_empty() {
+        write(path, &updated)?;
+    }
+    Ok(added)
+}
+
+fn read_or_empty(path: &Path) -> std::io::Result<String> {
+    if path.exists() {
+        fs::read_to_string(path)
+    } else {
+        Ok(String::new())
+    }
+}
+
+fn write(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(ls: &[&str]) -> Vec<String> {
+        ls.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn creates_block_at_end() {
+        let (out, added) = merge("alias ll='ls -l'\n", &lines(&["export A=1"]));
+        assert_eq!(added, lines(&["export A=1"]));
+        assert_eq!(
+            out,
+            format!("alias ll='ls -l'\n\n{}\nexport A=1\n{}\n", BEGIN, END)
+        );
+    }
+
+    #[test]
+    fn merges_into_existing_block_and_keeps_surroundings() {
+        let content = format!("before\n{}\nexport A=1\n{}\nafter\n", BEGIN, END);
+        let (out, added) = merge(&content, &lines(&["export A=1", "export B=2"]));
+
+        assert_eq!(added, lines(&["export B=2"]));
+        assert_eq!(
+            out,
+            format!(
+                "before\n{}\nexport A=1\nexport B=2\n{}\nafter\n",
+                BEGIN, END
+            )
+        );
+    }
+
+    #[test]
+    fn nothing_added_leaves_content_untouched() {
+        let content = format!("{}\nexport A=1\n{}\n", BEGIN, END);
+        let (out, added) = merge(&content, &lines(&["export A=1"]));
+        assert!(added.is_empty());
+        assert_eq!(out, content);
+    }
+
+    #[test]
+    fn replace_drops_the_old_value() {
+        let content = format!(
+            "{}\nexport EDITOR=\"vim\"\nexport PATH=\"x:$PATH\"\n{}\n",
+            BEGIN, END
+        );
+        let out = replace(&content, "export EDITOR=", "export EDITOR=\"hx\"");
+        assert_eq!(
+            block_lines(&out),
+            ["export PATH=\"x:$PATH\"", "export EDITOR=\"hx\""]
+        );
+    }
+
+    #[test]
+    fn block_lines_of_missing_block_is_empty() {
+        assert!(block_lines("export A=1\n").is_empty());
+    }
+}
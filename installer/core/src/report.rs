@@ -0,0 +1,186 @@
+//! Run summary export
+//! The Complete screen as Markdown for a team wiki or an onboarding doc:
+//! what installed and at which version, the config files written and
+//! what's left to do.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::catalog::InstallMethod;
+use crate::config;
+use crate::executor::{InstallSummary, BREW_ENV};
+use crate::failure::FailureKind;
+use crate::github::GitRunner;
+use crate::nextsteps::NextStep;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+/// File name the summary is written under
+pub const FILE_NAME: &str = "loadstar-summary.md";
+
+/// What Homebrew has installed, package to version. Empty when brew is
+/// missing or fails.
+pub fn brew_versions(runner: &dyn GitRunner) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+    for kind in ["--formula", "--cask"] {
+        let output = runner
+            .run_with_env("brew", &["list", kind, "--versions"], BREW_ENV)
+            .ok()
+            .filter(|o| o.success);
+        if let Some(output) = output {
+            versions.extend(parse_versions(&output.stdout));
+        }
+    }
+    versions
+}
+
+/// `name version...` lines; a package with several versions kept gets
+/// the last, which is the newest
+fn parse_versions(out: &str) -> HashMap<String, String> {
+    out.lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let name = words.next()?;
+            let version = words.last()?;
+            Some((name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// The summary for a finished run. `versions` comes from `brew_versions`;
+/// tools brew didn't install have no version in it.
+pub fn render(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    summary: &InstallSummary,
+    next_steps: &[NextStep],
+    versions: &HashMap<String, String>,
+) -> String {
+    let apps = wizard.get_apps_to_install();
+    let mut out = format!(
+        "# Setting up {}\n\n{} on {}, with loadstar: {} installed, {} already there, {} failed.\n",
+        system.hostname,
+        system.os.name(),
+        system.arch.name(),
+        summary.succeeded.len(),
+        summary.skipped.len(),
+        summary.failed.len()
+    );
+
+    if !summary.succeeded.is_empty() {
+        out.push_str("\n## Installed\n\n| Tool | Version | Installed with |\n|---|---|---|\n");
+        for name in &summary.succeeded {
+            let app = apps.iter().find(|app| app.name == name);
+            let version = app
+                .and_then(|app| match app.method() {
+                    InstallMethod::Brew(pkg) | InstallMethod::BrewCask(pkg) => {
+                        versions.get(pkg.rsplit('/').next().unwrap_or(pkg))
+                    }
+                    _ => None,
+                })
+                .map_or("—", String::as_str);
+            let command = app.map_or_else(String::new, |app| {
+                format!("`{}`", app.install_command().replace('|', "\\|"))
+            });
+            out.push_str(&format!("| {} | {} | {} |\n", name, version, command));
+        }
+    }
+
+    if !summary.skipped.is_empty() {
+        out.push_str("\n## Already there\n\n");
+        for (name, reason) in &summary.skipped {
+            out.push_str(&format!("- {} — {}\n", name, reason));
+        }
+    }
+
+    if !summary.failed.is_empty() {
+        out.push_str("\n## Failed\n\n");
+        for (name, error) in &summary.failed {
+            out.push_str(&format!("- **{}** — `{}`\n", name, error));
+            let kind = FailureKind::classify(error);
+            let method = apps
+                .iter()
+                .find(|app| app.name == name)
+                .map(|app| app.method());
+            if let Some(hint) = kind.hint(method) {
+                out.push_str(&format!("  - {}: {}\n", kind.name(), hint));
+            }
+        }
+    }
+
+    if !summary.configs.is_empty() {
+        out.push_str("\n## Config files\n\n");
+        for (label, path) in &summary.configs {
+            let path = config::display_path(path, &system.home_dir);
+            out.push_str(&format!("- {} — `{}`\n", label, path));
+        }
+        out.push_str(
+            "\nA file that was already there is backed up next to itself as `*.load-backup`.\n",
+        );
+    }
+
+    out.push_str("\n## Next steps\n\n- [ ] `exec $SHELL` — reload your shell\n");
+    for step in next_steps {
+        out.push_str(&format!("- [ ] `{}` — {}\n", step.command, step.note));
+    }
+    out
+}
+
+/// Write `markdown` to `FILE_NAME` under `dir`, returning the path
+pub fn write(markdown: &str, dir: &Path) -> Result<PathBuf, String> {
+    let path = dir.join(FILE_NAME);
+    fs::write(&path, markdown).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_summary_has_versions_failures_and_next_steps() {
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        wizard.selected_apps = ["ripgrep".to_string(), "rustup".to_string()]
+            .into_iter()
+            .collect();
+        let summary = InstallSummary {
+            succeeded: vec!["Ripgrep".to_string(), "Rustup".to_string()],
+            failed: vec![(
+                "fd".to_string(),
+                "curl: (6) Could not resolve host: ghcr.io".to_string(),
+            )],
+            skipped: vec![("Git".to_string(), "Already installed".to_string())],
+            warnings: Vec::new(),
+            configs: vec![(".gitconfig".to_string(), system.home_dir.join(".gitconfig"))],
+        };
+        let steps = [NextStep {
+            command: "gh auth login".to_string(),
+            note: "log in to GitHub".to_string(),
+        }];
+        let versions = parse_versions("ripgrep 14.0.3 14.1.0\nfirefox 131.0\n");
+        assert_eq!(versions["ripgrep"], "14.1.0");
+
+        let markdown = render(&wizard, &system, &summary, &steps, &versions);
+        assert!(markdown.starts_with(&format!("# Setting up {}\n", system.hostname)));
+        assert!(markdown.contains("2 installed, 1 already there, 1 failed."));
+        let ripgrep = markdown
+            .lines()
+            .find(|l| l.starts_with("| Ripgrep |"))
+            .unwrap();
+        assert!(ripgrep.starts_with("| Ripgrep | 14.1.0 | `"), "{}", ripgrep);
+        // Pipes in a command would end its table cell
+        let rustup = markdown
+            .lines()
+            .find(|l| l.starts_with("| Rustup |"))
+            .unwrap();
+        assert!(rustup.contains(" \\| sh"), "{}", rustup);
+        assert!(markdown.contains("- Git — Already installed\n"));
+        assert!(markdown.contains("  - network: Check the connection"));
+        assert!(markdown.contains("- .gitconfig — `~/.gitconfig`\n"));
+        // Only what the run wrote
+        assert!(!markdown.contains(".zshrc"));
+        assert!(markdown.ends_with("- [ ] `gh auth login` — log in to GitHub\n"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Environment scan
+//! Looks for signs of what the user already runs (their shell, a tmux
+//! config, cargo on PATH) and seeds the wizard's defaults from them.
+
+use std::path::Path;
+
+use crate::system::SystemInfo;
+use crate::wizard::{
+    EditorChoice, MultiplexerChoice, PromptChoice, ShellChoice, TerminalChoice, WizardState,
+};
+
+/// A default the scan changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seed {
+    Shell(ShellChoice),
+    Prompt(PromptChoice),
+    Terminal(TerminalChoice),
+    Multiplexer(MultiplexerChoice),
+    Editor(EditorChoice),
+    /// Preselect a catalog app
+    App(&'static str),
+}
+
+impl Seed {
+    pub fn describe(&self) -> String {
+        match self {
+            Seed::Shell(shell) => format!("{} as the shell", shell.name()),
+            Seed::Prompt(prompt) => format!("{} as the prompt", prompt.name()),
+            Seed::Terminal(terminal) => format!("{} as the terminal", terminal.name()),
+            Seed::Multiplexer(mux) => format!("{} as the multiplexer", mux.name()),
+            Seed::Editor(editor) => format!("{} as the editor", editor.name()),
+            Seed::App(id) => format!("{} preselected", id),
+        }
+    }
+}
+
+/// What was seen, and the default it leads to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub signal: String,
+    pub seed: Seed,
+}
+
+/// Scan this machine
+pub fn scan(system: &SystemInfo) -> Vec<Hint> {
+    scan_with(system, &|var| std::env::var(var).ok(), &|cmd| {
+        system.find_tool(cmd).is_some()
+    })
+}
+
+/// Scan with `env` standing in for the environment and `has_tool` for
+/// PATH lookups. At most one hint per choice; the first signal wins.
+pub fn scan_with(
+    system: &SystemInfo,
+    env: &dyn Fn(&str) -> Option<String>,
+    has_tool: &dyn Fn(&str) -> bool,
+) -> Vec<Hint> {
+    let home = &system.home_dir;
+    let config = &system.config_dir;
+    let mut hints = Vec::new();
+    let mut hint = |signal: String, seed: Seed| hints.push(Hint { signal, seed });
+    let exists = |path: &Path| path.exists();
+    let shown = |path: &Path| match path.strip_prefix(home) {
+        Ok(rest) => format!("~/{}", rest.display()),
+        Err(_) => path.display().to_string(),
+    };
+
+    let shell = match command_name(&system.shell) {
+        "zsh" => Some(ShellChoice::Zsh),
+        "bash" => Some(ShellChoice::Bash),
+        "fish" => Some(ShellChoice::Fish),
+        "nu" => Some(ShellChoice::Nushell),
+        _ => None,
+    };
+    if let Some(shell) = shell {
+        hint(format!("$SHELL is {}", system.shell), Seed::Shell(shell));
+    }
+
+    let prompts = [
+        (config.join("starship.toml"), PromptChoice::Starship),
+        (home.join(".p10k.zsh"), PromptChoice::Powerlevel10k),
+    ];
+    if let Some((path, prompt)) = prompts.iter().find(|(path, _)| exists(path)) {
+        hint(shown(path), Seed::Prompt(*prompt));
+    }
+
+    let term = env("TERM_PROGRAM")
+        .and_then(|program| {
+            let terminal = match program.as_str() {
+                "iTerm.app" => TerminalChoice::ITerm2,
+                "WezTerm" => TerminalChoice::WezTerm,
+                "ghostty" => TerminalChoice::Ghostty,
+                _ => return None,
+            };
+            Some((format!("$TERM_PROGRAM is {}", program), terminal))
+        })
+        .or_else(|| {
+            (env("TERM").as_deref() == Some("xterm-kitty"))
+                .then(|| ("$TERM is xterm-kitty".to_string(), TerminalChoice::Kitty))
+        })
+        .or_else(|| {
+            [
+                (config.join("ghostty"), TerminalChoice::Ghostty),
+                (config.join("wezterm"), TerminalChoice::WezTerm),
+                (home.join(".wezterm.lua"), TerminalChoice::WezTerm),
+                (config.join("kitty"), TerminalChoice::Kitty),
+                (config.join("alacritty"), TerminalChoice::Alacritty),
+            ]
+            .into_iter()
+            .find(|(path, _)| exists(path))
+            .map(|(path, terminal)| (shown(&path), terminal))
+        });
+    if let Some((signal, terminal)) = term {
+        hint(signal, Seed::Terminal(terminal));
+    }
+
+    let multiplexers = [
+        (home.join(".tmux.conf"), MultiplexerChoice::Tmux),
+        (
+            config.join("tmux").join("tmux.conf"),
+            MultiplexerChoice::Tmux,
+        ),
+        (config.join("zellij"), MultiplexerChoice::Zellij),
+    ];
+    if let Some((path, mux)) = multiplexers.iter().find(|(path, _)| exists(path)) {
+        hint(shown(path), Seed::Multiplexer(*mux));
+    }
+
+    let editor_var = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .find_map(|var| env(var).map(|value| (var, value)));
+    if let Some((var, value)) = editor_var {
+        let editor = match command_name(&value) {
+            "nvim" => Some(EditorChoice::Neovim),
+            "hx" | "helix" => Some(EditorChoice::Helix),
+            "code" => Some(EditorChoice::VSCode),
+            "zed" => Some(EditorChoice::Zed),
+            _ => None,
+        };
+        if let Some(editor) = editor {
+            hint(format!("${} is {}", var, value), Seed::Editor(editor));
+        }
+    }
+
+    // A language toolchain brings its language server
+    for (tool, app) in [("cargo", "rust-analyzer"), ("go", "gopls")] {
+        if has_tool(tool) {
+            hint(format!("{} is on PATH", tool), Seed::App(app));
+        }
+    }
+
+    hints
+}
+
+/// The program in a command line like `/usr/bin/nvim -f`
+fn command_name(command: &str) -> &str {
+    let program = command.split_whitespace().next().unwrap_or("");
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+/// Seed `wizard`'s defaults from `hints`. Zsh-only prompts are skipped
+/// when the shell isn't zsh.
+pub fn apply(hints: &[Hint], wizard: &mut WizardState) {
+    for hint in hints {
+        match hint.seed {
+            Seed::Shell(shell) => wizard.shell_config.shell = shell,
+            Seed::Prompt(prompt) => {
+                if !prompt.zsh_only() || wizard.shell_config.shell == ShellChoice::Zsh {
+                    wizard.shell_config.prompt = prompt;
+                }
+            }
+            Seed::Terminal(terminal) => wizard.shell_config.terminal = terminal,
+            Seed::Multiplexer(mux) => {
+                wizard.shell_config.multiplexer = Some(mux);
+                wizard.selected_apps.extend(mux.app_id().map(String::from));
+            }
+            Seed::Editor(editor) => wizard.editor = editor,
+            Seed::App(id) => {
+                wizard.selected_apps.insert(id.to_string());
+            }
+        }
+    }
+    wizard.include_dependencies();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn scratch_system(name: &str) -> SystemInfo {
+        let mut system = SystemInfo::detect().unwrap();
+        system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-scan-{}-{}", name, std::process::id()));
+        system.config_dir = system.home_dir.join(".config");
+        fs::create_dir_all(&system.config_dir).unwrap();
+        system
+    }
+
+    #[test]
+    fn existing_setup_seeds_the_defaults() {
+        let mut system = scratch_system("seeds");
+        system.shell = "/usr/bin/fish".to_string();
+        fs::write(system.home_dir.join(".tmux.conf"), "").unwrap();
+        fs::write(system.home_dir.join(".p10k.zsh"), "").unwrap();
+        fs::create_dir_all(system.config_dir.join("kitty")).unwrap();
+        let env = |var: &str| (var == "EDITOR").then(|| "hx".to_string());
+
+        let hints = scan_with(&system, &env, &|cmd| cmd == "cargo");
+        let seeds: Vec<Seed> = hints.iter().map(|h| h.seed).collect();
+        assert_eq!(
+            seeds,
+            [
+                Seed::Shell(ShellChoice::Fish),
+                Seed::Prompt(PromptChoice::Powerlevel10k),
+                Seed::Terminal(TerminalChoice::Kitty),
+                Seed::Multiplexer(MultiplexerChoice::Tmux),
+                Seed::Editor(EditorChoice::Helix),
+                Seed::App("rust-analyzer"),
+            ]
+        );
+        assert_eq!(hints[3].signal, "~/.tmux.conf");
+
+        let mut wizard = WizardState::new();
+        apply(&hints, &mut wizard);
+        assert_eq!(wizard.shell_config.shell, ShellChoice::Fish);
+        // p10k only loads in zsh
+        assert_eq!(wizard.shell_config.prompt, PromptChoice::Starship);
+        assert_eq!(wizard.editor, EditorChoice::Helix);
+        assert!(wizard.selected_apps.contains("rust-analyzer"));
+        assert!(wizard.selected_apps.contains("tmux"));
+        let _ = fs::remove_dir_all(&system.home_dir);
+    }
+
+    #[test]
+    fn the_running_terminal_beats_config_folders() {
+        let mut system = scratch_system("terminal");
+        system.shell = "/bin/sh".to_string();
+        fs::create_dir_all(system.config_dir.join("alacritty")).unwrap();
+        let env = |var: &str| (var == "TERM_PROGRAM").then(|| "iTerm.app".to_string());
+
+        let hints = scan_with(&system, &env, &|_| false);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].seed, Seed::Terminal(TerminalChoice::ITerm2));
+        assert_eq!(command_name("/opt/homebrew/bin/nvim -f"), "nvim");
+        let _ = fs::remove_dir_all(&system.home_dir);
+    }
+}
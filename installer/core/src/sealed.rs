@@ -0,0 +1,232 @@
+//! Encrypted profile secrets
+//! `[secrets]` values are ASCII-armored age files, so a profile can live in
+//! a public dotfiles repo. They're decrypted with `age` when it loads.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::base64;
+use crate::profile::SecretsSection;
+use crate::secret::Secret;
+use crate::system::SystemInfo;
+use crate::wizard::WizardState;
+
+const BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const END: &str = "-----END AGE ENCRYPTED FILE-----";
+
+/// Whether `value` is an armored age file (what `age --armor` writes)
+pub fn is_armored(value: &str) -> bool {
+    let value = value.trim();
+    value.starts_with(BEGIN) && value.ends_with(END)
+}
+
+/// Whether `armored` was encrypted with `age --passphrase` rather than to a
+/// key. Age takes either a passphrase or identities, never both, so this
+/// decides which to hand it. The header's first stanza says which.
+pub fn needs_passphrase(armored: &str) -> bool {
+    let body: String = armored
+        .trim()
+        .trim_start_matches(BEGIN)
+        .trim_end_matches(END)
+        .split_whitespace()
+        .collect();
+    let header = base64::decode(&body);
+    header
+        .windows(b"\n-> scrypt ".len())
+        .any(|w| w == b"\n-> scrypt ")
+}
+
+/// Where the key for secrets encrypted to a recipient is looked for
+/// unless the profile names one
+pub fn default_identity(system: &SystemInfo) -> PathBuf {
+    system.config_dir.join("loadstar").join("key.txt")
+}
+
+/// The `[secrets]` values, decrypted
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Opened {
+    pub ssh_passphrase: Option<Secret>,
+    pub github_token: Option<Secret>,
+    pub anthropic_key: Option<Secret>,
+    pub openai_key: Option<Secret>,
+}
+
+impl Opened {
+    /// Fill in the wizard's secrets. AI keys switch the AI step to pasted
+    /// keys if it was set to skip.
+    pub fn apply(self, wizard: &mut WizardState) {
+        if let Some(passphrase) = self.ssh_passphrase {
+            wizard.ssh_passphrase = passphrase;
+        }
+        if let Some(token) = self.github_token {
+            wizard.github_token = token;
+        }
+        let has_ai_key = self.anthropic_key.is_some() || self.openai_key.is_some();
+        if let Some(key) = self.anthropic_key {
+            wizard.ai.anthropic_key = key;
+        }
+        if let Some(key) = self.openai_key {
+            wizard.ai.openai_key = key;
+        }
+        if has_ai_key && wizard.ai.source == crate::ai::KeySource::Skip {
+            wizard.ai.source = crate::ai::KeySource::Paste;
+        }
+    }
+}
+
+/// Runs `age` with the given arguments and ciphertext on stdin, returning
+/// what it prints
+pub type Decrypt<'a> = &'a dyn Fn(&[&str], &str) -> Result<String, String>;
+
+/// Decrypt every secret in `section` with the `age` found on PATH.
+/// Passphrase-encrypted values make age ask on the terminal, so this runs
+/// before the TUI takes it over.
+pub fn open(section: &SecretsSection, system: &SystemInfo) -> Result<Opened, String> {
+    let Some(age) = system.find_tool("age") else {
+        return Err("age isn't installed".to_string());
+    };
+    let identity = section
+        .identity
+        .as_deref()
+        .map(|path| match path.trim().strip_prefix("~/") {
+            Some(rest) => system.home_dir.join(rest),
+            None => PathBuf::from(path.trim()),
+        })
+        .unwrap_or_else(|| default_identity(system));
+    open_with(section, &identity, &|args, input| {
+        run_age(&age, args, input)
+    })
+}
+
+/// Decrypt every secret in `section`, with `decrypt` standing in for age
+pub fn open_with(
+    section: &SecretsSection,
+    identity: &Path,
+    decrypt: Decrypt,
+) -> Result<Opened, String> {
+    let open_one = |name: &str, value: &Option<String>| -> Result<Option<Secret>, String> {
+        let Some(armored) = value else {
+            return Ok(None);
+        };
+        let plain = if needs_passphrase(armored) {
+            decrypt(&["--decrypt"], armored)
+        } else if identity.is_file() {
+            let identity = identity.to_string_lossy();
+            decrypt(&["--decrypt", "--identity", &identity], armored)
+        } else {
+            return Err(format!(
+                "secrets.{} is encrypted to a key, and there's no identity at {}",
+                name,
+                identity.display()
+            ));
+        };
+        let plain = plain.map_err(|e| format!("Could not decrypt secrets.{}: {}", name, e))?;
+        // `echo token | age` leaves the newline in
+        Ok(Some(Secret::new(plain.trim_end_matches(['\r', '\n']))))
+    };
+    Ok(Opened {
+        ssh_passphrase: open_one("ssh_passphrase", &section.ssh_passphrase)?,
+        github_token: open_one("github_token", &section.github_token)?,
+        anthropic_key: open_one("anthropic_key", &section.anthropic_key)?,
+        openai_key: open_one("openai_key", &section.openai_key)?,
+    })
+}
+
+fn run_age(age: &Path, args: &[&str], input: &str) -> Result<String, String> {
+    // stderr stays on the terminal: age prompts for passphrases there
+    let mut child = Command::new(age)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format!("Could not run age: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("Could not write to age: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("age failed: {}", e))?;
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .map_err(|_| "age printed something that isn't text".to_string())
+    } else {
+        Err("age exited with an error (see above)".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// An armored file whose header is `header`, with a made-up body
+    fn armor(header: &str) -> String {
+        let mut bytes = header.as_bytes().to_vec();
+        bytes.extend_from_slice(b"--- mac\n\x00\x01\x02");
+        let encoded = base64::encode(&bytes);
+        let lines: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(64)
+            .map(|c| std::str::from_utf8(c).unwrap())
+            .collect();
+        format!("{}\n{}\n{}\n", BEGIN, lines.join("\n"), END)
+    }
+
+    #[test]
+    fn the_header_says_passphrase_or_key() {
+        let scrypt = armor("age-encryption.org/v1\n-> scrypt c2FsdA 18\nYm9keQ\n");
+        let x25519 = armor("age-encryption.org/v1\n-> X25519 cHVibGlj\nYm9keQ\n");
+        assert!(is_armored(&scrypt) && is_armored(&x25519));
+        assert!(needs_passphrase(&scrypt));
+        assert!(!needs_passphrase(&x25519));
+        assert!(!is_armored("sk-ant-plain"));
+    }
+
+    #[test]
+    fn each_secret_goes_to_age_the_way_it_was_encrypted() {
+        let scrypt = armor("age-encryption.org/v1\n-> scrypt c2FsdA 18\nYm9keQ\n");
+        let x25519 = armor("age-encryption.org/v1\n-> X25519 cHVibGlj\nYm9keQ\n");
+        let section = SecretsSection {
+            ssh_passphrase: Some(scrypt),
+            openai_key: Some(x25519.clone()),
+            ..SecretsSection::default()
+        };
+        let calls = RefCell::new(Vec::new());
+        let age = |args: &[&str], _: &str| {
+            calls.borrow_mut().push(args.join(" "));
+            Ok(format!("plain {}\n", args.len()))
+        };
+        let identity = std::env::temp_dir().join(format!("loadstar-age-{}", std::process::id()));
+
+        // No key file: only the passphrase-encrypted one can be opened
+        let err = open_with(&section, &identity, &age).unwrap_err();
+        assert!(
+            err.starts_with("secrets.openai_key is encrypted to a key"),
+            "{}",
+            err
+        );
+
+        std::fs::write(&identity, "AGE-SECRET-KEY-1...").unwrap();
+        calls.borrow_mut().clear();
+        let opened = open_with(&section, &identity, &age).unwrap();
+        let calls = calls.into_inner();
+        assert_eq!(calls[0], "--decrypt");
+        assert_eq!(
+            calls[1],
+            format!("--decrypt --identity {}", identity.display())
+        );
+        assert_eq!(opened.ssh_passphrase, Some(Secret::new("plain 1")));
+        assert_eq!(opened.openai_key, Some(Secret::new("plain 3")));
+        assert_eq!(opened.github_token, None);
+
+        let mut wizard = WizardState::new();
+        opened.apply(&mut wizard);
+        assert_eq!(wizard.ai.openai_key.expose(), "plain 3");
+        assert_eq!(wizard.ai.source, crate::ai::KeySource::Paste);
+        let _ = std::fs::remove_file(&identity);
+    }
+}
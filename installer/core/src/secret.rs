@@ -0,0 +1,106 @@
+//! Secrets typed into the wizard
+//! Passphrases, the sudo password and API keys. The value only comes out
+//! through `expose`; Debug output, logs and reports never see it.
+
+use std::fmt;
+
+/// A text field the wizard edits a keystroke or a paste at a time
+pub trait TextField {
+    fn push(&mut self, c: char);
+    fn pop(&mut self);
+
+    /// Append pasted text. Line breaks and other control characters are
+    /// dropped: a key copied from a web page often ends in a newline.
+    fn paste(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.push(c);
+        }
+    }
+}
+
+impl TextField for String {
+    fn push(&mut self, c: char) {
+        String::push(self, c);
+    }
+
+    fn pop(&mut self) {
+        String::pop(self);
+    }
+}
+
+/// A value that shows up as `•••` on screen and in Debug output
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The value itself, for the command or file that needs it
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// One dot per character, for drawing the field
+    pub fn masked(&self) -> String {
+        mask(&self.0)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            f.write_str("Secret(\"\")")
+        } else {
+            f.write_str("Secret(•••)")
+        }
+    }
+}
+
+impl TextField for Secret {
+    fn push(&mut self, c: char) {
+        self.0.push(c);
+    }
+
+    fn pop(&mut self) {
+        self.0.pop();
+    }
+}
+
+/// `value` with every character drawn as a dot
+pub fn mask(value: &str) -> String {
+    "•".repeat(value.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_never_shows_the_value() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(format!("{:?}", secret), "Secret(•••)");
+        assert_eq!(format!("{:?}", Secret::default()), "Secret(\"\")");
+        assert_eq!(secret.masked(), "•••••••");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn paste_drops_line_breaks() {
+        let mut secret = Secret::default();
+        secret.paste("sk-ant-123\r\n");
+        secret.push('4');
+        assert_eq!(secret.expose(), "sk-ant-1234");
+        secret.pop();
+        assert_eq!(secret.masked().chars().count(), 10);
+
+        let mut plain = String::from("eu-");
+        plain.paste("west-1\n");
+        assert_eq!(plain, "eu-west-1");
+    }
+}
@@ -0,0 +1,149 @@
+//! Session statistics
+//! A run in numbers: time on each screen, keypresses, time per install
+//! phase and packages a minute. Each run is appended to `stats.jsonl`
+//! under the state directory, and the estimates learn from them how far
+//! off their first-run guesses are on this machine.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::hub;
+use crate::system::SystemInfo;
+
+/// Runs the pace is taken from, newest first
+const PACE_RUNS: usize = 5;
+
+/// However far off the guesses have been, they're scaled by no more than
+/// this either way
+const PACE_LIMIT: f64 = 4.0;
+
+/// One run, as the Complete screen's card shows it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionStats {
+    /// Milliseconds on each screen, by its name, in the order first shown
+    pub screens: Vec<(String, u64)>,
+    pub keypresses: u64,
+    /// Milliseconds in each install phase, in the order they ran
+    pub phases: Vec<(String, u64)>,
+    pub install_ms: u64,
+    /// Packages installed, not counting those already there
+    pub installed: usize,
+    /// What the estimates guessed for packages they had no earlier time
+    /// for, and what those packages took, in milliseconds
+    pub guessed_ms: u64,
+    pub guessed_actual_ms: u64,
+}
+
+impl SessionStats {
+    /// Time on the screens before the install, answering questions
+    pub fn wizard_time(&self) -> Duration {
+        let ms = self
+            .screens
+            .iter()
+            .take_while(|(screen, _)| screen != "INSTALL")
+            .map(|(_, ms)| ms)
+            .sum();
+        Duration::from_millis(ms)
+    }
+
+    /// `None` for a run that installed nothing
+    pub fn packages_per_minute(&self) -> Option<f64> {
+        (self.installed > 0 && self.install_ms > 0)
+            .then(|| self.installed as f64 / (self.install_ms as f64 / 60_000.0))
+    }
+}
+
+/// Where every run's stats are kept, one JSON object a line
+pub fn history_path(system: &SystemInfo) -> PathBuf {
+    hub::state_dir(system).join("stats.jsonl")
+}
+
+/// Add `stats` to the history
+pub fn append(stats: &SessionStats, system: &SystemInfo) -> io::Result<PathBuf> {
+    let path = history_path(system);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(stats).map_err(io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", line)?;
+    Ok(path)
+}
+
+/// Every run recorded, oldest first. Unreadable lines are skipped.
+pub fn history(system: &SystemInfo) -> Vec<SessionStats> {
+    fs::read_to_string(history_path(system))
+        .map(|text| parse_history(&text))
+        .unwrap_or_default()
+}
+
+fn parse_history(text: &str) -> Vec<SessionStats> {
+    text.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// How long packages take here next to the first-run guesses for them,
+/// over the last few runs that had any: 2.0 when they took twice as long
+pub fn pace(history: &[SessionStats]) -> Option<f64> {
+    let (guessed, actual) = history
+        .iter()
+        .rev()
+        .filter(|run| run.guessed_ms > 0)
+        .take(PACE_RUNS)
+        .fold((0, 0), |(guessed, actual), run| {
+            (guessed + run.guessed_ms, actual + run.guessed_actual_ms)
+        });
+    (guessed > 0).then(|| (actual as f64 / guessed as f64).clamp(1.0 / PACE_LIMIT, PACE_LIMIT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(guessed_ms: u64, guessed_actual_ms: u64) -> SessionStats {
+        SessionStats {
+            guessed_ms,
+            guessed_actual_ms,
+            ..SessionStats::default()
+        }
+    }
+
+    #[test]
+    fn the_pace_comes_from_recent_guesses() {
+        assert_eq!(pace(&[]), None);
+        assert_eq!(pace(&[run(0, 0)]), None);
+        assert_eq!(pace(&[run(60_000, 120_000), run(0, 0)]), Some(2.0));
+        // Only the newest few count, and a wild run is held to the limit
+        let mut history = vec![run(1_000, 1_000_000)];
+        history.extend((0..PACE_RUNS).map(|_| run(10_000, 5_000)));
+        assert_eq!(pace(&history), Some(0.5));
+        assert_eq!(pace(&history[..1]), Some(PACE_LIMIT));
+    }
+
+    #[test]
+    fn stats_round_trip_through_the_history() {
+        let stats = SessionStats {
+            screens: vec![
+                ("IDENTITY".to_string(), 30_000),
+                ("APPS".to_string(), 90_000),
+                ("INSTALL".to_string(), 300_000),
+            ],
+            keypresses: 142,
+            phases: vec![("Homebrew Formulae".to_string(), 240_000)],
+            install_ms: 300_000,
+            installed: 10,
+            ..SessionStats::default()
+        };
+        assert_eq!(stats.wizard_time(), Duration::from_secs(120));
+        assert_eq!(stats.packages_per_minute(), Some(2.0));
+        assert_eq!(SessionStats::default().packages_per_minute(), None);
+
+        let text = format!("{}\nnot json\n", serde_json::to_string(&stats).unwrap());
+        assert_eq!(parse_history(&text), [stats]);
+    }
+}
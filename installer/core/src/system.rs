@@ -1,9 +1,9 @@
 //! System detection and pre-flight checks
-//! Detects OS, architecture, available package managers, and validates
-//! the environment before installation begins.
+//! Detects OS, architecture, available package managers and hardware, and
+//! validates the environment before installation begins.
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Detected operating system
@@ -44,7 +44,10 @@ pub struct PackageManagers {
     pub homebrew: Option<PathBuf>,
     pub cargo: Option<PathBuf>,
     pub npm: Option<PathBuf>,
+    pub go: Option<PathBuf>,
     pub pip: Option<PathBuf>,
+    pub pipx: Option<PathBuf>,
+    pub uv: Option<PathBuf>,
     pub apt: Option<PathBuf>,
 }
 
@@ -55,6 +58,57 @@ pub struct LinuxDistro {
     pub name: String,
 }
 
+/// A GPU that local models can run on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gpu {
+    /// The GPU and Neural Engine share the system's memory
+    AppleSilicon,
+    /// From `nvidia-smi`, with its own memory when it says
+    Nvidia {
+        name: String,
+        memory_gb: Option<u64>,
+    },
+    /// An AMD card with the ROCm driver loaded
+    Amd,
+}
+
+impl Gpu {
+    pub fn name(&self) -> &str {
+        match self {
+            Gpu::AppleSilicon => "Apple Silicon GPU + Neural Engine",
+            Gpu::Nvidia { name, .. } => name,
+            Gpu::Amd => "AMD GPU (ROCm)",
+        }
+    }
+}
+
+/// Memory, GPU and free disk, for sizing local models. Each is `None`
+/// when it couldn't be read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hardware {
+    /// Physical memory, rounded to the nearest GB
+    pub memory_gb: Option<u64>,
+    pub gpu: Option<Gpu>,
+    /// Free space where the home directory is, in whole GB
+    pub free_disk_gb: Option<u64>,
+}
+
+impl Hardware {
+    /// One line for Preflight, such as "16 GB memory · Apple Silicon GPU
+    /// + Neural Engine". Disk space has a check of its own.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        parts.extend(self.memory_gb.map(|gb| format!("{} GB memory", gb)));
+        parts.push(
+            self.gpu
+                .as_ref()
+                .map_or("no GPU found", Gpu::name)
+                .to_string(),
+        );
+        parts.join(" · ")
+    }
+}
+
 /// Complete system information
 #[derive(Debug, Clone)]
 pub struct SystemInfo {
@@ -66,16 +120,15 @@ pub struct SystemInfo {
     pub config_dir: PathBuf,
     pub package_managers: PackageManagers,
     pub linux_distro: Option<LinuxDistro>,
+    pub hardware: Hardware,
 }
 
-/// Pre-flight check results (used in Phase 4 when pre-flight UI is added)
-#[allow(dead_code)]
+/// Pre-flight check results, shown on the wizard's Preflight screen
 #[derive(Debug, Clone)]
 pub struct PreflightResult {
     pub checks: Vec<PreflightCheck>,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct PreflightCheck {
     pub name: String,
@@ -83,7 +136,6 @@ pub struct PreflightCheck {
     pub detail: String,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CheckStatus {
     Pass,
@@ -110,6 +162,7 @@ impl SystemInfo {
         } else {
             None
         };
+        let hardware = detect_hardware(os, arch, &home_dir);
 
         Ok(Self {
             os,
@@ -120,6 +173,7 @@ impl SystemInfo {
             config_dir,
             package_managers,
             linux_distro,
+            hardware,
         })
     }
 
@@ -132,6 +186,42 @@ impl SystemInfo {
         }
     }
 
+    /// PATH with brew's bin in front, for running tools that were probably
+    /// installed moments ago
+    pub fn search_path(&self) -> String {
+        let brew_bin = format!("{}/bin", self.brew_prefix());
+        match std::env::var("PATH") {
+            Ok(path) => format!("{}:{}", brew_bin, path),
+            Err(_) => brew_bin,
+        }
+    }
+
+    /// Locate a command, looking in brew's bin first since it may have
+    /// been installed after this process started
+    pub fn find_tool(&self, cmd: &str) -> Option<PathBuf> {
+        let brewed = Path::new(self.brew_prefix()).join("bin").join(cmd);
+        if brewed.is_file() {
+            Some(brewed)
+        } else {
+            which(cmd)
+        }
+    }
+
+    /// User-owned npm global prefix, so `npm install -g` never needs sudo
+    pub fn npm_prefix(&self) -> PathBuf {
+        self.home_dir.join(".npm-global")
+    }
+
+    /// Whether Apple's Command Line Tools are installed. Homebrew needs
+    /// them, and its installer prompts for them with a GUI dialog.
+    pub fn has_xcode_clt(&self) -> bool {
+        self.os == Os::MacOS
+            && Command::new("xcode-select")
+                .arg("-p")
+                .output()
+                .is_ok_and(|output| output.status.success())
+    }
+
     /// Whether Homebrew is available
     pub fn has_homebrew(&self) -> bool {
         self.package_managers.homebrew.is_some()
@@ -143,7 +233,6 @@ impl SystemInfo {
     }
 }
 
-#[allow(dead_code)]
 impl PreflightResult {
     /// Run all pre-flight checks
     pub fn run(system: &SystemInfo) -> Self {
@@ -160,7 +249,7 @@ impl PreflightResult {
 
         // Xcode CLT (macOS only)
         if system.os == Os::MacOS {
-            checks.push(check_xcode_clt());
+            checks.push(check_xcode_clt(system));
         }
 
         // Writable home directory
@@ -169,6 +258,13 @@ impl PreflightResult {
         // Git available
         checks.push(check_git());
 
+        // Memory and GPU, for local models
+        checks.push(PreflightCheck {
+            name: "Hardware".to_string(),
+            status: CheckStatus::Pass,
+            detail: system.hardware.summary(),
+        });
+
         Self { checks }
     }
 
@@ -221,7 +317,10 @@ fn detect_package_managers() -> PackageManagers {
         homebrew: which("brew"),
         cargo: which("cargo"),
         npm: which("npm"),
+        go: which("go"),
         pip: which("pip3").or_else(|| which("pip")),
+        pipx: which("pipx"),
+        uv: which("uv"),
         apt: which("apt"),
     }
 }
@@ -247,6 +346,78 @@ fn detect_linux_distro() -> Option<LinuxDistro> {
     }
 }
 
+fn detect_hardware(os: Os, arch: Arch, home_dir: &Path) -> Hardware {
+    let memory_kb = match os {
+        Os::Linux => std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|meminfo| parse_meminfo_kb(&meminfo)),
+        Os::MacOS => command_stdout("sysctl", &["-n", "hw.memsize"])
+            .and_then(|bytes| bytes.trim().parse::<u64>().ok())
+            .map(|bytes| bytes / 1024),
+    };
+    let gpu = match (os, arch) {
+        (Os::MacOS, Arch::Aarch64) => Some(Gpu::AppleSilicon),
+        (Os::MacOS, Arch::X86_64) => None,
+        (Os::Linux, _) => command_stdout(
+            "nvidia-smi",
+            &[
+                "--query-gpu=name,memory.total",
+                "--format=csv,noheader,nounits",
+            ],
+        )
+        .and_then(|csv| parse_nvidia_smi(&csv))
+        .or_else(|| Path::new("/dev/kfd").exists().then_some(Gpu::Amd)),
+    };
+    Hardware {
+        memory_gb: memory_kb.map(|kb| (kb + 512 * 1024) / (1024 * 1024)),
+        gpu,
+        free_disk_gb: free_disk_kb(home_dir).map(|kb| kb / (1024 * 1024)),
+    }
+}
+
+/// `MemTotal` from /proc/meminfo, in KB
+fn parse_meminfo_kb(meminfo: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse().ok())
+}
+
+/// The first card from `nvidia-smi --query-gpu=name,memory.total`; the
+/// memory is in MiB
+fn parse_nvidia_smi(csv: &str) -> Option<Gpu> {
+    let (name, memory) = csv.lines().next()?.rsplit_once(',')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(Gpu::Nvidia {
+        name: name.to_string(),
+        memory_gb: memory
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(|mib| (mib + 512) / 1024),
+    })
+}
+
+/// Available KB on the filesystem holding `path`
+fn free_disk_kb(path: &Path) -> Option<u64> {
+    // POSIX df output in KB, so the columns line up on macOS and Linux
+    let df = command_stdout("df", &["-Pk", &path.to_string_lossy()])?;
+    parse_df_available_kb(&df)
+}
+
+/// A command's stdout, if it ran and succeeded
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// Find a command in PATH
 fn which(cmd: &str) -> Option<PathBuf> {
     Command::new("which")
@@ -267,9 +438,8 @@ fn which(cmd: &str) -> Option<PathBuf> {
         })
 }
 
-// ─── Pre-flight checks (used in Phase 4) ────────────────────────────
+// ─── Pre-flight checks ───────────────────────────────────────────────
 
-#[allow(dead_code)]
 fn check_internet() -> PreflightCheck {
     // Quick DNS check - try to resolve github.com
     let result = Command::new("ping")
@@ -290,7 +460,6 @@ fn check_internet() -> PreflightCheck {
     }
 }
 
-#[allow(dead_code)]
 fn check_homebrew(system: &SystemInfo) -> PreflightCheck {
     if system.has_homebrew() {
         PreflightCheck {
@@ -315,21 +484,10 @@ fn check_homebrew(system: &SystemInfo) -> PreflightCheck {
     }
 }
 
-#[allow(dead_code)]
 fn check_disk_space() -> PreflightCheck {
-    // Use df to check available space on /
-    let result = Command::new("df").args(["-g", "/"]).output();
-
-    match result {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            // Parse df output - available space is typically 4th column
-            let available_gb: u64 = stdout
-                .lines()
-                .nth(1)
-                .and_then(|line| line.split_whitespace().nth(3))
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(0);
+    match free_disk_kb(Path::new("/")) {
+        Some(available_kb) => {
+            let available_gb = available_kb / (1024 * 1024);
 
             if available_gb >= 10 {
                 PreflightCheck {
@@ -351,7 +509,7 @@ fn check_disk_space() -> PreflightCheck {
                 }
             }
         }
-        _ => PreflightCheck {
+        None => PreflightCheck {
             name: "Disk space".to_string(),
             status: CheckStatus::Warn,
             detail: "Could not determine available disk space".to_string(),
@@ -359,25 +517,31 @@ fn check_disk_space() -> PreflightCheck {
     }
 }
 
-#[allow(dead_code)]
-fn check_xcode_clt() -> PreflightCheck {
-    let result = Command::new("xcode-select").arg("-p").output();
+/// Available KB from `df -Pk` output: the 4th column of the second line
+fn parse_df_available_kb(stdout: &str) -> Option<u64> {
+    stdout
+        .lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|s| s.parse().ok())
+}
 
-    match result {
-        Ok(output) if output.status.success() => PreflightCheck {
+fn check_xcode_clt(system: &SystemInfo) -> PreflightCheck {
+    if system.has_xcode_clt() {
+        PreflightCheck {
             name: "Xcode CLT".to_string(),
             status: CheckStatus::Pass,
             detail: "Installed".to_string(),
-        },
-        _ => PreflightCheck {
+        }
+    } else {
+        PreflightCheck {
             name: "Xcode CLT".to_string(),
             status: CheckStatus::Warn,
-            detail: "Not installed - will prompt during Homebrew install".to_string(),
-        },
+            detail: "Not installed - will install before Homebrew".to_string(),
+        }
     }
 }
 
-#[allow(dead_code)]
 fn check_home_writable(system: &SystemInfo) -> PreflightCheck {
     let test_file = system.home_dir.join(".load_write_test");
     match std::fs::write(&test_file, "test") {
@@ -397,7 +561,6 @@ fn check_home_writable(system: &SystemInfo) -> PreflightCheck {
     }
 }
 
-#[allow(dead_code)]
 fn check_git() -> PreflightCheck {
     match which("git") {
         Some(path) => {
@@ -481,6 +644,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hardware_is_read_from_each_tools_output() {
+        let meminfo = "MemTotal:        7990432 kB\nMemFree:          512000 kB\n";
+        assert_eq!(parse_meminfo_kb(meminfo), Some(7990432));
+        assert_eq!(
+            parse_nvidia_smi("NVIDIA GeForce RTX 4090, 24564\n"),
+            Some(Gpu::Nvidia {
+                name: "NVIDIA GeForce RTX 4090".to_string(),
+                memory_gb: Some(24),
+            })
+        );
+        assert_eq!(parse_nvidia_smi(""), None);
+
+        let hardware = Hardware {
+            memory_gb: Some(8),
+            gpu: None,
+            free_disk_gb: Some(40),
+        };
+        assert_eq!(hardware.summary(), "8 GB memory · no GPU found");
+    }
+
     #[test]
     fn package_manager_detection_doesnt_panic() {
         let pm = detect_package_managers();
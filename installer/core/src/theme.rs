@@ -0,0 +1,256 @@
+//! Color themes and fonts for generated configs
+//! One palette drives every themed file, so the editor, pager and prompt
+//! all match whatever was picked in the wizard.
+
+use serde::{Deserialize, Serialize};
+
+/// Color theme applied to generated configs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    #[default]
+    CatppuccinMocha,
+    TokyoNight,
+    GruvboxDark,
+    Nord,
+}
+
+impl ThemeChoice {
+    pub fn all() -> &'static [ThemeChoice] {
+        &[
+            ThemeChoice::CatppuccinMocha,
+            ThemeChoice::TokyoNight,
+            ThemeChoice::GruvboxDark,
+            ThemeChoice::Nord,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.palette().name
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ThemeChoice::CatppuccinMocha => "Soothing pastels (the loadstar default)",
+            ThemeChoice::TokyoNight => "Deep blues and neon accents",
+            ThemeChoice::GruvboxDark => "Warm retro groove",
+            ThemeChoice::Nord => "Arctic, north-bluish calm",
+        }
+    }
+
+    pub fn palette(&self) -> &'static ThemePalette {
+        match self {
+            ThemeChoice::CatppuccinMocha => &CATPPUCCIN_MOCHA,
+            ThemeChoice::TokyoNight => &TOKYO_NIGHT,
+            ThemeChoice::GruvboxDark => &GRUVBOX_DARK,
+            ThemeChoice::Nord => &NORD,
+        }
+    }
+}
+
+/// Hex colors (`#rrggbb`) and per-tool theme names for one theme
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemePalette {
+    pub name: &'static str,
+    pub background: &'static str,
+    pub surface: &'static str,
+    pub overlay: &'static str,
+    pub foreground: &'static str,
+    pub muted: &'static str,
+    pub red: &'static str,
+    pub green: &'static str,
+    pub yellow: &'static str,
+    pub blue: &'static str,
+    pub magenta: &'static str,
+    pub cyan: &'static str,
+    pub accent: &'static str,
+    /// Theme name as Zed lists it
+    pub zed_theme: &'static str,
+    /// Zed extension that ships the theme, if it isn't built in
+    pub zed_extension: Option<&'static str>,
+    /// Closest syntax theme bundled with bat (and so delta)
+    pub bat_theme: &'static str,
+}
+
+impl ThemePalette {
+    /// `color` mixed into the background, `amount` of the way from the
+    /// background to the color. Diff highlights use this so they stay
+    /// readable behind syntax colors.
+    pub fn tint(&self, color: &str, amount: f32) -> String {
+        let rgb = |hex: &str| {
+            let hex = hex.trim_start_matches('#');
+            [0, 2, 4].map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0) as f32)
+        };
+        let (fg, bg) = (rgb(color), rgb(self.background));
+        let [r, g, b] = [0, 1, 2].map(|i| (bg[i] + (fg[i] - bg[i]) * amount).round() as u8);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    /// Terminal ANSI colors 0-15: the normal set, then the brights
+    pub fn ansi(&self) -> [&'static str; 16] {
+        [
+            self.surface,
+            self.red,
+            self.green,
+            self.yellow,
+            self.blue,
+            self.magenta,
+            self.cyan,
+            self.muted,
+            self.overlay,
+            self.red,
+            self.green,
+            self.yellow,
+            self.blue,
+            self.magenta,
+            self.cyan,
+            self.foreground,
+        ]
+    }
+}
+
+pub const CATPPUCCIN_MOCHA: ThemePalette = ThemePalette {
+    name: "Catppuccin Mocha",
+    background: "#1e1e2e",
+    surface: "#313244",
+    overlay: "#6c7086",
+    foreground: "#cdd6f4",
+    muted: "#a6adc8",
+    red: "#f38ba8",
+    green: "#a6e3a1",
+    yellow: "#f9e2af",
+    blue: "#89b4fa",
+    magenta: "#cba6f7",
+    cyan: "#94e2d5",
+    accent: "#b4befe",
+    zed_theme: "Catppuccin Mocha",
+    zed_extension: Some("catppuccin"),
+    bat_theme: "Catppuccin Mocha",
+};
+
+pub const TOKYO_NIGHT: ThemePalette = ThemePalette {
+    name: "Tokyo Night",
+    background: "#1a1b26",
+    surface: "#292e42",
+    overlay: "#565f89",
+    foreground: "#c0caf5",
+    muted: "#a9b1d6",
+    red: "#f7768e",
+    green: "#9ece6a",
+    yellow: "#e0af68",
+    blue: "#7aa2f7",
+    magenta: "#bb9af7",
+    cyan: "#7dcfff",
+    accent: "#7aa2f7",
+    zed_theme: "Tokyo Night",
+    zed_extension: Some("tokyo-night"),
+    bat_theme: "TwoDark",
+};
+
+pub const GRUVBOX_DARK: ThemePalette = ThemePalette {
+    name: "Gruvbox Dark",
+    background: "#282828",
+    surface: "#3c3836",
+    overlay: "#665c54",
+    foreground: "#ebdbb2",
+    muted: "#a89984",
+    red: "#fb4934",
+    green: "#b8bb26",
+    yellow: "#fabd2f",
+    blue: "#83a598",
+    magenta: "#d3869b",
+    cyan: "#8ec07c",
+    accent: "#fe8019",
+    zed_theme: "Gruvbox Dark",
+    zed_extension: None,
+    bat_theme: "gruvbox-dark",
+};
+
+pub const NORD: ThemePalette = ThemePalette {
+    name: "Nord",
+    background: "#2e3440",
+    surface: "#3b4252",
+    overlay: "#4c566a",
+    foreground: "#eceff4",
+    muted: "#d8dee9",
+    red: "#bf616a",
+    green: "#a3be8c",
+    yellow: "#ebcb8b",
+    blue: "#81a1c1",
+    magenta: "#b48ead",
+    cyan: "#88c0d0",
+    accent: "#88c0d0",
+    zed_theme: "Nord",
+    zed_extension: Some("nord"),
+    bat_theme: "Nord",
+};
+
+/// Monospace font for editors and terminals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FontChoice {
+    #[default]
+    JetBrainsMono,
+    FiraCode,
+    Hack,
+    /// Leave fonts alone
+    System,
+}
+
+impl FontChoice {
+    pub fn all() -> &'static [FontChoice] {
+        &[
+            FontChoice::JetBrainsMono,
+            FontChoice::FiraCode,
+            FontChoice::Hack,
+            FontChoice::System,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FontChoice::JetBrainsMono => "JetBrains Mono",
+            FontChoice::FiraCode => "Fira Code",
+            FontChoice::Hack => "Hack",
+            FontChoice::System => "System default",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            FontChoice::JetBrainsMono => "Tall x-height, ligatures (Nerd Font)",
+            FontChoice::FiraCode => "The ligature classic (Nerd Font)",
+            FontChoice::Hack => "No-nonsense, no ligatures (Nerd Font)",
+            FontChoice::System => "Don't install or set a font",
+        }
+    }
+
+    /// Family name as it appears in font pickers once installed
+    pub fn family(&self) -> Option<&'static str> {
+        match self {
+            FontChoice::JetBrainsMono => Some("JetBrainsMono Nerd Font"),
+            FontChoice::FiraCode => Some("FiraCode Nerd Font"),
+            FontChoice::Hack => Some("Hack Nerd Font"),
+            FontChoice::System => None,
+        }
+    }
+
+    /// PostScript name of the regular face, which iTerm2 wants instead
+    /// of the family
+    pub fn postscript_name(&self) -> Option<&'static str> {
+        match self {
+            FontChoice::JetBrainsMono => Some("JetBrainsMonoNF-Regular"),
+            FontChoice::FiraCode => Some("FiraCodeNF-Reg"),
+            FontChoice::Hack => Some("HackNF-Regular"),
+            FontChoice::System => None,
+        }
+    }
+
+    /// Catalog app that installs this font
+    pub fn app_id(&self) -> Option<&'static str> {
+        match self {
+            FontChoice::JetBrainsMono => Some("font-jetbrains-mono"),
+            FontChoice::FiraCode => Some("font-fira-code"),
+            FontChoice::Hack => Some("font-hack"),
+            FontChoice::System => None,
+        }
+    }
+}
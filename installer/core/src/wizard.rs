@@ -0,0 +1,1602 @@
+//! Wizard state machine for the installation process
+//! Guides users through logical phases of machine setup
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::ai::{AiSetup, KeySource};
+use crate::atuin::AtuinSetup;
+use crate::catalog::{find_app, App, Bundle, Category, Overlap, CATALOG, OVERLAPS};
+use crate::custom::CustomPackage;
+use crate::hooks;
+use crate::kube::KubeSetup;
+use crate::secret::Secret;
+use crate::theme::{FontChoice, ThemeChoice};
+
+/// The phases of the installation wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WizardPhase {
+    /// Boot sequence - dramatic intro
+    Boot,
+    /// Returning users pick what to do: set up again, sync, audit, upgrade
+    Hub,
+    /// Which of the profile's apps are installed, reached from the hub
+    Audit,
+    /// The tools Homebrew has newer versions of, reached from the hub
+    Upgrade,
+    /// Machine checks: network, disk, Homebrew, git
+    Preflight,
+    /// Who are you? Name, email, work/personal
+    Identity,
+    /// SSH key and commit signing, only with a GitHub username
+    GitHub,
+    /// Shell & prompt configuration
+    Shell,
+    /// Development tools selection
+    DevTools,
+    /// Application selection by category
+    Apps,
+    /// Cluster credentials, only when kubectl or k9s is picked
+    Kubernetes,
+    /// Atuin sync account, only when atuin is picked
+    Atuin,
+    /// API keys, only when aichat or Claude Code is picked
+    AiTools,
+    /// The dotfiles that will be written, before anything is
+    ConfigPreview,
+    /// Review configuration choices
+    Review,
+    /// Execute the installation
+    Install,
+    /// The org's checklist, only with `--onboarding`
+    Onboarding,
+    /// Completion and next steps
+    Complete,
+}
+
+impl WizardPhase {
+    pub fn all() -> &'static [WizardPhase] {
+        &[
+            WizardPhase::Boot,
+            WizardPhase::Hub,
+            WizardPhase::Audit,
+            WizardPhase::Upgrade,
+            WizardPhase::Preflight,
+            WizardPhase::Identity,
+            WizardPhase::GitHub,
+            WizardPhase::Shell,
+            WizardPhase::DevTools,
+            WizardPhase::Apps,
+            WizardPhase::Kubernetes,
+            WizardPhase::Atuin,
+            WizardPhase::AiTools,
+            WizardPhase::ConfigPreview,
+            WizardPhase::Review,
+            WizardPhase::Install,
+            WizardPhase::Onboarding,
+            WizardPhase::Complete,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WizardPhase::Boot => "BOOT",
+            WizardPhase::Hub => "HUB",
+            WizardPhase::Audit => "AUDIT",
+            WizardPhase::Upgrade => "UPGRADE",
+            WizardPhase::Preflight => "PREFLIGHT",
+            WizardPhase::Identity => "IDENTITY",
+            WizardPhase::GitHub => "GITHUB",
+            WizardPhase::Shell => "SHELL",
+            WizardPhase::DevTools => "DEV TOOLS",
+            WizardPhase::Apps => "APPS",
+            WizardPhase::Kubernetes => "KUBERNETES",
+            WizardPhase::Atuin => "ATUIN",
+            WizardPhase::AiTools => "AI TOOLS",
+            WizardPhase::ConfigPreview => "PREVIEW",
+            WizardPhase::Review => "REVIEW",
+            WizardPhase::Install => "INSTALL",
+            WizardPhase::Onboarding => "ONBOARDING",
+            WizardPhase::Complete => "COMPLETE",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            WizardPhase::Boot => "Initializing consciousness transfer...",
+            WizardPhase::Hub => "Welcome back, operator",
+            WizardPhase::Audit => "Taking inventory",
+            WizardPhase::Upgrade => "Checking for newer firmware",
+            WizardPhase::Preflight => "Running hardware diagnostics",
+            WizardPhase::Identity => "Establishing neural identity profile",
+            WizardPhase::GitHub => "Forging cryptographic credentials",
+            WizardPhase::Shell => "Configuring command interface layer",
+            WizardPhase::DevTools => "Selecting development arsenal",
+            WizardPhase::Apps => "Choosing software companions",
+            WizardPhase::Kubernetes => "Linking cluster uplinks",
+            WizardPhase::Atuin => "Syncing the memory banks",
+            WizardPhase::AiTools => "Handing over the keys to the machines",
+            WizardPhase::ConfigPreview => "Proofreading the dotfiles",
+            WizardPhase::Review => "Reviewing configuration matrix",
+            WizardPhase::Install => "Executing reality modification",
+            WizardPhase::Onboarding => "Clearing you for the building",
+            WizardPhase::Complete => "Transformation complete",
+        }
+    }
+}
+
+/// User identity information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+    pub github_username: String,
+    pub setup_type: SetupType,
+}
+
+impl Default for Identity {
+    fn default() -> Self {
+        Self {
+            name: whoami::realname(),
+            email: String::new(),
+            github_username: String::new(),
+            setup_type: SetupType::Personal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetupType {
+    Personal,
+    Work,
+    Minimal,
+    Full,
+}
+
+impl SetupType {
+    pub fn all() -> &'static [SetupType] {
+        &[
+            SetupType::Personal,
+            SetupType::Work,
+            SetupType::Minimal,
+            SetupType::Full,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SetupType::Personal => "Personal",
+            SetupType::Work => "Work",
+            SetupType::Minimal => "Minimal",
+            SetupType::Full => "Full",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            SetupType::Personal => "Personal development machine with all the bells and whistles",
+            SetupType::Work => "Professional setup with work-oriented tools",
+            SetupType::Minimal => "Essential tools only - fast and lean",
+            SetupType::Full => "Everything. Maximum power. No compromises.",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            SetupType::Personal => "🏠",
+            SetupType::Work => "💼",
+            SetupType::Minimal => "🍃",
+            SetupType::Full => "🚀",
+        }
+    }
+}
+
+/// Shell configuration choices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellConfig {
+    pub shell: ShellChoice,
+    pub prompt: PromptChoice,
+    pub terminal: TerminalChoice,
+    pub multiplexer: Option<MultiplexerChoice>,
+    /// Alias ls, cat, cd and friends to their modern replacements
+    pub aliases: bool,
+}
+
+impl Default for ShellConfig {
+    fn default() -> Self {
+        Self {
+            shell: ShellChoice::Zsh,
+            prompt: PromptChoice::Starship,
+            terminal: TerminalChoice::Default,
+            multiplexer: Some(MultiplexerChoice::Tmux),
+            aliases: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShellChoice {
+    Zsh,
+    Bash,
+    Fish,
+    Nushell,
+}
+
+impl ShellChoice {
+    pub fn all() -> &'static [ShellChoice] {
+        &[
+            ShellChoice::Zsh,
+            ShellChoice::Bash,
+            ShellChoice::Fish,
+            ShellChoice::Nushell,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShellChoice::Zsh => "Zsh",
+            ShellChoice::Bash => "Bash",
+            ShellChoice::Fish => "Fish",
+            ShellChoice::Nushell => "Nushell",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ShellChoice::Zsh => "Feature-rich, highly customizable (recommended)",
+            ShellChoice::Bash => "Classic Unix shell, maximum compatibility",
+            ShellChoice::Fish => "Friendly interactive shell with great defaults",
+            ShellChoice::Nushell => "Modern shell with structured data",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromptChoice {
+    Starship,
+    Powerlevel10k,
+    PurePurple,
+    Minimal,
+    None,
+}
+
+impl PromptChoice {
+    pub fn all() -> &'static [PromptChoice] {
+        &[
+            PromptChoice::Starship,
+            PromptChoice::Powerlevel10k,
+            PromptChoice::PurePurple,
+            PromptChoice::Minimal,
+            PromptChoice::None,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PromptChoice::Starship => "Starship",
+            PromptChoice::Powerlevel10k => "Powerlevel10k",
+            PromptChoice::PurePurple => "Pure",
+            PromptChoice::Minimal => "Minimal",
+            PromptChoice::None => "Default",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            PromptChoice::Starship => "Cross-shell prompt, fast & customizable (recommended)",
+            PromptChoice::Powerlevel10k => "Zsh theme with instant prompt",
+            PromptChoice::PurePurple => "Pretty, minimal and fast prompt",
+            PromptChoice::Minimal => "Simple, distraction-free prompt",
+            PromptChoice::None => "Keep system default",
+        }
+    }
+
+    /// Catalog app that installs this prompt
+    pub fn app_id(&self) -> Option<&'static str> {
+        match self {
+            PromptChoice::Starship => Some("starship"),
+            PromptChoice::Powerlevel10k => Some("powerlevel10k"),
+            PromptChoice::PurePurple => Some("pure"),
+            PromptChoice::Minimal | PromptChoice::None => None,
+        }
+    }
+
+    /// Prompts that only load in zsh
+    pub fn zsh_only(&self) -> bool {
+        matches!(self, PromptChoice::Powerlevel10k | PromptChoice::PurePurple)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerminalChoice {
+    Default,
+    WezTerm,
+    Alacritty,
+    Kitty,
+    ITerm2,
+    Ghostty,
+}
+
+impl TerminalChoice {
+    pub fn all() -> &'static [TerminalChoice] {
+        &[
+            TerminalChoice::Default,
+            TerminalChoice::WezTerm,
+            TerminalChoice::Alacritty,
+            TerminalChoice::Kitty,
+            TerminalChoice::ITerm2,
+            TerminalChoice::Ghostty,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TerminalChoice::Default => "Keep Current",
+            TerminalChoice::WezTerm => "WezTerm",
+            TerminalChoice::Alacritty => "Alacritty",
+            TerminalChoice::Kitty => "Kitty",
+            TerminalChoice::ITerm2 => "iTerm2",
+            TerminalChoice::Ghostty => "Ghostty",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TerminalChoice::Default => "Don't install a new terminal",
+            TerminalChoice::WezTerm => "GPU-accelerated with Lua config",
+            TerminalChoice::Alacritty => "Minimal, fast, GPU-accelerated",
+            TerminalChoice::Kitty => "Feature-rich, GPU-accelerated",
+            TerminalChoice::ITerm2 => "macOS classic with many features",
+            TerminalChoice::Ghostty => "Native, fast, by Mitchell Hashimoto",
+        }
+    }
+
+    /// Catalog app that installs this terminal
+    pub fn app_id(&self) -> Option<&'static str> {
+        match self {
+            TerminalChoice::Default => None,
+            TerminalChoice::WezTerm => Some("wezterm"),
+            TerminalChoice::Alacritty => Some("alacritty"),
+            TerminalChoice::Kitty => Some("kitty"),
+            TerminalChoice::ITerm2 => Some("iterm2"),
+            TerminalChoice::Ghostty => Some("ghostty"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultiplexerChoice {
+    Tmux,
+    Zellij,
+    None,
+}
+
+impl MultiplexerChoice {
+    pub fn all() -> &'static [MultiplexerChoice] {
+        &[
+            MultiplexerChoice::Tmux,
+            MultiplexerChoice::Zellij,
+            MultiplexerChoice::None,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MultiplexerChoice::Tmux => "Tmux",
+            MultiplexerChoice::Zellij => "Zellij",
+            MultiplexerChoice::None => "None",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            MultiplexerChoice::Tmux => "Classic multiplexer, huge ecosystem",
+            MultiplexerChoice::Zellij => "Modern alternative with better defaults",
+            MultiplexerChoice::None => "No terminal multiplexer",
+        }
+    }
+
+    /// Catalog app that installs this multiplexer
+    pub fn app_id(&self) -> Option<&'static str> {
+        match self {
+            MultiplexerChoice::Tmux => Some("tmux"),
+            MultiplexerChoice::Zellij => Some("zellij"),
+            MultiplexerChoice::None => None,
+        }
+    }
+}
+
+/// Editor preferences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EditorChoice {
+    Neovim,
+    Helix,
+    VSCode,
+    Zed,
+    None,
+}
+
+impl EditorChoice {
+    pub fn all() -> &'static [EditorChoice] {
+        &[
+            EditorChoice::Neovim,
+            EditorChoice::Helix,
+            EditorChoice::VSCode,
+            EditorChoice::Zed,
+            EditorChoice::None,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditorChoice::Neovim => "Neovim",
+            EditorChoice::Helix => "Helix",
+            EditorChoice::VSCode => "VS Code",
+            EditorChoice::Zed => "Zed",
+            EditorChoice::None => "None",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            EditorChoice::Neovim => "Hyperextensible Vim-based editor",
+            EditorChoice::Helix => "Post-modern modal editor, batteries included",
+            EditorChoice::VSCode => "The popular GUI editor",
+            EditorChoice::Zed => "Fast collaborative GUI editor",
+            EditorChoice::None => "Keep whatever you use now (falls back to vim)",
+        }
+    }
+
+    /// Command for `$EDITOR`, `$VISUAL` and git's `core.editor`. GUI editors
+    /// need `--wait` so git blocks until the file is closed.
+    pub fn command(&self) -> &'static str {
+        match self {
+            EditorChoice::Neovim => "nvim",
+            EditorChoice::Helix => "hx",
+            EditorChoice::VSCode => "code --wait",
+            EditorChoice::Zed => "zed --wait",
+            EditorChoice::None => "vim",
+        }
+    }
+
+    /// Catalog app that installs this editor
+    pub fn app_id(&self) -> Option<&'static str> {
+        match self {
+            EditorChoice::Neovim => Some("neovim"),
+            EditorChoice::Helix => Some("helix"),
+            EditorChoice::VSCode => Some("vscode"),
+            EditorChoice::Zed => Some("zed"),
+            EditorChoice::None => None,
+        }
+    }
+}
+
+/// What happens to a selected app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AppMode {
+    /// Install it and write its config
+    #[default]
+    Install,
+    /// Already installed some other way — only write its config
+    ConfigureOnly,
+    /// Install it but leave its config alone
+    SkipConfig,
+}
+
+impl AppMode {
+    pub fn all() -> &'static [AppMode] {
+        &[
+            AppMode::Install,
+            AppMode::ConfigureOnly,
+            AppMode::SkipConfig,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppMode::Install => "Install",
+            AppMode::ConfigureOnly => "Configure only",
+            AppMode::SkipConfig => "Skip config",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AppMode::Install => "Install and write config",
+            AppMode::ConfigureOnly => "Already installed, just write config",
+            AppMode::SkipConfig => "Install, keep my own config",
+        }
+    }
+
+    pub fn next(&self) -> AppMode {
+        let all = Self::all();
+        let idx = all.iter().position(|m| m == self).unwrap_or(0);
+        all[(idx + 1) % all.len()]
+    }
+
+    pub fn installs(&self) -> bool {
+        *self != AppMode::ConfigureOnly
+    }
+
+    pub fn configures(&self) -> bool {
+        *self != AppMode::SkipConfig
+    }
+}
+
+/// Which SSH key GitHub gets set up with
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SshKeyChoice {
+    /// `~/.ssh/id_ed25519` or `id_rsa` if either exists, otherwise a new
+    /// key when `generate_ssh_key` is on
+    #[default]
+    Default,
+    /// A key picked from the ones already in `~/.ssh`
+    Existing(PathBuf),
+    /// A new key of its own, next to the ones already there
+    Generate,
+}
+
+/// Categories the DevTools screen pages through by default: the ones a
+/// developer setup is mostly made of
+pub const DEVTOOLS_CATEGORIES: &[Category] = &[
+    Category::Language,
+    Category::Editor,
+    Category::Git,
+    Category::Container,
+    Category::Cloud,
+];
+
+/// Which categories each app list screen pages through, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenCategories {
+    pub devtools: Vec<Category>,
+    pub apps: Vec<Category>,
+}
+
+impl Default for ScreenCategories {
+    fn default() -> Self {
+        Self {
+            devtools: DEVTOOLS_CATEGORIES.to_vec(),
+            apps: Category::all().to_vec(),
+        }
+    }
+}
+
+/// The complete wizard state. It serializes to the answers alone: where
+/// the screens were left and the SSH passphrase stay behind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WizardState {
+    pub phase: WizardPhase,
+    /// Phases visited on the way to `phase`, oldest first. Back retraces
+    /// these, so it returns where the user came from even if their
+    /// answers have since changed which phases come next.
+    #[serde(skip)]
+    pub history: Vec<WizardPhase>,
+    /// loadstar has run on this machine before, so boot leads to the hub
+    pub returning: bool,
+    /// An org checklist from `--onboarding` follows the install
+    #[serde(skip)]
+    pub onboarding: bool,
+    pub identity: Identity,
+    pub shell_config: ShellConfig,
+    pub editor: EditorChoice,
+    pub theme: ThemeChoice,
+    pub font: FontChoice,
+    pub selected_apps: HashSet<String>,
+    /// Aliases the user keeps their own definition of, by name
+    #[serde(default)]
+    pub kept_aliases: HashSet<String>,
+    /// Per-app mode for selected apps; absent means `AppMode::Install`
+    pub app_modes: HashMap<String, AppMode>,
+    /// Packages outside the catalog, queued from the expert palette
+    #[serde(default)]
+    pub custom_packages: Vec<CustomPackage>,
+    /// `LANG` and `LC_ALL` to export; unset leaves bash, fish and nu alone
+    /// and has zsh export `locale::DEFAULT_LOCALE`
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Timezone to set the system to, such as `Europe/Berlin`
+    #[serde(default)]
+    pub timezone: Option<String>,
+    pub kube: KubeSetup,
+    pub atuin: AtuinSetup,
+    pub ai: AiSetup,
+    pub selected_categories: HashSet<Category>,
+    /// Categories shown on DevTools and Apps
+    #[serde(skip)]
+    pub categories: ScreenCategories,
+    /// `--dry-run`: log what the install would run and write, and do none
+    /// of it. Never saved, so a resumed session runs for real.
+    #[serde(skip)]
+    pub dry_run: bool,
+    pub install_homebrew: bool,
+    pub install_fonts: bool,
+    pub generate_ssh_key: bool,
+    pub ssh_key: SshKeyChoice,
+    /// For a newly generated key; empty leaves it unprotected
+    #[serde(skip)]
+    pub ssh_passphrase: Secret,
+    /// For gh to log in with when `$GH_TOKEN` isn't set. Only ever comes
+    /// from the profile's secrets.
+    #[serde(skip)]
+    pub github_token: Secret,
+    /// Store a `$GH_TOKEN` or `$GITHUB_TOKEN` as gh's login. Set for
+    /// `--headless --github` and `--cloud-init`, and in the wizard only
+    /// once asked: the variable may hold a CI or throwaway token.
+    #[serde(default)]
+    pub use_env_token: bool,
+    pub setup_git_signing: bool,
+    /// Rewrite https://github.com/ URLs to SSH. Off by default: it breaks
+    /// token auth in CI and HTTPS-only proxies.
+    pub rewrite_github_https: bool,
+    #[serde(skip)]
+    pub cursor_position: usize,
+    #[serde(skip)]
+    pub scroll_offset: usize,
+    /// Cursor and scroll offset each phase was left at, put back when
+    /// it's entered again
+    #[serde(skip)]
+    pub positions: HashMap<WizardPhase, (usize, usize)>,
+    /// Cursor in each category's app list, kept while switching categories
+    #[serde(skip)]
+    pub category_cursors: HashMap<Category, usize>,
+    #[serde(skip)]
+    pub input_buffer: String,
+    #[serde(skip)]
+    pub input_field: usize,
+    #[serde(skip)]
+    pub show_details: bool,
+}
+
+impl Default for WizardState {
+    fn default() -> Self {
+        // Pre-select essential apps
+        let mut selected_apps = HashSet::new();
+        for app in crate::catalog::essential_apps() {
+            selected_apps.insert(app.id.to_string());
+        }
+
+        let mut state = Self {
+            phase: WizardPhase::Boot,
+            history: Vec::new(),
+            returning: false,
+            onboarding: false,
+            identity: Identity::default(),
+            shell_config: ShellConfig::default(),
+            editor: EditorChoice::Neovim,
+            theme: ThemeChoice::default(),
+            font: FontChoice::default(),
+            selected_apps,
+            kept_aliases: HashSet::new(),
+            app_modes: HashMap::new(),
+            custom_packages: Vec::new(),
+            locale: None,
+            timezone: None,
+            kube: KubeSetup::default(),
+            atuin: AtuinSetup::default(),
+            ai: AiSetup::default(),
+            selected_categories: HashSet::new(),
+            categories: ScreenCategories::default(),
+            dry_run: false,
+            install_homebrew: true,
+            install_fonts: true,
+            generate_ssh_key: true,
+            ssh_key: SshKeyChoice::Default,
+            ssh_passphrase: Secret::default(),
+            github_token: Secret::default(),
+            use_env_token: false,
+            setup_git_signing: false,
+            rewrite_github_https: false,
+            cursor_position: 0,
+            scroll_offset: 0,
+            positions: HashMap::new(),
+            category_cursors: HashMap::new(),
+            input_buffer: String::new(),
+            input_field: 0,
+            show_details: true,
+        };
+        state.include_dependencies();
+        state
+    }
+}
+
+impl WizardState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The phase after `phase`, given the answers so far. The first arm
+    /// that matches wins, so optional phases fall through to the next one
+    /// that applies.
+    pub fn next_phase(&self, phase: WizardPhase) -> Option<WizardPhase> {
+        use WizardPhase::*;
+
+        // Minimal leaves cluster, sync and key setup for later
+        let extras = self.identity.setup_type != SetupType::Minimal;
+
+        Some(match phase {
+            Boot if self.returning => Hub,
+            Boot | Hub => Preflight,
+            // Left by going back to the hub, or on to Install
+            Audit => return None,
+            Upgrade => Install,
+            Preflight => Identity,
+            Identity if self.wants_github() => GitHub,
+            Identity | GitHub => Shell,
+            Shell => DevTools,
+            DevTools => Apps,
+            Apps if extras && self.wants_kube() => Kubernetes,
+            Apps | Kubernetes if extras && self.configures("atuin") => Atuin,
+            Apps | Kubernetes | Atuin if extras && self.wants_ai() => AiTools,
+            Apps | Kubernetes | Atuin | AiTools => ConfigPreview,
+            ConfigPreview => Review,
+            Review => Install,
+            Install if self.onboarding => Onboarding,
+            Install | Onboarding => Complete,
+            Complete => return None,
+        })
+    }
+
+    pub fn advance(&mut self) -> bool {
+        let Some(next) = self.next_phase(self.phase) else {
+            return false;
+        };
+        self.history.push(self.phase);
+        self.enter(next);
+        true
+    }
+
+    /// Return to the phase this one was reached from
+    pub fn go_back(&mut self) -> bool {
+        let Some(prev) = self.history.pop() else {
+            return false;
+        };
+        self.enter(prev);
+        true
+    }
+
+    /// Jump to `phase`. One already visited is gone back to, dropping the
+    /// phases after it from the history; any other is moved on to.
+    pub fn go_to(&mut self, phase: WizardPhase) {
+        match self.history.iter().position(|p| *p == phase) {
+            Some(i) => self.history.truncate(i),
+            None => self.history.push(self.phase),
+        }
+        self.enter(phase);
+    }
+
+    /// Switch to `phase`, where the cursor was when it was last left.
+    /// Lists may have changed since, so the caller clamps.
+    fn enter(&mut self, phase: WizardPhase) {
+        self.positions
+            .insert(self.phase, (self.cursor_position, self.scroll_offset));
+        self.phase = phase;
+        (self.cursor_position, self.scroll_offset) =
+            self.positions.get(&phase).copied().unwrap_or_default();
+    }
+
+    /// The categories the current screen pages through; empty on screens
+    /// without an app list
+    pub fn categories(&self) -> &[Category] {
+        match self.phase {
+            WizardPhase::DevTools => &self.categories.devtools,
+            WizardPhase::Apps => &self.categories.apps,
+            _ => &[],
+        }
+    }
+
+    /// The category the current screen is showing
+    pub fn current_category(&self) -> Option<Category> {
+        let categories = self.categories();
+        (!categories.is_empty()).then(|| categories[self.scroll_offset % categories.len()])
+    }
+
+    /// The current category's apps
+    pub fn current_apps(&self) -> Vec<&'static App> {
+        self.current_category()
+            .map(|cat| crate::catalog::apps_by_category(&cat))
+            .unwrap_or_default()
+    }
+
+    /// Show the category at `index` of the current screen's list, back on
+    /// the app the cursor was last on there
+    pub fn show_category(&mut self, index: usize) {
+        let Some(current) = self.current_category() else {
+            return;
+        };
+        self.category_cursors.insert(current, self.cursor_position);
+        self.scroll_offset = index % self.categories().len();
+        let shown = self.categories()[self.scroll_offset];
+        self.cursor_position = self.category_cursors.get(&shown).copied().unwrap_or(0);
+    }
+
+    /// The whole route for the progress bar: phases visited so far, the
+    /// current one, then the ones the current answers lead to
+    pub fn path(&self) -> Vec<WizardPhase> {
+        let mut path = self.history.clone();
+        let mut phase = Some(self.phase);
+        while let Some(p) = phase {
+            path.push(p);
+            phase = self.next_phase(p);
+        }
+        path
+    }
+
+    /// A GitHub username was given, so there is an account to set up for
+    pub fn wants_github(&self) -> bool {
+        !self.identity.github_username.trim().is_empty()
+    }
+
+    /// aichat or Claude Code is picked, so there are API keys to set
+    pub fn wants_ai(&self) -> bool {
+        self.configures("aichat") || self.configures("claude-code")
+    }
+
+    /// kubectl or k9s is picked, so there are clusters to connect to
+    pub fn wants_kube(&self) -> bool {
+        self.installs("kubectl")
+            || self.installs("k9s")
+            || self.configures("kubectl")
+            || self.configures("k9s")
+    }
+
+    /// Select an app along with its dependencies, or deselect it unless
+    /// another selected app needs it
+    pub fn toggle_app(&mut self, app_id: &str) {
+        if self.selected_apps.contains(app_id) {
+            if self.required_by(app_id).is_empty() {
+                self.selected_apps.remove(app_id);
+            }
+        } else {
+            self.selected_apps.insert(app_id.to_string());
+            self.include_dependencies();
+        }
+    }
+
+    /// Whether every app in the bundle is selected
+    pub fn bundle_selected(&self, bundle: &Bundle) -> bool {
+        bundle
+            .apps
+            .iter()
+            .all(|id| self.selected_apps.contains(*id))
+    }
+
+    /// Select the whole bundle, or drop it if it's already complete. Apps
+    /// something outside the bundle still needs stay selected.
+    pub fn toggle_bundle(&mut self, bundle: &Bundle) {
+        if self.bundle_selected(bundle) {
+            for id in bundle.apps {
+                self.selected_apps.remove(*id);
+            }
+        } else {
+            self.selected_apps
+                .extend(bundle.apps.iter().map(|id| id.to_string()));
+        }
+        self.include_dependencies();
+    }
+
+    /// Select every dependency of the selected apps, transitively
+    pub fn include_dependencies(&mut self) {
+        loop {
+            let missing: Vec<&'static str> = self
+                .get_selected_apps()
+                .iter()
+                .flat_map(|app| app.dependencies.iter().copied())
+                .filter(|dep| !self.selected_apps.contains(*dep))
+                .collect();
+            if missing.is_empty() {
+                break;
+            }
+            self.selected_apps
+                .extend(missing.into_iter().map(str::to_string));
+        }
+    }
+
+    /// Selected apps that depend on this one
+    pub fn required_by(&self, app_id: &str) -> Vec<&'static App> {
+        self.get_selected_apps()
+            .into_iter()
+            .filter(|app| app.dependencies.contains(&app_id))
+            .collect()
+    }
+
+    pub fn is_app_selected(&self, app_id: &str) -> bool {
+        self.selected_apps.contains(app_id)
+    }
+
+    pub fn get_selected_apps(&self) -> Vec<&'static App> {
+        CATALOG
+            .iter()
+            .filter(|app| self.selected_apps.contains(app.id.as_str()))
+            .collect()
+    }
+
+    pub fn app_mode(&self, app_id: &str) -> AppMode {
+        self.app_modes.get(app_id).copied().unwrap_or_default()
+    }
+
+    /// Step a selected app through Install → Configure only → Skip config
+    pub fn cycle_app_mode(&mut self, app_id: &str) {
+        if !self.selected_apps.contains(app_id) {
+            return;
+        }
+        let next = self.app_mode(app_id).next();
+        if next == AppMode::Install {
+            self.app_modes.remove(app_id);
+        } else {
+            self.app_modes.insert(app_id.to_string(), next);
+        }
+    }
+
+    /// Apps picked on the Shell screen rather than from the lists: the
+    /// terminal, the editor, and the font when fonts are enabled
+    pub fn chosen_app_ids(&self) -> Vec<&'static str> {
+        let prompt = self
+            .shell_config
+            .prompt
+            .app_id()
+            .filter(|_| self.prompt_warning().is_none());
+        [
+            prompt,
+            self.shell_config.terminal.app_id(),
+            self.editor.app_id(),
+            self.font.app_id().filter(|_| self.install_fonts),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Whether setup ends with an SSH key to load, picked or generated
+    pub fn sets_up_ssh_key(&self) -> bool {
+        self.ssh_key != SshKeyChoice::Default || self.generate_ssh_key
+    }
+
+    /// Whether Starship is the prompt that gets initialized. Only one prompt
+    /// is; a selected starship app stands in when the prompt is left at the
+    /// system default.
+    pub fn uses_starship(&self) -> bool {
+        let prompt = self.shell_config.prompt;
+        prompt == PromptChoice::Starship
+            || (prompt == PromptChoice::None && self.configures("starship"))
+    }
+
+    /// Set when the prompt can't load in the chosen shell; the prompt is
+    /// then neither installed nor wired up
+    pub fn prompt_warning(&self) -> Option<String> {
+        let ShellConfig { shell, prompt, .. } = self.shell_config;
+        if prompt.zsh_only() && shell != ShellChoice::Zsh {
+            Some(format!(
+                "{} is zsh-only and won't load in {}",
+                prompt.name(),
+                shell.name()
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Combinations that won't work as picked. Shown on Review; none of
+    /// them block the install.
+    pub fn warnings(&self) -> Vec<String> {
+        let ShellConfig { shell, prompt, .. } = self.shell_config;
+        let mut warnings: Vec<String> = self.prompt_warning().into_iter().collect();
+
+        // Other shells get hooks in their managed rc block, but only for
+        // tools that support them; the Minimal prompt is a zsh PROMPT line
+        if shell != ShellChoice::Zsh {
+            if prompt == PromptChoice::Minimal {
+                warnings.push(format!(
+                    "{} prompt is only wired into .zshrc; {} keeps its own",
+                    prompt.name(),
+                    shell.name()
+                ));
+            }
+            let unsupported = hooks::unsupported(self, shell);
+            if !unsupported.is_empty() {
+                warnings.push(format!(
+                    "{} has no hook for {}; set it up yourself",
+                    shell.name(),
+                    unsupported.join(", ")
+                ));
+            }
+        }
+
+        if self.wants_ai()
+            && self.ai.source == KeySource::OnePassword
+            && !self.is_wanted("1password-cli")
+        {
+            warnings.push(
+                "1Password keys need the 1Password CLI (op); pick it under Security".to_string(),
+            );
+        }
+
+        warnings
+    }
+
+    /// Overlap groups with more apps picked than they need, and the apps
+    /// picked from each
+    pub fn overlaps(&self) -> Vec<(&'static Overlap, Vec<&'static App>)> {
+        OVERLAPS
+            .iter()
+            .filter_map(|overlap| {
+                let picked: Vec<&'static App> = overlap
+                    .apps
+                    .iter()
+                    .filter(|id| self.is_wanted(id))
+                    .filter_map(|id| find_app(id))
+                    .collect();
+                (picked.len() > overlap.max).then_some((overlap, picked))
+            })
+            .collect()
+    }
+
+    /// Open the Apps screen on a category, e.g. to trim an overlap
+    pub fn jump_to_category(&mut self, category: Category) {
+        self.go_to(WizardPhase::Apps);
+        let index = self
+            .categories()
+            .iter()
+            .position(|c| *c == category)
+            .unwrap_or(0);
+        self.show_category(index);
+    }
+
+    fn is_wanted(&self, app_id: &str) -> bool {
+        self.selected_apps.contains(app_id) || self.chosen_app_ids().contains(&app_id)
+    }
+
+    /// Whether the app is selected (or chosen) and should be installed
+    pub fn installs(&self, app_id: &str) -> bool {
+        self.is_wanted(app_id) && self.app_mode(app_id).installs()
+    }
+
+    /// Whether the app is selected (or chosen) and its config should be written
+    pub fn configures(&self, app_id: &str) -> bool {
+        self.is_wanted(app_id) && self.app_mode(app_id).configures()
+    }
+
+    /// Apps the executor should install: selected and chosen ones, minus
+    /// those marked configure-only, then any custom packages
+    pub fn get_apps_to_install(&self) -> Vec<&'static App> {
+        CATALOG
+            .iter()
+            .filter(|app| self.installs(app.id.as_str()))
+            .chain(self.custom_packages.iter().map(|p| p.app()))
+            .collect()
+    }
+
+    pub fn get_apps_for_category(&self, category: &Category) -> Vec<&'static App> {
+        crate::catalog::apps_by_category(category)
+    }
+
+    pub fn selected_app_count(&self) -> usize {
+        self.selected_apps.len()
+    }
+
+    pub fn total_app_count(&self) -> usize {
+        CATALOG.len()
+    }
+
+    /// Get brew packages to install
+    pub fn get_brew_packages(&self) -> Vec<String> {
+        let mut packages = Vec::new();
+
+        for app in self.get_selected_apps() {
+            if let crate::catalog::InstallMethod::Brew(pkg) = app.method() {
+                packages.push(pkg.to_string());
+            }
+        }
+
+        packages
+    }
+
+    /// Get brew casks to install
+    pub fn get_brew_casks(&self) -> Vec<String> {
+        let mut casks = Vec::new();
+
+        for app in self.get_selected_apps() {
+            if let crate::catalog::InstallMethod::BrewCask(pkg) = app.method() {
+                casks.push(pkg.to_string());
+            }
+        }
+
+        casks
+    }
+
+    /// Calculate estimated install time in minutes
+    pub fn estimated_install_time(&self) -> u32 {
+        let app_count = self.selected_apps.len() as u32;
+        let base_time = 5; // Base setup time
+        let per_app = 1; // ~1 minute per app on average
+
+        base_time + (app_count * per_app)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wizard_state_defaults() {
+        let state = WizardState::new();
+        assert_eq!(state.phase, WizardPhase::Boot);
+        assert_eq!(state.shell_config.shell, ShellChoice::Zsh);
+        assert_eq!(state.shell_config.prompt, PromptChoice::Starship);
+        assert_eq!(state.editor, EditorChoice::Neovim);
+        assert!(state.install_homebrew);
+        assert!(state.generate_ssh_key);
+        assert!(state.show_details);
+    }
+
+    #[test]
+    fn wizard_pre_selects_essentials() {
+        let state = WizardState::new();
+        assert!(
+            !state.selected_apps.is_empty(),
+            "Essential apps should be pre-selected"
+        );
+        // Check a few known essentials
+        assert!(state.selected_apps.contains("fzf") || state.selected_apps.contains("ripgrep"));
+    }
+
+    #[test]
+    fn wizard_phase_advance() {
+        let mut state = WizardState::new();
+        assert_eq!(state.phase, WizardPhase::Boot);
+
+        for expected in [
+            WizardPhase::Preflight,
+            WizardPhase::Identity,
+            WizardPhase::Shell,
+            WizardPhase::DevTools,
+            WizardPhase::Apps,
+            WizardPhase::ConfigPreview,
+            WizardPhase::Review,
+            WizardPhase::Install,
+            WizardPhase::Complete,
+        ] {
+            assert!(state.advance());
+            assert_eq!(state.phase, expected);
+        }
+
+        // Can't advance past Complete
+        assert!(!state.advance());
+        assert_eq!(state.phase, WizardPhase::Complete);
+    }
+
+    #[test]
+    fn wizard_phase_go_back() {
+        let mut state = WizardState::new();
+        state.advance();
+        state.advance();
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::Shell);
+
+        assert!(state.go_back());
+        assert_eq!(state.phase, WizardPhase::Identity);
+        assert!(state.go_back());
+        assert!(state.go_back());
+        assert_eq!(state.phase, WizardPhase::Boot);
+        assert!(!state.go_back());
+    }
+
+    #[test]
+    fn positions_survive_leaving_a_phase_and_category() {
+        let mut state = WizardState::new();
+        while state.phase != WizardPhase::Apps {
+            state.advance();
+        }
+        state.cursor_position = 3;
+        state.show_category(4);
+        assert_eq!(state.cursor_position, 0);
+        state.cursor_position = 2;
+
+        state.go_back();
+        assert_eq!(state.phase, WizardPhase::DevTools);
+        assert_eq!((state.cursor_position, state.scroll_offset), (0, 0));
+        state.advance();
+        assert_eq!((state.cursor_position, state.scroll_offset), (2, 4));
+
+        state.show_category(0);
+        assert_eq!(state.cursor_position, 3);
+        assert_eq!(state.current_category(), Some(Category::Shell));
+
+        state.categories.apps = vec![Category::AI, Category::Cloud];
+        state.show_category(3);
+        assert_eq!(state.current_category(), Some(Category::Cloud));
+        assert!(state
+            .current_apps()
+            .iter()
+            .all(|a| a.category == Category::Cloud));
+    }
+
+    #[test]
+    fn back_retraces_the_visited_path() {
+        let mut state = WizardState::new();
+        state.identity.github_username = "ada".to_string();
+        while state.phase != WizardPhase::Apps {
+            state.advance();
+        }
+        assert_eq!(
+            state.history,
+            [
+                WizardPhase::Boot,
+                WizardPhase::Preflight,
+                WizardPhase::Identity,
+                WizardPhase::GitHub,
+                WizardPhase::Shell,
+                WizardPhase::DevTools,
+            ]
+        );
+
+        // Clearing the username now doesn't change where back goes
+        state.identity.github_username.clear();
+        for _ in 0..3 {
+            state.go_back();
+        }
+        assert_eq!(state.phase, WizardPhase::GitHub);
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::Shell);
+
+        // Jumping to a visited phase drops what came after it
+        state.go_to(WizardPhase::Identity);
+        assert_eq!(state.history.last(), Some(&WizardPhase::Preflight));
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::Shell, "no username, no GitHub");
+    }
+
+    #[test]
+    fn minimal_skips_the_follow_up_setup_screens() {
+        let mut state = WizardState::new();
+        state.toggle_app("k9s");
+        state.toggle_app("atuin");
+        assert_eq!(
+            state.next_phase(WizardPhase::Apps),
+            Some(WizardPhase::Kubernetes)
+        );
+
+        state.identity.setup_type = SetupType::Minimal;
+        assert_eq!(
+            state.next_phase(WizardPhase::Apps),
+            Some(WizardPhase::ConfigPreview)
+        );
+        assert!(!state.path().contains(&WizardPhase::Atuin));
+    }
+
+    #[test]
+    fn wizard_toggle_app() {
+        let mut state = WizardState::new();
+        let was_selected = state.is_app_selected("neovim");
+
+        state.toggle_app("neovim");
+        assert_ne!(state.is_app_selected("neovim"), was_selected);
+
+        state.toggle_app("neovim");
+        assert_eq!(state.is_app_selected("neovim"), was_selected);
+    }
+
+    #[test]
+    fn app_mode_splits_install_and_config() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.selected_apps.insert("starship".to_string());
+        state.selected_apps.insert("tmux".to_string());
+
+        state.cycle_app_mode("starship");
+        assert_eq!(state.app_mode("starship"), AppMode::ConfigureOnly);
+        assert!(!state.installs("starship"));
+        assert!(state.configures("starship"));
+
+        state.cycle_app_mode("tmux");
+        state.cycle_app_mode("tmux");
+        assert_eq!(state.app_mode("tmux"), AppMode::SkipConfig);
+        assert!(state.installs("tmux"));
+        assert!(!state.configures("tmux"));
+
+        state.editor = EditorChoice::None;
+        state.install_fonts = false;
+        let ids: Vec<_> = state.get_apps_to_install().iter().map(|a| a.id).collect();
+        assert_eq!(ids, ["tmux"]);
+
+        // Wraps back to the default, and unselected apps have no mode
+        state.cycle_app_mode("tmux");
+        assert_eq!(state.app_mode("tmux"), AppMode::Install);
+        state.cycle_app_mode("micro");
+        assert!(!state.configures("micro"));
+    }
+
+    #[test]
+    fn chosen_editor_is_installed_even_if_not_selected() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.install_fonts = false;
+        state.shell_config.prompt = PromptChoice::None;
+        state.editor = EditorChoice::Helix;
+
+        let ids: Vec<_> = state.get_apps_to_install().iter().map(|a| a.id).collect();
+        assert_eq!(ids, ["helix"]);
+
+        state.editor = EditorChoice::None;
+        assert!(state.get_apps_to_install().is_empty());
+
+        state.install_fonts = true;
+        state.font = FontChoice::Hack;
+        let ids: Vec<_> = state.get_apps_to_install().iter().map(|a| a.id).collect();
+        assert_eq!(ids, ["font-hack"]);
+    }
+
+    #[test]
+    fn every_terminal_choice_is_installable() {
+        for terminal in TerminalChoice::all() {
+            if let Some(id) = terminal.app_id() {
+                assert!(
+                    crate::catalog::find_app(id).is_some(),
+                    "{} missing from catalog",
+                    id
+                );
+            }
+        }
+
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.install_fonts = false;
+        state.shell_config.prompt = PromptChoice::None;
+        state.editor = EditorChoice::None;
+        state.shell_config.terminal = TerminalChoice::Ghostty;
+        let ids: Vec<_> = state.get_apps_to_install().iter().map(|a| a.id).collect();
+        assert_eq!(ids, ["ghostty"]);
+    }
+
+    #[test]
+    fn zsh_only_prompt_is_skipped_for_other_shells() {
+        let mut state = WizardState::new();
+        state.shell_config.prompt = PromptChoice::Powerlevel10k;
+        assert!(state.prompt_warning().is_none());
+        assert!(state.installs("powerlevel10k"));
+
+        state.shell_config.shell = ShellChoice::Fish;
+        assert!(state.prompt_warning().is_some());
+        assert!(!state.installs("powerlevel10k"));
+    }
+
+    #[test]
+    fn warnings_flag_shell_mismatches() {
+        let mut state = WizardState::new();
+        state.selected_apps.insert("zoxide".to_string());
+        assert!(state.warnings().is_empty());
+
+        state.shell_config.shell = ShellChoice::Fish;
+        state.shell_config.prompt = PromptChoice::Powerlevel10k;
+        let warnings = state.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("zsh-only"));
+
+        // Nushell has no fzf hook; zoxide still gets wired in
+        state.shell_config.shell = ShellChoice::Nushell;
+        state.shell_config.prompt = PromptChoice::Starship;
+        state.selected_apps.insert("fzf".to_string());
+        let warnings = state.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Nushell has no hook for"));
+        assert!(warnings[0].contains("fzf"));
+        assert!(!warnings[0].contains("zoxide"));
+    }
+
+    #[test]
+    fn dependencies_are_included_and_kept() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+
+        state.toggle_app("lazydocker");
+        assert!(state.is_app_selected("docker"));
+        let required: Vec<_> = state.required_by("docker").iter().map(|a| a.id).collect();
+        assert_eq!(required, ["lazydocker"]);
+
+        // docker can't be dropped while lazydocker needs it
+        state.toggle_app("docker");
+        assert!(state.is_app_selected("docker"));
+
+        state.toggle_app("lazydocker");
+        state.toggle_app("docker");
+        assert!(state.selected_apps.is_empty());
+    }
+
+    #[test]
+    fn overlaps_count_chosen_and_selected_apps() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.shell_config.prompt = PromptChoice::None;
+        state.selected_apps.insert("htop".to_string());
+        assert!(state.overlaps().is_empty());
+
+        state.selected_apps.insert("btop".to_string());
+        state.selected_apps.insert("kitty".to_string());
+        state.selected_apps.insert("alacritty".to_string());
+        state.shell_config.terminal = TerminalChoice::Ghostty;
+        let overlaps = state.overlaps();
+        let labels: Vec<_> = overlaps.iter().map(|(o, _)| o.label).collect();
+        assert_eq!(labels, ["terminal emulators", "system monitors"]);
+        assert_eq!(overlaps[0].1.len(), 3);
+
+        state.jump_to_category(overlaps[1].0.category);
+        assert_eq!(state.phase, WizardPhase::Apps);
+        assert_eq!(Category::all()[state.scroll_offset], Category::System);
+    }
+
+    #[test]
+    fn ai_step_only_with_ai_tools() {
+        let mut state = WizardState::new();
+        state.selected_apps.remove("claude-code");
+        assert_eq!(
+            state.next_phase(WizardPhase::Apps),
+            Some(WizardPhase::ConfigPreview)
+        );
+
+        state.toggle_app("aichat");
+        assert_eq!(
+            state.next_phase(WizardPhase::Apps),
+            Some(WizardPhase::AiTools)
+        );
+
+        state.ai.source = KeySource::OnePassword;
+        assert!(state.warnings().iter().any(|w| w.contains("1Password CLI")));
+        state.toggle_app("1password-cli");
+        assert!(state
+            .warnings()
+            .iter()
+            .all(|w| !w.contains("1Password CLI")));
+    }
+
+    #[test]
+    fn atuin_step_only_with_atuin() {
+        let mut state = WizardState::new();
+        state.phase = WizardPhase::Apps;
+        state.toggle_app("atuin");
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::Atuin);
+
+        state.go_back();
+        state.toggle_app("atuin");
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::ConfigPreview);
+    }
+
+    #[test]
+    fn kubernetes_step_only_with_kubectl_or_k9s() {
+        let mut state = WizardState::new();
+        state.phase = WizardPhase::Apps;
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::ConfigPreview);
+
+        state.go_back();
+        state.toggle_app("k9s");
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::Kubernetes);
+        state.advance();
+        assert_eq!(state.phase, WizardPhase::ConfigPreview);
+    }
+
+    #[test]
+    fn bundle_toggles_as_a_unit() {
+        let bundle = crate::catalog::bundle_for(&Category::Cloud).unwrap();
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.toggle_app("terraform");
+        assert!(!state.bundle_selected(bundle));
+
+        state.toggle_bundle(bundle);
+        assert!(state.bundle_selected(bundle));
+
+        state.toggle_bundle(bundle);
+        assert!(state.selected_apps.is_empty());
+    }
+
+    #[test]
+    fn wizard_get_selected_apps() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.selected_apps.insert("git".to_string());
+        state.selected_apps.insert("neovim".to_string());
+
+        let apps = state.get_selected_apps();
+        assert_eq!(apps.len(), 2);
+    }
+
+    #[test]
+    fn wizard_brew_packages() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        state.selected_apps.insert("git".to_string());
+        state.selected_apps.insert("fzf".to_string());
+        state.selected_apps.insert("docker".to_string()); // This is a cask
+
+        let brew = state.get_brew_packages();
+        let casks = state.get_brew_casks();
+
+        assert!(brew.contains(&"git".to_string()));
+        assert!(brew.contains(&"fzf".to_string()));
+        assert!(casks.contains(&"docker".to_string()));
+        assert!(!brew.contains(&"docker".to_string()));
+    }
+
+    #[test]
+    fn wizard_estimated_time() {
+        let mut state = WizardState::new();
+        state.selected_apps.clear();
+        assert_eq!(state.estimated_install_time(), 5); // base time only
+
+        state.selected_apps.insert("git".to_string());
+        state.selected_apps.insert("fzf".to_string());
+        assert_eq!(state.estimated_install_time(), 7); // 5 + 2
+    }
+
+    #[test]
+    fn every_phase_is_on_some_path() {
+        let mut state = WizardState::new();
+        state.returning = true;
+        state.onboarding = true;
+        state.identity.github_username = "ada".to_string();
+        for id in ["k9s", "atuin", "aichat"] {
+            state.toggle_app(id);
+        }
+        let mut all = WizardPhase::all().to_vec();
+        all.retain(|p| !matches!(p, WizardPhase::Audit | WizardPhase::Upgrade));
+        assert_eq!(state.path(), all);
+
+        state.advance();
+        state.go_to(WizardPhase::Audit);
+        assert_eq!(state.next_phase(WizardPhase::Audit), None);
+        assert!(state.go_back());
+        assert_eq!(state.phase, WizardPhase::Hub);
+    }
+
+    #[test]
+    fn only_returning_users_see_the_hub() {
+        let mut state = WizardState::new();
+        assert_eq!(
+            state.next_phase(WizardPhase::Boot),
+            Some(WizardPhase::Preflight)
+        );
+        state.returning = true;
+        assert_eq!(state.next_phase(WizardPhase::Boot), Some(WizardPhase::Hub));
+        assert_eq!(
+            state.next_phase(WizardPhase::Hub),
+            Some(WizardPhase::Preflight)
+        );
+    }
+
+    #[test]
+    fn phase_names_and_descriptions() {
+        for phase in WizardPhase::all() {
+            assert!(!phase.name().is_empty());
+            assert!(!phase.description().is_empty());
+        }
+    }
+
+    #[test]
+    fn setup_type_metadata() {
+        for t in SetupType::all() {
+            assert!(!t.name().is_empty());
+            assert!(!t.description().is_empty());
+            assert!(!t.icon().is_empty());
+        }
+    }
+
+    #[test]
+    fn shell_choice_metadata() {
+        for s in ShellChoice::all() {
+            assert!(!s.name().is_empty());
+            assert!(!s.description().is_empty());
+        }
+    }
+
+    #[test]
+    fn identity_defaults_to_real_name() {
+        let identity = Identity::default();
+        // whoami::realname() may be empty in CI, so only the setup type is checked
+        assert_eq!(identity.setup_type, SetupType::Personal);
+    }
+}
@@ -1,6 +1,8 @@
 //! Strange and artful ASCII art for the hacker installer
 //! These constants are used selectively by effects and render modules.
 
+use rand::Rng;
+
 // Matrix rain characters
 pub const MATRIX_CHARS: &[char] = &[
     'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
@@ -16,8 +18,313 @@ pub const GLITCH_CHARS: &[char] = &[
 ];
 
 // Spinner frames
-#[allow(dead_code)]
 pub const SPINNER_DOTS: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 pub const SPINNER_BRAILLE: &[&str] = &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
-#[allow(dead_code)]
 pub const SPINNER_BLOCKS: &[&str] = &["▖", "▘", "▝", "▗"];
+
+/// A title card for the boot and Complete screens, with the boot lines
+/// that go with it
+pub struct Banner {
+    pub name: &'static str,
+    /// The full loading screen, for the boot screen when it fits
+    pub art: &'static [&'static str],
+    /// A few lines of it, for the Complete screen and small terminals
+    pub title: &'static [&'static str],
+    /// Typed out one by one while booting; the last is always `READY.`
+    pub boot: &'static [&'static str],
+}
+
+pub const BANNERS: &[Banner] = &[
+    Banner {
+        name: "load",
+        art: &[
+            "    **** COMMODORE 64 BASIC V2 ****",
+            " 64K RAM SYSTEM  38911 BASIC BYTES FREE",
+            "",
+            "READY.",
+            "LOAD\"*\",8,1",
+            "",
+            "SEARCHING FOR *",
+            "LOADING",
+            " █   ▄▀▄ ▄▀▄ █▀▄ ▄▀▀ ▀█▀ ▄▀▄ █▀▄",
+            " █   █ █ █▀█ █ █  ▀▄  █  █▀█ █▀▄",
+            " ▀▀▀  ▀  ▀ ▀ ▀▀  ▀▀   ▀  ▀ ▀ ▀ ▀",
+            "READY.",
+            "RUN",
+            "█",
+        ],
+        title: &["LOAD\"*\",8,1", "SEARCHING FOR *", "LOADING"],
+        boot: &[
+            "BIOS CHECK... ",
+            "NEURAL INTERFACE ONLINE... ",
+            "SCANNING REALITY MATRIX... ",
+            "QUANTUM ENTANGLEMENT STABLE... ",
+            "CONSCIOUSNESS UPLOAD READY... ",
+            "READY.",
+        ],
+    },
+    Banner {
+        name: "commodore",
+        art: &[
+            "      ▄▄██████▄▄",
+            "    ▄████████████▄    ▄▄▄▄▄▄▄▄▄",
+            "   ████▀▀    ▀▀███    █████████▀",
+            "  ████▀               ▀▀▀▀▀▀▀▀",
+            "  ████",
+            "  ████▄               ▄▄▄▄▄▄▄▄",
+            "   ████▄▄    ▄▄███    █████████▄",
+            "    ▀████████████▀    ▀▀▀▀▀▀▀▀▀",
+            "      ▀▀██████▀▀",
+            "",
+            "    **** COMMODORE 64 BASIC V2 ****",
+            " 64K RAM SYSTEM  38911 BASIC BYTES FREE",
+        ],
+        title: &[
+            "    **** COMMODORE 64 BASIC V2 ****",
+            " 64K RAM SYSTEM  38911 BASIC BYTES FREE",
+        ],
+        boot: &[
+            "POKE 53280,0... ",
+            "POKE 53281,0... ",
+            "SYS 64738... ",
+            "DEVICE 8 PRESENT... ",
+            "DIRECTORY \"$\" READ... ",
+            "READY.",
+        ],
+    },
+    Banner {
+        name: "turbo",
+        art: &[
+            "▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄",
+            "▄▀                                  ▀▄",
+            "▀▄  T U R B O   L O A D E R   V 2   ▄▀",
+            "▄▀                                  ▀▄",
+            "▀▄      ░▒▓█ LOADSTAR █▓▒░          ▄▀",
+            "▄▀                                  ▀▄",
+            "▀▄      PRESS PLAY ON TAPE          ▄▀",
+            "▄▀                                  ▀▄",
+            "▀▄  ████████████████░░░░░░░░░░  62% ▄▀",
+            "▄▀                                  ▀▄",
+            "▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀",
+        ],
+        title: &[
+            "▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄",
+            "    T U R B O   L O A D E R   V 2",
+            "▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀▄▀",
+            "PRESS PLAY ON TAPE",
+        ],
+        boot: &[
+            "OK... ",
+            "FOUND LOADSTAR... ",
+            "TURBO ON... ",
+            "DECRUNCHING... ",
+            "BORDER STRIPES NOMINAL... ",
+            "READY.",
+        ],
+    },
+    Banner {
+        name: "cracktro",
+        art: &[
+            "╔══════════════════════════════════════╗",
+            "║ ·:·:·:·  THE LOADSTAR CREW  ·:·:·:·  ║",
+            "║                                      ║",
+            "║            PRESENTS:                 ║",
+            "║                                      ║",
+            "║            █ █ ▄▀▄ █ █               ║",
+            "║            ▀█▀ █ █ █ █               ║",
+            "║             ▀   ▀  ▀▀▀               ║",
+            "║                                      ║",
+            "║  +5 TOOLS TRAINER  ·  1 PLAYER       ║",
+            "║  F1 INFINITE COFFEE  F3 NO BUGS      ║",
+            "║                                      ║",
+            "║░▒▓ GREETINGS TO ALL ELITE SYSOPS  ▓▒░║",
+            "╚══════════════════════════════════════╝",
+        ],
+        title: &[
+            "╔════════════════════════════════════╗",
+            "║  THE LOADSTAR CREW PRESENTS: YOU   ║",
+            "║   +5 TOOLS TRAINER  ·  1 PLAYER    ║",
+            "╚════════════════════════════════════╝",
+        ],
+        boot: &[
+            "SID TUNE LOADED... ",
+            "SCROLLTEXT ARMED... ",
+            "GREETINGS SENT TO ALL ELITE... ",
+            "TRAINER INSTALLED... ",
+            "COPY PARTY STARTED... ",
+            "READY.",
+        ],
+    },
+    Banner {
+        name: "disk",
+        art: &[
+            "┌──────────────────────────────┐",
+            "│▪                       ┌──┐  │",
+            "│      LOADSTAR SETUP    └──┘  │",
+            "│      DISK 1 OF 1             │",
+            "│            ▄▄▄▄▄▄            │",
+            "│          ▐████████▌          │",
+            "│          ▐███  ███▌          │",
+            "│          ▐████████▌          │",
+            "│            ▀▀▀▀▀▀            │",
+            "│                              │",
+            "│             ▐██▌             │",
+            "│             ▐██▌             │",
+            "└──────────────────────────────┘",
+        ],
+        title: &[
+            "┌────────────┐",
+            "│ ▪   ▄▄▄▄   │  DISK 1 OF 1",
+            "│    ▐████▌  │  LOADSTAR SETUP",
+            "└────────────┘",
+        ],
+        boot: &[
+            "SPINNING UP DRIVE 8... ",
+            "TRACK 18 SECTOR 0... ",
+            "READING DIRECTORY... ",
+            "FASTLOADER ENGAGED... ",
+            "CHECKSUM OK... ",
+            "READY.",
+        ],
+    },
+];
+
+impl Banner {
+    pub fn random() -> &'static Banner {
+        &BANNERS[rand::thread_rng().gen_range(0..BANNERS.len())]
+    }
+
+    /// The banner `[appearance] banner` names, or any of them for none or
+    /// `"random"`
+    pub fn pick(setting: Option<&str>) -> Result<&'static Banner, String> {
+        match setting {
+            None | Some("random") => Ok(Self::random()),
+            Some(name) => BANNERS.iter().find(|b| b.name == name).ok_or_else(|| {
+                let names: Vec<&str> = BANNERS.iter().map(|b| b.name).collect();
+                format!(
+                    "Unknown banner '{}' in appearance.banner; use \"random\" or one of {}",
+                    name,
+                    names.join(", ")
+                )
+            }),
+        }
+    }
+}
+
+/// Block letters three rows high, for names on the Complete screen
+const FONT: &[(char, [&str; 3])] = &[
+    ('A', ["▄▀▄", "█▀█", "▀ ▀"]),
+    ('B', ["█▀▄", "█▀▄", "▀▀ "]),
+    ('C', ["▄▀▀", "█  ", " ▀▀"]),
+    ('D', ["█▀▄", "█ █", "▀▀ "]),
+    ('E', ["█▀▀", "█▀▀", "▀▀▀"]),
+    ('F', ["█▀▀", "█▀ ", "▀  "]),
+    ('G', ["▄▀▀", "█ ▄", "▀▀▀"]),
+    ('H', ["█ █", "█▀█", "▀ ▀"]),
+    ('I', ["▀█▀", " █ ", "▀▀▀"]),
+    ('J', ["  █", "  █", "▀▀ "]),
+    ('K', ["█▄▀", "█▀▄", "▀ ▀"]),
+    ('L', ["█  ", "█  ", "▀▀▀"]),
+    ('M', ["█▄ ▄█", "█ ▀ █", "▀   ▀"]),
+    ('N', ["█▄ █", "█ ▀█", "▀  ▀"]),
+    ('O', ["▄▀▄", "█ █", " ▀ "]),
+    ('P', ["█▀▄", "█▀ ", "▀  "]),
+    ('Q', ["▄▀▄", "█ █", " ▀▄"]),
+    ('R', ["█▀▄", "█▀▄", "▀ ▀"]),
+    ('S', ["▄▀▀", " ▀▄", "▀▀ "]),
+    ('T', ["▀█▀", " █ ", " ▀ "]),
+    ('U', ["█ █", "█ █", "▀▀▀"]),
+    ('V', ["█ █", "▀▄▀", " ▀ "]),
+    ('W', ["█   █", "█ █ █", " ▀ ▀ "]),
+    ('X', ["▀▄▀", " █ ", "▀ ▀"]),
+    ('Y', ["█ █", "▀█▀", " ▀ "]),
+    ('Z', ["▀▀█", "▄▀ ", "▀▀▀"]),
+    ('0', ["█▀█", "█ █", "▀▀▀"]),
+    ('1', ["▄█ ", " █ ", "▀▀▀"]),
+    ('2', ["▀▀█", "█▀▀", "▀▀▀"]),
+    ('3', ["▀▀█", " ▀█", "▀▀▀"]),
+    ('4', ["█ █", "▀▀█", "  ▀"]),
+    ('5', ["█▀▀", "▀▀█", "▀▀▀"]),
+    ('6', ["█▀▀", "█▀█", "▀▀▀"]),
+    ('7', ["▀▀█", "  █", "  ▀"]),
+    ('8', ["█▀█", "█▀█", "▀▀▀"]),
+    ('9', ["█▀█", "▀▀█", "▀▀▀"]),
+    (' ', ["  ", "  ", "  "]),
+    ('-', ["   ", "▀▀▀", "   "]),
+    ('.', [" ", " ", "▀"]),
+    ('\'', ["▀", " ", " "]),
+];
+
+/// `text` in block letters, as three rows. Letters are upper-cased;
+/// anything the font has no glyph for is left out.
+pub fn figlet(text: &str) -> [String; 3] {
+    let mut rows: [String; 3] = Default::default();
+    let glyphs = text
+        .chars()
+        .flat_map(char::to_uppercase)
+        .filter_map(|c| FONT.iter().find(|(f, _)| *f == c).map(|(_, g)| g));
+    for (i, glyph) in glyphs.enumerate() {
+        for (row, part) in rows.iter_mut().zip(glyph) {
+            if i > 0 {
+                row.push(' ');
+            }
+            row.push_str(part);
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banners_are_picked_by_name_or_at_random() {
+        assert_eq!(Banner::pick(Some("turbo")).unwrap().name, "turbo");
+        assert!(Banner::pick(None).is_ok());
+        assert!(Banner::pick(Some("random")).is_ok());
+        let err = Banner::pick(Some("amiga")).err().unwrap();
+        assert!(
+            err.contains("'amiga'") && err.contains("cracktro"),
+            "{}",
+            err
+        );
+        for banner in BANNERS {
+            assert_eq!(banner.boot.last(), Some(&"READY."), "{}", banner.name);
+        }
+    }
+
+    #[test]
+    fn banners_fill_the_boot_screen() {
+        for banner in BANNERS {
+            assert!(banner.art.len() >= 10, "{}", banner.name);
+            assert!(banner.title.len() < banner.art.len(), "{}", banner.name);
+            for line in banner.art.iter().chain(banner.title) {
+                assert!(line.chars().count() <= 43, "{}: {}", banner.name, line);
+            }
+            // Every row of a box closes in the same column as its top edge
+            for art in [banner.art, banner.title] {
+                let widths: Vec<_> = art
+                    .iter()
+                    .filter(|line| line.starts_with(['║', '╔', '╚', '│', '┌', '└']))
+                    .map(|line| {
+                        let edge = line.rfind(['║', '╗', '╝', '│', '┐', '┘']).unwrap_or(0);
+                        (line, line[..edge].chars().count() + 1)
+                    })
+                    .collect();
+                for (line, width) in &widths {
+                    assert_eq!(*width, widths[0].1, "{}: {}", banner.name, line);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn names_come_out_in_block_letters() {
+        assert_eq!(
+            figlet("Ada é!"),
+            ["▄▀▄ █▀▄ ▄▀▄   ", "█▀█ █ █ █▀█   ", "▀ ▀ ▀▀  ▀ ▀   "]
+        );
+        assert_eq!(figlet(""), ["", "", ""]);
+    }
+}
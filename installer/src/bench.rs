@@ -0,0 +1,316 @@
+//! Executor benchmarks
+//! Compares install strategies on one profile: modeled from a per-command
+//! cost table with `--demo`, or timed for real in a container per strategy.
+
+use std::path::Path;
+use std::time::Duration;
+
+use loadstar_core::catalog::{self, App, InstallMethod};
+use loadstar_core::executor::Plan;
+use loadstar_core::system::SystemInfo;
+
+use crate::selftest;
+
+/// One way of running the install
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// One brew call per package, brew update first
+    Serial,
+    /// One brew call per phase (`--fast`)
+    Batched,
+    /// brew update alongside the non-brew tools
+    Background,
+    /// Both of the above
+    BackgroundBatched,
+    /// Non-brew tools on their own thread for the whole run. Not in the
+    /// executor; modeled to see whether it would be worth building.
+    Parallel,
+}
+
+impl Strategy {
+    pub fn all() -> &'static [Strategy] {
+        &[
+            Strategy::Serial,
+            Strategy::Batched,
+            Strategy::Background,
+            Strategy::BackgroundBatched,
+            Strategy::Parallel,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Serial => "serial",
+            Strategy::Batched => "batched",
+            Strategy::Background => "background",
+            Strategy::BackgroundBatched => "background+batched",
+            Strategy::Parallel => "parallel (model)",
+        }
+    }
+
+    /// Flags for a headless run, or `None` when the executor can't run it
+    pub fn args(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Strategy::Serial => Some(&["--brew-update", "full"]),
+            Strategy::Batched => Some(&["--brew-update", "full", "--fast"]),
+            Strategy::Background => Some(&["--brew-update", "background"]),
+            Strategy::BackgroundBatched => Some(&["--brew-update", "background", "--fast"]),
+            Strategy::Parallel => None,
+        }
+    }
+
+    fn batched(&self) -> bool {
+        matches!(
+            self,
+            Strategy::Batched | Strategy::BackgroundBatched | Strategy::Parallel
+        )
+    }
+}
+
+/// Time spent per phase. Phases can overlap, so `total` may be less than
+/// their sum.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timings {
+    pub update: Duration,
+    pub formulae: Duration,
+    pub casks: Duration,
+    pub other: Duration,
+    pub total: Duration,
+}
+
+// ─── Demo model ──────────────────────────────────────────────────────
+
+/// Ruby start and tap loading, paid by every brew call
+const BREW_STARTUP: f64 = 2.0;
+/// `brew update` on a stale tap
+const BREW_UPDATE: f64 = 30.0;
+/// The per-package `brew list` check that runs before installing
+const BREW_CHECK: f64 = 0.8;
+
+/// Modeled seconds to install one package, not counting brew startup.
+/// Rough figures for a decent connection; the ratios are what matter.
+fn install_cost(method: &InstallMethod) -> f64 {
+    match method {
+        InstallMethod::Brew(_) => 6.0,
+        InstallMethod::BrewCask(_) => 15.0,
+        InstallMethod::Cargo(_) => 60.0,
+        InstallMethod::Go(_) => 15.0,
+        InstallMethod::Npm(_) => 5.0,
+        InstallMethod::Pip(_) => 3.0,
+        InstallMethod::Script(_) | InstallMethod::Manual(_) => 10.0,
+        InstallMethod::Apt(_) => 4.0,
+        InstallMethod::PerOs { .. } => install_cost(method.for_current_os()),
+    }
+}
+
+/// A brew phase: the per-package checks, then one brew call per package
+/// or one for the lot
+fn brew_phase(apps: &[&App], batched: bool) -> f64 {
+    if apps.is_empty() {
+        return 0.0;
+    }
+    let installs: f64 = apps.iter().map(|a| install_cost(a.method())).sum();
+    let calls = if batched { 1 } else { apps.len() };
+    apps.len() as f64 * BREW_CHECK + calls as f64 * BREW_STARTUP + installs
+}
+
+fn tools_phase(apps: &[&App]) -> f64 {
+    apps.iter().map(|a| install_cost(a.method())).sum()
+}
+
+/// Modeled timings for a plan, with Homebrew present but out of date
+pub fn model(plan: &Plan, strategy: Strategy) -> Timings {
+    let update = BREW_UPDATE;
+    let formulae = brew_phase(&plan.formulae, strategy.batched());
+    let casks = brew_phase(&plan.casks, strategy.batched());
+    let ready = tools_phase(&plan.ready);
+    let waiting = tools_phase(&plan.waiting);
+
+    let total = match strategy {
+        Strategy::Serial | Strategy::Batched => update + formulae + casks + ready + waiting,
+        Strategy::Background | Strategy::BackgroundBatched => {
+            update.max(ready) + formulae + casks + waiting
+        }
+        Strategy::Parallel => (update + formulae + casks + waiting).max(ready),
+    };
+
+    Timings {
+        update: Duration::from_secs_f64(update),
+        formulae: Duration::from_secs_f64(formulae),
+        casks: Duration::from_secs_f64(casks),
+        other: Duration::from_secs_f64(ready + waiting),
+        total: Duration::from_secs_f64(total),
+    }
+}
+
+/// Model every strategy for `apps` on a machine with no toolchains yet
+pub fn run_demo(apps: Vec<&'static App>) -> String {
+    let (apps, _) = catalog::with_toolchains(apps, |_| false);
+    let plan = Plan::new(&apps, |_| false);
+
+    let mut report = format!(
+        "[BENCH] {} apps: {} formulae, {} casks, {} other ({} waiting on a toolchain)\n  \
+         Modeled timings: Homebrew installed with a stale tap, no toolchains yet\n\n",
+        apps.len(),
+        plan.formulae.len(),
+        plan.casks.len(),
+        plan.other.len(),
+        plan.waiting.len()
+    );
+    let results: Vec<(Strategy, Timings)> = Strategy::all()
+        .iter()
+        .map(|s| (*s, model(&plan, *s)))
+        .collect();
+    report.push_str(&table(&results));
+    report
+}
+
+// ─── Container runs ──────────────────────────────────────────────────
+
+/// Time each strategy the executor supports in a fresh container
+pub fn run_containers(profile: &Path, image: &str, system: &SystemInfo) -> anyhow::Result<String> {
+    let mut results = Vec::new();
+    let mut failures = Vec::new();
+
+    for strategy in Strategy::all() {
+        let Some(args) = strategy.args() else {
+            continue;
+        };
+        println!("[BENCH] {}", strategy.name());
+        let report = selftest::run(profile, image, args, system)?;
+        if !report.passed() {
+            failures.push(strategy.name());
+        }
+        results.push((*strategy, timings_from_phases(&report.phases)));
+    }
+
+    let mut table = table(&results);
+    if !failures.is_empty() {
+        table.push_str(&format!(
+            "\n  [WARN] Failures in {}; their timings are not comparable\n",
+            failures.join(", ")
+        ));
+    }
+    Ok(table)
+}
+
+/// Map the headless phases onto timing columns. Container setup is left
+/// out of the total; "Additional Tools" can run twice in background mode.
+fn timings_from_phases(phases: &[(String, Duration)]) -> Timings {
+    let mut timings = Timings::default();
+    for (phase, elapsed) in phases {
+        let column = match phase.as_str() {
+            "Homebrew Bootstrap" => &mut timings.update,
+            "Homebrew Formulae" => &mut timings.formulae,
+            "Homebrew Casks" => &mut timings.casks,
+            "Additional Tools" => &mut timings.other,
+            _ if phase.as_str() == selftest::SETUP_PHASE => continue,
+            _ => &mut timings.total,
+        };
+        *column += *elapsed;
+    }
+    timings.total += timings.update + timings.formulae + timings.casks + timings.other;
+    timings
+}
+
+// ─── Report ──────────────────────────────────────────────────────────
+
+fn table(results: &[(Strategy, Timings)]) -> String {
+    let baseline = results
+        .iter()
+        .find(|(s, _)| *s == Strategy::Serial)
+        .map(|(_, t)| t.total.as_secs_f64());
+
+    let mut table = format!(
+        "  {:<20}{:>8}{:>10}{:>8}{:>8}{:>9}{:>11}\n",
+        "strategy", "update", "formulae", "casks", "other", "total", "vs serial"
+    );
+    for (strategy, t) in results {
+        let speedup = match baseline {
+            Some(base) if t.total.as_secs_f64() > 0.0 => {
+                format!("{:.2}x", base / t.total.as_secs_f64())
+            }
+            _ => "-".to_string(),
+        };
+        table.push_str(&format!(
+            "  {:<20}{:>8}{:>10}{:>8}{:>8}{:>9}{:>11}\n",
+            strategy.name(),
+            secs(t.update),
+            secs(t.formulae),
+            secs(t.casks),
+            secs(t.other),
+            secs(t.total),
+            speedup
+        ));
+    }
+    table
+}
+
+fn secs(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loadstar_core::catalog::find_app;
+
+    fn plan(ids: &[&str]) -> Plan {
+        let apps: Vec<&'static App> = ids.iter().map(|id| find_app(id).unwrap()).collect();
+        let (apps, _) = catalog::with_toolchains(apps, |_| false);
+        Plan::new(&apps, |_| false)
+    }
+
+    #[test]
+    fn batching_and_overlap_only_ever_save_time() {
+        let plan = plan(&["ripgrep", "bat", "fd", "jq", "ruff"]);
+        let serial = model(&plan, Strategy::Serial).total;
+        let batched = model(&plan, Strategy::Batched).total;
+        let background = model(&plan, Strategy::Background).total;
+        let parallel = model(&plan, Strategy::Parallel).total;
+
+        // Five formulae (uv comes along for ruff): four brew startups saved
+        assert_eq!(
+            serial - batched,
+            Duration::from_secs_f64(4.0 * BREW_STARTUP)
+        );
+        assert!(background <= serial);
+        assert!(parallel <= model(&plan, Strategy::BackgroundBatched).total);
+    }
+
+    #[test]
+    fn background_hides_the_update_behind_ready_tools() {
+        // Nothing ready before brew: ruff waits on uv, so no overlap
+        let plan = plan(&["ruff"]);
+        assert_eq!(
+            model(&plan, Strategy::Background).total,
+            model(&plan, Strategy::Serial).total
+        );
+    }
+
+    #[test]
+    fn container_phases_map_to_columns() {
+        let phases = [
+            (selftest::SETUP_PHASE.to_string(), Duration::from_secs(40)),
+            ("Homebrew Bootstrap".to_string(), Duration::from_secs(20)),
+            ("Additional Tools".to_string(), Duration::from_secs(3)),
+            ("Homebrew Formulae".to_string(), Duration::from_secs(10)),
+            ("Additional Tools".to_string(), Duration::from_secs(2)),
+        ];
+        let timings = timings_from_phases(&phases);
+        assert_eq!(timings.other, Duration::from_secs(5));
+        assert_eq!(timings.total, Duration::from_secs(35));
+    }
+
+    #[test]
+    fn table_compares_against_serial() {
+        let plan = plan(&["ripgrep", "bat"]);
+        let results: Vec<(Strategy, Timings)> = [Strategy::Serial, Strategy::Batched]
+            .iter()
+            .map(|s| (*s, model(&plan, *s)))
+            .collect();
+        let table = table(&results);
+        assert!(table.contains("vs serial"));
+        assert!(table.lines().nth(1).unwrap().ends_with("1.00x"));
+    }
+}
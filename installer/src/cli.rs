@@ -0,0 +1,364 @@
+//! Command-line arguments
+//! Flags override the matching profile setting for a single run.
+
+use std::path::PathBuf;
+
+use loadstar_core::executor::BrewUpdate;
+
+pub const USAGE: &str = "\
+Usage: loadstar [OPTIONS]
+       loadstar test --profile <PATH> [--image <IMAGE>]
+       loadstar bench [--profile <PATH>] [--demo | --image <IMAGE>]
+       loadstar attach
+       loadstar catalog check
+       loadstar apply <PLAN>
+
+Commands:
+  test                   Install the profile's apps in a throwaway Docker
+                         container and report which ones succeeded
+  bench                  Compare install strategies (serial, batched,
+                         background update) on the profile's apps
+  attach                 Show the install started with --detach, if it
+                         is still running
+  catalog check          Check every catalog entry against Homebrew's
+                         index, package registries and its homepage
+  apply <PLAN>           Run a plan exported from Review (e) as written,
+                         without the wizard, once it's checked against
+                         this machine
+
+Options:
+  --profile <PATH>       Load preferences from PATH instead of
+                         ~/.config/loadstar/profile.toml. gh:USER or
+                         gh:USER/REPO pulls a backed-up profile
+  --onboarding <URL>     Fetch a team profile over https and, after the
+                         install, walk through its [onboarding] checklist
+                         and write a report for IT
+  --headless             Install the profile's apps without the TUI,
+                         logging to stdout (what `test` runs inside)
+  --github               With --headless, also set up git, SSH and gh;
+                         gh logs in from $GH_TOKEN or $GITHUB_TOKEN
+  --cloud-init           First boot of a VM or container: --headless
+                         that never prompts, takes LOADSTAR_* variables,
+                         writes the config files and an MOTD
+  --detach               Run the install in the background, so closing
+                         the terminal doesn't stop it
+  --dry-run              Go through the install and print each command
+                         and file write as [DRY] instead of doing it
+  --force                Install even while another loadstar run holds
+                         the run lock
+  --narrate              Announce each step and result as plain lines on
+                         stdout, for screen readers. Redirect stdout to
+                         hear them as they happen; on the TUI's own
+                         terminal they print when it's suspended or quit
+  --narrate-file <PATH>  Append the announcements to PATH instead
+  --image <IMAGE>        Image for `test` and `bench` (default: ubuntu:24.04)
+  --demo                 `bench` with modeled timings instead of a
+                         container per strategy
+  --brew-update <MODE>   full | skip | background (default: full)
+  --no-brew-update       Same as --brew-update skip
+  --brew-cleanup         Run `brew cleanup` after installing
+  --fast                 One `brew install` for all formulae (and one
+                         for all casks) instead of one per package
+  -h, --help             Print this help
+  -V, --version          Print the version";
+
+/// Image `loadstar test` runs in unless `--image` says otherwise
+pub const DEFAULT_TEST_IMAGE: &str = "ubuntu:24.04";
+
+/// A subcommand in place of the wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subcommand {
+    /// Self-test a profile in a container
+    Test,
+    /// Compare executor strategies
+    Bench,
+    /// Follow a detached install
+    Attach,
+    /// Check that catalog entries still resolve
+    CatalogCheck,
+    /// Run an exported plan
+    Apply,
+    /// Run a detached install handed over on stdin. Only `--detach`
+    /// starts this, so it stays out of the usage text.
+    Job,
+}
+
+/// Parsed command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cli {
+    pub command: Option<Subcommand>,
+    pub profile: Option<PathBuf>,
+    /// Team profile with a new-hire checklist, fetched from this URL
+    pub onboarding: Option<String>,
+    /// The plan `apply` runs
+    pub plan: Option<PathBuf>,
+    pub headless: bool,
+    pub github: bool,
+    /// Headless first boot, settings from the environment too
+    pub cloud_init: bool,
+    pub detach: bool,
+    /// Log the install's commands and writes instead of running them
+    pub dry_run: bool,
+    pub force: bool,
+    /// List the apps this machine can't install and exit; for CI, so it
+    /// stays out of the usage text
+    pub check_catalog: bool,
+    pub narrate: bool,
+    pub narrate_file: Option<PathBuf>,
+    pub image: Option<String>,
+    pub demo: bool,
+    pub brew_update: Option<BrewUpdate>,
+    pub brew_cleanup: bool,
+    pub brew_batch: bool,
+    pub help: bool,
+    pub version: bool,
+}
+
+impl Cli {
+    /// Parse arguments (without the program name)
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // Accept both `--flag value` and `--flag=value`
+            let (flag, inline) = match arg.split_once('=') {
+                Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+                _ => (arg, None),
+            };
+            let mut value = |name: &str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", name))
+            };
+
+            match flag.as_str() {
+                "test" if cli.command.is_none() => cli.command = Some(Subcommand::Test),
+                "bench" if cli.command.is_none() => cli.command = Some(Subcommand::Bench),
+                "attach" if cli.command.is_none() => cli.command = Some(Subcommand::Attach),
+                "job" if cli.command.is_none() => cli.command = Some(Subcommand::Job),
+                "apply" if cli.command.is_none() => {
+                    cli.command = Some(Subcommand::Apply);
+                    cli.plan = Some(PathBuf::from(value("apply")?));
+                }
+                "catalog" if cli.command.is_none() => match value("catalog")?.as_str() {
+                    "check" => cli.command = Some(Subcommand::CatalogCheck),
+                    other => return Err(format!("Unknown catalog command '{}'", other)),
+                },
+                "--demo" => cli.demo = true,
+                "--profile" => cli.profile = Some(PathBuf::from(value("--profile")?)),
+                "--onboarding" => cli.onboarding = Some(value("--onboarding")?),
+                "--headless" => cli.headless = true,
+                "--github" => cli.github = true,
+                "--cloud-init" => cli.cloud_init = true,
+                "--detach" => cli.detach = true,
+                "--dry-run" => cli.dry_run = true,
+                "--force" => cli.force = true,
+                "--check-catalog" => cli.check_catalog = true,
+                "--narrate" => cli.narrate = true,
+                "--narrate-file" => {
+                    cli.narrate_file = Some(PathBuf::from(value("--narrate-file")?))
+                }
+                "--image" => cli.image = Some(value("--image")?),
+                "--brew-update" => {
+                    let mode = value("--brew-update")?;
+                    cli.brew_update = Some(BrewUpdate::from_name(&mode).ok_or_else(|| {
+                        format!(
+                            "Unknown brew update mode '{}' (expected full, skip or background)",
+                            mode
+                        )
+                    })?);
+                }
+                "--no-brew-update" => cli.brew_update = Some(BrewUpdate::Skip),
+                "--brew-cleanup" => cli.brew_cleanup = true,
+                "--fast" => cli.brew_batch = true,
+                "-h" | "--help" => cli.help = true,
+                "-V" | "--version" => cli.version = true,
+                other => return Err(format!("Unknown argument '{}'", other)),
+            }
+        }
+
+        if cli.cloud_init {
+            if cli.command.is_some() || cli.detach || cli.onboarding.is_some() {
+                return Err("--cloud-init is a run of its own".to_string());
+            }
+            cli.headless = true;
+        }
+        if cli.image.is_some() && (cli.command.is_none() || cli.demo) {
+            return Err("--image only applies to `loadstar test` and `loadstar bench`".to_string());
+        }
+        if cli.github && !cli.headless {
+            return Err("--github only applies with --headless".to_string());
+        }
+        if cli.detach && (cli.headless || cli.command.is_some()) {
+            return Err("--detach only applies to the wizard".to_string());
+        }
+        if cli.dry_run && (cli.cloud_init || cli.detach || cli.command.is_some()) {
+            return Err("--dry-run only applies to the wizard and --headless".to_string());
+        }
+        let tui = !cli.headless && matches!(cli.command, None | Some(Subcommand::Attach));
+        if (cli.narrate || cli.narrate_file.is_some()) && !tui {
+            return Err("--narrate only applies to the wizard".to_string());
+        }
+        if cli.onboarding.is_some() && (cli.headless || cli.command.is_some()) {
+            return Err("--onboarding only applies to the wizard".to_string());
+        }
+        if cli.onboarding.is_some() && cli.profile.is_some() {
+            return Err("--onboarding and --profile are one or the other".to_string());
+        }
+        if cli.narrate && cli.narrate_file.is_some() {
+            return Err("--narrate and --narrate-file are one or the other".to_string());
+        }
+        if cli.force && !matches!(cli.command, None | Some(Subcommand::Apply)) {
+            return Err("--force only applies to runs that install".to_string());
+        }
+        if cli.demo && cli.command != Some(Subcommand::Bench) {
+            return Err("--demo only applies to `loadstar bench`".to_string());
+        }
+        // Only a modeled bench can make do with the default selection
+        if cli.profile.is_none() {
+            match cli.command {
+                Some(Subcommand::Test) => {
+                    return Err("`loadstar test` needs --profile".to_string());
+                }
+                Some(Subcommand::Bench) if !cli.demo => {
+                    return Err("`loadstar bench` needs --profile (or --demo)".to_string());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        Cli::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn no_args_is_default() {
+        assert_eq!(parse(&[]).unwrap(), Cli::default());
+    }
+
+    #[test]
+    fn brew_update_flags() {
+        assert_eq!(
+            parse(&["--no-brew-update"]).unwrap().brew_update,
+            Some(BrewUpdate::Skip)
+        );
+        assert_eq!(
+            parse(&["--brew-update", "background"]).unwrap().brew_update,
+            Some(BrewUpdate::Background)
+        );
+        assert_eq!(
+            parse(&["--brew-update=full"]).unwrap().brew_update,
+            Some(BrewUpdate::Full)
+        );
+        assert!(parse(&["--brew-update", "never"]).is_err());
+        assert!(parse(&["--brew-update"]).is_err());
+        assert!(parse(&["--brew-cleanup"]).unwrap().brew_cleanup);
+        assert!(parse(&["--fast"]).unwrap().brew_batch);
+    }
+
+    #[test]
+    fn profile_path() {
+        let cli = parse(&["--profile", "/tmp/p.toml"]).unwrap();
+        assert_eq!(cli.profile, Some(PathBuf::from("/tmp/p.toml")));
+    }
+
+    #[test]
+    fn test_subcommand_needs_a_profile() {
+        let cli = parse(&["test", "--profile", "p.toml", "--image", "debian:12"]).unwrap();
+        assert_eq!(cli.command, Some(Subcommand::Test));
+        assert_eq!(cli.image.as_deref(), Some("debian:12"));
+
+        assert!(parse(&["test"]).is_err());
+        assert!(parse(&["--image", "debian:12"]).is_err());
+        assert!(parse(&["test", "--profile", "p.toml", "test"]).is_err());
+        assert!(parse(&["--headless"]).unwrap().headless);
+        assert!(parse(&["--headless", "--github"]).unwrap().github);
+        assert!(parse(&["--github"]).is_err());
+        assert!(parse(&["--check-catalog"]).unwrap().check_catalog);
+        assert!(parse(&["--detach"]).unwrap().detach);
+        assert!(parse(&["--headless", "--detach"]).is_err());
+        assert!(parse(&["attach", "--detach"]).is_err());
+        assert!(parse(&["--narrate"]).unwrap().narrate);
+        assert!(parse(&["attach", "--narrate-file", "/tmp/said"]).is_ok());
+        assert!(parse(&["--headless", "--narrate"]).is_err());
+        assert!(parse(&["--narrate", "--narrate-file=/tmp/said"]).is_err());
+        assert_eq!(
+            parse(&["attach"]).unwrap().command,
+            Some(Subcommand::Attach)
+        );
+        assert_eq!(
+            parse(&["catalog", "check"]).unwrap().command,
+            Some(Subcommand::CatalogCheck)
+        );
+        assert!(parse(&["catalog"]).is_err());
+        assert!(parse(&["catalog", "fix"]).is_err());
+        let cli = parse(&["apply", "plan.json", "--fast"]).unwrap();
+        assert_eq!(cli.command, Some(Subcommand::Apply));
+        assert_eq!(cli.plan, Some(PathBuf::from("plan.json")));
+        assert!(parse(&["apply"]).is_err());
+        assert!(parse(&["apply", "plan.json", "--force"]).unwrap().force);
+        assert!(parse(&["attach", "--force"]).is_err());
+    }
+
+    #[test]
+    fn cloud_init_is_headless() {
+        let cli = parse(&["--cloud-init", "--github", "--fast"]).unwrap();
+        assert!(cli.cloud_init && cli.headless && cli.github && cli.brew_batch);
+        assert!(parse(&["--cloud-init", "--detach"]).is_err());
+        assert!(parse(&["--cloud-init", "--narrate"]).is_err());
+        assert!(parse(&["test", "--profile", "p.toml", "--cloud-init"]).is_err());
+    }
+
+    #[test]
+    fn dry_run_is_for_installs_in_the_foreground() {
+        assert!(parse(&["--dry-run"]).unwrap().dry_run);
+        assert!(
+            parse(&["--headless", "--github", "--dry-run"])
+                .unwrap()
+                .dry_run
+        );
+        assert!(parse(&["--dry-run", "--detach"]).is_err());
+        assert!(parse(&["--cloud-init", "--dry-run"]).is_err());
+        assert!(parse(&["apply", "plan.json", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn onboarding_stands_in_for_the_profile() {
+        let url = "https://acme.example/onboarding.toml";
+        let cli = parse(&["--onboarding", url]).unwrap();
+        assert_eq!(cli.onboarding.as_deref(), Some(url));
+        assert!(parse(&["--onboarding", url, "--profile", "p.toml"]).is_err());
+        assert!(parse(&["--onboarding", url, "--headless"]).is_err());
+        assert!(parse(&["--onboarding"]).is_err());
+    }
+
+    #[test]
+    fn bench_demo_needs_no_container() {
+        let cli = parse(&["bench", "--demo"]).unwrap();
+        assert_eq!(cli.command, Some(Subcommand::Bench));
+        assert!(cli.demo);
+
+        assert!(parse(&["bench"]).is_err());
+        assert!(parse(&["bench", "--demo", "--image", "debian:12"]).is_err());
+        assert!(parse(&["--demo"]).is_err());
+        assert!(parse(&["bench", "--profile", "p.toml", "--image", "debian:12"]).is_ok());
+    }
+
+    #[test]
+    fn unknown_argument_is_rejected() {
+        assert!(parse(&["--frobnicate"]).is_err());
+    }
+}
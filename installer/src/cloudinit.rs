@@ -0,0 +1,162 @@
+//! Cloud-init first boot
+//! `--cloud-init` installs on a fresh VM or container with no one at the
+//! keyboard: settings come from flags and `LOADSTAR_*` variables, nothing
+//! prompts, and an MOTD says what was set up.
+
+use std::path::PathBuf;
+
+use loadstar_core::catalog::{find_app, Query};
+use loadstar_core::executor::{InstallOptions, InstallSummary};
+use loadstar_core::profile::Profile;
+use loadstar_core::system::{Os, SystemInfo};
+use loadstar_core::wizard::WizardState;
+use loadstar_core::{github, locale, motd, nextsteps};
+
+use crate::cli::Cli;
+use crate::headless;
+
+/// Profile path, when `--profile` isn't given
+pub const PROFILE_VAR: &str = "LOADSTAR_PROFILE";
+/// Comma-separated catalog ids, in place of the profile's apps
+pub const APPS_VAR: &str = "LOADSTAR_APPS";
+/// In place of the profile's `[system]` settings
+pub const LOCALE_VAR: &str = "LOADSTAR_LOCALE";
+pub const TIMEZONE_VAR: &str = "LOADSTAR_TIMEZONE";
+/// Who commits, for the generated .gitconfig
+pub const NAME_VAR: &str = "LOADSTAR_NAME";
+pub const EMAIL_VAR: &str = "LOADSTAR_EMAIL";
+
+/// Fill in what the flags left out: the profile from `LOADSTAR_PROFILE`,
+/// and `--github` when there's a token for gh to log in with
+pub fn apply_env(cli: &mut Cli, var: impl Fn(&str) -> Option<String>) {
+    let set = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+    if cli.profile.is_none() {
+        cli.profile = set(PROFILE_VAR).map(PathBuf::from);
+    }
+    if github::TOKEN_VARS.iter().any(|name| set(name).is_some()) {
+        cli.github = true;
+    }
+}
+
+/// The apps, git identity, locale and timezone. `LOADSTAR_APPS` beats
+/// the profile's apps, and with neither only the essentials for this
+/// platform install.
+pub fn seed(
+    wizard: &mut WizardState,
+    system: &SystemInfo,
+    profile: &Profile,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    let set = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+    if let Some(apps) = set(APPS_VAR) {
+        let ids: Vec<String> = apps
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect();
+        if let Some(unknown) = ids.iter().find(|id| find_app(id).is_none()) {
+            return Err(format!("Unknown app '{}' in {}", unknown, APPS_VAR));
+        }
+        wizard.selected_apps = ids.into_iter().collect();
+    } else if profile.apps.is_none() {
+        wizard.selected_apps = Query::new()
+            .tag("essential")
+            .platform(system.os)
+            .ids()
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+    }
+
+    if let Some(name) = set(NAME_VAR) {
+        wizard.identity.name = name;
+    }
+    if let Some(email) = set(EMAIL_VAR) {
+        wizard.identity.email = email;
+    }
+    if let Some(value) = set(LOCALE_VAR) {
+        locale::check_locale(&value).map_err(|e| format!("{}: {}", LOCALE_VAR, e))?;
+        wizard.locale = Some(value);
+    }
+    if let Some(value) = set(TIMEZONE_VAR) {
+        locale::check_timezone(&value).map_err(|e| format!("{}: {}", TIMEZONE_VAR, e))?;
+        wizard.timezone = Some(value);
+    }
+    Ok(())
+}
+
+/// Install and set up, then leave the next steps and the MOTD behind.
+/// The MOTD is printed too, so it's in cloud-init's output log.
+pub fn run(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    options: &InstallOptions,
+    with_github: bool,
+) -> InstallSummary {
+    let (summary, next_steps) = headless::first_boot(wizard, system, options, with_github);
+    if !next_steps.is_empty() {
+        if let Err(e) = nextsteps::save(&next_steps, system) {
+            println!("  [WARN] Could not save the next steps: {}", e);
+        }
+    }
+
+    let message = motd::render(wizard, system, &summary, &next_steps);
+    print!("{}", message);
+    // macOS shows only /etc/motd, which belongs to the admin
+    if system.os == Os::Linux {
+        match motd::write(&message) {
+            Ok(path) => println!("[MOTD] Wrote {}", path.display()),
+            Err(e) => println!("  [WARN] {}", e),
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn the_environment_fills_in_what_flags_leave_out() {
+        let env: HashMap<&str, &str> = [
+            (PROFILE_VAR, "/etc/loadstar.toml"),
+            ("GITHUB_TOKEN", "ghp_x"),
+            (APPS_VAR, "ripgrep, bat,"),
+            (TIMEZONE_VAR, "Europe/Berlin"),
+            (EMAIL_VAR, "ada@example.com"),
+        ]
+        .into_iter()
+        .collect();
+        let var = |name: &str| env.get(name).map(|v| v.to_string());
+
+        let mut cli = Cli::parse(["--cloud-init".to_string()]).unwrap();
+        apply_env(&mut cli, var);
+        assert_eq!(cli.profile, Some(PathBuf::from("/etc/loadstar.toml")));
+        assert!(cli.github);
+
+        let system = SystemInfo::detect().unwrap();
+        let mut wizard = WizardState::new();
+        seed(&mut wizard, &system, &Profile::default(), var).unwrap();
+        let mut apps: Vec<&str> = wizard.selected_apps.iter().map(String::as_str).collect();
+        apps.sort();
+        assert_eq!(apps, ["bat", "ripgrep"]);
+        assert_eq!(wizard.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(wizard.locale, None);
+        assert_eq!(wizard.identity.email, "ada@example.com");
+
+        // Nothing set: only the essentials
+        let mut wizard = WizardState::new();
+        seed(&mut wizard, &system, &Profile::default(), |_| None).unwrap();
+        assert!(!wizard.selected_apps.is_empty());
+        assert!(wizard
+            .get_selected_apps()
+            .iter()
+            .all(|app| app.tags.contains(&"essential")));
+
+        let typo = |name: &str| (name == APPS_VAR).then(|| "ripgrap".to_string());
+        let err = seed(&mut wizard, &system, &Profile::default(), typo).unwrap_err();
+        assert_eq!(err, "Unknown app 'ripgrap' in LOADSTAR_APPS");
+    }
+}
@@ -0,0 +1,87 @@
+//! Background install job
+//! `loadstar job`, started by `--detach`: reads the job from stdin and runs
+//! the whole install with the event log as its only output.
+
+use std::io;
+use std::sync::mpsc;
+
+use loadstar_core::checkpoint::Checkpoint;
+use loadstar_core::events::Recorder;
+use loadstar_core::executor::InstallMessage;
+use loadstar_core::job::{self, Job};
+use loadstar_core::lock;
+use loadstar_core::nextsteps::{self, NextStep};
+use loadstar_core::system::SystemInfo;
+
+pub fn run(system: &SystemInfo) -> anyhow::Result<()> {
+    let job = Job::from_wire(io::stdin().lock()).map_err(anyhow::Error::msg)?;
+
+    // The terminal that started this can close at any time; its hangup
+    // mustn't stop the install
+    #[cfg(unix)]
+    signal_hook::flag::register(
+        signal_hook::consts::SIGHUP,
+        std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+    )?;
+
+    // The wizard that started this checked the lock and hands it over;
+    // it has marked the job running too, but it may not have got to either
+    let _lock = lock::acquire(system, true).map_err(anyhow::Error::msg)?;
+    job::mark_running(&job.events, std::process::id())?;
+    // Saved before the first package, so even that can be resumed
+    let mut checkpoint = match &job.resume {
+        Some(previous) => previous.resumed_as(&job.wizard),
+        None => Checkpoint::new(&job.wizard),
+    };
+    let apps = checkpoint.remaining();
+    let saved = checkpoint.save(system);
+    let (out_tx, rx) = mpsc::channel();
+    let (tx, recorder) = Recorder::start_at(&job.events, out_tx);
+    if let Err(e) = saved {
+        let _ = tx.send(InstallMessage::Warning(format!(
+            "Could not save a checkpoint, so this run can't be resumed: {}",
+            e
+        )));
+    }
+    if let Some(previous) = &job.resume {
+        let _ = tx.send(InstallMessage::Log(format!(
+            "[RESUME] Picking up the interrupted install: {}",
+            previous.progress()
+        )));
+    }
+
+    // Nobody may be watching, so the checklist and the checkpoint are
+    // kept here
+    let watched = system.clone();
+    let steps = std::thread::spawn(move || {
+        let mut steps = Vec::new();
+        let mut fatal = false;
+        for message in rx {
+            if checkpoint.record(&message) {
+                let _ = checkpoint.save(&watched);
+            }
+            match message {
+                InstallMessage::NextStep { command, note } => {
+                    steps.push(NextStep { command, note })
+                }
+                InstallMessage::FatalError(_) => fatal = true,
+                _ => {}
+            }
+        }
+        (steps, fatal)
+    });
+    crate::install_everything(&job.wizard, system, apps, &job.options, &tx);
+    drop(tx);
+    recorder.finish();
+
+    let (steps, fatal) = steps.join().unwrap_or((Vec::new(), true));
+    // One that stopped on a fatal error stays resumable
+    if !fatal {
+        Checkpoint::clear(system);
+    }
+    if !steps.is_empty() {
+        let _ = nextsteps::save(&steps, system);
+    }
+    job::mark_finished(&job.events);
+    Ok(())
+}
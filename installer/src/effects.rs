@@ -8,13 +8,50 @@ use ratatui::{
     style::{Color, Modifier, Style},
     widgets::Widget,
 };
+use std::time::Duration;
 
 use crate::ascii_art::MATRIX_CHARS;
 
+/// Rain falls, the spinner turns and text types a step this often,
+/// however often the app ticks
+const RAIN_STEP: Duration = Duration::from_millis(50);
+const SPINNER_STEP: Duration = Duration::from_millis(50);
+const TYPE_STEP: Duration = Duration::from_millis(50);
+
+/// Most steps made up at once after a stall, so a long blocking redraw
+/// doesn't replay seconds of animation in one frame
+const MAX_CATCH_UP: u32 = 20;
+
+/// Turns elapsed time into whole steps, carrying the remainder over, so
+/// animations keep their speed on an uneven or adaptive tick
+#[derive(Debug, Clone, Copy)]
+struct Steps {
+    every: Duration,
+    carried: Duration,
+}
+
+impl Steps {
+    const fn every(every: Duration) -> Self {
+        Self {
+            every,
+            carried: Duration::ZERO,
+        }
+    }
+
+    /// Steps due now that `elapsed` more has passed
+    fn due(&mut self, elapsed: Duration) -> u32 {
+        let total = self.carried + elapsed;
+        let steps = (total.as_nanos() / self.every.as_nanos()) as u32;
+        self.carried = total - self.every * steps;
+        steps.min(MAX_CATCH_UP)
+    }
+}
+
 /// Matrix rain effect
 pub struct MatrixRain {
     columns: Vec<MatrixColumn>,
     height: u16,
+    steps: Steps,
 }
 
 struct MatrixColumn {
@@ -32,28 +69,42 @@ impl MatrixRain {
                 chars: (0..height)
                     .map(|_| MATRIX_CHARS[rng.gen_range(0..MATRIX_CHARS.len())])
                     .collect(),
-                head: rng.gen_range(-(height as i32)..0),
+                head: rng.gen_range(-(height as i32)..=0),
                 speed: rng.gen_range(1..4),
                 length: rng.gen_range(5..15),
             })
             .collect();
 
-        Self { columns, height }
+        Self {
+            columns,
+            height,
+            steps: Steps::every(RAIN_STEP),
+        }
+    }
+
+    /// Move the rain on by `elapsed`
+    pub fn advance(&mut self, elapsed: Duration) {
+        for _ in 0..self.steps.due(elapsed) {
+            self.step();
+        }
     }
 
-    pub fn tick(&mut self) {
+    fn step(&mut self) {
         let mut rng = rand::thread_rng();
 
         for col in &mut self.columns {
             col.head += col.speed as i32;
 
             if col.head > (self.height as i32 + col.length as i32) {
-                col.head = rng.gen_range(-(self.height as i32)..-(col.length as i32));
-                col.speed = rng.gen_range(1..4);
                 col.length = rng.gen_range(5..15);
+                // Start again above the top, a screen's height up at most;
+                // on a screen shorter than the trail, just past its end
+                let top = (self.height as i32).max(col.length as i32);
+                col.head = rng.gen_range(-top..=-(col.length as i32));
+                col.speed = rng.gen_range(1..4);
             }
 
-            if rng.gen_bool(0.1) {
+            if !col.chars.is_empty() && rng.gen_bool(0.1) {
                 let idx = rng.gen_range(0..col.chars.len());
                 col.chars[idx] = MATRIX_CHARS[rng.gen_range(0..MATRIX_CHARS.len())];
             }
@@ -63,6 +114,8 @@ impl MatrixRain {
 
 impl Widget for &MatrixRain {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        // Only the part of `area` the buffer has cells for
+        let area = area.intersection(buf.area);
         for (x, col) in self.columns.iter().enumerate() {
             if x >= area.width as usize {
                 break;
@@ -71,7 +124,10 @@ impl Widget for &MatrixRain {
             for y in 0..area.height {
                 let dist_from_head = col.head - y as i32;
 
-                if dist_from_head >= 0 && dist_from_head < col.length as i32 {
+                if dist_from_head >= 0
+                    && dist_from_head < col.length as i32
+                    && !col.chars.is_empty()
+                {
                     let char_idx = (y as usize) % col.chars.len();
                     let ch = col.chars[char_idx];
 
@@ -101,6 +157,7 @@ pub struct TypeWriter {
     pub visible_chars: usize,
     pub cursor_visible: bool,
     pub complete: bool,
+    steps: Steps,
 }
 
 impl TypeWriter {
@@ -110,10 +167,19 @@ impl TypeWriter {
             visible_chars: 0,
             cursor_visible: true,
             complete: false,
+            steps: Steps::every(TYPE_STEP),
         }
     }
 
-    pub fn tick(&mut self) {
+    /// Type on for `elapsed`: a character a step, the cursor blinking
+    /// with each
+    pub fn advance(&mut self, elapsed: Duration) {
+        for _ in 0..self.steps.due(elapsed) {
+            self.step();
+        }
+    }
+
+    fn step(&mut self) {
         if self.visible_chars < self.full_text.len() {
             self.visible_chars += 1;
         } else {
@@ -143,22 +209,144 @@ impl TypeWriter {
     }
 }
 
+/// Spinner frames, picked with `[appearance] spinner`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpinnerStyle {
+    Braille,
+    Dots,
+    Blocks,
+}
+
+impl SpinnerStyle {
+    pub fn all() -> &'static [SpinnerStyle] {
+        &[
+            SpinnerStyle::Braille,
+            SpinnerStyle::Dots,
+            SpinnerStyle::Blocks,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpinnerStyle::Braille => "braille",
+            SpinnerStyle::Dots => "dots",
+            SpinnerStyle::Blocks => "blocks",
+        }
+    }
+
+    /// The style the profile names; braille if it names none
+    pub fn from_setting(setting: Option<&str>) -> Result<Self, String> {
+        from_setting(Self::all(), Self::name, setting, "spinner")
+    }
+
+    fn frames(&self) -> &'static [&'static str] {
+        match self {
+            SpinnerStyle::Braille => crate::ascii_art::SPINNER_BRAILLE,
+            SpinnerStyle::Dots => crate::ascii_art::SPINNER_DOTS,
+            SpinnerStyle::Blocks => crate::ascii_art::SPINNER_BLOCKS,
+        }
+    }
+}
+
+/// How progress bars are drawn, picked with `[appearance] gauge`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeStyle {
+    Blocks,
+    Line,
+    Dots,
+    Ascii,
+}
+
+impl GaugeStyle {
+    pub fn all() -> &'static [GaugeStyle] {
+        &[
+            GaugeStyle::Blocks,
+            GaugeStyle::Line,
+            GaugeStyle::Dots,
+            GaugeStyle::Ascii,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GaugeStyle::Blocks => "blocks",
+            GaugeStyle::Line => "line",
+            GaugeStyle::Dots => "dots",
+            GaugeStyle::Ascii => "ascii",
+        }
+    }
+
+    /// The style the profile names; blocks if it names none
+    pub fn from_setting(setting: Option<&str>) -> Result<Self, String> {
+        from_setting(Self::all(), Self::name, setting, "gauge")
+    }
+
+    /// Glyphs for the filled and empty parts
+    fn glyphs(&self) -> (char, char) {
+        match self {
+            GaugeStyle::Blocks => ('█', '░'),
+            GaugeStyle::Line => ('━', '─'),
+            GaugeStyle::Dots => ('●', '·'),
+            GaugeStyle::Ascii => ('#', '-'),
+        }
+    }
+
+    /// A bar `width` cells wide, `fraction` of it filled
+    pub fn bar(&self, fraction: f64, width: usize) -> String {
+        let filled = ((fraction.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+        let (full, empty) = self.glyphs();
+        std::iter::repeat_n(full, filled)
+            .chain(std::iter::repeat_n(empty, width - filled))
+            .collect()
+    }
+}
+
+/// The first of `styles` when `setting` is none, else the one it names
+fn from_setting<T: Copy>(
+    styles: &[T],
+    name: fn(&T) -> &'static str,
+    setting: Option<&str>,
+    key: &str,
+) -> Result<T, String> {
+    let Some(setting) = setting else {
+        return Ok(styles[0]);
+    };
+    styles
+        .iter()
+        .copied()
+        .find(|style| name(style) == setting)
+        .ok_or_else(|| {
+            let names: Vec<&str> = styles.iter().map(name).collect();
+            format!(
+                "Unknown {} '{}' in appearance.{}; use one of {}",
+                key,
+                setting,
+                key,
+                names.join(", ")
+            )
+        })
+}
+
 /// Spinner animation
 pub struct Spinner {
     frames: &'static [&'static str],
     current: usize,
+    steps: Steps,
 }
 
 impl Spinner {
-    pub fn braille() -> Self {
+    pub fn new(style: SpinnerStyle) -> Self {
         Self {
-            frames: crate::ascii_art::SPINNER_BRAILLE,
+            frames: style.frames(),
             current: 0,
+            steps: Steps::every(SPINNER_STEP),
         }
     }
 
-    pub fn tick(&mut self) {
-        self.current = (self.current + 1) % self.frames.len();
+    /// Turn on by `elapsed`
+    pub fn advance(&mut self, elapsed: Duration) {
+        let frames = self.steps.due(elapsed) as usize;
+        self.current = (self.current + frames) % self.frames.len();
     }
 
     pub fn current(&self) -> &'static str {
@@ -329,3 +517,69 @@ impl HackerTheme {
         Style::default().bg(Theme::SURFACE0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animations_keep_time_on_an_uneven_tick() {
+        let ms = Duration::from_millis;
+        let mut steps = Steps::every(ms(50));
+        assert_eq!(steps.due(ms(30)), 0);
+        assert_eq!(steps.due(ms(30)), 1);
+        assert_eq!(steps.due(ms(140)), 3);
+        // A stall is made up only so far
+        assert_eq!(steps.due(Duration::from_secs(10)), MAX_CATCH_UP);
+
+        let mut typed = TypeWriter::new("READY.");
+        typed.advance(ms(120));
+        assert_eq!(typed.visible_text(), "RE");
+        typed.advance(ms(180));
+        assert_eq!(typed.visible_text(), "READY.");
+    }
+
+    #[test]
+    fn rain_stays_inside_the_buffer_whatever_the_area() {
+        let buf_area = Rect::new(10, 5, 20, 8);
+        for (rain, area) in [
+            ((20, 8), buf_area),
+            // A sub-area off the buffer's origin
+            ((20, 8), Rect::new(15, 7, 6, 3)),
+            // Hanging off the right and bottom edges, and wholly outside
+            ((40, 20), Rect::new(25, 10, 30, 30)),
+            ((40, 20), Rect::new(0, 0, 40, 20)),
+            ((40, 20), Rect::new(100, 100, 5, 5)),
+            ((20, 8), Rect::new(12, 6, 0, 0)),
+            // Rain sized for a smaller or empty screen than it's drawn on
+            ((3, 2), buf_area),
+            ((0, 0), buf_area),
+        ] {
+            let mut matrix = MatrixRain::new(rain.0, rain.1);
+            let mut buf = Buffer::empty(buf_area);
+            for _ in 0..40 {
+                matrix.advance(Duration::from_secs(1));
+                (&matrix).render(area, &mut buf);
+            }
+        }
+    }
+
+    #[test]
+    fn styles_come_from_the_profile_by_name() {
+        assert_eq!(SpinnerStyle::from_setting(None), Ok(SpinnerStyle::Braille));
+        assert_eq!(
+            SpinnerStyle::from_setting(Some("dots")),
+            Ok(SpinnerStyle::Dots)
+        );
+        let err = GaugeStyle::from_setting(Some("fancy")).unwrap_err();
+        assert!(
+            err.contains("appearance.gauge") && err.contains("ascii"),
+            "{}",
+            err
+        );
+
+        assert_eq!(GaugeStyle::Ascii.bar(0.42, 10), "####------");
+        assert_eq!(GaugeStyle::Line.bar(1.5, 3), "━━━");
+        assert_eq!(GaugeStyle::Blocks.bar(0.0, 2), "░░");
+    }
+}
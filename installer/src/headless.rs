@@ -0,0 +1,272 @@
+//! Headless install
+//! Runs the package install without the TUI and logs to stdout, one line
+//! per message. `loadstar test` runs this inside a container and reads
+//! the result lines back; `loadstar apply` runs a plan this way.
+
+use std::sync::mpsc;
+
+use loadstar_core::catalog::App;
+use loadstar_core::config;
+use loadstar_core::events::{self, PhaseTimes, Recorder};
+use loadstar_core::executor::{self, InstallMessage, InstallOptions, InstallSummary};
+use loadstar_core::failure::FailureKind;
+use loadstar_core::github;
+use loadstar_core::locale;
+use loadstar_core::localmodel;
+use loadstar_core::nextsteps::NextStep;
+use loadstar_core::plan::Plan;
+use loadstar_core::system::SystemInfo;
+use loadstar_core::wizard::WizardState;
+
+/// Result line prefixes, shared with the self-test parser
+pub const OK: &str = "[OK] ";
+pub const SKIP: &str = "[SKIP] ";
+pub const FAIL: &str = "[FAIL] ";
+/// Under a failure with a known cause, what to do about it
+pub const HINT: &str = "       hint: ";
+
+/// Install the wizard's apps, printing progress as it goes. With
+/// `with_github`, git and GitHub get set up afterwards too, gh logging in from
+/// a token in the environment. The profile's locale and timezone are set
+/// last. A dry run shows what it would do and records nothing.
+pub fn run(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    options: &InstallOptions,
+    with_github: bool,
+) -> InstallSummary {
+    let wizard = wizard.clone();
+    install(
+        wizard.get_apps_to_install(),
+        system,
+        options,
+        move |system, tx| {
+            if with_github {
+                github::setup_github(&wizard, system, tx);
+            }
+            locale::setup_locale(&wizard, system, tx);
+        },
+    )
+    .0
+}
+
+/// `run`, plus the config files and Ollama's model: everything the wizard
+/// sets up that needs no one at the keyboard. The next steps come back with the summary.
+pub fn first_boot(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    options: &InstallOptions,
+    with_github: bool,
+) -> (InstallSummary, Vec<NextStep>) {
+    let wizard = wizard.clone();
+    let timeout = options.package_timeout;
+    install(
+        wizard.get_apps_to_install(),
+        system,
+        options,
+        move |system, tx| {
+            if with_github {
+                github::setup_github(&wizard, system, tx);
+            }
+            config::generate_configs(&wizard, system, tx);
+            locale::setup_locale(&wizard, system, tx);
+            localmodel::setup_local_model(&wizard, system, timeout, tx);
+        },
+    )
+}
+
+/// Install `apps` (the plan's, already checked against this machine),
+/// then write its files and git keys
+pub fn apply(
+    plan: Plan,
+    apps: Vec<&'static App>,
+    system: &SystemInfo,
+    options: &InstallOptions,
+) -> InstallSummary {
+    install(apps, system, options, move |system, tx| {
+        plan.apply(system, tx)
+    })
+    .0
+}
+
+/// Install `apps`, then run `after` on the same channel
+fn install(
+    apps: Vec<&'static App>,
+    system: &SystemInfo,
+    options: &InstallOptions,
+    after: impl FnOnce(&SystemInfo, &mpsc::Sender<InstallMessage>) + Send + 'static,
+) -> (InstallSummary, Vec<NextStep>) {
+    let system = system.clone();
+    let options = options.clone();
+    let planned = apps.clone();
+
+    let (out_tx, rx) = mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        // A dry run's record would count as a real run in the history
+        // and estimates
+        let (tx, recorder) = if options.dry_run {
+            (out_tx, None)
+        } else {
+            let (tx, recorder) = Recorder::start(&system, out_tx);
+            (tx, Some(recorder))
+        };
+        let summary = executor::run_install(&system, apps, &options, &tx);
+        after(&system, &tx);
+        drop(tx);
+        if let Some(recorder) = recorder {
+            recorder.finish();
+        }
+        summary
+    });
+
+    // The channel closes when the install thread drops its sender
+    let mut times = PhaseTimes::default();
+    let mut next_steps = Vec::new();
+    let mut configs = Vec::new();
+    for message in rx {
+        if let InstallMessage::ConfigWritten { label, path } = &message {
+            configs.push((label.clone(), path.clone()));
+        }
+        times.record(&message);
+        if let InstallMessage::NextStep { command, note } = &message {
+            next_steps.push(NextStep {
+                command: command.clone(),
+                note: note.clone(),
+            });
+        }
+        if let Some(line) = format_message(&message, &planned) {
+            println!("{}", line);
+        }
+    }
+    times.finish();
+    for line in time_summary(&times) {
+        println!("{}", line);
+    }
+
+    // The install's own summary ends with the packages; `after` writes
+    // the config files
+    let mut summary = handle.join().unwrap_or_default();
+    summary.configs = configs;
+    (summary, next_steps)
+}
+
+/// One stdout line for a message, or `None` for ones that only drive the
+/// progress bar or the summary. `apps` are the ones being installed, for failure hints.
+fn format_message(message: &InstallMessage, apps: &[&App]) -> Option<String> {
+    match message {
+        InstallMessage::PhaseStart { phase } => Some(format!("=== {} ===", phase)),
+        InstallMessage::PackageStart { name, method, .. } => {
+            Some(format!("[INSTALL] {} ({})", name, method))
+        }
+        InstallMessage::PackageSuccess { name, duration_ms } => Some(format!(
+            "{}{} ({:.1}s)",
+            OK,
+            name,
+            *duration_ms as f64 / 1000.0
+        )),
+        InstallMessage::PackageSkipped { name, reason } => {
+            Some(format!("{}{}: {}", SKIP, name, reason))
+        }
+        InstallMessage::PackageFailed { name, error } => {
+            let line = format!("{}{}: {}", FAIL, name, error);
+            let method = apps
+                .iter()
+                .find(|app| app.name == name)
+                .map(|app| app.method());
+            Some(match FailureKind::classify(error).hint(method) {
+                Some(hint) => format!("{}\n{}{}", line, HINT, hint),
+                None => line,
+            })
+        }
+        InstallMessage::Log(line) => Some(line.clone()),
+        InstallMessage::Warning(warning) => Some(format!("  [WARN] {}", warning)),
+        InstallMessage::NextStep { command, note } => {
+            Some(format!("[NEXT] {}  # {}", command, note))
+        }
+        InstallMessage::GitConfigSet { key, old, new } => {
+            Some(github::describe_git_change(key, old.as_deref(), new))
+        }
+        InstallMessage::ConfigWritten { .. } | InstallMessage::Progress { .. } => None,
+        InstallMessage::Done {
+            succeeded,
+            failed,
+            skipped,
+        } => Some(format!(
+            "[DONE] {} installed, {} skipped, {} failed",
+            succeeded, skipped, failed
+        )),
+        InstallMessage::FatalError(e) => Some(format!("[FATAL] {}", e)),
+    }
+}
+
+/// One `[TIME]` line per phase, then the total
+fn time_summary(times: &PhaseTimes) -> Vec<String> {
+    let mut lines: Vec<String> = times
+        .phases
+        .iter()
+        .map(|(phase, took)| format!("[TIME] {}: {}", phase, events::format_duration(*took)))
+        .collect();
+    if let Some(total) = times.total {
+        lines.push(format!("[TIME] Total: {}", events::format_duration(total)));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_use_the_shared_prefixes() {
+        let line = format_message(
+            &InstallMessage::PackageSuccess {
+                name: "ripgrep".to_string(),
+                duration_ms: 2500,
+            },
+            &[],
+        );
+        assert_eq!(line.as_deref(), Some("[OK] ripgrep (2.5s)"));
+
+        let line = format_message(
+            &InstallMessage::PackageFailed {
+                name: "bat".to_string(),
+                error: "exit 1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        assert!(line.starts_with(FAIL));
+        assert!(!line.contains(HINT));
+
+        let line = format_message(
+            &InstallMessage::PackageFailed {
+                name: "bat".to_string(),
+                error: "curl: (6) Could not resolve host: ghcr.io".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        let (fail, hint) = line.split_once('\n').unwrap();
+        assert!(fail.starts_with(FAIL));
+        assert!(hint.starts_with(HINT));
+        assert!(format_message(
+            &InstallMessage::Progress {
+                completed: 1,
+                total: 2
+            },
+            &[]
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn time_summary_lists_phases_then_the_total() {
+        let mut times = PhaseTimes::default();
+        times.record(&InstallMessage::PhaseStart {
+            phase: "Git & GitHub Setup".to_string(),
+        });
+        times.finish();
+        let lines = time_summary(&times);
+        assert_eq!(lines, ["[TIME] Git & GitHub Setup: 0s", "[TIME] Total: 0s"]);
+    }
+}
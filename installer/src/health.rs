@@ -0,0 +1,479 @@
+//! Catalog health check
+//! `loadstar catalog check`: whether every catalog entry still resolves,
+//! so renamed formulae and dead links turn up before a user hits them.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use loadstar_core::catalog::{App, InstallMethod, CATALOG};
+use loadstar_core::github::GitRunner;
+use loadstar_core::system::Os;
+
+/// Homebrew's published indexes; they need neither brew nor a Mac
+const FORMULA_INDEX: &str = "https://formulae.brew.sh/api/formula.json";
+const CASK_INDEX: &str = "https://formulae.brew.sh/api/cask.json";
+
+/// Sent to registries and homepages; crates.io turns away anonymous clients
+const USER_AGENT: &str = "loadstar catalog check";
+
+/// Requests in flight at once while checking links
+const WORKERS: usize = 8;
+
+/// What's wrong with one thing an app points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The package isn't there under that name
+    Missing,
+    /// The package was renamed; it's installed under the new name
+    Renamed(String),
+    /// Homebrew still installs it, but is going to stop
+    Deprecated(String),
+    /// Homebrew won't install it any more
+    Disabled(String),
+    /// The link answered with an error, or not at all
+    Unreachable(String),
+}
+
+impl Issue {
+    /// Whether installing the app as listed fails, or its link is dead.
+    /// Deprecation is a warning: the install still works for now.
+    pub fn is_broken(&self) -> bool {
+        !matches!(self, Issue::Deprecated(_))
+    }
+}
+
+/// A problem with one app
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub app: &'static App,
+    /// What was checked, as in `brew exa` or `homepage`
+    pub target: String,
+    pub issue: Issue,
+}
+
+impl Finding {
+    fn line(&self) -> String {
+        let issue = match &self.issue {
+            Issue::Missing => "not found".to_string(),
+            Issue::Renamed(to) => format!("renamed to {}", to),
+            Issue::Deprecated(reason) => format!("deprecated ({})", reason),
+            Issue::Disabled(reason) => format!("disabled ({})", reason),
+            Issue::Unreachable(why) => why.clone(),
+        };
+        let mark = if self.issue.is_broken() { "✗" } else { "⚠" };
+        format!(
+            "  {} {:<20} {}: {}",
+            mark,
+            self.app.id.as_str(),
+            self.target,
+            issue
+        )
+    }
+}
+
+/// Everything the check found
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Packages and links looked at
+    pub checked: usize,
+    /// Install methods there's no registry to check against
+    pub unchecked: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Nothing is broken; deprecations alone still pass
+    pub fn passed(&self) -> bool {
+        !self.findings.iter().any(|f| f.issue.is_broken())
+    }
+
+    pub fn summary(&self) -> String {
+        let broken = self.findings.iter().filter(|f| f.issue.is_broken()).count();
+        let mut summary = format!(
+            "\n  {} apps, {} checks: {} broken, {} warnings ({} install methods not checkable)\n",
+            CATALOG.len(),
+            self.checked,
+            broken,
+            self.findings.len() - broken,
+            self.unchecked
+        );
+        for finding in &self.findings {
+            summary.push_str(&finding.line());
+            summary.push('\n');
+        }
+        summary
+    }
+}
+
+// ─── Homebrew ────────────────────────────────────────────────────────
+
+/// One formula or cask in Homebrew's index
+#[derive(Debug, Clone, Default)]
+struct Entry {
+    deprecated: Option<String>,
+    disabled: Option<String>,
+}
+
+/// Formulae or casks by name, with the other names they answer to
+#[derive(Debug, Default)]
+struct Names {
+    entries: HashMap<String, Entry>,
+    /// Old name to the current one
+    renamed: HashMap<String, String>,
+    /// Alias to the formula it installs
+    aliases: HashMap<String, String>,
+}
+
+/// Homebrew's formulae and casks
+#[derive(Debug, Default)]
+pub struct BrewIndex {
+    formulae: Names,
+    casks: Names,
+}
+
+impl BrewIndex {
+    /// Read `formula.json` and `cask.json` from formulae.brew.sh
+    pub fn parse(formulae: &str, casks: &str) -> Result<Self, String> {
+        let list = |json: &str, which: &str| -> Result<Vec<Value>, String> {
+            match serde_json::from_str(json) {
+                Ok(Value::Array(items)) => Ok(items),
+                _ => Err(format!("Homebrew's {} index isn't a JSON list", which)),
+            }
+        };
+        let mut index = Self::default();
+        for formula in list(formulae, "formula")? {
+            let Some(name) = formula["name"].as_str() else {
+                continue;
+            };
+            let names = &mut index.formulae;
+            for old in strings(&formula["oldnames"]).chain(strings(&formula["oldname"])) {
+                names.renamed.insert(old, name.to_string());
+            }
+            for alias in strings(&formula["aliases"]) {
+                names.aliases.insert(alias, name.to_string());
+            }
+            names.entries.insert(name.to_string(), entry(&formula));
+        }
+        for cask in list(casks, "cask")? {
+            let Some(token) = cask["token"].as_str() else {
+                continue;
+            };
+            let names = &mut index.casks;
+            for old in strings(&cask["old_tokens"]) {
+                names.renamed.insert(old, token.to_string());
+            }
+            names.entries.insert(token.to_string(), entry(&cask));
+        }
+        Ok(index)
+    }
+
+    /// What's wrong with installing `name`, if anything. An alias is as
+    /// good as the name; an old name counts as a rename.
+    pub fn check(&self, name: &str, cask: bool) -> Option<Issue> {
+        let names = if cask { &self.casks } else { &self.formulae };
+        let entry = match names.entries.get(name) {
+            Some(entry) => entry,
+            None => {
+                if let Some(current) = names.renamed.get(name) {
+                    return Some(Issue::Renamed(current.clone()));
+                }
+                let aliased = names.aliases.get(name).and_then(|n| names.entries.get(n));
+                let Some(entry) = aliased else {
+                    return Some(Issue::Missing);
+                };
+                entry
+            }
+        };
+        if let Some(reason) = &entry.disabled {
+            Some(Issue::Disabled(reason.clone()))
+        } else {
+            entry.deprecated.clone().map(Issue::Deprecated)
+        }
+    }
+}
+
+/// A string or list of strings in an index entry, as owned strings
+fn strings(value: &Value) -> impl Iterator<Item = String> + '_ {
+    let list = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::String(_) => vec![value],
+        _ => Vec::new(),
+    };
+    list.into_iter()
+        .filter_map(|v| v.as_str().map(str::to_string))
+}
+
+fn entry(item: &Value) -> Entry {
+    let reason = |flag: &str, reason: &str| {
+        item[flag].as_bool().unwrap_or(false).then(|| {
+            item[reason]
+                .as_str()
+                .unwrap_or("no reason given")
+                .replace('_', " ")
+        })
+    };
+    Entry {
+        deprecated: reason("deprecated", "deprecation_reason"),
+        disabled: reason("disabled", "disable_reason"),
+    }
+}
+
+// ─── Checking ────────────────────────────────────────────────────────
+
+/// Something an app points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Formula(&'static str),
+    Cask(&'static str),
+    /// A registry page that's there only if the package is
+    Package {
+        label: String,
+        url: String,
+    },
+    Homepage(&'static str),
+}
+
+/// What `app` points at on either OS, and how many of its install
+/// methods have nothing to check against (scripts, taps, apt, go)
+fn targets(app: &'static App) -> (Vec<Target>, usize) {
+    let mut targets = Vec::new();
+    let mut unchecked = 0;
+    for os in [Os::MacOS, Os::Linux] {
+        let target = match app.install_method.for_os(os) {
+            InstallMethod::Brew(name) if !name.contains('/') => Target::Formula(name),
+            InstallMethod::BrewCask(name) if !name.contains('/') => Target::Cask(name),
+            InstallMethod::Cargo(name) => Target::Package {
+                label: format!("cargo {}", name),
+                url: format!("https://crates.io/api/v1/crates/{}", name),
+            },
+            InstallMethod::Npm(name) => Target::Package {
+                label: format!("npm {}", name),
+                url: format!("https://registry.npmjs.org/{}", name.replace('/', "%2F")),
+            },
+            InstallMethod::Pip(name) => Target::Package {
+                label: format!("pypi {}", name),
+                url: format!("https://pypi.org/pypi/{}/json", name),
+            },
+            _ => {
+                unchecked += 1;
+                continue;
+            }
+        };
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    if !app.url.is_empty() {
+        targets.push(Target::Homepage(app.url));
+    }
+    (targets, unchecked)
+}
+
+/// Check every catalog entry: brew packages against Homebrew's index,
+/// cargo, npm and pip packages against their registries, and homepages
+/// by fetching them. Needs curl, not brew.
+pub fn run(runner: &(dyn GitRunner + Sync)) -> Result<Report, String> {
+    println!("[CATALOG] Fetching Homebrew's formula and cask index");
+    let index = BrewIndex::parse(
+        &download(runner, FORMULA_INDEX)?,
+        &download(runner, CASK_INDEX)?,
+    )?;
+    Ok(check(&index, runner))
+}
+
+/// `run` once the index is in hand
+pub fn check(index: &BrewIndex, runner: &(dyn GitRunner + Sync)) -> Report {
+    let mut report = Report::default();
+    let mut links = Vec::new();
+    for app in CATALOG {
+        let (targets, unchecked) = targets(app);
+        report.unchecked += unchecked;
+        report.checked += targets.len();
+        for target in targets {
+            let (target, issue) = match target {
+                Target::Formula(name) => (format!("brew {}", name), index.check(name, false)),
+                Target::Cask(name) => (format!("cask {}", name), index.check(name, true)),
+                Target::Package { label, url } => {
+                    links.push((app, label, url, true));
+                    continue;
+                }
+                Target::Homepage(url) => {
+                    links.push((app, "homepage".to_string(), url.to_string(), false));
+                    continue;
+                }
+            };
+            if let Some(issue) = issue {
+                report.findings.push(Finding { app, target, issue });
+            }
+        }
+    }
+
+    println!(
+        "[CATALOG] Fetching {} registry pages and homepages",
+        links.len()
+    );
+    let chunk = links.len().div_ceil(WORKERS).max(1);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = links
+            .chunks(chunk)
+            .map(|links| {
+                scope.spawn(move || {
+                    links
+                        .iter()
+                        .filter_map(|(app, target, url, registry)| {
+                            let issue = link_issue(status(runner, url), *registry)?;
+                            Some(Finding {
+                                app,
+                                target: target.clone(),
+                                issue,
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for worker in workers {
+            report.findings.extend(worker.join().unwrap_or_default());
+        }
+    });
+    report.findings.sort_by_key(|f| f.app.id);
+    report
+}
+
+/// What a fetch of a registry page (`registry`) or homepage says is
+/// wrong. Sites that turn scripts away (401, 403, 429) can't be judged,
+/// so they pass.
+fn link_issue(status: Result<u16, String>, registry: bool) -> Option<Issue> {
+    match status {
+        Err(e) => Some(Issue::Unreachable(e)),
+        Ok(404 | 410) if registry => Some(Issue::Missing),
+        Ok(200..=399 | 401 | 403 | 429) => None,
+        Ok(code) => Some(Issue::Unreachable(format!("answered {}", code))),
+    }
+}
+
+/// The status `url` ends at after redirects
+fn status(runner: &dyn GitRunner, url: &str) -> Result<u16, String> {
+    let args = [
+        "-sS",
+        "-L",
+        "-o",
+        "/dev/null",
+        "-w",
+        "%{http_code}",
+        "--max-time",
+        "20",
+        "-A",
+        USER_AGENT,
+        url,
+    ];
+    let out = runner.run("curl", &args)?;
+    match out.stdout.trim().parse::<u16>() {
+        Ok(code) if code != 0 => Ok(code),
+        _ => Err(out.stderr.trim().trim_start_matches("curl: ").to_string()),
+    }
+}
+
+fn download(runner: &dyn GitRunner, url: &str) -> Result<String, String> {
+    match runner.run("curl", &["-fsSL", "--compressed", "-A", USER_AGENT, url]) {
+        Ok(out) if out.success => Ok(out.stdout),
+        Ok(out) => Err(format!("Could not fetch {}: {}", url, out.stderr.trim())),
+        Err(e) => Err(format!("Could not run curl: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loadstar_core::fakerunner::{ok, FakeRunner};
+    use loadstar_core::github::CommandOutput;
+
+    #[test]
+    fn the_index_knows_renames_aliases_and_deprecations() {
+        let formulae = r#"[
+            {"name": "eza", "oldnames": [], "aliases": []},
+            {"name": "ripgrep", "aliases": ["rg"]},
+            {"name": "fd", "oldname": "fd-find"},
+            {"name": "exa", "disabled": true, "disable_reason": "unmaintained"},
+            {"name": "old", "deprecated": true, "deprecation_reason": null}
+        ]"#;
+        let casks = r#"[{"token": "ghostty", "old_tokens": ["ghostty-tip"]}]"#;
+        let index = BrewIndex::parse(formulae, casks).unwrap();
+
+        assert_eq!(index.check("eza", false), None);
+        assert_eq!(index.check("rg", false), None);
+        assert_eq!(
+            index.check("fd-find", false),
+            Some(Issue::Renamed("fd".into()))
+        );
+        assert_eq!(
+            index.check("exa", false),
+            Some(Issue::Disabled("unmaintained".into()))
+        );
+        assert_eq!(
+            index.check("old", false),
+            Some(Issue::Deprecated("no reason given".into()))
+        );
+        assert_eq!(index.check("nope", false), Some(Issue::Missing));
+        assert_eq!(
+            index.check("ghostty-tip", true),
+            Some(Issue::Renamed("ghostty".into()))
+        );
+        assert_eq!(index.check("ghostty", false), Some(Issue::Missing));
+        assert!(BrewIndex::parse("{}", "[]").is_err());
+    }
+
+    /// Answers the status check of URLs starting with each prefix with
+    /// its code; any other URL is there. `000` is curl not connecting.
+    fn statuses(codes: &[(&str, &str)]) -> FakeRunner {
+        let check = format!(
+            "curl -sS -L -o /dev/null -w %{{http_code}} --max-time 20 -A {} ",
+            USER_AGENT
+        );
+        codes
+            .iter()
+            .fold(FakeRunner::default(), |runner, (prefix, code)| {
+                let output = CommandOutput {
+                    success: *code != "000",
+                    stdout: code.to_string(),
+                    stderr: "curl: (6) Could not resolve host".to_string(),
+                };
+                runner.answer(format!("{}{}", check, prefix), Ok(output))
+            })
+            .otherwise(ok("200"))
+    }
+
+    #[test]
+    fn registries_and_homepages_are_fetched() {
+        let index = BrewIndex::parse("[]", "[]").unwrap();
+        let runner = statuses(&[
+            ("https://registry.npmjs.org/", "404"),
+            ("https://github.com/BurntSushi", "000"),
+        ]);
+        let report = check(&index, &runner);
+        let about = |id: &str| -> Vec<String> {
+            report
+                .findings
+                .iter()
+                .filter(|f| f.app.id.as_str() == id)
+                .map(|f| f.line())
+                .collect()
+        };
+
+        assert!(!report.passed());
+        let ripgrep = about("ripgrep");
+        assert!(
+            ripgrep[0].contains("brew ripgrep: not found"),
+            "{:?}",
+            ripgrep
+        );
+        assert!(ripgrep[1].contains("homepage: (6) Could not resolve host"));
+        let npm = CATALOG
+            .iter()
+            .find(|app| matches!(app.install_method, InstallMethod::Npm(_)))
+            .unwrap();
+        assert!(about(npm.id.as_str())
+            .iter()
+            .any(|line| line.contains(": not found") && line.contains("npm ")));
+    }
+}
@@ -0,0 +1,342 @@
+//! Named actions and their key bindings
+//! Screens ask "which of my actions does this key trigger?" instead of
+//! matching raw key codes, so any binding can be changed from the profile.
+
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+use loadstar_core::profile::KeyList;
+
+/// Everything a key can do somewhere in the wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Toggle,
+    CycleMode,
+    Confirm,
+    Back,
+    NextCategory,
+    PrevCategory,
+    Categories,
+    SelectAll,
+    SelectNone,
+    Bundle,
+    Details,
+    Export,
+    ExportPlan,
+    SavePreset,
+    Presets,
+    Backup,
+    Expert,
+    Palette,
+    LogFilter,
+    CollapseOutput,
+    Attract,
+    ReleaseNotes,
+    Greedy,
+    OpenLink,
+    Yes,
+    No,
+    Quit,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::Up,
+            Action::Down,
+            Action::Left,
+            Action::Right,
+            Action::Toggle,
+            Action::CycleMode,
+            Action::Confirm,
+            Action::Back,
+            Action::NextCategory,
+            Action::PrevCategory,
+            Action::Categories,
+            Action::SelectAll,
+            Action::SelectNone,
+            Action::Bundle,
+            Action::Details,
+            Action::Export,
+            Action::ExportPlan,
+            Action::SavePreset,
+            Action::Presets,
+            Action::Backup,
+            Action::Expert,
+            Action::Palette,
+            Action::LogFilter,
+            Action::CollapseOutput,
+            Action::Attract,
+            Action::ReleaseNotes,
+            Action::Greedy,
+            Action::OpenLink,
+            Action::Yes,
+            Action::No,
+            Action::Quit,
+        ]
+    }
+
+    /// Name used in the `[keys]` section of the profile
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Toggle => "toggle",
+            Action::CycleMode => "cycle_mode",
+            Action::Confirm => "confirm",
+            Action::Back => "back",
+            Action::NextCategory => "next_category",
+            Action::PrevCategory => "prev_category",
+            Action::Categories => "categories",
+            Action::SelectAll => "select_all",
+            Action::SelectNone => "select_none",
+            Action::Bundle => "bundle",
+            Action::Details => "details",
+            Action::Export => "export",
+            Action::ExportPlan => "export_plan",
+            Action::SavePreset => "save_preset",
+            Action::Presets => "presets",
+            Action::Backup => "backup",
+            Action::Expert => "expert",
+            Action::Palette => "palette",
+            Action::LogFilter => "log_filter",
+            Action::CollapseOutput => "collapse_output",
+            Action::Attract => "attract",
+            Action::ReleaseNotes => "release_notes",
+            Action::Greedy => "greedy",
+            Action::OpenLink => "open_link",
+            Action::Yes => "yes",
+            Action::No => "no",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::all().iter().copied().find(|a| a.name() == name)
+    }
+
+    fn default_keys(&self) -> &'static [KeyCode] {
+        match self {
+            Action::Up => &[KeyCode::Up, KeyCode::Char('k')],
+            Action::Down => &[KeyCode::Down, KeyCode::Char('j')],
+            Action::Left => &[KeyCode::Left, KeyCode::Char('h')],
+            Action::Right => &[KeyCode::Right, KeyCode::Char('l')],
+            Action::Toggle => &[KeyCode::Char(' ')],
+            Action::CycleMode => &[KeyCode::Char('m')],
+            Action::Confirm => &[KeyCode::Enter],
+            Action::Back => &[KeyCode::Esc],
+            Action::NextCategory => &[KeyCode::Tab],
+            Action::PrevCategory => &[KeyCode::BackTab],
+            Action::Categories => &[KeyCode::Char('c')],
+            Action::SelectAll => &[KeyCode::Char('a')],
+            Action::SelectNone => &[KeyCode::Char('n')],
+            Action::Bundle => &[KeyCode::Char('b')],
+            Action::Details => &[KeyCode::Char('d')],
+            Action::Export => &[KeyCode::Char('x')],
+            Action::ExportPlan => &[KeyCode::Char('e')],
+            Action::SavePreset => &[KeyCode::Char('s')],
+            Action::Presets => &[KeyCode::Char('p')],
+            Action::Backup => &[KeyCode::Char('u')],
+            Action::Expert => &[KeyCode::Char('e')],
+            Action::Palette => &[KeyCode::Char(':')],
+            Action::LogFilter => &[KeyCode::Char('f')],
+            Action::CollapseOutput => &[KeyCode::Char('o')],
+            Action::Attract => &[KeyCode::Char('a')],
+            Action::ReleaseNotes => &[KeyCode::Char('o')],
+            Action::Greedy => &[KeyCode::Char('g')],
+            Action::OpenLink => &[KeyCode::Char('o')],
+            Action::Yes => &[KeyCode::Char('y')],
+            Action::No => &[KeyCode::Char('n')],
+            Action::Quit => &[KeyCode::Char('q')],
+        }
+    }
+}
+
+/// Resolved key bindings for every action
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::all()
+            .iter()
+            .map(|a| (*a, a.default_keys().to_vec()))
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Build a keymap from profile overrides. Each listed action replaces
+    /// its default keys entirely; unlisted actions keep their defaults.
+    pub fn from_profile(overrides: &HashMap<String, KeyList>) -> Result<Self, String> {
+        let mut keymap = Self::default();
+
+        for (name, keys) in overrides {
+            let action = Action::from_name(name)
+                .ok_or_else(|| format!("Unknown action '{}' in [keys]", name))?;
+            let codes = keys
+                .keys()
+                .into_iter()
+                .map(|k| {
+                    parse_key(k).ok_or_else(|| format!("Unknown key '{}' for action '{}'", k, name))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            keymap.bindings.insert(action, codes);
+        }
+
+        Ok(keymap)
+    }
+
+    /// Whether `key` is bound to `action`
+    pub fn is(&self, action: Action, key: KeyCode) -> bool {
+        self.bindings
+            .get(&action)
+            .map(|keys| keys.contains(&key))
+            .unwrap_or(false)
+    }
+
+    /// The first action in `context` that `key` is bound to. Screens pass
+    /// only the actions they understand, so one key can mean different
+    /// things on different screens (`n` is "none" on DevTools, "no" on Review).
+    pub fn resolve(&self, key: KeyCode, context: &[Action]) -> Option<Action> {
+        context.iter().copied().find(|a| self.is(*a, key))
+    }
+
+    /// Short label of the primary key for an action, for footer hints
+    pub fn hint(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|keys| keys.first())
+            .map(|k| key_label(*k))
+            .unwrap_or_else(|| "?".to_string())
+    }
+}
+
+/// Parse a key name from the profile (`"k"`, `"Up"`, `"enter"`, `"space"`)
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    match name.to_ascii_lowercase().as_str() {
+        "up" => Some(KeyCode::Up),
+        "down" => Some(KeyCode::Down),
+        "left" => Some(KeyCode::Left),
+        "right" => Some(KeyCode::Right),
+        "enter" | "return" => Some(KeyCode::Enter),
+        "esc" | "escape" => Some(KeyCode::Esc),
+        "tab" => Some(KeyCode::Tab),
+        "backtab" | "shift+tab" | "s-tab" => Some(KeyCode::BackTab),
+        "space" => Some(KeyCode::Char(' ')),
+        "backspace" => Some(KeyCode::Backspace),
+        "home" => Some(KeyCode::Home),
+        "end" => Some(KeyCode::End),
+        "pageup" => Some(KeyCode::PageUp),
+        "pagedown" => Some(KeyCode::PageDown),
+        _ => None,
+    }
+}
+
+/// Display label for a key
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::BackTab => "S-tab".to_string(),
+        KeyCode::Backspace => "bksp".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pgup".to_string(),
+        KeyCode::PageDown => "pgdn".to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_include_vim_keys() {
+        let km = Keymap::default();
+        assert!(km.is(Action::Up, KeyCode::Char('k')));
+        assert!(km.is(Action::Up, KeyCode::Up));
+        assert!(km.is(Action::Down, KeyCode::Char('j')));
+    }
+
+    #[test]
+    fn resolve_respects_context() {
+        let km = Keymap::default();
+        let key = KeyCode::Char('n');
+        assert_eq!(
+            km.resolve(key, &[Action::Up, Action::SelectNone]),
+            Some(Action::SelectNone)
+        );
+        assert_eq!(
+            km.resolve(key, &[Action::Yes, Action::No]),
+            Some(Action::No)
+        );
+        assert_eq!(km.resolve(key, &[Action::Up, Action::Down]), None);
+    }
+
+    #[test]
+    fn profile_override_replaces_defaults() {
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "up".to_string(),
+            KeyList::Many(vec!["Up".to_string(), "w".to_string()]),
+        );
+        let km = Keymap::from_profile(&overrides).unwrap();
+
+        assert!(km.is(Action::Up, KeyCode::Char('w')));
+        assert!(km.is(Action::Up, KeyCode::Up));
+        assert!(!km.is(Action::Up, KeyCode::Char('k')));
+        // Untouched actions keep their defaults
+        assert!(km.is(Action::Down, KeyCode::Char('j')));
+    }
+
+    #[test]
+    fn unknown_action_or_key_is_rejected() {
+        let mut overrides = HashMap::new();
+        overrides.insert("jump".to_string(), KeyList::One("x".to_string()));
+        assert!(Keymap::from_profile(&overrides).is_err());
+
+        let mut overrides = HashMap::new();
+        overrides.insert("up".to_string(), KeyList::One("hyper".to_string()));
+        assert!(Keymap::from_profile(&overrides).is_err());
+    }
+
+    #[test]
+    fn parse_key_names() {
+        assert_eq!(parse_key("k"), Some(KeyCode::Char('k')));
+        assert_eq!(parse_key("Enter"), Some(KeyCode::Enter));
+        assert_eq!(parse_key("space"), Some(KeyCode::Char(' ')));
+        assert_eq!(parse_key("shift+tab"), Some(KeyCode::BackTab));
+        assert_eq!(parse_key("nope"), None);
+    }
+
+    #[test]
+    fn hints_use_primary_key() {
+        let km = Keymap::default();
+        assert_eq!(km.hint(Action::Up), "↑");
+        assert_eq!(km.hint(Action::Toggle), "space");
+        assert_eq!(km.hint(Action::NextCategory), "tab");
+    }
+}
@@ -0,0 +1,201 @@
+//! Install log view
+//! Which log lines the Install screen shows: everything, warnings and up,
+//! or errors only, with command output optionally folded away.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+
+/// One line of the install log, with how far into the run it arrived
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub elapsed: Duration,
+    pub text: String,
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", stamp(self.elapsed), self.text)
+    }
+}
+
+/// Run time as mm:ss, for the start of a log line
+pub fn stamp(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// How much a log line matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Passed-through stdout and stderr of the commands being run
+    Output,
+    Info,
+    Warning,
+    Error,
+    /// Phase banners, kept at every filter so lines keep their context
+    Heading,
+}
+
+impl LogLevel {
+    /// Tags count after leading whitespace too: `  [NEXT] ...` is
+    /// loadstar's, not a tool's
+    pub fn of(line: &str) -> LogLevel {
+        let tagged = line.trim_start();
+        if tagged.starts_with("[PHASE]") {
+            LogLevel::Heading
+        } else if tagged.starts_with("[FAIL]")
+            || tagged.starts_with("[FATAL]")
+            || line.contains("[ERROR]")
+        {
+            LogLevel::Error
+        } else if line.contains("[WARN]") || tagged.starts_with("[ABORT]") {
+            LogLevel::Warning
+        } else if line.starts_with("  ") && !has_tag(tagged) {
+            LogLevel::Output
+        } else {
+            LogLevel::Info
+        }
+    }
+}
+
+/// Whether a line opens with a tag such as `[NEXT]`
+fn has_tag(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .is_some_and(|(tag, _)| !tag.is_empty() && tag.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+/// The least a line has to matter to be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFilter {
+    #[default]
+    All,
+    Warnings,
+    Errors,
+}
+
+impl LogFilter {
+    pub fn next(&self) -> LogFilter {
+        match self {
+            LogFilter::All => LogFilter::Warnings,
+            LogFilter::Warnings => LogFilter::Errors,
+            LogFilter::Errors => LogFilter::All,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogFilter::All => "all",
+            LogFilter::Warnings => "warnings+",
+            LogFilter::Errors => "errors",
+        }
+    }
+
+    fn shows(&self, level: LogLevel) -> bool {
+        match self {
+            LogFilter::All => true,
+            LogFilter::Warnings => level >= LogLevel::Warning,
+            LogFilter::Errors => level >= LogLevel::Error,
+        }
+    }
+}
+
+/// A line to draw: when it arrived, and its text
+pub type Shown<'a> = (Duration, Cow<'a, str>);
+
+/// The lines to draw, in order. With `collapse_output`, each run of
+/// command output becomes one line saying how many were folded, stamped
+/// with when the run started.
+pub fn visible(log: &[LogLine], filter: LogFilter, collapse_output: bool) -> Vec<Shown<'_>> {
+    let mut lines = Vec::new();
+    let mut folded = None;
+    for line in log {
+        let level = LogLevel::of(&line.text);
+        if !filter.shows(level) {
+            continue;
+        }
+        if collapse_output && level == LogLevel::Output {
+            let (_, count) = folded.get_or_insert((line.elapsed, 0));
+            *count += 1;
+            continue;
+        }
+        push_folded(&mut lines, &mut folded);
+        lines.push((line.elapsed, Cow::Borrowed(line.text.as_str())));
+    }
+    push_folded(&mut lines, &mut folded);
+    lines
+}
+
+fn push_folded(lines: &mut Vec<Shown<'_>>, folded: &mut Option<(Duration, usize)>) {
+    match folded.take() {
+        None => {}
+        Some((elapsed, 1)) => lines.push((elapsed, Cow::Borrowed("  … 1 line of output"))),
+        Some((elapsed, n)) => {
+            lines.push((elapsed, Cow::Owned(format!("  … {} lines of output", n))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> Vec<LogLine> {
+        [
+            "[PHASE] ═══ Homebrew Formulae ═══",
+            "[RUN] brew install ripgrep bat",
+            "  ==> Fetching ripgrep",
+            "  ==> Pouring ripgrep.bottle.tar.gz",
+            "[OK] ripgrep (2.5s)",
+            "  [WARN] bat: caveats printed",
+            "[FAIL] fzff: No available formula",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, text)| LogLine {
+            elapsed: Duration::from_secs(i as u64 * 30),
+            text: text.to_string(),
+        })
+        .collect()
+    }
+
+    fn texts<'a>(lines: &'a [Shown<'_>]) -> Vec<&'a str> {
+        lines.iter().map(|(_, text)| text.as_ref()).collect()
+    }
+
+    #[test]
+    fn filters_keep_phase_headings() {
+        let log = log();
+        let errors = visible(&log, LogFilter::Errors, false);
+        assert_eq!(
+            texts(&errors),
+            [
+                "[PHASE] ═══ Homebrew Formulae ═══",
+                "[FAIL] fzff: No available formula"
+            ]
+        );
+        assert_eq!(visible(&log, LogFilter::Warnings, false).len(), 3);
+        assert_eq!(visible(&log, LogFilter::All, false).len(), 7);
+    }
+
+    #[test]
+    fn collapsing_folds_runs_of_output() {
+        let log = log();
+        let lines = visible(&log, LogFilter::All, true);
+        assert_eq!(
+            lines[2],
+            (Duration::from_secs(60), "  … 2 lines of output".into())
+        );
+        // Indented warnings are not output
+        assert_eq!(
+            texts(&lines)[3..5],
+            ["[OK] ripgrep (2.5s)", "  [WARN] bat: caveats printed"]
+        );
+        assert_eq!(LogLevel::of("  [NEXT] gh auth login"), LogLevel::Info);
+        assert_eq!(LogLevel::of("  [FAIL] nested"), LogLevel::Error);
+        assert_eq!(LogLevel::of("  [1/3] Resolving packages"), LogLevel::Output);
+        assert_eq!(LogFilter::Errors.next(), LogFilter::All);
+        assert_eq!(log[4].to_string(), "[02:00] [OK] ripgrep (2.5s)");
+    }
+}
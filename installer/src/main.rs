@@ -7,33 +7,58 @@ Your terminal will never be the same.
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
-    io::{self, Stdout},
+    collections::HashMap,
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+    process::Stdio,
     time::{Duration, Instant},
 };
 
 mod ascii_art;
-mod catalog;
-mod config;
+mod bench;
+mod cli;
+mod cloudinit;
+mod detach;
 mod effects;
-mod executor;
-mod github;
+mod headless;
+mod health;
+mod keymap;
+mod logview;
+mod narrate;
 mod render;
-mod system;
-mod wizard;
+mod selftest;
 
-use effects::{MatrixRain, Spinner, TypeWriter};
-use executor::InstallMessage;
+use loadstar_core::{
+    ai, aliascheck, atuin, backup, catalog, checkpoint, cloud, config, coverage, crashreport,
+    custom, devcontainer, estimate, events, executor, github, github_api, hookcheck, hub, job,
+    kube, locale, localmodel, lock, nextsteps, onboarding, pathcheck, plan, preset, profile,
+    report, scan, sealed, secret, stats, system, theme, wizard,
+};
+
+use ascii_art::Banner;
+use checkpoint::Checkpoint;
+use cli::{Cli, Subcommand};
+use effects::{GaugeStyle, MatrixRain, Spinner, SpinnerStyle, TypeWriter};
+use executor::{InstallMessage, InstallOptions, InstallSummary};
+use keymap::{Action, Keymap};
+use logview::{LogFilter, LogLine};
+use profile::Profile;
 use render::render_app;
-use system::SystemInfo;
-use wizard::{WizardPhase, WizardState};
+use secret::{Secret, TextField};
+use system::{Os, SystemInfo};
+use wizard::{SshKeyChoice, WizardPhase, WizardState};
 
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 
 /// Main application state
 pub struct App {
@@ -41,26 +66,253 @@ pub struct App {
     pub system: SystemInfo,
     pub matrix_rain: MatrixRain,
     pub spinner: Spinner,
+    /// How progress bars are drawn
+    pub gauge: GaugeStyle,
     pub typewriter: Option<TypeWriter>,
     pub boot_sequence: BootSequence,
+    /// Title card for the boot and Complete screens
+    pub banner: &'static Banner,
     pub last_tick: Instant,
     pub tick_rate: Duration,
     pub should_quit: bool,
-    pub install_log: Vec<String>,
+    pub install_log: Vec<LogLine>,
+    /// When the Install screen went to attract mode, while it's there
+    pub attract: Option<Instant>,
+    /// Idle time before it does; `None` never
+    pub attract_after: Option<Duration>,
+    pub last_key: Instant,
+    /// Per-phase timing of the current or last install
+    pub phase_times: events::PhaseTimes,
+    /// Time on each wizard screen
+    pub screen_times: events::PhaseTimes,
+    /// This session's numbers; the install's are filled in when it ends
+    pub session: stats::SessionStats,
+    /// First-run guesses for packages of this install with no past time,
+    /// checked off as they finish
+    pub guessed: HashMap<String, Duration>,
     pub install_progress: f64,
     pub install_total: usize,
     pub install_completed: usize,
-    pub install_succeeded: usize,
-    pub install_failed: usize,
-    pub install_skipped: usize,
-    pub failed_packages: Vec<(String, String)>,  // (name, error)
-    pub skipped_packages: Vec<(String, String)>, // (name, reason)
+    /// Expected seconds per package still to finish, by name. When set,
+    /// the progress bar moves by expected time instead of package count.
+    pub work_left: HashMap<String, f64>,
+    /// Expected seconds of the packages finished so far, and of all of them
+    pub work_done: f64,
+    pub work_total: f64,
+    /// Package results, counted from the same messages as the executor's
+    pub summary: InstallSummary,
+    pub next_steps: Vec<nextsteps::NextStep>,
     pub current_package: Option<String>,
     pub package_started_at: Option<Instant>,
+    /// When the running package is stopped: a batch gets all its
+    /// packages' time
+    pub package_limit: Option<Duration>,
     pub is_installing: bool,
     pub install_receiver: Option<mpsc::Receiver<InstallMessage>>,
     pub install_thread: Option<std::thread::JoinHandle<()>>,
+    /// Install in a background process instead of a thread (`--detach`)
+    pub detach: bool,
+    /// The background install the Install screen is following
+    pub following: Option<job::Running>,
     pub error_message: Option<String>,
+    /// Diagnostic report written when the install died
+    pub crash_report: Option<PathBuf>,
+    /// The user's own aliases that loadstar's would shadow, looked for
+    /// each time the dotfile preview opens
+    pub alias_conflicts: Vec<aliascheck::Conflict>,
+    /// Outcome of the last devcontainer export from the Review screen
+    pub export_status: Option<Result<String, String>>,
+    /// Where the last plan export from the Review screen went
+    pub plan_status: Option<Result<PathBuf, String>>,
+    /// Where the last Markdown summary from the Complete screen went
+    pub summary_status: Option<Result<PathBuf, String>>,
+    /// brew's installed versions, on their way for the summary export
+    pub versions_receiver: Option<mpsc::Receiver<HashMap<String, String>>>,
+    /// Pre-flight check results, once the background checks finish
+    pub preflight: Option<system::PreflightResult>,
+    pub preflight_receiver: Option<mpsc::Receiver<system::PreflightResult>>,
+    /// Name being typed for a new preset on Review
+    pub preset_name: Option<String>,
+    /// Outcome of the last backup to GitHub
+    pub backup_status: Option<Result<String, String>>,
+    pub backup_receiver: Option<mpsc::Receiver<Result<String, String>>>,
+    /// The profile file this run loaded, or would have
+    pub profile_path: PathBuf,
+    pub backup_repo: String,
+    /// Outcome of the last preset save
+    pub preset_status: Option<Result<String, String>>,
+    /// Saved presets, while the DevTools picker is open
+    pub preset_picker: Option<PresetPicker>,
+    /// DevTools and Apps also take packages outside the catalog
+    pub expert: bool,
+    /// Package being typed while the expert palette is open
+    pub palette: Option<String>,
+    /// Outcome of the last palette entry
+    pub palette_status: Option<Result<String, String>>,
+    pub palette_receiver: Option<mpsc::Receiver<Result<(custom::CustomPackage, String), String>>>,
+    /// What brew suggests for the palette entry, and the one picked
+    pub suggestions: Vec<custom::Suggestion>,
+    pub suggestion_cursor: Option<usize>,
+    /// When the palette entry last changed, until brew is searched for it
+    pub palette_typed_at: Option<Instant>,
+    pub suggest_receiver: Option<mpsc::Receiver<(String, Vec<custom::Suggestion>)>>,
+    /// Check the GitHub username against the API on the way to the GitHub
+    /// screen; headless runs have no screen to show it on
+    pub look_up_github: bool,
+    /// The variable holding a GitHub token, until the GitHub screen has
+    /// asked whether gh may store it
+    pub env_token: Option<&'static str>,
+    /// The username last looked up, and what GitHub said about it
+    pub github_user: Option<GithubUser>,
+    pub github_user_receiver: Option<mpsc::Receiver<GithubUser>>,
+    /// What the environment scan found, and the defaults it seeded
+    pub hints: Vec<scan::Hint>,
+    /// Apps this machine has no way to install, flagged in the lists
+    pub gaps: Vec<coverage::Gap>,
+    /// Cursor in the Apps sidebar while picking a category to jump to
+    pub category_jump: Option<usize>,
+    /// Key pairs already in ~/.ssh, for the GitHub screen
+    pub ssh_keys: Vec<github::SshKey>,
+    /// Cursor in the SSH key picker while it's open; one past the last
+    /// key is "generate a new key"
+    pub ssh_key_picker: Option<usize>,
+    /// sudo password being typed before an install that needs it
+    pub sudo_prompt: Option<Secret>,
+    /// Open confirmation, if any
+    pub modal: Option<Modal>,
+    /// Least important install log lines still shown
+    pub log_filter: LogFilter,
+    /// Fold runs of command output in the install log into one line
+    pub collapse_output: bool,
+    /// The profile lists apps, so the hub has something to sync
+    pub saved_apps: bool,
+    /// Left by an install that didn't finish, for the hub to offer
+    pub resumable: Option<Checkpoint>,
+    /// Picked from the hub: the next install skips what this finished
+    pub resuming: Option<Checkpoint>,
+    /// The running install's progress, saved after every package
+    pub checkpoint: Option<Checkpoint>,
+    /// Result of the hub's last audit, once brew has answered
+    pub audit: Option<hub::AuditReport>,
+    pub audit_receiver: Option<mpsc::Receiver<hub::AuditReport>>,
+    /// What the hub's upgrade check found Homebrew has newer versions of
+    pub upgrades: Option<Result<Vec<hub::Outdated>, String>>,
+    pub upgrades_receiver: Option<mpsc::Receiver<Result<Vec<hub::Outdated>, String>>>,
+    /// Runs brew for the hub's upgrade check
+    pub runner: Arc<dyn github::GitRunner + Send + Sync>,
+    /// Which release notes were opened last, or where to find them
+    pub upgrade_notice: Option<String>,
+    /// The profile's `[upgrade]` section, saved back as it's changed
+    pub upgrade_prefs: profile::UpgradeSection,
+    /// The org's checklist from `--onboarding`, ticked off after the install
+    pub onboarding: Option<onboarding::Checklist>,
+    /// Which checklist link was opened last, or where to find it
+    pub onboarding_notice: Option<String>,
+    /// Where the onboarding report went
+    pub onboarding_report: Option<Result<PathBuf, String>>,
+    pub keymap: Keymap,
+    pub install_options: InstallOptions,
+    /// Set by Ctrl+Z or SIGTSTP; the main loop suspends when it sees it
+    pub suspend_signal: Arc<AtomicBool>,
+    /// Held from the start of an install until it finishes, so another
+    /// run can't install at the same time
+    pub run_lock: Option<lock::RunLock>,
+    /// `--force`: take the lock from a run that holds it
+    pub force: bool,
+    /// Announces steps and results for screen readers (`--narrate`)
+    pub narrator: Option<narrate::Narrator>,
+}
+
+/// A username and what GitHub said about it
+pub type GithubUser = (String, Result<Option<github_api::User>, String>);
+
+/// Everything that can change the app: input, time passing, and news
+/// from the install thread. All of it goes through `App::reduce`.
+#[derive(Debug)]
+pub enum AppEvent {
+    Key(KeyCode, KeyModifiers),
+    Resize(u16, u16),
+    Tick,
+    Install(InstallMessage),
+    /// The install thread ended; `Err` carries its panic message
+    InstallFinished(Result<(), String>),
+    /// The pre-flight checks finished
+    Preflight(system::PreflightResult),
+    /// The backup to GitHub finished
+    Backup(Result<String, String>),
+    /// A package typed into the palette was checked; `Ok` queues it
+    Palette(Result<(custom::CustomPackage, String), String>),
+    /// brew's suggestions for a palette entry
+    Suggestions(String, Vec<custom::Suggestion>),
+    /// GitHub answered for a username
+    GithubUser(GithubUser),
+    /// The hub's audit finished
+    Audit(hub::AuditReport),
+    /// brew said what's outdated
+    Upgrades(Result<Vec<hub::Outdated>, String>),
+    /// brew listed its versions; the summary can be exported
+    Versions(HashMap<String, String>),
+    /// Bracketed paste; kept as a `Secret` since it's as often a key as
+    /// anything else
+    Paste(Secret),
+}
+
+/// What a confirmation modal does when the user says yes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModalAction {
+    /// Quit, dropping the wizard's answers
+    Quit,
+    /// Stop following a running install and skip to the summary
+    AbortInstall,
+    /// Go ahead with an install that replaces existing config files
+    Install,
+    /// Store `$GH_TOKEN` as gh's login; saying no goes on without
+    UseEnvToken,
+}
+
+/// A yes/no question drawn over the current screen. While one is open it
+/// takes every key.
+pub struct Modal {
+    pub title: &'static str,
+    pub message: String,
+    /// What saying yes is called in the footer, e.g. "quit"
+    pub confirm: &'static str,
+    pub action: ModalAction,
+}
+
+/// The screen for an install another run is in the way of. Saying yes
+/// quits; saying no keeps the answers so far, to install once it's done.
+fn busy_modal(message: String) -> Modal {
+    Modal {
+        title: "RUN IN PROGRESS",
+        message: format!(
+            "{}. Wait for it to finish, or `loadstar attach` to watch it if it's in the \
+             background. --force installs anyway.",
+            message
+        ),
+        confirm: "quit",
+        action: ModalAction::Quit,
+    }
+}
+
+/// How long typing in the expert palette pauses before brew is searched
+const SUGGEST_DELAY: Duration = Duration::from_millis(300);
+
+/// Seconds without a key before a running install goes to attract mode,
+/// unless the profile says otherwise
+const ATTRACT_AFTER: u64 = 90;
+
+/// Where `--profile gh:...` and `--onboarding` keep the profile they fetch,
+/// under the state directory
+const PULLED_PROFILE: &str = "pulled-profile.toml";
+const ONBOARDING_PROFILE: &str = "onboarding-profile.toml";
+
+/// The DevTools preset picker
+pub struct PresetPicker {
+    pub presets: Vec<preset::Preset>,
+    pub cursor: usize,
+    /// Replace the selection instead of adding to it
+    pub replace: bool,
 }
 
 /// Boot sequence state
@@ -71,22 +323,9 @@ pub struct BootSequence {
     pub complete: bool,
 }
 
-impl Default for BootSequence {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl BootSequence {
-    pub fn new() -> Self {
-        let messages = vec![
-            ("BIOS CHECK... ", false),
-            ("NEURAL INTERFACE ONLINE... ", false),
-            ("SCANNING REALITY MATRIX... ", false),
-            ("QUANTUM ENTANGLEMENT STABLE... ", false),
-            ("CONSCIOUSNESS UPLOAD READY... ", false),
-            ("READY.", false),
-        ];
+    pub fn new(lines: &'static [&'static str]) -> Self {
+        let messages: Vec<_> = lines.iter().map(|line| (*line, false)).collect();
 
         let current_typewriter = Some(TypeWriter::new(messages[0].0));
 
@@ -98,13 +337,14 @@ impl BootSequence {
         }
     }
 
-    pub fn tick(&mut self) {
+    /// Type on for `elapsed`, starting the next line when one finishes
+    pub fn advance(&mut self, elapsed: Duration) {
         if self.complete {
             return;
         }
 
         if let Some(tw) = &mut self.current_typewriter {
-            tw.tick();
+            tw.advance(elapsed);
 
             if tw.complete {
                 self.messages[self.stage].1 = true;
@@ -132,81 +372,606 @@ impl BootSequence {
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let system = SystemInfo::detect()?;
+    pub fn new(system: SystemInfo, profile: &Profile, cli: &Cli) -> Result<Self> {
+        let keymap = Keymap::from_profile(&profile.keys).map_err(anyhow::Error::msg)?;
+        // A misspelt banner isn't worth refusing to start over
+        let banner = Banner::pick(profile.appearance.banner.as_deref()).unwrap_or_else(|e| {
+            eprintln!("[WARN] {}; picking one at random", e);
+            Banner::random()
+        });
+        let spinner = SpinnerStyle::from_setting(profile.appearance.spinner.as_deref())
+            .map_err(anyhow::Error::msg)?;
+        let gauge = GaugeStyle::from_setting(profile.appearance.gauge.as_deref())
+            .map_err(anyhow::Error::msg)?;
+        let install_options = InstallOptions {
+            brew_update: cli
+                .brew_update
+                .or(profile.install.brew_update)
+                .unwrap_or_default(),
+            brew_cleanup: cli.brew_cleanup || profile.install.brew_cleanup.unwrap_or(false),
+            brew_batch: cli.brew_batch || profile.install.brew_batch.unwrap_or(false),
+            native_packages: profile.install.native_packages.unwrap_or(false),
+            network_retries: profile
+                .install
+                .network_retries
+                .unwrap_or(executor::NETWORK_RETRIES),
+            package_timeout: Some(
+                profile
+                    .install
+                    .package_timeout
+                    .unwrap_or(executor::PACKAGE_TIMEOUT),
+            )
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs),
+            sudo_password: None,
+            dry_run: cli.dry_run,
+        };
+
+        // Only the wizard offers to resume, and a dry run has nothing to
+        let resumable = if cli.headless || cli.command.is_some() || cli.dry_run {
+            None
+        } else {
+            Checkpoint::load(&system)
+        };
+
+        let mut wizard = WizardState::new();
+        wizard.dry_run = cli.dry_run;
+        // Onboarding is a first setup, even though fetching its profile
+        // made the state directory
+        wizard.returning =
+            (hub::is_returning(&system) || resumable.is_some()) && cli.onboarding.is_none();
+        let checklist = cli
+            .onboarding
+            .as_deref()
+            .and_then(|url| onboarding::Checklist::new(url, &profile.onboarding));
+        wizard.onboarding = checklist.is_some();
+        wizard.categories = profile.categories.screens();
+        // Headless runs install what the profile says, and `apply` what
+        // the plan says, whatever the machine
+        let (hints, gaps) = if cli.headless || cli.command == Some(Subcommand::Apply) {
+            (Vec::new(), Vec::new())
+        } else {
+            (scan::scan(&system), coverage::check(&system))
+        };
+        scan::apply(&hints, &mut wizard);
+        if let Some(apps) = &profile.apps {
+            wizard.selected_apps = apps.iter().cloned().collect();
+        }
+        wizard.locale = profile.system.locale.clone();
+        wizard.timezone = profile.system.timezone.clone();
+        wizard.ai.local_model = profile.ai.local_model.clone().unwrap_or_default();
+
+        let ssh_keys = github::find_ssh_keys(&system);
+        let narrator = match &cli.narrate_file {
+            Some(path) => Some(narrate::Narrator::file(path).map_err(|e| {
+                anyhow::anyhow!("Could not open {} to narrate to: {}", path.display(), e)
+            })?),
+            None if cli.narrate => Some(narrate::Narrator::stdout(io::stdout().is_terminal())),
+            None => None,
+        };
+        let profile_path = cli
+            .profile
+            .clone()
+            .unwrap_or_else(|| Profile::default_path(&system));
+        let mut screen_times = events::PhaseTimes::default();
+        screen_times.enter(wizard.phase.name());
 
         Ok(Self {
-            wizard: WizardState::new(),
+            wizard,
             system,
             matrix_rain: MatrixRain::new(120, 40),
-            spinner: Spinner::braille(),
+            spinner: Spinner::new(spinner),
+            gauge,
             typewriter: None,
-            boot_sequence: BootSequence::new(),
+            boot_sequence: BootSequence::new(banner.boot),
+            banner,
             last_tick: Instant::now(),
             tick_rate: Duration::from_millis(50),
             should_quit: false,
             install_log: Vec::new(),
+            attract: None,
+            attract_after: match profile.appearance.attract_after.unwrap_or(ATTRACT_AFTER) {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            last_key: Instant::now(),
+            phase_times: events::PhaseTimes::default(),
+            screen_times,
+            session: stats::SessionStats::default(),
+            guessed: HashMap::new(),
             install_progress: 0.0,
             install_total: 0,
             install_completed: 0,
-            install_succeeded: 0,
-            install_failed: 0,
-            install_skipped: 0,
-            failed_packages: Vec::new(),
-            skipped_packages: Vec::new(),
+            work_left: HashMap::new(),
+            work_done: 0.0,
+            work_total: 0.0,
+            summary: InstallSummary::default(),
+            next_steps: Vec::new(),
             current_package: None,
             package_started_at: None,
+            package_limit: None,
             is_installing: false,
             install_receiver: None,
             install_thread: None,
+            detach: cli.detach,
+            following: None,
             error_message: None,
+            crash_report: None,
+            alias_conflicts: Vec::new(),
+            export_status: None,
+            plan_status: None,
+            summary_status: None,
+            versions_receiver: None,
+            preflight: None,
+            preflight_receiver: None,
+            backup_status: None,
+            backup_receiver: None,
+            profile_path,
+            backup_repo: profile
+                .backup
+                .repo
+                .clone()
+                .unwrap_or_else(|| backup::DEFAULT_REPO.to_string()),
+            preset_name: None,
+            preset_status: None,
+            preset_picker: None,
+            expert: false,
+            palette: None,
+            palette_status: None,
+            palette_receiver: None,
+            suggestions: Vec::new(),
+            suggestion_cursor: None,
+            palette_typed_at: None,
+            suggest_receiver: None,
+            look_up_github: !cli.headless,
+            env_token: github::token_from_env()
+                .map(|(var, _)| var)
+                .filter(|_| !cli.headless),
+            github_user: None,
+            github_user_receiver: None,
+            hints,
+            gaps,
+            category_jump: None,
+            ssh_keys,
+            ssh_key_picker: None,
+            sudo_prompt: None,
+            modal: None,
+            log_filter: LogFilter::default(),
+            collapse_output: false,
+            saved_apps: profile.apps.is_some(),
+            resumable,
+            resuming: None,
+            checkpoint: None,
+            audit: None,
+            audit_receiver: None,
+            upgrades: None,
+            upgrades_receiver: None,
+            runner: Arc::new(github::SystemRunner),
+            upgrade_notice: None,
+            upgrade_prefs: profile.upgrade.clone(),
+            onboarding: checklist,
+            onboarding_notice: None,
+            onboarding_report: None,
+            keymap,
+            install_options,
+            suspend_signal: Arc::new(AtomicBool::new(false)),
+            run_lock: None,
+            force: cli.force,
+            narrator,
         })
     }
 
-    pub fn tick(&mut self) {
-        self.matrix_rain.tick();
-        self.spinner.tick();
+    /// The one place state changes. The main loop only turns terminal
+    /// events and channel reads into `AppEvent`s.
+    pub fn reduce(&mut self, event: AppEvent) {
+        let phase = self.wizard.phase;
+        match event {
+            AppEvent::Key(key, modifiers) => {
+                self.session.keypresses += 1;
+                self.handle_key(key, modifiers);
+                // A phase comes back where it was left; its lists may have
+                // changed since
+                if self.wizard.phase != phase {
+                    if self.wizard.phase == WizardPhase::ConfigPreview {
+                        self.find_alias_conflicts();
+                    }
+                    self.clamp_cursors();
+                    if self.wizard.phase == WizardPhase::GitHub {
+                        self.start_github_lookup();
+                    }
+                }
+            }
+            AppEvent::Resize(width, height) => self.resize(width, height),
+            AppEvent::Tick => self.tick(),
+            AppEvent::Install(message) => self.apply_install_message(message),
+            AppEvent::InstallFinished(result) => self.finish_install(result),
+            AppEvent::Preflight(result) => {
+                let count = |status| result.checks.iter().filter(|c| c.status == status).count();
+                self.narrate(format!(
+                    "Pre-flight checks: {} passed, {} warning(s), {} failed",
+                    count(system::CheckStatus::Pass),
+                    count(system::CheckStatus::Warn),
+                    count(system::CheckStatus::Fail)
+                ));
+                self.preflight = Some(result);
+                self.preflight_receiver = None;
+            }
+            AppEvent::Backup(result) => {
+                self.narrate(match &result {
+                    Ok(status) => status.clone(),
+                    Err(e) => format!("Backup failed: {}", e),
+                });
+                self.backup_status = Some(result);
+                self.backup_receiver = None;
+            }
+            AppEvent::Palette(result) => {
+                self.narrate(match &result {
+                    Ok((_, note)) => note.clone(),
+                    Err(e) => e.clone(),
+                });
+                self.palette_status = Some(result.map(|(package, note)| {
+                    if !self.wizard.custom_packages.contains(&package) {
+                        self.wizard.custom_packages.push(package);
+                    }
+                    note
+                }));
+                self.palette_receiver = None;
+            }
+            AppEvent::Suggestions(entry, found) => {
+                // Typing went on while brew searched; the next search is due
+                if self.palette.as_ref() == Some(&entry) {
+                    self.suggestions = found;
+                    self.suggestion_cursor = None;
+                }
+                self.suggest_receiver = None;
+            }
+            AppEvent::GithubUser(answer) => {
+                match &answer {
+                    (_, Ok(Some(user))) => {
+                        self.narrate(format!("GitHub user {} found", user.login))
+                    }
+                    (login, Ok(None)) => {
+                        self.narrate(format!("GitHub has no user called {}", login))
+                    }
+                    _ => {}
+                }
+                self.github_user = Some(answer);
+                self.github_user_receiver = None;
+            }
+            AppEvent::Audit(report) => {
+                self.narrate(format!(
+                    "Audit: {} installed, {} missing",
+                    report.installed.len(),
+                    report.missing.len()
+                ));
+                self.audit = Some(report);
+                self.audit_receiver = None;
+            }
+            AppEvent::Versions(versions) => {
+                self.versions_receiver = None;
+                let markdown = report::render(
+                    &self.wizard,
+                    &self.system,
+                    &self.summary,
+                    &self.next_steps,
+                    &versions,
+                );
+                self.summary_status = Some(
+                    std::env::current_dir()
+                        .map_err(|e| e.to_string())
+                        .and_then(|dir| report::write(&markdown, &dir)),
+                );
+            }
+            AppEvent::Upgrades(outdated) => {
+                if let Ok(outdated) = &outdated {
+                    self.narrate(format!("{} upgrades available", outdated.len()));
+                }
+                self.upgrades = Some(outdated);
+                self.upgrades_receiver = None;
+            }
+            AppEvent::Paste(text) => self.handle_paste(text.expose()),
+        }
+        if self.wizard.phase != phase {
+            self.screen_times.enter(self.wizard.phase.name());
+            self.narrate(narrate::phase(self.wizard.phase));
+        }
+    }
+
+    /// Announce `line` when narrating
+    fn narrate(&mut self, line: impl Into<String>) {
+        if let Some(narrator) = &mut self.narrator {
+            narrator.say(line.into());
+        }
+    }
+
+    /// Ask GitHub about the username off the UI thread, unless it's
+    /// already been asked about
+    fn start_github_lookup(&mut self) {
+        let login = self.wizard.identity.github_username.trim().to_string();
+        let asked = self.github_user.as_ref().is_some_and(|(l, _)| *l == login);
+        if !self.look_up_github || login.is_empty() || asked || self.github_user_receiver.is_some()
+        {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let system = self.system.clone();
+        std::thread::spawn(move || {
+            let user = github_api::Client::from_env(&github::SystemRunner, &system).user(&login);
+            let _ = tx.send((login, user));
+        });
+        self.github_user_receiver = Some(rx);
+    }
+
+    /// Push the profile and generated configs to GitHub off the UI thread;
+    /// it's a handful of API calls
+    fn start_backup(&mut self) {
+        let contents = backup::collect(&self.wizard, &self.system, &self.profile_path);
+        let repo = self.backup_repo.clone();
+        let hostname = self.system.hostname.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let pushed = backup::push(&github::SystemRunner, &repo, &contents.files, &hostname)
+                .map(|full_name| {
+                    let mut status =
+                        format!("Backed up {} files to {}", contents.files.len(), full_name);
+                    if !contents.withheld.is_empty() {
+                        status.push_str(&format!(
+                            "; left out {}, which hold a secret",
+                            contents.withheld.join(", ")
+                        ));
+                    }
+                    status
+                });
+            let _ = tx.send(pushed);
+        });
+        self.backup_status = None;
+        self.backup_receiver = Some(rx);
+    }
+
+    /// Look a palette entry up with `brew search` off the UI thread; it
+    /// can take a few seconds while brew refreshes its index
+    fn start_palette_check(&mut self, package: custom::CustomPackage) {
+        let (tx, rx) = mpsc::channel();
+        let system = self.system.clone();
+        std::thread::spawn(move || {
+            let checked = custom::check(&package, &system).map(|note| (package, note));
+            let _ = tx.send(checked);
+        });
+        self.palette_status = None;
+        self.palette_receiver = Some(rx);
+    }
+
+    /// Ask brew what matches the palette entry, off the UI thread
+    fn start_suggestions(&mut self, entry: String) {
+        let (tx, rx) = mpsc::channel();
+        let system = self.system.clone();
+        std::thread::spawn(move || {
+            let found = custom::suggest(&entry, &system);
+            let _ = tx.send((entry, found));
+        });
+        self.suggest_receiver = Some(rx);
+    }
+
+    /// Run the pre-flight checks off the UI thread; they ping the network
+    /// and shell out, so they're usually done by the end of the boot screen
+    fn start_preflight(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        let system = self.system.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(system::PreflightResult::run(&system));
+        });
+        self.preflight_receiver = Some(rx);
+    }
+
+    fn tick(&mut self) {
+        // Animations move by the time since the last tick, not by ticks,
+        // so a slow redraw doesn't slow them down
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last_tick);
+        self.last_tick = now;
+        self.matrix_rain.advance(elapsed);
+        self.spinner.advance(elapsed);
 
         if let Some(tw) = &mut self.typewriter {
-            tw.tick();
+            tw.advance(elapsed);
         }
 
         if self.wizard.phase == WizardPhase::Boot {
-            self.boot_sequence.tick();
+            self.boot_sequence.advance(elapsed);
 
             if self.boot_sequence.complete {
                 // Auto-advance after boot
                 self.wizard.advance();
             }
         }
+
+        // Search brew once typing in the palette pauses
+        if let (Some(entry), Some(at)) = (&self.palette, self.palette_typed_at) {
+            if at.elapsed() >= SUGGEST_DELAY && self.suggest_receiver.is_none() {
+                let entry = entry.clone();
+                self.palette_typed_at = None;
+                self.start_suggestions(entry);
+            }
+        }
+
+        // Credit the running package for its time so far
+        if self.is_installing {
+            self.update_progress();
+        }
+
+        let idle = self
+            .attract_after
+            .is_some_and(|after| self.last_key.elapsed() >= after);
+        let watching = self.is_installing && self.wizard.phase == WizardPhase::Install;
+        if idle && watching && self.modal.is_none() {
+            self.attract.get_or_insert_with(Instant::now);
+        }
+    }
+
+    /// React to a terminal resize: size the rain to the new screen and pull
+    /// cursors back inside lists that may have changed under them
+    fn resize(&mut self, width: u16, height: u16) {
+        self.matrix_rain = MatrixRain::new(width, height);
+        self.clamp_cursors();
+    }
+
+    /// Pull the cursor back inside the current screen's rows
+    fn clamp_cursors(&mut self) {
+        match self.wizard.phase {
+            WizardPhase::Identity => {
+                self.wizard.input_field = self.wizard.input_field.min(3);
+            }
+            WizardPhase::Shell => {
+                self.wizard.cursor_position = self.wizard.cursor_position.min(7);
+            }
+            WizardPhase::Hub => {
+                let max = hub::HubAction::menu(self.resumable.is_some()).len();
+                self.wizard.cursor_position = self.wizard.cursor_position.min(max - 1);
+            }
+            WizardPhase::Upgrade => {
+                let max = match &self.upgrades {
+                    Some(Ok(outdated)) => outdated.len(),
+                    _ => 0,
+                };
+                self.wizard.cursor_position =
+                    self.wizard.cursor_position.min(max.saturating_sub(1));
+            }
+            WizardPhase::Onboarding => {
+                let max = self.onboarding.as_ref().map_or(0, |c| c.items.len());
+                self.wizard.cursor_position =
+                    self.wizard.cursor_position.min(max.saturating_sub(1));
+            }
+            WizardPhase::GitHub => {
+                self.wizard.cursor_position = self.wizard.cursor_position.min(3);
+            }
+            WizardPhase::ConfigPreview => {
+                let max = self.planned_configs().len() + self.alias_conflicts.len();
+                self.wizard.cursor_position =
+                    self.wizard.cursor_position.min(max.saturating_sub(1));
+            }
+            WizardPhase::Kubernetes => {
+                let rows = self.wizard.kube.source.fields().len() + 1;
+                self.wizard.cursor_position = self.wizard.cursor_position.min(rows - 1);
+            }
+            WizardPhase::Atuin => {
+                let rows = self.wizard.atuin.sync.fields().len() + 1;
+                self.wizard.cursor_position = self.wizard.cursor_position.min(rows - 1);
+            }
+            WizardPhase::AiTools => {
+                let rows = self.wizard.ai.source.fields().len() + 1;
+                self.wizard.cursor_position = self.wizard.cursor_position.min(rows - 1);
+            }
+            WizardPhase::DevTools | WizardPhase::Apps => {
+                let max = self.get_current_list_len();
+                self.wizard.cursor_position =
+                    self.wizard.cursor_position.min(max.saturating_sub(1));
+            }
+            // Review scroll is clamped against the rendered content height
+            _ => {}
+        }
     }
 
-    pub fn handle_key(&mut self, key: KeyCode, modifiers: KeyModifiers) {
-        // Global quit (during install, skip to complete instead of hard exit)
+    fn handle_key(&mut self, key: KeyCode, modifiers: KeyModifiers) {
+        self.last_key = Instant::now();
+        // Any key brings the log back, and does nothing else
+        let woke = self.attract.take().is_some();
+
+        // Raw mode swallows the terminal's own Ctrl+Z, so request it here
+        if modifiers.contains(KeyModifiers::CONTROL) && key == KeyCode::Char('z') {
+            self.suspend_signal.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        // Global quit (during install, skip to complete instead of hard
+        // exit). Anything that would be lost asks first; a second Ctrl+C
+        // answers yes.
         if modifiers.contains(KeyModifiers::CONTROL) && key == KeyCode::Char('c') {
-            if self.is_installing && self.wizard.phase == WizardPhase::Install {
-                // Abort install gracefully — drop the receiver so the thread is detached
-                self.install_receiver = None;
-                self.is_installing = false;
-                self.install_log
-                    .push("[ABORT] Installation interrupted by user".to_string());
-                self.wizard.phase = WizardPhase::Complete;
+            let action = if self.is_installing && self.wizard.phase == WizardPhase::Install {
+                ModalAction::AbortInstall
             } else {
-                self.should_quit = true;
+                ModalAction::Quit
+            };
+            let asked = self.modal.as_ref().is_some_and(|m| m.action == action);
+            if asked || (action == ModalAction::Quit && !self.has_unsaved_answers()) {
+                self.modal = None;
+                self.run_modal_action(action);
+            } else {
+                self.modal = Some(match action {
+                    ModalAction::AbortInstall => Modal {
+                        title: "ABORT INSTALL",
+                        message: "Stop following the install and skip to the summary? \
+                                  Packages already installed stay installed."
+                            .to_string(),
+                        confirm: "abort",
+                        action,
+                    },
+                    _ => Modal {
+                        title: "QUIT",
+                        message: "Quit without installing? Your answers and picks aren't \
+                                  saved; a preset saved from Review keeps the picks."
+                            .to_string(),
+                        confirm: "quit",
+                        action,
+                    },
+                });
             }
             return;
         }
 
+        if self.modal.is_some() {
+            self.handle_modal_input(key);
+            return;
+        }
+        if woke {
+            return;
+        }
+
         match self.wizard.phase {
             WizardPhase::Boot => {
                 // Any key skips boot sequence
                 self.boot_sequence.skip();
             }
 
+            WizardPhase::Hub => {
+                self.handle_hub_input(key);
+            }
+
+            WizardPhase::Audit => match self
+                .keymap
+                .resolve(key, &[Action::Up, Action::Down, Action::Back])
+            {
+                Some(Action::Up) => {
+                    self.wizard.scroll_offset = self.wizard.scroll_offset.saturating_sub(1);
+                }
+                Some(Action::Down) => {
+                    self.wizard.scroll_offset += 1;
+                }
+                Some(Action::Back) => {
+                    self.wizard.go_back();
+                }
+                _ => {}
+            },
+
+            WizardPhase::Upgrade => self.handle_upgrade_input(key),
+
+            WizardPhase::Preflight => {
+                // Failures are shown but don't block; the install reports
+                // whatever they break
+                if self.preflight.is_some()
+                    && self.keymap.resolve(key, &[Action::Confirm]) == Some(Action::Confirm)
+                {
+                    self.wizard.advance();
+                }
+            }
+
             WizardPhase::Identity => {
                 self.handle_identity_input(key);
             }
 
+            WizardPhase::GitHub => {
+                self.handle_github_input(key);
+            }
+
             WizardPhase::Shell => {
                 self.handle_shell_input(key);
             }
@@ -219,38 +984,134 @@ impl App {
                 self.handle_apps_input(key);
             }
 
+            WizardPhase::Kubernetes => {
+                self.handle_kube_input(key);
+            }
+
+            WizardPhase::Atuin => {
+                self.handle_atuin_input(key);
+            }
+
+            WizardPhase::AiTools => {
+                self.handle_ai_input(key);
+            }
+
+            WizardPhase::ConfigPreview => {
+                self.handle_config_preview_input(key);
+            }
+
             WizardPhase::Review => {
                 self.handle_review_input(key);
             }
 
             WizardPhase::Install => {
-                // No input during install
+                let context = [Action::LogFilter, Action::CollapseOutput, Action::Attract];
+                match self.keymap.resolve(key, &context) {
+                    Some(Action::LogFilter) => {
+                        self.log_filter = self.log_filter.next();
+                    }
+                    Some(Action::CollapseOutput) => {
+                        self.collapse_output = !self.collapse_output;
+                    }
+                    Some(Action::Attract) if self.is_installing => {
+                        self.attract = Some(Instant::now());
+                    }
+                    _ => {}
+                }
             }
 
-            WizardPhase::Complete => match key {
-                KeyCode::Enter | KeyCode::Char('q') => {
-                    self.should_quit = true;
+            WizardPhase::Onboarding => self.handle_onboarding_input(key),
+
+            WizardPhase::Complete => {
+                let context = [
+                    Action::Confirm,
+                    Action::Quit,
+                    Action::Backup,
+                    Action::Export,
+                ];
+                match self.keymap.resolve(key, &context) {
+                    Some(Action::Confirm | Action::Quit) => self.should_quit = true,
+                    // brew takes a moment to list its versions
+                    Some(Action::Export) if self.versions_receiver.is_none() => {
+                        let runner = Arc::clone(&self.runner);
+                        let (tx, rx) = mpsc::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(report::brew_versions(runner.as_ref()));
+                        });
+                        self.summary_status = None;
+                        self.versions_receiver = Some(rx);
+                    }
+                    Some(Action::Backup) if self.backup_receiver.is_none() => {
+                        self.start_backup();
+                    }
+                    _ => {}
                 }
-                _ => {}
-            },
+            }
         }
     }
 
-    fn handle_identity_input(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Tab | KeyCode::Down => {
-                self.wizard.input_field = (self.wizard.input_field + 1) % 4;
+    fn handle_onboarding_input(&mut self, key: KeyCode) {
+        let Some(checklist) = &mut self.onboarding else {
+            self.wizard.advance();
+            return;
+        };
+        let cursor = self.wizard.cursor_position;
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::Toggle,
+            Action::OpenLink,
+            Action::Confirm,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                self.wizard.cursor_position = cursor.saturating_sub(1);
             }
-            KeyCode::BackTab | KeyCode::Up => {
-                self.wizard.input_field = self.wizard.input_field.checked_sub(1).unwrap_or(3);
+            Some(Action::Down) => {
+                self.wizard.cursor_position =
+                    (cursor + 1).min(checklist.items.len().saturating_sub(1));
             }
-            KeyCode::Char(c) => match self.wizard.input_field {
-                0 => self.wizard.identity.name.push(c),
-                1 => self.wizard.identity.email.push(c),
-                2 => self.wizard.identity.github_username.push(c),
-                _ => {}
-            },
-            KeyCode::Backspace => match self.wizard.input_field {
+            Some(Action::Toggle) => checklist.toggle(cursor),
+            Some(Action::OpenLink) => {
+                let Some(url) = checklist.items.get(cursor).and_then(|i| i.url.clone()) else {
+                    return;
+                };
+                self.onboarding_notice = Some(match open_url(&url, self.system.os) {
+                    Ok(()) => format!("Opened {}", url),
+                    Err(e) => format!("{}; it's at {}", e, url),
+                });
+            }
+            Some(Action::Confirm) => {
+                let report =
+                    onboarding::report(checklist, &self.wizard, &self.system, &self.summary);
+                self.onboarding_report = Some(
+                    std::env::current_dir()
+                        .map_err(|e| e.to_string())
+                        .and_then(|dir| onboarding::write(&report, &dir)),
+                );
+                self.wizard.advance();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_identity_input(&mut self, key: KeyCode) {
+        // Typed characters always go into the focused text field, so letter
+        // bindings like h/j/k/l never hijack a name or email
+        if let KeyCode::Char(c) = key {
+            if self.wizard.input_field != 3 {
+                match self.wizard.input_field {
+                    0 => self.wizard.identity.name.push(c),
+                    1 => self.wizard.identity.email.push(c),
+                    2 => self.wizard.identity.github_username.push(c),
+                    _ => {}
+                }
+                return;
+            }
+        }
+
+        if key == KeyCode::Backspace {
+            match self.wizard.input_field {
                 0 => {
                     self.wizard.identity.name.pop();
                 }
@@ -261,81 +1122,398 @@ impl App {
                     self.wizard.identity.github_username.pop();
                 }
                 _ => {}
-            },
-            KeyCode::Left | KeyCode::Right => {
-                if self.wizard.input_field == 3 {
-                    let types = wizard::SetupType::all();
-                    let current = types
-                        .iter()
-                        .position(|t| *t == self.wizard.identity.setup_type)
-                        .unwrap_or(0);
-                    let new_idx = if key == KeyCode::Right {
-                        (current + 1) % types.len()
-                    } else {
-                        current.checked_sub(1).unwrap_or(types.len() - 1)
-                    };
-                    self.wizard.identity.setup_type = types[new_idx];
-                }
             }
-            KeyCode::Enter => {
+            return;
+        }
+
+        let context = [
+            Action::NextCategory,
+            Action::PrevCategory,
+            Action::Down,
+            Action::Up,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::NextCategory | Action::Down) => {
+                self.wizard.input_field = (self.wizard.input_field + 1) % 4;
+            }
+            Some(Action::PrevCategory | Action::Up) => {
+                self.wizard.input_field = self.wizard.input_field.checked_sub(1).unwrap_or(3);
+            }
+            Some(action @ (Action::Left | Action::Right)) if self.wizard.input_field == 3 => {
+                let types = wizard::SetupType::all();
+                let current = types
+                    .iter()
+                    .position(|t| *t == self.wizard.identity.setup_type)
+                    .unwrap_or(0);
+                let new_idx = if action == Action::Right {
+                    (current + 1) % types.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(types.len() - 1)
+                };
+                self.wizard.identity.setup_type = types[new_idx];
+            }
+            Some(Action::Confirm) => {
                 if self.wizard.input_field == 3 {
                     self.wizard.advance();
                 } else {
                     self.wizard.input_field = (self.wizard.input_field + 1) % 4;
                 }
             }
-            KeyCode::Esc => {
+            Some(Action::Back) => {
                 self.wizard.go_back();
             }
             _ => {}
         }
     }
 
-    fn handle_shell_input(&mut self, key: KeyCode) {
-        let max_items = 4; // shell, prompt, terminal, multiplexer
+    fn handle_hub_input(&mut self, key: KeyCode) {
+        let actions = hub::HubAction::menu(self.resumable.is_some());
+        let context = [Action::Up, Action::Down, Action::Confirm];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
+            }
+            Some(Action::Down) => {
+                self.wizard.cursor_position =
+                    (self.wizard.cursor_position + 1).min(actions.len() - 1);
+            }
+            Some(Action::Confirm) => match actions[self.wizard.cursor_position] {
+                // The interrupted run's answers, straight to Review
+                hub::HubAction::Resume => {
+                    let Some(previous) = self.resumable.take() else {
+                        return;
+                    };
+                    let mut wizard = previous.wizard.clone();
+                    wizard.categories = self.wizard.categories.clone();
+                    wizard.returning = true;
+                    self.wizard = wizard;
+                    self.resuming = Some(previous);
+                    self.wizard.go_to(WizardPhase::Review);
+                }
+                hub::HubAction::NewSetup => {
+                    self.wizard.advance();
+                }
+                // Only what the profile lists; identity and configs are
+                // already on this machine from the first run
+                hub::HubAction::Sync if self.saved_apps => {
+                    if !self.take_run_lock() {
+                        return;
+                    }
+                    let apps = self.wizard.get_apps_to_install();
+                    let options = self.install_options.clone();
+                    self.spawn_install(&apps.clone(), move |system, tx| {
+                        executor::run_install(system, apps, &options, tx);
+                    });
+                    self.wizard.go_to(WizardPhase::Install);
+                }
+                hub::HubAction::Sync => {}
+                // brew takes a while to list what it has, so the screen
+                // shows the audit once it's in
+                hub::HubAction::Audit => {
+                    let apps = self.wizard.get_apps_to_install();
+                    let system = self.system.clone();
+                    let (tx, rx) = mpsc::channel();
+                    std::thread::spawn(move || {
+                        let inventory = executor::BrewInventory::detect();
+                        let _ = tx.send(hub::audit(&apps, &system, &inventory));
+                    });
+                    self.audit = None;
+                    self.audit_receiver = Some(rx);
+                    self.wizard.go_to(WizardPhase::Audit);
+                }
+                // As is asking brew what's outdated, twice
+                hub::HubAction::Upgrade => {
+                    let apps = catalog::Query::new().platform(self.system.os).apps();
+                    let runner = Arc::clone(&self.runner);
+                    let (tx, rx) = mpsc::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(hub::outdated(runner.as_ref(), &apps));
+                    });
+                    self.upgrades = None;
+                    self.upgrades_receiver = Some(rx);
+                    self.upgrade_notice = None;
+                    self.wizard.go_to(WizardPhase::Upgrade);
+                }
+            },
+            _ => {}
+        }
+    }
 
-        match key {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_upgrade_input(&mut self, key: KeyCode) {
+        let outdated: Vec<hub::Outdated> = match &self.upgrades {
+            Some(Ok(outdated)) => outdated.clone(),
+            _ => Vec::new(),
+        };
+        let selected = outdated
+            .get(self.wizard.cursor_position)
+            .map(|tool| tool.app);
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::Toggle,
+            Action::Greedy,
+            Action::ReleaseNotes,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
                 self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.wizard.cursor_position = (self.wizard.cursor_position + 1).min(max_items - 1);
+            Some(Action::Down) => {
+                self.wizard.cursor_position =
+                    (self.wizard.cursor_position + 1).min(outdated.len().saturating_sub(1));
             }
-            KeyCode::Left | KeyCode::Char('h') => {
-                self.cycle_shell_option(false);
+            Some(Action::Toggle) => {
+                let Some(app) = selected else {
+                    return;
+                };
+                profile::UpgradeSection::toggle(&mut self.upgrade_prefs.exclude, app.id.as_str());
+                self.save_upgrade_prefs();
             }
-            KeyCode::Right | KeyCode::Char('l') => {
-                self.cycle_shell_option(true);
+            Some(Action::Greedy) => {
+                let Some(app) = selected else {
+                    return;
+                };
+                if !matches!(app.method(), catalog::InstallMethod::BrewCask(_)) {
+                    self.upgrade_notice =
+                        Some(format!("{} isn't a cask; --greedy is for casks", app.name));
+                    return;
+                }
+                profile::UpgradeSection::toggle(&mut self.upgrade_prefs.greedy, app.id.as_str());
+                self.save_upgrade_prefs();
             }
-            KeyCode::Enter => {
-                self.wizard.advance();
+            Some(Action::ReleaseNotes) => {
+                let Some(app) = selected else {
+                    return;
+                };
+                let url = hub::release_notes(app);
+                self.upgrade_notice = Some(match open_url(&url, self.system.os) {
+                    Ok(()) => format!("Opened {}", url),
+                    Err(e) => format!("{}; the release notes are at {}", e, url),
+                });
             }
-            KeyCode::Esc => {
+            Some(Action::Confirm) => {
+                let prefs = &self.upgrade_prefs;
+                let apps: Vec<&'static catalog::App> = outdated
+                    .iter()
+                    .filter(|tool| hub::will_upgrade(tool, prefs))
+                    .map(|tool| tool.app)
+                    .collect();
+                if apps.is_empty() {
+                    return;
+                }
+                let greedy: Vec<&'static catalog::App> = apps
+                    .iter()
+                    .copied()
+                    .filter(|app| prefs.is_greedy(app.id.as_str()))
+                    .collect();
+                if !self.take_run_lock() {
+                    return;
+                }
+                self.spawn_install(&[], move |_, tx| {
+                    executor::run_upgrade(&apps, &greedy, tx);
+                });
+                self.wizard.go_to(WizardPhase::Install);
+            }
+            Some(Action::Back) => {
                 self.wizard.go_back();
             }
             _ => {}
         }
     }
 
-    fn cycle_shell_option(&mut self, forward: bool) {
-        match self.wizard.cursor_position {
-            0 => {
-                let opts = wizard::ShellChoice::all();
-                let current = opts
-                    .iter()
-                    .position(|o| *o == self.wizard.shell_config.shell)
-                    .unwrap_or(0);
-                let new_idx = if forward {
-                    (current + 1) % opts.len()
-                } else {
-                    current.checked_sub(1).unwrap_or(opts.len() - 1)
-                };
-                self.wizard.shell_config.shell = opts[new_idx];
-            }
-            1 => {
-                let opts = wizard::PromptChoice::all();
-                let current = opts
-                    .iter()
+    /// Write the upgrade choices to the profile, saying where they went
+    fn save_upgrade_prefs(&mut self) {
+        let path = self.own_profile_path();
+        self.upgrade_notice = Some(match self.upgrade_prefs.save(&path) {
+            Ok(()) => format!("Saved to {}", path.display()),
+            Err(e) => e,
+        });
+    }
+
+    /// The profile this run loaded, unless it's a copy fetched for the run
+    /// that the next fetch replaces; then the user's own profile.toml
+    fn own_profile_path(&self) -> PathBuf {
+        let state = hub::state_dir(&self.system);
+        let fetched = [PULLED_PROFILE, ONBOARDING_PROFILE]
+            .iter()
+            .any(|name| self.profile_path == state.join(name));
+        if fetched {
+            Profile::default_path(&self.system)
+        } else {
+            self.profile_path.clone()
+        }
+    }
+
+    fn handle_github_input(&mut self, key: KeyCode) {
+        if self.ssh_key_picker.is_some() {
+            self.handle_ssh_key_picker_input(key);
+            return;
+        }
+        let rows = 4; // SSH key, passphrase, git signing, https rewrite
+        if self.wizard.cursor_position == 1 && type_into(&mut self.wizard.ssh_passphrase, key) {
+            return;
+        }
+
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::Toggle,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
+            }
+            Some(Action::Down) => {
+                self.wizard.cursor_position = (self.wizard.cursor_position + 1).min(rows - 1);
+            }
+            Some(Action::Toggle | Action::Left | Action::Right) => {
+                match self.wizard.cursor_position {
+                    0 if self.ssh_keys.len() > 1 => self.open_ssh_key_picker(),
+                    0 => self.wizard.generate_ssh_key = !self.wizard.generate_ssh_key,
+                    2 => self.wizard.setup_git_signing = !self.wizard.setup_git_signing,
+                    3 => self.wizard.rewrite_github_https = !self.wizard.rewrite_github_https,
+                    _ => {}
+                }
+            }
+            Some(Action::Confirm) => {
+                // With several keys around, which one GitHub gets is asked,
+                // not guessed
+                let undecided =
+                    self.wizard.ssh_key == SshKeyChoice::Default && self.wizard.generate_ssh_key;
+                if self.ssh_keys.len() > 1 && undecided {
+                    self.open_ssh_key_picker();
+                } else if let Some(var) = self
+                    .env_token
+                    .take()
+                    .filter(|_| self.wizard.configures("gh"))
+                {
+                    // Asked once: it may be a CI or throwaway token
+                    self.modal = Some(Modal {
+                        title: "GITHUB TOKEN",
+                        message: format!(
+                            "${} is set. Log gh in with it? gh stores it in its own config, \
+                             where it stays after the variable is gone; say no for a CI or \
+                             short-lived token.",
+                            var
+                        ),
+                        confirm: "use it",
+                        action: ModalAction::UseEnvToken,
+                    });
+                } else {
+                    self.wizard.advance();
+                }
+            }
+            Some(Action::Back) => {
+                self.wizard.go_back();
+            }
+            _ => {}
+        }
+    }
+
+    /// The keys in `~/.ssh`, then a new key, then no new key: the row's
+    /// on/off for generating, which it can't toggle while it opens this
+    fn open_ssh_key_picker(&mut self) {
+        let cursor = match &self.wizard.ssh_key {
+            SshKeyChoice::Existing(path) => self.ssh_keys.iter().position(|k| &k.path == path),
+            SshKeyChoice::Generate => Some(self.ssh_keys.len()),
+            SshKeyChoice::Default if !self.wizard.generate_ssh_key => Some(self.ssh_keys.len() + 1),
+            SshKeyChoice::Default => None,
+        };
+        self.ssh_key_picker = Some(cursor.unwrap_or(0));
+    }
+
+    fn handle_ssh_key_picker_input(&mut self, key: KeyCode) {
+        let Some(cursor) = &mut self.ssh_key_picker else {
+            return;
+        };
+        let context = [Action::Up, Action::Down, Action::Confirm, Action::Back];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                *cursor = cursor.saturating_sub(1);
+            }
+            Some(Action::Down) => {
+                *cursor = (*cursor + 1).min(self.ssh_keys.len() + 1);
+            }
+            Some(Action::Confirm) => {
+                let keys = self.ssh_keys.len();
+                self.wizard.ssh_key = match self.ssh_keys.get(*cursor) {
+                    Some(key) => SshKeyChoice::Existing(key.path.clone()),
+                    None if *cursor == keys => SshKeyChoice::Generate,
+                    None => SshKeyChoice::Default,
+                };
+                self.wizard.generate_ssh_key = *cursor <= keys;
+                self.ssh_key_picker = None;
+            }
+            Some(Action::Back) => {
+                self.ssh_key_picker = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_shell_input(&mut self, key: KeyCode) {
+        let max_items = 8; // shell, prompt, terminal, multiplexer, editor, theme, font, aliases
+
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
+            }
+            Some(Action::Down) => {
+                self.wizard.cursor_position = (self.wizard.cursor_position + 1).min(max_items - 1);
+            }
+            Some(Action::Left) => {
+                self.cycle_shell_option(false);
+            }
+            Some(Action::Right) => {
+                self.cycle_shell_option(true);
+            }
+            Some(Action::Confirm) => {
+                self.wizard.advance();
+            }
+            Some(Action::Back) => {
+                self.wizard.go_back();
+            }
+            _ => {}
+        }
+    }
+
+    fn cycle_shell_option(&mut self, forward: bool) {
+        match self.wizard.cursor_position {
+            0 => {
+                let opts = wizard::ShellChoice::all();
+                let current = opts
+                    .iter()
+                    .position(|o| *o == self.wizard.shell_config.shell)
+                    .unwrap_or(0);
+                let new_idx = if forward {
+                    (current + 1) % opts.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(opts.len() - 1)
+                };
+                self.wizard.shell_config.shell = opts[new_idx];
+            }
+            1 => {
+                let opts = wizard::PromptChoice::all();
+                let current = opts
+                    .iter()
                     .position(|o| *o == self.wizard.shell_config.prompt)
                     .unwrap_or(0);
                 let new_idx = if forward {
@@ -376,251 +1554,1629 @@ impl App {
                         Some(opts[new_idx])
                     };
             }
+            4 => {
+                let opts = wizard::EditorChoice::all();
+                let current = opts
+                    .iter()
+                    .position(|o| *o == self.wizard.editor)
+                    .unwrap_or(0);
+                let new_idx = if forward {
+                    (current + 1) % opts.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(opts.len() - 1)
+                };
+                self.wizard.editor = opts[new_idx];
+            }
+            5 => {
+                let opts = theme::ThemeChoice::all();
+                let current = opts
+                    .iter()
+                    .position(|o| *o == self.wizard.theme)
+                    .unwrap_or(0);
+                let new_idx = if forward {
+                    (current + 1) % opts.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(opts.len() - 1)
+                };
+                self.wizard.theme = opts[new_idx];
+            }
+            6 => {
+                let opts = theme::FontChoice::all();
+                let current = opts
+                    .iter()
+                    .position(|o| *o == self.wizard.font)
+                    .unwrap_or(0);
+                let new_idx = if forward {
+                    (current + 1) % opts.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(opts.len() - 1)
+                };
+                self.wizard.font = opts[new_idx];
+            }
+            7 => {
+                self.wizard.shell_config.aliases = !self.wizard.shell_config.aliases;
+            }
             _ => {}
         }
     }
 
     fn handle_devtools_input(&mut self, key: KeyCode) {
-        let categories = &[
-            catalog::Category::Language,
-            catalog::Category::Editor,
-            catalog::Category::Git,
-            catalog::Category::Container,
-            catalog::Category::Cloud,
-        ];
+        if self.preset_picker.is_some() {
+            self.handle_preset_picker_input(key);
+            return;
+        }
+        if self.palette.is_some() {
+            self.handle_palette_input(key);
+            return;
+        }
 
-        match key {
-            KeyCode::Up | KeyCode::Char('k') => {
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::NextCategory,
+            Action::Toggle,
+            Action::CycleMode,
+            Action::Confirm,
+            Action::Back,
+            Action::SelectAll,
+            Action::SelectNone,
+            Action::Bundle,
+            Action::Presets,
+            Action::Expert,
+            Action::Palette,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
                 self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Some(Action::Down) => {
                 let max = self.get_current_list_len();
                 self.wizard.cursor_position =
                     (self.wizard.cursor_position + 1).min(max.saturating_sub(1));
             }
-            KeyCode::Tab => {
+            Some(Action::NextCategory) => {
                 // Cycle through categories
-                self.wizard.scroll_offset = (self.wizard.scroll_offset + 1) % categories.len();
-                self.wizard.cursor_position = 0;
+                self.wizard.show_category(self.wizard.scroll_offset + 1);
             }
-            KeyCode::Char(' ') => {
+            Some(Action::Toggle) => {
                 self.toggle_current_app();
             }
-            KeyCode::Enter => {
+            Some(Action::CycleMode) => {
+                self.cycle_current_app_mode();
+            }
+            Some(Action::Confirm) => {
                 self.wizard.advance();
             }
-            KeyCode::Esc => {
+            Some(Action::Back) => {
                 self.wizard.go_back();
             }
-            KeyCode::Char('a') => {
+            Some(Action::SelectAll) => {
                 // Select all in current category
                 self.select_all_in_category();
             }
-            KeyCode::Char('n') => {
+            Some(Action::SelectNone) => {
                 // Deselect all in current category
                 self.deselect_all_in_category();
             }
+            Some(Action::Bundle) => {
+                let bundle = self.wizard.current_category();
+                if let Some(bundle) = bundle.and_then(|cat| catalog::bundle_for(&cat)) {
+                    self.wizard.toggle_bundle(bundle);
+                }
+            }
+            Some(Action::Presets) => {
+                self.preset_picker = Some(PresetPicker {
+                    presets: preset::list(&self.system),
+                    cursor: 0,
+                    replace: false,
+                });
+            }
+            Some(Action::Expert) => {
+                self.expert = !self.expert;
+            }
+            Some(Action::Palette) if self.expert => {
+                self.palette = Some(String::new());
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_preset_picker_input(&mut self, key: KeyCode) {
+        let Some(picker) = &mut self.preset_picker else {
+            return;
+        };
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                picker.cursor = picker.cursor.saturating_sub(1);
+            }
+            Some(Action::Down) => {
+                picker.cursor = (picker.cursor + 1).min(picker.presets.len().saturating_sub(1));
+            }
+            Some(Action::Left | Action::Right) => {
+                picker.replace = !picker.replace;
+            }
+            Some(Action::Confirm) => {
+                if let Some(preset) = picker.presets.get(picker.cursor) {
+                    preset.apply(&mut self.wizard, picker.replace);
+                }
+                self.preset_picker = None;
+            }
+            Some(Action::Back) => {
+                self.preset_picker = None;
+            }
             _ => {}
         }
     }
 
     fn handle_apps_input(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Up | KeyCode::Char('k') => {
+        if self.category_jump.is_some() {
+            self.handle_category_jump_input(key);
+            return;
+        }
+        if self.palette.is_some() {
+            self.handle_palette_input(key);
+            return;
+        }
+
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::NextCategory,
+            Action::PrevCategory,
+            Action::Categories,
+            Action::Toggle,
+            Action::CycleMode,
+            Action::Details,
+            Action::Confirm,
+            Action::Back,
+            Action::Expert,
+            Action::Palette,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
                 self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Some(Action::Down) => {
                 let max = self.get_current_list_len();
                 self.wizard.cursor_position =
                     (self.wizard.cursor_position + 1).min(max.saturating_sub(1));
             }
-            KeyCode::Tab => {
+            Some(Action::NextCategory) => {
                 // Cycle through categories
-                let categories = catalog::Category::all();
-                self.wizard.scroll_offset = (self.wizard.scroll_offset + 1) % categories.len();
-                self.wizard.cursor_position = 0;
+                self.wizard.show_category(self.wizard.scroll_offset + 1);
             }
-            KeyCode::BackTab => {
-                let categories = catalog::Category::all();
-                self.wizard.scroll_offset = self
+            Some(Action::PrevCategory) => {
+                let prev = self
                     .wizard
                     .scroll_offset
                     .checked_sub(1)
-                    .unwrap_or(categories.len() - 1);
-                self.wizard.cursor_position = 0;
+                    .unwrap_or(self.wizard.categories().len() - 1);
+                self.wizard.show_category(prev);
             }
-            KeyCode::Char(' ') => {
+            Some(Action::Categories) => {
+                self.category_jump =
+                    Some(self.wizard.scroll_offset % self.wizard.categories().len());
+            }
+            Some(Action::Toggle) => {
                 self.toggle_current_app();
             }
-            KeyCode::Char('d') => {
+            Some(Action::CycleMode) => {
+                self.cycle_current_app_mode();
+            }
+            Some(Action::Details) => {
                 self.wizard.show_details = !self.wizard.show_details;
             }
-            KeyCode::Enter => {
+            Some(Action::Confirm) => {
                 self.wizard.advance();
             }
-            KeyCode::Esc => {
+            Some(Action::Back) => {
                 self.wizard.go_back();
             }
+            Some(Action::Expert) => {
+                self.expert = !self.expert;
+            }
+            Some(Action::Palette) if self.expert => {
+                self.palette = Some(String::new());
+            }
             _ => {}
         }
     }
 
-    fn handle_review_input(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Enter | KeyCode::Char('y') => {
-                self.start_installation();
-                self.wizard.advance();
-            }
-            KeyCode::Esc | KeyCode::Char('n') => {
-                self.wizard.go_back();
+    /// Picking a category in the Apps sidebar. A letter moves to the next
+    /// category starting with it, or jumps straight there if only one does.
+    fn handle_category_jump_input(&mut self, key: KeyCode) {
+        let Some(cursor) = self.category_jump else {
+            return;
+        };
+        let categories = self.wizard.categories().to_vec();
+        let context = [Action::Up, Action::Down, Action::Confirm, Action::Back];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                self.category_jump = Some(cursor.checked_sub(1).unwrap_or(categories.len() - 1));
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.wizard.scroll_offset = self.wizard.scroll_offset.saturating_sub(1);
+            Some(Action::Down) => {
+                self.category_jump = Some((cursor + 1) % categories.len());
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.wizard.scroll_offset += 1;
+            Some(Action::Confirm) => self.jump_to_category(cursor),
+            Some(Action::Back) => self.category_jump = None,
+            _ => {
+                let KeyCode::Char(letter) = key else {
+                    return;
+                };
+                let starts = |i: &usize| {
+                    categories[*i]
+                        .name()
+                        .chars()
+                        .next()
+                        .is_some_and(|c| c.eq_ignore_ascii_case(&letter))
+                };
+                let matching: Vec<usize> = (0..categories.len()).filter(starts).collect();
+                match matching[..] {
+                    [] => {}
+                    [only] => self.jump_to_category(only),
+                    _ => {
+                        let next = matching.iter().find(|i| **i > cursor);
+                        self.category_jump = Some(*next.unwrap_or(&matching[0]));
+                    }
+                }
             }
-            _ => {}
         }
     }
 
-    fn get_current_list_len(&self) -> usize {
-        match self.wizard.phase {
-            WizardPhase::DevTools => {
-                let categories = &[
-                    catalog::Category::Language,
-                    catalog::Category::Editor,
-                    catalog::Category::Git,
-                    catalog::Category::Container,
-                    catalog::Category::Cloud,
-                ];
-                let cat = &categories[self.wizard.scroll_offset % categories.len()];
-                catalog::apps_by_category(cat).len()
+    fn jump_to_category(&mut self, index: usize) {
+        self.wizard.show_category(index);
+        self.category_jump = None;
+    }
+
+    fn handle_kube_input(&mut self, key: KeyCode) {
+        // Row 0 picks the source; the rows below are its text fields
+        let fields = self.wizard.kube.source.fields();
+        let rows = fields.len() + 1;
+        let focused = self
+            .wizard
+            .cursor_position
+            .checked_sub(1)
+            .and_then(|i| fields.get(i).copied());
+
+        if let Some(field) = focused {
+            if type_into(self.wizard.kube.field_mut(field), key) {
+                return;
             }
-            WizardPhase::Apps => {
-                let categories = catalog::Category::all();
-                let cat = &categories[self.wizard.scroll_offset % categories.len()];
-                catalog::apps_by_category(cat).len()
+        }
+
+        let context = [
+            Action::NextCategory,
+            Action::PrevCategory,
+            Action::Down,
+            Action::Up,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::NextCategory | Action::Down) => {
+                self.wizard.cursor_position = (self.wizard.cursor_position + 1) % rows;
+            }
+            Some(Action::PrevCategory | Action::Up) => {
+                self.wizard.cursor_position = self
+                    .wizard
+                    .cursor_position
+                    .checked_sub(1)
+                    .unwrap_or(rows - 1);
+            }
+            Some(action @ (Action::Left | Action::Right)) if focused.is_none() => {
+                let sources = kube::KubeSource::all();
+                let current = sources
+                    .iter()
+                    .position(|s| *s == self.wizard.kube.source)
+                    .unwrap_or(0);
+                let new_idx = if action == Action::Right {
+                    (current + 1) % sources.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(sources.len() - 1)
+                };
+                self.wizard.kube.source = sources[new_idx];
+            }
+            Some(Action::Confirm) => {
+                if self.wizard.cursor_position + 1 >= rows {
+                    self.wizard.advance();
+                } else {
+                    self.wizard.cursor_position += 1;
+                }
+            }
+            Some(Action::Back) => {
+                self.wizard.go_back();
             }
-            _ => 0,
+            _ => {}
         }
     }
 
-    fn toggle_current_app(&mut self) {
-        let app_id = self.get_current_app_id();
-        if let Some(id) = app_id {
-            self.wizard.toggle_app(&id);
+    fn handle_atuin_input(&mut self, key: KeyCode) {
+        // Row 0 picks sync on or off; the rows below are its text fields
+        let fields = self.wizard.atuin.sync.fields();
+        let rows = fields.len() + 1;
+        let focused = self
+            .wizard
+            .cursor_position
+            .checked_sub(1)
+            .and_then(|i| fields.get(i).copied());
+
+        if let Some(field) = focused {
+            if type_into(self.wizard.atuin.field_mut(field), key) {
+                return;
+            }
+        }
+
+        let context = [
+            Action::NextCategory,
+            Action::PrevCategory,
+            Action::Down,
+            Action::Up,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::NextCategory | Action::Down) => {
+                self.wizard.cursor_position = (self.wizard.cursor_position + 1) % rows;
+            }
+            Some(Action::PrevCategory | Action::Up) => {
+                self.wizard.cursor_position = self
+                    .wizard
+                    .cursor_position
+                    .checked_sub(1)
+                    .unwrap_or(rows - 1);
+            }
+            Some(action @ (Action::Left | Action::Right)) if focused.is_none() => {
+                let choices = atuin::AtuinSync::all();
+                let current = choices
+                    .iter()
+                    .position(|s| *s == self.wizard.atuin.sync)
+                    .unwrap_or(0);
+                let new_idx = if action == Action::Right {
+                    (current + 1) % choices.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(choices.len() - 1)
+                };
+                self.wizard.atuin.sync = choices[new_idx];
+            }
+            Some(Action::Confirm) => {
+                if self.wizard.cursor_position + 1 >= rows {
+                    self.wizard.advance();
+                } else {
+                    self.wizard.cursor_position += 1;
+                }
+            }
+            Some(Action::Back) => {
+                self.wizard.go_back();
+            }
+            _ => {}
         }
     }
 
-    fn get_current_app_id(&self) -> Option<String> {
-        let categories = match self.wizard.phase {
-            WizardPhase::DevTools => vec![
-                catalog::Category::Language,
-                catalog::Category::Editor,
-                catalog::Category::Git,
-                catalog::Category::Container,
-                catalog::Category::Cloud,
-            ],
-            WizardPhase::Apps => catalog::Category::all().to_vec(),
-            _ => return None,
-        };
+    fn handle_ai_input(&mut self, key: KeyCode) {
+        // Row 0 picks the key source; the rows below are its text fields
+        let fields = self.wizard.ai.source.fields();
+        let rows = fields.len() + 1;
+        let focused = self
+            .wizard
+            .cursor_position
+            .checked_sub(1)
+            .and_then(|i| fields.get(i).copied());
 
-        let cat_idx = self.wizard.scroll_offset % categories.len();
-        let cat = &categories[cat_idx];
+        if let Some(field) = focused {
+            if type_into(self.wizard.ai.field_mut(field), key) {
+                return;
+            }
+        }
 
-        let apps = catalog::apps_by_category(cat);
-        apps.get(self.wizard.cursor_position)
-            .map(|a| a.id.to_string())
+        let context = [
+            Action::NextCategory,
+            Action::PrevCategory,
+            Action::Down,
+            Action::Up,
+            Action::Left,
+            Action::Right,
+            Action::Confirm,
+            Action::Back,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::NextCategory | Action::Down) => {
+                self.wizard.cursor_position = (self.wizard.cursor_position + 1) % rows;
+            }
+            Some(Action::PrevCategory | Action::Up) => {
+                self.wizard.cursor_position = self
+                    .wizard
+                    .cursor_position
+                    .checked_sub(1)
+                    .unwrap_or(rows - 1);
+            }
+            Some(action @ (Action::Left | Action::Right)) if focused.is_none() => {
+                let sources = ai::KeySource::all();
+                let current = sources
+                    .iter()
+                    .position(|s| *s == self.wizard.ai.source)
+                    .unwrap_or(0);
+                let new_idx = if action == Action::Right {
+                    (current + 1) % sources.len()
+                } else {
+                    current.checked_sub(1).unwrap_or(sources.len() - 1)
+                };
+                self.wizard.ai.source = sources[new_idx];
+            }
+            Some(Action::Confirm) => {
+                if self.wizard.cursor_position + 1 >= rows {
+                    self.wizard.advance();
+                } else {
+                    self.wizard.cursor_position += 1;
+                }
+            }
+            Some(Action::Back) => {
+                self.wizard.go_back();
+            }
+            _ => {}
+        }
     }
 
-    fn select_all_in_category(&mut self) {
-        let categories = &[
-            catalog::Category::Language,
-            catalog::Category::Editor,
-            catalog::Category::Git,
-            catalog::Category::Container,
-            catalog::Category::Cloud,
-        ];
-        let cat = &categories[self.wizard.scroll_offset % categories.len()];
-        for app in catalog::apps_by_category(cat) {
-            self.wizard.selected_apps.insert(app.id.to_string());
+    /// The config files the install would write, as of the current answers
+    pub fn planned_configs(&self) -> Vec<config::PlannedConfig> {
+        config::planned_configs(&self.wizard, &self.system, None)
+    }
+
+    /// Look for the user's own definitions of the aliases about to be
+    /// written. One not seen before is kept until the user says otherwise;
+    /// their choice on one seen before stands.
+    fn find_alias_conflicts(&mut self) {
+        let conflicts = aliascheck::find(&self.wizard, &self.system);
+        for conflict in &conflicts {
+            let name = conflict.alias.name;
+            if !self.alias_conflicts.iter().any(|c| c.alias.name == name) {
+                self.wizard.kept_aliases.insert(name.to_string());
+            }
         }
+        self.alias_conflicts = conflicts;
     }
 
-    fn deselect_all_in_category(&mut self) {
-        let categories = &[
-            catalog::Category::Language,
-            catalog::Category::Editor,
-            catalog::Category::Git,
-            catalog::Category::Container,
-            catalog::Category::Cloud,
+    /// The files, then the alias conflicts, are one list
+    fn handle_config_preview_input(&mut self, key: KeyCode) {
+        let files = self.planned_configs().len();
+        let max = files + self.alias_conflicts.len();
+        let context = [
+            Action::Up,
+            Action::Down,
+            Action::Toggle,
+            Action::Confirm,
+            Action::Back,
         ];
-        let cat = &categories[self.wizard.scroll_offset % categories.len()];
-        for app in catalog::apps_by_category(cat) {
-            self.wizard.selected_apps.remove(app.id);
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Toggle) => {
+                let cursor = self.wizard.cursor_position;
+                if let Some(conflict) = cursor
+                    .checked_sub(files)
+                    .and_then(|i| self.alias_conflicts.get(i))
+                {
+                    let name = conflict.alias.name;
+                    if !self.wizard.kept_aliases.remove(name) {
+                        self.wizard.kept_aliases.insert(name.to_string());
+                    }
+                }
+            }
+            Some(Action::Up) => {
+                self.wizard.cursor_position = self.wizard.cursor_position.saturating_sub(1);
+                self.wizard.scroll_offset = 0;
+            }
+            Some(Action::Down) => {
+                self.wizard.cursor_position =
+                    (self.wizard.cursor_position + 1).min(max.saturating_sub(1));
+                self.wizard.scroll_offset = 0;
+            }
+            Some(Action::Confirm) => {
+                self.wizard.advance();
+            }
+            Some(Action::Back) => {
+                self.wizard.go_back();
+            }
+            _ => {}
         }
     }
 
-    fn start_installation(&mut self) {
-        self.is_installing = true;
-        self.install_progress = 0.0;
-        self.install_completed = 0;
-        self.failed_packages.clear();
-        self.skipped_packages.clear();
-        self.install_log.clear();
-        self.install_log
-            .push("[INIT] Starting installation sequence...".to_string());
-
-        let selected_apps = self.wizard.get_selected_apps();
-        self.install_total = selected_apps.len();
+    /// Typing a package into the expert palette. Up and Down pick one of
+    /// brew's suggestions, Enter checks and queues the entry (or the pick),
+    /// Backspace on an empty entry drops the last one queued, Esc closes
+    /// the palette.
+    fn handle_palette_input(&mut self, key: KeyCode) {
+        let Some(entry) = &mut self.palette else {
+            return;
+        };
+        if let KeyCode::Char(c) = key {
+            entry.push(c);
+            self.palette_edited();
+            return;
+        }
+        if key == KeyCode::Backspace {
+            if entry.pop().is_some() {
+                self.palette_edited();
+            } else if let Some(package) = self.wizard.custom_packages.pop() {
+                self.palette_status = Some(Ok(format!("Dropped {}", package)));
+            }
+            return;
+        }
+        let context = [Action::Up, Action::Down, Action::Confirm, Action::Back];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Up) => {
+                self.suggestion_cursor = self.suggestion_cursor.and_then(|i| i.checked_sub(1));
+            }
+            Some(Action::Down) if !self.suggestions.is_empty() => {
+                let last = self.suggestions.len() - 1;
+                self.suggestion_cursor =
+                    Some(self.suggestion_cursor.map_or(0, |i| (i + 1).min(last)));
+            }
+            // One check at a time; Enter again once it's answered
+            Some(Action::Confirm) if self.palette_receiver.is_none() => {
+                let picked = self
+                    .suggestion_cursor
+                    .and_then(|i| self.suggestions.get(i))
+                    .map(|s| s.package.clone());
+                let found = picked.is_some();
+                let package = match picked.map_or_else(|| custom::CustomPackage::parse(entry), Ok) {
+                    Ok(package) => package,
+                    Err(e) => {
+                        self.palette_status = Some(Err(e));
+                        return;
+                    }
+                };
+                entry.clear();
+                self.palette_edited();
+                if let Some(app) = package.in_catalog() {
+                    self.wizard.selected_apps.insert(app.id.to_string());
+                    self.palette_status = Some(Ok(format!(
+                        "{} is in the catalog as {}; selected it there",
+                        package, app.name
+                    )));
+                } else if self.wizard.custom_packages.contains(&package) {
+                    self.palette_status = Some(Err(format!("{} is already queued", package)));
+                } else if found {
+                    // brew search just listed it, so there's nothing to check
+                    self.palette_status = Some(Ok(format!("{} found with brew search", package)));
+                    self.wizard.custom_packages.push(package);
+                } else {
+                    self.start_palette_check(package);
+                }
+            }
+            Some(Action::Back) => {
+                self.palette = None;
+                self.palette_edited();
+            }
+            _ => {}
+        }
+    }
 
-        // Clone what the thread needs (system and wizard state are not Send,
-        // so we extract the data we need)
-        let system = self.system.clone();
+    /// The palette entry changed: its suggestions are stale, and brew is
+    /// searched again once typing pauses
+    fn palette_edited(&mut self) {
+        self.suggestions.clear();
+        self.suggestion_cursor = None;
+        self.palette_typed_at = Some(Instant::now()).filter(|_| self.palette.is_some());
+    }
+
+    /// Typing a name for a new preset; Enter saves, Esc cancels
+    fn handle_preset_name_input(&mut self, key: KeyCode) {
+        let Some(name) = &mut self.preset_name else {
+            return;
+        };
+        if let KeyCode::Char(c) = key {
+            name.push(c);
+            return;
+        }
+        if key == KeyCode::Backspace {
+            name.pop();
+            return;
+        }
+        match self.keymap.resolve(key, &[Action::Confirm, Action::Back]) {
+            Some(Action::Confirm) => {
+                let saved = preset::Preset::from_selection(name, &self.wizard)
+                    .and_then(|p| p.save(&self.system).map(|path| (p, path)));
+                self.preset_status = Some(saved.map(|(p, path)| {
+                    format!(
+                        "Saved {} apps as \"{}\" to {}",
+                        p.apps.len(),
+                        p.name,
+                        path.display()
+                    )
+                }));
+                self.preset_name = None;
+            }
+            Some(Action::Back) => {
+                self.preset_name = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Start the install, asking for the sudo password first if it needs
+    /// one. A sudo prompt mid-install would sit hidden behind the TUI.
+    fn confirm_install(&mut self) {
+        if !self.take_run_lock() {
+            return;
+        }
+        let apps = self.wizard.get_apps_to_install();
+        let needs_sudo =
+            executor::needs_sudo(&apps, &self.system) || locale::needs_sudo(&self.wizard);
+        if needs_sudo && !self.wizard.dry_run && !executor::sudo_ready() {
+            self.sudo_prompt = Some(Secret::default());
+        } else {
+            self.start_installation();
+            self.wizard.advance();
+        }
+    }
+
+    /// Take the run lock, unless it's already held. When another run
+    /// has it, a modal says so and `false` comes back.
+    fn take_run_lock(&mut self) -> bool {
+        if self.run_lock.is_some() {
+            return true;
+        }
+        match lock::acquire(&self.system, self.force) {
+            Ok(lock) => {
+                self.run_lock = Some(lock);
+                true
+            }
+            Err(e) => {
+                self.modal = Some(busy_modal(e));
+                false
+            }
+        }
+    }
+
+    /// Past the opening screens and not installed yet, so quitting loses
+    /// the answers given so far
+    fn has_unsaved_answers(&self) -> bool {
+        !matches!(
+            self.wizard.phase,
+            WizardPhase::Boot
+                | WizardPhase::Hub
+                | WizardPhase::Audit
+                | WizardPhase::Upgrade
+                | WizardPhase::Preflight
+                | WizardPhase::Install
+                | WizardPhase::Complete
+        )
+    }
+
+    fn handle_modal_input(&mut self, key: KeyCode) {
+        let context = [Action::Confirm, Action::Yes, Action::Back, Action::No];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Confirm | Action::Yes) => {
+                if let Some(modal) = self.modal.take() {
+                    self.run_modal_action(modal.action);
+                }
+            }
+            Some(Action::Back | Action::No) => {
+                // A no to the token is an answer, not a cancel
+                let action = self.modal.take().map(|m| m.action);
+                if action == Some(ModalAction::UseEnvToken) {
+                    self.wizard.use_env_token = false;
+                    self.wizard.advance();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn run_modal_action(&mut self, action: ModalAction) {
+        match action {
+            ModalAction::Quit => {
+                self.should_quit = true;
+            }
+            ModalAction::AbortInstall => {
+                // Drop the receiver so the thread is detached. The
+                // checkpoint file stays, for resuming next time.
+                self.install_receiver = None;
+                self.checkpoint = None;
+                crashreport::unwatch();
+                self.is_installing = false;
+                self.phase_times.finish();
+                self.save_session_stats();
+                if self.following.take().is_some() {
+                    self.log("[DETACH] Stopped following; the install carries on. `loadstar attach` to watch it again");
+                } else {
+                    self.log("[ABORT] Installation interrupted by user");
+                }
+                self.wizard.phase = WizardPhase::Complete;
+            }
+            ModalAction::Install => self.confirm_install(),
+            ModalAction::UseEnvToken => {
+                self.wizard.use_env_token = true;
+                self.wizard.advance();
+            }
+        }
+    }
+
+    fn handle_sudo_input(&mut self, key: KeyCode) {
+        let Some(password) = &mut self.sudo_prompt else {
+            return;
+        };
+        if type_into(password, key) {
+            return;
+        }
+        match self.keymap.resolve(key, &[Action::Confirm, Action::Back]) {
+            Some(Action::Confirm) => {
+                // Empty goes ahead without; whatever needs sudo then fails
+                // and says so in the log
+                let password = self.sudo_prompt.take().unwrap_or_default();
+                self.install_options.sudo_password = (!password.is_empty()).then_some(password);
+                self.start_installation();
+                self.wizard.advance();
+            }
+            Some(Action::Back) => {
+                self.sudo_prompt = None;
+                self.run_lock = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// The text field typing goes to right now, if any
+    fn focused_field(&mut self) -> Option<&mut dyn TextField> {
+        if let Some(password) = self.sudo_prompt.as_mut() {
+            return Some(password);
+        }
+        if let Some(name) = self.preset_name.as_mut() {
+            return Some(name);
+        }
+        if let Some(entry) = self.palette.as_mut() {
+            return Some(entry);
+        }
+        if self.ssh_key_picker.is_some() {
+            return None;
+        }
+        let wizard = &mut self.wizard;
+        let row = wizard.cursor_position.checked_sub(1);
+        match wizard.phase {
+            WizardPhase::Identity => match wizard.input_field {
+                0 => Some(&mut wizard.identity.name),
+                1 => Some(&mut wizard.identity.email),
+                2 => Some(&mut wizard.identity.github_username),
+                _ => None,
+            },
+            WizardPhase::GitHub if wizard.cursor_position == 1 => Some(&mut wizard.ssh_passphrase),
+            WizardPhase::Kubernetes => {
+                let field = row.and_then(|i| wizard.kube.source.fields().get(i))?;
+                Some(wizard.kube.field_mut(*field))
+            }
+            WizardPhase::Atuin => {
+                let field = row.and_then(|i| wizard.atuin.sync.fields().get(i))?;
+                Some(wizard.atuin.field_mut(*field))
+            }
+            WizardPhase::AiTools => {
+                let field = row.and_then(|i| wizard.ai.source.fields().get(i))?;
+                Some(wizard.ai.field_mut(*field))
+            }
+            _ => None,
+        }
+    }
+
+    /// A paste arrives whole rather than as keystrokes, so nothing in it
+    /// can set off a binding on the way in
+    fn handle_paste(&mut self, text: &str) {
+        if let Some(field) = self.focused_field() {
+            field.paste(text);
+        }
+        if self.palette.is_some() {
+            self.palette_edited();
+        }
+    }
+
+    fn handle_review_input(&mut self, key: KeyCode) {
+        if self.preset_name.is_some() {
+            self.handle_preset_name_input(key);
+            return;
+        }
+        if self.sudo_prompt.is_some() {
+            self.handle_sudo_input(key);
+            return;
+        }
+
+        let context = [
+            Action::Confirm,
+            Action::Yes,
+            Action::Back,
+            Action::No,
+            Action::Up,
+            Action::Down,
+            Action::Export,
+            Action::ExportPlan,
+            Action::SavePreset,
+        ];
+        match self.keymap.resolve(key, &context) {
+            Some(Action::Confirm | Action::Yes) => {
+                let replaced: Vec<String> = self
+                    .planned_configs()
+                    .iter()
+                    .filter(|c| c.path.exists())
+                    .map(|c| c.label.to_string())
+                    .collect();
+                if replaced.is_empty() {
+                    self.confirm_install();
+                } else {
+                    self.modal = Some(Modal {
+                        title: "REPLACE CONFIGS",
+                        message: format!(
+                            "This replaces {} you already have: {}. Each is backed up \
+                             next to itself as *.load-backup first.",
+                            if replaced.len() == 1 {
+                                "a config file".to_string()
+                            } else {
+                                format!("{} config files", replaced.len())
+                            },
+                            replaced.join(", ")
+                        ),
+                        confirm: "install",
+                        action: ModalAction::Install,
+                    });
+                }
+            }
+            Some(Action::Back | Action::No) => {
+                self.wizard.go_back();
+            }
+            Some(Action::Up) => {
+                self.wizard.scroll_offset = self.wizard.scroll_offset.saturating_sub(1);
+            }
+            Some(Action::Down) => {
+                self.wizard.scroll_offset += 1;
+            }
+            Some(Action::Export) => {
+                self.export_status = Some(self.export_devcontainer());
+            }
+            Some(Action::ExportPlan) => {
+                self.plan_status = Some(
+                    std::env::current_dir()
+                        .map_err(|e| e.to_string())
+                        .and_then(|dir| {
+                            plan::write(&plan::build(&self.wizard, &self.system), &dir)
+                        }),
+                );
+            }
+            Some(Action::SavePreset) => {
+                self.preset_name = Some(String::new());
+            }
+            // Digits jump to the category of the matching overlap
+            None => {
+                if let KeyCode::Char(c @ '1'..='9') = key {
+                    let index = c as usize - '1' as usize;
+                    if let Some((overlap, _)) = self.wizard.overlaps().get(index) {
+                        self.wizard.jump_to_category(overlap.category);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Fold one message from the install thread into the log and counts
+    fn apply_install_message(&mut self, message: InstallMessage) {
+        self.summary.record(&message);
+        self.phase_times.record(&message);
+        if let Some(checkpoint) = &mut self.checkpoint {
+            if checkpoint.record(&message) {
+                // Not worth a warning per package; the install goes on
+                let _ = checkpoint.save(&self.system);
+            }
+        }
+
+        match message {
+            InstallMessage::PhaseStart { phase } => {
+                self.narrate(format!("Installing: {}", phase));
+                self.log(format!("[PHASE] ═══ {} ═══", phase));
+            }
+            InstallMessage::PackageStart {
+                name,
+                method,
+                timeout_ms,
+            } => {
+                self.current_package = Some(name.clone());
+                self.package_started_at = Some(Instant::now());
+                self.package_limit = timeout_ms.map(Duration::from_millis);
+                self.log(format!("[INSTALL] {} ({})", name, method));
+            }
+            InstallMessage::PackageSuccess { name, duration_ms } => {
+                self.narrate(format!("Installed {}", name));
+                if let Some(guess) = self.guessed.remove(&name) {
+                    self.session.guessed_ms += guess.as_millis() as u64;
+                    self.session.guessed_actual_ms += duration_ms;
+                }
+                self.finish_work(&name);
+                self.log(format!(
+                    "[OK] {} ({:.1}s)",
+                    name,
+                    duration_ms as f64 / 1000.0
+                ));
+            }
+            InstallMessage::PackageSkipped { name, reason } => {
+                self.finish_work(&name);
+                self.log(format!("[SKIP] {} — {}", name, reason));
+            }
+            InstallMessage::PackageFailed { name, error } => {
+                self.narrate(format!("{} failed: {}", name, error));
+                self.finish_work(&name);
+                self.log(format!("[FAIL] {} — {}", name, error));
+            }
+            InstallMessage::Log(line) => {
+                self.log(line);
+            }
+            InstallMessage::Warning(warning) => {
+                self.log(format!("  [WARN] {}", warning));
+            }
+            InstallMessage::NextStep { command, note } => {
+                self.log(format!("  [NEXT] {} — {}", command, note));
+                self.next_steps.push(nextsteps::NextStep { command, note });
+            }
+            InstallMessage::GitConfigSet { key, old, new } => {
+                self.log(github::describe_git_change(&key, old.as_deref(), &new));
+            }
+            // Counted above; the log line before it said so
+            InstallMessage::ConfigWritten { .. } => {}
+            InstallMessage::Progress { completed, total } => {
+                self.install_completed = completed;
+                self.install_total = total;
+                self.update_progress();
+            }
+            InstallMessage::Done {
+                succeeded,
+                failed,
+                skipped,
+            } => {
+                self.narrate(format!(
+                    "Install done: {} succeeded, {} failed, {} skipped",
+                    succeeded, failed, skipped
+                ));
+                self.log(format!(
+                    "[DONE] {} succeeded, {} failed, {} skipped",
+                    succeeded, failed, skipped
+                ));
+            }
+            InstallMessage::FatalError(err) => {
+                self.narrate(format!("Install stopped: {}", err));
+                self.log(format!("[FATAL] {}", err));
+                self.error_message = Some(err.clone());
+                self.save_crash_report(&err);
+            }
+        }
+    }
+
+    /// The install thread is gone: save the checklist and move on
+    fn finish_install(&mut self, result: Result<(), String>) {
+        // A run that panicked or stopped on a fatal error stays resumable
+        if self.checkpoint.take().is_some() && result.is_ok() && self.error_message.is_none() {
+            Checkpoint::clear(&self.system);
+        }
+        if let Err(panic) = result {
+            let err = format!("Install thread panicked: {}", panic);
+            self.log(format!("[FATAL] {}", err));
+            self.error_message = Some(err.clone());
+            // The panic hook got there first where it could
+            match crashreport::take_saved() {
+                Some(path) => {
+                    self.log(format!(
+                        "[CRASH] Diagnostic report saved to {}",
+                        path.display()
+                    ));
+                    self.crash_report = Some(path);
+                }
+                None => self.save_crash_report(&err),
+            }
+        }
+        crashreport::unwatch();
+
+        self.install_receiver = None;
+        self.following = None;
+        self.is_installing = false;
+        self.attract = None;
+        self.install_progress = 100.0;
+        self.phase_times.finish();
+        // A dry run's timings would throw off the estimates
+        if !self.wizard.dry_run {
+            self.save_session_stats();
+        }
+        // Nothing left to abort
+        if self.modal.as_ref().map(|m| m.action) == Some(ModalAction::AbortInstall) {
+            self.modal = None;
+        }
+        if !self.next_steps.is_empty() && !self.wizard.dry_run {
+            match nextsteps::save(&self.next_steps, &self.system) {
+                Ok(path) => self.log(format!("[NEXT] Checklist saved to {}", path.display())),
+                Err(e) => self.apply_install_message(InstallMessage::Warning(format!(
+                    "Could not save the next-steps checklist: {}",
+                    e
+                ))),
+            }
+        }
+        // A run that stopped on a fatal error hasn't set anything up
+        if self.error_message.is_none() && !self.wizard.dry_run {
+            if let Err(e) = hub::mark_installed(&self.system) {
+                self.apply_install_message(InstallMessage::Warning(format!(
+                    "Could not note the install in {}: {}",
+                    hub::state_dir(&self.system).display(),
+                    e
+                )));
+            }
+        }
+        self.run_lock = None;
+        self.log("[COMPLETE] Installation finished!");
+        self.wizard.advance();
+    }
+
+    /// Fill in the install's numbers and add the session to the history
+    /// the estimates learn from
+    fn save_session_stats(&mut self) {
+        let ms = |took: &Duration| took.as_millis() as u64;
+        let closed = |times: &events::PhaseTimes| {
+            times
+                .phases
+                .iter()
+                .map(|(n, t)| (n.clone(), ms(t)))
+                .collect()
+        };
+        self.session.screens = closed(&self.screen_times);
+        self.session.phases = closed(&self.phase_times);
+        self.session.install_ms = self.phase_times.total.as_ref().map_or(0, ms);
+        self.session.installed = self.summary.succeeded.len();
+        if let Err(e) = stats::append(&self.session, &self.system) {
+            self.apply_install_message(InstallMessage::Warning(format!(
+                "Could not save this session's stats: {}",
+                e
+            )));
+        }
+    }
+
+    /// Add a line to the install log, stamped with the run time so far
+    fn log(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        crashreport::note(&text);
+        self.install_log.push(LogLine {
+            elapsed: self.phase_times.elapsed(),
+            text,
+        });
+    }
+
+    /// Write a diagnostic report for a dead install and log where it went
+    fn save_crash_report(&mut self, error: &str) {
+        let log: Vec<String> = self.install_log.iter().map(|l| l.to_string()).collect();
+        match crashreport::save(error, &log, &self.wizard, &self.system) {
+            Ok(path) => {
+                self.log(format!(
+                    "[CRASH] Diagnostic report saved to {}",
+                    path.display()
+                ));
+                self.crash_report = Some(path);
+            }
+            Err(e) => self.apply_install_message(InstallMessage::Warning(format!(
+                "Could not save the diagnostic report: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Write .devcontainer/ into the working directory
+    fn export_devcontainer(&self) -> Result<String, String> {
+        let dir = std::env::current_dir().map_err(|e| e.to_string())?;
+        let export = devcontainer::export(&self.wizard, &dir)?;
+        let mut status = format!(
+            "Wrote {} ({} tools)",
+            dir.join(".devcontainer").display(),
+            export.tools
+        );
+        if !export.skipped.is_empty() {
+            status.push_str(&format!("; left out {}", export.skipped.join(", ")));
+        }
+        Ok(status)
+    }
+
+    fn get_current_list_len(&self) -> usize {
+        self.wizard.current_apps().len()
+    }
+
+    fn toggle_current_app(&mut self) {
+        let app_id = self.get_current_app_id();
+        if let Some(id) = app_id {
+            self.wizard.toggle_app(&id);
+        }
+    }
+
+    fn cycle_current_app_mode(&mut self) {
+        if let Some(id) = self.get_current_app_id() {
+            self.wizard.cycle_app_mode(&id);
+        }
+    }
+
+    fn get_current_app_id(&self) -> Option<String> {
+        self.wizard
+            .current_apps()
+            .get(self.wizard.cursor_position)
+            .map(|a| a.id.to_string())
+    }
+
+    fn select_all_in_category(&mut self) {
+        for app in self.wizard.current_apps() {
+            self.wizard.selected_apps.insert(app.id.to_string());
+        }
+        self.wizard.include_dependencies();
+    }
+
+    fn deselect_all_in_category(&mut self) {
+        for app in self.wizard.current_apps() {
+            self.wizard.selected_apps.remove(app.id.as_str());
+        }
+        // Put back anything a selection elsewhere still needs
+        self.wizard.include_dependencies();
+    }
+
+    fn start_installation(&mut self) {
+        let mut apps: Vec<&'static catalog::App> = self.wizard.get_apps_to_install();
+        if self.detach {
+            self.start_detached();
+            return;
+        }
+        // Saved before the first package, so even that can be resumed
+        let resumed = self.resuming.take();
+        if !self.wizard.dry_run {
+            let checkpoint = match &resumed {
+                Some(previous) => previous.resumed_as(&self.wizard),
+                None => Checkpoint::new(&self.wizard),
+            };
+            apps = checkpoint.remaining();
+            self.checkpoint = Some(checkpoint);
+        }
+        let saved = self.checkpoint.as_ref().map(|c| c.save(&self.system));
         let wizard_clone = self.wizard.clone();
-        let apps: Vec<&'static catalog::App> = selected_apps;
+        let options = self.install_options.clone();
 
+        self.spawn_install(&apps.clone(), move |system, tx| {
+            install_everything(&wizard_clone, system, apps, &options, tx);
+        });
+        if let Some(Err(e)) = saved {
+            self.apply_install_message(InstallMessage::Warning(format!(
+                "Could not save a checkpoint, so this run can't be resumed: {}",
+                e
+            )));
+        }
+        if let Some(previous) = resumed {
+            self.log(format!(
+                "[RESUME] Picking up the interrupted install: {}",
+                previous.progress()
+            ));
+        }
+    }
+
+    /// Hand the install to a process of its own and follow its event log.
+    /// The job keeps the checkpoint, picking up from the one being resumed.
+    fn start_detached(&mut self) {
+        let mut job = job::Job::new(&self.wizard, &self.install_options, &self.system);
+        job.resume = self.resuming.clone();
+        let apps = match &job.resume {
+            Some(previous) => previous.resumed_as(&self.wizard).remaining(),
+            None => self.wizard.get_apps_to_install(),
+        };
+        let started = std::env::current_exe()
+            .map_err(|e| e.to_string())
+            .and_then(|program| job.spawn(&program));
+        match started {
+            Ok(pid) => {
+                // The job takes the lock over, and is running from here on
+                // even before it gets to say so itself
+                let handed_over = match self.run_lock.take() {
+                    Some(lock) => lock.hand_over(pid),
+                    None => Ok(()),
+                };
+                let marked = job::mark_running(&job.events, pid);
+                self.resuming = None;
+                self.follow_job(
+                    job::Running {
+                        pid,
+                        events: job.events,
+                    },
+                    &apps,
+                );
+                self.log(format!(
+                    "[DETACH] Installing in the background (pid {}); closing this terminal won't stop it. `loadstar attach` picks it back up",
+                    pid
+                ));
+                if let Err(e) = handed_over {
+                    self.apply_install_message(InstallMessage::Warning(format!(
+                        "Could not hand the run lock to the background install: {}",
+                        e
+                    )));
+                }
+                if let Err(e) = marked {
+                    self.apply_install_message(InstallMessage::Warning(format!(
+                        "Could not mark the background install as running: {}",
+                        e
+                    )));
+                }
+            }
+            Err(e) => {
+                self.detach = false;
+                self.start_installation();
+                // After the start, which clears the log and the counts
+                self.apply_install_message(InstallMessage::Warning(format!(
+                    "{}; installing here instead",
+                    e
+                )));
+            }
+        }
+    }
+
+    /// Show a background install on the Install screen: everything it has
+    /// logged so far, then each new event as it's written
+    fn follow_job(&mut self, running: job::Running, apps: &[&'static catalog::App]) {
+        self.begin_install(apps);
         let (tx, rx) = mpsc::channel();
         self.install_receiver = Some(rx);
+        let job = running.clone();
+        self.install_thread = Some(std::thread::spawn(move || {
+            job::follow(&job.events, || job.is_running(), &tx);
+        }));
+        self.following = Some(running);
+    }
 
-        let handle = std::thread::spawn(move || {
-            // Phase 1: Install packages
-            let _summary = executor::run_install(&system, apps, &tx);
+    /// How long the running package has taken and the most it may, once
+    /// it's past half of that
+    pub fn budget_used(&self) -> Option<(Duration, Duration)> {
+        let timeout = self.package_limit?;
+        let taken = self.package_started_at?.elapsed();
+        (taken >= timeout / 2).then_some((taken, timeout))
+    }
+
+    /// A package is through: its expected time counts as done
+    fn finish_work(&mut self, name: &str) {
+        self.current_package = None;
+        self.package_started_at = None;
+        self.package_limit = None;
+        self.work_done += self.work_left.remove(name).unwrap_or(0.0);
+        self.update_progress();
+    }
+
+    /// Progress by expected time when known, with the running package
+    /// credited for its time so far (up to 90% of what it should take, so
+    /// a slow one doesn't look finished); by package count otherwise
+    fn update_progress(&mut self) {
+        if self.work_total > 0.0 {
+            let running = self
+                .current_package
+                .as_ref()
+                .and_then(|name| self.work_left.get(name))
+                .zip(self.package_started_at)
+                .map(|(expected, started)| started.elapsed().as_secs_f64().min(expected * 0.9))
+                .unwrap_or(0.0);
+            self.install_progress =
+                ((self.work_done + running) / self.work_total * 100.0).min(100.0);
+        } else if self.install_total > 0 {
+            self.install_progress =
+                (self.install_completed as f64 / self.install_total as f64) * 100.0;
+        }
+    }
+
+    /// Run `job` on the install thread, with the Install screen showing
+    /// what it sends. `apps` are the packages it installs, weighted by how
+    /// long each should take; a job with none goes by its own counts.
+    fn spawn_install(
+        &mut self,
+        apps: &[&'static catalog::App],
+        job: impl FnOnce(&SystemInfo, &mpsc::Sender<InstallMessage>) + Send + 'static,
+    ) {
+        self.begin_install(apps);
+        crashreport::watch(&self.wizard, &self.system);
+        self.log("[INIT] Starting installation sequence...");
+
+        // Clone what the thread needs (system and wizard state are not Send,
+        // so we extract the data we need)
+        let system = self.system.clone();
+        let dry_run = self.wizard.dry_run;
+        let (out_tx, rx) = mpsc::channel();
+        self.install_receiver = Some(rx);
 
-            // Phase 2: Git & GitHub setup (SSH keys, git config, gh auth)
-            github::setup_github(&wizard_clone, &system, &tx);
+        let handle = std::thread::spawn(move || {
+            // Everything sent on tx is recorded to the run's event log
+            // before it reaches the UI. Not a dry run's: the history and
+            // estimates would take it for a real one.
+            let (tx, recorder) = if dry_run {
+                (out_tx, None)
+            } else {
+                let (tx, recorder) = events::Recorder::start(&system, out_tx);
+                (tx, Some(recorder))
+            };
+            job(&system, &tx);
 
-            // Phase 3: Generate config files
-            config::generate_configs(&wizard_clone, &system, &tx);
+            // The thread counts as finished once the last message is through
+            drop(tx);
+            if let Some(recorder) = recorder {
+                recorder.finish();
+            }
         });
 
         self.install_thread = Some(handle);
     }
+
+    /// Reset the Install screen for a run of `apps`
+    fn begin_install(&mut self, apps: &[&'static catalog::App]) {
+        self.is_installing = true;
+        self.install_progress = 0.0;
+        self.install_completed = 0;
+        self.install_total = apps.len();
+        let estimates = estimate::Estimates::load(&self.system);
+        self.work_left = apps
+            .iter()
+            .map(|app| (app.name.to_string(), estimates.expected(app).as_secs_f64()))
+            .collect();
+        self.guessed = apps
+            .iter()
+            .filter(|app| estimates.is_guess(app))
+            .map(|app| (app.name.to_string(), estimate::guess(app)))
+            .collect();
+        self.session.guessed_ms = 0;
+        self.session.guessed_actual_ms = 0;
+        self.work_done = 0.0;
+        self.work_total = self.work_left.values().sum();
+        self.summary = InstallSummary::default();
+        self.next_steps.clear();
+        self.install_log.clear();
+        self.phase_times = events::PhaseTimes::default();
+    }
+}
+
+/// The whole install: packages, then every setup step that depends on them
+fn install_everything(
+    wizard: &WizardState,
+    system: &SystemInfo,
+    apps: Vec<&'static catalog::App>,
+    options: &InstallOptions,
+    tx: &mpsc::Sender<InstallMessage>,
+) {
+    // Phase 1: Install packages
+    let _summary = executor::run_install(system, apps, options, tx);
+
+    // Phase 2: Git & GitHub setup (SSH keys, git config, gh auth)
+    github::setup_github(wizard, system, tx);
+
+    // Phase 3: Generate config files
+    config::generate_configs(wizard, system, tx);
+
+    // Phase 4: Locale exports and the system timezone
+    locale::setup_locale(wizard, system, tx);
+
+    // Phase 5: Cluster credentials and default namespace
+    kube::setup_kube(wizard, system, tx);
+
+    // Phase 6: Cloud CLI profile stubs and login reminders
+    cloud::setup_cloud(wizard, system, tx);
+
+    // Phase 7: Atuin history import and sync account
+    atuin::setup_atuin(wizard, system, tx);
+
+    // Phase 8: AI tool API keys and aichat config
+    ai::setup_ai(wizard, system, tx);
+
+    // Phase 9: Pull a model for Ollama that fits this machine
+    localmodel::setup_local_model(wizard, system, options.package_timeout, tx);
+
+    // Phase 10: Make sure everything just installed is on PATH
+    pathcheck::verify_path(wizard, system, tx);
+
+    // Phase 11: Check the shell integrations load in a new shell
+    hookcheck::verify_hooks(wizard, system, options.package_timeout, tx);
 }
 
 fn main() -> Result<()> {
-    // Initialize terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let mut cli = Cli::parse(std::env::args().skip(1))
+        .map_err(|e| anyhow::anyhow!("{}\n\n{}", e, cli::USAGE))?;
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if cli.version {
+        println!("loadstar {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+    if cli.cloud_init {
+        cloudinit::apply_env(&mut cli, |name| std::env::var(name).ok());
+    }
+    crashreport::install_hook();
+
+    // Detect the system and load the profile before touching the terminal,
+    // so a bad profile reports a readable error instead of a garbled screen
+    let system = SystemInfo::detect()?;
+    if cli.check_catalog {
+        let gaps = coverage::check(&system);
+        for gap in &gaps {
+            println!("{:<20} {}", gap.app.as_str(), gap.reason);
+        }
+        println!("{} app(s) can't be installed on this machine", gaps.len());
+        if !gaps.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if cli.command == Some(Subcommand::CatalogCheck) {
+        let report = health::run(&github::SystemRunner).map_err(anyhow::Error::msg)?;
+        println!("{}", report.summary());
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // `--profile gh:owner/repo` is a backup on GitHub; it's fetched to a
+    // file so everything after can treat it like any other profile
+    if let Some(remote) = cli
+        .profile
+        .as_deref()
+        .and_then(|path| path.to_str())
+        .and_then(backup::Remote::parse)
+    {
+        let content = backup::pull(&github::SystemRunner, &remote).map_err(anyhow::Error::msg)?;
+        let path = hub::state_dir(&system).join(PULLED_PROFILE);
+        std::fs::create_dir_all(hub::state_dir(&system))?;
+        std::fs::write(&path, content)?;
+        println!(
+            "Pulled the profile from {}/{} to {}",
+            remote.owner,
+            remote.repo,
+            path.display()
+        );
+        cli.profile = Some(path);
+    }
+    // `--onboarding <url>` is an org's team profile, fetched the same way
+    if let Some(url) = &cli.onboarding {
+        let content = onboarding::fetch(&github::SystemRunner, url).map_err(anyhow::Error::msg)?;
+        let path = hub::state_dir(&system).join(ONBOARDING_PROFILE);
+        std::fs::create_dir_all(hub::state_dir(&system))?;
+        std::fs::write(&path, content)?;
+        println!(
+            "Fetched the onboarding profile from {} to {}",
+            url,
+            path.display()
+        );
+        cli.profile = Some(path);
+    }
+    let profile = match &cli.profile {
+        Some(path) if !path.exists() => {
+            anyhow::bail!("Profile not found: {}", path.display())
+        }
+        Some(path) => Profile::load(path, &system.hostname)?,
+        None => Profile::load(&Profile::default_path(&system), &system.hostname)?,
+    };
+
+    if cli.command == Some(Subcommand::Job) {
+        return detach::run(&system);
+    }
 
-    // Create app
-    let mut app = App::new()?;
+    if cli.command == Some(Subcommand::Test) {
+        let path = cli.profile.as_deref().expect("test requires --profile");
+        let image = cli.image.as_deref().unwrap_or(cli::DEFAULT_TEST_IMAGE);
+        let report = selftest::run(path, image, &[], &system)?;
+        println!("{}", report.summary());
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut app = App::new(system, &profile, &cli)?;
+    if cli.cloud_init {
+        cloudinit::seed(&mut app.wizard, &app.system, &profile, |name| {
+            std::env::var(name).ok()
+        })
+        .map_err(anyhow::Error::msg)?;
+    }
+    app.start_preflight();
+
+    if cli.command == Some(Subcommand::Bench) {
+        if cli.demo {
+            println!("{}", bench::run_demo(app.wizard.get_apps_to_install()));
+        } else {
+            let path = cli.profile.as_deref().expect("bench requires --profile");
+            let image = cli.image.as_deref().unwrap_or(cli::DEFAULT_TEST_IMAGE);
+            println!("{}", bench::run_containers(path, image, &app.system)?);
+        }
+        return Ok(());
+    }
+
+    // Before the TUI takes the terminal: age may need to ask for a passphrase
+    if cli.command.is_none() && !profile.secrets.is_empty() {
+        if cli.cloud_init {
+            eprintln!("[WARN] --cloud-init never prompts, so the profile's secrets stay locked");
+        } else if app.system.find_tool("age").is_some() {
+            sealed::open(&profile.secrets, &app.system)
+                .map_err(anyhow::Error::msg)?
+                .apply(&mut app.wizard);
+        } else {
+            eprintln!("[WARN] age isn't installed, so the profile's secrets stay locked; the wizard asks for them instead");
+        }
+    }
+
+    if cli.command == Some(Subcommand::Apply) {
+        let path = cli.plan.as_deref().expect("apply requires a plan");
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read {}: {}", path.display(), e))?;
+        let plan = plan::Plan::parse(&text).map_err(anyhow::Error::msg)?;
+        let apps = plan.check(&app.system).map_err(anyhow::Error::msg)?;
+        let run_lock = lock::acquire(&app.system, cli.force)
+            .map_err(|e| anyhow::anyhow!("{}; --force runs anyway", e))?;
+        let summary = headless::apply(plan, apps, &app.system, &app.install_options);
+        drop(run_lock);
+        if !summary.failed.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.headless {
+        // No one to ask; `--github` (which `--cloud-init` turns on for a
+        // token) is the say-so
+        app.wizard.use_env_token = cli.github;
+        let run_lock = lock::acquire(&app.system, cli.force)
+            .map_err(|e| anyhow::anyhow!("{}; --force runs anyway", e))?;
+        let summary = if cli.cloud_init {
+            cloudinit::run(&app.wizard, &app.system, &app.install_options, cli.github)
+        } else {
+            headless::run(&app.wizard, &app.system, &app.install_options, cli.github)
+        };
+        drop(run_lock);
+        if !app.wizard.dry_run {
+            if let Err(e) = hub::mark_installed(&app.system) {
+                println!("  [WARN] Could not note the install: {}", e);
+            }
+        }
+        if !summary.failed.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.command == Some(Subcommand::Attach) {
+        let Some(running) = job::running(&app.system) else {
+            anyhow::bail!("No install is running in the background");
+        };
+        app.follow_job(running, &[]);
+        app.wizard.go_to(WizardPhase::Install);
+    }
+
+    // Another run installing already; attaching is how to watch it
+    if cli.command.is_none() && !cli.force {
+        if let Some(pid) = lock::holder(&app.system) {
+            app.modal = Some(busy_modal(lock::busy(pid)));
+        }
+    }
+
+    // Ctrl+Z from another process (kill -TSTP) goes through the same
+    // suspend path as the key, so the terminal is always restored first
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGTSTP, app.suspend_signal.clone())?;
+
+    // Initialize terminal. Narration redirected away from it leaves stdout
+    // to the narrator, so the TUI draws on the controlling terminal.
+    let on_tty = cli.narrate && !io::stdout().is_terminal();
+    let mut terminal = setup_terminal(on_tty)?;
+    let size = terminal.size()?;
+    app.reduce(AppEvent::Resize(size.width, size.height));
 
     // Main loop
     let result = run_app(&mut terminal, &mut app);
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    restore_terminal(&mut terminal)?;
+    if let Some(narrator) = &mut app.narrator {
+        narrator.release();
+    }
 
     // Handle result
     if let Err(e) = result {
@@ -636,117 +3192,214 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> Result<()> {
+/// Hand `url` to the desktop's browser. Over SSH there may be none to
+/// hand it to, so callers show the URL as well.
+fn open_url(url: &str, os: Os) -> Result<(), String> {
+    let program = match os {
+        Os::MacOS => "open",
+        _ => "xdg-open",
+    };
+    let mut child = std::process::Command::new(program)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not run {}: {}", program, e))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Send a typed character or Backspace to a text field. `false` means the
+/// key wasn't one of those, so the screen's own bindings get it.
+fn type_into(field: &mut dyn TextField, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char(c) => field.push(c),
+        KeyCode::Backspace => field.pop(),
+        _ => return false,
+    }
+    true
+}
+
+/// Where the TUI draws: stdout, or the controlling terminal when stdout is
+/// taken by `--narrate`
+type Screen = Box<dyn Write>;
+
+fn setup_terminal(on_tty: bool) -> Result<Terminal<CrosstermBackend<Screen>>> {
+    let mut screen: Screen = if on_tty {
+        Box::new(std::fs::OpenOptions::new().write(true).open("/dev/tty")?)
+    } else {
+        Box::new(io::stdout())
+    };
+    enable_raw_mode()?;
+    execute!(
+        screen,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    let backend = CrosstermBackend::new(screen);
+    Ok(Terminal::new(backend)?)
+}
+
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Screen>>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Hand the terminal back to the shell and stop the process. Execution
+/// continues here after `fg` (SIGCONT), where raw mode and the alternate
+/// screen are re-entered and the whole frame is redrawn.
+#[cfg(unix)]
+fn suspend(
+    terminal: &mut Terminal<CrosstermBackend<Screen>>,
+    narrator: Option<&mut narrate::Narrator>,
+) -> Result<()> {
+    restore_terminal(terminal)?;
+    if let Some(narrator) = narrator {
+        narrator.release();
+    }
+
+    // SIGTSTP is caught by our own handler, so stop with SIGSTOP instead
+    signal_hook::low_level::raise(signal_hook::consts::SIGSTOP)?;
+
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    terminal.clear()?;
+    Ok(())
+}
+
+fn run_app(terminal: &mut Terminal<CrosstermBackend<Screen>>, app: &mut App) -> Result<()> {
     loop {
+        // Suspend on Ctrl+Z or an external SIGTSTP
+        if app.suspend_signal.swap(false, Ordering::Relaxed) {
+            #[cfg(unix)]
+            suspend(terminal, app.narrator.as_mut())?;
+        }
+
         // Draw
         terminal.draw(|frame| render_app(frame, app))?;
 
         // Handle input with timeout for animations
         if event::poll(app.tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                app.handle_key(key.code, key.modifiers);
+            match event::read()? {
+                Event::Key(key) => {
+                    app.reduce(AppEvent::Key(key.code, key.modifiers));
+                }
+                Event::Paste(text) => {
+                    app.reduce(AppEvent::Paste(Secret::new(text)));
+                }
+                Event::Resize(width, height) => {
+                    app.reduce(AppEvent::Resize(width, height));
+                    // Throw away the old buffer so nothing from the previous
+                    // geometry survives the next draw
+                    terminal.autoresize()?;
+                    terminal.clear()?;
+                }
+                _ => {}
             }
         }
 
         // Update animations
         if app.last_tick.elapsed() >= app.tick_rate {
-            app.tick();
-            app.last_tick = Instant::now();
+            app.reduce(AppEvent::Tick);
         }
 
-        // Process install messages from the background thread
-        if app.is_installing && app.wizard.phase == WizardPhase::Install {
-            // Drain all pending messages from the install thread
-            if let Some(rx) = &app.install_receiver {
-                while let Ok(msg) = rx.try_recv() {
-                    match msg {
-                        InstallMessage::PhaseStart { phase } => {
-                            app.install_log.push(format!("[PHASE] ═══ {} ═══", phase));
-                        }
-                        InstallMessage::PackageStart { name, method } => {
-                            app.current_package = Some(name.clone());
-                            app.package_started_at = Some(Instant::now());
-                            app.install_log
-                                .push(format!("[INSTALL] {} ({})", name, method));
-                        }
-                        InstallMessage::PackageSuccess { name, duration_ms } => {
-                            app.current_package = None;
-                            app.package_started_at = None;
-                            app.install_log.push(format!(
-                                "[OK] {} ({:.1}s)",
-                                name,
-                                duration_ms as f64 / 1000.0
-                            ));
-                            app.install_completed += 1;
-                            app.install_succeeded += 1;
-                        }
-                        InstallMessage::PackageSkipped { name, reason } => {
-                            app.current_package = None;
-                            app.package_started_at = None;
-                            app.install_log
-                                .push(format!("[SKIP] {} — {}", name, reason));
-                            app.skipped_packages
-                                .push((name.clone(), reason.clone()));
-                            app.install_completed += 1;
-                            app.install_skipped += 1;
-                        }
-                        InstallMessage::PackageFailed { name, error } => {
-                            app.current_package = None;
-                            app.package_started_at = None;
-                            app.install_log.push(format!("[FAIL] {} — {}", name, error));
-                            app.failed_packages.push((name.clone(), error.clone()));
-                            app.install_completed += 1;
-                            app.install_failed += 1;
-                        }
-                        InstallMessage::Log(line) => {
-                            app.install_log.push(line);
-                        }
-                        InstallMessage::Progress { completed, total } => {
-                            app.install_completed = completed;
-                            app.install_total = total;
-                        }
-                        InstallMessage::Done {
-                            succeeded,
-                            failed,
-                            skipped,
-                        } => {
-                            app.install_log.push(format!(
-                                "[DONE] {} succeeded, {} failed, {} skipped",
-                                succeeded, failed, skipped
-                            ));
-                        }
-                        InstallMessage::FatalError(err) => {
-                            app.install_log.push(format!("[FATAL] {}", err));
-                            app.error_message = Some(err);
-                        }
-                    }
-                }
+        // A check that panicked leaves an empty list rather than a
+        // screen stuck on "checking"
+        match app.preflight_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(result)) => app.reduce(AppEvent::Preflight(result)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => {
+                app.reduce(AppEvent::Preflight(system::PreflightResult {
+                    checks: Vec::new(),
+                }))
             }
-
-            // Update progress percentage
-            if app.install_total > 0 {
-                app.install_progress =
-                    (app.install_completed as f64 / app.install_total as f64) * 100.0;
+            _ => {}
+        }
+        match app.backup_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(result)) => app.reduce(AppEvent::Backup(result)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => app.reduce(AppEvent::Backup(Err(
+                "The backup stopped without a result".to_string(),
+            ))),
+            _ => {}
+        }
+        match app.palette_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(checked)) => app.reduce(AppEvent::Palette(checked)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => app.reduce(AppEvent::Palette(Err(
+                "The package check stopped without a result".to_string(),
+            ))),
+            _ => {}
+        }
+        match app.github_user_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(answer)) => app.reduce(AppEvent::GithubUser(answer)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => app.reduce(AppEvent::GithubUser((
+                String::new(),
+                Err("The lookup stopped without a result".to_string()),
+            ))),
+            _ => {}
+        }
+        match app.suggest_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok((entry, found))) => app.reduce(AppEvent::Suggestions(entry, found)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => {
+                app.reduce(AppEvent::Suggestions(String::new(), Vec::new()))
+            }
+            _ => {}
+        }
+        match app.audit_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(report)) => app.reduce(AppEvent::Audit(report)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => {
+                app.reduce(AppEvent::Audit(hub::AuditReport::default()))
+            }
+            _ => {}
+        }
+        match app.versions_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(versions)) => app.reduce(AppEvent::Versions(versions)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => {
+                app.reduce(AppEvent::Versions(HashMap::new()))
             }
+            _ => {}
+        }
+        match app.upgrades_receiver.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(outdated)) => app.reduce(AppEvent::Upgrades(outdated)),
+            Some(Err(mpsc::TryRecvError::Disconnected)) => app.reduce(AppEvent::Upgrades(Err(
+                "The check for upgrades stopped before brew answered".to_string(),
+            ))),
+            _ => {}
+        }
 
-            // Check if the install thread has finished
-            let thread_done = app
-                .install_thread
+        // Hand the install thread's messages over. Whether it had finished
+        // is checked first, so nothing it sent before exiting is missed.
+        if app.is_installing && app.wizard.phase == WizardPhase::Install {
+            let thread_done = app.install_thread.as_ref().is_some_and(|h| h.is_finished());
+
+            let messages: Vec<InstallMessage> = app
+                .install_receiver
                 .as_ref()
-                .map(|h| h.is_finished())
-                .unwrap_or(false);
+                .map(|rx| rx.try_iter().collect())
+                .unwrap_or_default();
+            for message in messages {
+                app.reduce(AppEvent::Install(message));
+            }
 
             if thread_done {
-                // Join the thread
                 if let Some(handle) = app.install_thread.take() {
-                    let _ = handle.join();
+                    let result = handle
+                        .join()
+                        .map_err(|payload| crashreport::panic_message(payload.as_ref()));
+                    app.reduce(AppEvent::InstallFinished(result));
                 }
-                app.install_receiver = None;
-                app.is_installing = false;
-                app.install_progress = 100.0;
-                app.install_log
-                    .push("[COMPLETE] Installation finished!".to_string());
-                app.wizard.advance();
             }
         }
 
@@ -758,3 +3411,817 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) ->
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use loadstar_core::fakerunner::{ok, FakeRunner};
+
+    fn app() -> App {
+        let mut system = SystemInfo::detect().unwrap();
+        system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-reduce-{}", std::process::id()));
+        // Headless skips the environment scan, so defaults don't depend on
+        // the machine running the tests
+        let cli = Cli {
+            headless: true,
+            ..Cli::default()
+        };
+        App::new(system, &Profile::default(), &cli).unwrap()
+    }
+
+    #[test]
+    fn install_messages_are_counted_once() {
+        let mut app = app();
+        for message in [
+            InstallMessage::PackageSuccess {
+                name: "ripgrep".to_string(),
+                duration_ms: 1200,
+            },
+            InstallMessage::PackageSkipped {
+                name: "git".to_string(),
+                reason: "Already installed".to_string(),
+            },
+            InstallMessage::PackageFailed {
+                name: "bat".to_string(),
+                error: "exit 1".to_string(),
+            },
+            InstallMessage::Progress {
+                completed: 3,
+                total: 4,
+            },
+        ] {
+            app.reduce(AppEvent::Install(message));
+        }
+
+        assert_eq!(app.summary.succeeded, ["ripgrep"]);
+        assert_eq!(app.summary.skipped.len(), 1);
+        assert_eq!(app.summary.failed[0].0, "bat");
+        assert_eq!(app.install_completed, 3);
+        assert_eq!(app.install_progress, 75.0);
+        assert_eq!(app.install_log.last().unwrap().text, "[FAIL] bat — exit 1");
+    }
+
+    #[test]
+    fn attaching_replays_what_the_background_job_logged() {
+        let dir = std::env::temp_dir().join(format!("loadstar-attach-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let events = dir.join("1.jsonl");
+        let (out_tx, _out) = mpsc::channel();
+        let (tx, recorder) = events::Recorder::start_at(&events, out_tx);
+        for message in [
+            InstallMessage::PackageSuccess {
+                name: "Ripgrep".to_string(),
+                duration_ms: 900,
+            },
+            InstallMessage::Progress {
+                completed: 1,
+                total: 2,
+            },
+        ] {
+            tx.send(message).unwrap();
+        }
+        drop(tx);
+        recorder.finish();
+
+        // No marker: the job has already finished
+        let mut app = app();
+        app.follow_job(
+            job::Running {
+                pid: std::process::id(),
+                events,
+            },
+            &[],
+        );
+        app.install_thread.take().unwrap().join().unwrap();
+        let messages: Vec<_> = app.install_receiver.as_ref().unwrap().try_iter().collect();
+        for message in messages {
+            app.reduce(AppEvent::Install(message));
+        }
+        assert_eq!(app.summary.succeeded, ["Ripgrep"]);
+        assert_eq!(app.install_progress, 50.0);
+        app.reduce(AppEvent::InstallFinished(Ok(())));
+        assert!(app.following.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn progress_follows_expected_time_not_package_count() {
+        let mut app = app();
+        app.work_left = [("Docker", 360.0), ("Ripgrep", 20.0), ("Bat", 20.0)]
+            .into_iter()
+            .map(|(name, secs)| (name.to_string(), secs))
+            .collect();
+        app.work_total = 400.0;
+        app.install_total = 3;
+
+        for message in [
+            InstallMessage::PackageSuccess {
+                name: "Ripgrep".to_string(),
+                duration_ms: 900,
+            },
+            InstallMessage::Progress {
+                completed: 1,
+                total: 3,
+            },
+            InstallMessage::PackageFailed {
+                name: "Bat".to_string(),
+                error: "exit 1".to_string(),
+            },
+            InstallMessage::Progress {
+                completed: 2,
+                total: 3,
+            },
+        ] {
+            app.reduce(AppEvent::Install(message));
+        }
+        // Two of three packages, but only a tenth of the work
+        assert_eq!(app.install_completed, 2);
+        assert_eq!(app.install_progress, 10.0);
+
+        // A package running long never looks finished
+        app.current_package = Some("Docker".to_string());
+        app.package_started_at = Some(Instant::now() - Duration::from_secs(600));
+        app.is_installing = true;
+        app.reduce(AppEvent::Tick);
+        assert_eq!(app.install_progress, 91.0);
+        // and past half its time limit it says so
+        let limit = Duration::from_secs(executor::PACKAGE_TIMEOUT);
+        app.package_limit = Some(limit);
+        assert_eq!(app.budget_used().map(|(_, l)| l), Some(limit));
+        app.package_started_at = Some(Instant::now());
+        assert_eq!(app.budget_used(), None);
+        app.package_started_at = Some(Instant::now() - Duration::from_secs(600));
+
+        app.reduce(AppEvent::Install(InstallMessage::PackageSuccess {
+            name: "Docker".to_string(),
+            duration_ms: 600_000,
+        }));
+        assert_eq!(app.install_progress, 100.0);
+    }
+
+    #[test]
+    fn a_batch_is_held_to_its_own_limit() {
+        let mut app = app();
+        let package = Duration::from_secs(executor::PACKAGE_TIMEOUT);
+        app.reduce(AppEvent::Install(InstallMessage::PackageStart {
+            name: "3 formulae".to_string(),
+            method: "brew install bat fd jq".to_string(),
+            timeout_ms: Some(package.as_millis() as u64 * 3),
+        }));
+        // Past one package's time, but not half the batch's
+        app.package_started_at = Some(Instant::now() - package);
+        assert_eq!(app.budget_used(), None);
+        app.package_started_at = Some(Instant::now() - package * 2);
+        assert_eq!(app.budget_used().map(|(_, l)| l), Some(package * 3));
+
+        // A step with no limit never warns
+        app.reduce(AppEvent::Install(InstallMessage::PackageStart {
+            name: "Docker".to_string(),
+            method: "brew install --cask docker".to_string(),
+            timeout_ms: None,
+        }));
+        app.package_started_at = Some(Instant::now() - package * 2);
+        assert_eq!(app.budget_used(), None);
+    }
+
+    #[test]
+    fn a_panicked_install_still_completes_with_a_report() {
+        let mut app = app();
+        app.wizard.phase = WizardPhase::Install;
+        app.is_installing = true;
+
+        app.reduce(AppEvent::InstallFinished(Err("oops".to_string())));
+
+        assert_eq!(app.wizard.phase, WizardPhase::Complete);
+        assert!(!app.is_installing);
+        assert_eq!(
+            app.error_message.as_deref(),
+            Some("Install thread panicked: oops")
+        );
+        let report = app.crash_report.take().unwrap();
+        assert!(report.exists());
+        let _ = std::fs::remove_dir_all(&app.system.config_dir);
+    }
+
+    #[test]
+    fn preflight_waits_for_its_results() {
+        let mut app = app();
+        app.wizard.returning = false;
+        app.wizard.advance();
+        assert_eq!(app.wizard.phase, WizardPhase::Preflight);
+
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Preflight);
+
+        app.reduce(AppEvent::Preflight(system::PreflightResult {
+            checks: Vec::new(),
+        }));
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Identity);
+    }
+
+    #[test]
+    fn returning_users_pick_from_the_hub() {
+        let mut app = app();
+        app.wizard.returning = true;
+        app.wizard.advance();
+        assert_eq!(app.wizard.phase, WizardPhase::Hub);
+
+        // Sync needs apps in the profile
+        app.reduce(AppEvent::Key(KeyCode::Down, KeyModifiers::NONE));
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Hub);
+        assert!(!app.is_installing);
+
+        app.reduce(AppEvent::Key(KeyCode::Up, KeyModifiers::NONE));
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Preflight);
+    }
+
+    #[test]
+    fn an_interrupted_install_is_resumed_from_the_hub() {
+        let mut app = app();
+        let mut previous = app.wizard.clone();
+        previous.selected_apps.insert("ripgrep".to_string());
+        let mut checkpoint = Checkpoint::new(&previous);
+        checkpoint.record(&InstallMessage::PackageSuccess {
+            name: "Ripgrep".to_string(),
+            duration_ms: 900,
+        });
+        app.resumable = Some(checkpoint);
+        app.wizard.returning = true;
+        app.wizard.advance();
+        assert_eq!(app.wizard.phase, WizardPhase::Hub);
+
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Review);
+        assert!(app.wizard.installs("ripgrep"));
+        assert!(app.resumable.is_none());
+        let resuming = app.resuming.as_ref().unwrap();
+        assert_eq!(resuming.succeeded, ["ripgrep"]);
+    }
+
+    #[test]
+    fn an_install_stopped_by_a_fatal_error_stays_resumable() {
+        let mut app = app();
+        app.system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-resume-{}", std::process::id()));
+        let checkpoint = Checkpoint::new(&app.wizard);
+        checkpoint.save(&app.system).unwrap();
+        app.checkpoint = Some(checkpoint);
+        app.wizard.go_to(WizardPhase::Install);
+        app.reduce(AppEvent::Install(InstallMessage::FatalError(
+            "Homebrew failed to install".to_string(),
+        )));
+        app.finish_install(Ok(()));
+        assert!(Checkpoint::load(&app.system).is_some());
+
+        app.checkpoint = Some(Checkpoint::new(&app.wizard));
+        app.error_message = None;
+        app.finish_install(Ok(()));
+        assert!(Checkpoint::load(&app.system).is_none());
+        let _ = std::fs::remove_dir_all(&app.system.home_dir);
+    }
+
+    #[test]
+    fn the_onboarding_checklist_follows_the_install() {
+        let mut app = app();
+        let team = Profile::parse(
+            "[[onboarding.items]]\ntitle = \"Join the VPN\"\n\
+             [[onboarding.items]]\ntitle = \"Badge photo\"\n",
+        )
+        .unwrap();
+        app.onboarding =
+            onboarding::Checklist::new("https://acme.example/p.toml", &team.onboarding);
+        app.wizard.onboarding = true;
+        app.wizard.go_to(WizardPhase::Install);
+        app.finish_install(Ok(()));
+        assert_eq!(app.wizard.phase, WizardPhase::Onboarding);
+
+        for key in [KeyCode::Down, KeyCode::Down, KeyCode::Char(' ')] {
+            app.reduce(AppEvent::Key(key, KeyModifiers::NONE));
+        }
+        let checklist = app.onboarding.as_ref().unwrap();
+        assert_eq!(checklist.done, [false, true]);
+        // Quitting now would lose what's ticked off
+        assert!(app.has_unsaved_answers());
+    }
+
+    #[test]
+    fn session_stats_teach_the_next_estimates() {
+        let mut app = app();
+        app.system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-stats-{}", std::process::id()));
+        for _ in 0..2 {
+            app.reduce(AppEvent::Key(KeyCode::Null, KeyModifiers::NONE));
+        }
+        let ripgrep = catalog::find_app("ripgrep").unwrap();
+        app.begin_install(&[ripgrep]);
+        app.reduce(AppEvent::Install(InstallMessage::PackageSuccess {
+            name: "Ripgrep".to_string(),
+            duration_ms: estimate::guess(ripgrep).as_millis() as u64 * 3 / 2,
+        }));
+        app.reduce(AppEvent::InstallFinished(Ok(())));
+
+        assert_eq!(app.session.keypresses, 2);
+        assert_eq!(app.session.installed, 1);
+        assert_eq!(stats::history(&app.system), [app.session.clone()]);
+        // Ripgrep took half as long again as guessed, so the next guesses
+        // allow for that
+        let bat = catalog::find_app("bat").unwrap();
+        let estimates = estimate::Estimates::load(&app.system);
+        assert_eq!(estimates.expected(bat), estimate::guess(bat).mul_f64(1.5));
+        let _ = std::fs::remove_dir_all(&app.system.config_dir);
+    }
+
+    #[test]
+    fn alias_conflicts_are_kept_until_toggled() {
+        let mut app = app();
+        app.system.home_dir =
+            std::env::temp_dir().join(format!("loadstar-aliases-{}", std::process::id()));
+        std::fs::create_dir_all(&app.system.home_dir).unwrap();
+        std::fs::write(app.system.home_dir.join(".bashrc"), "alias cat='cat -v'\n").unwrap();
+        app.wizard.shell_config.shell = wizard::ShellChoice::Bash;
+        app.wizard.selected_apps = ["bat".to_string()].into_iter().collect();
+        app.wizard.go_to(WizardPhase::Apps);
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::ConfigPreview);
+        assert_eq!(app.alias_conflicts.len(), 1);
+        assert!(app.wizard.kept_aliases.contains("cat"));
+
+        // Space on the conflict, after the files, hands `cat` to bat
+        app.wizard.cursor_position = app.planned_configs().len();
+        app.reduce(AppEvent::Key(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert!(app.wizard.kept_aliases.is_empty());
+        // and coming back doesn't take it away again
+        app.reduce(AppEvent::Key(KeyCode::Esc, KeyModifiers::NONE));
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::ConfigPreview);
+        assert!(app.wizard.kept_aliases.is_empty());
+        let _ = std::fs::remove_dir_all(&app.system.home_dir);
+    }
+
+    #[test]
+    fn an_idle_install_goes_to_attract_mode_until_a_key() {
+        let mut app = app();
+        app.wizard.go_to(WizardPhase::Install);
+        app.is_installing = true;
+        app.reduce(AppEvent::Tick);
+        assert!(app.attract.is_none());
+
+        app.last_key -= Duration::from_secs(ATTRACT_AFTER);
+        app.reduce(AppEvent::Tick);
+        assert!(app.attract.is_some());
+        // The key that wakes it isn't also a log filter change
+        let filter = app.log_filter;
+        app.reduce(AppEvent::Key(KeyCode::Char('f'), KeyModifiers::NONE));
+        assert!(app.attract.is_none());
+        assert_eq!(app.log_filter, filter);
+
+        app.reduce(AppEvent::Key(KeyCode::Char('a'), KeyModifiers::NONE));
+        assert!(app.attract.is_some());
+        app.finish_install(Ok(()));
+        assert!(app.attract.is_none());
+    }
+
+    #[test]
+    fn upgrades_are_listed_before_they_run() {
+        let mut app = app();
+        let json = r#"{"formulae": [{"name": "ripgrep", "installed_versions": ["14.0.3"],
+                       "current_version": "14.1.1"}], "casks": []}"#;
+        app.runner = Arc::new(FakeRunner::default().answer("brew outdated", ok(json)));
+        app.wizard.returning = true;
+        app.wizard.advance();
+        for _ in 0..3 {
+            app.reduce(AppEvent::Key(KeyCode::Down, KeyModifiers::NONE));
+        }
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Upgrade);
+        assert!(app.upgrades.is_none());
+        let answer = app.upgrades_receiver.as_ref().unwrap().recv().unwrap();
+        app.reduce(AppEvent::Upgrades(answer));
+        assert!(app.upgrades_receiver.is_none());
+        let listed = app.upgrades.clone().unwrap().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].latest, "14.1.1");
+
+        let outdated = |id: &str| hub::Outdated {
+            app: catalog::find_app(id).unwrap(),
+            installed: "1.0".to_string(),
+            latest: "1.1".to_string(),
+            self_updating: false,
+        };
+        app.upgrades = Some(Ok(vec![outdated("ripgrep"), outdated("bat")]));
+        for _ in 0..3 {
+            app.reduce(AppEvent::Key(KeyCode::Down, KeyModifiers::NONE));
+        }
+        assert_eq!(app.wizard.cursor_position, 1);
+
+        // Excluding bat is saved to the profile; bat isn't a cask to be greedy
+        app.profile_path =
+            std::env::temp_dir().join(format!("loadstar-upgrade-{}.toml", std::process::id()));
+        std::fs::write(&app.profile_path, "# mine\napps = [\"bat\"]\n").unwrap();
+        app.reduce(AppEvent::Key(KeyCode::Char(' '), KeyModifiers::NONE));
+        app.reduce(AppEvent::Key(KeyCode::Char('g'), KeyModifiers::NONE));
+        assert_eq!(app.upgrade_prefs.exclude, ["bat"]);
+        assert!(app.upgrade_prefs.greedy.is_empty());
+        let saved = std::fs::read_to_string(&app.profile_path).unwrap();
+        assert!(saved.starts_with("# mine\n"), "{}", saved);
+        let profile = Profile::parse(&saved).unwrap();
+        assert_eq!(profile.upgrade.exclude, ["bat"]);
+        let _ = std::fs::remove_file(&app.profile_path);
+
+        // A profile pulled for this run isn't where choices are kept
+        app.profile_path = hub::state_dir(&app.system).join(PULLED_PROFILE);
+        assert_eq!(app.own_profile_path(), Profile::default_path(&app.system));
+
+        // Nothing to upgrade, nothing to start
+        app.upgrades = Some(Ok(Vec::new()));
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Upgrade);
+        assert!(!app.is_installing);
+
+        app.reduce(AppEvent::Key(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Hub);
+    }
+
+    #[test]
+    fn presets_saved_on_review_load_on_devtools() {
+        let mut app = app();
+        app.system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-presets-ui-{}", std::process::id()));
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+
+        app.wizard.phase = WizardPhase::Review;
+        app.wizard.selected_apps = ["k9s".to_string()].into_iter().collect();
+        key(&mut app, KeyCode::Char('s'));
+        for c in "k8s".chars() {
+            key(&mut app, KeyCode::Char(c));
+        }
+        key(&mut app, KeyCode::Enter);
+        assert!(matches!(&app.preset_status, Some(Ok(_))));
+        assert_eq!(
+            app.wizard.phase,
+            WizardPhase::Review,
+            "typing didn't navigate"
+        );
+
+        app.wizard.phase = WizardPhase::DevTools;
+        app.wizard.selected_apps.clear();
+        key(&mut app, KeyCode::Char('p'));
+        assert_eq!(app.preset_picker.as_ref().unwrap().presets.len(), 1);
+        key(&mut app, KeyCode::Enter);
+        assert!(app.preset_picker.is_none());
+        assert!(app.wizard.selected_apps.contains("k9s"));
+        let _ = std::fs::remove_dir_all(&app.system.config_dir);
+    }
+
+    #[test]
+    fn the_expert_palette_queues_packages_outside_the_catalog() {
+        let mut app = app();
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+        let typed = |app: &mut App, text: &str| {
+            for c in text.chars() {
+                key(app, KeyCode::Char(c));
+            }
+            key(app, KeyCode::Enter);
+        };
+        app.wizard.phase = WizardPhase::Apps;
+        app.wizard.selected_apps.clear();
+
+        // The palette is only there in expert mode
+        key(&mut app, KeyCode::Char(':'));
+        assert!(app.palette.is_none());
+        key(&mut app, KeyCode::Char('e'));
+        key(&mut app, KeyCode::Char(':'));
+        assert_eq!(app.palette.as_deref(), Some(""));
+
+        // A bad entry stays put to be fixed
+        typed(&mut app, "pip:httpie");
+        assert!(matches!(&app.palette_status, Some(Err(_))));
+        assert_eq!(app.palette.as_deref(), Some("pip:httpie"));
+        for _ in "pip:httpie".chars() {
+            key(&mut app, KeyCode::Backspace);
+        }
+        typed(&mut app, "jq");
+        assert!(app.wizard.selected_apps.contains("jq"));
+        assert!(app.wizard.custom_packages.is_empty());
+
+        typed(&mut app, "cargo:bacon");
+        let checked = app.palette_receiver.take().unwrap().recv().unwrap();
+        app.reduce(AppEvent::Palette(checked));
+        assert!(matches!(&app.palette_status, Some(Ok(_))));
+        let installs: Vec<&str> = app
+            .wizard
+            .get_apps_to_install()
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect();
+        assert!(installs.contains(&"cargo:bacon"));
+        assert_eq!(
+            app.wizard.phase,
+            WizardPhase::Apps,
+            "typing didn't navigate"
+        );
+
+        // Backspace on an empty entry drops it again
+        key(&mut app, KeyCode::Backspace);
+        assert!(app.wizard.custom_packages.is_empty());
+        key(&mut app, KeyCode::Esc);
+        assert!(app.palette.is_none());
+    }
+
+    #[test]
+    fn brew_suggestions_follow_the_palette_entry() {
+        let mut app = app();
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+        let suggestion = |name: &str| custom::Suggestion {
+            package: custom::CustomPackage::parse(name).unwrap(),
+            description: String::new(),
+        };
+        app.wizard.phase = WizardPhase::DevTools;
+        app.expert = true;
+        key(&mut app, KeyCode::Char(':'));
+        key(&mut app, KeyCode::Char('t'));
+        key(&mut app, KeyCode::Char('l'));
+        assert!(app.palette_typed_at.is_some());
+
+        // An answer for what was typed before is dropped
+        app.reduce(AppEvent::Suggestions(
+            "t".to_string(),
+            vec![suggestion("tig")],
+        ));
+        assert!(app.suggestions.is_empty());
+        app.reduce(AppEvent::Suggestions(
+            "tl".to_string(),
+            vec![suggestion("tldr"), suggestion("cask:tlauncher")],
+        ));
+        assert_eq!(app.suggestions.len(), 2);
+
+        key(&mut app, KeyCode::Down);
+        key(&mut app, KeyCode::Down);
+        key(&mut app, KeyCode::Up);
+        assert_eq!(app.suggestion_cursor, Some(0));
+        key(&mut app, KeyCode::Enter);
+        // brew just listed it, so it's queued without another search
+        assert!(app.palette_receiver.is_none());
+        assert_eq!(app.wizard.custom_packages[0].to_string(), "brew:tldr");
+        assert_eq!(app.palette.as_deref(), Some(""));
+        assert!(app.suggestions.is_empty());
+    }
+
+    #[test]
+    fn categories_jump_by_letter() {
+        let mut app = app();
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+        let category = |app: &App| app.wizard.current_category().unwrap();
+        app.wizard.phase = WizardPhase::Apps;
+
+        // Only "AI & ML Tools" starts with a
+        key(&mut app, KeyCode::Char('c'));
+        key(&mut app, KeyCode::Char('a'));
+        assert_eq!(category(&app), catalog::Category::AI);
+        assert_eq!(app.category_jump, None);
+
+        // Several start with s: each press moves to the next one
+        key(&mut app, KeyCode::Char('c'));
+        key(&mut app, KeyCode::Char('s'));
+        key(&mut app, KeyCode::Char('s'));
+        assert_eq!(category(&app), catalog::Category::AI);
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(category(&app), catalog::Category::Search);
+
+        key(&mut app, KeyCode::Char('c'));
+        key(&mut app, KeyCode::Up);
+        key(&mut app, KeyCode::Esc);
+        assert_eq!(category(&app), catalog::Category::Search);
+        assert_eq!(app.wizard.phase, WizardPhase::Apps);
+    }
+
+    #[test]
+    fn narration_announces_steps_and_results() {
+        let mut app = app();
+        let path = std::env::temp_dir().join(format!("loadstar-said-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        app.narrator = Some(narrate::Narrator::file(&path).unwrap());
+        app.wizard.phase = WizardPhase::Preflight;
+
+        app.reduce(AppEvent::Preflight(system::PreflightResult {
+            checks: vec![system::PreflightCheck {
+                name: "Disk".to_string(),
+                status: system::CheckStatus::Warn,
+                detail: String::new(),
+            }],
+        }));
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        app.reduce(AppEvent::Install(InstallMessage::PackageFailed {
+            name: "jq".to_string(),
+            error: "no bottle".to_string(),
+        }));
+
+        let said = std::fs::read_to_string(&path).unwrap();
+        let said: Vec<&str> = said.lines().collect();
+        assert_eq!(
+            said[0],
+            "Pre-flight checks: 0 passed, 1 warning(s), 0 failed"
+        );
+        assert!(said[1].starts_with("Step: Identity. "), "{}", said[1]);
+        assert_eq!(said[2], "jq failed: no bottle");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn a_run_in_progress_blocks_the_install() {
+        let mut app = app();
+        // Its own state directory, so installs in other tests aren't blocked
+        app.system.config_dir =
+            std::env::temp_dir().join(format!("loadstar-lock-ui-{}", std::process::id()));
+        app.wizard.phase = WizardPhase::Review;
+        let path = lock::path(&app.system);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let holder = std::os::unix::process::parent_id();
+        std::fs::write(&path, holder.to_string()).unwrap();
+
+        app.confirm_install();
+        let modal = app.modal.take().unwrap();
+        assert_eq!(modal.title, "RUN IN PROGRESS");
+        assert!(modal.message.contains(&format!("PID {}", holder)));
+        assert_eq!(app.wizard.phase, WizardPhase::Review);
+        assert!(!app.is_installing);
+        let _ = std::fs::remove_dir_all(&app.system.config_dir);
+    }
+
+    #[test]
+    fn github_usernames_are_looked_up_once() {
+        let mut app = app();
+        assert!(!app.look_up_github);
+        app.wizard.identity.github_username = "octocat".to_string();
+        app.reduce(AppEvent::GithubUser((
+            "octocat".to_string(),
+            Ok(Some(github_api::User {
+                login: "octocat".to_string(),
+                id: 583231,
+                name: None,
+            })),
+        )));
+        let (login, answer) = app.github_user.clone().unwrap();
+        assert_eq!(login, "octocat");
+        assert_eq!(
+            answer.unwrap().unwrap().noreply_email(),
+            "583231+octocat@users.noreply.github.com"
+        );
+
+        // The same name isn't asked about again
+        app.look_up_github = true;
+        app.start_github_lookup();
+        assert!(app.github_user_receiver.is_none());
+    }
+
+    #[test]
+    fn several_ssh_keys_mean_picking_one() {
+        let mut app = app();
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+        app.ssh_keys = ["id_ed25519", "work"]
+            .iter()
+            .map(|name| github::SshKey {
+                path: PathBuf::from("/home/ada/.ssh").join(name),
+                kind: "ED25519".to_string(),
+                comment: String::new(),
+                fingerprint: String::new(),
+                modified: None,
+            })
+            .collect();
+        app.wizard.phase = WizardPhase::GitHub;
+
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(app.ssh_key_picker, Some(0));
+        assert_eq!(app.wizard.phase, WizardPhase::GitHub);
+        key(&mut app, KeyCode::Down);
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(
+            app.wizard.ssh_key,
+            SshKeyChoice::Existing(PathBuf::from("/home/ada/.ssh/work"))
+        );
+
+        key(&mut app, KeyCode::Char(' '));
+        key(&mut app, KeyCode::Down);
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(app.wizard.ssh_key, SshKeyChoice::Generate);
+
+        // Last: no new key, the row's "off"
+        key(&mut app, KeyCode::Char(' '));
+        key(&mut app, KeyCode::Down);
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(app.wizard.ssh_key, SshKeyChoice::Default);
+        assert!(!app.wizard.generate_ssh_key);
+        key(&mut app, KeyCode::Enter);
+        assert_ne!(app.wizard.phase, WizardPhase::GitHub);
+    }
+
+    #[test]
+    fn a_token_in_the_environment_is_only_stored_when_allowed() {
+        let mut app = app();
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+        app.wizard.selected_apps.insert("gh".to_string());
+        app.env_token = Some("GH_TOKEN");
+        app.wizard.phase = WizardPhase::GitHub;
+
+        key(&mut app, KeyCode::Enter);
+        assert_eq!(app.modal.as_ref().unwrap().action, ModalAction::UseEnvToken);
+        key(&mut app, KeyCode::Char('n'));
+        assert!(!app.wizard.use_env_token);
+        assert_ne!(app.wizard.phase, WizardPhase::GitHub);
+
+        // Asked once per run
+        app.wizard.phase = WizardPhase::GitHub;
+        key(&mut app, KeyCode::Enter);
+        assert!(app.modal.is_none());
+    }
+
+    #[test]
+    fn secrets_take_typing_and_paste_without_triggering_bindings() {
+        let mut app = app();
+        let key = |app: &mut App, code| app.reduce(AppEvent::Key(code, KeyModifiers::NONE));
+
+        app.wizard.phase = WizardPhase::GitHub;
+        key(&mut app, KeyCode::Down);
+        // Space toggles elsewhere on this screen; here it's part of the passphrase
+        for c in "a b".chars() {
+            key(&mut app, KeyCode::Char(c));
+        }
+        app.reduce(AppEvent::Paste(Secret::new("cd\n")));
+        assert_eq!(app.wizard.ssh_passphrase.expose(), "a bcd");
+        assert!(app.wizard.generate_ssh_key);
+        assert!(!format!("{:?}", app.wizard).contains("a bcd"));
+
+        app.wizard.phase = WizardPhase::Review;
+        app.sudo_prompt = Some(Secret::default());
+        app.reduce(AppEvent::Paste(Secret::new("hunter2")));
+        key(&mut app, KeyCode::Backspace);
+        assert_eq!(app.sudo_prompt.as_ref().unwrap().expose(), "hunter");
+        key(&mut app, KeyCode::Esc);
+        assert!(app.sudo_prompt.is_none());
+        assert_eq!(app.wizard.phase, WizardPhase::Review);
+        assert!(app.install_options.sudo_password.is_none());
+    }
+
+    #[test]
+    fn ctrl_c_quits_outside_the_install() {
+        let mut app = app();
+        app.reduce(AppEvent::Key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn ctrl_c_mid_wizard_asks_and_a_second_one_quits() {
+        let mut app = app();
+        let ctrl_c =
+            |app: &mut App| app.reduce(AppEvent::Key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        app.wizard.phase = WizardPhase::Shell;
+
+        ctrl_c(&mut app);
+        assert!(!app.should_quit);
+        assert_eq!(app.modal.as_ref().unwrap().action, ModalAction::Quit);
+        app.reduce(AppEvent::Key(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert!(app.modal.is_none());
+        assert_eq!(app.wizard.phase, WizardPhase::Shell);
+
+        ctrl_c(&mut app);
+        ctrl_c(&mut app);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn aborting_an_install_asks_first() {
+        let mut app = app();
+        app.wizard.phase = WizardPhase::Install;
+        app.is_installing = true;
+        app.reduce(AppEvent::Key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(app.wizard.phase, WizardPhase::Install);
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.wizard.phase, WizardPhase::Complete);
+        assert!(app
+            .install_log
+            .iter()
+            .any(|l| l.text.starts_with("[ABORT]")));
+    }
+
+    #[test]
+    fn replacing_configs_asks_before_installing() {
+        let mut app = app();
+        app.system.home_dir = app.system.config_dir.join("home");
+        app.wizard.phase = WizardPhase::Review;
+        let existing = &app.planned_configs()[0].path;
+        std::fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        std::fs::write(existing, "mine").unwrap();
+
+        app.reduce(AppEvent::Key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.modal.as_ref().unwrap().action, ModalAction::Install);
+        app.reduce(AppEvent::Key(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(app.modal.is_none());
+        assert_eq!(app.wizard.phase, WizardPhase::Review);
+        let _ = std::fs::remove_dir_all(&app.system.config_dir);
+    }
+}
@@ -0,0 +1,102 @@
+//! Screen reader narration
+//! Phase changes and results as plain lines, for screen readers that can't
+//! follow a TUI redrawing itself.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use loadstar_core::wizard::WizardPhase;
+
+/// Where announcements go
+pub struct Narrator {
+    out: Box<dyn Write + Send>,
+    /// Lines waiting for the TUI to let go of the terminal they'd print
+    /// on; `None` when they go out as they're said
+    held: Option<Vec<String>>,
+    last: Option<String>,
+}
+
+impl Narrator {
+    /// Announce on stdout. When stdout is the terminal the TUI draws on,
+    /// lines are held until it's let go of (Ctrl+Z or quitting).
+    pub fn stdout(hold: bool) -> Self {
+        Self {
+            out: Box::new(io::stdout()),
+            held: hold.then(Vec::new),
+            last: None,
+        }
+    }
+
+    /// Append announcements to `path`, for a screen reader following it
+    /// in another terminal
+    pub fn file(path: &Path) -> io::Result<Self> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            out: Box::new(file),
+            held: None,
+            last: None,
+        })
+    }
+
+    /// Announce `line`, unless it was the last thing said
+    pub fn say(&mut self, line: String) {
+        if self.last.as_ref() == Some(&line) {
+            return;
+        }
+        match &mut self.held {
+            Some(held) => held.push(line.clone()),
+            None => {
+                let _ = writeln!(self.out, "{}", line);
+                let _ = self.out.flush();
+            }
+        }
+        self.last = Some(line);
+    }
+
+    /// Print what was held while the TUI had the terminal. Call it once
+    /// the alternate screen is left.
+    pub fn release(&mut self) {
+        let Some(held) = &mut self.held else {
+            return;
+        };
+        for line in held.drain(..) {
+            let _ = writeln!(self.out, "{}", line);
+        }
+        let _ = self.out.flush();
+    }
+}
+
+/// What's said on arriving at `phase`
+pub fn phase(phase: WizardPhase) -> String {
+    let name = phase.name().to_lowercase();
+    let mut chars = name.chars();
+    let title: String = chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default();
+    format!("Step: {}. {}", title, phase.description())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn announcements_append_to_the_file_once_each() {
+        let path =
+            std::env::temp_dir().join(format!("loadstar-narrate-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut narrator = Narrator::file(&path).unwrap();
+        narrator.say(phase(WizardPhase::DevTools));
+        narrator.say(phase(WizardPhase::DevTools));
+        narrator.say("Installed ripgrep".to_string());
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Step: Dev tools. "), "{}", lines[0]);
+        assert_eq!(lines[1], "Installed ripgrep");
+        let _ = std::fs::remove_file(&path);
+    }
+}
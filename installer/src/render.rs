@@ -6,15 +6,26 @@ use ratatui::{
     style::{Color, Modifier, Style},
     symbols,
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
-use crate::{
+use loadstar_core::{
     catalog::{self, Category},
-    effects::{HackerTheme, Theme},
-    wizard::{SetupType, WizardPhase},
-    App,
+    events,
+    failure::FailureKind,
+    hub::{self, HubAction},
+    localmodel, onboarding,
+    secret::{self, Secret},
+    system::CheckStatus,
+    wizard::{AppMode, SetupType, ShellChoice, SshKeyChoice, WizardPhase, WizardState},
+};
+
+use crate::{
+    ascii_art,
+    effects::{GaugeStyle, HackerTheme, Theme},
+    keymap::{Action, Keymap},
+    logview, App, Modal,
 };
 
 pub fn render_app(frame: &mut Frame, app: &mut App) {
@@ -26,14 +37,28 @@ pub fn render_app(frame: &mut Frame, app: &mut App) {
 
     match app.wizard.phase {
         WizardPhase::Boot => render_boot(frame, app, size),
+        WizardPhase::Hub => render_hub(frame, app, size),
+        WizardPhase::Audit => render_audit(frame, app, size),
+        WizardPhase::Upgrade => render_upgrade(frame, app, size),
+        WizardPhase::Preflight => render_preflight(frame, app, size),
         WizardPhase::Identity => render_identity(frame, app, size),
+        WizardPhase::GitHub => render_github(frame, app, size),
         WizardPhase::Shell => render_shell(frame, app, size),
         WizardPhase::DevTools => render_devtools(frame, app, size),
         WizardPhase::Apps => render_apps(frame, app, size),
+        WizardPhase::Kubernetes => render_kubernetes(frame, app, size),
+        WizardPhase::Atuin => render_atuin(frame, app, size),
+        WizardPhase::AiTools => render_ai_tools(frame, app, size),
+        WizardPhase::ConfigPreview => render_config_preview(frame, app, size),
         WizardPhase::Review => render_review(frame, app, size),
         WizardPhase::Install => render_install(frame, app, size),
+        WizardPhase::Onboarding => render_onboarding(frame, app, size),
         WizardPhase::Complete => render_complete(frame, app, size),
     }
+
+    if let Some(modal) = &app.modal {
+        render_modal(frame, size, modal, &app.keymap);
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -45,7 +70,7 @@ fn render_boot(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(&app.matrix_rain, area);
 
     // Center content
-    let center = centered_rect(60, 50, area);
+    let center = centered_rect(60, 90, area);
     frame.render_widget(Clear, center);
 
     let block = Block::default()
@@ -57,14 +82,26 @@ fn render_boot(frame: &mut Frame, app: &mut App, area: Rect) {
     let inner = block.inner(center);
     frame.render_widget(block, center);
 
+    // The whole loading screen when it fits beside the boot lines, else
+    // just its title
+    let banner = app.banner;
+    let fits = banner.art.len() + banner.boot.len() + 4 <= inner.height as usize
+        && banner
+            .art
+            .iter()
+            .all(|l| l.chars().count() + 3 <= inner.width as usize);
+    let art = if fits { banner.art } else { banner.title };
+
     let mut lines: Vec<Line> = Vec::new();
     lines.push(Line::from(""));
-    lines.push(Line::from(Span::styled(
-        "    **** COMMODORE 64 BASIC V2 ****",
-        Style::default()
-            .fg(Theme::BLUE)
-            .add_modifier(Modifier::BOLD),
-    )));
+    for art in art {
+        lines.push(Line::from(Span::styled(
+            format!("   {}", art),
+            Style::default()
+                .fg(Theme::BLUE)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
     lines.push(Line::from(""));
 
     for (i, (msg, complete)) in app.boot_sequence.messages.iter().enumerate() {
@@ -109,6 +146,400 @@ fn render_boot(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(paragraph, inner);
 }
 
+// ═══════════════════════════════════════════════════════════════════════
+//  Hub screen — returning users
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_hub(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "WELCOME BACK");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let form_area = centered_rect(65, 90, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(form_area);
+    frame.render_widget(block, form_area);
+
+    let mut lines = vec![Line::from("")];
+    for (i, action) in HubAction::menu(app.resumable.is_some()).iter().enumerate() {
+        let selected = i == app.wizard.cursor_position;
+        let unavailable = *action == HubAction::Sync && !app.saved_apps;
+        let style = if unavailable {
+            HackerTheme::muted()
+        } else if selected {
+            HackerTheme::selected()
+        } else {
+            HackerTheme::primary()
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                if selected { "  ▸ " } else { "    " },
+                Style::default().fg(Theme::BLUE),
+            ),
+            Span::styled(action.name(), style),
+        ]));
+        let description = match (action, &app.resumable) {
+            _ if unavailable => "No apps listed in profile.toml, so nothing to sync".to_string(),
+            (HubAction::Resume, Some(checkpoint)) => {
+                format!("{}; {}", action.description(), checkpoint.progress())
+            }
+            _ => action.description().to_string(),
+        };
+        lines.push(Line::from(Span::styled(
+            format!("    {}", description),
+            Style::default().fg(Theme::SUBTEXT0),
+        )));
+        lines.push(Line::from(""));
+    }
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+
+    let km = &app.keymap;
+    render_footer(
+        frame,
+        chunks[4],
+        &[
+            (&(km.hint(Action::Up) + &km.hint(Action::Down)), "navigate"),
+            (&km.hint(Action::Confirm), "select"),
+        ],
+    );
+}
+
+fn render_audit(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "MACHINE AUDIT");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let content_area = centered_rect(80, 95, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(content_area);
+    frame.render_widget(block, content_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(report) = &app.audit {
+        let sections = [
+            ("MISSING", &report.missing, "✗", HackerTheme::error()),
+            (
+                "NOT FOUND ON PATH",
+                &report.unverified,
+                "?",
+                HackerTheme::warning(),
+            ),
+            ("INSTALLED", &report.installed, "✓", HackerTheme::success()),
+        ];
+        for (title, apps, icon, style) in sections {
+            if apps.is_empty() {
+                continue;
+            }
+            lines.push(section_header(&format!("{} ({})", title, apps.len())));
+            for app in apps {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {} ", icon), style),
+                    Span::styled(format!("{:<22}", app.name), HackerTheme::primary()),
+                    Span::styled(app.install_command(), HackerTheme::muted()),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+        if !report.missing.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  Sync with saved profile installs the missing ones.",
+                HackerTheme::muted(),
+            )));
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            "  Checking what's installed...",
+            HackerTheme::muted(),
+        )));
+    }
+
+    // Keep the scroll inside the content, even after the window grows
+    let max_scroll = lines.len().saturating_sub(inner.height as usize);
+    app.wizard.scroll_offset = app.wizard.scroll_offset.min(max_scroll);
+    frame.render_widget(
+        Paragraph::new(lines).scroll((app.wizard.scroll_offset as u16, 0)),
+        inner,
+    );
+
+    let km = &app.keymap;
+    render_footer(
+        frame,
+        chunks[4],
+        &[
+            (&(km.hint(Action::Up) + &km.hint(Action::Down)), "scroll"),
+            (&km.hint(Action::Back), "back"),
+        ],
+    );
+}
+
+fn render_upgrade(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "UPGRADES");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let content_area = centered_rect(80, 95, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(content_area);
+    frame.render_widget(block, content_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut cursor_line = 0;
+    match &app.upgrades {
+        Some(Ok(outdated)) if outdated.is_empty() => {
+            lines.push(Line::from(Span::styled(
+                "  ✓ Everything Homebrew installed from the catalog is up to date.",
+                HackerTheme::success(),
+            )));
+        }
+        Some(Ok(outdated)) => {
+            lines.push(section_header(&format!("OUTDATED ({})", outdated.len())));
+            let prefs = &app.upgrade_prefs;
+            for (i, tool) in outdated.iter().enumerate() {
+                let selected = i == app.wizard.cursor_position;
+                if selected {
+                    cursor_line = lines.len();
+                }
+                let id = tool.app.id.as_str();
+                let note = if prefs.is_excluded(id) {
+                    "  excluded"
+                } else if prefs.is_greedy(id) {
+                    "  greedy"
+                } else if tool.self_updating {
+                    "  updates itself"
+                } else {
+                    ""
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        if selected { "  ▸ " } else { "    " },
+                        Style::default().fg(Theme::BLUE),
+                    ),
+                    Span::styled(
+                        format!("{:<22}", tool.app.name),
+                        if selected {
+                            HackerTheme::selected()
+                        } else if !hub::will_upgrade(tool, prefs) {
+                            HackerTheme::muted()
+                        } else {
+                            HackerTheme::primary()
+                        },
+                    ),
+                    Span::styled(tool.installed.clone(), HackerTheme::muted()),
+                    Span::styled(" → ", HackerTheme::muted()),
+                    Span::styled(tool.latest.clone(), HackerTheme::success()),
+                    Span::styled(note, HackerTheme::warning()),
+                ]));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "  Versions are as of Homebrew's last update; the upgrade runs one first.",
+                HackerTheme::muted(),
+            )));
+            lines.push(Line::from(Span::styled(
+                "  Casks that update themselves are skipped unless made greedy. Both choices are saved to the profile.",
+                HackerTheme::muted(),
+            )));
+        }
+        Some(Err(e)) => {
+            lines.push(Line::from(Span::styled(
+                format!("  ✗ {}", e),
+                HackerTheme::error(),
+            )));
+        }
+        None => {
+            lines.push(Line::from(Span::styled(
+                "  Asking Homebrew what's outdated...",
+                HackerTheme::muted(),
+            )));
+        }
+    }
+    if let Some(notice) = &app.upgrade_notice {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", notice),
+            HackerTheme::muted(),
+        )));
+    }
+
+    // Keep the selected tool on screen
+    let height = inner.height as usize;
+    if cursor_line < app.wizard.scroll_offset {
+        app.wizard.scroll_offset = cursor_line;
+    } else if height > 0 && cursor_line >= app.wizard.scroll_offset + height {
+        app.wizard.scroll_offset = cursor_line + 1 - height;
+    }
+    frame.render_widget(
+        Paragraph::new(lines).scroll((app.wizard.scroll_offset as u16, 0)),
+        inner,
+    );
+
+    let km = &app.keymap;
+    let mut hints = vec![
+        (km.hint(Action::Up) + &km.hint(Action::Down), "navigate"),
+        (km.hint(Action::Toggle), "exclude"),
+        (km.hint(Action::Greedy), "greedy"),
+        (km.hint(Action::ReleaseNotes), "release notes"),
+        (km.hint(Action::Confirm), "upgrade"),
+        (km.hint(Action::Back), "back"),
+    ];
+    if !matches!(&app.upgrades, Some(Ok(outdated)) if !outdated.is_empty()) {
+        hints.drain(..5);
+    }
+    let hints: Vec<(&str, &str)> = hints.iter().map(|(k, l)| (k.as_str(), *l)).collect();
+    render_footer(frame, chunks[4], &hints);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  Pre-flight screen
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_preflight(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "SYSTEM DIAGNOSTICS");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let form_area = centered_rect(65, 90, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(form_area);
+    frame.render_widget(block, form_area);
+
+    let mut lines = vec![Line::from("")];
+    match &app.preflight {
+        None => lines.push(Line::from(Span::styled(
+            format!("  {} Running checks...", app.spinner.current()),
+            HackerTheme::muted(),
+        ))),
+        Some(result) => {
+            for check in &result.checks {
+                let (icon, style) = match check.status {
+                    CheckStatus::Pass => ("✓", HackerTheme::success()),
+                    CheckStatus::Warn => ("!", HackerTheme::warning()),
+                    CheckStatus::Fail => ("✗", HackerTheme::error()),
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {} ", icon), style),
+                    Span::styled(format!("{:<24}", check.name), HackerTheme::primary()),
+                    Span::styled(check.detail.clone(), Style::default().fg(Theme::SUBTEXT0)),
+                ]));
+            }
+            lines.push(Line::from(""));
+            if !result.all_passed() {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {} check(s) failed. You can continue, but parts of the install may not work.",
+                        result.fail_count()
+                    ),
+                    HackerTheme::warning(),
+                )));
+            }
+            if !app.gaps.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {} app(s) can't be installed on this machine; they're marked ⚠ in the lists.",
+                        app.gaps.len()
+                    ),
+                    HackerTheme::warning(),
+                )));
+            }
+            if !app.hints.is_empty() {
+                if !result.all_passed() || !app.gaps.is_empty() {
+                    lines.push(Line::from(""));
+                }
+                lines.push(Line::from(Span::styled(
+                    "  DEFAULTS FROM THIS MACHINE",
+                    HackerTheme::muted(),
+                )));
+                for hint in &app.hints {
+                    lines.push(Line::from(vec![
+                        Span::styled("  → ", HackerTheme::success()),
+                        Span::styled(
+                            format!("{:<32}", hint.seed.describe()),
+                            HackerTheme::primary(),
+                        ),
+                        Span::styled(hint.signal.clone(), Style::default().fg(Theme::SUBTEXT0)),
+                    ]));
+                }
+            }
+        }
+    }
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }),
+        inner.inner(&ratatui::layout::Margin::new(1, 0)),
+    );
+
+    let km = &app.keymap;
+    let footer: &[(&str, &str)] = if app.preflight.is_some() {
+        &[(&km.hint(Action::Confirm), "continue")]
+    } else {
+        &[]
+    };
+    render_footer(frame, chunks[4], footer);
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 //  Identity screen
 // ═══════════════════════════════════════════════════════════════════════
@@ -127,7 +558,7 @@ fn render_identity(frame: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
 
     render_header(frame, chunks[0], "IDENTITY MATRIX");
-    render_phase_indicator(frame, chunks[1], &app.wizard.phase);
+    render_phase_indicator(frame, chunks[1], &app.wizard);
 
     // System info bar
     let sys = &app.system;
@@ -204,18 +635,23 @@ fn render_identity(frame: &mut Frame, app: &mut App, area: Rect) {
         app.wizard.input_field == 3,
     );
 
+    let km = &app.keymap;
     render_footer(
         frame,
         chunks[5],
-        &[("tab", "next"), ("enter", "continue"), ("esc", "back")],
+        &[
+            (&km.hint(Action::NextCategory), "next"),
+            (&km.hint(Action::Confirm), "continue"),
+            (&km.hint(Action::Back), "back"),
+        ],
     );
 }
 
 // ═══════════════════════════════════════════════════════════════════════
-//  Shell screen
+//  GitHub screen
 // ═══════════════════════════════════════════════════════════════════════
 
-fn render_shell(frame: &mut Frame, app: &mut App, area: Rect) {
+fn render_github(frame: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -227,10 +663,10 @@ fn render_shell(frame: &mut Frame, app: &mut App, area: Rect) {
         ])
         .split(area);
 
-    render_header(frame, chunks[0], "COMMAND INTERFACE");
-    render_phase_indicator(frame, chunks[1], &app.wizard.phase);
+    render_header(frame, chunks[0], "CREDENTIALS");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
 
-    let content_area = centered_rect(75, 90, chunks[3]);
+    let form_area = centered_rect(65, 90, chunks[3]);
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -238,75 +674,379 @@ fn render_shell(frame: &mut Frame, app: &mut App, area: Rect) {
         .border_style(HackerTheme::border())
         .style(Style::default().bg(Theme::MANTLE));
 
-    let inner = block.inner(content_area);
-    frame.render_widget(block, content_area);
+    let inner = block.inner(form_area);
+    frame.render_widget(block, form_area);
 
-    let option_chunks = Layout::default()
+    let field_chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
             Constraint::Length(4),
-            Constraint::Length(4),
+            Constraint::Length(3),
             Constraint::Length(4),
             Constraint::Length(4),
             Constraint::Min(0),
         ])
         .split(inner);
 
+    let picked = match &app.wizard.ssh_key {
+        SshKeyChoice::Existing(path) => app.ssh_keys.iter().find(|k| &k.path == path),
+        _ => None,
+    };
+    let (ssh, ssh_desc) = match (&app.wizard.ssh_key, picked) {
+        (SshKeyChoice::Existing(_), Some(key)) => (
+            key.path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            format!("{} {}", key.kind, key.comment),
+        ),
+        (SshKeyChoice::Generate, _) => (
+            "New key".to_string(),
+            "A dedicated ed25519 key, next to the ones already in ~/.ssh".to_string(),
+        ),
+        _ if app.ssh_keys.len() > 1 && app.wizard.generate_ssh_key => (
+            "Pick one".to_string(),
+            format!(
+                "~/.ssh has {} keys; choose which GitHub gets",
+                app.ssh_keys.len()
+            ),
+        ),
+        _ if app.wizard.generate_ssh_key => (
+            "On".to_string(),
+            "Generate an ed25519 key, unless ~/.ssh already has one".to_string(),
+        ),
+        _ => (
+            "Off".to_string(),
+            "Use an existing key, or none".to_string(),
+        ),
+    };
     render_option_selector(
         frame,
-        option_chunks[0],
-        "SHELL",
-        app.wizard.shell_config.shell.name(),
-        app.wizard.shell_config.shell.description(),
+        field_chunks[0],
+        "SSH KEY",
+        &ssh,
+        &ssh_desc,
         app.wizard.cursor_position == 0,
     );
-    render_option_selector(
+    render_masked_field(
         frame,
-        option_chunks[1],
-        "PROMPT",
-        app.wizard.shell_config.prompt.name(),
-        app.wizard.shell_config.prompt.description(),
+        field_chunks[1],
+        "KEY PASSPHRASE (blank for none)",
+        &app.wizard.ssh_passphrase,
         app.wizard.cursor_position == 1,
     );
+    let (signing, signing_desc) = if app.wizard.setup_git_signing {
+        ("On", "Sign commits with your GPG key")
+    } else {
+        ("Off", "Leave commits unsigned")
+    };
     render_option_selector(
         frame,
-        option_chunks[2],
-        "TERMINAL",
-        app.wizard.shell_config.terminal.name(),
-        app.wizard.shell_config.terminal.description(),
+        field_chunks[2],
+        "COMMIT SIGNING",
+        signing,
+        signing_desc,
         app.wizard.cursor_position == 2,
     );
-
-    let mux_name = app
-        .wizard
-        .shell_config
-        .multiplexer
-        .map(|m| m.name())
-        .unwrap_or("None");
-    let mux_desc = app
-        .wizard
-        .shell_config
-        .multiplexer
-        .map(|m| m.description())
-        .unwrap_or("No terminal multiplexer");
+    let (rewrite, rewrite_desc) = if app.wizard.rewrite_github_https {
+        (
+            "On",
+            "Clone https://github.com/ URLs over SSH with your key; breaks CI tokens and HTTPS-only proxies",
+        )
+    } else {
+        (
+            "Off",
+            "Leave https://github.com/ URLs alone, so tokens and proxies keep working",
+        )
+    };
     render_option_selector(
         frame,
-        option_chunks[3],
-        "MULTIPLEXER",
-        mux_name,
-        mux_desc,
+        field_chunks[3],
+        "HTTPS → SSH REWRITE",
+        rewrite,
+        rewrite_desc,
         app.wizard.cursor_position == 3,
     );
-
+    let username = &app.wizard.identity.github_username;
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "  Set up for github.com/{}. The key is added to GitHub after install.",
+            username
+        ),
+        HackerTheme::muted(),
+    ))];
+    // A failed lookup says nothing: the username may well be fine
+    match app
+        .github_user
+        .as_ref()
+        .filter(|(login, _)| login == username.trim())
+    {
+        Some((_, Ok(Some(user)))) => {
+            let who = match &user.name {
+                Some(name) => format!("  ✓ {} ({})", user.login, name),
+                None => format!("  ✓ {}", user.login),
+            };
+            lines.push(Line::from(Span::styled(who, HackerTheme::success())));
+            if !app
+                .wizard
+                .identity
+                .email
+                .ends_with("users.noreply.github.com")
+            {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "    To keep your email private, commit as {}",
+                        user.noreply_email()
+                    ),
+                    HackerTheme::muted(),
+                )));
+            }
+        }
+        Some((_, Ok(None))) => lines.push(Line::from(Span::styled(
+            format!(
+                "  ✗ GitHub has no user called {}; go back to fix it",
+                username
+            ),
+            HackerTheme::warning(),
+        ))),
+        _ => {}
+    }
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }),
+        field_chunks[4],
+    );
+
+    let km = &app.keymap;
     render_footer(
         frame,
         chunks[4],
         &[
-            ("↑↓", "navigate"),
-            ("←→", "change"),
-            ("enter", "continue"),
-            ("esc", "back"),
+            (&(km.hint(Action::Up) + &km.hint(Action::Down)), "navigate"),
+            (&(km.hint(Action::Left) + &km.hint(Action::Right)), "change"),
+            (&km.hint(Action::Confirm), "continue"),
+            (&km.hint(Action::Back), "back"),
+        ],
+    );
+
+    if let Some(cursor) = app.ssh_key_picker {
+        let mut lines = vec![Line::from("")];
+        for (i, key) in app.ssh_keys.iter().enumerate() {
+            let selected = i == cursor;
+            lines.push(Line::from(vec![
+                Span::styled(
+                    if selected { "  ▸ " } else { "    " },
+                    Style::default().fg(Theme::BLUE),
+                ),
+                Span::styled(
+                    format!("{:<8}", key.kind),
+                    Style::default().fg(Theme::PEACH),
+                ),
+                Span::styled(
+                    key.path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                    if selected {
+                        HackerTheme::selected()
+                    } else {
+                        HackerTheme::primary()
+                    },
+                ),
+                Span::styled(format!("  {}", key.comment), HackerTheme::muted()),
+            ]));
+            lines.push(Line::from(Span::styled(
+                format!("            {} · {}", key.fingerprint, key.age()),
+                HackerTheme::dim(),
+            )));
+        }
+        let extras = [
+            ("+ Generate a new key for GitHub", Theme::GREEN),
+            (
+                "− No new key: id_ed25519 or id_rsa if there, otherwise none",
+                Theme::OVERLAY1,
+            ),
+        ];
+        for (i, (label, color)) in extras.into_iter().enumerate() {
+            let selected = cursor == app.ssh_keys.len() + i;
+            lines.push(Line::from(vec![
+                Span::styled(
+                    if selected { "  ▸ " } else { "    " },
+                    Style::default().fg(Theme::BLUE),
+                ),
+                Span::styled(
+                    label,
+                    if selected {
+                        HackerTheme::selected()
+                    } else {
+                        Style::default().fg(color)
+                    },
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {} use, {} close",
+                km.hint(Action::Confirm),
+                km.hint(Action::Back)
+            ),
+            HackerTheme::muted(),
+        )));
+        render_popup(frame, area, "SSH KEYS", lines);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  Shell screen
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_shell(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "COMMAND INTERFACE");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let content_area = centered_rect(75, 90, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(content_area);
+    frame.render_widget(block, content_area);
+
+    let option_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    render_option_selector(
+        frame,
+        option_chunks[0],
+        "SHELL",
+        app.wizard.shell_config.shell.name(),
+        app.wizard.shell_config.shell.description(),
+        app.wizard.cursor_position == 0,
+    );
+    render_option_selector(
+        frame,
+        option_chunks[1],
+        "PROMPT",
+        app.wizard.shell_config.prompt.name(),
+        app.wizard.shell_config.prompt.description(),
+        app.wizard.cursor_position == 1,
+    );
+    render_option_selector(
+        frame,
+        option_chunks[2],
+        "TERMINAL",
+        app.wizard.shell_config.terminal.name(),
+        app.wizard.shell_config.terminal.description(),
+        app.wizard.cursor_position == 2,
+    );
+
+    let mux_name = app
+        .wizard
+        .shell_config
+        .multiplexer
+        .map(|m| m.name())
+        .unwrap_or("None");
+    let mux_desc = app
+        .wizard
+        .shell_config
+        .multiplexer
+        .map(|m| m.description())
+        .unwrap_or("No terminal multiplexer");
+    render_option_selector(
+        frame,
+        option_chunks[3],
+        "MULTIPLEXER",
+        mux_name,
+        mux_desc,
+        app.wizard.cursor_position == 3,
+    );
+    render_option_selector(
+        frame,
+        option_chunks[4],
+        "EDITOR",
+        app.wizard.editor.name(),
+        app.wizard.editor.description(),
+        app.wizard.cursor_position == 4,
+    );
+    render_option_selector(
+        frame,
+        option_chunks[5],
+        "THEME",
+        app.wizard.theme.name(),
+        app.wizard.theme.description(),
+        app.wizard.cursor_position == 5,
+    );
+    render_option_selector(
+        frame,
+        option_chunks[6],
+        "FONT",
+        app.wizard.font.name(),
+        app.wizard.font.description(),
+        app.wizard.cursor_position == 6,
+    );
+    let (aliases, aliases_desc) = if app.wizard.shell_config.aliases {
+        ("On", "ls → eza, cat → bat, cd → z, for the tools you pick")
+    } else {
+        ("Off", "Keep ls, cat and cd as they are")
+    };
+    render_option_selector(
+        frame,
+        option_chunks[7],
+        "ALIASES",
+        aliases,
+        aliases_desc,
+        app.wizard.cursor_position == 7,
+    );
+
+    if let Some(warning) = app.wizard.prompt_warning() {
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                format!("  ⚠ {}", warning),
+                Style::default().fg(Theme::YELLOW),
+            ))),
+            option_chunks[8],
+        );
+    }
+
+    let km = &app.keymap;
+    render_footer(
+        frame,
+        chunks[4],
+        &[
+            (&(km.hint(Action::Up) + &km.hint(Action::Down)), "navigate"),
+            (&(km.hint(Action::Left) + &km.hint(Action::Right)), "change"),
+            (&km.hint(Action::Confirm), "continue"),
+            (&km.hint(Action::Back), "back"),
         ],
     );
 }
@@ -327,41 +1067,133 @@ fn render_devtools(frame: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
 
     render_header(frame, chunks[0], "DEVELOPMENT ARSENAL");
-    render_phase_indicator(frame, chunks[1], &app.wizard.phase);
+    render_phase_indicator(frame, chunks[1], &app.wizard);
 
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(22), Constraint::Min(0)])
         .split(chunks[2]);
 
-    let categories = &[
-        Category::Language,
-        Category::Editor,
-        Category::Git,
-        Category::Container,
-        Category::Cloud,
-    ];
-
+    let categories = app.wizard.categories();
     render_category_sidebar(
         frame,
         content_chunks[0],
         categories,
         app.wizard.scroll_offset,
+        None,
     );
     let cat = &categories[app.wizard.scroll_offset % categories.len()];
-    render_app_list(frame, content_chunks[1], cat, app);
-
-    render_footer(
-        frame,
-        chunks[3],
-        &[
-            ("tab", "category"),
-            ("space", "toggle"),
-            ("a", "all"),
-            ("n", "none"),
-            ("enter", "continue"),
-        ],
-    );
+    let bundle = catalog::bundle_for(cat);
+    let list_area = match bundle {
+        Some(bundle) => {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(2), Constraint::Min(0)])
+                .split(content_chunks[1]);
+            let picked = app.wizard.bundle_selected(bundle);
+            frame.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::styled(
+                        if picked { "   ◉ " } else { "   ○ " },
+                        Style::default().fg(if picked {
+                            Theme::GREEN
+                        } else {
+                            Theme::OVERLAY1
+                        }),
+                    ),
+                    Span::styled(
+                        bundle.name,
+                        Style::default()
+                            .fg(Theme::LAVENDER)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("  {}", bundle.description),
+                        Style::default().fg(Theme::OVERLAY0),
+                    ),
+                ])),
+                rows[0],
+            );
+            rows[1]
+        }
+        None => content_chunks[1],
+    };
+    render_app_list(frame, list_area, cat, app);
+
+    let km = &app.keymap;
+    let mut keys = vec![
+        (km.hint(Action::NextCategory), "category"),
+        (km.hint(Action::Toggle), "toggle"),
+        (km.hint(Action::CycleMode), "mode"),
+        (km.hint(Action::SelectAll), "all"),
+        (km.hint(Action::SelectNone), "none"),
+    ];
+    if bundle.is_some() {
+        keys.push((km.hint(Action::Bundle), "bundle"));
+    }
+    keys.push((km.hint(Action::Presets), "presets"));
+    keys.extend(expert_keys(app));
+    keys.push((km.hint(Action::Confirm), "continue"));
+    let keys: Vec<(&str, &str)> = keys.iter().map(|(k, a)| (k.as_str(), *a)).collect();
+    render_footer(frame, chunks[3], &keys);
+    render_palette(frame, area, app);
+
+    if let Some(picker) = &app.preset_picker {
+        let mut lines = vec![Line::from("")];
+        if picker.presets.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  No saved presets yet. Press {} on Review to save one.",
+                    km.hint(Action::SavePreset)
+                ),
+                HackerTheme::muted(),
+            )));
+        }
+        for (i, preset) in picker.presets.iter().enumerate() {
+            let selected = i == picker.cursor;
+            lines.push(Line::from(vec![
+                Span::styled(
+                    if selected { "  ▸ " } else { "    " },
+                    Style::default().fg(Theme::BLUE),
+                ),
+                Span::styled(
+                    preset.name.clone(),
+                    if selected {
+                        HackerTheme::selected()
+                    } else {
+                        HackerTheme::primary()
+                    },
+                ),
+                Span::styled(
+                    format!("  {} apps", preset.apps.len()),
+                    HackerTheme::muted(),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Mode: ", Style::default().fg(Theme::SUBTEXT0)),
+            Span::styled(
+                if picker.replace {
+                    "◂ replace the selection ▸"
+                } else {
+                    "◂ add to the selection ▸"
+                },
+                Style::default().fg(Theme::GREEN),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {} load, {}{} mode, {} close",
+                km.hint(Action::Confirm),
+                km.hint(Action::Left),
+                km.hint(Action::Right),
+                km.hint(Action::Back)
+            ),
+            HackerTheme::muted(),
+        )));
+        render_popup(frame, area, "PRESETS", lines);
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -380,34 +1212,345 @@ fn render_apps(frame: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
 
     render_header(frame, chunks[0], "SOFTWARE COMPANIONS");
-    render_phase_indicator(frame, chunks[1], &app.wizard.phase);
+    render_phase_indicator(frame, chunks[1], &app.wizard);
 
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(24), Constraint::Min(0)])
         .split(chunks[2]);
 
-    let categories = Category::all();
+    let categories = app.wizard.categories();
     render_category_sidebar(
         frame,
         content_chunks[0],
         categories,
         app.wizard.scroll_offset,
+        app.category_jump,
     );
 
     let cat = &categories[app.wizard.scroll_offset % categories.len()];
     render_app_list(frame, content_chunks[1], cat, app);
 
-    render_footer(
-        frame,
-        chunks[3],
-        &[
-            ("tab/S-tab", "category"),
-            ("space", "toggle"),
-            ("d", "details"),
-            ("enter", "continue"),
-        ],
-    );
+    let km = &app.keymap;
+    if app.category_jump.is_some() {
+        render_footer(
+            frame,
+            chunks[3],
+            &[
+                ("a-z", "first letter"),
+                (
+                    &format!("{}{}", km.hint(Action::Up), km.hint(Action::Down)),
+                    "move",
+                ),
+                (&km.hint(Action::Confirm), "jump"),
+                (&km.hint(Action::Back), "cancel"),
+            ],
+        );
+        return;
+    }
+    let mut keys = vec![
+        (
+            format!(
+                "{}/{}",
+                km.hint(Action::NextCategory),
+                km.hint(Action::PrevCategory)
+            ),
+            "category",
+        ),
+        (km.hint(Action::Categories), "jump"),
+        (km.hint(Action::Toggle), "toggle"),
+        (km.hint(Action::CycleMode), "mode"),
+        (km.hint(Action::Details), "details"),
+    ];
+    keys.extend(expert_keys(app));
+    keys.push((km.hint(Action::Confirm), "continue"));
+    let keys: Vec<(&str, &str)> = keys.iter().map(|(k, a)| (k.as_str(), *a)).collect();
+    render_footer(frame, chunks[3], &keys);
+    render_palette(frame, area, app);
+}
+
+/// Footer hints for expert mode, shared by DevTools and Apps
+fn expert_keys(app: &App) -> Vec<(String, &'static str)> {
+    let km = &app.keymap;
+    if app.expert {
+        vec![
+            (km.hint(Action::Palette), "add package"),
+            (km.hint(Action::Expert), "expert off"),
+        ]
+    } else {
+        vec![(km.hint(Action::Expert), "expert")]
+    }
+}
+
+/// The expert palette: the entry being typed, what the last one came to,
+/// and the packages queued so far
+fn render_palette(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(entry) = &app.palette else {
+        return;
+    };
+    let km = &app.keymap;
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Package: ", Style::default().fg(Theme::SUBTEXT0)),
+            Span::styled(entry.clone(), HackerTheme::primary()),
+            Span::styled("█", Style::default().fg(Theme::BLUE)),
+        ]),
+        Line::from(Span::styled(
+            "  brew:name, cask:name, cargo:name or npm:name; a bare name is brew",
+            HackerTheme::muted(),
+        )),
+        Line::from(""),
+    ];
+    if app.suggest_receiver.is_some() && app.suggestions.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {} Searching brew...", app.spinner.current()),
+            HackerTheme::muted(),
+        )));
+        lines.push(Line::from(""));
+    }
+    for (i, suggestion) in app.suggestions.iter().enumerate() {
+        let selected = app.suggestion_cursor == Some(i);
+        lines.push(Line::from(vec![
+            Span::styled(
+                if selected { "  ▸ " } else { "    " },
+                Style::default().fg(Theme::BLUE),
+            ),
+            Span::styled(
+                suggestion.package.to_string(),
+                if selected {
+                    HackerTheme::selected()
+                } else {
+                    HackerTheme::primary()
+                },
+            ),
+            Span::styled(
+                format!("  {}", suggestion.description),
+                HackerTheme::muted(),
+            ),
+        ]));
+    }
+    if !app.suggestions.is_empty() {
+        lines.push(Line::from(""));
+    }
+    let status = match &app.palette_status {
+        _ if app.palette_receiver.is_some() => Some((
+            format!("  {} Checking with brew search...", app.spinner.current()),
+            Theme::YELLOW,
+        )),
+        Some(Ok(message)) => Some((format!("  ✓ {}", message), Theme::GREEN)),
+        Some(Err(e)) => Some((format!("  ✗ {}", e), Theme::RED)),
+        None => None,
+    };
+    if let Some((text, color)) = status {
+        lines.push(Line::from(Span::styled(text, Style::default().fg(color))));
+        lines.push(Line::from(""));
+    }
+    if !app.wizard.custom_packages.is_empty() {
+        let queued: Vec<String> = app
+            .wizard
+            .custom_packages
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled("  Queued: ", Style::default().fg(Theme::SUBTEXT0)),
+            Span::styled(queued.join(", "), HackerTheme::primary()),
+        ]));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {}{} pick a match, {} add, bksp on an empty entry drops the last, {} close",
+            km.hint(Action::Up),
+            km.hint(Action::Down),
+            km.hint(Action::Confirm),
+            km.hint(Action::Back)
+        ),
+        HackerTheme::muted(),
+    )));
+    render_popup(frame, area, "EXPERT PALETTE", lines);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  Config preview screen
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_config_preview(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "DOTFILE PREVIEW");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let content_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(34), Constraint::Min(0)])
+        .split(chunks[3]);
+
+    let planned = app.planned_configs();
+    let conflicts = &app.alias_conflicts;
+    let selected = app
+        .wizard
+        .cursor_position
+        .min((planned.len() + conflicts.len()).saturating_sub(1));
+    let home = &app.system.home_dir;
+    let display = |path: &std::path::Path| {
+        path.strip_prefix(home)
+            .map(|p| format!("~/{}", p.display()))
+            .unwrap_or_else(|_| path.display().to_string())
+    };
+
+    let block = Block::default()
+        .borders(Borders::RIGHT)
+        .border_style(HackerTheme::border_dim());
+    let inner = block.inner(content_chunks[0]);
+    frame.render_widget(block, content_chunks[0]);
+
+    let marker = |is_selected: bool| {
+        Span::styled(
+            if is_selected { " ▸ " } else { "   " },
+            Style::default().fg(Theme::BLUE),
+        )
+    };
+    let name_style = |is_selected: bool| {
+        if is_selected {
+            HackerTheme::selected()
+        } else {
+            HackerTheme::primary()
+        }
+    };
+    let mut items: Vec<ListItem> = planned
+        .iter()
+        .enumerate()
+        .map(|(i, config)| {
+            let is_selected = i == selected;
+            let exists = config.path.exists();
+            ListItem::new(Line::from(vec![
+                marker(is_selected),
+                Span::styled(display(&config.path), name_style(is_selected)),
+                Span::styled(if exists { " (replace)" } else { "" }, HackerTheme::muted()),
+            ]))
+        })
+        .collect();
+    if !conflicts.is_empty() {
+        items.push(ListItem::new(""));
+        items.push(ListItem::new(Span::styled(
+            "   YOUR ALIASES",
+            HackerTheme::muted(),
+        )));
+    }
+    for (i, conflict) in conflicts.iter().enumerate() {
+        let is_selected = planned.len() + i == selected;
+        let name = conflict.alias.name;
+        let (choice, color) = if app.wizard.kept_aliases.contains(name) {
+            ("keep mine", Theme::GREEN)
+        } else {
+            ("use loadstar's", Theme::YELLOW)
+        };
+        items.push(ListItem::new(Line::from(vec![
+            marker(is_selected),
+            Span::styled(format!("{:<6}", name), name_style(is_selected)),
+            Span::styled(choice, Style::default().fg(color)),
+        ])));
+    }
+    frame.render_widget(List::new(items), inner);
+
+    if let Some(conflict) = selected
+        .checked_sub(planned.len())
+        .and_then(|i| conflicts.get(i))
+    {
+        let alias = conflict.alias;
+        let shell = app.wizard.shell_config.shell;
+        let place = match conflict.line {
+            0 => display(&conflict.file),
+            line => format!("{} line {}", display(&conflict.file), line),
+        };
+        let mut lines = vec![Line::from(Span::styled(
+            format!(" Yours, in {}:", place),
+            HackerTheme::muted(),
+        ))];
+        lines.extend(
+            conflict
+                .definition
+                .lines()
+                .map(|l| Line::from(Span::styled(format!("   {}", l), HackerTheme::primary()))),
+        );
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" loadstar's, for {}:", alias.app),
+            HackerTheme::muted(),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("   {}", alias.line(shell).unwrap_or_default()),
+            HackerTheme::primary(),
+        )));
+        lines.push(Line::from(""));
+        let outcome = if app.wizard.kept_aliases.contains(alias.name) {
+            if shell == ShellChoice::Zsh {
+                format!(
+                    " Keeping yours: {} isn't aliased, and yours moves to ~/.zshrc.local",
+                    alias.name
+                )
+            } else {
+                format!(" Keeping yours: loadstar leaves {} alone", alias.name)
+            }
+        } else {
+            format!(
+                " Using loadstar's: {} runs {}. Yours stays where it is, shadowed",
+                alias.name, alias.app
+            )
+        };
+        lines.push(Line::from(Span::styled(
+            outcome,
+            Style::default().fg(Theme::LAVENDER),
+        )));
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default().borders(Borders::NONE).title(Span::styled(
+                    format!(" alias {} ", alias.name),
+                    Style::default().fg(Theme::LAVENDER),
+                )),
+            ),
+            content_chunks[1],
+        );
+    } else if let Some(config) = planned.get(selected) {
+        let lines: Vec<Line> = config
+            .content
+            .lines()
+            .map(|l| Line::from(Span::styled(format!(" {}", l), HackerTheme::primary())))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::NONE).title(
+                Span::styled(
+                    format!(" {} ", display(&config.path)),
+                    Style::default().fg(Theme::LAVENDER),
+                ),
+            )),
+            content_chunks[1],
+        );
+    }
+
+    let km = &app.keymap;
+    let mut hints = vec![
+        (km.hint(Action::Up) + &km.hint(Action::Down), "file"),
+        (km.hint(Action::Toggle), "keep mine / use loadstar's"),
+        (km.hint(Action::Confirm), "continue"),
+        (km.hint(Action::Back), "back"),
+    ];
+    if app.alias_conflicts.is_empty() {
+        hints.remove(1);
+    }
+    let hints: Vec<(&str, &str)> = hints.iter().map(|(k, l)| (k.as_str(), *l)).collect();
+    render_footer(frame, chunks[4], &hints);
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -427,7 +1570,7 @@ fn render_review(frame: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
 
     render_header(frame, chunks[0], "CONFIGURATION REVIEW");
-    render_phase_indicator(frame, chunks[1], &app.wizard.phase);
+    render_phase_indicator(frame, chunks[1], &app.wizard);
 
     let content_area = centered_rect(80, 95, chunks[3]);
 
@@ -440,12 +1583,111 @@ fn render_review(frame: &mut Frame, app: &mut App, area: Rect) {
     let inner = block.inner(content_area);
     frame.render_widget(block, content_area);
 
-    let mut lines: Vec<Line> = vec![
+    let mut lines: Vec<Line> = Vec::new();
+
+    if let Some(previous) = &app.resuming {
+        lines.push(section_header("RESUMING"));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}; the rest install, then setup runs",
+                previous.progress()
+            ),
+            HackerTheme::primary(),
+        )));
+        if !previous.secrets_left_out.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  Not saved with it: {}. Go back to enter them again",
+                    previous.secrets_left_out.join(", ")
+                ),
+                Style::default().fg(Theme::YELLOW),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    if app.wizard.dry_run {
+        lines.push(section_header("DRY RUN"));
+        lines.push(Line::from(Span::styled(
+            "  Installing prints each command and file write; none of them happen",
+            Style::default().fg(Theme::YELLOW),
+        )));
+        lines.push(Line::from(""));
+    }
+
+    if let Some(status) = &app.export_status {
+        let (text, color) = match status {
+            Ok(message) => (format!("  ✓ {}", message), Theme::GREEN),
+            Err(e) => (format!("  ✗ Export failed: {}", e), Theme::RED),
+        };
+        lines.push(section_header("DEVCONTAINER"));
+        lines.push(Line::from(Span::styled(text, Style::default().fg(color))));
+        lines.push(Line::from(""));
+    }
+
+    if let Some(status) = &app.plan_status {
+        let (text, color) = match status {
+            Ok(path) => (
+                format!("  ✓ Wrote the plan to {}", path.display()),
+                Theme::GREEN,
+            ),
+            Err(e) => (format!("  ✗ Plan not written: {}", e), Theme::RED),
+        };
+        lines.push(section_header("PLAN"));
+        lines.push(Line::from(Span::styled(text, Style::default().fg(color))));
+        lines.push(Line::from(""));
+    }
+
+    if let Some(status) = &app.preset_status {
+        let (text, color) = match status {
+            Ok(message) => (format!("  ✓ {}", message), Theme::GREEN),
+            Err(e) => (format!("  ✗ Preset not saved: {}", e), Theme::RED),
+        };
+        lines.push(section_header("PRESET"));
+        lines.push(Line::from(Span::styled(text, Style::default().fg(color))));
+        lines.push(Line::from(""));
+    }
+
+    let mut warnings = app.wizard.warnings();
+    warnings.extend(localmodel::warning(&app.wizard, &app.system.hardware));
+    if !warnings.is_empty() {
+        lines.push(section_header("WARNINGS"));
+        for warning in &warnings {
+            lines.push(Line::from(Span::styled(
+                format!("  ⚠ {}", warning),
+                Style::default().fg(Theme::YELLOW),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    let overlaps = app.wizard.overlaps();
+    if !overlaps.is_empty() {
+        lines.push(section_header("OVERLAP"));
+        for (i, (overlap, picked)) in overlaps.iter().take(9).enumerate() {
+            let names: Vec<&str> = picked.iter().map(|a| a.name).collect();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  [{}] ", i + 1), HackerTheme::key_hint_key()),
+                Span::styled(
+                    format!("{} {}: ", picked.len(), overlap.label),
+                    Style::default().fg(Theme::YELLOW),
+                ),
+                Span::styled(names.join(", "), HackerTheme::primary()),
+                Span::styled(
+                    format!("  → {}", overlap.category.name()),
+                    HackerTheme::muted(),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines.extend([
         section_header("IDENTITY"),
         review_line("  Name", &app.wizard.identity.name),
         review_line("  Email", &app.wizard.identity.email),
         review_line("  GitHub", &app.wizard.identity.github_username),
-    ];
+    ]);
     let setup_str = format!(
         "{} {}",
         app.wizard.identity.setup_type.icon(),
@@ -473,14 +1715,46 @@ fn render_review(frame: &mut Frame, app: &mut App, area: Rect) {
             .map(|m| m.name())
             .unwrap_or("None"),
     ));
+    lines.push(review_line("  Editor", app.wizard.editor.name()));
+    lines.push(review_line("  Theme", app.wizard.theme.name()));
+    lines.push(review_line("  Font", app.wizard.font.name()));
+    if let Some(locale) = &app.wizard.locale {
+        lines.push(review_line("  Locale", locale));
+    }
+    if let Some(timezone) = &app.wizard.timezone {
+        lines.push(review_line("  Timezone", timezone));
+    }
     lines.push(Line::from(""));
 
     // Apps
     lines.push(section_header("APPLICATIONS"));
     let selected_str = format!("{} apps", app.wizard.selected_app_count());
     lines.push(review_line("  Selected", &selected_str));
+    let configure_only = app
+        .wizard
+        .get_selected_apps()
+        .iter()
+        .filter(|a| app.wizard.app_mode(a.id.as_str()) == AppMode::ConfigureOnly)
+        .count();
+    if configure_only > 0 {
+        let configure_str = format!("{} (not installed, config written)", configure_only);
+        lines.push(review_line("  Config only", &configure_str));
+    }
+    if !app.wizard.custom_packages.is_empty() {
+        let custom: Vec<String> = app
+            .wizard
+            .custom_packages
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        lines.push(review_line("  Custom", &custom.join(", ")));
+    }
     let time_str = format!("~{} minutes", app.wizard.estimated_install_time());
     lines.push(review_line("  Est. time", &time_str));
+    lines.push(review_line(
+        "  Brew update",
+        app.install_options.brew_update.description(),
+    ));
     lines.push(Line::from(""));
 
     for cat in Category::all() {
@@ -512,51 +1786,201 @@ fn render_review(frame: &mut Frame, app: &mut App, area: Rect) {
         );
         lines.push(review_line("  Identity", &identity_str));
     }
-    if app.wizard.generate_ssh_key {
-        lines.push(review_line(
-            "  SSH key",
-            "Generate ed25519 (or use existing)",
-        ));
+    match &app.wizard.ssh_key {
+        SshKeyChoice::Existing(path) => {
+            lines.push(review_line("  SSH key", &format!("Use {}", path.display())));
+        }
+        SshKeyChoice::Generate => {
+            lines.push(review_line("  SSH key", "Generate a dedicated ed25519 key"));
+        }
+        SshKeyChoice::Default if app.wizard.generate_ssh_key => {
+            lines.push(review_line(
+                "  SSH key",
+                "Generate ed25519 (or use existing)",
+            ));
+        }
+        SshKeyChoice::Default => {}
     }
-    if app.wizard.selected_apps.contains("gh") {
+    if app.wizard.configures("gh") {
         lines.push(review_line("  GitHub CLI", "Configure auth (post-install)"));
     }
     if app.wizard.setup_git_signing {
         lines.push(review_line("  GPG signing", "Configure (or guide)"));
     }
-    if app.wizard.selected_apps.contains("delta") {
+    if app.wizard.rewrite_github_https {
+        lines.push(review_line("  HTTPS URLs", "Rewrite github.com to SSH"));
+    }
+    if app.wizard.configures("delta") {
         lines.push(review_line("  Delta", "Set as git pager"));
     }
     lines.push(Line::from(""));
 
+    // Kubernetes
+    let kube = &app.wizard.kube;
+    if app.wizard.wants_kube() && kube.is_set() {
+        lines.push(section_header("KUBERNETES"));
+        let source = match kube.source {
+            loadstar_core::kube::KubeSource::File => format!("Merge {}", kube.kubeconfig.trim()),
+            loadstar_core::kube::KubeSource::Eks | loadstar_core::kube::KubeSource::Gke => format!(
+                "{} {} ({})",
+                kube.source.name(),
+                kube.cluster.trim(),
+                kube.region.trim()
+            ),
+            loadstar_core::kube::KubeSource::Keep => kube.source.name().to_string(),
+        };
+        lines.push(review_line("  Credentials", &source));
+        if !kube.namespace.trim().is_empty() {
+            lines.push(review_line("  Namespace", kube.namespace.trim()));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Atuin
+    if app.wizard.configures("atuin") {
+        let atuin = &app.wizard.atuin;
+        lines.push(section_header("ATUIN"));
+        lines.push(review_line("  History", "Import from your shell"));
+        let sync = match atuin.account_command() {
+            Some(command) => format!("{} (post-install)", command),
+            None => atuin.sync.name().to_string(),
+        };
+        lines.push(review_line("  Sync", &sync));
+        if let Some(address) = atuin.sync_address() {
+            lines.push(review_line("  Server", address));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // AI tools
+    if app.wizard.wants_ai() {
+        let ai = &app.wizard.ai;
+        lines.push(section_header("AI TOOLS"));
+        let keys: Vec<&str> = ai.keys().iter().filter_map(|f| f.env_var()).collect();
+        if keys.is_empty() {
+            lines.push(review_line("  API keys", "Set up yourself (post-install)"));
+        } else {
+            lines.push(review_line(
+                "  API keys",
+                &format!("{} ({})", keys.join(", "), ai.source.name()),
+            ));
+        }
+        if let Some(model) = ai
+            .aichat_model()
+            .filter(|_| app.wizard.configures("aichat"))
+        {
+            lines.push(review_line("  aichat model", &model));
+        }
+        lines.push(Line::from(""));
+    }
+
+    // Ollama's model
+    if app.wizard.installs("ollama") {
+        let hardware = &app.system.hardware;
+        let model = localmodel::chosen(&app.wizard, hardware);
+        lines.push(section_header("LOCAL MODEL"));
+        let size = localmodel::size_gb(&model)
+            .map(|gb| format!(" ({:.0} GB)", gb.ceil()))
+            .unwrap_or_default();
+        lines.push(review_line("  Ollama", &format!("{}{}", model, size)));
+        lines.push(review_line("  Hardware", &hardware.summary()));
+        lines.push(Line::from(""));
+    }
+
     // Config files
     lines.push(section_header("CONFIG FILES"));
     lines.push(Line::from(Span::styled(
         "  ~/.gitconfig",
         HackerTheme::primary(),
     )));
-    if app.wizard.shell_config.shell == crate::wizard::ShellChoice::Zsh {
+    if app.wizard.shell_config.shell == loadstar_core::wizard::ShellChoice::Zsh {
         lines.push(Line::from(Span::styled(
             "  ~/.zshrc",
             HackerTheme::primary(),
         )));
     }
-    if app.wizard.shell_config.prompt == crate::wizard::PromptChoice::Starship
-        || app.wizard.selected_apps.contains("starship")
+    if app.wizard.shell_config.prompt == loadstar_core::wizard::PromptChoice::Starship
+        || app.wizard.configures("starship")
     {
         lines.push(Line::from(Span::styled(
             "  ~/.config/starship.toml",
             HackerTheme::primary(),
         )));
     }
-    if app.wizard.shell_config.multiplexer == Some(crate::wizard::MultiplexerChoice::Tmux)
-        || app.wizard.selected_apps.contains("tmux")
+    if app.wizard.shell_config.multiplexer == Some(loadstar_core::wizard::MultiplexerChoice::Tmux)
+        || app.wizard.configures("tmux")
     {
         lines.push(Line::from(Span::styled(
             "  ~/.tmux.conf",
             HackerTheme::primary(),
         )));
     }
+    if app.wizard.configures("zed") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/zed/settings.json",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("ghostty") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/ghostty/config",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("bat") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/bat/config",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("ripgrep") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.ripgreprc",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("lazygit") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/lazygit/config.yml",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("yt-dlp") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/yt-dlp/config",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("k9s") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/k9s/skins/loadstar.yaml",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("btop") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/btop/themes/loadstar.theme",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("bottom") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.config/bottom/bottom.toml",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("terraform") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.terraformrc",
+            HackerTheme::primary(),
+        )));
+    }
+    if app.wizard.configures("awscli") {
+        lines.push(Line::from(Span::styled(
+            "  ~/.aws/config (SSO stubs, only if missing)",
+            HackerTheme::primary(),
+        )));
+    }
     lines.push(Line::from(Span::styled(
         "  ~/.editorconfig",
         HackerTheme::primary(),
@@ -572,18 +1996,343 @@ fn render_review(frame: &mut Frame, app: &mut App, area: Rect) {
             "  Ready to transform your machine? ",
             Style::default().fg(Theme::YELLOW),
         ),
-        Span::styled("[y/enter]", HackerTheme::key_hint_key()),
+        Span::styled(
+            format!(
+                "[{}/{}]",
+                app.keymap.hint(Action::Yes),
+                app.keymap.hint(Action::Confirm)
+            ),
+            HackerTheme::key_hint_key(),
+        ),
     ]));
 
+    // Keep the scroll inside the content, even after the window grows
+    let wrap_width = (inner.width as usize).max(1);
+    let content_height: usize = lines
+        .iter()
+        .map(|l| l.width().max(1).div_ceil(wrap_width))
+        .sum();
+    let max_scroll = content_height.saturating_sub(inner.height as usize);
+    app.wizard.scroll_offset = app.wizard.scroll_offset.min(max_scroll);
+
     let paragraph = Paragraph::new(lines)
         .wrap(Wrap { trim: true })
         .scroll((app.wizard.scroll_offset as u16, 0));
     frame.render_widget(paragraph, inner);
 
+    let km = &app.keymap;
+    let scroll = km.hint(Action::Up) + &km.hint(Action::Down);
+    let install = format!("{}/{}", km.hint(Action::Confirm), km.hint(Action::Yes));
+    let back = format!("{}/{}", km.hint(Action::Back), km.hint(Action::No));
+    let export = km.hint(Action::Export);
+    let export_plan = km.hint(Action::ExportPlan);
+    let save = km.hint(Action::SavePreset);
+    let mut keys: Vec<(&str, &str)> = vec![(&scroll, "scroll")];
+    if !overlaps.is_empty() {
+        keys.push(("1-9", "trim"));
+    }
+    keys.extend([
+        (export.as_str(), "devcontainer"),
+        (export_plan.as_str(), "plan"),
+        (save.as_str(), "save preset"),
+        (install.as_str(), "install"),
+        (back.as_str(), "back"),
+    ]);
+    render_footer(frame, chunks[4], &keys);
+
+    if let Some(name) = &app.preset_name {
+        let lines = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("  Name: ", Style::default().fg(Theme::SUBTEXT0)),
+                Span::styled(name.clone(), HackerTheme::primary()),
+                Span::styled("█", Style::default().fg(Theme::BLUE)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!(
+                    "  {} apps. {} save, {} cancel",
+                    app.wizard.selected_apps.len(),
+                    km.hint(Action::Confirm),
+                    km.hint(Action::Back)
+                ),
+                HackerTheme::muted(),
+            )),
+        ];
+        render_popup(frame, area, "SAVE PRESET", lines);
+    }
+
+    if let Some(password) = &app.sudo_prompt {
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "  Some steps run sudo. The password is handed to sudo once and never logged.",
+                Style::default().fg(Theme::SUBTEXT0),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("  Password: ", Style::default().fg(Theme::SUBTEXT0)),
+                Span::styled(password.masked(), HackerTheme::primary()),
+                Span::styled("█", Style::default().fg(Theme::BLUE)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!(
+                    "  {} install, {} cancel",
+                    km.hint(Action::Confirm),
+                    km.hint(Action::Back)
+                ),
+                HackerTheme::muted(),
+            )),
+        ];
+        render_popup(frame, area, "SUDO PASSWORD", lines);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  Kubernetes screen
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_kubernetes(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "CLUSTER UPLINK");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let form_area = centered_rect(65, 90, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(form_area);
+    frame.render_widget(block, form_area);
+
+    let kube = &app.wizard.kube;
+    let fields = kube.source.fields();
+    let mut constraints = vec![Constraint::Length(4)];
+    constraints.extend(fields.iter().map(|_| Constraint::Length(3)));
+    constraints.push(Constraint::Min(0));
+
+    let field_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints(constraints)
+        .split(inner);
+
+    render_option_selector(
+        frame,
+        field_chunks[0],
+        "CREDENTIALS",
+        kube.source.name(),
+        kube.source.description(),
+        app.wizard.cursor_position == 0,
+    );
+    for (i, field) in fields.iter().enumerate() {
+        render_input_field(
+            frame,
+            field_chunks[i + 1],
+            field.label(),
+            kube.field(*field),
+            app.wizard.cursor_position == i + 1,
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            "  Leave the namespace empty to keep the context's default",
+            HackerTheme::muted(),
+        ))),
+        field_chunks[fields.len() + 1],
+    );
+
+    let km = &app.keymap;
+    render_footer(
+        frame,
+        chunks[4],
+        &[
+            (&km.hint(Action::NextCategory), "next"),
+            (&(km.hint(Action::Left) + &km.hint(Action::Right)), "source"),
+            (&km.hint(Action::Confirm), "continue"),
+            (&km.hint(Action::Back), "back"),
+        ],
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  Atuin screen
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_atuin(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "HISTORY SYNC");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let form_area = centered_rect(65, 90, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(form_area);
+    frame.render_widget(block, form_area);
+
+    let atuin = &app.wizard.atuin;
+    let fields = atuin.sync.fields();
+    let mut constraints = vec![Constraint::Length(4)];
+    constraints.extend(fields.iter().map(|_| Constraint::Length(3)));
+    constraints.push(Constraint::Min(0));
+
+    let field_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints(constraints)
+        .split(inner);
+
+    render_option_selector(
+        frame,
+        field_chunks[0],
+        "SYNC",
+        atuin.sync.name(),
+        atuin.sync.description(),
+        app.wizard.cursor_position == 0,
+    );
+    for (i, field) in fields.iter().enumerate() {
+        render_input_field(
+            frame,
+            field_chunks[i + 1],
+            field.label(),
+            atuin.field(*field),
+            app.wizard.cursor_position == i + 1,
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            "  Your existing history is imported either way. Passwords and keys are asked for after install.",
+            HackerTheme::muted(),
+        )))
+        .wrap(Wrap { trim: false }),
+        field_chunks[fields.len() + 1],
+    );
+
+    let km = &app.keymap;
+    render_footer(
+        frame,
+        chunks[4],
+        &[
+            (&km.hint(Action::NextCategory), "next"),
+            (&(km.hint(Action::Left) + &km.hint(Action::Right)), "sync"),
+            (&km.hint(Action::Confirm), "continue"),
+            (&km.hint(Action::Back), "back"),
+        ],
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  AI tools screen
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_ai_tools(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "NEURAL LINK");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let form_area = centered_rect(65, 90, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(form_area);
+    frame.render_widget(block, form_area);
+
+    let ai = &app.wizard.ai;
+    let fields = ai.source.fields();
+    let mut constraints = vec![Constraint::Length(4)];
+    constraints.extend(fields.iter().map(|_| Constraint::Length(3)));
+    constraints.push(Constraint::Min(0));
+
+    let field_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints(constraints)
+        .split(inner);
+
+    render_option_selector(
+        frame,
+        field_chunks[0],
+        "API KEYS",
+        ai.source.name(),
+        ai.source.description(),
+        app.wizard.cursor_position == 0,
+    );
+    for (i, field) in fields.iter().enumerate() {
+        let value = ai.field(*field);
+        let shown = if ai.is_masked(*field) {
+            secret::mask(value)
+        } else {
+            value.to_string()
+        };
+        render_input_field(
+            frame,
+            field_chunks[i + 1],
+            field.label(),
+            &shown,
+            app.wizard.cursor_position == i + 1,
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            "  Leave a key empty to skip that provider",
+            HackerTheme::muted(),
+        ))),
+        field_chunks[fields.len() + 1],
+    );
+
+    let km = &app.keymap;
     render_footer(
         frame,
         chunks[4],
-        &[("↑↓", "scroll"), ("enter/y", "install"), ("esc/n", "back")],
+        &[
+            (&km.hint(Action::NextCategory), "next"),
+            (&(km.hint(Action::Left) + &km.hint(Action::Right)), "source"),
+            (&km.hint(Action::Confirm), "continue"),
+            (&km.hint(Action::Back), "back"),
+        ],
     );
 }
 
@@ -592,6 +2341,11 @@ fn render_review(frame: &mut Frame, app: &mut App, area: Rect) {
 // ═══════════════════════════════════════════════════════════════════════
 
 fn render_install(frame: &mut Frame, app: &mut App, area: Rect) {
+    if let Some(since) = app.attract {
+        render_attract(frame, app, area, since);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -606,7 +2360,7 @@ fn render_install(frame: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
 
     render_header(frame, chunks[0], "REALITY MODIFICATION");
-    render_phase_indicator(frame, chunks[1], &app.wizard.phase);
+    render_phase_indicator(frame, chunks[1], &app.wizard);
 
     // Status line — current package + timer + counters
     let status_area = centered_rect(85, 100, chunks[3]);
@@ -647,49 +2401,79 @@ fn render_install(frame: &mut Frame, app: &mut App, area: Rect) {
         Span::styled(elapsed_str, elapsed_style),
         Span::styled("  ", Style::default()),
         Span::styled(
-            format!(" {} ok ", app.install_succeeded),
+            format!(" {} ok ", app.summary.succeeded.len()),
             Style::default().fg(Theme::GREEN),
         ),
         Span::styled(
-            format!(" {} skip ", app.install_skipped),
+            format!(" {} skip ", app.summary.skipped.len()),
             Style::default().fg(Theme::YELLOW),
         ),
         Span::styled(
-            format!(" {} fail ", app.install_failed),
-            if app.install_failed > 0 {
+            format!(" {} fail ", app.summary.failed.len()),
+            if !app.summary.failed.is_empty() {
                 Style::default().fg(Theme::RED)
             } else {
                 HackerTheme::muted()
             },
         ),
+        Span::styled(
+            format!(" {} warn ", app.summary.warnings.len()),
+            if !app.summary.warnings.is_empty() {
+                Style::default().fg(Theme::PEACH)
+            } else {
+                HackerTheme::muted()
+            },
+        ),
         Span::styled("  ", Style::default()),
         Span::styled(
             format!("{}/{}", app.install_completed, app.install_total),
             Style::default().fg(Theme::LAVENDER),
         ),
     ]);
-    let status_paragraph = Paragraph::new(status_line);
-    frame.render_widget(status_paragraph, status_area);
+    let mut status_lines = vec![status_line];
+    if let Some((taken, timeout)) = app.budget_used() {
+        status_lines.push(Line::from(Span::styled(
+            format!(
+                " ⚠ {} of its {} limit used; it's stopped at the limit",
+                events::format_duration(taken),
+                events::format_duration(timeout)
+            ),
+            HackerTheme::warning(),
+        )));
+    }
+    frame.render_widget(Paragraph::new(status_lines), status_area);
 
-    // Progress bar
-    let progress_area = centered_rect(85, 100, chunks[4]);
-    let pct = (app.install_progress as u16).min(100);
-    let gauge = Gauge::default()
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(ratatui::widgets::BorderType::Rounded)
-                .border_style(HackerTheme::border()),
-        )
-        .gauge_style(Style::default().fg(Theme::GREEN).bg(Theme::SURFACE0))
-        .percent(pct)
-        .label(Span::styled(
-            format!("{:.0}%", app.install_progress),
-            Style::default()
-                .fg(Theme::TEXT)
-                .add_modifier(Modifier::BOLD),
-        ));
-    frame.render_widget(gauge, progress_area);
+    // Progress bar
+    let progress_area = centered_rect(85, 100, chunks[4]);
+    let progress_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border());
+    let label = Span::styled(
+        format!("{:.0}%", app.install_progress),
+        Style::default()
+            .fg(Theme::TEXT)
+            .add_modifier(Modifier::BOLD),
+    );
+    if app.gauge == GaugeStyle::Blocks {
+        let pct = (app.install_progress as u16).min(100);
+        let gauge = Gauge::default()
+            .block(progress_block)
+            .gauge_style(Style::default().fg(Theme::GREEN).bg(Theme::SURFACE0))
+            .percent(pct)
+            .label(label);
+        frame.render_widget(gauge, progress_area);
+    } else {
+        let inner = progress_block.inner(progress_area);
+        let width = (inner.width as usize).saturating_sub(label.content.chars().count() + 1);
+        let bar = app.gauge.bar(app.install_progress / 100.0, width);
+        let line = Line::from(vec![
+            Span::styled(bar, Style::default().fg(Theme::GREEN)),
+            Span::raw(" "),
+            label,
+        ]);
+        frame.render_widget(Paragraph::new(line).block(progress_block), progress_area);
+    }
 
     // Log output
     let log_area = centered_rect(85, 100, chunks[5]);
@@ -702,13 +2486,13 @@ fn render_install(frame: &mut Frame, app: &mut App, area: Rect) {
     let log_inner = log_block.inner(log_area);
     frame.render_widget(log_block, log_area);
 
-    let log_lines: Vec<Line> = app
-        .install_log
+    let shown = logview::visible(&app.install_log, app.log_filter, app.collapse_output);
+    let log_lines: Vec<Line> = shown
         .iter()
         .rev()
         .take(log_inner.height as usize)
         .rev()
-        .map(|s| {
+        .map(|(elapsed, s)| {
             let style = if s.starts_with("[OK]") || s.contains("[COMPLETE]") {
                 Style::default().fg(Theme::GREEN)
             } else if s.starts_with("[FAIL]") || s.starts_with("[FATAL]") || s.contains("[ERROR]") {
@@ -730,14 +2514,221 @@ fn render_install(frame: &mut Frame, app: &mut App, area: Rect) {
             } else {
                 HackerTheme::primary()
             };
-            Line::from(Span::styled(s.as_str(), style))
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", logview::stamp(*elapsed)),
+                    Style::default().fg(Theme::SURFACE2),
+                ),
+                Span::styled(s.as_ref(), style),
+            ])
         })
         .collect();
 
     let log_paragraph = Paragraph::new(log_lines);
     frame.render_widget(log_paragraph, log_inner);
 
-    render_footer(frame, chunks[6], &[("ctrl+c", "abort")]);
+    let km = &app.keymap;
+    let filter = format!("show {}", app.log_filter.name());
+    render_footer(
+        frame,
+        chunks[6],
+        &[
+            ("ctrl+c", "abort"),
+            (&km.hint(Action::LogFilter), &filter),
+            (
+                &km.hint(Action::CollapseOutput),
+                if app.collapse_output {
+                    "expand output"
+                } else {
+                    "fold output"
+                },
+            ),
+            (&km.hint(Action::Attract), "attract mode"),
+        ],
+    );
+}
+
+/// The install left alone: full-screen rain, with the progress bouncing
+/// round it like a sprite
+fn render_attract(frame: &mut Frame, app: &App, area: Rect, since: std::time::Instant) {
+    frame.render_widget(&app.matrix_rain, area);
+
+    let width = 34.min(area.width);
+    let height = 6.min(area.height);
+    // One cell every 150ms, turning at the edges
+    let step = (since.elapsed().as_millis() / 150) as u64;
+    let sprite = Rect {
+        x: area.x + bounce(step, area.width - width),
+        y: area.y + bounce(step, area.height - height),
+        width,
+        height,
+    };
+    frame.render_widget(Clear, sprite);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::PHOSPHOR_DIM))
+        .style(Style::default().bg(Color::Rgb(10, 10, 15)));
+    let inner = block.inner(sprite);
+    frame.render_widget(block, sprite);
+
+    let cursor = if step % 4 < 2 { "█" } else { " " };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("LOADING {:>3.0}%", app.install_progress),
+            Style::default()
+                .fg(Theme::PHOSPHOR)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            app.gauge
+                .bar(app.install_progress / 100.0, inner.width as usize),
+            Style::default().fg(Theme::PHOSPHOR),
+        )),
+        Line::from(Span::styled(
+            app.current_package.clone().unwrap_or_default(),
+            Style::default().fg(Theme::PHOSPHOR_DIM),
+        )),
+        Line::from(Span::styled(
+            format!("READY.{}", cursor),
+            Style::default().fg(Theme::PHOSPHOR_DIM),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Where a sprite moving one cell a step is after `step` steps along a
+/// `span` it bounces back and forth in
+fn bounce(step: u64, span: u16) -> u16 {
+    if span == 0 {
+        return 0;
+    }
+    let span = span as u64;
+    let at = step % (span * 2);
+    (if at <= span { at } else { span * 2 - at }) as u16
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+//  Onboarding screen — the org's checklist
+// ═══════════════════════════════════════════════════════════════════════
+
+fn render_onboarding(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(1), // Phase indicator
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Content
+            Constraint::Length(1), // Footer
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], "ONBOARDING");
+    render_phase_indicator(frame, chunks[1], &app.wizard);
+
+    let content_area = centered_rect(80, 95, chunks[3]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border())
+        .style(Style::default().bg(Theme::MANTLE));
+
+    let inner = block.inner(content_area);
+    frame.render_widget(block, content_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut cursor_line = 0;
+    if let Some(checklist) = &app.onboarding {
+        lines.push(section_header(&format!(
+            "{} CHECKLIST ({} OF {} DONE)",
+            checklist.org.as_deref().unwrap_or("TEAM").to_uppercase(),
+            checklist.done_count(),
+            checklist.items.len()
+        )));
+        for (i, (item, done)) in checklist.items.iter().zip(&checklist.done).enumerate() {
+            let selected = i == app.wizard.cursor_position;
+            if selected {
+                cursor_line = lines.len();
+            }
+            lines.push(Line::from(vec![
+                Span::styled(
+                    if selected { "  ▸ " } else { "    " },
+                    Style::default().fg(Theme::BLUE),
+                ),
+                Span::styled(
+                    if *done { "[✓] " } else { "[ ] " },
+                    if *done {
+                        HackerTheme::success()
+                    } else {
+                        HackerTheme::muted()
+                    },
+                ),
+                Span::styled(
+                    item.title.clone(),
+                    if selected {
+                        HackerTheme::selected()
+                    } else if *done {
+                        HackerTheme::muted()
+                    } else {
+                        HackerTheme::primary()
+                    },
+                ),
+            ]));
+            for note in [&item.detail, &item.url].into_iter().flatten() {
+                lines.push(Line::from(Span::styled(
+                    format!("        {}", note),
+                    HackerTheme::dim(),
+                )));
+            }
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            match &checklist.contact {
+                Some(contact) => format!(
+                    "  Continuing writes {} to the current directory, for {}.",
+                    onboarding::FILE_NAME,
+                    contact
+                ),
+                None => format!(
+                    "  Continuing writes {} to the current directory, for IT.",
+                    onboarding::FILE_NAME
+                ),
+            },
+            HackerTheme::muted(),
+        )));
+    }
+    if let Some(notice) = &app.onboarding_notice {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", notice),
+            HackerTheme::muted(),
+        )));
+    }
+
+    // Keep the selected item on screen
+    let height = inner.height as usize;
+    if cursor_line < app.wizard.scroll_offset {
+        app.wizard.scroll_offset = cursor_line;
+    } else if height > 0 && cursor_line >= app.wizard.scroll_offset + height {
+        app.wizard.scroll_offset = cursor_line + 1 - height;
+    }
+    frame.render_widget(
+        Paragraph::new(lines).scroll((app.wizard.scroll_offset as u16, 0)),
+        inner,
+    );
+
+    let km = &app.keymap;
+    let hints = [
+        (km.hint(Action::Up) + &km.hint(Action::Down), "navigate"),
+        (km.hint(Action::Toggle), "done"),
+        (km.hint(Action::OpenLink), "open link"),
+        (km.hint(Action::Confirm), "write report"),
+    ];
+    let hints: Vec<(&str, &str)> = hints.iter().map(|(k, l)| (k.as_str(), *l)).collect();
+    render_footer(frame, chunks[4], &hints);
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -748,7 +2739,7 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
     // Matrix rain background — callback to the boot screen
     frame.render_widget(&app.matrix_rain, area);
 
-    let center = centered_rect(60, 65, area);
+    let center = centered_rect(60, 80, area);
     frame.render_widget(Clear, center);
 
     let block = Block::default()
@@ -760,27 +2751,33 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
     let inner = block.inner(center);
     frame.render_widget(block, center);
 
-    let has_failures = app.install_failed > 0;
+    let has_failures = !app.summary.failed.is_empty();
 
     let mut text: Vec<Line> = Vec::new();
 
-    // ─── C64 header ─────────────────────────────────────────────
+    // ─── C64 header: the run's banner, then who it was for ──────
     text.push(Line::from(""));
-    text.push(Line::from(Span::styled(
-        "    LOAD\"*\",8,1",
-        Style::default()
-            .fg(Theme::PHOSPHOR)
-            .add_modifier(Modifier::BOLD),
-    )));
-    text.push(Line::from(Span::styled(
-        "    SEARCHING FOR *",
-        Style::default().fg(Theme::PHOSPHOR_DIM),
-    )));
-    text.push(Line::from(Span::styled(
-        "    LOADING",
-        Style::default().fg(Theme::PHOSPHOR_DIM),
-    )));
+    for (i, art) in app.banner.title.iter().enumerate() {
+        let style = if i == 0 {
+            Style::default()
+                .fg(Theme::PHOSPHOR)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Theme::PHOSPHOR_DIM)
+        };
+        text.push(Line::from(Span::styled(format!("    {}", art), style)));
+    }
     text.push(Line::from(""));
+    // First name only, so it fits
+    if let Some(name) = app.wizard.identity.name.split_whitespace().next() {
+        for row in ascii_art::figlet(name) {
+            text.push(Line::from(Span::styled(
+                format!("    {}", row),
+                Style::default().fg(Theme::PHOSPHOR),
+            )));
+        }
+        text.push(Line::from(""));
+    }
 
     // ─── Divider ────────────────────────────────────────────────
     text.push(Line::from(Span::styled(
@@ -793,7 +2790,7 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
     text.push(Line::from(vec![
         Span::styled("    ", Style::default()),
         Span::styled(
-            format!("{}", app.install_succeeded),
+            format!("{}", app.summary.succeeded.len()),
             Style::default()
                 .fg(Theme::GREEN)
                 .add_modifier(Modifier::BOLD),
@@ -801,13 +2798,13 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
         Span::styled(" installed", Style::default().fg(Theme::SUBTEXT0)),
         Span::styled("   ", Style::default()),
         Span::styled(
-            format!("{}", app.install_skipped),
+            format!("{}", app.summary.skipped.len()),
             Style::default().fg(Theme::YELLOW),
         ),
         Span::styled(" skipped", Style::default().fg(Theme::SUBTEXT0)),
         Span::styled("   ", Style::default()),
         Span::styled(
-            format!("{}", app.install_failed),
+            format!("{}", app.summary.failed.len()),
             if has_failures {
                 Style::default().fg(Theme::RED).add_modifier(Modifier::BOLD)
             } else {
@@ -822,42 +2819,107 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
                 Style::default().fg(Theme::SURFACE2)
             },
         ),
+        Span::styled("   ", Style::default()),
+        Span::styled(
+            format!("{}", app.summary.warnings.len()),
+            if app.summary.warnings.is_empty() {
+                Style::default().fg(Theme::SURFACE2)
+            } else {
+                Style::default().fg(Theme::PEACH)
+            },
+        ),
+        Span::styled(
+            " warnings",
+            if app.summary.warnings.is_empty() {
+                Style::default().fg(Theme::SURFACE2)
+            } else {
+                Style::default().fg(Theme::SUBTEXT0)
+            },
+        ),
     ]));
     text.push(Line::from(""));
 
     // ─── Failed packages ─────────────────────────────────────────
-    if !app.failed_packages.is_empty() {
-        for (name, error) in &app.failed_packages {
+    if !app.summary.failed.is_empty() {
+        let apps = app.wizard.get_apps_to_install();
+        for (name, error) in &app.summary.failed {
             text.push(Line::from(vec![
                 Span::styled("    ✗ ", Style::default().fg(Theme::RED)),
                 Span::styled(
                     name.as_str(),
-                    Style::default()
-                        .fg(Theme::RED)
-                        .add_modifier(Modifier::BOLD),
+                    Style::default().fg(Theme::RED).add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(format!("  {}", error), HackerTheme::dim()),
             ]));
+            let kind = FailureKind::classify(error);
+            let method = apps
+                .iter()
+                .find(|app| app.name == name)
+                .map(|app| app.method());
+            if let Some(hint) = kind.hint(method) {
+                text.push(Line::from(vec![
+                    Span::styled(
+                        format!("      {}: ", kind.name()),
+                        Style::default().fg(Theme::PEACH),
+                    ),
+                    Span::styled(hint, Style::default().fg(Theme::SUBTEXT0)),
+                ]));
+            }
+        }
+        text.push(Line::from(""));
+    }
+
+    // ─── Crash report ────────────────────────────────────────────
+    if let Some(err) = &app.error_message {
+        text.push(Line::from(vec![
+            Span::styled("    ✗ ", Style::default().fg(Theme::RED)),
+            Span::styled(err.as_str(), Style::default().fg(Theme::RED)),
+        ]));
+        if let Some(path) = &app.crash_report {
+            text.push(Line::from(Span::styled(
+                format!("      diagnostics saved to {}", path.display()),
+                HackerTheme::dim(),
+            )));
+        }
+        text.push(Line::from(""));
+    }
+
+    // ─── Warnings ────────────────────────────────────────────────
+    if !app.summary.warnings.is_empty() {
+        for warning in app.summary.warnings.iter().take(5) {
+            text.push(Line::from(vec![
+                Span::styled("    ! ", Style::default().fg(Theme::PEACH)),
+                Span::styled(warning.as_str(), Style::default().fg(Theme::PEACH)),
+            ]));
+        }
+        if app.summary.warnings.len() > 5 {
+            text.push(Line::from(Span::styled(
+                format!(
+                    "    … and {} more in the install log",
+                    app.summary.warnings.len() - 5
+                ),
+                HackerTheme::dim(),
+            )));
         }
         text.push(Line::from(""));
     }
 
     // ─── Skipped packages ───────────────────────────────────────
-    if !app.skipped_packages.is_empty() {
+    if !app.summary.skipped.is_empty() {
         // Show up to 5, then summarize
-        let show_count = app.skipped_packages.len().min(5);
-        for (name, reason) in app.skipped_packages.iter().take(show_count) {
+        let show_count = app.summary.skipped.len().min(5);
+        for (name, reason) in app.summary.skipped.iter().take(show_count) {
             text.push(Line::from(vec![
                 Span::styled("    ○ ", Style::default().fg(Theme::YELLOW)),
                 Span::styled(name.as_str(), Style::default().fg(Theme::YELLOW)),
                 Span::styled(format!("  {}", reason), HackerTheme::dim()),
             ]));
         }
-        if app.skipped_packages.len() > 5 {
+        if app.summary.skipped.len() > 5 {
             text.push(Line::from(Span::styled(
                 format!(
                     "    … and {} more already installed",
-                    app.skipped_packages.len() - 5
+                    app.summary.skipped.len() - 5
                 ),
                 HackerTheme::dim(),
             )));
@@ -865,14 +2927,30 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
         text.push(Line::from(""));
     }
 
+    // ─── Where the time went — the stats card ───────────────────
+    if let Some(total) = app.phase_times.total {
+        text.extend(stats_card(app, total));
+        text.push(Line::from(""));
+    }
+
     // ─── Config files generated ─────────────────────────────────
     let config_count: usize = [
         true, // .gitconfig always
-        app.wizard.shell_config.shell == crate::wizard::ShellChoice::Zsh,
-        app.wizard.shell_config.prompt == crate::wizard::PromptChoice::Starship
-            || app.wizard.selected_apps.contains("starship"),
-        app.wizard.shell_config.multiplexer == Some(crate::wizard::MultiplexerChoice::Tmux)
-            || app.wizard.selected_apps.contains("tmux"),
+        app.wizard.shell_config.shell == loadstar_core::wizard::ShellChoice::Zsh,
+        app.wizard.shell_config.prompt == loadstar_core::wizard::PromptChoice::Starship
+            || app.wizard.configures("starship"),
+        app.wizard.shell_config.multiplexer == Some(loadstar_core::wizard::MultiplexerChoice::Tmux)
+            || app.wizard.configures("tmux"),
+        app.wizard.configures("zed"),
+        app.wizard.configures("ghostty"),
+        app.wizard.configures("bat"),
+        app.wizard.configures("ripgrep"),
+        app.wizard.configures("lazygit"),
+        app.wizard.configures("yt-dlp"),
+        app.wizard.configures("k9s"),
+        app.wizard.configures("btop"),
+        app.wizard.configures("bottom"),
+        app.wizard.configures("terraform"),
         true, // .editorconfig always
     ]
     .iter()
@@ -892,6 +2970,47 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
             Style::default().fg(Theme::SUBTEXT0),
         ),
     ]));
+    let backup = match &app.backup_status {
+        _ if app.backup_receiver.is_some() => Some((
+            format!("    {} Backing up to GitHub…", app.spinner.current()),
+            Theme::LAVENDER,
+        )),
+        Some(Ok(message)) => Some((format!("    ✓ {}", message), Theme::GREEN)),
+        Some(Err(e)) => Some((format!("    ✗ Backup failed: {}", e), Theme::RED)),
+        None => None,
+    };
+    if let Some((line, color)) = backup {
+        text.push(Line::from(Span::styled(line, Style::default().fg(color))));
+    }
+    let onboarding_report = match &app.onboarding_report {
+        Some(Ok(path)) => Some((
+            format!("    ✓ Wrote the onboarding report to {}", path.display()),
+            Theme::GREEN,
+        )),
+        Some(Err(e)) => Some((
+            format!("    ✗ Onboarding report not written: {}", e),
+            Theme::RED,
+        )),
+        None => None,
+    };
+    if let Some((line, color)) = onboarding_report {
+        text.push(Line::from(Span::styled(line, Style::default().fg(color))));
+    }
+    let export = match &app.summary_status {
+        Some(Ok(path)) => Some((
+            format!("    ✓ Wrote the summary to {}", path.display()),
+            Theme::GREEN,
+        )),
+        Some(Err(e)) => Some((format!("    ✗ Summary not written: {}", e), Theme::RED)),
+        None if app.versions_receiver.is_some() => Some((
+            "    Asking Homebrew for versions...".to_string(),
+            Theme::SUBTEXT0,
+        )),
+        None => None,
+    };
+    if let Some((line, color)) = export {
+        text.push(Line::from(Span::styled(line, Style::default().fg(color))));
+    }
     text.push(Line::from(""));
 
     // ─── Divider ────────────────────────────────────────────────
@@ -917,41 +3036,21 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
         Span::styled("  — reload your shell", HackerTheme::dim()),
     ]));
 
-    let mut step = 2;
-
-    if app.wizard.selected_apps.contains("gh")
-        && !app
-            .install_log
-            .iter()
-            .any(|l| l.contains("Already authenticated"))
-    {
+    for (i, step) in app.next_steps.iter().enumerate() {
         text.push(Line::from(vec![
             Span::styled("      ", Style::default()),
-            Span::styled(format!("{} ", step), Style::default().fg(Theme::SURFACE2)),
-            Span::styled("gh auth login", Style::default().fg(Theme::TEXT)),
-            Span::styled("  — authenticate GitHub CLI", HackerTheme::dim()),
+            Span::styled(format!("{} ", i + 2), Style::default().fg(Theme::SURFACE2)),
+            Span::styled(step.command.as_str(), Style::default().fg(Theme::TEXT)),
+            Span::styled(format!("  — {}", step.note), HackerTheme::dim()),
         ]));
-        step += 1;
     }
-
-    if app.wizard.generate_ssh_key
-        && app
-            .install_log
-            .iter()
-            .any(|l| l.contains("SSH key generated"))
-    {
-        text.push(Line::from(vec![
-            Span::styled("      ", Style::default()),
-            Span::styled(format!("{} ", step), Style::default().fg(Theme::SURFACE2)),
-            Span::styled("ssh -T git@github.com", Style::default().fg(Theme::TEXT)),
-            Span::styled("  — test SSH connection", HackerTheme::dim()),
-        ]));
-        step += 1;
+    if !app.next_steps.is_empty() {
+        text.push(Line::from(Span::styled(
+            "      (saved to ~/.config/loadstar/next-steps.md)",
+            HackerTheme::dim(),
+        )));
     }
 
-    // Suppress unused variable warning — step is intentionally incremented for future use
-    let _ = step;
-
     text.push(Line::from(""));
 
     // ─── READY. — the sign-off ──────────────────────────────────
@@ -972,7 +3071,13 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
     )));
     text.push(Line::from(""));
     text.push(Line::from(Span::styled(
-        "    Press ENTER or q to exit",
+        format!(
+            "    Press {} or {} to exit · {} to back up to GitHub · {} for a Markdown summary",
+            app.keymap.hint(Action::Confirm).to_uppercase(),
+            app.keymap.hint(Action::Quit),
+            app.keymap.hint(Action::Backup),
+            app.keymap.hint(Action::Export)
+        ),
         Style::default().fg(Theme::SURFACE2),
     )));
 
@@ -980,6 +3085,69 @@ fn render_complete(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(paragraph, inner);
 }
 
+/// The session in numbers, boxed like a C64 start-up screen: time, keys,
+/// pace, and a bar for each of the slowest phases
+fn stats_card(app: &App, total: std::time::Duration) -> Vec<Line<'static>> {
+    const WIDTH: usize = 38;
+    let bright = Style::default().fg(Theme::PHOSPHOR);
+    let dim = Style::default().fg(Theme::PHOSPHOR_DIM);
+    let row = |content: String| {
+        Line::from(vec![
+            Span::styled("    ║ ", dim),
+            Span::styled(format!("{:<w$}", content, w = WIDTH - 2), bright),
+            Span::styled(" ║", dim),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(format!("    ╔{}╗", "═".repeat(WIDTH)), dim)),
+        Line::from(vec![
+            Span::styled("    ║", dim),
+            Span::styled(
+                format!("{:^w$}", "**** LOADSTAR SESSION STATS ****", w = WIDTH),
+                bright.add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("║", dim),
+        ]),
+        row(format!(
+            "INSTALL {}  WIZARD {}",
+            events::format_duration(total),
+            events::format_duration(app.session.wizard_time())
+        )),
+        row(format!(
+            "{} KEYS PRESSED  {} PKGS/MIN",
+            app.session.keypresses,
+            app.session
+                .packages_per_minute()
+                .map_or_else(|| "--".to_string(), |rate| format!("{:.1}", rate))
+        )),
+    ];
+
+    // The slowest few, longest first, barred against the slowest
+    let mut phases: Vec<_> = app.phase_times.phases.iter().collect();
+    phases.sort_by_key(|(_, took)| std::cmp::Reverse(*took));
+    let slowest = phases.first().map_or(0.0, |(_, took)| took.as_secs_f64());
+    for (phase, took) in phases.iter().take(4) {
+        let share = if slowest > 0.0 {
+            took.as_secs_f64() / slowest
+        } else {
+            0.0
+        };
+        let name: String = phase.to_uppercase().chars().take(17).collect();
+        lines.push(row(format!(
+            "{:<17} {} {}",
+            name,
+            app.gauge.bar(share, 10),
+            events::format_duration(*took)
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        format!("    ╚{}╝", "═".repeat(WIDTH)),
+        dim,
+    )));
+    lines
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 //  Shared components
 // ═══════════════════════════════════════════════════════════════════════
@@ -1018,8 +3186,10 @@ fn render_header(frame: &mut Frame, area: Rect, title: &str) {
     }
 }
 
-fn render_phase_indicator(frame: &mut Frame, area: Rect, current: &WizardPhase) {
-    let phases = WizardPhase::all();
+fn render_phase_indicator(frame: &mut Frame, area: Rect, wizard: &WizardState) {
+    let current = &wizard.phase;
+    let phases = wizard.path();
+    let current_pos = phases.iter().position(|p| p == current).unwrap_or(0);
     let mut spans: Vec<Span> = Vec::new();
 
     spans.push(Span::raw("  "));
@@ -1036,7 +3206,7 @@ fn render_phase_indicator(frame: &mut Frame, area: Rect, current: &WizardPhase)
                     .fg(Theme::BLUE)
                     .add_modifier(Modifier::BOLD),
             )
-        } else if phase.index() < current.index() {
+        } else if i < current_pos {
             ("●", Style::default().fg(Theme::GREEN))
         } else {
             ("○", HackerTheme::muted())
@@ -1045,7 +3215,7 @@ fn render_phase_indicator(frame: &mut Frame, area: Rect, current: &WizardPhase)
         spans.push(Span::styled(icon, style));
 
         // Only show name for current and adjacent phases to save space
-        let dist = (phase.index() as i32 - current.index() as i32).unsigned_abs() as usize;
+        let dist = i.abs_diff(current_pos);
         if dist <= 1 {
             spans.push(Span::styled(format!(" {}", phase.name()), style));
         }
@@ -1074,6 +3244,11 @@ fn render_footer(frame: &mut Frame, area: Rect, keys: &[(&str, &str)]) {
     frame.render_widget(paragraph, area);
 }
 
+/// An input field for a `Secret`: one dot per character, never the value
+fn render_masked_field(frame: &mut Frame, area: Rect, label: &str, secret: &Secret, focused: bool) {
+    render_input_field(frame, area, label, &secret.masked(), focused);
+}
+
 fn render_input_field(frame: &mut Frame, area: Rect, label: &str, value: &str, focused: bool) {
     let border_style = if focused {
         HackerTheme::border_focused()
@@ -1226,15 +3401,22 @@ fn render_option_selector(
     frame.render_widget(text, inner);
 }
 
+/// The category list. `jump` is the cursor while picking a category to
+/// jump to; the current category stays highlighted until the jump.
 fn render_category_sidebar(
     frame: &mut Frame,
     area: Rect,
     categories: &[Category],
     selected: usize,
+    jump: Option<usize>,
 ) {
     let block = Block::default()
         .borders(Borders::RIGHT)
-        .border_style(HackerTheme::border_dim());
+        .border_style(if jump.is_some() {
+            HackerTheme::border_focused()
+        } else {
+            HackerTheme::border_dim()
+        });
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -1249,16 +3431,13 @@ fn render_category_sidebar(
             } else {
                 HackerTheme::primary()
             };
-            let prefix = if is_selected { " ▸ " } else { "   " };
+            let (prefix, prefix_style) = match jump {
+                Some(cursor) if cursor == i => (" » ", Style::default().fg(Theme::GREEN)),
+                _ if is_selected => (" ▸ ", Style::default().fg(Theme::BLUE)),
+                _ => ("   ", Style::default()),
+            };
             ListItem::new(Line::from(vec![
-                Span::styled(
-                    prefix,
-                    if is_selected {
-                        Style::default().fg(Theme::BLUE)
-                    } else {
-                        Style::default()
-                    },
-                ),
+                Span::styled(prefix, prefix_style),
                 Span::styled(format!("{} {}", cat.icon(), cat.name()), style),
             ]))
         })
@@ -1282,7 +3461,7 @@ fn render_app_list(frame: &mut Frame, area: Rect, category: &Category, app: &App
         .iter()
         .enumerate()
         .map(|(i, a)| {
-            let selected = app.wizard.is_app_selected(a.id);
+            let selected = app.wizard.is_app_selected(a.id.as_str());
             let focused = i == app.wizard.cursor_position;
 
             let checkbox = if selected { "◉" } else { "○" };
@@ -1315,6 +3494,38 @@ fn render_app_list(frame: &mut Frame, area: Rect, category: &Category, app: &App
                 Span::styled(a.name, name_style),
             ];
 
+            // Cross-listed here by a tag; picking it picks it everywhere
+            if a.category != *category {
+                spans.push(Span::styled(
+                    format!("  {} {}", a.category.icon(), a.category.name()),
+                    HackerTheme::dim(),
+                ));
+            }
+
+            let mode = app.wizard.app_mode(a.id.as_str());
+            if selected && mode != AppMode::Install {
+                spans.push(Span::styled(
+                    format!("  [{}]", mode.name().to_lowercase()),
+                    Style::default().fg(Theme::PEACH),
+                ));
+            }
+
+            if let Some(gap) = app.gaps.iter().find(|gap| gap.app == a.id) {
+                spans.push(Span::styled(
+                    format!("  ⚠ {}", gap.reason),
+                    HackerTheme::warning(),
+                ));
+            }
+
+            let required_by = app.wizard.required_by(a.id.as_str());
+            if selected && !required_by.is_empty() {
+                let names: Vec<&str> = required_by.iter().map(|r| r.name).collect();
+                spans.push(Span::styled(
+                    format!("  required by {}", names.join(", ")),
+                    Style::default().fg(Theme::MAUVE),
+                ));
+            }
+
             if app.wizard.show_details {
                 spans.push(Span::styled(
                     format!("  {}", a.description),
@@ -1326,8 +3537,11 @@ fn render_app_list(frame: &mut Frame, area: Rect, category: &Category, app: &App
         })
         .collect();
 
+    // Stateful render keeps the focused row on screen when the list is
+    // taller than the window
+    let mut state = ListState::default().with_selected(Some(app.wizard.cursor_position));
     let list = List::new(items);
-    frame.render_widget(list, inner);
+    frame.render_stateful_widget(list, inner, &mut state);
 }
 
 // ─── Review helpers ──────────────────────────────────────────────────
@@ -1355,6 +3569,55 @@ fn review_line(label: &str, value: &str) -> Line<'static> {
     ])
 }
 
+/// A bordered box over the middle of `area`, for prompts and pickers
+fn render_popup(frame: &mut Frame, area: Rect, title: &str, lines: Vec<Line>) {
+    let popup = centered_rect(50, 40, area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .border_style(HackerTheme::border_focused())
+        .style(Style::default().bg(Theme::MANTLE))
+        .title(Span::styled(
+            format!(" {} ", title),
+            Style::default()
+                .fg(Theme::BLUE)
+                .add_modifier(Modifier::BOLD),
+        ));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        popup,
+    );
+}
+
+/// A confirmation over whatever screen is up
+fn render_modal(frame: &mut Frame, area: Rect, modal: &Modal, km: &Keymap) {
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {}", modal.message),
+            Style::default().fg(Theme::TEXT),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("  {} ", km.hint(Action::Confirm)),
+                HackerTheme::accent(),
+            ),
+            Span::styled(modal.confirm, HackerTheme::primary()),
+            Span::styled(
+                format!("   {} ", km.hint(Action::Back)),
+                HackerTheme::accent(),
+            ),
+            Span::styled("cancel", HackerTheme::muted()),
+        ]),
+    ];
+    render_popup(frame, area, modal.title, lines);
+}
+
 // ─── Layout helper ───────────────────────────────────────────────────
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -0,0 +1,210 @@
+//! Profile self-test
+//! Runs a headless install of a profile in a throwaway Docker container,
+//! so catalog and profile changes can be tried without touching this machine.
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use loadstar_core::system::{Os, SystemInfo};
+
+use crate::headless;
+
+/// Prepares an apt-based image the way a fresh machine looks: a sudo user
+/// (Homebrew refuses to run as root) and the basics Homebrew needs. Then
+/// hands over to the mounted binary, with `{args}` added to its flags.
+const SETUP_SCRIPT: &str = "\
+set -e
+export DEBIAN_FRONTEND=noninteractive
+echo '[SETUP] Installing Homebrew prerequisites'
+apt-get update -qq
+apt-get install -y -qq build-essential ca-certificates curl file git procps sudo >/dev/null
+useradd -m -s /bin/bash loadstar
+echo 'loadstar ALL=(ALL) NOPASSWD:ALL' > /etc/sudoers.d/loadstar
+exec su - loadstar -c 'loadstar --headless --profile /tmp/profile.toml{args}' 2>&1
+";
+
+/// Phase name for the time before the first headless phase starts
+pub const SETUP_PHASE: &str = "Container setup";
+
+/// What the container reported
+#[derive(Debug, Default)]
+pub struct Report {
+    pub succeeded: Vec<String>,
+    pub skipped: Vec<(String, String)>,
+    pub failed: Vec<(String, String)>,
+    pub fatal: Option<String>,
+    /// Wall-clock time per phase, in the order they ran
+    pub phases: Vec<(String, Duration)>,
+}
+
+impl Report {
+    /// Take in one line of headless output
+    fn record(&mut self, line: &str) {
+        if let Some(rest) = line.strip_prefix(headless::OK) {
+            // "name (2.5s)"
+            let name = rest.rsplit_once(" (").map_or(rest, |(name, _)| name);
+            self.succeeded.push(name.to_string());
+        } else if let Some(rest) = line.strip_prefix(headless::SKIP) {
+            self.skipped.push(split_reason(rest));
+        } else if let Some(rest) = line.strip_prefix(headless::FAIL) {
+            self.failed.push(split_reason(rest));
+        } else if let Some(rest) = line.strip_prefix("[FATAL] ") {
+            self.fatal = Some(rest.to_string());
+        }
+    }
+
+    /// Start timing a new phase on a `=== Phase ===` line
+    fn phase_started(&mut self, line: &str) -> bool {
+        let Some(phase) = line
+            .strip_prefix("=== ")
+            .and_then(|l| l.strip_suffix(" ==="))
+        else {
+            return false;
+        };
+        self.phases.push((phase.to_string(), Duration::ZERO));
+        true
+    }
+
+    /// Everything installed or was already there
+    pub fn passed(&self) -> bool {
+        self.fatal.is_none() && self.failed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "\n  {} installed, {} skipped, {} failed\n",
+            self.succeeded.len(),
+            self.skipped.len(),
+            self.failed.len()
+        );
+        for (name, error) in &self.failed {
+            summary.push_str(&format!("  ✗ {}: {}\n", name, error));
+        }
+        if let Some(fatal) = &self.fatal {
+            summary.push_str(&format!("  ✗ {}\n", fatal));
+        }
+        summary
+    }
+}
+
+fn split_reason(rest: &str) -> (String, String) {
+    let (name, reason) = rest.split_once(": ").unwrap_or((rest, ""));
+    (name.to_string(), reason.to_string())
+}
+
+/// Run the profile's install in `image`, echoing the container's output.
+/// `args` go to the headless run inside, e.g. `--fast`.
+pub fn run(
+    profile: &Path,
+    image: &str,
+    args: &[&str],
+    system: &SystemInfo,
+) -> anyhow::Result<Report> {
+    // The container runs this very binary
+    if system.os != Os::Linux {
+        anyhow::bail!(
+            "`loadstar test` mounts this binary into a Linux container, so it needs a Linux build"
+        );
+    }
+    let docker = Command::new("docker")
+        .args(["info", "--format", "{{.ServerVersion}}"])
+        .output();
+    if !matches!(&docker, Ok(output) if output.status.success()) {
+        anyhow::bail!("`loadstar test` needs Docker, and the Docker daemon isn't reachable");
+    }
+
+    let binary = std::env::current_exe()?;
+    let profile = profile
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("{}: {}", profile.display(), e))?;
+
+    println!("[TEST] {} in {}", profile.display(), image);
+    let mut child = Command::new("docker")
+        .args(["run", "--rm", "-v"])
+        .arg(format!("{}:/usr/local/bin/loadstar:ro", binary.display()))
+        .arg("-v")
+        .arg(format!("{}:/tmp/profile.toml:ro", profile.display()))
+        .args([image, "/bin/sh", "-c"])
+        .arg(setup_script(args))
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run docker: {}", e))?;
+
+    let mut report = Report::default();
+    report
+        .phases
+        .push((SETUP_PHASE.to_string(), Duration::ZERO));
+    let mut phase_start = Instant::now();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            println!("  {}", line);
+            if report.phase_started(&line) {
+                phase_start = Instant::now();
+            }
+            report.record(&line);
+            if let Some((_, elapsed)) = report.phases.last_mut() {
+                *elapsed = phase_start.elapsed();
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    if !status.success() && report.fatal.is_none() && report.failed.is_empty() {
+        report.fatal = Some(format!("Container exited with {}", status));
+    }
+    Ok(report)
+}
+
+fn setup_script(args: &[&str]) -> String {
+    let args: String = args.iter().map(|a| format!(" {}", a)).collect();
+    SETUP_SCRIPT.replace("{args}", &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_reads_headless_result_lines() {
+        let mut report = Report::default();
+        for line in [
+            "=== Homebrew Bootstrap ===",
+            "[OK] ripgrep (2.5s)",
+            "[OK] GitHub CLI (gh) (1.0s)",
+            "[SKIP] git: already installed",
+            "[FAIL] bat: exit status: 1",
+            "[DONE] 2 installed, 1 skipped, 1 failed",
+        ] {
+            report.record(line);
+        }
+
+        assert_eq!(report.succeeded, ["ripgrep", "GitHub CLI (gh)"]);
+        assert_eq!(
+            report.skipped,
+            [("git".to_string(), "already installed".to_string())]
+        );
+        assert_eq!(
+            report.failed,
+            [("bat".to_string(), "exit status: 1".to_string())]
+        );
+        assert!(!report.passed());
+        assert!(report.phases.is_empty());
+        assert!(report
+            .summary()
+            .contains("2 installed, 1 skipped, 1 failed"));
+    }
+
+    #[test]
+    fn phases_and_args_reach_the_container() {
+        let mut report = Report::default();
+        assert!(report.phase_started("=== Homebrew Formulae ==="));
+        assert!(!report.phase_started("[OK] ripgrep (2.5s)"));
+        assert_eq!(report.phases[0].0, "Homebrew Formulae");
+
+        let script = setup_script(&["--fast", "--brew-update", "skip"]);
+        assert!(script.contains("--profile /tmp/profile.toml --fast --brew-update skip'"));
+        assert!(setup_script(&[]).contains("--profile /tmp/profile.toml'"));
+    }
+}